@@ -0,0 +1,1004 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+
+use crate::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use crate::utils::content;
+use crate::utils::hash::hash64;
+use crate::utils::time::{derive_timestamp, format_unix_ms, normalize_timestamp_exact};
+
+pub const CHAT_HISTORY_FILE_NAME: &str = ".aider.chat.history.md";
+pub const INPUT_HISTORY_FILE_NAME: &str = ".aider.input.history";
+pub const LLM_HISTORY_FILE_NAME: &str = ".aider.llm.history";
+
+pub const DEFAULT_PATHS: &[&str] = &[
+    CHAT_HISTORY_FILE_NAME,
+    INPUT_HISTORY_FILE_NAME,
+    LLM_HISTORY_FILE_NAME,
+];
+
+pub const REPO_SCAN_MAX_DEPTH: usize = 4;
+
+const SESSION_HEADER_PREFIX: &str = "# aider chat started at ";
+const LLM_REQUEST_PREFIX: &str = "TO LLM ";
+const LLM_RESPONSE_PREFIX: &str = "LLM RESPONSE ";
+const LLM_MESSAGE_SEPARATOR: &str = "-------";
+const SKIPPED_SCAN_DIRS: &[&str] = &["node_modules", "target"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiderChatHistoryParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiderInputHistoryParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AiderLlmHistoryParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+/// Every Aider history file (any of [`DEFAULT_PATHS`]) under `root`, found in
+/// a single walk so the three history kinds share one scan.
+pub fn find_repo_history_files(root: &Path) -> Result<Vec<PathBuf>> {
+    if root.is_file() {
+        return Ok(if is_history_file_name(root) {
+            vec![root.to_path_buf()]
+        } else {
+            Vec::new()
+        });
+    }
+
+    let mut found = Vec::new();
+    scan_repo_dir(root, 0, &mut found)?;
+    found.sort();
+    Ok(found)
+}
+
+/// The subset of `files` named `file_name`.
+#[must_use]
+pub fn history_files_named(files: &[PathBuf], file_name: &str) -> Vec<PathBuf> {
+    files
+        .iter()
+        .filter(|path| path.file_name().is_some_and(|name| name == file_name))
+        .cloned()
+        .collect()
+}
+
+fn is_history_file_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|name| DEFAULT_PATHS.contains(&name))
+}
+
+pub fn parse_chat_history_file(path: &Path, run_id: &str) -> Result<AiderChatHistoryParseResult> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read aider chat history file: {path:?}"))?;
+    Ok(parse_chat_history_markdown(
+        &content,
+        run_id,
+        path.to_string_lossy().as_ref(),
+    ))
+}
+
+pub fn parse_input_history_file(path: &Path, run_id: &str) -> Result<AiderInputHistoryParseResult> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read aider input history file: {path:?}"))?;
+    Ok(parse_input_history_text(
+        &content,
+        run_id,
+        path.to_string_lossy().as_ref(),
+    ))
+}
+
+pub fn parse_llm_history_file(path: &Path, run_id: &str) -> Result<AiderLlmHistoryParseResult> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read aider llm history file: {path:?}"))?;
+    Ok(parse_llm_history_text(
+        &content,
+        run_id,
+        path.to_string_lossy().as_ref(),
+    ))
+}
+
+#[must_use]
+pub fn parse_chat_history_markdown(
+    input: &str,
+    run_id: &str,
+    source_path: &str,
+) -> AiderChatHistoryParseResult {
+    let mut builder = EventBuilder::new(run_id, source_path, "chat_history");
+    let mut warnings = Vec::new();
+
+    let mut session: Option<ChatSession> = None;
+    let mut headerless_warned = false;
+    let mut pending_command: Option<(String, String)> = None;
+
+    for segment in split_chat_segments(input) {
+        let block = match segment {
+            ChatSegment::SessionStart { line_number, raw } => {
+                let timestamp = parse_aider_timestamp(&raw);
+                if timestamp.is_none() {
+                    warnings.push(format!(
+                        "line {line_number}: invalid session header timestamp `{raw}`; using fallback timestamps"
+                    ));
+                }
+                let session_index = session.as_ref().map_or(1, |current| current.index + 1);
+                let session_id = format!(
+                    "aider-{:016x}",
+                    hash64(&(source_path.to_string(), session_index, raw.clone()))
+                );
+                let started = ChatSession {
+                    index: session_index,
+                    id: session_id,
+                    anchor_unix_ms: timestamp.map(|(unix_ms, _)| unix_ms),
+                    offset_ms: 0,
+                    model: None,
+                    aider_version: None,
+                    saw_user_turn: false,
+                };
+                pending_command = None;
+
+                let (timestamp_unix_ms, timestamp_utc, timestamp_quality) = match timestamp {
+                    Some((unix_ms, quality)) => (unix_ms, format_unix_ms(unix_ms), quality),
+                    None => fallback_timestamp_parts(line_number),
+                };
+                builder.push(
+                    EventDraft {
+                        record_format: RecordFormat::System,
+                        event_type: EventType::SystemNotice,
+                        role: ActorRole::System,
+                        line_number,
+                        text: format!("aider chat started at {raw}"),
+                        timestamp_unix_ms,
+                        timestamp_utc,
+                        timestamp_quality,
+                        session_id: Some(started.id.clone()),
+                        model: None,
+                        tool_name: None,
+                        tool_call_id: None,
+                        tool_arguments_json: None,
+                        metadata: BTreeMap::from([(
+                            "aider_session_started_at".to_string(),
+                            json!(raw),
+                        )]),
+                    },
+                    &[raw.as_str()],
+                );
+                session = Some(started);
+                continue;
+            }
+            ChatSegment::Block(block) => block,
+        };
+
+        let text = block.text();
+        if text.is_empty() {
+            continue;
+        }
+
+        if session.is_none() && !headerless_warned {
+            warnings.push(format!(
+                "line {}: transcript content before any `# aider chat started at` header; using fallback timestamps",
+                block.start_line
+            ));
+            headerless_warned = true;
+        }
+
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) = match session.as_mut() {
+            Some(ChatSession {
+                anchor_unix_ms: Some(anchor),
+                offset_ms,
+                ..
+            }) => {
+                *offset_ms += 1;
+                match derive_timestamp(*anchor, *offset_ms) {
+                    Ok(derived) => (
+                        derived.timestamp_unix_ms,
+                        derived.timestamp_utc(),
+                        derived.timestamp_quality,
+                    ),
+                    Err(_) => fallback_timestamp_parts(block.start_line),
+                }
+            }
+            _ => fallback_timestamp_parts(block.start_line),
+        };
+
+        let mut metadata = BTreeMap::new();
+        let session_id = session.as_ref().map(|current| current.id.clone());
+        let draft = match block.kind {
+            ChatBlockKind::User => {
+                if let Some(current) = session.as_mut() {
+                    current.saw_user_turn = true;
+                }
+                if let Some(command) = text.strip_prefix('/') {
+                    let tool_name = command
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_ascii_lowercase();
+                    let tool_call_id = builder.next_event_id();
+                    pending_command = Some((tool_name.clone(), tool_call_id.clone()));
+                    EventDraft {
+                        record_format: RecordFormat::ToolCall,
+                        event_type: EventType::ToolInvocation,
+                        role: ActorRole::User,
+                        tool_name: Some(tool_name),
+                        tool_call_id: Some(tool_call_id),
+                        tool_arguments_json: Some(json!({ "command": text }).to_string()),
+                        ..EventDraft::message(block.start_line, text.clone())
+                    }
+                } else {
+                    pending_command = None;
+                    EventDraft {
+                        record_format: RecordFormat::Message,
+                        event_type: EventType::Prompt,
+                        role: ActorRole::User,
+                        ..EventDraft::message(block.start_line, text.clone())
+                    }
+                }
+            }
+            ChatBlockKind::Assistant => {
+                pending_command = None;
+                EventDraft {
+                    record_format: RecordFormat::Message,
+                    event_type: EventType::Response,
+                    role: ActorRole::Assistant,
+                    model: session.as_ref().and_then(|current| current.model.clone()),
+                    ..EventDraft::message(block.start_line, text.clone())
+                }
+            }
+            ChatBlockKind::Tool => {
+                let banner = session
+                    .as_ref()
+                    .is_some_and(|current| !current.saw_user_turn);
+                if banner {
+                    if let Some(current) = session.as_mut() {
+                        absorb_banner_details(current, &block.lines);
+                        if let Some(version) = &current.aider_version {
+                            metadata.insert("aider_version".to_string(), json!(version));
+                        }
+                    }
+                    EventDraft {
+                        record_format: RecordFormat::System,
+                        event_type: EventType::SystemNotice,
+                        role: ActorRole::System,
+                        model: session.as_ref().and_then(|current| current.model.clone()),
+                        ..EventDraft::message(block.start_line, text.clone())
+                    }
+                } else {
+                    let (tool_name, tool_call_id) = pending_command.clone().unzip();
+                    EventDraft {
+                        record_format: RecordFormat::ToolResult,
+                        event_type: EventType::ToolOutput,
+                        role: ActorRole::Tool,
+                        tool_name,
+                        tool_call_id,
+                        ..EventDraft::message(block.start_line, text.clone())
+                    }
+                }
+            }
+        };
+
+        metadata.insert("aider_block_kind".to_string(), json!(block.kind.as_str()));
+        let raw_lines = block.lines.iter().map(String::as_str).collect::<Vec<_>>();
+        builder.push(
+            EventDraft {
+                timestamp_unix_ms,
+                timestamp_utc,
+                timestamp_quality,
+                session_id,
+                metadata,
+                ..draft
+            },
+            &raw_lines,
+        );
+    }
+
+    AiderChatHistoryParseResult {
+        events: builder.finish(),
+        warnings,
+    }
+}
+
+#[must_use]
+pub fn parse_input_history_text(
+    input: &str,
+    run_id: &str,
+    source_path: &str,
+) -> AiderInputHistoryParseResult {
+    let mut builder = EventBuilder::new(run_id, source_path, "input_history");
+    let mut warnings = Vec::new();
+    let mut entries: Vec<InputHistoryEntry> = Vec::new();
+
+    for (index, raw_line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim_end_matches('\r');
+        if let Some(raw_timestamp) = line.strip_prefix("# ") {
+            entries.push(InputHistoryEntry {
+                line_number,
+                raw_timestamp: Some(raw_timestamp.trim().to_string()),
+                lines: Vec::new(),
+            });
+        } else if let Some(text) = line.strip_prefix('+') {
+            match entries.last_mut() {
+                Some(entry) => entry.lines.push(text.to_string()),
+                None => {
+                    warnings.push(format!(
+                        "line {line_number}: input line without timestamp header; using fallback timestamp"
+                    ));
+                    entries.push(InputHistoryEntry {
+                        line_number,
+                        raw_timestamp: None,
+                        lines: vec![text.to_string()],
+                    });
+                }
+            }
+        } else if !line.trim().is_empty() {
+            warnings.push(format!(
+                "line {line_number}: unrecognized input history line; skipped"
+            ));
+        }
+    }
+
+    for entry in entries {
+        let text = entry.lines.join("\n").trim().to_string();
+        if text.is_empty() {
+            continue;
+        }
+
+        let parsed = entry
+            .raw_timestamp
+            .as_deref()
+            .and_then(parse_aider_timestamp);
+        if entry.raw_timestamp.is_some() && parsed.is_none() {
+            warnings.push(format!(
+                "line {}: invalid input history timestamp; using fallback timestamp",
+                entry.line_number
+            ));
+        }
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) = match parsed {
+            Some((unix_ms, quality)) => (unix_ms, format_unix_ms(unix_ms), quality),
+            None => fallback_timestamp_parts(entry.line_number),
+        };
+
+        let raw_lines = entry.lines.iter().map(String::as_str).collect::<Vec<_>>();
+        builder.push(
+            EventDraft {
+                record_format: RecordFormat::Message,
+                event_type: EventType::Prompt,
+                role: ActorRole::User,
+                timestamp_unix_ms,
+                timestamp_utc,
+                timestamp_quality,
+                ..EventDraft::message(entry.line_number, text)
+            },
+            &raw_lines,
+        );
+    }
+
+    AiderInputHistoryParseResult {
+        events: builder.finish(),
+        warnings,
+    }
+}
+
+#[must_use]
+pub fn parse_llm_history_text(
+    input: &str,
+    run_id: &str,
+    source_path: &str,
+) -> AiderLlmHistoryParseResult {
+    let mut builder = EventBuilder::new(run_id, source_path, "llm_history");
+    let mut warnings = Vec::new();
+    let mut exchanges: Vec<LlmExchange> = Vec::new();
+
+    for (index, raw_line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim_end_matches('\r');
+        if let Some((direction, raw_timestamp)) = parse_llm_header(line) {
+            exchanges.push(LlmExchange {
+                line_number,
+                direction,
+                raw_timestamp: raw_timestamp.to_string(),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+
+        match exchanges.last_mut() {
+            Some(exchange) => exchange.lines.push(line.to_string()),
+            None if !line.trim().is_empty() => warnings.push(format!(
+                "line {line_number}: content before any `TO LLM`/`LLM RESPONSE` header; skipped"
+            )),
+            None => {}
+        }
+    }
+
+    for exchange in exchanges {
+        let messages = split_llm_messages(&exchange.lines);
+        let text = match exchange.direction {
+            LlmDirection::Request => messages
+                .iter()
+                .rev()
+                .find(|(role, _)| role == "USER")
+                .or_else(|| messages.last())
+                .map(|(_, text)| text.clone()),
+            LlmDirection::Response => Some(
+                messages
+                    .iter()
+                    .filter(|(role, _)| role == "ASSISTANT")
+                    .map(|(_, text)| text.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty());
+        let Some(text) = text else {
+            warnings.push(format!(
+                "line {}: `{}` block has no message content; skipped",
+                exchange.line_number,
+                exchange.direction.header()
+            ));
+            continue;
+        };
+
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) =
+            match parse_aider_timestamp(&exchange.raw_timestamp) {
+                Some((unix_ms, quality)) => (unix_ms, format_unix_ms(unix_ms), quality),
+                None => {
+                    warnings.push(format!(
+                        "line {}: invalid `{}` timestamp; using fallback timestamp",
+                        exchange.line_number,
+                        exchange.direction.header()
+                    ));
+                    fallback_timestamp_parts(exchange.line_number)
+                }
+            };
+
+        let (event_type, role) = match exchange.direction {
+            LlmDirection::Request => (EventType::Prompt, ActorRole::User),
+            LlmDirection::Response => (EventType::Response, ActorRole::Assistant),
+        };
+        let metadata = BTreeMap::from([
+            (
+                "aider_llm_direction".to_string(),
+                json!(exchange.direction.key()),
+            ),
+            ("aider_llm_message_count".to_string(), json!(messages.len())),
+        ]);
+        let raw_lines = exchange
+            .lines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        builder.push(
+            EventDraft {
+                record_format: RecordFormat::Message,
+                event_type,
+                role,
+                timestamp_unix_ms,
+                timestamp_utc,
+                timestamp_quality,
+                metadata,
+                ..EventDraft::message(exchange.line_number, text)
+            },
+            &raw_lines,
+        );
+    }
+
+    AiderLlmHistoryParseResult {
+        events: builder.finish(),
+        warnings,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChatBlockKind {
+    User,
+    Assistant,
+    Tool,
+}
+
+impl ChatBlockKind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Assistant => "assistant",
+            Self::Tool => "tool",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ChatBlock {
+    kind: ChatBlockKind,
+    start_line: usize,
+    lines: Vec<String>,
+}
+
+impl ChatBlock {
+    fn text(&self) -> String {
+        self.lines.join("\n").trim().to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum ChatSegment {
+    SessionStart { line_number: usize, raw: String },
+    Block(ChatBlock),
+}
+
+#[derive(Debug, Clone)]
+struct ChatSession {
+    index: usize,
+    id: String,
+    anchor_unix_ms: Option<u64>,
+    offset_ms: u64,
+    model: Option<String>,
+    aider_version: Option<String>,
+    saw_user_turn: bool,
+}
+
+fn split_chat_segments(input: &str) -> Vec<ChatSegment> {
+    let mut segments = Vec::new();
+    let mut current: Option<ChatBlock> = None;
+    let mut in_fence = false;
+
+    for (index, raw_line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw_line.trim_end();
+        let is_fence = line.trim_start().starts_with("```");
+
+        if in_fence {
+            if let Some(block) = current.as_mut() {
+                block.lines.push(line.to_string());
+            }
+            in_fence = !is_fence;
+            continue;
+        }
+
+        if let Some(raw) = line.strip_prefix(SESSION_HEADER_PREFIX) {
+            flush_block(&mut current, &mut segments);
+            segments.push(ChatSegment::SessionStart {
+                line_number,
+                raw: raw.trim().to_string(),
+            });
+            continue;
+        }
+
+        let user_text = line
+            .strip_prefix("#### ")
+            .or_else(|| (line == "####").then_some(""));
+        if let Some(text) = user_text {
+            continue_or_start(
+                &mut current,
+                &mut segments,
+                ChatBlockKind::User,
+                line_number,
+            );
+            push_line(&mut current, text);
+            continue;
+        }
+
+        let tool_text = line
+            .strip_prefix("> ")
+            .or_else(|| (line == ">").then_some(""));
+        if let Some(text) = tool_text {
+            continue_or_start(
+                &mut current,
+                &mut segments,
+                ChatBlockKind::Tool,
+                line_number,
+            );
+            push_line(&mut current, text);
+            continue;
+        }
+
+        if line.is_empty() {
+            match current.as_ref().map(|block| block.kind) {
+                Some(ChatBlockKind::Assistant) => push_line(&mut current, ""),
+                Some(_) => flush_block(&mut current, &mut segments),
+                None => {}
+            }
+            continue;
+        }
+
+        let continues_tool_output =
+            current.as_ref().map(|block| block.kind) == Some(ChatBlockKind::Tool);
+        if !continues_tool_output {
+            continue_or_start(
+                &mut current,
+                &mut segments,
+                ChatBlockKind::Assistant,
+                line_number,
+            );
+            in_fence = is_fence;
+        }
+        push_line(&mut current, line);
+    }
+
+    flush_block(&mut current, &mut segments);
+    segments
+}
+
+fn continue_or_start(
+    current: &mut Option<ChatBlock>,
+    segments: &mut Vec<ChatSegment>,
+    kind: ChatBlockKind,
+    line_number: usize,
+) {
+    if current.as_ref().is_some_and(|block| block.kind == kind) {
+        return;
+    }
+    flush_block(current, segments);
+    *current = Some(ChatBlock {
+        kind,
+        start_line: line_number,
+        lines: Vec::new(),
+    });
+}
+
+fn push_line(current: &mut Option<ChatBlock>, text: &str) {
+    if let Some(block) = current.as_mut() {
+        block.lines.push(text.to_string());
+    }
+}
+
+fn flush_block(current: &mut Option<ChatBlock>, segments: &mut Vec<ChatSegment>) {
+    if let Some(block) = current.take() {
+        segments.push(ChatSegment::Block(block));
+    }
+}
+
+fn absorb_banner_details(session: &mut ChatSession, lines: &[String]) {
+    for line in lines {
+        let line = line.trim();
+        if let Some(model_line) = line
+            .strip_prefix("Model: ")
+            .or_else(|| line.strip_prefix("Main model: "))
+            && let Some(model) = model_line.split_whitespace().next()
+        {
+            session.model = Some(model.trim_end_matches(',').to_string());
+        }
+        if let Some(version) = line.strip_prefix("Aider v") {
+            session.aider_version = Some(version.trim().to_string());
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct InputHistoryEntry {
+    line_number: usize,
+    raw_timestamp: Option<String>,
+    lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LlmDirection {
+    Request,
+    Response,
+}
+
+impl LlmDirection {
+    const fn header(self) -> &'static str {
+        match self {
+            Self::Request => "TO LLM",
+            Self::Response => "LLM RESPONSE",
+        }
+    }
+
+    const fn key(self) -> &'static str {
+        match self {
+            Self::Request => "to_llm",
+            Self::Response => "llm_response",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LlmExchange {
+    line_number: usize,
+    direction: LlmDirection,
+    raw_timestamp: String,
+    lines: Vec<String>,
+}
+
+fn parse_llm_header(line: &str) -> Option<(LlmDirection, &str)> {
+    let (direction, rest) = if let Some(rest) = line.strip_prefix(LLM_REQUEST_PREFIX) {
+        (LlmDirection::Request, rest)
+    } else {
+        (
+            LlmDirection::Response,
+            line.strip_prefix(LLM_RESPONSE_PREFIX)?,
+        )
+    };
+    let raw_timestamp = rest.trim();
+    let looks_like_timestamp = raw_timestamp.len() >= 19
+        && !raw_timestamp.contains(' ')
+        && raw_timestamp.as_bytes()[..4].iter().all(u8::is_ascii_digit);
+    looks_like_timestamp.then_some((direction, raw_timestamp))
+}
+
+fn split_llm_messages(lines: &[String]) -> Vec<(String, String)> {
+    let mut messages: Vec<(String, Vec<String>)> = Vec::new();
+    let mut separated = true;
+
+    for line in lines {
+        if line.trim() == LLM_MESSAGE_SEPARATOR {
+            separated = true;
+            continue;
+        }
+        let (role, text) = match line.split_once(' ') {
+            Some((role, text)) if is_llm_role(role) => (role, text),
+            _ if is_llm_role(line) => (line.as_str(), ""),
+            _ => {
+                if let Some((_, message_lines)) = messages.last_mut() {
+                    message_lines.push(line.clone());
+                }
+                continue;
+            }
+        };
+
+        let starts_new = separated || messages.last().is_none_or(|(current, _)| current != role);
+        if starts_new {
+            messages.push((role.to_string(), Vec::new()));
+        }
+        if let Some((_, message_lines)) = messages.last_mut() {
+            message_lines.push(text.to_string());
+        }
+        separated = false;
+    }
+
+    messages
+        .into_iter()
+        .map(|(role, lines)| (role, lines.join("\n")))
+        .collect()
+}
+
+fn is_llm_role(token: &str) -> bool {
+    matches!(token, "SYSTEM" | "USER" | "ASSISTANT" | "TOOL" | "FUNCTION")
+}
+
+/// Aider writes local wall-clock time without an offset. Those values are
+/// read as UTC and marked `derived`, since the writer's zone is unknown; only
+/// timestamps that carry their own offset are `exact`.
+fn parse_aider_timestamp(raw: &str) -> Option<(u64, TimestampQuality)> {
+    let trimmed = raw.trim();
+    let has_offset = trimmed.ends_with('Z')
+        || trimmed
+            .get(19..)
+            .is_some_and(|suffix| suffix.contains('+') || suffix.contains('-'));
+    let (candidate, quality) = if has_offset {
+        (trimmed.replacen(' ', "T", 1), TimestampQuality::Exact)
+    } else {
+        (
+            format!("{}Z", trimmed.replacen(' ', "T", 1)),
+            TimestampQuality::Derived,
+        )
+    };
+    normalize_timestamp_exact(&candidate)
+        .ok()
+        .map(|normalized| (normalized.timestamp_unix_ms, quality))
+}
+
+fn fallback_timestamp_parts(line_number: usize) -> (u64, String, TimestampQuality) {
+    let fallback_unix_ms = line_number as u64;
+    (
+        fallback_unix_ms,
+        format_unix_ms(fallback_unix_ms),
+        TimestampQuality::Fallback,
+    )
+}
+
+fn scan_repo_dir(dir: &Path, depth: usize, out: &mut Vec<PathBuf>) -> Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) if depth > 0 => return Ok(()),
+        Err(error) => {
+            return Err(error)
+                .with_context(|| format!("failed to read aider scan root: {}", dir.display()));
+        }
+    };
+    let mut entries = entries
+        .filter_map(std::result::Result::ok)
+        .collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.path());
+
+    for entry in entries {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_file() {
+            if DEFAULT_PATHS.contains(&name.as_ref()) {
+                out.push(path);
+            }
+        } else if path.is_dir()
+            && depth < REPO_SCAN_MAX_DEPTH
+            && !name.starts_with('.')
+            && !SKIPPED_SCAN_DIRS.contains(&name.as_ref())
+        {
+            scan_repo_dir(&path, depth + 1, out)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+struct EventDraft {
+    record_format: RecordFormat,
+    event_type: EventType,
+    role: ActorRole,
+    line_number: usize,
+    text: String,
+    timestamp_unix_ms: u64,
+    timestamp_utc: String,
+    timestamp_quality: TimestampQuality,
+    session_id: Option<String>,
+    model: Option<String>,
+    tool_name: Option<String>,
+    tool_call_id: Option<String>,
+    tool_arguments_json: Option<String>,
+    metadata: BTreeMap<String, Value>,
+}
+
+impl EventDraft {
+    fn message(line_number: usize, text: String) -> Self {
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) =
+            fallback_timestamp_parts(line_number);
+        Self {
+            record_format: RecordFormat::Message,
+            event_type: EventType::Response,
+            role: ActorRole::Assistant,
+            line_number,
+            text,
+            timestamp_unix_ms,
+            timestamp_utc,
+            timestamp_quality,
+            session_id: None,
+            model: None,
+            tool_name: None,
+            tool_call_id: None,
+            tool_arguments_json: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+}
+
+struct EventBuilder<'a> {
+    run_id: &'a str,
+    source_path: &'a str,
+    source_path_hash: u64,
+    repo_root: Option<String>,
+    tag: &'static str,
+    content_mime: &'static str,
+    events: Vec<AgentLogEvent>,
+}
+
+impl<'a> EventBuilder<'a> {
+    fn new(run_id: &'a str, source_path: &'a str, tag: &'static str) -> Self {
+        let repo_root = Path::new(source_path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().to_string())
+            .filter(|parent| !parent.is_empty());
+        Self {
+            run_id,
+            source_path,
+            source_path_hash: hash64(&source_path.to_string()),
+            repo_root,
+            tag,
+            content_mime: if tag == "chat_history" {
+                "text/markdown"
+            } else {
+                "text/plain"
+            },
+            events: Vec::new(),
+        }
+    }
+
+    fn next_event_id(&self) -> String {
+        format!(
+            "aider-{:016x}-{:06}",
+            self.source_path_hash,
+            self.events.len() + 1
+        )
+    }
+
+    fn push(&mut self, draft: EventDraft, raw_lines: &[&str]) {
+        let event_id = self.next_event_id();
+        let normalized_content = draft.text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let raw_hash = format!("{:016x}", hash64(&raw_lines.join("\n")));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                "aider.transcript.v1",
+                draft.session_id.clone(),
+                actor_role_key(draft.role),
+                draft.timestamp_utc.clone(),
+                normalized_content
+            ))
+        );
+        let content_excerpt =
+            content::derive_excerpt(&draft.text, content::DEFAULT_EXCERPT_MAX_CHARS);
+
+        let mut metadata = draft.metadata;
+        metadata.insert("source_line".to_string(), json!(draft.line_number));
+        if let Some(repo_root) = &self.repo_root {
+            metadata.insert("aider_repo_root".to_string(), json!(repo_root));
+        }
+
+        let tool_result_text =
+            (draft.record_format == RecordFormat::ToolResult).then(|| draft.text.clone());
+
+        self.events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: self.run_id.to_string(),
+            sequence_global: self.events.len() as u64,
+            sequence_source: Some(self.events.len() as u64),
+            source_kind: AgentSource::Aider,
+            source_path: self.source_path.to_string(),
+            source_record_locator: format!("line:{}", draft.line_number),
+            source_record_hash: None,
+            adapter_name: AgentSource::Aider,
+            adapter_version: Some("v1".to_string()),
+            record_format: draft.record_format,
+            event_type: draft.event_type,
+            role: draft.role,
+            timestamp_utc: draft.timestamp_utc,
+            timestamp_unix_ms: draft.timestamp_unix_ms,
+            timestamp_quality: draft.timestamp_quality,
+            conversation_id: draft.session_id.clone(),
+            session_id: draft.session_id,
+            turn_id: None,
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: None,
+            model: draft.model,
+            content_text: Some(draft.text),
+            content_excerpt,
+            content_mime: Some(self.content_mime.to_string()),
+            tool_name: draft.tool_name,
+            tool_call_id: draft.tool_call_id,
+            tool_arguments_json: draft.tool_arguments_json,
+            tool_result_text,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+            tags: vec!["aider".to_string(), self.tag.to_string()],
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata,
+        });
+    }
+
+    fn finish(self) -> Vec<AgentLogEvent> {
+        self.events
+    }
+}
+
+const fn actor_role_key(role: ActorRole) -> &'static str {
+    match role {
+        ActorRole::User => "user",
+        ActorRole::Assistant => "assistant",
+        ActorRole::System => "system",
+        ActorRole::Tool => "tool",
+        ActorRole::Runtime => "runtime",
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::{Value, json};
+
+use crate::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use crate::utils::content;
+use crate::utils::hash::hash64;
+use crate::utils::time::{derive_timestamp, format_unix_ms, normalize_timestamp_exact};
+use crate::utils::vscdb::{self, ITEM_TABLE, KeySelector};
+
+pub const DEFAULT_PATHS: &[&str] = &[
+    "~/.config/Code/User/workspaceStorage",
+    "~/.config/Code/User/globalStorage/state.vscdb",
+    "~/Library/Application Support/Code/User/workspaceStorage",
+    "~/Library/Application Support/Code/User/globalStorage/state.vscdb",
+];
+
+pub const INTERACTIVE_SESSIONS_KEY: &str = "interactive.sessions";
+pub const CHAT_SESSIONS_DIR_NAME: &str = "chatSessions";
+
+const TOOL_INVOCATION_PART_KINDS: &[&str] = &["toolInvocation", "toolInvocationSerialized"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CopilotChatParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+pub fn parse_state_db_file(path: &Path, run_id: &str) -> Result<CopilotChatParseResult> {
+    let connection = vscdb::open_state_db(path)?;
+    let items = vscdb::read_json_entries(
+        &connection,
+        ITEM_TABLE,
+        &[KeySelector::Exact(INTERACTIVE_SESSIONS_KEY)],
+    )?;
+
+    let source_path = path.to_string_lossy();
+    let mut builder = CopilotEventBuilder::new(run_id, source_path.as_ref());
+    let mut warnings = items.warnings;
+    for entry in &items.entries {
+        let Some(sessions) = entry.value.as_array() else {
+            warnings.push(format!(
+                "key `{}`: expected an array of chat sessions; skipped",
+                entry.key
+            ));
+            continue;
+        };
+        for (index, session) in sessions.iter().enumerate() {
+            let locator = format!("{ITEM_TABLE}:{}[{index}]", entry.key);
+            map_session(session, &locator, &mut builder, &mut warnings);
+        }
+    }
+
+    Ok(CopilotChatParseResult {
+        events: builder.events,
+        warnings,
+    })
+}
+
+pub fn parse_chat_session_file(path: &Path, run_id: &str) -> Result<CopilotChatParseResult> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read copilot chat session file: {path:?}"))?;
+    parse_chat_session_json(&content, run_id, path.to_string_lossy().as_ref())
+}
+
+pub fn parse_chat_session_json(
+    input: &str,
+    run_id: &str,
+    source_path: &str,
+) -> Result<CopilotChatParseResult> {
+    let session =
+        serde_json::from_str::<Value>(input).context("copilot chat session must be valid JSON")?;
+    if session.get("requests").and_then(Value::as_array).is_none() {
+        anyhow::bail!("copilot chat session must include a `requests` array");
+    }
+
+    let mut builder = CopilotEventBuilder::new(run_id, source_path);
+    let mut warnings = Vec::new();
+    map_session(&session, "session", &mut builder, &mut warnings);
+    Ok(CopilotChatParseResult {
+        events: builder.events,
+        warnings,
+    })
+}
+
+#[must_use]
+pub fn is_chat_session_file(path: &Path) -> bool {
+    let in_sessions_dir = path
+        .parent()
+        .and_then(Path::file_name)
+        .is_some_and(|name| name == CHAT_SESSIONS_DIR_NAME);
+    let is_json = path
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    in_sessions_dir && is_json
+}
+
+fn map_session(
+    session: &Value,
+    locator: &str,
+    builder: &mut CopilotEventBuilder<'_>,
+    warnings: &mut Vec<String>,
+) {
+    let Some(requests) = session.get("requests").and_then(Value::as_array) else {
+        warnings.push(format!(
+            "{locator}: session has no `requests` array; skipped"
+        ));
+        return;
+    };
+    let session_id = string_at(session, "/sessionId")
+        .unwrap_or_else(|| format!("copilot-{:016x}", hash64(&(builder.source_path, locator))));
+    let created_ms = session.get("creationDate").and_then(timestamp_ms);
+
+    for (request_index, request) in requests.iter().enumerate() {
+        let request_locator = format!("{locator}.requests[{request_index}]");
+        let request_ms = request.get("timestamp").and_then(timestamp_ms);
+        let prompt_timestamp = request_ms
+            .map(|unix_ms| (unix_ms, TimestampQuality::Exact))
+            .or_else(|| {
+                created_ms
+                    .and_then(|anchor| derive_timestamp(anchor, request_index as u64 + 1).ok())
+                    .map(|derived| (derived.timestamp_unix_ms, TimestampQuality::Derived))
+            });
+        let response_timestamp = prompt_timestamp.and_then(|(unix_ms, _)| {
+            let elapsed = request
+                .pointer("/result/timings/totalElapsed")
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            derive_timestamp(unix_ms, elapsed)
+                .ok()
+                .map(|derived| (derived.timestamp_unix_ms, TimestampQuality::Derived))
+        });
+
+        let turn_id = string_at(request, "/requestId");
+        let model = string_at(request, "/modelId");
+        let mut metadata = BTreeMap::new();
+        if let Some(agent) = string_at(request, "/agent/id") {
+            metadata.insert("copilot_agent_id".to_string(), json!(agent));
+        }
+        if let Some(title) = string_at(session, "/customTitle") {
+            metadata.insert("copilot_session_title".to_string(), json!(title));
+        }
+        let context = CopilotTurnContext {
+            session_id: session_id.clone(),
+            turn_id,
+            model,
+            metadata,
+        };
+
+        match string_at(request, "/message/text") {
+            Some(text) => builder.push(
+                &context,
+                CopilotEventDraft {
+                    locator: format!("{request_locator}.message"),
+                    record_format: RecordFormat::Message,
+                    event_type: EventType::Prompt,
+                    role: ActorRole::User,
+                    text,
+                    timestamp: prompt_timestamp,
+                    tool_name: None,
+                    tool_call_id: None,
+                },
+                request,
+                warnings,
+            ),
+            None => warnings.push(format!(
+                "{request_locator}: missing `message.text`; prompt skipped"
+            )),
+        }
+
+        let parts = request
+            .get("response")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let mut markdown = Vec::new();
+        for (part_index, part) in parts.iter().enumerate() {
+            let kind = part.get("kind").and_then(Value::as_str).unwrap_or_default();
+            if TOOL_INVOCATION_PART_KINDS.contains(&kind) {
+                let Some(tool_name) = string_at(part, "/toolId") else {
+                    warnings.push(format!(
+                        "{request_locator}.response[{part_index}]: tool invocation without `toolId`; skipped"
+                    ));
+                    continue;
+                };
+                let text = string_at(part, "/invocationMessage/value")
+                    .or_else(|| string_at(part, "/invocationMessage"))
+                    .unwrap_or_else(|| tool_name.clone());
+                builder.push(
+                    &context,
+                    CopilotEventDraft {
+                        locator: format!("{request_locator}.response[{part_index}]"),
+                        record_format: RecordFormat::ToolCall,
+                        event_type: EventType::ToolInvocation,
+                        role: ActorRole::Assistant,
+                        text,
+                        timestamp: response_timestamp,
+                        tool_name: Some(tool_name),
+                        tool_call_id: string_at(part, "/toolCallId"),
+                    },
+                    part,
+                    warnings,
+                );
+            } else if matches!(kind, "" | "markdownContent")
+                && let Some(value) = part
+                    .pointer("/value")
+                    .or_else(|| part.pointer("/content/value"))
+                    .and_then(Value::as_str)
+            {
+                markdown.push(value);
+            }
+        }
+
+        let response_text = markdown.concat().trim().to_string();
+        if !response_text.is_empty() {
+            builder.push(
+                &context,
+                CopilotEventDraft {
+                    locator: format!("{request_locator}.response"),
+                    record_format: RecordFormat::Message,
+                    event_type: EventType::Response,
+                    role: ActorRole::Assistant,
+                    text: response_text,
+                    timestamp: response_timestamp,
+                    tool_name: None,
+                    tool_call_id: None,
+                },
+                request.get("response").unwrap_or(&Value::Null),
+                warnings,
+            );
+        }
+    }
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    let text = value.pointer(pointer)?.as_str()?.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn timestamp_ms(value: &Value) -> Option<u64> {
+    let raw = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        _ => return None,
+    };
+    normalize_timestamp_exact(&raw)
+        .ok()
+        .map(|normalized| normalized.timestamp_unix_ms)
+}
+
+#[derive(Debug, Clone)]
+struct CopilotTurnContext {
+    session_id: String,
+    turn_id: Option<String>,
+    model: Option<String>,
+    metadata: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone)]
+struct CopilotEventDraft {
+    locator: String,
+    record_format: RecordFormat,
+    event_type: EventType,
+    role: ActorRole,
+    text: String,
+    timestamp: Option<(u64, TimestampQuality)>,
+    tool_name: Option<String>,
+    tool_call_id: Option<String>,
+}
+
+struct CopilotEventBuilder<'a> {
+    run_id: &'a str,
+    source_path: &'a str,
+    source_path_hash: u64,
+    events: Vec<AgentLogEvent>,
+}
+
+impl<'a> CopilotEventBuilder<'a> {
+    fn new(run_id: &'a str, source_path: &'a str) -> Self {
+        Self {
+            run_id,
+            source_path,
+            source_path_hash: hash64(&source_path.to_string()),
+            events: Vec::new(),
+        }
+    }
+
+    fn push(
+        &mut self,
+        context: &CopilotTurnContext,
+        draft: CopilotEventDraft,
+        raw: &Value,
+        warnings: &mut Vec<String>,
+    ) {
+        let sequence = self.events.len() as u64;
+        let (timestamp_unix_ms, timestamp_quality) = draft.timestamp.unwrap_or_else(|| {
+            warnings.push(format!(
+                "{}: missing timestamp; using fallback timestamp",
+                draft.locator
+            ));
+            (sequence + 1, TimestampQuality::Fallback)
+        });
+        let timestamp_utc = format_unix_ms(timestamp_unix_ms);
+        let event_id = format!("copilot-{:016x}-{:06}", self.source_path_hash, sequence + 1);
+        let raw_hash = format!("{:016x}", hash64(&raw.to_string()));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                "copilot.chat.v1",
+                context.session_id.clone(),
+                draft.locator.clone(),
+                draft.text.split_whitespace().collect::<Vec<_>>().join(" "),
+                timestamp_utc.clone()
+            ))
+        );
+        let content_excerpt =
+            content::derive_excerpt(&draft.text, content::DEFAULT_EXCERPT_MAX_CHARS);
+
+        self.events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: self.run_id.to_string(),
+            sequence_global: sequence,
+            sequence_source: Some(sequence),
+            source_kind: AgentSource::Copilot,
+            source_path: self.source_path.to_string(),
+            source_record_locator: draft.locator,
+            source_record_hash: None,
+            adapter_name: AgentSource::Copilot,
+            adapter_version: Some("v1".to_string()),
+            record_format: draft.record_format,
+            event_type: draft.event_type,
+            role: draft.role,
+            timestamp_utc,
+            timestamp_unix_ms,
+            timestamp_quality,
+            session_id: Some(context.session_id.clone()),
+            conversation_id: Some(context.session_id.clone()),
+            turn_id: context.turn_id.clone(),
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: Some("github".to_string()),
+            model: context.model.clone(),
+            content_text: Some(draft.text),
+            content_excerpt,
+            content_mime: Some("text/markdown".to_string()),
+            tool_name: draft.tool_name,
+            tool_call_id: draft.tool_call_id,
+            tool_arguments_json: None,
+            tool_result_text: None,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+            tags: vec!["copilot".to_string(), "vscode_chat".to_string()],
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata: context.metadata.clone(),
+        });
+    }
+}
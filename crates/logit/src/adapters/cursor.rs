@@ -0,0 +1,513 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::Result;
+use serde_json::{Map, Value, json};
+
+use crate::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use crate::utils::content;
+use crate::utils::hash::hash64;
+use crate::utils::time::{derive_timestamp, format_unix_ms, normalize_timestamp_exact};
+use crate::utils::vscdb::{self, CURSOR_DISK_KV_TABLE, ITEM_TABLE, KeySelector, StateEntry};
+
+pub const DEFAULT_PATHS: &[&str] = &[
+    "~/.config/Cursor/User/globalStorage/state.vscdb",
+    "~/.config/Cursor/User/workspaceStorage",
+    "~/Library/Application Support/Cursor/User/globalStorage/state.vscdb",
+    "~/Library/Application Support/Cursor/User/workspaceStorage",
+];
+
+pub const CHAT_DATA_KEY: &str = "workbench.panel.aichat.view.aichat.chatdata";
+pub const COMPOSER_DATA_KEY_PREFIX: &str = "composerData:";
+pub const BUBBLE_KEY_PREFIX: &str = "bubbleId:";
+
+const COMPOSER_BUBBLE_USER: i64 = 1;
+const COMPOSER_BUBBLE_ASSISTANT: i64 = 2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorStateParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+pub fn parse_state_db_file(path: &Path, run_id: &str) -> Result<CursorStateParseResult> {
+    let connection = vscdb::open_state_db(path)?;
+    let items = vscdb::read_json_entries(
+        &connection,
+        ITEM_TABLE,
+        &[KeySelector::Exact(CHAT_DATA_KEY)],
+    )?;
+    let kv = vscdb::read_json_entries(
+        &connection,
+        CURSOR_DISK_KV_TABLE,
+        &[
+            KeySelector::Prefix(COMPOSER_DATA_KEY_PREFIX),
+            KeySelector::Prefix(BUBBLE_KEY_PREFIX),
+        ],
+    )?;
+
+    let mut entries = items.entries;
+    entries.extend(kv.entries);
+    let mut result = parse_state_entries(&entries, run_id, path.to_string_lossy().as_ref());
+    let mut warnings = items.warnings;
+    warnings.extend(kv.warnings);
+    warnings.append(&mut result.warnings);
+    result.warnings = warnings;
+    Ok(result)
+}
+
+#[must_use]
+pub fn parse_state_entries(
+    entries: &[StateEntry],
+    run_id: &str,
+    source_path: &str,
+) -> CursorStateParseResult {
+    let mut builder = CursorEventBuilder::new(run_id, source_path);
+    let mut warnings = Vec::new();
+
+    let bubbles = entries
+        .iter()
+        .filter_map(|entry| {
+            entry
+                .key
+                .strip_prefix(BUBBLE_KEY_PREFIX)
+                .map(|suffix| (suffix.to_string(), &entry.value))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    for entry in entries {
+        if entry.key == CHAT_DATA_KEY {
+            map_chat_tabs(entry, &mut builder, &mut warnings);
+        } else if let Some(composer_id) = entry.key.strip_prefix(COMPOSER_DATA_KEY_PREFIX) {
+            map_composer(entry, composer_id, &bubbles, &mut builder, &mut warnings);
+        }
+    }
+
+    CursorStateParseResult {
+        events: builder.events,
+        warnings,
+    }
+}
+
+fn map_chat_tabs(
+    entry: &StateEntry,
+    builder: &mut CursorEventBuilder<'_>,
+    warnings: &mut Vec<String>,
+) {
+    let Some(tabs) = entry.value.get("tabs").and_then(Value::as_array) else {
+        warnings.push(format!(
+            "key `{}`: missing `tabs` array; skipped",
+            entry.key
+        ));
+        return;
+    };
+
+    for (tab_index, tab) in tabs.iter().enumerate() {
+        let tab_id = string_field(tab, "tabId").unwrap_or_else(|| format!("tab-{}", tab_index + 1));
+        let bubbles = tab
+            .get("bubbles")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default();
+        let last_send_ms = tab.get("lastSendTime").and_then(timestamp_ms);
+        let bubble_count = bubbles.len() as u64;
+
+        for (bubble_index, bubble) in bubbles.iter().enumerate() {
+            let locator = format!(
+                "{ITEM_TABLE}:{}:tabs[{tab_index}].bubbles[{bubble_index}]",
+                entry.key
+            );
+            let kind = string_field(bubble, "type").unwrap_or_default();
+            let (event_type, role) = match kind.as_str() {
+                "user" => (EventType::Prompt, ActorRole::User),
+                "ai" => (EventType::Response, ActorRole::Assistant),
+                other => {
+                    warnings.push(format!("{locator}: unknown bubble type `{other}`; skipped"));
+                    continue;
+                }
+            };
+            let Some(text) =
+                string_field(bubble, "text").or_else(|| string_field(bubble, "rawText"))
+            else {
+                continue;
+            };
+
+            let timestamp = bubble
+                .get("timestamp")
+                .and_then(timestamp_ms)
+                .map(|unix_ms| (unix_ms, TimestampQuality::Exact))
+                .or_else(|| {
+                    last_send_ms.map(|last| {
+                        let remaining = bubble_count - 1 - bubble_index as u64;
+                        (last.saturating_sub(remaining), TimestampQuality::Derived)
+                    })
+                });
+
+            let mut metadata = BTreeMap::new();
+            metadata.insert("cursor_store".to_string(), json!("chat"));
+            if let Some(title) = string_field(tab, "chatTitle") {
+                metadata.insert("cursor_chat_title".to_string(), json!(title));
+            }
+            if let Some(bubble_id) = string_field(bubble, "id") {
+                metadata.insert("cursor_bubble_id".to_string(), json!(bubble_id));
+            }
+
+            builder.push(
+                CursorEventDraft {
+                    locator,
+                    record_format: RecordFormat::Message,
+                    event_type,
+                    role,
+                    text,
+                    timestamp,
+                    conversation_id: tab_id.clone(),
+                    model: string_field(bubble, "modelType"),
+                    tool_name: None,
+                    tool_call_id: None,
+                    tool_arguments_json: None,
+                    tool_result_text: None,
+                    input_tokens: None,
+                    output_tokens: None,
+                    metadata,
+                },
+                bubble,
+                warnings,
+            );
+        }
+    }
+}
+
+fn map_composer(
+    entry: &StateEntry,
+    composer_id: &str,
+    stored_bubbles: &BTreeMap<String, &Value>,
+    builder: &mut CursorEventBuilder<'_>,
+    warnings: &mut Vec<String>,
+) {
+    let composer = &entry.value;
+    let created_ms = composer.get("createdAt").and_then(timestamp_ms);
+    let composer_model = composer
+        .pointer("/modelConfig/modelName")
+        .and_then(Value::as_str)
+        .map(ToString::to_string);
+
+    let bubbles = if let Some(inline) = composer.get("conversation").and_then(Value::as_array) {
+        inline.iter().collect::<Vec<_>>()
+    } else if let Some(headers) = composer
+        .get("fullConversationHeadersOnly")
+        .and_then(Value::as_array)
+    {
+        headers
+            .iter()
+            .filter_map(|header| {
+                let bubble_id = string_field(header, "bubbleId")?;
+                let stored = stored_bubbles.get(&format!("{composer_id}:{bubble_id}")).copied();
+                if stored.is_none() {
+                    warnings.push(format!(
+                        "key `{}`: bubble `{bubble_id}` not found in {CURSOR_DISK_KV_TABLE}; skipped",
+                        entry.key
+                    ));
+                }
+                stored
+            })
+            .collect()
+    } else {
+        warnings.push(format!(
+            "key `{}`: composer has no conversation bubbles; skipped",
+            entry.key
+        ));
+        return;
+    };
+
+    for (bubble_index, bubble) in bubbles.into_iter().enumerate() {
+        let locator = format!(
+            "{CURSOR_DISK_KV_TABLE}:{}:bubbles[{bubble_index}]",
+            entry.key
+        );
+        let bubble_type = bubble.get("type").and_then(Value::as_i64);
+        let (event_type, role) = match bubble_type {
+            Some(COMPOSER_BUBBLE_USER) => (EventType::Prompt, ActorRole::User),
+            Some(COMPOSER_BUBBLE_ASSISTANT) => (EventType::Response, ActorRole::Assistant),
+            _ => {
+                warnings.push(format!(
+                    "{locator}: unknown composer bubble type {bubble_type:?}; skipped"
+                ));
+                continue;
+            }
+        };
+
+        let timestamp = bubble
+            .get("createdAt")
+            .and_then(timestamp_ms)
+            .or_else(|| {
+                bubble
+                    .pointer("/timingInfo/clientStartTime")
+                    .and_then(timestamp_ms)
+            })
+            .map(|unix_ms| (unix_ms, TimestampQuality::Exact))
+            .or_else(|| {
+                created_ms
+                    .and_then(|anchor| derive_timestamp(anchor, bubble_index as u64 + 1).ok())
+                    .map(|derived| (derived.timestamp_unix_ms, TimestampQuality::Derived))
+            });
+        let model = bubble
+            .pointer("/modelInfo/modelName")
+            .and_then(Value::as_str)
+            .map(ToString::to_string)
+            .or_else(|| composer_model.clone());
+        let input_tokens = bubble
+            .pointer("/tokenCount/inputTokens")
+            .and_then(Value::as_u64)
+            .filter(|tokens| *tokens > 0);
+        let output_tokens = bubble
+            .pointer("/tokenCount/outputTokens")
+            .and_then(Value::as_u64)
+            .filter(|tokens| *tokens > 0);
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("cursor_store".to_string(), json!("composer"));
+        if let Some(name) = string_field(composer, "name") {
+            metadata.insert("cursor_composer_name".to_string(), json!(name));
+        }
+        if let Some(bubble_id) = string_field(bubble, "bubbleId") {
+            metadata.insert("cursor_bubble_id".to_string(), json!(bubble_id));
+        }
+
+        if let Some(text) = string_field(bubble, "text") {
+            builder.push(
+                CursorEventDraft {
+                    locator: locator.clone(),
+                    record_format: RecordFormat::Message,
+                    event_type,
+                    role,
+                    text,
+                    timestamp,
+                    conversation_id: composer_id.to_string(),
+                    model: model.clone(),
+                    tool_name: None,
+                    tool_call_id: None,
+                    tool_arguments_json: None,
+                    tool_result_text: None,
+                    input_tokens,
+                    output_tokens,
+                    metadata: metadata.clone(),
+                },
+                bubble,
+                warnings,
+            );
+        }
+
+        let Some(tool) = bubble.get("toolFormerData").and_then(Value::as_object) else {
+            continue;
+        };
+        let Some(tool_name) = object_string(tool, "name").or_else(|| object_string(tool, "tool"))
+        else {
+            warnings.push(format!("{locator}: tool bubble without tool name; skipped"));
+            continue;
+        };
+        let tool_call_id = object_string(tool, "toolCallId");
+        let tool_arguments_json = tool
+            .get("rawArgs")
+            .or_else(|| tool.get("params"))
+            .map(|args| match args {
+                Value::String(raw) => raw.clone(),
+                other => other.to_string(),
+            });
+
+        builder.push(
+            CursorEventDraft {
+                locator: format!("{locator}:tool_call"),
+                record_format: RecordFormat::ToolCall,
+                event_type: EventType::ToolInvocation,
+                role: ActorRole::Assistant,
+                text: format!(
+                    "{tool_name} {}",
+                    tool_arguments_json.as_deref().unwrap_or("{}")
+                ),
+                timestamp,
+                conversation_id: composer_id.to_string(),
+                model: model.clone(),
+                tool_name: Some(tool_name.clone()),
+                tool_call_id: tool_call_id.clone(),
+                tool_arguments_json,
+                tool_result_text: None,
+                input_tokens: None,
+                output_tokens: None,
+                metadata: metadata.clone(),
+            },
+            bubble,
+            warnings,
+        );
+
+        let result_text = tool.get("result").and_then(|result| match result {
+            Value::String(raw) => Some(raw.clone()),
+            Value::Null => None,
+            other => Some(other.to_string()),
+        });
+        if let Some(result_text) = result_text.filter(|text| !text.trim().is_empty()) {
+            builder.push(
+                CursorEventDraft {
+                    locator: format!("{locator}:tool_result"),
+                    record_format: RecordFormat::ToolResult,
+                    event_type: EventType::ToolOutput,
+                    role: ActorRole::Tool,
+                    text: result_text.clone(),
+                    timestamp,
+                    conversation_id: composer_id.to_string(),
+                    model: None,
+                    tool_name: Some(tool_name),
+                    tool_call_id,
+                    tool_arguments_json: None,
+                    tool_result_text: Some(result_text),
+                    input_tokens: None,
+                    output_tokens: None,
+                    metadata,
+                },
+                bubble,
+                warnings,
+            );
+        }
+    }
+}
+
+fn string_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .as_object()
+        .and_then(|object| object_string(object, key))
+}
+
+fn object_string(object: &Map<String, Value>, key: &str) -> Option<String> {
+    let text = object.get(key)?.as_str()?.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn timestamp_ms(value: &Value) -> Option<u64> {
+    let raw = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(text) => text.clone(),
+        _ => return None,
+    };
+    normalize_timestamp_exact(&raw)
+        .ok()
+        .map(|normalized| normalized.timestamp_unix_ms)
+}
+
+#[derive(Debug, Clone)]
+struct CursorEventDraft {
+    locator: String,
+    record_format: RecordFormat,
+    event_type: EventType,
+    role: ActorRole,
+    text: String,
+    timestamp: Option<(u64, TimestampQuality)>,
+    conversation_id: String,
+    model: Option<String>,
+    tool_name: Option<String>,
+    tool_call_id: Option<String>,
+    tool_arguments_json: Option<String>,
+    tool_result_text: Option<String>,
+    input_tokens: Option<u64>,
+    output_tokens: Option<u64>,
+    metadata: BTreeMap<String, Value>,
+}
+
+struct CursorEventBuilder<'a> {
+    run_id: &'a str,
+    source_path: &'a str,
+    source_path_hash: u64,
+    events: Vec<AgentLogEvent>,
+}
+
+impl<'a> CursorEventBuilder<'a> {
+    fn new(run_id: &'a str, source_path: &'a str) -> Self {
+        Self {
+            run_id,
+            source_path,
+            source_path_hash: hash64(&source_path.to_string()),
+            events: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, draft: CursorEventDraft, raw: &Value, warnings: &mut Vec<String>) {
+        let sequence = self.events.len() as u64;
+        let (timestamp_unix_ms, timestamp_quality) = draft.timestamp.unwrap_or_else(|| {
+            warnings.push(format!(
+                "{}: missing timestamp; using fallback timestamp",
+                draft.locator
+            ));
+            (sequence + 1, TimestampQuality::Fallback)
+        });
+        let timestamp_utc = format_unix_ms(timestamp_unix_ms);
+        let event_id = format!("cursor-{:016x}-{:06}", self.source_path_hash, sequence + 1);
+        let raw_hash = format!("{:016x}", hash64(&raw.to_string()));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                "cursor.state.v1",
+                draft.conversation_id.clone(),
+                draft.locator.clone(),
+                draft.text.split_whitespace().collect::<Vec<_>>().join(" "),
+                timestamp_utc.clone()
+            ))
+        );
+        let content_excerpt =
+            content::derive_excerpt(&draft.text, content::DEFAULT_EXCERPT_MAX_CHARS);
+        let total_tokens = match (draft.input_tokens, draft.output_tokens) {
+            (None, None) => None,
+            (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+        };
+
+        self.events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: self.run_id.to_string(),
+            sequence_global: sequence,
+            sequence_source: Some(sequence),
+            source_kind: AgentSource::Cursor,
+            source_path: self.source_path.to_string(),
+            source_record_locator: draft.locator,
+            source_record_hash: None,
+            adapter_name: AgentSource::Cursor,
+            adapter_version: Some("v1".to_string()),
+            record_format: draft.record_format,
+            event_type: draft.event_type,
+            role: draft.role,
+            timestamp_utc,
+            timestamp_unix_ms,
+            timestamp_quality,
+            session_id: Some(draft.conversation_id.clone()),
+            conversation_id: Some(draft.conversation_id),
+            turn_id: None,
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: None,
+            model: draft.model,
+            content_text: Some(draft.text),
+            content_excerpt,
+            content_mime: Some("text/markdown".to_string()),
+            tool_name: draft.tool_name,
+            tool_call_id: draft.tool_call_id,
+            tool_arguments_json: draft.tool_arguments_json,
+            tool_result_text: draft.tool_result_text,
+            input_tokens: draft.input_tokens,
+            output_tokens: draft.output_tokens,
+            total_tokens,
+            cost_usd: None,
+            tags: vec!["cursor".to_string(), "state_vscdb".to_string()],
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata: draft.metadata,
+        });
+    }
+}
@@ -0,0 +1,697 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::adapters::openai_messages::{self, MessagePreset, MessageSource};
+use crate::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use crate::utils::content;
+use crate::utils::hash::hash64;
+use crate::utils::time::{format_unix_ms, normalize_timestamp_exact};
+
+pub const SPEC_DIR: &str = ".logit/adapters";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomRecordLayout {
+    #[default]
+    Jsonl,
+    Json,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomFieldMapping {
+    pub event_id: Option<String>,
+    pub timestamp: Option<String>,
+    pub role: Option<String>,
+    pub content: Option<String>,
+    pub tool_name: Option<String>,
+    pub tool_call_id: Option<String>,
+    pub tool_arguments: Option<String>,
+    pub tool_result: Option<String>,
+    pub session_id: Option<String>,
+    pub conversation_id: Option<String>,
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomAdapterSpec {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    pub paths: Vec<String>,
+    #[serde(default = "default_file_globs")]
+    pub file_globs: Vec<String>,
+    #[serde(default = "default_recursive")]
+    pub recursive: bool,
+    #[serde(default)]
+    pub format: CustomRecordLayout,
+    #[serde(default)]
+    pub records_pointer: Option<String>,
+    #[serde(default)]
+    pub preset: Option<MessagePreset>,
+    #[serde(default)]
+    pub mapping: CustomFieldMapping,
+    #[serde(default)]
+    pub role_aliases: BTreeMap<String, String>,
+    #[serde(skip)]
+    pub spec_path: Option<PathBuf>,
+    #[serde(skip)]
+    pub layout_from_extension: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct LoadedCustomAdapters {
+    pub specs: Vec<CustomAdapterSpec>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+fn default_file_globs() -> Vec<String> {
+    vec!["*.jsonl".to_string(), "*.json".to_string()]
+}
+
+const fn default_recursive() -> bool {
+    true
+}
+
+#[must_use]
+pub fn spec_dir(home_dir: &Path) -> PathBuf {
+    home_dir.join(SPEC_DIR)
+}
+
+/// Built-in adapter for `normalize --message-format`: reads `paths` with a
+/// message preset, picking the `json` or `jsonl` layout per file extension.
+#[must_use]
+pub fn message_format_spec(preset: MessagePreset, paths: Vec<String>) -> CustomAdapterSpec {
+    CustomAdapterSpec {
+        name: preset.as_str().to_string(),
+        version: Some("builtin".to_string()),
+        paths,
+        file_globs: vec![
+            "*.jsonl".to_string(),
+            "*.ndjson".to_string(),
+            "*.json".to_string(),
+        ],
+        recursive: default_recursive(),
+        format: CustomRecordLayout::Jsonl,
+        records_pointer: None,
+        preset: Some(preset),
+        mapping: CustomFieldMapping::default(),
+        role_aliases: BTreeMap::new(),
+        spec_path: None,
+        layout_from_extension: true,
+    }
+}
+
+pub fn load_custom_adapter_specs(dir: &Path) -> Result<LoadedCustomAdapters> {
+    if !dir.is_dir() {
+        return Ok(LoadedCustomAdapters::default());
+    }
+
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read custom adapter directory: {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<Vec<_>>>()
+        .with_context(|| {
+            format!(
+                "failed to enumerate custom adapter directory: {}",
+                dir.display()
+            )
+        })?;
+    paths.sort();
+
+    let mut loaded = LoadedCustomAdapters::default();
+    let mut seen_names = BTreeSet::new();
+    for path in paths {
+        let extension = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+        if !matches!(extension.as_deref(), Some("toml") | Some("json")) || !path.is_file() {
+            continue;
+        }
+
+        match parse_spec_file(&path) {
+            Ok(spec) if !seen_names.insert(spec.name.clone()) => {
+                loaded.warnings.push(format!(
+                    "custom adapter spec `{}` skipped: duplicate adapter name `{}`",
+                    path.display(),
+                    spec.name
+                ));
+            }
+            Ok(spec) => loaded.specs.push(spec),
+            Err(error) => loaded.warnings.push(format!(
+                "custom adapter spec `{}` rejected: {error:#}",
+                path.display()
+            )),
+        }
+    }
+
+    Ok(loaded)
+}
+
+pub fn parse_spec_file(path: &Path) -> Result<CustomAdapterSpec> {
+    let input = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read custom adapter spec: {}", path.display()))?;
+    let is_json = path
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    let mut spec = if is_json {
+        parse_spec_json(&input)?
+    } else {
+        parse_spec_toml(&input)?
+    };
+    spec.spec_path = Some(path.to_path_buf());
+    Ok(spec)
+}
+
+pub fn parse_spec_toml(input: &str) -> Result<CustomAdapterSpec> {
+    let spec = toml::from_str::<CustomAdapterSpec>(input)
+        .context("custom adapter spec must be valid TOML")?;
+    validate_spec(&spec)?;
+    Ok(spec)
+}
+
+pub fn parse_spec_json(input: &str) -> Result<CustomAdapterSpec> {
+    let spec = serde_json::from_str::<CustomAdapterSpec>(input)
+        .context("custom adapter spec must be valid JSON")?;
+    validate_spec(&spec)?;
+    Ok(spec)
+}
+
+fn validate_spec(spec: &CustomAdapterSpec) -> Result<()> {
+    if !AgentSource::is_valid_custom_name(&spec.name) {
+        bail!(
+            "adapter name `{}` must be a lowercase slug ([a-z][a-z0-9_-]*) that does not shadow a built-in adapter",
+            spec.name
+        );
+    }
+    if spec.paths.is_empty() {
+        bail!("adapter `{}` must declare at least one path", spec.name);
+    }
+    if spec.file_globs.is_empty() {
+        bail!(
+            "adapter `{}` must declare at least one file glob",
+            spec.name
+        );
+    }
+
+    let mapping = &spec.mapping;
+    let pointers = [
+        ("event_id", &mapping.event_id),
+        ("timestamp", &mapping.timestamp),
+        ("role", &mapping.role),
+        ("content", &mapping.content),
+        ("tool_name", &mapping.tool_name),
+        ("tool_call_id", &mapping.tool_call_id),
+        ("tool_arguments", &mapping.tool_arguments),
+        ("tool_result", &mapping.tool_result),
+        ("session_id", &mapping.session_id),
+        ("conversation_id", &mapping.conversation_id),
+        ("model", &mapping.model),
+        ("records_pointer", &spec.records_pointer),
+    ];
+    for (field, pointer) in pointers {
+        if let Some(pointer) = pointer
+            && !pointer.is_empty()
+            && !pointer.starts_with('/')
+        {
+            bail!(
+                "adapter `{}` {field} mapping `{pointer}` must be a JSON pointer starting with `/`",
+                spec.name
+            );
+        }
+    }
+    if let Some(preset) = spec.preset {
+        if *mapping != CustomFieldMapping::default() || !spec.role_aliases.is_empty() {
+            bail!(
+                "adapter `{}` preset `{}` defines its own field mapping; remove `mapping` and `role_aliases`",
+                spec.name,
+                preset.as_str()
+            );
+        }
+        return Ok(());
+    }
+    if mapping.content.is_none() && mapping.tool_name.is_none() && mapping.tool_result.is_none() {
+        bail!(
+            "adapter `{}` mapping must declare at least one of content, tool_name or tool_result",
+            spec.name
+        );
+    }
+    for (alias, role) in &spec.role_aliases {
+        if canonical_role(role).is_none() {
+            bail!(
+                "adapter `{}` role alias `{alias}` maps to unknown role `{role}`",
+                spec.name
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[must_use]
+pub fn matches_file_glob(spec: &CustomAdapterSpec, path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(std::ffi::OsStr::to_str) else {
+        return false;
+    };
+    spec.file_globs
+        .iter()
+        .any(|pattern| wildcard_matches(pattern.as_bytes(), file_name.as_bytes()))
+}
+
+pub fn parse_custom_source_file(
+    spec: &CustomAdapterSpec,
+    path: &Path,
+    run_id: &str,
+) -> Result<CustomParseResult> {
+    let input = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "failed to read source file for custom adapter `{}`: {}",
+            spec.name,
+            path.display()
+        )
+    })?;
+    let is_json = path
+        .extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    if spec.layout_from_extension && is_json {
+        let spec = CustomAdapterSpec {
+            format: CustomRecordLayout::Json,
+            ..spec.clone()
+        };
+        return parse_custom_records(&spec, &input, run_id, path.to_string_lossy().as_ref());
+    }
+    parse_custom_records(spec, &input, run_id, path.to_string_lossy().as_ref())
+}
+
+pub fn parse_custom_records(
+    spec: &CustomAdapterSpec,
+    input: &str,
+    run_id: &str,
+    source_path: &str,
+) -> Result<CustomParseResult> {
+    let mut warnings = Vec::new();
+    let records = match spec.format {
+        CustomRecordLayout::Jsonl => {
+            let mut records = Vec::new();
+            for (index, line) in input.lines().enumerate() {
+                let line_number = index + 1;
+                let trimmed = line.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(trimmed) {
+                    Ok(value) => records.push((format!("line:{line_number}"), value)),
+                    Err(error) => {
+                        warnings.push(format!("line {line_number}: invalid JSON ({error})"));
+                    }
+                }
+            }
+            records
+        }
+        CustomRecordLayout::Json => {
+            let parsed = serde_json::from_str::<Value>(input).with_context(|| {
+                format!("custom adapter `{}` payload must be valid JSON", spec.name)
+            })?;
+            let pointer = spec.records_pointer.as_deref().unwrap_or_else(|| {
+                if spec.preset.is_some() && parsed.get("messages").is_some_and(Value::is_array) {
+                    "/messages"
+                } else {
+                    ""
+                }
+            });
+            let selected = parsed.pointer(pointer).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "custom adapter `{}` records_pointer `{pointer}` not found in payload",
+                    spec.name
+                )
+            })?;
+            match selected {
+                Value::Array(items) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| (format!("index:{}", index + 1), item.clone()))
+                    .collect(),
+                other => vec![("root".to_string(), other.clone())],
+            }
+        }
+    };
+
+    let source_kind = AgentSource::Custom(spec.name.clone());
+    if let Some(preset) = spec.preset {
+        let mut metadata = BTreeMap::new();
+        if let Some(spec_path) = &spec.spec_path {
+            metadata.insert(
+                "custom_adapter_spec".to_string(),
+                serde_json::json!(spec_path.to_string_lossy()),
+            );
+        }
+        let adapter_version = spec.version.as_deref().unwrap_or("custom");
+        let source = MessageSource {
+            source_kind,
+            adapter_version,
+            run_id,
+            source_path,
+            tags: vec![
+                spec.name.clone(),
+                "custom_adapter".to_string(),
+                preset.as_str().to_string(),
+            ],
+            metadata,
+        };
+        let mut parsed = openai_messages::map_message_records(preset, &records, &source);
+        warnings.append(&mut parsed.warnings);
+        return Ok(CustomParseResult {
+            events: parsed.events,
+            warnings,
+        });
+    }
+
+    let source_path_hash = hash64(&source_path.to_string());
+    let mut events = Vec::new();
+
+    for (index, (locator, record)) in records.iter().enumerate() {
+        let record_number = index + 1;
+        if !record.is_object() {
+            warnings.push(format!(
+                "record {record_number}: entry is not an object; skipped"
+            ));
+            continue;
+        }
+
+        let mapping = &spec.mapping;
+        let role_hint = pointer_string(record, mapping.role.as_deref());
+        let tool_name = pointer_string(record, mapping.tool_name.as_deref());
+        let tool_call_id = pointer_string(record, mapping.tool_call_id.as_deref());
+        let tool_arguments_json = pointer_value(record, mapping.tool_arguments.as_deref())
+            .and_then(|value| match value {
+                Value::String(raw) => Some(raw.clone()),
+                other => serde_json::to_string(other).ok(),
+            });
+        let tool_result_text =
+            pointer_value(record, mapping.tool_result.as_deref()).and_then(content::extract_text);
+        let (record_format, event_type, role) = classify_custom_record(
+            spec,
+            role_hint.as_deref(),
+            tool_name.is_some() || tool_arguments_json.is_some(),
+            tool_result_text.is_some(),
+            record_number,
+            &mut warnings,
+        );
+
+        let content_text = pointer_value(record, mapping.content.as_deref())
+            .and_then(content::extract_text)
+            .or_else(|| tool_result_text.clone());
+        let content_excerpt = content_text
+            .as_deref()
+            .and_then(|text| content::derive_excerpt(text, content::DEFAULT_EXCERPT_MAX_CHARS));
+
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) =
+            map_custom_timestamp(record, mapping, record_number, &mut warnings);
+
+        let source_event_id = pointer_string(record, mapping.event_id.as_deref());
+        let event_id = source_event_id
+            .as_ref()
+            .map(|value| format!("{}-{source_path_hash:016x}-{value}", spec.name))
+            .unwrap_or_else(|| format!("{}-{source_path_hash:016x}-{record_number:06}", spec.name));
+        let session_id = pointer_string(record, mapping.session_id.as_deref());
+        let conversation_id = pointer_string(record, mapping.conversation_id.as_deref())
+            .or_else(|| session_id.clone());
+
+        let raw_hash = format!("{:016x}", hash64(&record.to_string()));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                event_id.clone(),
+                source_path,
+                locator,
+                role_hint.clone(),
+                content_text.clone(),
+                tool_name.clone()
+            ))
+        );
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert("source_index".to_string(), serde_json::json!(record_number));
+        if let Some(spec_path) = &spec.spec_path {
+            metadata.insert(
+                "custom_adapter_spec".to_string(),
+                serde_json::json!(spec_path.to_string_lossy()),
+            );
+        }
+        if let Some(role) = &role_hint {
+            metadata.insert("custom_role".to_string(), serde_json::json!(role));
+        }
+        if let Some(source_event_id) = &source_event_id {
+            metadata.insert(
+                "custom_source_event_id".to_string(),
+                serde_json::json!(source_event_id),
+            );
+        }
+
+        events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: run_id.to_string(),
+            sequence_global: events.len() as u64,
+            sequence_source: Some(index as u64),
+            source_kind: source_kind.clone(),
+            source_path: source_path.to_string(),
+            source_record_locator: locator.clone(),
+            source_record_hash: None,
+            adapter_name: source_kind.clone(),
+            adapter_version: Some(spec.version.clone().unwrap_or_else(|| "custom".to_string())),
+            record_format,
+            event_type,
+            role,
+            timestamp_utc,
+            timestamp_unix_ms,
+            timestamp_quality,
+            session_id,
+            conversation_id,
+            turn_id: None,
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: None,
+            model: pointer_string(record, mapping.model.as_deref()),
+            content_text,
+            content_excerpt,
+            content_mime: Some("text/plain".to_string()),
+            tool_name,
+            tool_call_id,
+            tool_arguments_json,
+            tool_result_text,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+            tags: vec![spec.name.clone(), "custom_adapter".to_string()],
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata,
+        });
+    }
+
+    Ok(CustomParseResult { events, warnings })
+}
+
+fn classify_custom_record(
+    spec: &CustomAdapterSpec,
+    role_hint: Option<&str>,
+    has_tool_call: bool,
+    has_tool_result: bool,
+    record_number: usize,
+    warnings: &mut Vec<String>,
+) -> (RecordFormat, EventType, ActorRole) {
+    if has_tool_result {
+        return (
+            RecordFormat::ToolResult,
+            EventType::ToolOutput,
+            ActorRole::Tool,
+        );
+    }
+    if has_tool_call {
+        return (
+            RecordFormat::ToolCall,
+            EventType::ToolInvocation,
+            ActorRole::Tool,
+        );
+    }
+
+    let Some(role_hint) = role_hint else {
+        return (
+            RecordFormat::Diagnostic,
+            EventType::DebugLog,
+            ActorRole::Runtime,
+        );
+    };
+    let normalized = role_hint.to_ascii_lowercase();
+    let aliased = spec
+        .role_aliases
+        .get(&normalized)
+        .map_or(normalized.as_str(), String::as_str);
+
+    match canonical_role(aliased) {
+        Some(ActorRole::User) => (RecordFormat::Message, EventType::Prompt, ActorRole::User),
+        Some(ActorRole::Assistant) => (
+            RecordFormat::Message,
+            EventType::Response,
+            ActorRole::Assistant,
+        ),
+        Some(ActorRole::System) => (
+            RecordFormat::System,
+            EventType::SystemNotice,
+            ActorRole::System,
+        ),
+        Some(ActorRole::Tool) => (
+            RecordFormat::ToolResult,
+            EventType::ToolOutput,
+            ActorRole::Tool,
+        ),
+        Some(ActorRole::Runtime) => (
+            RecordFormat::Diagnostic,
+            EventType::DebugLog,
+            ActorRole::Runtime,
+        ),
+        None => {
+            warnings.push(format!(
+                "record {record_number}: unknown role `{role_hint}`; mapped to diagnostic runtime event"
+            ));
+            (
+                RecordFormat::Diagnostic,
+                EventType::DebugLog,
+                ActorRole::Runtime,
+            )
+        }
+    }
+}
+
+fn canonical_role(role: &str) -> Option<ActorRole> {
+    match role.to_ascii_lowercase().as_str() {
+        "user" | "human" => Some(ActorRole::User),
+        "assistant" | "model" | "ai" | "bot" => Some(ActorRole::Assistant),
+        "system" => Some(ActorRole::System),
+        "tool" | "function" => Some(ActorRole::Tool),
+        "runtime" | "log" => Some(ActorRole::Runtime),
+        _ => None,
+    }
+}
+
+fn map_custom_timestamp(
+    record: &Value,
+    mapping: &CustomFieldMapping,
+    record_number: usize,
+    warnings: &mut Vec<String>,
+) -> (u64, String, TimestampQuality) {
+    if let Some(raw_timestamp) = pointer_string(record, mapping.timestamp.as_deref()) {
+        match normalize_timestamp_exact(&raw_timestamp) {
+            Ok(normalized) => {
+                return (
+                    normalized.timestamp_unix_ms,
+                    normalized.timestamp_utc(),
+                    normalized.timestamp_quality,
+                );
+            }
+            Err(error) => {
+                warnings.push(format!(
+                    "record {record_number}: invalid timestamp `{raw_timestamp}` ({error}); using fallback"
+                ));
+            }
+        }
+    } else {
+        warnings.push(format!(
+            "record {record_number}: missing timestamp; using fallback"
+        ));
+    }
+
+    let fallback_unix_ms = record_number as u64;
+    (
+        fallback_unix_ms,
+        format_unix_ms(fallback_unix_ms),
+        TimestampQuality::Fallback,
+    )
+}
+
+fn pointer_value<'a>(record: &'a Value, pointer: Option<&str>) -> Option<&'a Value> {
+    record.pointer(pointer?).filter(|value| !value.is_null())
+}
+
+fn pointer_string(record: &Value, pointer: Option<&str>) -> Option<String> {
+    match pointer_value(record, pointer)? {
+        Value::String(raw) => {
+            let trimmed = raw.trim();
+            (!trimmed.is_empty()).then(|| trimmed.to_string())
+        }
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(boolean) => Some(boolean.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Matches `*` (any run of bytes) and `?` (any single byte) against `text`.
+#[must_use]
+pub fn wildcard_matches(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0_usize, 0_usize);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(b'?') => {
+                p += 1;
+                t += 1;
+            }
+            Some(byte) if *byte == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => {
+                let Some((star_p, star_t)) = backtrack else {
+                    return false;
+                };
+                backtrack = Some((star_p, star_t + 1));
+                p = star_p + 1;
+                t = star_t + 1;
+            }
+        }
+    }
+
+    pattern[p..].iter().all(|byte| *byte == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildcard_matches;
+
+    #[test]
+    fn wildcard_matches_star_and_question_mark() {
+        assert!(wildcard_matches(b"*.jsonl", b"session.jsonl"));
+        assert!(wildcard_matches(b"session-??.json", b"session-01.json"));
+        assert!(wildcard_matches(b"*", b"anything"));
+        assert!(!wildcard_matches(b"*.jsonl", b"session.json"));
+        assert!(!wildcard_matches(b"session-?.json", b"session-10.json"));
+    }
+}
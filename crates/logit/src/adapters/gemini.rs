@@ -9,6 +9,7 @@ use crate::models::{
 };
 use crate::utils::content;
 use crate::utils::hash::hash64;
+use crate::utils::protobuf;
 use crate::utils::time::{format_unix_ms, normalize_timestamp_exact};
 
 pub const DEFAULT_PATHS: &[&str] = &["~/.gemini/tmp", "~/.gemini/history", "~/.gemini/debug"];
@@ -25,6 +26,36 @@ pub struct GeminiChatParseResult {
     pub warnings: Vec<String>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeminiProtobufParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+const PB_CONVERSATION_ID_FIELD: u32 = 1;
+const PB_CONVERSATION_SESSION_ID_FIELD: u32 = 2;
+const PB_CONVERSATION_MODEL_FIELD: u32 = 3;
+const PB_CONVERSATION_MESSAGES_FIELD: u32 = 4;
+const PB_CONVERSATION_KNOWN_FIELDS: &[u32] = &[
+    PB_CONVERSATION_ID_FIELD,
+    PB_CONVERSATION_SESSION_ID_FIELD,
+    PB_CONVERSATION_MODEL_FIELD,
+    PB_CONVERSATION_MESSAGES_FIELD,
+];
+
+const PB_MESSAGE_ID_FIELD: u32 = 1;
+const PB_MESSAGE_ROLE_FIELD: u32 = 2;
+const PB_MESSAGE_CONTENT_FIELD: u32 = 3;
+const PB_MESSAGE_TIMESTAMP_FIELD: u32 = 4;
+const PB_MESSAGE_MODEL_FIELD: u32 = 5;
+const PB_MESSAGE_KNOWN_FIELDS: &[u32] = &[
+    PB_MESSAGE_ID_FIELD,
+    PB_MESSAGE_ROLE_FIELD,
+    PB_MESSAGE_CONTENT_FIELD,
+    PB_MESSAGE_TIMESTAMP_FIELD,
+    PB_MESSAGE_MODEL_FIELD,
+];
+
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 struct GeminiToolDetails {
     tool_name: Option<String>,
@@ -417,6 +448,264 @@ pub fn parse_chat_session_json(
     Ok(GeminiChatParseResult { events, warnings })
 }
 
+pub fn parse_conversation_protobuf_file(
+    path: &Path,
+    run_id: &str,
+) -> Result<GeminiProtobufParseResult> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read gemini protobuf conversation file: {path:?}"))?;
+    parse_conversation_protobuf_bytes(&bytes, run_id, path.to_string_lossy().as_ref())
+}
+
+pub fn parse_conversation_protobuf_bytes(
+    bytes: &[u8],
+    run_id: &str,
+    source_path: &str,
+) -> Result<GeminiProtobufParseResult> {
+    // `.pb` files under `~/.gemini` are not all conversations (and Gemini CLI
+    // leaves 0-byte ones behind), so anything that does not match the layout
+    // is skipped with a warning instead of failing the adapter.
+    let skipped = |reason: String| GeminiProtobufParseResult {
+        events: Vec::new(),
+        warnings: vec![format!("{reason}; skipped `{source_path}`")],
+    };
+    if bytes.is_empty() {
+        return Ok(skipped("gemini protobuf file is empty".to_string()));
+    }
+    let root = match protobuf::decode_message(bytes) {
+        Ok(root) => root,
+        Err(error) => {
+            return Ok(skipped(format!(
+                "gemini protobuf file is not valid protobuf wire data ({error})"
+            )));
+        }
+    };
+    let messages = protobuf::repeated_field(&root, PB_CONVERSATION_MESSAGES_FIELD);
+    if messages.is_empty() {
+        return Ok(skipped(format!(
+            "gemini protobuf file has no repeated message field {PB_CONVERSATION_MESSAGES_FIELD}"
+        )));
+    }
+
+    let mut events = Vec::new();
+    let mut warnings = Vec::new();
+    let source_path_hash = hash64(&source_path.to_string());
+
+    let root_conversation_id = protobuf_string(&root, PB_CONVERSATION_ID_FIELD);
+    let root_session_id = protobuf_string(&root, PB_CONVERSATION_SESSION_ID_FIELD);
+    let root_model = protobuf_string(&root, PB_CONVERSATION_MODEL_FIELD);
+    let root_unknown_fields = unknown_protobuf_fields(&root, PB_CONVERSATION_KNOWN_FIELDS);
+
+    for (index, message) in messages.into_iter().enumerate() {
+        let message_number = index + 1;
+        if !message.is_object() {
+            warnings.push(format!(
+                "message {message_number}: protobuf field {PB_CONVERSATION_MESSAGES_FIELD} entry is not a nested message; skipped"
+            ));
+            continue;
+        }
+
+        let role_hint = protobuf_string(message, PB_MESSAGE_ROLE_FIELD);
+        let (record_format, event_type, role) =
+            classify_chat_role(role_hint.as_deref(), message_number, &mut warnings);
+
+        let source_event_id = protobuf_string(message, PB_MESSAGE_ID_FIELD);
+        let event_id = source_event_id
+            .as_ref()
+            .map(|value| format!("gemini-{source_path_hash:016x}-{value}"))
+            .unwrap_or_else(|| format!("gemini-pb-{source_path_hash:016x}-{message_number:06}"));
+        let content_text =
+            protobuf::field(message, PB_MESSAGE_CONTENT_FIELD).and_then(protobuf_content_text);
+        if content_text.is_none() {
+            warnings.push(format!(
+                "message {message_number}: missing content text; emitting empty content"
+            ));
+        }
+        let content_excerpt = content_text
+            .as_deref()
+            .and_then(|text| content::derive_excerpt(text, content::DEFAULT_EXCERPT_MAX_CHARS));
+
+        let (timestamp_unix_ms, timestamp_utc, timestamp_quality) =
+            map_protobuf_timestamp(message, message_number, &mut warnings);
+        let model = protobuf_string(message, PB_MESSAGE_MODEL_FIELD).or_else(|| root_model.clone());
+
+        let raw_hash = format!("{:016x}", hash64(&message.to_string()));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                event_id.clone(),
+                root_conversation_id.clone(),
+                root_session_id.clone(),
+                role_hint.clone(),
+                content_text.clone(),
+                message_number
+            ))
+        );
+
+        let mut metadata = BTreeMap::new();
+        metadata.insert(
+            "source_index".to_string(),
+            serde_json::json!(message_number),
+        );
+        if let Some(role) = &role_hint {
+            metadata.insert("gemini_role".to_string(), serde_json::json!(role));
+        }
+        if let Some(source_event_id) = &source_event_id {
+            metadata.insert(
+                "gemini_source_event_id".to_string(),
+                serde_json::json!(source_event_id),
+            );
+        }
+        let unknown_fields = unknown_protobuf_fields(message, PB_MESSAGE_KNOWN_FIELDS);
+        if !unknown_fields.is_empty() {
+            metadata.insert(
+                "gemini_pb_unknown_fields".to_string(),
+                Value::Object(unknown_fields),
+            );
+        }
+        if !root_unknown_fields.is_empty() {
+            metadata.insert(
+                "gemini_pb_root_unknown_fields".to_string(),
+                Value::Object(root_unknown_fields.clone()),
+            );
+        }
+
+        events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: run_id.to_string(),
+            sequence_global: events.len() as u64,
+            sequence_source: Some(index as u64),
+            source_kind: AgentSource::Gemini,
+            source_path: source_path.to_string(),
+            source_record_locator: format!("protobuf:messages:{message_number}"),
+            source_record_hash: None,
+            adapter_name: AgentSource::Gemini,
+            adapter_version: Some("v1".to_string()),
+            record_format,
+            event_type,
+            role,
+            timestamp_utc,
+            timestamp_unix_ms,
+            timestamp_quality,
+            session_id: root_session_id.clone(),
+            conversation_id: root_conversation_id.clone(),
+            turn_id: None,
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: Some("google".to_string()),
+            model,
+            content_text,
+            content_excerpt,
+            content_mime: Some("text/plain".to_string()),
+            tool_name: None,
+            tool_call_id: None,
+            tool_arguments_json: None,
+            tool_result_text: None,
+            input_tokens: None,
+            output_tokens: None,
+            total_tokens: None,
+            cost_usd: None,
+            tags: vec!["gemini".to_string(), "conversation_protobuf".to_string()],
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata,
+        });
+    }
+
+    Ok(GeminiProtobufParseResult { events, warnings })
+}
+
+fn protobuf_string(message: &Value, field_number: u32) -> Option<String> {
+    protobuf::field(message, field_number).and_then(scalar_to_string)
+}
+
+fn protobuf_content_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(_) => scalar_to_string(value),
+        Value::Array(items) => {
+            let fragments = items
+                .iter()
+                .filter_map(protobuf_content_text)
+                .collect::<Vec<_>>();
+            (!fragments.is_empty()).then(|| fragments.join("\n"))
+        }
+        Value::Object(_) => protobuf::field(value, 1).and_then(protobuf_content_text),
+        Value::Null | Value::Bool(_) | Value::Number(_) => None,
+    }
+}
+
+fn map_protobuf_timestamp(
+    message: &Value,
+    message_number: usize,
+    warnings: &mut Vec<String>,
+) -> (u64, String, TimestampQuality) {
+    let raw_timestamp = match protobuf::field(message, PB_MESSAGE_TIMESTAMP_FIELD) {
+        Some(Value::Number(epoch)) => Some(epoch.to_string()),
+        Some(timestamp @ Value::Object(_)) => {
+            let seconds = protobuf::field(timestamp, 1).and_then(Value::as_u64);
+            let nanos = protobuf::field(timestamp, 2)
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            seconds
+                .and_then(|seconds| seconds.checked_mul(1_000))
+                .and_then(|millis| millis.checked_add(nanos / 1_000_000))
+                .map(|millis| millis.to_string())
+        }
+        _ => None,
+    };
+
+    if let Some(raw_timestamp) = raw_timestamp {
+        match normalize_timestamp_exact(&raw_timestamp) {
+            Ok(normalized) => {
+                return (
+                    normalized.timestamp_unix_ms,
+                    normalized.timestamp_utc(),
+                    normalized.timestamp_quality,
+                );
+            }
+            Err(error) => {
+                warnings.push(format!(
+                    "message {message_number}: invalid timestamp `{raw_timestamp}` ({error}); using fallback"
+                ));
+            }
+        }
+    } else {
+        warnings.push(format!(
+            "message {message_number}: missing timestamp; using fallback"
+        ));
+    }
+
+    let fallback_unix_ms = message_number as u64;
+    (
+        fallback_unix_ms,
+        format_unix_ms(fallback_unix_ms),
+        TimestampQuality::Fallback,
+    )
+}
+
+fn unknown_protobuf_fields(
+    message: &Value,
+    known_fields: &[u32],
+) -> serde_json::Map<String, Value> {
+    let Some(object) = message.as_object() else {
+        return serde_json::Map::new();
+    };
+    object
+        .iter()
+        .filter(|(key, _)| {
+            key.parse::<u32>()
+                .map_or(true, |field_number| !known_fields.contains(&field_number))
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
 fn classify_record(
     role_hint: Option<&str>,
     level_hint: Option<&str>,
@@ -1,7 +1,12 @@
+pub mod aider;
 pub mod amp;
 pub mod claude;
 pub mod codex;
+pub mod copilot;
+pub mod cursor;
+pub mod custom;
 pub mod gemini;
+pub mod openai_messages;
 pub mod opencode;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -11,6 +16,9 @@ pub enum AdapterKind {
     Gemini,
     Amp,
     OpenCode,
+    Aider,
+    Cursor,
+    Copilot,
 }
 
 impl AdapterKind {
@@ -22,18 +30,32 @@ impl AdapterKind {
             Self::Gemini => "gemini",
             Self::Amp => "amp",
             Self::OpenCode => "opencode",
+            Self::Aider => "aider",
+            Self::Cursor => "cursor",
+            Self::Copilot => "copilot",
         }
     }
+
+    #[must_use]
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        all_adapter_kinds()
+            .into_iter()
+            .find(|kind| kind.as_str().eq_ignore_ascii_case(raw))
+    }
 }
 
 #[must_use]
-pub const fn all_adapter_kinds() -> [AdapterKind; 5] {
+pub const fn all_adapter_kinds() -> [AdapterKind; 8] {
     [
         AdapterKind::Codex,
         AdapterKind::Claude,
         AdapterKind::Gemini,
         AdapterKind::Amp,
         AdapterKind::OpenCode,
+        AdapterKind::Aider,
+        AdapterKind::Cursor,
+        AdapterKind::Copilot,
     ]
 }
 
@@ -45,5 +67,8 @@ pub const fn default_paths(kind: AdapterKind) -> &'static [&'static str] {
         AdapterKind::Gemini => gemini::DEFAULT_PATHS,
         AdapterKind::Amp => amp::DEFAULT_PATHS,
         AdapterKind::OpenCode => opencode::DEFAULT_PATHS,
+        AdapterKind::Aider => aider::DEFAULT_PATHS,
+        AdapterKind::Cursor => cursor::DEFAULT_PATHS,
+        AdapterKind::Copilot => copilot::DEFAULT_PATHS,
     }
 }
@@ -0,0 +1,548 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use crate::utils::content;
+use crate::utils::hash::hash64;
+use crate::utils::time::{derive_timestamp, format_unix_ms, normalize_timestamp_exact};
+
+const TIMESTAMP_KEYS: &[&str] = &["timestamp", "created_at", "created"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum MessagePreset {
+    #[serde(rename = "openai-messages", alias = "openai_messages")]
+    OpenAiMessages,
+    #[serde(rename = "goose")]
+    Goose,
+}
+
+impl MessagePreset {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::OpenAiMessages => "openai-messages",
+            Self::Goose => "goose",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "openai-messages" | "openai_messages" => Ok(Self::OpenAiMessages),
+            "goose" => Ok(Self::Goose),
+            other => Err(format!(
+                "unknown message format `{other}` (expected openai-messages or goose)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSource<'a> {
+    pub source_kind: AgentSource,
+    pub adapter_version: &'a str,
+    pub run_id: &'a str,
+    pub source_path: &'a str,
+    pub tags: Vec<String>,
+    pub metadata: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageParseResult {
+    pub events: Vec<AgentLogEvent>,
+    pub warnings: Vec<String>,
+}
+
+#[must_use]
+pub fn map_message_records(
+    preset: MessagePreset,
+    records: &[(String, Value)],
+    source: &MessageSource<'_>,
+) -> MessageParseResult {
+    let session_id = Path::new(source.source_path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty());
+    let mut builder = MessageEventBuilder {
+        source,
+        session_id,
+        source_path_hash: hash64(&source.source_path.to_string()),
+        session_metadata: BTreeMap::new(),
+        last_timestamp_ms: None,
+        events: Vec::new(),
+    };
+    let mut warnings = Vec::new();
+    let mut pending_tool_calls = BTreeMap::<String, String>::new();
+
+    for (index, (locator, record)) in records.iter().enumerate() {
+        let record_number = index + 1;
+        let Some(object) = record.as_object() else {
+            warnings.push(format!(
+                "record {record_number}: entry is not an object; skipped"
+            ));
+            continue;
+        };
+
+        if !object.contains_key("role") {
+            if preset == MessagePreset::Goose && index == 0 {
+                builder.capture_goose_session_header(record);
+            } else {
+                warnings.push(format!(
+                    "record {record_number}: message has no `role`; skipped"
+                ));
+            }
+            continue;
+        }
+
+        let message = match preset {
+            MessagePreset::OpenAiMessages => openai_message(record),
+            MessagePreset::Goose => goose_message(record, record_number, &mut warnings),
+        };
+        builder.push_message(
+            &message,
+            record,
+            locator,
+            record_number,
+            &mut pending_tool_calls,
+            &mut warnings,
+        );
+    }
+
+    MessageParseResult {
+        events: builder.events,
+        warnings,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct NormalizedMessage {
+    role: String,
+    text: Option<String>,
+    tool_calls: Vec<ToolCallPart>,
+    tool_results: Vec<ToolResultPart>,
+}
+
+#[derive(Debug, Clone)]
+struct ToolCallPart {
+    id: Option<String>,
+    name: String,
+    arguments_json: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct ToolResultPart {
+    id: Option<String>,
+    name: Option<String>,
+    text: String,
+}
+
+fn openai_message(record: &Value) -> NormalizedMessage {
+    let role = string_at(record, "/role")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let text = record.get("content").and_then(content::extract_text);
+
+    if matches!(role.as_str(), "tool" | "function") {
+        return NormalizedMessage {
+            role,
+            text: None,
+            tool_calls: Vec::new(),
+            tool_results: vec![ToolResultPart {
+                id: string_at(record, "/tool_call_id"),
+                name: string_at(record, "/name"),
+                text: text.unwrap_or_default(),
+            }],
+        };
+    }
+
+    let mut tool_calls = record
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .map(|calls| {
+            calls
+                .iter()
+                .filter_map(|call| {
+                    Some(ToolCallPart {
+                        id: string_at(call, "/id"),
+                        name: string_at(call, "/function/name")?,
+                        arguments_json: call.pointer("/function/arguments").map(arguments_json),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if let Some(name) = string_at(record, "/function_call/name") {
+        tool_calls.push(ToolCallPart {
+            id: None,
+            name,
+            arguments_json: record
+                .pointer("/function_call/arguments")
+                .map(arguments_json),
+        });
+    }
+
+    NormalizedMessage {
+        role,
+        text,
+        tool_calls,
+        tool_results: Vec::new(),
+    }
+}
+
+fn goose_message(
+    record: &Value,
+    record_number: usize,
+    warnings: &mut Vec<String>,
+) -> NormalizedMessage {
+    let role = string_at(record, "/role")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let parts = match record.get("content") {
+        Some(Value::Array(parts)) => parts.clone(),
+        Some(other) => vec![json!({"type": "text", "text": other})],
+        None => Vec::new(),
+    };
+
+    let mut message = NormalizedMessage {
+        role,
+        ..NormalizedMessage::default()
+    };
+    let mut text_fragments = Vec::new();
+    for part in &parts {
+        match part.get("type").and_then(Value::as_str).unwrap_or_default() {
+            "text" => {
+                if let Some(text) = part.get("text").and_then(content::extract_text) {
+                    text_fragments.push(text);
+                }
+            }
+            "toolRequest" => {
+                let Some(name) = string_at(part, "/toolCall/value/name") else {
+                    warnings.push(format!(
+                        "record {record_number}: goose toolRequest without a tool name; skipped"
+                    ));
+                    continue;
+                };
+                message.tool_calls.push(ToolCallPart {
+                    id: string_at(part, "/id"),
+                    name,
+                    arguments_json: part
+                        .pointer("/toolCall/value/arguments")
+                        .map(arguments_json),
+                });
+            }
+            "toolResponse" => {
+                let text = part
+                    .pointer("/toolResult/value")
+                    .and_then(content::extract_text)
+                    .or_else(|| string_at(part, "/toolResult/error"))
+                    .unwrap_or_default();
+                message.tool_results.push(ToolResultPart {
+                    id: string_at(part, "/id"),
+                    name: None,
+                    text,
+                });
+            }
+            _ => {}
+        }
+    }
+    if !text_fragments.is_empty() {
+        message.text = Some(text_fragments.join("\n"));
+    }
+    message
+}
+
+fn arguments_json(value: &Value) -> String {
+    match value {
+        Value::String(raw) => raw.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn string_at(value: &Value, pointer: &str) -> Option<String> {
+    let text = value.pointer(pointer)?.as_str()?.trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+fn record_timestamp_ms(record: &Value) -> Option<u64> {
+    TIMESTAMP_KEYS.iter().find_map(|key| {
+        let raw = match record.get(*key)? {
+            Value::Number(number) => number.to_string(),
+            Value::String(text) => text.clone(),
+            _ => return None,
+        };
+        normalize_timestamp_exact(&raw)
+            .ok()
+            .map(|normalized| normalized.timestamp_unix_ms)
+    })
+}
+
+fn usage_tokens(record: &Value) -> (Option<u64>, Option<u64>, Option<u64>) {
+    let Some(usage) = record.get("usage") else {
+        return (None, None, None);
+    };
+    let first_u64 = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| usage.get(*key).and_then(Value::as_u64))
+    };
+    let input = first_u64(&["prompt_tokens", "input_tokens"]);
+    let output = first_u64(&["completion_tokens", "output_tokens"]);
+    let total = first_u64(&["total_tokens"]).or(match (input, output) {
+        (None, None) => None,
+        (input, output) => Some(input.unwrap_or(0) + output.unwrap_or(0)),
+    });
+    (input, output, total)
+}
+
+#[derive(Debug, Clone)]
+struct MessageEventDraft {
+    locator: String,
+    record_format: RecordFormat,
+    event_type: EventType,
+    role: ActorRole,
+    text: String,
+    tool_name: Option<String>,
+    tool_call_id: Option<String>,
+    tool_arguments_json: Option<String>,
+    tool_result_text: Option<String>,
+}
+
+struct MessageEventBuilder<'a> {
+    source: &'a MessageSource<'a>,
+    session_id: Option<String>,
+    source_path_hash: u64,
+    session_metadata: BTreeMap<String, Value>,
+    last_timestamp_ms: Option<u64>,
+    events: Vec<AgentLogEvent>,
+}
+
+impl MessageEventBuilder<'_> {
+    fn capture_goose_session_header(&mut self, header: &Value) {
+        if let Some(description) = string_at(header, "/description") {
+            self.session_metadata
+                .insert("goose_description".to_string(), json!(description));
+        }
+        if let Some(working_dir) = string_at(header, "/working_dir") {
+            self.session_metadata
+                .insert("goose_working_dir".to_string(), json!(working_dir));
+        }
+    }
+
+    fn push_message(
+        &mut self,
+        message: &NormalizedMessage,
+        record: &Value,
+        locator: &str,
+        record_number: usize,
+        pending_tool_calls: &mut BTreeMap<String, String>,
+        warnings: &mut Vec<String>,
+    ) {
+        let (timestamp_unix_ms, timestamp_quality) = match record_timestamp_ms(record) {
+            Some(unix_ms) => (unix_ms, TimestampQuality::Exact),
+            None => match self
+                .last_timestamp_ms
+                .and_then(|anchor| derive_timestamp(anchor, 1).ok())
+            {
+                Some(derived) => (derived.timestamp_unix_ms, TimestampQuality::Derived),
+                None => {
+                    warnings.push(format!(
+                        "record {record_number}: missing timestamp; using fallback"
+                    ));
+                    (record_number as u64, TimestampQuality::Fallback)
+                }
+            },
+        };
+        if timestamp_quality != TimestampQuality::Fallback {
+            self.last_timestamp_ms = Some(timestamp_unix_ms);
+        }
+
+        let mut drafts = Vec::new();
+        if let Some(text) = &message.text {
+            let classified = match message.role.as_str() {
+                "user" | "human" => {
+                    Some((RecordFormat::Message, EventType::Prompt, ActorRole::User))
+                }
+                "assistant" | "model" => Some((
+                    RecordFormat::Message,
+                    EventType::Response,
+                    ActorRole::Assistant,
+                )),
+                "system" | "developer" => Some((
+                    RecordFormat::System,
+                    EventType::SystemNotice,
+                    ActorRole::System,
+                )),
+                other => {
+                    warnings.push(format!(
+                        "record {record_number}: unknown role `{other}`; message skipped"
+                    ));
+                    None
+                }
+            };
+            if let Some((record_format, event_type, role)) = classified {
+                drafts.push(MessageEventDraft {
+                    locator: locator.to_string(),
+                    record_format,
+                    event_type,
+                    role,
+                    text: text.clone(),
+                    tool_name: None,
+                    tool_call_id: None,
+                    tool_arguments_json: None,
+                    tool_result_text: None,
+                });
+            }
+        }
+
+        for (call_index, call) in message.tool_calls.iter().enumerate() {
+            if let Some(id) = &call.id {
+                pending_tool_calls.insert(id.clone(), call.name.clone());
+            }
+            drafts.push(MessageEventDraft {
+                locator: format!("{locator}:tool_calls[{call_index}]"),
+                record_format: RecordFormat::ToolCall,
+                event_type: EventType::ToolInvocation,
+                role: ActorRole::Assistant,
+                text: format!(
+                    "{} {}",
+                    call.name,
+                    call.arguments_json.as_deref().unwrap_or("{}")
+                ),
+                tool_name: Some(call.name.clone()),
+                tool_call_id: call.id.clone(),
+                tool_arguments_json: call.arguments_json.clone(),
+                tool_result_text: None,
+            });
+        }
+
+        for (result_index, result) in message.tool_results.iter().enumerate() {
+            let paired_name = result
+                .id
+                .as_ref()
+                .and_then(|id| pending_tool_calls.get(id).cloned());
+            if paired_name.is_none() {
+                warnings.push(format!(
+                    "record {record_number}: tool result `{}` has no matching tool call",
+                    result.id.as_deref().unwrap_or("<missing id>")
+                ));
+            }
+            let locator = if message.tool_results.len() == 1 && message.tool_calls.is_empty() {
+                locator.to_string()
+            } else {
+                format!("{locator}:tool_results[{result_index}]")
+            };
+            drafts.push(MessageEventDraft {
+                locator,
+                record_format: RecordFormat::ToolResult,
+                event_type: EventType::ToolOutput,
+                role: ActorRole::Tool,
+                text: result.text.clone(),
+                tool_name: paired_name.or_else(|| result.name.clone()),
+                tool_call_id: result.id.clone(),
+                tool_arguments_json: None,
+                tool_result_text: Some(result.text.clone()),
+            });
+        }
+
+        let mut usage = Some(usage_tokens(record));
+        let model = string_at(record, "/model");
+        for draft in drafts {
+            let tokens = usage.take().unwrap_or((None, None, None));
+            self.push(
+                draft,
+                record,
+                model.clone(),
+                tokens,
+                (timestamp_unix_ms, timestamp_quality),
+            );
+        }
+    }
+
+    fn push(
+        &mut self,
+        draft: MessageEventDraft,
+        record: &Value,
+        model: Option<String>,
+        (input_tokens, output_tokens, total_tokens): (Option<u64>, Option<u64>, Option<u64>),
+        (timestamp_unix_ms, timestamp_quality): (u64, TimestampQuality),
+    ) {
+        let sequence = self.events.len() as u64;
+        let source = self.source;
+        let timestamp_utc = format_unix_ms(timestamp_unix_ms);
+        let event_id = format!(
+            "{}-{:016x}-{:06}",
+            source.source_kind.as_str(),
+            self.source_path_hash,
+            sequence + 1
+        );
+        let raw_hash = format!("{:016x}", hash64(&record.to_string()));
+        let canonical_hash = format!(
+            "{:016x}",
+            hash64(&(
+                "openai_messages.v1",
+                self.session_id.clone(),
+                draft.locator.clone(),
+                draft.text.split_whitespace().collect::<Vec<_>>().join(" "),
+                timestamp_utc.clone()
+            ))
+        );
+        let content_excerpt =
+            content::derive_excerpt(&draft.text, content::DEFAULT_EXCERPT_MAX_CHARS);
+        let mut metadata = source.metadata.clone();
+        metadata.extend(self.session_metadata.clone());
+
+        self.events.push(AgentLogEvent {
+            schema_version: crate::models::SchemaVersion::AgentLogV1,
+            event_id,
+            run_id: source.run_id.to_string(),
+            sequence_global: sequence,
+            sequence_source: Some(sequence),
+            source_kind: source.source_kind.clone(),
+            source_path: source.source_path.to_string(),
+            source_record_locator: draft.locator,
+            source_record_hash: None,
+            adapter_name: source.source_kind.clone(),
+            adapter_version: Some(source.adapter_version.to_string()),
+            record_format: draft.record_format,
+            event_type: draft.event_type,
+            role: draft.role,
+            timestamp_utc,
+            timestamp_unix_ms,
+            timestamp_quality,
+            session_id: self.session_id.clone(),
+            conversation_id: self.session_id.clone(),
+            turn_id: None,
+            parent_event_id: None,
+            actor_id: None,
+            actor_name: None,
+            provider: None,
+            model,
+            content_text: Some(draft.text),
+            content_excerpt,
+            content_mime: Some("text/plain".to_string()),
+            tool_name: draft.tool_name,
+            tool_call_id: draft.tool_call_id,
+            tool_arguments_json: draft.tool_arguments_json,
+            tool_result_text: draft.tool_result_text,
+            input_tokens,
+            output_tokens,
+            total_tokens,
+            cost_usd: None,
+            tags: source.tags.clone(),
+            flags: Vec::new(),
+            pii_redacted: None,
+            warnings: Vec::new(),
+            errors: Vec::new(),
+            raw_hash,
+            canonical_hash,
+            metadata,
+        });
+    }
+}
@@ -615,10 +615,10 @@ fn update_timestamp_bounds(
     first: &mut Option<String>,
     last: &mut Option<String>,
 ) {
-    if first.as_ref().is_none_or(|current| created_at < current) {
+    if first.as_deref().is_none_or(|current| created_at < current) {
         *first = Some(created_at.to_string());
     }
-    if last.as_ref().is_none_or(|current| created_at > current) {
+    if last.as_deref().is_none_or(|current| created_at > current) {
         *last = Some(created_at.to_string());
     }
 }
@@ -0,0 +1,362 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::models::AgentLogEvent;
+use crate::utils::fs::write_atomic;
+use crate::utils::redaction::{
+    PseudonymKey, RedactionPolicy, RedactionRules, find_redaction_spans,
+};
+
+pub const AUDIT_REPORT_SCHEMA_VERSION: &str = "logit.audit-secrets.v1";
+pub const AUDIT_BASELINE_SCHEMA_VERSION: &str = "logit.audit-baseline.v1";
+pub const AUDIT_THRESHOLD_ERROR_CODE: &str = "audit_findings_over_threshold";
+pub const HIGH_ENTROPY_CLASS: &str = "high_entropy_string";
+pub const FINGERPRINT_SALT_FILE: &str = "fingerprint.salt";
+
+const FINGERPRINT_HEX_CHARS: usize = 16;
+const ENTROPY_MIN_CHARS: usize = 20;
+const ENTROPY_MIN_BITS_PER_CHAR: f64 = 3.8;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SecretFinding {
+    pub finding_id: String,
+    pub source_kind: String,
+    pub source_path: String,
+    pub source_record_locator: String,
+    pub session_id: Option<String>,
+    pub event_type: String,
+    pub field: String,
+    pub redaction_class: String,
+    pub fingerprint: String,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SecretAuditReport {
+    pub schema_version: String,
+    pub input: String,
+    pub policy: String,
+    pub events_scanned: usize,
+    pub findings_total: usize,
+    pub findings_suppressed: usize,
+    pub findings_active: usize,
+    pub max_findings: usize,
+    pub threshold_exceeded: bool,
+    pub class_counts: BTreeMap<String, usize>,
+    pub findings: Vec<SecretFinding>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditBaseline {
+    #[serde(default = "baseline_schema_version")]
+    pub schema_version: String,
+    #[serde(default)]
+    pub fingerprints: BTreeSet<String>,
+    #[serde(default)]
+    pub finding_ids: BTreeSet<String>,
+}
+
+fn baseline_schema_version() -> String {
+    AUDIT_BASELINE_SCHEMA_VERSION.to_string()
+}
+
+impl AuditBaseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read audit baseline: {}", path.display()))?;
+        serde_json::from_str(&input)
+            .with_context(|| format!("audit baseline must be valid JSON: {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn from_findings(findings: &[SecretFinding]) -> Self {
+        Self {
+            schema_version: baseline_schema_version(),
+            fingerprints: BTreeSet::new(),
+            finding_ids: findings
+                .iter()
+                .map(|finding| finding.finding_id.clone())
+                .collect(),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).with_context(|| {
+                format!(
+                    "failed to create audit baseline directory: {}",
+                    parent.display()
+                )
+            })?;
+        }
+        let encoded = serde_json::to_vec_pretty(self).context("failed to encode audit baseline")?;
+        write_atomic(path, &encoded)
+            .with_context(|| format!("failed to write audit baseline: {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn suppresses(&self, finding: &SecretFinding) -> bool {
+        self.finding_ids.contains(&finding.finding_id)
+            || self.fingerprints.contains(&finding.fingerprint)
+    }
+}
+
+#[must_use]
+pub fn default_salt_path(out_dir: &Path) -> PathBuf {
+    out_dir.join("audit").join(FINGERPRINT_SALT_FILE)
+}
+
+#[must_use]
+pub fn report_artifact_path(out_dir: &Path) -> PathBuf {
+    out_dir.join("audit").join("secrets_report.json")
+}
+
+#[must_use]
+pub fn scan_events(
+    events: &[AgentLogEvent],
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+    salt: &PseudonymKey,
+) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    for event in events {
+        let fields = [
+            ("content_text", event.content_text.as_deref()),
+            ("tool_arguments_json", event.tool_arguments_json.as_deref()),
+            ("tool_result_text", event.tool_result_text.as_deref()),
+        ];
+        for (field, text) in fields {
+            let Some(text) = text else {
+                continue;
+            };
+            for (class_name, start, end) in find_secret_spans(text, policy, rules) {
+                findings.push(build_finding(
+                    event,
+                    field,
+                    text,
+                    &class_name,
+                    start,
+                    end,
+                    salt,
+                ));
+            }
+        }
+    }
+    findings
+}
+
+fn find_secret_spans(
+    text: &str,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) -> Vec<(String, usize, usize)> {
+    let mut spans = find_redaction_spans(text, policy, rules)
+        .into_iter()
+        .map(|span| (span.class_name, span.start, span.end))
+        .collect::<Vec<_>>();
+    if policy == RedactionPolicy::Off {
+        return spans;
+    }
+    for (start, end) in find_high_entropy_spans(text) {
+        let overlaps = spans
+            .iter()
+            .any(|(_, claimed_start, claimed_end)| start < *claimed_end && *claimed_start < end);
+        if !overlaps {
+            spans.push((HIGH_ENTROPY_CLASS.to_string(), start, end));
+        }
+    }
+    spans.sort_by_key(|(_, start, _)| *start);
+    spans
+}
+
+#[must_use]
+pub fn find_high_entropy_spans(text: &str) -> Vec<(usize, usize)> {
+    let is_token_char =
+        |ch: char| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '/' | '_' | '-' | '=');
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (index, ch) in text
+        .char_indices()
+        .chain(std::iter::once((text.len(), ' ')))
+    {
+        match (is_token_char(ch), start) {
+            (true, None) => start = Some(index),
+            (false, Some(token_start)) => {
+                if looks_high_entropy(&text[token_start..index]) {
+                    spans.push((token_start, index));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+fn looks_high_entropy(token: &str) -> bool {
+    if token.len() < ENTROPY_MIN_CHARS || token.starts_with('/') {
+        return false;
+    }
+    if token.chars().all(|ch| ch.is_ascii_hexdigit() || ch == '-') {
+        return false;
+    }
+    let has_lower = token.chars().any(|ch| ch.is_ascii_lowercase());
+    let has_upper = token.chars().any(|ch| ch.is_ascii_uppercase());
+    let has_digit = token.chars().any(|ch| ch.is_ascii_digit());
+    if !(has_lower && has_upper && has_digit) {
+        return false;
+    }
+    shannon_entropy(token) >= ENTROPY_MIN_BITS_PER_CHAR
+}
+
+#[must_use]
+pub fn shannon_entropy(value: &str) -> f64 {
+    let mut counts = BTreeMap::<char, usize>::new();
+    let mut total = 0_usize;
+    for ch in value.chars() {
+        *counts.entry(ch).or_insert(0) += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return 0.0;
+    }
+    let total = total as f64;
+    counts
+        .values()
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+fn build_finding(
+    event: &AgentLogEvent,
+    field: &str,
+    text: &str,
+    class_name: &str,
+    start: usize,
+    end: usize,
+    salt: &PseudonymKey,
+) -> SecretFinding {
+    let fingerprint = salt.digest_hex(&text[start..end])[..FINGERPRINT_HEX_CHARS].to_string();
+    let event_type = serde_json::to_value(event.event_type)
+        .ok()
+        .and_then(|value| value.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default();
+
+    let mut hasher = Sha256::new();
+    for part in [
+        event.source_path.as_str(),
+        event.source_record_locator.as_str(),
+        field,
+        class_name,
+        fingerprint.as_str(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let finding_id = hasher
+        .finalize()
+        .iter()
+        .take(FINGERPRINT_HEX_CHARS / 2)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+
+    let prefix = &text[..start];
+    let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+    SecretFinding {
+        finding_id,
+        source_kind: event.source_kind.as_str().to_string(),
+        source_path: event.source_path.clone(),
+        source_record_locator: event.source_record_locator.clone(),
+        session_id: event.session_id.clone(),
+        event_type,
+        field: field.to_string(),
+        redaction_class: class_name.to_string(),
+        fingerprint,
+        line: prefix.matches('\n').count() + 1,
+        column: prefix[line_start..].chars().count() + 1,
+        length: text[start..end].chars().count(),
+    }
+}
+
+#[must_use]
+pub fn build_report(
+    input: &str,
+    policy: RedactionPolicy,
+    events_scanned: usize,
+    findings: Vec<SecretFinding>,
+    baseline: &AuditBaseline,
+    max_findings: usize,
+    warnings: Vec<String>,
+) -> SecretAuditReport {
+    let findings_total = findings.len();
+    let active = findings
+        .into_iter()
+        .filter(|finding| !baseline.suppresses(finding))
+        .collect::<Vec<_>>();
+    let mut class_counts = BTreeMap::new();
+    for finding in &active {
+        *class_counts
+            .entry(finding.redaction_class.clone())
+            .or_insert(0) += 1;
+    }
+
+    SecretAuditReport {
+        schema_version: AUDIT_REPORT_SCHEMA_VERSION.to_string(),
+        input: input.to_string(),
+        policy: policy.as_str().to_string(),
+        events_scanned,
+        findings_total,
+        findings_suppressed: findings_total - active.len(),
+        findings_active: active.len(),
+        max_findings,
+        threshold_exceeded: active.len() > max_findings,
+        class_counts,
+        findings: active,
+        warnings,
+    }
+}
+
+pub fn read_events_jsonl(path: &Path) -> Result<(Vec<AgentLogEvent>, Vec<String>)> {
+    let input = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read normalized events file: {}", path.display()))?;
+    let mut events = Vec::new();
+    let mut warnings = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<AgentLogEvent>(trimmed) {
+            Ok(event) => events.push(event),
+            Err(error) => warnings.push(format!(
+                "invalid events jsonl row at line {}: {error}",
+                index + 1
+            )),
+        }
+    }
+    Ok((events, warnings))
+}
+
+pub fn write_report_artifact(path: &Path, report: &SecretAuditReport) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create audit report directory: {}",
+                parent.display()
+            )
+        })?;
+    }
+    let encoded = serde_json::to_vec_pretty(report).context("failed to encode audit report")?;
+    write_atomic(path, &encoded)
+        .with_context(|| format!("failed to write audit report: {}", path.display()))
+}
@@ -1,10 +1,22 @@
 use std::path::PathBuf;
 
-use clap::{Args, Parser, Subcommand};
+use anyhow::{Result, bail};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, Args, Parser, Subcommand};
 
+use crate::config::LogitConfig;
+use crate::discovery::{PipelineFilters, SourceFormatHint, TimeWindow};
+use crate::utils::time::{parse_time_bound, unix_timestamp_milliseconds};
+
+use super::commands::audit::{AuditArgs, AuditCommand};
+use super::commands::ingest::{IngestArgs, IngestCommand};
+use super::commands::query::{QueryArgs, QueryCommand};
+use super::commands::redact::{RedactArgs, RedactCommand};
 use super::commands::{
-    ingest::IngestArgs, inspect::InspectArgs, normalize::NormalizeArgs, query::QueryArgs,
-    snapshot::SnapshotArgs, validate::ValidateArgs,
+    config::ConfigArgs, doctor::DoctorArgs, export::ExportArgs, inspect::InspectArgs,
+    mart::MartArgs, mcp::McpArgs, normalize::NormalizeArgs, prune::PruneArgs, purge::PurgeArgs,
+    run::RunArgs, serve::ServeArgs, show::ShowArgs, snapshot::SnapshotArgs, tui::TuiArgs,
+    validate::ValidateArgs,
 };
 
 #[derive(Debug, Parser)]
@@ -29,6 +41,51 @@ pub struct RuntimeArgs {
     pub out_dir: Option<PathBuf>,
 }
 
+#[derive(Debug, Clone, Default, Args)]
+pub struct SourceFilterArgs {
+    #[arg(long = "adapter", value_name = "NAME")]
+    pub adapters: Vec<String>,
+
+    #[arg(long = "format", value_name = "FORMAT", value_parser = SourceFormatHint::parse)]
+    pub formats: Vec<SourceFormatHint>,
+
+    #[arg(long = "path-contains", value_name = "SUBSTRING")]
+    pub path_substrings: Vec<String>,
+
+    #[arg(long, value_name = "TIME", value_parser = parse_time_bound_now)]
+    pub since: Option<u64>,
+
+    #[arg(long, value_name = "TIME", value_parser = parse_time_bound_now)]
+    pub until: Option<u64>,
+}
+
+impl SourceFilterArgs {
+    pub fn to_filters(&self) -> Result<PipelineFilters> {
+        if let (Some(since), Some(until)) = (self.since, self.until)
+            && since > until
+        {
+            bail!("--since must not be later than --until");
+        }
+        Ok(PipelineFilters {
+            adapters: self
+                .adapters
+                .iter()
+                .map(|adapter| adapter.trim().to_ascii_lowercase())
+                .collect(),
+            format_hints: self.formats.clone(),
+            path_substrings: self.path_substrings.clone(),
+            window: TimeWindow {
+                since_unix_ms: self.since,
+                until_unix_ms: self.until,
+            },
+        })
+    }
+}
+
+pub fn parse_time_bound_now(raw: &str) -> Result<u64, String> {
+    parse_time_bound(raw, unix_timestamp_milliseconds()).map_err(|error| format!("{error:#}"))
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
     Snapshot(SnapshotArgs),
@@ -36,5 +93,89 @@ pub enum Command {
     Inspect(InspectArgs),
     Validate(ValidateArgs),
     Ingest(IngestArgs),
+    Mart(MartArgs),
     Query(QueryArgs),
+    Redact(RedactArgs),
+    Audit(AuditArgs),
+    Show(ShowArgs),
+    Tui(TuiArgs),
+    Serve(ServeArgs),
+    Mcp(McpArgs),
+    Export(ExportArgs),
+    Config(ConfigArgs),
+    Run(RunArgs),
+    Doctor(DoctorArgs),
+    Prune(PruneArgs),
+    Purge(PurgeArgs),
+}
+
+/// Fills in arguments the user did not pass on the command line from the
+/// merged config, so flags keep the highest precedence.
+pub fn apply_config_defaults(command: &mut Command, matches: &ArgMatches, config: &LogitConfig) {
+    let Some((_, matches)) = matches.subcommand() else {
+        return;
+    };
+    let defaulted =
+        |matches: &ArgMatches, id: &str| matches.value_source(id) != Some(ValueSource::CommandLine);
+    let nested_defaulted = |id: &str| {
+        matches
+            .subcommand()
+            .is_some_and(|(_, matches)| defaulted(matches, id))
+    };
+    let pseudonymize = |flag: bool, no_flag: bool| !no_flag && (flag || config.pseudonymize.value);
+    let adapters = |filters: &mut SourceFilterArgs| {
+        if filters.adapters.is_empty() {
+            filters.adapters.clone_from(&config.adapters.value);
+        }
+    };
+    match command {
+        Command::Snapshot(args) => {
+            args.pseudonymize = pseudonymize(args.pseudonymize, args.no_pseudonymize);
+            adapters(&mut args.filters);
+        }
+        Command::Normalize(args) => {
+            if defaulted(matches, "redaction") {
+                args.redaction = config.redaction_policy.value;
+            }
+            args.pseudonymize = pseudonymize(args.pseudonymize, args.no_pseudonymize);
+            adapters(&mut args.filters);
+        }
+        Command::Run(args) => {
+            if defaulted(matches, "redaction") {
+                args.redaction = config.redaction_policy.value;
+            }
+            args.pseudonymize = pseudonymize(args.pseudonymize, args.no_pseudonymize);
+            if args.gates.is_empty() && !args.no_gates {
+                args.gates.clone_from(&config.run_gates.value);
+            }
+            adapters(&mut args.filters);
+        }
+        Command::Ingest(args) => {
+            if let IngestCommand::Refresh(refresh) = &mut args.command {
+                adapters(&mut refresh.filters);
+            }
+        }
+        Command::Query(args) => match &mut args.command {
+            QueryCommand::Sql(sql) if nested_defaulted("row_cap") => {
+                sql.row_cap = config.sql_row_cap.value;
+            }
+            QueryCommand::Search(search) if nested_defaulted("row_cap") => {
+                search.row_cap = config.search_row_cap.value;
+            }
+            _ => {}
+        },
+        Command::Audit(args) => {
+            let AuditCommand::Secrets(secrets) = &mut args.command;
+            if nested_defaulted("redaction") {
+                secrets.redaction = config.redaction_policy.value;
+            }
+        }
+        Command::Redact(args) => {
+            let RedactCommand::Test(test) = &mut args.command;
+            if nested_defaulted("redaction") {
+                test.redaction = config.redaction_policy.value;
+            }
+        }
+        _ => {}
+    }
 }
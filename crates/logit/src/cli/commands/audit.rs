@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+use anyhow::{Error, Result};
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use crate::audit::{
+    AUDIT_THRESHOLD_ERROR_CODE, AuditBaseline, SecretAuditReport, build_report, default_salt_path,
+    read_events_jsonl, report_artifact_path, scan_events, write_report_artifact,
+};
+use crate::config::RuntimePaths;
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::utils::redaction::{PseudonymKey, RedactionPolicy, RedactionRules, rules_path};
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditArgs {
+    #[command(subcommand)]
+    pub command: AuditCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuditCommand {
+    Secrets(AuditSecretsArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditSecretsArgs {
+    #[arg(long, value_name = "PATH")]
+    pub events: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub source_root: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "secrets-only",
+        value_parser = RedactionPolicy::parse
+    )]
+    pub redaction: RedactionPolicy,
+
+    #[arg(long, default_value_t = 0)]
+    pub max_findings: usize,
+
+    #[arg(long, value_name = "PATH")]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub write_baseline: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub salt_file: Option<PathBuf>,
+}
+
+pub fn run(args: &AuditArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        AuditCommand::Secrets(secrets_args) => run_secrets_command(secrets_args, runtime_paths),
+    }
+}
+
+fn run_secrets_command(args: &AuditSecretsArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let report = audit_secrets(args, runtime_paths).map_err(|error| {
+        let envelope = QueryEnvelope::error("audit.secrets", "audit_failed", "secret audit failed")
+            .with_error_details(json!({ "cause": format!("{error:#}") }));
+        Error::new(QueryEnvelopeCommandFailure::new(envelope))
+    })?;
+    let artifact_path = report_artifact_path(&runtime_paths.out_dir);
+    write_report_artifact(&artifact_path, &report).map_err(|error| {
+        let envelope = QueryEnvelope::error(
+            "audit.secrets",
+            "audit_report_artifact_write_failed",
+            "failed to write audit report artifact",
+        )
+        .with_meta("artifact_path", json!(artifact_path.display().to_string()))
+        .with_error_details(json!({ "cause": format!("{error:#}") }));
+        Error::new(QueryEnvelopeCommandFailure::new(envelope))
+    })?;
+
+    let data = serde_json::to_value(&report)?;
+    if report.threshold_exceeded {
+        let envelope = QueryEnvelope::error(
+            "audit.secrets",
+            AUDIT_THRESHOLD_ERROR_CODE,
+            format!(
+                "{} active findings exceed --max-findings {}",
+                report.findings_active, report.max_findings
+            ),
+        )
+        .with_data(data)
+        .with_meta("artifact_path", json!(artifact_path.display().to_string()));
+        return Err(Error::new(QueryEnvelopeCommandFailure::new(envelope)));
+    }
+
+    let envelope = QueryEnvelope::ok("audit.secrets", data)
+        .with_meta("artifact_path", json!(artifact_path.display().to_string()));
+    println!("{}", serde_json::to_string(&envelope)?);
+    Ok(())
+}
+
+pub fn audit_secrets(
+    args: &AuditSecretsArgs,
+    runtime_paths: &RuntimePaths,
+) -> Result<SecretAuditReport> {
+    let rules = RedactionRules::load(&rules_path(&runtime_paths.home_dir))?;
+    let salt_path = args
+        .salt_file
+        .clone()
+        .unwrap_or_else(|| default_salt_path(&runtime_paths.out_dir));
+    let salt = PseudonymKey::load_or_create_at(&salt_path)?;
+
+    let (input, events, warnings) = match &args.events {
+        Some(events_path) => {
+            let (events, warnings) = read_events_jsonl(events_path)?;
+            (events_path.display().to_string(), events, warnings)
+        }
+        None => {
+            let mut plan = crate::normalize::default_plan();
+            plan.redaction_policy = RedactionPolicy::Off;
+            plan.repo_scan_root = Some(runtime_paths.cwd.clone());
+            plan.custom_adapters = crate::adapters::custom::load_custom_adapter_specs(
+                &crate::adapters::custom::spec_dir(&runtime_paths.home_dir),
+            )?
+            .specs;
+            let zsh_history = std::fs::read_to_string(runtime_paths.home_dir.join(".zsh_history"))
+                .unwrap_or_else(|_| String::new());
+            let orchestration = crate::normalize::orchestrate_normalization(
+                &plan,
+                &runtime_paths.home_dir,
+                args.source_root.as_deref(),
+                &zsh_history,
+            )?;
+            (
+                "discovered".to_string(),
+                orchestration.events,
+                orchestration.warnings,
+            )
+        }
+    };
+
+    let findings = scan_events(&events, args.redaction, &rules, &salt);
+    if let Some(path) = &args.write_baseline {
+        AuditBaseline::from_findings(&findings).write(path)?;
+    }
+    let baseline = match (&args.write_baseline, &args.baseline) {
+        (Some(_), _) => AuditBaseline::from_findings(&findings),
+        (None, Some(path)) => AuditBaseline::load(path)?,
+        (None, None) => AuditBaseline::default(),
+    };
+
+    Ok(build_report(
+        &input,
+        args.redaction,
+        events.len(),
+        findings,
+        &baseline,
+        args.max_findings,
+        warnings,
+    ))
+}
@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use serde_json::{Map, Value, json};
+
+use crate::config::settings::{CONFIG_SCHEMA_FILE, write_config_schema_artifact};
+use crate::config::{LogitConfig, RuntimePaths};
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommand {
+    Show(ConfigShowArgs),
+    Schema(ConfigSchemaArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigShowArgs {
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigSchemaArgs {
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: &ConfigArgs, config: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        ConfigCommand::Show(show_args) => {
+            let report = build_show_report(config, runtime_paths);
+            if show_args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).context("failed to encode config")?
+                );
+            } else {
+                print_show_report(&report);
+            }
+            Ok(())
+        }
+        ConfigCommand::Schema(schema_args) => {
+            let path = schema_args
+                .output
+                .clone()
+                .map(|path| runtime_paths.cwd.join(path))
+                .unwrap_or_else(|| runtime_paths.out_dir.join(CONFIG_SCHEMA_FILE));
+            write_config_schema_artifact(&path)?;
+            println!("config: schema path={}", path.display());
+            Ok(())
+        }
+    }
+}
+
+/// `{files, settings}` where each setting carries its effective value and the
+/// layer it came from; `out_dir` reports the resolved runtime directory.
+#[must_use]
+pub fn build_show_report(config: &LogitConfig, runtime_paths: &RuntimePaths) -> Value {
+    let mut settings = Map::new();
+    for (key, value, origin) in config.entries() {
+        let value = if key == "out_dir" {
+            json!(runtime_paths.out_dir.display().to_string())
+        } else {
+            value
+        };
+        settings.insert(key, json!({ "value": value, "origin": origin }));
+    }
+    json!({
+        "files": config
+            .files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        "settings": settings,
+    })
+}
+
+fn print_show_report(report: &Value) {
+    for file in report["files"].as_array().into_iter().flatten() {
+        println!("config: file {}", file.as_str().unwrap_or_default());
+    }
+    for (key, setting) in report["settings"].as_object().into_iter().flatten() {
+        println!(
+            "config: setting key={key} value={} origin={}",
+            setting["value"],
+            setting["origin"].as_str().unwrap_or_default()
+        );
+    }
+}
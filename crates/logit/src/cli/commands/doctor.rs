@@ -0,0 +1,118 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use serde_json::json;
+
+use crate::adapters::AdapterKind;
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::discovery::roots::SourceRoots;
+use crate::doctor::{CheckStatus, DoctorPlan, DoctorReport, run_checks};
+use crate::models::QueryEnvelope;
+
+pub const DOCTOR_CHECKS_FAILED_ERROR_CODE: &str = "doctor_checks_failed";
+
+#[derive(Debug, Clone, Args)]
+pub struct DoctorArgs {
+    #[arg(long, value_name = "PATH")]
+    pub source_root: Option<PathBuf>,
+
+    #[arg(long = "adapter", value_name = "ADAPTER", value_parser = parse_adapter)]
+    pub adapters: Vec<AdapterKind>,
+
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+/// Returned when at least one check fails; the report has already been printed.
+#[derive(Debug)]
+pub struct DoctorCheckFailure {
+    pub failed: usize,
+}
+
+impl fmt::Display for DoctorCheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "doctor: {} check(s) failed", self.failed)
+    }
+}
+
+impl std::error::Error for DoctorCheckFailure {}
+
+pub fn run(args: &DoctorArgs, config: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
+    let plan = DoctorPlan {
+        home_dir: runtime_paths.home_dir.clone(),
+        cwd: runtime_paths.cwd.clone(),
+        source_root: args.source_root.clone(),
+        out_dir: runtime_paths.out_dir.clone(),
+        adapters: args.adapters.clone(),
+        source_roots: SourceRoots::load_with(&runtime_paths.home_dir, config.root_entries())?,
+        host: config.host_id(),
+    };
+    let report = run_checks(&plan);
+
+    if args.json {
+        let data = serde_json::to_value(&report).context("failed to encode doctor report")?;
+        let envelope = if report.counts.fail > 0 {
+            QueryEnvelope::error(
+                "doctor",
+                DOCTOR_CHECKS_FAILED_ERROR_CODE,
+                format!("{} doctor check(s) failed", report.counts.fail),
+            )
+            .with_data(data)
+        } else {
+            QueryEnvelope::ok("doctor", data)
+        }
+        .with_meta(
+            "out_dir",
+            json!(runtime_paths.out_dir.display().to_string()),
+        )
+        .with_meta(
+            "source_root",
+            json!(
+                args.source_root
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+            ),
+        );
+        println!(
+            "{}",
+            serde_json::to_string(&envelope).context("failed to encode doctor envelope")?
+        );
+    } else {
+        print_checklist(&report);
+    }
+
+    if report.counts.fail > 0 {
+        return Err(DoctorCheckFailure {
+            failed: report.counts.fail,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+fn print_checklist(report: &DoctorReport) {
+    for check in &report.checks {
+        let mark = match check.status {
+            CheckStatus::Pass => "[ok]  ",
+            CheckStatus::Warn => "[warn]",
+            CheckStatus::Fail => "[FAIL]",
+            CheckStatus::Skip => "[skip]",
+        };
+        println!("{mark} {}: {}", check.id, check.summary);
+        if check.status != CheckStatus::Pass
+            && let Some(remediation) = &check.remediation
+        {
+            println!("       hint: {remediation}");
+        }
+    }
+    println!(
+        "doctor: pass={} warn={} fail={} skip={}",
+        report.counts.pass, report.counts.warn, report.counts.fail, report.counts.skip
+    );
+}
+
+fn parse_adapter(raw: &str) -> Result<AdapterKind, String> {
+    AdapterKind::parse(raw).ok_or_else(|| format!("unknown built-in adapter `{raw}`"))
+}
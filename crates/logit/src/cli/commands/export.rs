@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use clap::{Args, Subcommand};
+
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::otlp::collector::{CollectorEndpoint, post_json};
+use crate::otlp::{OtlpExportOptions, build_session_trace, session_ids, write_trace};
+use crate::sqlite::open_sqlite_connection_read_only;
+
+#[derive(Debug, Clone, Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub command: ExportCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ExportCommand {
+    Otlp(ExportOtlpArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ExportOtlpArgs {
+    #[arg(long, value_name = "PATH")]
+    pub mart: Option<PathBuf>,
+
+    #[arg(long = "session", value_name = "SESSION_ID")]
+    pub sessions: Vec<String>,
+
+    #[arg(long, value_name = "PATH")]
+    pub output_dir: Option<PathBuf>,
+
+    #[arg(long, value_name = "URL", value_parser = CollectorEndpoint::parse)]
+    pub endpoint: Option<CollectorEndpoint>,
+
+    #[arg(long, default_value_t = false)]
+    pub include_content: bool,
+}
+
+pub fn run(args: &ExportArgs, settings: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        ExportCommand::Otlp(otlp_args) => run_otlp(otlp_args, settings, runtime_paths),
+    }
+}
+
+fn run_otlp(
+    args: &ExportOtlpArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+) -> Result<()> {
+    let sqlite_path = args
+        .mart
+        .clone()
+        .unwrap_or_else(|| runtime_paths.out_dir.join("mart.sqlite"));
+    if !sqlite_path.is_file() {
+        bail!("sqlite mart not found: {}", sqlite_path.display());
+    }
+    let output_dir = args
+        .output_dir
+        .clone()
+        .unwrap_or_else(|| runtime_paths.out_dir.join("otlp"));
+    let collector_timeout = Duration::from_secs(settings.collector_timeout_secs.value);
+    let connection = open_sqlite_connection_read_only(&sqlite_path)?;
+    let sessions = if args.sessions.is_empty() {
+        session_ids(&connection)?
+    } else {
+        args.sessions.clone()
+    };
+    let options = OtlpExportOptions {
+        include_content: args.include_content,
+    };
+    println!(
+        "export: start format=otlp-json sessions={} include_content={} mart={} output_dir={}",
+        sessions.len(),
+        options.include_content,
+        sqlite_path.display(),
+        output_dir.display()
+    );
+
+    let mut span_total = 0;
+    for session_id in &sessions {
+        let trace = build_session_trace(&connection, session_id, options)?;
+        let path = write_trace(&output_dir, &trace)?;
+        span_total += trace.span_count;
+        println!(
+            "export: session={} trace_id={} spans={} file={}",
+            trace.session_id,
+            trace.trace_id,
+            trace.span_count,
+            path.display()
+        );
+        if let Some(endpoint) = &args.endpoint {
+            let status = post_json(endpoint, &trace.encode()?, collector_timeout)?;
+            println!(
+                "export: posted trace_id={} endpoint={} status={status}",
+                trace.trace_id,
+                endpoint.url()
+            );
+        }
+    }
+    println!(
+        "export: complete traces={} spans={span_total}",
+        sessions.len()
+    );
+    Ok(())
+}
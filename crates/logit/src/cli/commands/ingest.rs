@@ -1,15 +1,21 @@
 use std::path::PathBuf;
 
-use anyhow::{Error, Result};
+use anyhow::{Context, Error, Result};
 use clap::{Args, Subcommand};
 use serde_json::json;
 
-use crate::config::RuntimePaths;
+use crate::cli::app::SourceFilterArgs;
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::ingest::import::{
+    IMPORT_HOST_MISSING_ERROR_CODE, IMPORT_INVALID_ERROR_CODE, ImportHostMissing,
+    ImportValidationFailure, IngestImportPlan, run_import,
+};
 use crate::ingest::{
     build_ingest_report_artifact, default_plan_from_paths, ingest_report_artifact_path,
     run_refresh, write_ingest_report_artifact,
 };
 use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::purge::{TombstoneList, tombstones_path};
 
 #[derive(Debug, Clone, Args)]
 pub struct IngestArgs {
@@ -20,6 +26,7 @@ pub struct IngestArgs {
 #[derive(Debug, Clone, Subcommand)]
 pub enum IngestCommand {
     Refresh(IngestRefreshArgs),
+    Import(IngestImportArgs),
 }
 
 #[derive(Debug, Clone, Args)]
@@ -29,20 +36,50 @@ pub struct IngestRefreshArgs {
 
     #[arg(long, default_value_t = false)]
     pub fail_fast: bool,
+
+    #[command(flatten)]
+    pub filters: SourceFilterArgs,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct IngestImportArgs {
+    #[arg(value_name = "EVENTS_JSONL|MART_SQLITE")]
+    pub path: PathBuf,
+
+    #[arg(long, value_name = "HOST")]
+    pub host: Option<String>,
 }
 
-pub fn run(args: &IngestArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+pub fn run(args: &IngestArgs, config: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
     match &args.command {
-        IngestCommand::Refresh(refresh_args) => run_refresh_command(refresh_args, runtime_paths),
+        IngestCommand::Refresh(refresh_args) => {
+            run_refresh_command(refresh_args, config, runtime_paths)
+        }
+        IngestCommand::Import(import_args) => run_import_command(import_args, runtime_paths),
     }
 }
 
-fn run_refresh_command(args: &IngestRefreshArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+fn run_refresh_command(
+    args: &IngestRefreshArgs,
+    config: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+) -> Result<()> {
     let source_root = args
         .source_root
         .as_deref()
         .unwrap_or(runtime_paths.cwd.as_path());
-    let plan = default_plan_from_paths(&runtime_paths.out_dir, source_root, args.fail_fast);
+    let mut plan = default_plan_from_paths(&runtime_paths.out_dir, source_root, args.fail_fast);
+    plan.host = Some(config.host_id());
+    plan.tombstones = TombstoneList::load(&tombstones_path(&runtime_paths.out_dir))?.selectors();
+    plan.filters = args.filters.to_filters().map_err(|error| {
+        let envelope = QueryEnvelope::error(
+            "ingest.refresh",
+            "ingest_filters_invalid",
+            "invalid ingest source filters",
+        )
+        .with_error_details(json!({ "cause": format!("{error:#}") }));
+        Error::new(QueryEnvelopeCommandFailure::new(envelope))
+    })?;
     let artifact_path = ingest_report_artifact_path(&runtime_paths.out_dir);
 
     let report = match run_refresh(&plan) {
@@ -84,7 +121,8 @@ fn run_refresh_command(args: &IngestRefreshArgs, runtime_paths: &RuntimePaths) -
     })?;
     let envelope = QueryEnvelope::ok("ingest.refresh", data)
         .with_meta("artifact_path", json!(artifact_path.display().to_string()))
-        .with_meta("fail_fast", json!(args.fail_fast));
+        .with_meta("fail_fast", json!(args.fail_fast))
+        .with_meta("filtered", json!(report.filters.active));
     let encoded = serde_json::to_string(&envelope).map_err(|error| {
         let fallback = QueryEnvelope::error(
             "ingest.refresh",
@@ -105,6 +143,80 @@ fn run_refresh_command(args: &IngestRefreshArgs, runtime_paths: &RuntimePaths) -
     Ok(())
 }
 
+fn run_import_command(args: &IngestImportArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let plan = IngestImportPlan {
+        input_path: runtime_paths.cwd.join(&args.path),
+        sqlite_path: runtime_paths.out_dir.join("mart.sqlite"),
+        host: args.host.clone(),
+        run_id: None,
+        tombstones: TombstoneList::load(&tombstones_path(&runtime_paths.out_dir))?.selectors(),
+    };
+    let report = run_import(&plan).map_err(|error| {
+        let envelope = if let Some(failure) = error.downcast_ref::<ImportValidationFailure>() {
+            QueryEnvelope::error(
+                "ingest.import",
+                IMPORT_INVALID_ERROR_CODE,
+                "import input failed validation",
+            )
+            .with_error_details(json!({
+                "errors": failure.errors,
+                "warnings": failure.warnings,
+                "issues": failure.issues,
+            }))
+        } else if let Some(missing) = error.downcast_ref::<ImportHostMissing>() {
+            QueryEnvelope::error(
+                "ingest.import",
+                IMPORT_HOST_MISSING_ERROR_CODE,
+                "imported events have no host; pass --host",
+            )
+            .with_error_details(json!({ "events_without_host": missing.events_without_host }))
+        } else {
+            QueryEnvelope::error(
+                "ingest.import",
+                classify_import_error_code(&error),
+                "ingest import failed",
+            )
+            .with_error_details(json!({ "cause": format!("{error:#}") }))
+        };
+        Error::new(QueryEnvelopeCommandFailure::new(
+            envelope
+                .with_meta("input_path", json!(plan.input_path.display().to_string()))
+                .with_meta("sqlite_path", json!(plan.sqlite_path.display().to_string())),
+        ))
+    })?;
+
+    let data = serde_json::to_value(&report).map_err(|error| {
+        let envelope = QueryEnvelope::error(
+            "ingest.import",
+            "ingest_report_encode_failed",
+            "failed to encode ingest import report",
+        )
+        .with_error_details(json!({ "cause": format!("{error:#}") }));
+        Error::new(QueryEnvelopeCommandFailure::new(envelope))
+    })?;
+    let envelope = QueryEnvelope::ok("ingest.import", data)
+        .with_meta("input_path", json!(plan.input_path.display().to_string()))
+        .with_meta("sqlite_path", json!(plan.sqlite_path.display().to_string()));
+    println!(
+        "{}",
+        serde_json::to_string(&envelope).context("failed to encode ingest import envelope")?
+    );
+    Ok(())
+}
+
+fn classify_import_error_code(error: &anyhow::Error) -> &'static str {
+    let message = format!("{error:#}");
+    if message.contains("failed to open import input") {
+        "ingest_import_input_missing"
+    } else if message.contains("host id") {
+        "ingest_import_host_invalid"
+    } else if message.contains("sqlite") || message.contains("mart") {
+        "ingest_sqlite_failure"
+    } else {
+        "ingest_import_failed"
+    }
+}
+
 fn classify_ingest_error_code(error: &anyhow::Error) -> &'static str {
     let message = format!("{error:#}");
     if message.contains("failed to read normalized events file") {
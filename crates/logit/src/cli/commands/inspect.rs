@@ -83,7 +83,8 @@ pub fn inspect_target(path: &Path) -> Result<InspectReport> {
         crate::discovery::SourceClassification::TextLog => {
             analyze_text_log(&bytes, &mut report)?;
         }
-        crate::discovery::SourceClassification::Binary => {}
+        crate::discovery::SourceClassification::Sqlite
+        | crate::discovery::SourceClassification::Binary => {}
     }
 
     Ok(report)
@@ -315,6 +316,7 @@ const fn source_classification_key(
         crate::discovery::SourceClassification::Jsonl => "jsonl",
         crate::discovery::SourceClassification::Json => "json",
         crate::discovery::SourceClassification::TextLog => "text_log",
+        crate::discovery::SourceClassification::Sqlite => "sqlite",
         crate::discovery::SourceClassification::Binary => "binary",
     }
 }
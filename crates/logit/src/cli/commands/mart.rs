@@ -0,0 +1,99 @@
+use anyhow::{Context, Error, Result};
+use clap::{Args, Subcommand};
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::config::RuntimePaths;
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::sqlite::migrations::{apply_migrations, migration_status};
+use crate::sqlite::{open_sqlite_connection, open_sqlite_connection_read_only};
+
+#[derive(Debug, Clone, Args)]
+pub struct MartArgs {
+    #[command(subcommand)]
+    pub command: MartCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum MartCommand {
+    Migrate(MartMigrateArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MartMigrateArgs {
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+impl MartArgs {
+    /// Whether this invocation changes the mart (and so needs the out-dir lock).
+    #[must_use]
+    pub fn writes(&self) -> bool {
+        match &self.command {
+            MartCommand::Migrate(migrate) => !migrate.dry_run,
+        }
+    }
+}
+
+pub fn run(args: &MartArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        MartCommand::Migrate(migrate_args) => run_migrate(migrate_args, runtime_paths),
+    }
+}
+
+fn run_migrate(args: &MartMigrateArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let sqlite_path = runtime_paths.out_dir.join("mart.sqlite");
+    let failure = |code: &str, message: &str, error: &Error| {
+        Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error("mart.migrate", code, message)
+                .with_meta("dry_run", json!(args.dry_run))
+                .with_meta("sqlite_path", json!(sqlite_path.display().to_string()))
+                .with_error_details(json!({ "cause": format!("{error:#}") })),
+        ))
+    };
+
+    let data = if args.dry_run {
+        // A missing mart is reported as fully pending without creating it.
+        let connection = if sqlite_path.exists() {
+            open_sqlite_connection_read_only(&sqlite_path)
+        } else {
+            Connection::open_in_memory().context("failed to open in-memory sqlite database")
+        }
+        .map_err(|error| failure("mart_unavailable", "unable to open sqlite mart", &error))?;
+        let status = migration_status(&connection).map_err(|error| {
+            failure(
+                "mart_migration_status_failed",
+                "failed to read mart migration status",
+                &error,
+            )
+        })?;
+        json!({
+            "dry_run": true,
+            "from_version": status.current_version,
+            "to_version": status.latest_version,
+            "migrations": status.pending,
+            "views": status.stale_views,
+        })
+    } else {
+        let connection = open_sqlite_connection(&sqlite_path)
+            .map_err(|error| failure("mart_unavailable", "unable to open sqlite mart", &error))?;
+        let outcome = apply_migrations(&connection)
+            .map_err(|error| failure("mart_migration_failed", "mart migration failed", &error))?;
+        json!({
+            "dry_run": false,
+            "from_version": outcome.from_version,
+            "to_version": outcome.to_version,
+            "migrations": outcome.applied,
+            "views": outcome.recreated_views,
+        })
+    };
+
+    let envelope = QueryEnvelope::ok("mart.migrate", data)
+        .with_meta("dry_run", json!(args.dry_run))
+        .with_meta("sqlite_path", json!(sqlite_path.display().to_string()));
+    println!(
+        "{}",
+        serde_json::to_string(&envelope).context("failed to encode mart migrate envelope")?
+    );
+    Ok(())
+}
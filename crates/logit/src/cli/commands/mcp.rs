@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::config::RuntimePaths;
+use crate::mcp::McpServer;
+
+#[derive(Debug, Clone, Args)]
+pub struct McpArgs {
+    #[arg(long, value_name = "PATH")]
+    pub mart: Option<PathBuf>,
+}
+
+pub fn run(args: &McpArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let sqlite_path = args
+        .mart
+        .clone()
+        .unwrap_or_else(|| runtime_paths.out_dir.join("mart.sqlite"));
+    let stdin = std::io::stdin();
+    McpServer::new(sqlite_path).serve(stdin.lock(), std::io::stdout().lock())
+}
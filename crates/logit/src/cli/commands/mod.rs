@@ -1,6 +1,19 @@
+pub mod audit;
+pub mod config;
+pub mod doctor;
+pub mod export;
 pub mod ingest;
 pub mod inspect;
+pub mod mart;
+pub mod mcp;
 pub mod normalize;
+pub mod prune;
+pub mod purge;
 pub mod query;
+pub mod redact;
+pub mod run;
+pub mod serve;
+pub mod show;
 pub mod snapshot;
+pub mod tui;
 pub mod validate;
@@ -1,33 +1,212 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use clap::Args;
 
-use crate::config::RuntimePaths;
+use crate::adapters::AdapterKind;
+use crate::adapters::custom::{message_format_spec, wildcard_matches};
+use crate::adapters::openai_messages::MessagePreset;
+use crate::cli::app::SourceFilterArgs;
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::discovery::roots::{RootOrigin, SourceRoots};
+use crate::normalize::AdapterHealthReport;
+use crate::purge::{TombstoneList, tombstones_path};
+use crate::utils::redaction::{PseudonymKey, RedactionPolicy, RedactionRules};
 
 #[derive(Debug, Clone, Args)]
 pub struct NormalizeArgs {
+    #[arg(value_name = "PATH")]
+    pub inputs: Vec<PathBuf>,
+
     #[arg(long)]
     pub source_root: Option<PathBuf>,
 
+    #[arg(long, value_name = "ADAPTER", value_parser = parse_input_adapter, requires = "inputs")]
+    pub input_adapter: Option<AdapterKind>,
+
+    #[arg(
+        long,
+        value_name = "PRESET",
+        value_parser = MessagePreset::parse,
+        conflicts_with_all = ["input_adapter", "adapters"]
+    )]
+    pub message_format: Option<MessagePreset>,
+
     #[arg(long, default_value_t = false)]
     pub fail_fast: bool,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "secrets-only",
+        value_parser = RedactionPolicy::parse
+    )]
+    pub redaction: RedactionPolicy,
+
+    #[arg(long, default_value_t = false)]
+    pub pseudonymize: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "pseudonymize")]
+    pub no_pseudonymize: bool,
+
+    #[command(flatten)]
+    pub filters: SourceFilterArgs,
+}
+
+pub fn run(
+    args: &NormalizeArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+) -> Result<()> {
+    execute(args, settings, runtime_paths, None).map(|_| ())
 }
 
-pub fn run(args: &NormalizeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+/// Runs normalize and returns per-adapter health. A `run_id` replaces the
+/// default orchestrator run id on events and is recorded in `stats.json`.
+pub fn execute(
+    args: &NormalizeArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+    run_id: Option<&str>,
+) -> Result<BTreeMap<String, AdapterHealthReport>> {
     let mut plan = crate::normalize::default_plan();
     plan.fail_fast = args.fail_fast;
+    if let Some(run_id) = run_id {
+        plan.run_id = run_id.to_string();
+    }
+    plan.redaction_policy = args.redaction;
+    let rules_path = crate::utils::redaction::rules_path(&runtime_paths.home_dir);
+    plan.redaction_rules = RedactionRules::load(&rules_path)?;
+    if args.pseudonymize {
+        plan.redaction_rules = plan
+            .redaction_rules
+            .with_pseudonym_key(PseudonymKey::load_or_create(&runtime_paths.out_dir)?);
+    }
+    let custom_adapters = crate::adapters::custom::load_custom_adapter_specs(
+        &crate::adapters::custom::spec_dir(&runtime_paths.home_dir),
+    )?;
+    plan.filters = args.filters.to_filters()?;
+    let (builtin_adapters, other_adapters) = plan.filters.builtin_adapters();
+    plan.adapters = builtin_adapters;
+    plan.custom_adapters = custom_adapters
+        .specs
+        .into_iter()
+        .filter(|spec| plan.filters.admits_adapter_name(&spec.name))
+        .collect();
+    if let Some(unknown) = other_adapters.iter().find(|name| {
+        !plan
+            .custom_adapters
+            .iter()
+            .any(|spec| spec.name.eq_ignore_ascii_case(name))
+    }) {
+        bail!("unknown adapter `{unknown}` (not a built-in or custom adapter)");
+    }
+    plan.inputs = args
+        .inputs
+        .iter()
+        .map(|input| runtime_paths.cwd.join(input))
+        .collect();
+    plan.input_adapter = args.input_adapter;
+    if let Some(preset) = args.message_format {
+        let paths = if !plan.inputs.is_empty() {
+            expand_input_globs(&plan.inputs)
+        } else if args.source_root.is_some() {
+            vec!["~".to_string()]
+        } else {
+            bail!("--message-format needs PATH inputs or --source-root");
+        };
+        plan.adapters = Vec::new();
+        plan.inputs = Vec::new();
+        plan.custom_adapters = vec![message_format_spec(preset, paths)];
+    }
+    plan.source_roots = SourceRoots::load_with(&runtime_paths.home_dir, settings.root_entries())?;
+    plan.repo_scan_root = Some(runtime_paths.cwd.clone());
+    plan.host = Some(settings.host_id());
+    plan.tags.clone_from(&settings.tags.value);
+    plan.pricing = settings
+        .pricing
+        .iter()
+        .map(|(model, pricing)| (model.clone(), pricing.value))
+        .collect();
+    plan.tombstones = TombstoneList::load(&tombstones_path(&runtime_paths.out_dir))?.selectors();
     let source_root = args
         .source_root
         .as_deref()
         .map(|path| path.to_string_lossy().to_string())
         .unwrap_or_else(|| "<auto>".to_string());
     println!(
-        "normalize: start fail_fast={} source_root={} out_dir={}",
+        "normalize: start fail_fast={} redaction={} pseudonymize={} source_root={} out_dir={}",
         plan.fail_fast,
+        plan.redaction_policy.as_str(),
+        plan.redaction_rules.pseudonymizes(),
         source_root,
         runtime_paths.out_dir.display()
     );
+    if !plan.inputs.is_empty() {
+        println!(
+            "normalize: inputs count={} input_adapter={}",
+            plan.inputs.len(),
+            plan.input_adapter.map_or("auto", AdapterKind::as_str)
+        );
+    }
+    if let Some(preset) = args.message_format {
+        println!(
+            "normalize: message_format preset={} paths={}",
+            preset.as_str(),
+            plan.custom_adapters
+                .iter()
+                .map(|spec| spec.paths.len())
+                .sum::<usize>()
+        );
+    }
+    if plan.filters.is_active() {
+        println!("normalize: filters {}", plan.filters.summary());
+    }
+    if let Some(host) = &plan.host {
+        println!("normalize: host {host}");
+    }
+    if !plan.tags.is_empty() {
+        println!("normalize: tags {}", plan.tags.join(","));
+    }
+    if !plan.tombstones.is_empty() {
+        println!("normalize: tombstones count={}", plan.tombstones.len());
+    }
+    for root in plan
+        .source_roots
+        .roots
+        .iter()
+        .filter(|root| root.origin != RootOrigin::Builtin)
+    {
+        println!(
+            "normalize: adapter_root adapter={} profile={} origin={} path={}",
+            root.adapter.as_str(),
+            root.profile,
+            root.origin.as_str(),
+            root.path
+        );
+    }
+    if !plan.redaction_rules.is_empty() {
+        println!(
+            "normalize: redaction_rules path={} custom_classes={} allowlist_entries={}",
+            rules_path.display(),
+            plan.redaction_rules.custom_classes().len(),
+            plan.redaction_rules.allowlist().len()
+        );
+    }
+    for spec in &plan.custom_adapters {
+        println!(
+            "normalize: custom_adapter name={} spec={}",
+            spec.name,
+            spec.spec_path
+                .as_deref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|| "<inline>".to_string())
+        );
+    }
+    for warning in &custom_adapters.warnings {
+        println!("normalize: custom_adapter_warning detail={warning}");
+    }
 
     let artifacts = crate::normalize::build_artifact_layout(&runtime_paths.out_dir);
     let zsh_history = std::fs::read_to_string(runtime_paths.home_dir.join(".zsh_history"))
@@ -44,6 +223,19 @@ pub fn run(args: &NormalizeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         orchestration.events.len(),
         orchestration.dedupe_stats.input_records
     );
+    for input in &orchestration.detected_inputs {
+        println!(
+            "normalize: input path={} adapter={} parser={} detection={}",
+            input.path.display(),
+            input.fingerprint.adapter().as_str(),
+            input.fingerprint.as_str(),
+            if input.overridden {
+                "override"
+            } else {
+                "content"
+            }
+        );
+    }
     for (adapter, report) in &orchestration.adapter_health {
         println!(
             "normalize: adapter_health adapter={} status={} reason={} sources_considered={} files_discovered={} files_parsed={} events_emitted={} warnings={} errors={}",
@@ -82,8 +274,16 @@ pub fn run(args: &NormalizeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         "normalize: checkpoint schema_written {}",
         artifacts.schema_json.display()
     );
-    let stats =
-        crate::normalize::build_normalize_stats(&orchestration.events, orchestration.dedupe_stats);
+    let mut stats = crate::normalize::build_normalize_stats(
+        &orchestration.events,
+        orchestration.dedupe_stats,
+        plan.redaction_policy,
+    );
+    if plan.redaction_rules.pseudonymizes() {
+        stats.redaction.replacement = "pseudonym".to_string();
+    }
+    stats.filters = orchestration.filters.clone();
+    stats.run_id = run_id.map(str::to_string);
     crate::normalize::write_stats_artifact(&artifacts.stats_json, &stats)?;
     println!(
         "normalize: checkpoint stats_written {}",
@@ -111,6 +311,19 @@ pub fn run(args: &NormalizeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         stats.counts.warnings,
         stats.counts.errors
     );
+    if orchestration.filters.active {
+        println!(
+            "normalize: filtered files_pruned_by_mtime={} events_outside_window={}",
+            orchestration.filters.files_pruned_by_mtime,
+            orchestration.filters.events_outside_window
+        );
+    }
+    if !plan.tombstones.is_empty() {
+        println!(
+            "normalize: tombstoned events_dropped={}",
+            orchestration.filters.events_tombstoned
+        );
+    }
     println!(
         "normalize: artifacts events={} schema={} stats={} discovery_sources={} discovery_history={}",
         artifacts.events_jsonl.display(),
@@ -124,5 +337,48 @@ pub fn run(args: &NormalizeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         artifacts.events_jsonl.display()
     );
 
-    Ok(())
+    Ok(orchestration.adapter_health)
+}
+
+/// Expands a `*`/`?` wildcard in the final component of each input that does
+/// not exist as written, so quoted globs work without a shell.
+fn expand_input_globs(inputs: &[PathBuf]) -> Vec<String> {
+    let mut paths = Vec::new();
+    for input in inputs {
+        let pattern = input
+            .file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .filter(|name| name.contains(['*', '?']) && !input.exists());
+        let matches = pattern
+            .and_then(|pattern| Some((pattern, std::fs::read_dir(input.parent()?).ok()?)))
+            .map(|(pattern, entries)| {
+                let mut matches = entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.file_name()
+                            .and_then(std::ffi::OsStr::to_str)
+                            .is_some_and(|name| {
+                                wildcard_matches(pattern.as_bytes(), name.as_bytes())
+                            })
+                    })
+                    .collect::<Vec<_>>();
+                matches.sort();
+                matches
+            })
+            .unwrap_or_default();
+        if matches.is_empty() {
+            paths.push(display_path(input));
+        } else {
+            paths.extend(matches.iter().map(|path| display_path(path)));
+        }
+    }
+    paths
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn parse_input_adapter(raw: &str) -> Result<AdapterKind, String> {
+    AdapterKind::parse(raw).ok_or_else(|| format!("unknown built-in adapter `{raw}`"))
 }
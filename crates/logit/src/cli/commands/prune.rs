@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::Args;
+
+use crate::cli::app::parse_time_bound_now;
+use crate::config::RuntimePaths;
+use crate::purge::{PurgeKind, PurgeSelector};
+
+#[derive(Debug, Clone, Args)]
+pub struct PruneArgs {
+    #[arg(long, value_name = "AGE", value_parser = parse_time_bound_now)]
+    pub older_than: u64,
+
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: &PruneArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    super::purge::execute(
+        PurgeKind::Prune,
+        vec![PurgeSelector::OlderThan {
+            before_unix_ms: args.older_than,
+        }],
+        args.dry_run,
+        runtime_paths,
+    )
+}
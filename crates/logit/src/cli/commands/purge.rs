@@ -0,0 +1,110 @@
+use anyhow::{Context, Error, Result};
+use clap::{ArgGroup, Args};
+use serde_json::json;
+
+use crate::config::RuntimePaths;
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::purge::{PurgeKind, PurgePlan, PurgeSelector, run_purge};
+
+#[derive(Debug, Clone, Args)]
+#[command(group(
+    ArgGroup::new("selector")
+        .required(true)
+        .multiple(true)
+        .args(["sessions", "source_paths", "event_ids"])
+))]
+pub struct PurgeArgs {
+    #[arg(long = "session", value_name = "ID")]
+    pub sessions: Vec<String>,
+
+    #[arg(long = "source-path", value_name = "GLOB")]
+    pub source_paths: Vec<String>,
+
+    #[arg(long = "event-id", value_name = "ID")]
+    pub event_ids: Vec<String>,
+
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+impl PurgeArgs {
+    #[must_use]
+    pub fn selectors(&self) -> Vec<PurgeSelector> {
+        let sessions = self
+            .sessions
+            .iter()
+            .map(|session_id| PurgeSelector::Session {
+                session_id: session_id.clone(),
+            });
+        let source_paths = self
+            .source_paths
+            .iter()
+            .map(|glob| PurgeSelector::SourcePath { glob: glob.clone() });
+        let event_ids = self
+            .event_ids
+            .iter()
+            .map(|event_id| PurgeSelector::EventId {
+                event_id: event_id.clone(),
+            });
+        sessions.chain(source_paths).chain(event_ids).collect()
+    }
+}
+
+pub fn run(args: &PurgeArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    execute(
+        PurgeKind::Purge,
+        args.selectors(),
+        args.dry_run,
+        runtime_paths,
+    )
+}
+
+/// Runs a purge or prune and prints its JSON envelope; shared by both commands.
+pub fn execute(
+    kind: PurgeKind,
+    selectors: Vec<PurgeSelector>,
+    dry_run: bool,
+    runtime_paths: &RuntimePaths,
+) -> Result<()> {
+    let command = match kind {
+        PurgeKind::Prune => "prune",
+        PurgeKind::Purge => "purge",
+    };
+    let plan = PurgePlan {
+        out_dir: runtime_paths.out_dir.clone(),
+        kind,
+        selectors,
+        dry_run,
+        purge_id: None,
+    };
+    let report = run_purge(&plan).map_err(|error| {
+        Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error(
+                command,
+                format!("{command}_failed"),
+                format!("{command} failed"),
+            )
+            .with_meta("dry_run", json!(dry_run))
+            .with_meta(
+                "out_dir",
+                json!(runtime_paths.out_dir.display().to_string()),
+            )
+            .with_error_details(json!({ "cause": format!("{error:#}") })),
+        ))
+    })?;
+
+    let envelope = QueryEnvelope::ok(
+        command,
+        serde_json::to_value(&report).context("failed to encode purge report")?,
+    )
+    .with_meta("dry_run", json!(dry_run))
+    .with_meta(
+        "out_dir",
+        json!(runtime_paths.out_dir.display().to_string()),
+    );
+    println!(
+        "{}",
+        serde_json::to_string(&envelope).context("failed to encode purge envelope")?
+    );
+    Ok(())
+}
@@ -12,6 +12,11 @@ use serde_json::json;
 
 use crate::config::RuntimePaths;
 use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::sqlite::migrations;
+use crate::utils::fs::write_atomic;
+
+pub const DEFAULT_SQL_ROW_CAP: usize = 1_000;
+pub const DEFAULT_SEARCH_ROW_CAP: usize = 50;
 
 #[derive(Debug, Clone, Args)]
 pub struct QueryArgs {
@@ -22,6 +27,7 @@ pub struct QueryArgs {
 #[derive(Debug, Clone, Subcommand)]
 pub enum QueryCommand {
     Sql(QuerySqlArgs),
+    Search(QuerySearchArgs),
     Schema(QuerySchemaArgs),
     Catalog(QueryCatalogArgs),
     Benchmark(QueryBenchmarkArgs),
@@ -35,7 +41,19 @@ pub struct QuerySqlArgs {
     #[arg(long, value_name = "JSON")]
     pub params: Option<String>,
 
-    #[arg(long, default_value_t = 1_000)]
+    #[arg(long, default_value_t = DEFAULT_SQL_ROW_CAP)]
+    pub row_cap: usize,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct QuerySearchArgs {
+    #[arg(value_name = "TERMS")]
+    pub terms: String,
+
+    #[arg(long, default_value_t = false)]
+    pub raw: bool,
+
+    #[arg(long, default_value_t = DEFAULT_SEARCH_ROW_CAP)]
     pub row_cap: usize,
 }
 
@@ -61,17 +79,38 @@ pub struct QueryBenchmarkArgs {
 }
 
 pub fn run(args: &QueryArgs, runtime_paths: &RuntimePaths) -> Result<()> {
-    match &args.command {
-        QueryCommand::Sql(sql_args) => run_sql_query(sql_args, runtime_paths),
-        QueryCommand::Schema(schema_args) => run_schema_query(schema_args, runtime_paths),
-        QueryCommand::Catalog(catalog_args) => run_catalog_query(catalog_args),
+    let sqlite_path = runtime_paths.out_dir.join("mart.sqlite");
+    let envelope = match &args.command {
+        QueryCommand::Sql(sql_args) => sql_query_envelope(sql_args, &sqlite_path)?,
+        QueryCommand::Search(search_args) => search_query_envelope(search_args, &sqlite_path)?,
+        QueryCommand::Schema(schema_args) => schema_query_envelope(schema_args, &sqlite_path)?,
+        QueryCommand::Catalog(catalog_args) => catalog_query_envelope(catalog_args),
         QueryCommand::Benchmark(benchmark_args) => {
-            run_benchmark_query(benchmark_args, runtime_paths)
+            return run_benchmark_query(benchmark_args, runtime_paths);
         }
-    }
+    };
+    print_envelope(&envelope)
 }
 
-fn run_sql_query(args: &QuerySqlArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+pub fn encode_envelope(envelope: &QueryEnvelope) -> Result<String> {
+    serde_json::to_string(envelope).map_err(|error| {
+        Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error(
+                &envelope.command,
+                "query_response_encode_failed",
+                "failed to encode query response",
+            )
+            .with_error_details(json!({ "cause": format!("{error:#}") })),
+        ))
+    })
+}
+
+fn print_envelope(envelope: &QueryEnvelope) -> Result<()> {
+    println!("{}", encode_envelope(envelope)?);
+    Ok(())
+}
+
+pub fn sql_query_envelope(args: &QuerySqlArgs, sqlite_path: &Path) -> Result<QueryEnvelope> {
     let sql_profile = analyze_sql_profile(&args.sql);
 
     if let Err(violation) = validate_read_only_sql(&args.sql) {
@@ -116,8 +155,7 @@ fn run_sql_query(args: &QuerySqlArgs, runtime_paths: &RuntimePaths) -> Result<()
         ))
     })?;
 
-    let sqlite_path = runtime_paths.out_dir.join("mart.sqlite");
-    let connection = crate::sqlite::open_sqlite_connection(&sqlite_path).map_err(|error| {
+    let connection = crate::sqlite::open_sqlite_connection(sqlite_path).map_err(|error| {
         Error::new(QueryEnvelopeCommandFailure::new(
             QueryEnvelope::error(
                 "query.sql",
@@ -179,19 +217,7 @@ fn run_sql_query(args: &QuerySqlArgs, runtime_paths: &RuntimePaths) -> Result<()
             ),
         );
 
-    let encoded = serde_json::to_string(&envelope).map_err(|error| {
-        Error::new(QueryEnvelopeCommandFailure::new(
-            QueryEnvelope::error(
-                "query.sql",
-                "query_response_encode_failed",
-                "failed to encode query response",
-            )
-            .with_error_details(json!({ "cause": format!("{error:#}") })),
-        ))
-    })?;
-    println!("{encoded}");
-
-    Ok(())
+    Ok(envelope)
 }
 
 const ANSWERABILITY_CORPUS_SCHEMA_VERSION: &str = "logit.answerability-corpus.v1";
@@ -497,15 +523,187 @@ struct CatalogRelationDescriptor {
     purpose: String,
 }
 
-fn run_catalog_query(args: &QueryCatalogArgs) -> Result<()> {
-    let concepts = vec![
-        tool_calls_concept(args.verbose),
-        sessions_concept(args.verbose),
-        adapters_concept(args.verbose),
-        quality_concept(args.verbose),
+const SEARCH_SQL: &str = r#"
+SELECT
+    e.event_id,
+    e.run_id,
+    e.session_id,
+    e.adapter_name,
+    e.event_type,
+    e.role,
+    e.tool_name,
+    e.timestamp_utc,
+    e.source_path,
+    e.source_record_locator,
+    snippet(agentlog_events_fts, -1, '<mark>', '</mark>', '...', 16) AS snippet,
+    bm25(agentlog_events_fts) AS score,
+    (
+        SELECT COUNT(*)
+        FROM agentlog_events AS session_events
+        WHERE session_events.session_id = e.session_id
+    ) AS session_event_count,
+    (
+        SELECT MIN(session_events.timestamp_utc)
+        FROM agentlog_events AS session_events
+        WHERE session_events.session_id = e.session_id
+    ) AS session_first_timestamp_utc,
+    (
+        SELECT MAX(session_events.timestamp_utc)
+        FROM agentlog_events AS session_events
+        WHERE session_events.session_id = e.session_id
+    ) AS session_last_timestamp_utc
+FROM agentlog_events_fts
+JOIN agentlog_events AS e ON e.rowid = agentlog_events_fts.rowid
+WHERE agentlog_events_fts MATCH ?1
+ORDER BY score ASC, e.timestamp_unix_ms DESC, e.event_id ASC
+LIMIT ?2
+"#;
+
+pub fn search_query_envelope(args: &QuerySearchArgs, sqlite_path: &Path) -> Result<QueryEnvelope> {
+    let match_expression = if args.raw {
+        args.terms.trim().to_string()
+    } else {
+        crate::sqlite::fts_match_expression(&args.terms)
+    };
+    if match_expression.is_empty() || args.row_cap == 0 {
+        let (code, message) = if match_expression.is_empty() {
+            ("query_search_terms_empty", "search terms must not be empty")
+        } else {
+            ("query_row_cap_invalid", "row_cap must be greater than zero")
+        };
+        let envelope = QueryEnvelope::error("query.search", code, message)
+            .with_meta("implemented", json!(true))
+            .with_error_details(json!({ "terms": args.terms, "row_cap": args.row_cap }));
+        return Err(Error::new(QueryEnvelopeCommandFailure::new(envelope)));
+    }
+
+    // Search never writes: a missing mart is not created, and an outdated one
+    // is reported rather than migrated, so `mart migrate` stays the only path
+    // that changes the schema outside of `ingest refresh`.
+    let unavailable = |code: &str, message: &str, cause: String| {
+        Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error("query.search", code, message)
+                .with_meta("implemented", json!(true))
+                .with_error_details(json!({
+                    "sqlite_path": sqlite_path.display().to_string(),
+                    "cause": cause
+                })),
+        ))
+    };
+    if !sqlite_path.is_file() {
+        return Err(unavailable(
+            "query_mart_unavailable",
+            "unable to open sqlite mart",
+            "sqlite mart does not exist; run `logit ingest refresh` first".to_string(),
+        ));
+    }
+    let connection =
+        crate::sqlite::open_sqlite_connection_read_only(sqlite_path).map_err(|error| {
+            unavailable(
+                "query_mart_unavailable",
+                "unable to open sqlite mart",
+                format!("{error:#}"),
+            )
+        })?;
+    let readiness = migrations::migration_status(&connection)
+        .and_then(|status| Ok((status, migrations::events_fts_present(&connection)?)));
+    match readiness {
+        Err(error) => {
+            return Err(unavailable(
+                "query_mart_unavailable",
+                "unable to inspect sqlite mart",
+                format!("{error:#}"),
+            ));
+        }
+        Ok((status, _)) if !status.pending.is_empty() => {
+            return Err(unavailable(
+                "query_schema_migration_pending",
+                "sqlite mart has pending migrations",
+                format!(
+                    "mart is at migration {} of {}; run `logit mart migrate`",
+                    status.current_version, status.latest_version
+                ),
+            ));
+        }
+        Ok((_, false)) => {
+            return Err(unavailable(
+                "query_search_index_missing",
+                "sqlite mart has no full-text index",
+                format!(
+                    "{} is missing; run `logit mart migrate` or `logit ingest refresh`",
+                    crate::sqlite::EVENTS_FTS_TABLE
+                ),
+            ));
+        }
+        Ok(_) => {}
+    }
+
+    let started = std::time::Instant::now();
+    let params = [
+        SqlValue::Text(match_expression.clone()),
+        SqlValue::Integer(i64::try_from(args.row_cap.saturating_add(1)).unwrap_or(i64::MAX)),
     ];
+    let result = execute_read_only_query(&connection, SEARCH_SQL, &params, args.row_cap).map_err(
+        |error| {
+            Error::new(QueryEnvelopeCommandFailure::new(
+                QueryEnvelope::error(
+                    "query.search",
+                    "query_execution_failed",
+                    "search query execution failed",
+                )
+                .with_meta("implemented", json!(true))
+                .with_meta("match_expression", json!(match_expression))
+                .with_error_details(json!({ "cause": format!("{error:#}") })),
+            ))
+        },
+    )?;
+    let duration_ms = started.elapsed().as_millis() as u64;
 
-    let relations = vec![
+    let envelope = QueryEnvelope::ok("query.search", json!({ "hits": result.rows }))
+        .with_meta("implemented", json!(true))
+        .with_meta("match_expression", json!(match_expression))
+        .with_meta("raw", json!(args.raw))
+        .with_meta("row_count", json!(result.row_count))
+        .with_meta("truncated", json!(result.truncated))
+        .with_meta("row_cap", json!(args.row_cap))
+        .with_meta("duration_ms", json!(duration_ms));
+
+    Ok(envelope)
+}
+
+#[must_use]
+pub fn catalog_query_envelope(args: &QueryCatalogArgs) -> QueryEnvelope {
+    let concepts = catalog_concepts(args.verbose);
+    let relations = catalog_relations();
+
+    let concept_count = concepts.len();
+    let relation_count = relations.len();
+    QueryEnvelope::ok(
+        "query.catalog",
+        json!({
+            "schema_version": "logit.semantic-catalog.v1",
+            "concepts": concepts,
+            "relations": relations,
+        }),
+    )
+    .with_meta("implemented", json!(true))
+    .with_meta("verbose", json!(args.verbose))
+    .with_meta("concept_count", json!(concept_count))
+    .with_meta("relation_count", json!(relation_count))
+}
+
+fn catalog_concepts(verbose: bool) -> Vec<CatalogConceptDescriptor> {
+    vec![
+        tool_calls_concept(verbose),
+        sessions_concept(verbose),
+        adapters_concept(verbose),
+        quality_concept(verbose),
+        transcript_search_concept(verbose),
+    ]
+}
+
+fn catalog_relations() -> Vec<CatalogRelationDescriptor> {
+    vec![
         catalog_relation(
             "v_tool_calls",
             "view",
@@ -526,46 +724,27 @@ fn run_catalog_query(args: &QueryCatalogArgs) -> Result<()> {
             "view",
             "timestamp-quality and warning/error quality rollups",
         ),
+        catalog_relation(
+            "v_hosts",
+            "view",
+            "per-host event, session and adapter counts for merged marts",
+        ),
         catalog_relation(
             "ingest_runs",
             "table",
-            "refresh run lifecycle/status and ingest counters",
+            "refresh and import run lifecycle/status, host and ingest counters",
         ),
         catalog_relation(
             "ingest_watermarks",
             "table",
-            "per-source freshness/staleness and watermark frontiers",
+            "per-host, per-source freshness/staleness and watermark frontiers",
         ),
-    ];
-
-    let concept_count = concepts.len();
-    let relation_count = relations.len();
-    let envelope = QueryEnvelope::ok(
-        "query.catalog",
-        json!({
-            "schema_version": "logit.semantic-catalog.v1",
-            "concepts": concepts,
-            "relations": relations,
-        }),
-    )
-    .with_meta("implemented", json!(true))
-    .with_meta("verbose", json!(args.verbose))
-    .with_meta("concept_count", json!(concept_count))
-    .with_meta("relation_count", json!(relation_count));
-
-    let encoded = serde_json::to_string(&envelope).map_err(|error| {
-        Error::new(QueryEnvelopeCommandFailure::new(
-            QueryEnvelope::error(
-                "query.catalog",
-                "query_response_encode_failed",
-                "failed to encode query response",
-            )
-            .with_error_details(json!({ "cause": format!("{error:#}") })),
-        ))
-    })?;
-    println!("{encoded}");
-
-    Ok(())
+        catalog_relation(
+            "agentlog_events_fts",
+            "virtual_table",
+            "FTS5 index over content_text, tool_arguments_json and tool_result_text",
+        ),
+    ]
 }
 
 fn tool_calls_concept(verbose: bool) -> CatalogConceptDescriptor {
@@ -621,7 +800,7 @@ fn sessions_concept(verbose: bool) -> CatalogConceptDescriptor {
         primary_relation: "v_sessions".to_string(),
         grain: "run_id + session_id".to_string(),
         key_fields: strings(&["run_id", "session_id"]),
-        suggested_dimensions: strings(&["session_id", "run_id"]),
+        suggested_dimensions: strings(&["session_id", "run_id", "host"]),
         suggested_metrics: strings(&[
             "event_count",
             "duration_ms",
@@ -649,6 +828,7 @@ fn sessions_concept(verbose: bool) -> CatalogConceptDescriptor {
                 ("prompt_count", "Prompt events in session"),
                 ("response_count", "Response events in session"),
                 ("error_count", "Error events in session"),
+                ("host", "Machine the session was collected on"),
             ])
         }),
     }
@@ -728,6 +908,41 @@ fn quality_concept(verbose: bool) -> CatalogConceptDescriptor {
     }
 }
 
+fn transcript_search_concept(verbose: bool) -> CatalogConceptDescriptor {
+    CatalogConceptDescriptor {
+        concept_id: "transcript_search".to_string(),
+        description:
+            "Full-text search over transcript and tool payload text using FTS5 MATCH, bm25() and snippet()"
+                .to_string(),
+        primary_relation: "agentlog_events_fts".to_string(),
+        grain: "event_id (rowid mirrors agentlog_events.rowid)".to_string(),
+        key_fields: strings(&["rowid", "event_id"]),
+        suggested_dimensions: strings(&["session_id", "adapter_name"]),
+        suggested_metrics: strings(&[
+            "bm25(agentlog_events_fts) AS score",
+            "snippet(agentlog_events_fts, -1, '<mark>', '</mark>', '...', 16) AS snippet",
+        ]),
+        default_ordering: strings(&["score ASC", "event_id ASC"]),
+        joins: vec![catalog_join(
+            "sessions",
+            "v_sessions",
+            "left",
+            "agentlog_events_fts.session_id = v_sessions.session_id",
+            "Attach session rollups to full-text hits; join agentlog_events on rowid for event fields",
+        )],
+        field_catalog: verbose.then(|| {
+            catalog_fields(&[
+                ("content_text", "Indexed prompt/response text"),
+                ("tool_arguments_json", "Indexed tool call arguments"),
+                ("tool_result_text", "Indexed tool output text"),
+                ("event_id", "Unindexed event identifier"),
+                ("session_id", "Unindexed session identifier"),
+                ("adapter_name", "Unindexed adapter name"),
+            ])
+        }),
+    }
+}
+
 fn catalog_join(
     to_concept: &str,
     relation: &str,
@@ -784,9 +999,8 @@ struct SchemaObjectDescriptor {
     columns: Vec<SchemaColumnDescriptor>,
 }
 
-fn run_schema_query(args: &QuerySchemaArgs, runtime_paths: &RuntimePaths) -> Result<()> {
-    let sqlite_path = runtime_paths.out_dir.join("mart.sqlite");
-    let connection = crate::sqlite::open_sqlite_connection(&sqlite_path).map_err(|error| {
+pub fn schema_query_envelope(args: &QuerySchemaArgs, sqlite_path: &Path) -> Result<QueryEnvelope> {
+    let connection = crate::sqlite::open_sqlite_connection(sqlite_path).map_err(|error| {
         Error::new(QueryEnvelopeCommandFailure::new(
             QueryEnvelope::error(
                 "query.schema",
@@ -801,23 +1015,35 @@ fn run_schema_query(args: &QuerySchemaArgs, runtime_paths: &RuntimePaths) -> Res
             })),
         ))
     })?;
-    crate::sqlite::ensure_sqlite_schema(&connection).map_err(|error| {
-        Error::new(QueryEnvelopeCommandFailure::new(
-            QueryEnvelope::error(
-                "query.schema",
-                "query_schema_introspection_failed",
-                "failed to ensure sqlite schema before introspection",
-            )
-            .with_meta("implemented", json!(true))
-            .with_meta("include_internal", json!(args.include_internal))
-            .with_error_details(json!({
-                "sqlite_path": sqlite_path.display().to_string(),
-                "cause": format!("{error:#}")
-            })),
-        ))
-    })?;
+    // Only a brand-new mart is initialized here; existing marts are reported
+    // as they are so pending migrations stay visible until `mart migrate`.
+    migrations::schema_initialized(&connection)
+        .and_then(|initialized| {
+            if initialized {
+                Ok(())
+            } else {
+                crate::sqlite::ensure_sqlite_schema(&connection)
+            }
+        })
+        .map_err(|error| {
+            Error::new(QueryEnvelopeCommandFailure::new(
+                QueryEnvelope::error(
+                    "query.schema",
+                    "query_schema_introspection_failed",
+                    "failed to ensure sqlite schema before introspection",
+                )
+                .with_meta("implemented", json!(true))
+                .with_meta("include_internal", json!(args.include_internal))
+                .with_error_details(json!({
+                    "sqlite_path": sqlite_path.display().to_string(),
+                    "cause": format!("{error:#}")
+                })),
+            ))
+        })?;
 
-    let objects = load_schema_descriptors(&connection, args.include_internal).map_err(|error| {
+    let objects = load_schema_descriptors(&connection, args.include_internal)
+        .and_then(|objects| Ok((objects, migrations::migration_status(&connection)?)));
+    let (objects, migration_status) = objects.map_err(|error| {
         Error::new(QueryEnvelopeCommandFailure::new(
             QueryEnvelope::error(
                 "query.schema",
@@ -844,29 +1070,19 @@ fn run_schema_query(args: &QuerySchemaArgs, runtime_paths: &RuntimePaths) -> Res
         json!({
             "tables": tables,
             "views": views,
+            "migrations": migration_status,
         }),
     )
     .with_meta("implemented", json!(true))
     .with_meta("include_internal", json!(args.include_internal))
     .with_meta("sqlite_path", json!(sqlite_path.display().to_string()))
+    .with_meta("migration_version", json!(migration_status.current_version))
+    .with_meta("migrations_pending", json!(migration_status.pending.len()))
     .with_meta("table_count", json!(table_count))
     .with_meta("view_count", json!(view_count))
     .with_meta("object_count", json!(table_count + view_count));
 
-    // Recompute counts from the payload values to avoid drift if fields are reordered later.
-    let encoded = serde_json::to_string(&envelope).map_err(|error| {
-        Error::new(QueryEnvelopeCommandFailure::new(
-            QueryEnvelope::error(
-                "query.schema",
-                "query_response_encode_failed",
-                "failed to encode query response",
-            )
-            .with_error_details(json!({ "cause": format!("{error:#}") })),
-        ))
-    })?;
-    println!("{encoded}");
-
-    Ok(())
+    Ok(envelope)
 }
 
 fn load_answerability_corpus(path: &Path) -> Result<AnswerabilityCorpus> {
@@ -923,44 +1139,8 @@ fn build_benchmark_preflight(
                 }
             });
 
-    let concepts = [
-        tool_calls_concept(false),
-        sessions_concept(false),
-        adapters_concept(false),
-        quality_concept(false),
-    ];
-    let relations = [
-        catalog_relation(
-            "v_tool_calls",
-            "view",
-            "tool call/result pairing, duration, and pairing status",
-        ),
-        catalog_relation(
-            "v_sessions",
-            "view",
-            "session-level rollups for event and tool activity",
-        ),
-        catalog_relation(
-            "v_adapters",
-            "view",
-            "adapter-level usage/reliability aggregate counters",
-        ),
-        catalog_relation(
-            "v_quality",
-            "view",
-            "timestamp-quality and warning/error quality rollups",
-        ),
-        catalog_relation(
-            "ingest_runs",
-            "table",
-            "refresh run lifecycle/status and ingest counters",
-        ),
-        catalog_relation(
-            "ingest_watermarks",
-            "table",
-            "per-source freshness/staleness and watermark frontiers",
-        ),
-    ];
+    let concepts = catalog_concepts(false);
+    let relations = catalog_relations();
 
     Ok(AnswerabilityBenchmarkPreflight {
         schema_table_count,
@@ -1234,7 +1414,7 @@ fn write_benchmark_artifact(path: &Path, report: &AnswerabilityBenchmarkReport)
     }
     let encoded = serde_json::to_string_pretty(report)
         .context("failed to encode answerability benchmark artifact")?;
-    std::fs::write(path, encoded)
+    write_atomic(path, encoded.as_bytes())
         .with_context(|| format!("failed to write benchmark artifact: {}", path.display()))?;
     Ok(())
 }
@@ -1698,7 +1878,12 @@ fn load_schema_columns(
     connection: &rusqlite::Connection,
     object_name: &str,
 ) -> Result<Vec<SchemaColumnDescriptor>> {
-    let pragma_sql = format!("PRAGMA table_info({})", sqlite_single_quoted(object_name));
+    // `table_xinfo` also lists generated columns such as `agentlog_events.host`;
+    // hidden virtual-table columns (hidden = 1) are skipped.
+    let pragma_sql = format!(
+        "SELECT cid, name, type, \"notnull\", dflt_value, pk FROM pragma_table_xinfo({}) WHERE hidden != 1",
+        sqlite_single_quoted(object_name)
+    );
     let mut statement = connection
         .prepare(&pragma_sql)
         .with_context(|| format!("failed to prepare column introspection for `{object_name}`"))?;
@@ -1722,7 +1907,11 @@ fn load_schema_columns(
 }
 
 fn is_internal_schema_object(object_name: &str) -> bool {
-    object_name.starts_with("sqlite_") || object_name == crate::sqlite::SCHEMA_META_TABLE
+    object_name.starts_with("sqlite_")
+        || object_name == crate::sqlite::SCHEMA_META_TABLE
+        || object_name
+            .strip_prefix(crate::sqlite::EVENTS_FTS_TABLE)
+            .is_some_and(|suffix| suffix.starts_with('_'))
 }
 
 fn sqlite_single_quoted(value: &str) -> String {
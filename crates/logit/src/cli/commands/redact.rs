@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use serde::Serialize;
+
+use crate::config::RuntimePaths;
+use crate::utils::redaction::{
+    self, RedactionFinding, RedactionPolicy, RedactionRules, find_redactions,
+    redaction_catalog_classes,
+};
+
+#[derive(Debug, Clone, Args)]
+pub struct RedactArgs {
+    #[command(subcommand)]
+    pub command: RedactCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum RedactCommand {
+    Test(RedactTestArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RedactTestArgs {
+    #[arg(value_name = "TEXT|FILE")]
+    pub input: String,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "secrets-only",
+        value_parser = RedactionPolicy::parse
+    )]
+    pub redaction: RedactionPolicy,
+
+    #[arg(long, value_name = "PATH")]
+    pub rules: Option<PathBuf>,
+
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RedactTestReport {
+    pub input: String,
+    pub policy: String,
+    pub rules_path: String,
+    pub catalog_classes: Vec<String>,
+    pub allowlist_entries: usize,
+    pub class_counts: BTreeMap<String, usize>,
+    pub findings: Vec<RedactionFinding>,
+}
+
+pub fn run(args: &RedactArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        RedactCommand::Test(test_args) => {
+            let report = build_test_report(test_args, runtime_paths)?;
+            if test_args.json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report)
+                        .context("failed to encode redact test report")?
+                );
+            } else {
+                println!("{}", render_text_report(&report));
+            }
+            Ok(())
+        }
+    }
+}
+
+pub fn build_test_report(
+    args: &RedactTestArgs,
+    runtime_paths: &RuntimePaths,
+) -> Result<RedactTestReport> {
+    let rules_path = args
+        .rules
+        .clone()
+        .unwrap_or_else(|| redaction::rules_path(&runtime_paths.home_dir));
+    let rules = RedactionRules::load(&rules_path)?;
+
+    let input_path = PathBuf::from(&args.input);
+    let (input, text) = if input_path.is_file() {
+        let text = std::fs::read_to_string(&input_path).with_context(|| {
+            format!("failed to read redact test input: {}", input_path.display())
+        })?;
+        (input_path.display().to_string(), text)
+    } else {
+        ("<text>".to_string(), args.input.clone())
+    };
+
+    let findings = find_redactions(&text, args.redaction, &rules);
+    let mut class_counts = BTreeMap::new();
+    for finding in &findings {
+        *class_counts.entry(finding.class_name.clone()).or_insert(0) += 1;
+    }
+
+    Ok(RedactTestReport {
+        input,
+        policy: args.redaction.as_str().to_string(),
+        rules_path: rules_path.display().to_string(),
+        catalog_classes: redaction_catalog_classes(&rules),
+        allowlist_entries: rules.allowlist().len(),
+        class_counts,
+        findings,
+    })
+}
+
+#[must_use]
+pub fn render_text_report(report: &RedactTestReport) -> String {
+    let mut lines = vec![
+        format!("input: {}", report.input),
+        format!("policy: {}", report.policy),
+        format!("rules_path: {}", report.rules_path),
+        format!("catalog_classes: {}", report.catalog_classes.join(",")),
+        format!("allowlist_entries: {}", report.allowlist_entries),
+        format!("findings: {}", report.findings.len()),
+    ];
+    for (class_name, count) in &report.class_counts {
+        lines.push(format!("class_counts.{class_name}: {count}"));
+    }
+    for finding in &report.findings {
+        lines.push(format!(
+            "finding: class={} line={} column={} length={}",
+            finding.class_name, finding.line, finding.column, finding.length
+        ));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,300 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::Result;
+use clap::Args;
+use serde_json::json;
+
+use crate::cli::app::SourceFilterArgs;
+use crate::cli::commands::config::build_show_report;
+use crate::cli::commands::normalize::NormalizeArgs;
+use crate::cli::commands::snapshot::SnapshotArgs;
+use crate::cli::commands::validate::ValidateArgs;
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::pipeline::{
+    GateTrip, RUN_MANIFEST_SCHEMA_VERSION, RunGate, RunGateFailure, RunManifest, RunStage,
+    RunStatus, STAGE_EXIT_FAILED, STAGE_EXIT_GATED, STAGE_EXIT_SUCCESS, StageRecord, StageStatus,
+    artifact_record, evaluate_adapter_gates, evaluate_validation_gates, new_run_id,
+    run_manifest_path, write_run_manifest,
+};
+use crate::purge::{TombstoneList, tombstones_path};
+use crate::utils::redaction::RedactionPolicy;
+use crate::utils::time::{format_unix_ms, unix_timestamp_milliseconds};
+
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
+    #[arg(long, value_name = "PATH")]
+    pub source_root: Option<PathBuf>,
+
+    #[arg(long, default_value_t = 3)]
+    pub sample_size: usize,
+
+    #[arg(long, default_value_t = false)]
+    pub fail_fast: bool,
+
+    #[arg(
+        long,
+        value_name = "POLICY",
+        default_value = "secrets-only",
+        value_parser = RedactionPolicy::parse
+    )]
+    pub redaction: RedactionPolicy,
+
+    #[arg(long, default_value_t = false)]
+    pub pseudonymize: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "pseudonymize")]
+    pub no_pseudonymize: bool,
+
+    #[arg(long = "gate", value_name = "GATE", value_parser = RunGate::parse)]
+    pub gates: Vec<RunGate>,
+
+    #[arg(long, default_value_t = false, conflicts_with = "gates")]
+    pub no_gates: bool,
+
+    #[command(flatten)]
+    pub filters: SourceFilterArgs,
+}
+
+pub fn run(args: &RunArgs, config: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
+    let started_unix_ms = unix_timestamp_milliseconds();
+    let started = Instant::now();
+    let run_id = new_run_id(started_unix_ms);
+    let gates = if args.no_gates {
+        Vec::new()
+    } else {
+        args.gates.clone()
+    };
+    println!(
+        "run: start run_id={run_id} gates={} out_dir={}",
+        gate_list(&gates),
+        runtime_paths.out_dir.display()
+    );
+
+    let mut stages = Vec::new();
+    let outcome = run_stages(args, config, runtime_paths, &run_id, &gates, &mut stages);
+    for stage in RunStage::ALL.into_iter().skip(stages.len()) {
+        stages.push(StageRecord::skipped(stage));
+    }
+    let (status, exit_code, gate_tripped) = match &outcome {
+        Ok(None) => (RunStatus::Success, STAGE_EXIT_SUCCESS, None),
+        Ok(Some(trip)) => (RunStatus::Gated, STAGE_EXIT_GATED, Some(trip.clone())),
+        Err(_) => (RunStatus::Failed, STAGE_EXIT_FAILED, None),
+    };
+    let mut effective_config = build_show_report(config, runtime_paths);
+    effective_config["run"] = json!({
+        "source_root": args.source_root.as_ref().map(|path| path.display().to_string()),
+        "sample_size": args.sample_size,
+        "fail_fast": args.fail_fast,
+        "redaction": args.redaction.as_str(),
+        "pseudonymize": args.pseudonymize,
+        "validation_mode": "strict",
+        "filters": args.filters.to_filters().ok().map(|filters| filters.record()),
+    });
+    let manifest = RunManifest {
+        schema_version: RUN_MANIFEST_SCHEMA_VERSION.to_string(),
+        run_id: run_id.clone(),
+        status,
+        exit_code,
+        started_at_utc: format_unix_ms(started_unix_ms),
+        finished_at_utc: format_unix_ms(unix_timestamp_milliseconds()),
+        duration_ms: elapsed_ms(started),
+        gates,
+        gate_tripped,
+        stages,
+        config: effective_config,
+    };
+    let manifest_path = run_manifest_path(&runtime_paths.out_dir);
+    write_run_manifest(&manifest_path, &manifest)?;
+    for stage in &manifest.stages {
+        println!(
+            "run: stage name={} status={} duration_ms={} artifacts={}",
+            stage.stage.as_str(),
+            stage.status.as_str(),
+            stage.duration_ms,
+            stage.artifacts.len()
+        );
+    }
+    println!(
+        "run: complete run_id={run_id} status={} exit_code={exit_code} duration_ms={} manifest={}",
+        manifest.status.as_str(),
+        manifest.duration_ms,
+        manifest_path.display()
+    );
+
+    match outcome {
+        Ok(None) => Ok(()),
+        Ok(Some(trip)) => Err(RunGateFailure { run_id, trip }.into()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Executes the stages in order, stopping at the first failure or tripped
+/// gate; every attempted stage leaves a record in `stages`.
+fn run_stages(
+    args: &RunArgs,
+    config: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+    run_id: &str,
+    gates: &[RunGate],
+    stages: &mut Vec<StageRecord>,
+) -> Result<Option<GateTrip>> {
+    let out_dir = &runtime_paths.out_dir;
+
+    let snapshot_artifacts = crate::snapshot::build_artifact_layout(out_dir);
+    let snapshot_args = SnapshotArgs {
+        source_root: args.source_root.clone(),
+        sample_size: args.sample_size,
+        pseudonymize: args.pseudonymize,
+        no_pseudonymize: args.no_pseudonymize,
+        filters: args.filters.clone(),
+    };
+    run_stage(
+        stages,
+        RunStage::Snapshot,
+        &[
+            snapshot_artifacts.index_json.as_path(),
+            snapshot_artifacts.samples_jsonl.as_path(),
+            snapshot_artifacts.schema_profile_json.as_path(),
+        ],
+        || super::snapshot::execute(&snapshot_args, config, runtime_paths, Some(run_id)),
+    )?;
+
+    let normalize_artifacts = crate::normalize::build_artifact_layout(out_dir);
+    let discovery_artifacts = crate::discovery::build_artifact_layout(out_dir);
+    let normalize_args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: args.source_root.clone(),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: args.fail_fast,
+        redaction: args.redaction,
+        pseudonymize: args.pseudonymize,
+        no_pseudonymize: args.no_pseudonymize,
+        filters: args.filters.clone(),
+    };
+    let adapter_health = run_stage(
+        stages,
+        RunStage::Normalize,
+        &[
+            normalize_artifacts.events_jsonl.as_path(),
+            normalize_artifacts.schema_json.as_path(),
+            normalize_artifacts.stats_json.as_path(),
+            discovery_artifacts.sources_json.as_path(),
+            discovery_artifacts.zsh_history_usage_json.as_path(),
+        ],
+        || super::normalize::execute(&normalize_args, config, runtime_paths, Some(run_id)),
+    )?;
+    if let Some(trip) = evaluate_adapter_gates(gates, &adapter_health) {
+        return Ok(Some(mark_gated(stages, trip)));
+    }
+
+    let validate_artifacts = crate::validate::build_artifact_layout(out_dir);
+    let validate_args = ValidateArgs {
+        input: normalize_artifacts.events_jsonl.clone(),
+        strict: true,
+    };
+    let report = run_stage(
+        stages,
+        RunStage::Validate,
+        &[validate_artifacts.report_json.as_path()],
+        || super::validate::execute(&validate_args, runtime_paths, Some(run_id)),
+    )?;
+    if let Some(trip) = evaluate_validation_gates(gates, &report) {
+        return Ok(Some(mark_gated(stages, trip)));
+    }
+
+    let ingest_report_path = crate::ingest::ingest_report_artifact_path(out_dir);
+    let sqlite_path = out_dir.join("mart.sqlite");
+    run_stage(
+        stages,
+        RunStage::Ingest,
+        &[ingest_report_path.as_path(), sqlite_path.as_path()],
+        || {
+            let source_root = args
+                .source_root
+                .as_deref()
+                .unwrap_or(runtime_paths.cwd.as_path());
+            let mut plan =
+                crate::ingest::default_plan_from_paths(out_dir, source_root, args.fail_fast);
+            plan.filters = args.filters.to_filters()?;
+            plan.run_id = Some(run_id.to_string());
+            plan.host = Some(config.host_id());
+            plan.tombstones = TombstoneList::load(&tombstones_path(out_dir))?.selectors();
+            let report = crate::ingest::run_refresh(&plan)?;
+            crate::ingest::write_ingest_report_artifact(&ingest_report_path, &report)?;
+            println!(
+                "ingest: complete ingest_run_id={} events_read={} events_written={} events_skipped={} report={}",
+                report.ingest_run_id,
+                report.events_read,
+                report.events_written,
+                report.events_skipped,
+                ingest_report_path.display()
+            );
+            Ok(())
+        },
+    )?;
+    Ok(None)
+}
+
+fn run_stage<T>(
+    stages: &mut Vec<StageRecord>,
+    stage: RunStage,
+    artifacts: &[&Path],
+    body: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    println!("run: stage_start name={}", stage.as_str());
+    let started = Instant::now();
+    let result = body();
+    let mut record = StageRecord {
+        stage,
+        status: StageStatus::Success,
+        duration_ms: elapsed_ms(started),
+        exit_code: Some(STAGE_EXIT_SUCCESS),
+        error: None,
+        artifacts: Vec::new(),
+    };
+    match &result {
+        Ok(_) => {
+            for path in artifacts.iter().filter(|path| path.is_file()) {
+                record.artifacts.push(artifact_record(path)?);
+            }
+        }
+        Err(error) => {
+            record.status = StageStatus::Failed;
+            record.exit_code = Some(STAGE_EXIT_FAILED);
+            record.error = Some(format!("{error:#}"));
+        }
+    }
+    stages.push(record);
+    result
+}
+
+fn mark_gated(stages: &mut [StageRecord], trip: GateTrip) -> GateTrip {
+    if let Some(record) = stages.last_mut() {
+        record.status = StageStatus::Gated;
+        record.exit_code = Some(STAGE_EXIT_GATED);
+    }
+    println!(
+        "run: gate_tripped gate={} stage={} detail={}",
+        trip.gate.as_str(),
+        trip.stage.as_str(),
+        trip.detail
+    );
+    trip
+}
+
+fn gate_list(gates: &[RunGate]) -> String {
+    if gates.is_empty() {
+        return "none".to_string();
+    }
+    gates
+        .iter()
+        .map(|gate| gate.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
@@ -0,0 +1,62 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use clap::Args;
+use signal_hook::consts::{SIGINT, SIGTERM};
+
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::serve::{DEFAULT_MAX_CONNECTIONS, DEFAULT_PORT, ServeConfig, Server, load_token};
+
+#[derive(Debug, Clone, Args)]
+pub struct ServeArgs {
+    #[arg(long, default_value_t = DEFAULT_PORT)]
+    pub port: u16,
+
+    #[arg(long, value_name = "PATH")]
+    pub mart: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub token_file: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub request_log: Option<PathBuf>,
+}
+
+pub fn build_config(
+    args: &ServeArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+) -> Result<ServeConfig> {
+    Ok(ServeConfig {
+        sqlite_path: args
+            .mart
+            .clone()
+            .unwrap_or_else(|| runtime_paths.out_dir.join("mart.sqlite")),
+        token: args.token_file.as_deref().map(load_token).transpose()?,
+        request_log: args.request_log.clone(),
+        request_timeout: Duration::from_secs(settings.serve_request_timeout_secs.value),
+        max_connections: DEFAULT_MAX_CONNECTIONS,
+    })
+}
+
+pub fn run(args: &ServeArgs, settings: &LogitConfig, runtime_paths: &RuntimePaths) -> Result<()> {
+    let config = build_config(args, settings, runtime_paths)?;
+    let auth = if config.token.is_some() {
+        "bearer token"
+    } else {
+        "none"
+    };
+    let server = Server::bind(args.port, config)?;
+    for signal in [SIGINT, SIGTERM] {
+        signal_hook::flag::register(signal, server.shutdown_flag())
+            .context("failed to register shutdown signal handler")?;
+    }
+    println!(
+        "logit: serving http://{} (read-only, auth: {auth}); Ctrl-C to stop",
+        server.local_addr()?
+    );
+    server.run()?;
+    println!("logit: serve shut down");
+    Ok(())
+}
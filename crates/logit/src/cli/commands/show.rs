@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Error, Result};
+use clap::{Args, Subcommand};
+use serde_json::json;
+
+use crate::config::RuntimePaths;
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::sqlite::reader::MartReader;
+use crate::transcript::{
+    RenderOptions, SessionTranscript, TranscriptFormat, TurnRange, load_session_from_events_jsonl,
+    load_session_from_mart, render,
+};
+use crate::utils::fs::write_atomic;
+
+#[derive(Debug, Clone, Args)]
+pub struct ShowArgs {
+    #[command(subcommand)]
+    pub command: ShowCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ShowCommand {
+    Session(ShowSessionArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ShowSessionArgs {
+    #[arg(value_name = "SESSION_ID")]
+    pub session_id: String,
+
+    #[arg(long, value_name = "RANGE", value_parser = TurnRange::parse)]
+    pub turns: Option<TurnRange>,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        value_parser = TranscriptFormat::parse
+    )]
+    pub format: TranscriptFormat,
+
+    #[arg(long, value_name = "PATH", conflicts_with = "mart")]
+    pub events: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub mart: Option<PathBuf>,
+
+    #[arg(long, default_value_t = false)]
+    pub full: bool,
+
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: &ShowArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    match &args.command {
+        ShowCommand::Session(session_args) => {
+            let rendered = render_session(session_args, runtime_paths)?;
+            match &session_args.output {
+                Some(path) => write_atomic(path, rendered.as_bytes())
+                    .with_context(|| format!("failed to write transcript: {}", path.display())),
+                None => {
+                    println!("{rendered}");
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+pub fn render_session(args: &ShowSessionArgs, runtime_paths: &RuntimePaths) -> Result<String> {
+    let default_mart = runtime_paths.out_dir.join("mart.sqlite");
+    let (path, from_mart) = match (&args.events, &args.mart) {
+        (Some(path), _) => (path.clone(), false),
+        (None, Some(path)) => (path.clone(), true),
+        (None, None) if default_mart.is_file() => (default_mart, true),
+        (None, None) => (runtime_paths.out_dir.join("events.jsonl"), false),
+    };
+    let events = if from_mart {
+        load_session_from_mart(&path, &args.session_id)?
+    } else {
+        load_session_from_events_jsonl(&path, &args.session_id)?
+    };
+
+    let transcript = SessionTranscript::build(
+        &args.session_id,
+        &path.display().to_string(),
+        events,
+        args.turns,
+    )?;
+    Ok(render(
+        &transcript,
+        args.format,
+        RenderOptions {
+            collapse_tool_output: !args.full,
+        },
+    ))
+}
+
+fn open_mart_reader(command: &str, sqlite_path: &Path) -> Result<MartReader> {
+    MartReader::open(sqlite_path).map_err(|error| {
+        Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error(
+                command,
+                "query_mart_unavailable",
+                "unable to open sqlite mart",
+            )
+            .with_error_details(json!({
+                "sqlite_path": sqlite_path.display().to_string(),
+                "cause": format!("{error:#}")
+            })),
+        ))
+    })
+}
+
+pub fn sessions_envelope(sqlite_path: &Path) -> Result<QueryEnvelope> {
+    let sessions = open_mart_reader("sessions.list", sqlite_path)?.sessions()?;
+    let row_count = sessions.len();
+    Ok(
+        QueryEnvelope::ok("sessions.list", json!({ "sessions": sessions }))
+            .with_meta("row_count", json!(row_count)),
+    )
+}
+
+pub fn session_transcript_envelope(
+    sqlite_path: &Path,
+    session_id: &str,
+    format: Option<&str>,
+    turns: Option<&str>,
+    full: bool,
+) -> Result<QueryEnvelope> {
+    let command = "sessions.transcript";
+    let invalid = |message: String| {
+        Error::new(QueryEnvelopeCommandFailure::new(QueryEnvelope::error(
+            command,
+            "transcript_request_invalid",
+            message,
+        )))
+    };
+    let parsed_format = format
+        .map_or(Ok(TranscriptFormat::Text), TranscriptFormat::parse)
+        .map_err(invalid)?;
+    let turns = turns.map(TurnRange::parse).transpose().map_err(invalid)?;
+
+    let events = open_mart_reader(command, sqlite_path)?.transcript_events(session_id)?;
+    if events.is_empty() {
+        return Err(Error::new(QueryEnvelopeCommandFailure::new(
+            QueryEnvelope::error(
+                command,
+                "session_not_found",
+                format!("no events found for session `{session_id}`"),
+            )
+            .with_error_details(json!({ "session_id": session_id })),
+        )));
+    }
+    let source = sqlite_path.display().to_string();
+    let transcript = SessionTranscript::build(session_id, &source, events, turns)
+        .map_err(|error| invalid(format!("{error:#}")))?;
+    let document = render(
+        &transcript,
+        parsed_format,
+        RenderOptions {
+            collapse_tool_output: !full,
+        },
+    );
+    Ok(QueryEnvelope::ok(
+        command,
+        json!({
+            "session_id": session_id,
+            "format": format.unwrap_or("text"),
+            "turn_count": transcript.turns.len(),
+            "document": document,
+        }),
+    )
+    .with_meta("full", json!(full)))
+}
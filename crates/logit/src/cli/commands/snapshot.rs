@@ -3,7 +3,10 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::Args;
 
-use crate::config::RuntimePaths;
+use crate::cli::app::SourceFilterArgs;
+use crate::config::{LogitConfig, RuntimePaths};
+use crate::discovery::roots::{RootOrigin, SourceRoots};
+use crate::utils::redaction::{PseudonymKey, RedactionRules, rules_path};
 
 #[derive(Debug, Clone, Args)]
 pub struct SnapshotArgs {
@@ -12,12 +15,43 @@ pub struct SnapshotArgs {
 
     #[arg(long, default_value_t = 3)]
     pub sample_size: usize,
+
+    #[arg(long, default_value_t = false)]
+    pub pseudonymize: bool,
+
+    #[arg(long, default_value_t = false, conflicts_with = "pseudonymize")]
+    pub no_pseudonymize: bool,
+
+    #[command(flatten)]
+    pub filters: SourceFilterArgs,
+}
+
+pub fn run(
+    args: &SnapshotArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+) -> Result<()> {
+    execute(args, settings, runtime_paths, None)
 }
 
-pub fn run(args: &SnapshotArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+/// Runs snapshot, recording `run_id` in `snapshot/index.json` when given.
+pub fn execute(
+    args: &SnapshotArgs,
+    settings: &LogitConfig,
+    runtime_paths: &RuntimePaths,
+    run_id: Option<&str>,
+) -> Result<()> {
+    let mut redaction_rules = RedactionRules::load(&rules_path(&runtime_paths.home_dir))?;
+    if args.pseudonymize {
+        redaction_rules = redaction_rules
+            .with_pseudonym_key(PseudonymKey::load_or_create(&runtime_paths.out_dir)?);
+    }
     let config = crate::snapshot::SnapshotConfig {
         sample_size: args.sample_size,
         redact_sensitive_values: true,
+        redaction_rules,
+        filters: args.filters.to_filters()?,
+        source_roots: SourceRoots::load_with(&runtime_paths.home_dir, settings.root_entries())?,
     };
     let source_root = args
         .source_root
@@ -25,21 +59,40 @@ pub fn run(args: &SnapshotArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         .map(|path| path.to_string_lossy().to_string())
         .unwrap_or_else(|| "<auto>".to_string());
     println!(
-        "snapshot: start sample_size={} source_root={} out_dir={}",
+        "snapshot: start sample_size={} pseudonymize={} source_root={} out_dir={}",
         config.sample_size,
+        config.redaction_rules.pseudonymizes(),
         source_root,
         runtime_paths.out_dir.display()
     );
+    if config.filters.is_active() {
+        println!("snapshot: filters {}", config.filters.summary());
+    }
+    for root in config
+        .source_roots
+        .roots
+        .iter()
+        .filter(|root| root.origin != RootOrigin::Builtin)
+    {
+        println!(
+            "snapshot: adapter_root adapter={} profile={} origin={} path={}",
+            root.adapter.as_str(),
+            root.profile,
+            root.origin.as_str(),
+            root.path
+        );
+    }
 
     let zsh_history = std::fs::read_to_string(runtime_paths.home_dir.join(".zsh_history"))
         .unwrap_or_else(|_| String::new());
-    let collection = crate::snapshot::collect_snapshot_data(
+    let mut collection = crate::snapshot::collect_snapshot_data(
         &config,
         &runtime_paths.home_dir,
         args.source_root.as_deref(),
         &zsh_history,
     )?;
 
+    collection.index.run_id = run_id.map(str::to_string);
     let artifacts = crate::snapshot::build_artifact_layout(&runtime_paths.out_dir);
     crate::snapshot::write_snapshot_artifacts(&artifacts, &collection)?;
     println!(
@@ -51,6 +104,12 @@ pub fn run(args: &SnapshotArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         collection.index.counts.samples_emitted,
         collection.index.counts.warnings
     );
+    if collection.index.filters.active {
+        println!(
+            "snapshot: filtered files_pruned_by_mtime={}",
+            collection.index.filters.files_pruned_by_mtime
+        );
+    }
     println!(
         "snapshot: artifacts index={} samples={} schema_profile={}",
         artifacts.index_json.display(),
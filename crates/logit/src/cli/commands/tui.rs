@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Args;
+
+use crate::config::RuntimePaths;
+use crate::tui::{App, MartReader, load_validation_report};
+
+#[derive(Debug, Clone, Args)]
+pub struct TuiArgs {
+    #[arg(long, value_name = "PATH")]
+    pub mart: Option<PathBuf>,
+
+    #[arg(long, value_name = "PATH")]
+    pub validate_report: Option<PathBuf>,
+}
+
+pub fn build_app(args: &TuiArgs, runtime_paths: &RuntimePaths) -> Result<App> {
+    let mart_path = args
+        .mart
+        .clone()
+        .unwrap_or_else(|| runtime_paths.out_dir.join("mart.sqlite"));
+    let report_path = args.validate_report.clone().unwrap_or_else(|| {
+        crate::validate::build_artifact_layout(&runtime_paths.out_dir).report_json
+    });
+    let reader = MartReader::open(&mart_path)?;
+    let report = load_validation_report(&report_path)?;
+    App::new(reader, report_path, report)
+}
+
+pub fn run(args: &TuiArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let mut app = build_app(args, runtime_paths)?;
+    crate::tui::run(&mut app)
+}
@@ -4,6 +4,7 @@ use anyhow::Result;
 use clap::Args;
 
 use crate::config::RuntimePaths;
+use crate::validate::ValidationReport;
 
 #[derive(Debug, Clone, Args)]
 pub struct ValidateArgs {
@@ -33,6 +34,29 @@ impl std::fmt::Display for ValidationCommandFailure {
 impl std::error::Error for ValidationCommandFailure {}
 
 pub fn run(args: &ValidateArgs, runtime_paths: &RuntimePaths) -> Result<()> {
+    let report = execute(args, runtime_paths, None)?;
+    if report.errors > 0 {
+        let first_issue = report
+            .issues
+            .first()
+            .map(|issue| format!("line {}: {}", issue.line, issue.detail))
+            .filter(|text| !text.is_empty());
+        return Err(ValidationCommandFailure {
+            errors: report.errors,
+            first_issue,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Validates and writes the report, recording `run_id` in it when given.
+/// Validation errors are reported, not returned as `Err`.
+pub fn execute(
+    args: &ValidateArgs,
+    runtime_paths: &RuntimePaths,
+    run_id: Option<&str>,
+) -> Result<ValidationReport> {
     let mode = if args.strict {
         crate::validate::ValidationMode::Strict
     } else {
@@ -45,7 +69,8 @@ pub fn run(args: &ValidateArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         runtime_paths.out_dir.display()
     );
 
-    let report = crate::validate::validate_jsonl_file(args.input.as_path(), mode)?;
+    let mut report = crate::validate::validate_jsonl_file(args.input.as_path(), mode)?;
+    report.run_id = run_id.map(str::to_string);
     let artifacts = crate::validate::build_artifact_layout(&runtime_paths.out_dir);
     crate::validate::write_report_artifact(&artifacts.report_json, &report)?;
     println!(
@@ -63,16 +88,7 @@ pub fn run(args: &ValidateArgs, runtime_paths: &RuntimePaths) -> Result<()> {
             "validate: failed errors={} warnings={} next=inspect_report",
             report.errors, report.warnings
         );
-        let first_issue = report
-            .issues
-            .first()
-            .map(|issue| format!("line {}: {}", issue.line, issue.detail))
-            .filter(|text| !text.is_empty());
-        return Err(ValidationCommandFailure {
-            errors: report.errors,
-            first_issue,
-        }
-        .into());
+        return Ok(report);
     }
 
     println!(
@@ -81,7 +97,7 @@ pub fn run(args: &ValidateArgs, runtime_paths: &RuntimePaths) -> Result<()> {
         artifacts.report_json.display()
     );
 
-    Ok(())
+    Ok(report)
 }
 
 fn validation_mode_key(mode: crate::validate::ValidationMode) -> &'static str {
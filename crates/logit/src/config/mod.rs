@@ -2,6 +2,10 @@ use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Result, bail};
 
+pub mod settings;
+
+pub use settings::{ConfigOrigin, LogitConfig, Setting};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RuntimePaths {
     pub home_dir: PathBuf,
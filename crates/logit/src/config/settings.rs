@@ -0,0 +1,564 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::cli::commands::query::{DEFAULT_SEARCH_ROW_CAP, DEFAULT_SQL_ROW_CAP};
+use crate::discovery::roots::{AdapterRoot, RootOrigin, configured_root};
+use crate::otlp::collector::COLLECTOR_TIMEOUT;
+use crate::pipeline::{DEFAULT_GATES, RunGate};
+use crate::serve::REQUEST_READ_TIMEOUT;
+use crate::utils::fs::write_atomic;
+use crate::utils::host::{derived_host_id, normalize_host_id};
+use crate::utils::redaction::RedactionPolicy;
+
+pub const USER_CONFIG_FILE: &str = ".logit/config.toml";
+pub const PROJECT_CONFIG_FILE: &str = ".logit.toml";
+pub const CONFIG_SCHEMA_FILE: &str = "logit-config.v1.schema.json";
+
+pub const ENV_OUT_DIR: &str = "LOGIT_OUT_DIR";
+pub const ENV_ADAPTERS: &str = "LOGIT_ADAPTERS";
+pub const ENV_TAGS: &str = "LOGIT_TAGS";
+pub const ENV_HOST: &str = "LOGIT_HOST";
+pub const ENV_REDACTION: &str = "LOGIT_REDACTION";
+pub const ENV_PSEUDONYMIZE: &str = "LOGIT_PSEUDONYMIZE";
+pub const ENV_SQL_ROW_CAP: &str = "LOGIT_SQL_ROW_CAP";
+pub const ENV_SEARCH_ROW_CAP: &str = "LOGIT_SEARCH_ROW_CAP";
+pub const ENV_COLLECTOR_TIMEOUT: &str = "LOGIT_COLLECTOR_TIMEOUT_SECS";
+pub const ENV_SERVE_REQUEST_TIMEOUT: &str = "LOGIT_SERVE_REQUEST_TIMEOUT_SECS";
+pub const ENV_RUN_GATES: &str = "LOGIT_RUN_GATES";
+
+/// On-disk shape shared by `~/.logit/config.toml` and project `.logit.toml`
+/// files. Every key is optional; later layers override earlier ones.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[schemars(title = "LogitConfig")]
+pub struct ConfigFile {
+    /// Artifact directory; relative paths resolve against the config file's directory.
+    pub out_dir: Option<String>,
+    /// Adapters enabled for snapshot, normalize and ingest when no `--adapter` is given.
+    pub adapters: Option<Vec<String>>,
+    /// Tags appended to every normalized event.
+    pub tags: Option<Vec<String>>,
+    /// Host id recorded on events and ingest runs; derived from the machine name when unset.
+    pub host: Option<String>,
+    /// Extra adapter roots, added after those in `~/.logit/roots.toml`.
+    #[serde(default)]
+    pub roots: Vec<ConfigRoot>,
+    #[serde(default)]
+    pub redaction: RedactionSection,
+    #[serde(default)]
+    pub query: QuerySection,
+    #[serde(default)]
+    pub timeouts: TimeoutSection,
+    #[serde(default)]
+    pub run: RunSection,
+    /// Per-model token prices in USD per million tokens, keyed by model name.
+    #[serde(default)]
+    pub pricing: BTreeMap<String, ModelPricing>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigRoot {
+    pub adapter: String,
+    pub profile: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RedactionSection {
+    /// One of `off`, `secrets-only` or `secrets+pii`.
+    pub policy: Option<String>,
+    pub pseudonymize: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QuerySection {
+    #[schemars(range(min = 1))]
+    pub sql_row_cap: Option<usize>,
+    #[schemars(range(min = 1))]
+    pub search_row_cap: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutSection {
+    #[schemars(range(min = 1))]
+    pub collector_secs: Option<u64>,
+    #[schemars(range(min = 1))]
+    pub serve_request_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RunSection {
+    /// Gates that stop `logit run`: `validation-errors`, `validation-warnings`,
+    /// `adapter-failed`, `adapter-partial-failure`. An empty list disables gating.
+    pub gates: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ModelPricing {
+    pub input_per_mtok: f64,
+    pub output_per_mtok: f64,
+    pub cached_input_per_mtok: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigOrigin {
+    Default,
+    User(PathBuf),
+    Project(PathBuf),
+    Env(&'static str),
+    Flag(&'static str),
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::User(path) => write!(f, "user:{}", path.display()),
+            Self::Project(path) => write!(f, "project:{}", path.display()),
+            Self::Env(name) => write!(f, "env:{name}"),
+            Self::Flag(name) => write!(f, "flag:{name}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting<T> {
+    pub value: T,
+    pub origin: ConfigOrigin,
+}
+
+impl<T> Setting<T> {
+    fn default_value(value: T) -> Self {
+        Self {
+            value,
+            origin: ConfigOrigin::Default,
+        }
+    }
+
+    fn set(&mut self, value: T, origin: &ConfigOrigin) {
+        self.value = value;
+        self.origin = origin.clone();
+    }
+}
+
+/// Effective settings after merging, in increasing precedence, built-in
+/// defaults, the user config, the nearest project config, `LOGIT_*`
+/// environment variables and command-line flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogitConfig {
+    pub files: Vec<ConfigOrigin>,
+    pub out_dir: Setting<Option<PathBuf>>,
+    pub adapters: Setting<Vec<String>>,
+    pub tags: Setting<Vec<String>>,
+    pub host: Setting<Option<String>>,
+    pub roots: Vec<Setting<AdapterRoot>>,
+    pub redaction_policy: Setting<RedactionPolicy>,
+    pub pseudonymize: Setting<bool>,
+    pub sql_row_cap: Setting<usize>,
+    pub search_row_cap: Setting<usize>,
+    pub collector_timeout_secs: Setting<u64>,
+    pub serve_request_timeout_secs: Setting<u64>,
+    pub run_gates: Setting<Vec<RunGate>>,
+    pub pricing: BTreeMap<String, Setting<ModelPricing>>,
+}
+
+impl Default for LogitConfig {
+    fn default() -> Self {
+        Self {
+            files: Vec::new(),
+            out_dir: Setting::default_value(None),
+            adapters: Setting::default_value(Vec::new()),
+            tags: Setting::default_value(Vec::new()),
+            host: Setting::default_value(None),
+            roots: Vec::new(),
+            redaction_policy: Setting::default_value(RedactionPolicy::default()),
+            pseudonymize: Setting::default_value(false),
+            sql_row_cap: Setting::default_value(DEFAULT_SQL_ROW_CAP),
+            search_row_cap: Setting::default_value(DEFAULT_SEARCH_ROW_CAP),
+            collector_timeout_secs: Setting::default_value(COLLECTOR_TIMEOUT.as_secs()),
+            serve_request_timeout_secs: Setting::default_value(REQUEST_READ_TIMEOUT.as_secs()),
+            run_gates: Setting::default_value(DEFAULT_GATES.to_vec()),
+            pricing: BTreeMap::new(),
+        }
+    }
+}
+
+impl LogitConfig {
+    pub fn load(home_dir: &Path, cwd: &Path) -> Result<Self> {
+        Self::resolve(home_dir, cwd, |name| std::env::var(name).ok())
+    }
+
+    pub fn resolve(
+        home_dir: &Path,
+        cwd: &Path,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let mut config = Self::default();
+        let user_path = user_config_path(home_dir);
+        if user_path.is_file() {
+            config.apply_file(&user_path, ConfigOrigin::User(user_path.clone()))?;
+        }
+        if let Some(project_path) = find_project_config(cwd) {
+            config.apply_file(&project_path, ConfigOrigin::Project(project_path.clone()))?;
+        }
+        config.apply_env(env)?;
+        Ok(config)
+    }
+
+    pub fn apply_out_dir_flag(&mut self, out_dir: Option<&Path>) {
+        if let Some(out_dir) = out_dir {
+            self.out_dir.set(
+                Some(out_dir.to_path_buf()),
+                &ConfigOrigin::Flag("--out-dir"),
+            );
+        }
+    }
+
+    /// The configured host id, or one derived from the machine name.
+    #[must_use]
+    pub fn host_id(&self) -> String {
+        self.host.value.clone().unwrap_or_else(derived_host_id)
+    }
+
+    #[must_use]
+    pub fn root_entries(&self) -> Vec<AdapterRoot> {
+        self.roots.iter().map(|root| root.value.clone()).collect()
+    }
+
+    fn apply_file(&mut self, path: &Path, origin: ConfigOrigin) -> Result<()> {
+        let raw = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file: {}", path.display()))?;
+        let file = parse_config_toml(&raw)
+            .with_context(|| format!("invalid config file: {}", path.display()))?;
+        let base_dir = path.parent().unwrap_or(Path::new("/"));
+        self.merge_file(file, base_dir, &origin)
+            .with_context(|| format!("invalid config file: {}", path.display()))?;
+        self.files.push(origin);
+        Ok(())
+    }
+
+    fn merge_file(
+        &mut self,
+        file: ConfigFile,
+        base_dir: &Path,
+        origin: &ConfigOrigin,
+    ) -> Result<()> {
+        if let Some(out_dir) = file.out_dir {
+            let out_dir = non_empty("out_dir", &out_dir)?;
+            let path = if out_dir.starts_with('~') || Path::new(out_dir).is_absolute() {
+                PathBuf::from(out_dir)
+            } else {
+                base_dir.join(out_dir)
+            };
+            self.out_dir.set(Some(path), origin);
+        }
+        if let Some(adapters) = file.adapters {
+            self.adapters.set(names("adapters", adapters)?, origin);
+        }
+        if let Some(tags) = file.tags {
+            self.tags.set(names("tags", tags)?, origin);
+        }
+        if let Some(host) = file.host {
+            let host = normalize_host_id(&host).context("invalid `host`")?;
+            self.host.set(Some(host), origin);
+        }
+        for root in file.roots {
+            let value =
+                configured_root(&root.adapter, &root.profile, &root.path, RootOrigin::Config)
+                    .context("invalid `roots` entry")?;
+            self.roots.push(Setting {
+                value,
+                origin: origin.clone(),
+            });
+        }
+        if let Some(policy) = file.redaction.policy {
+            let policy = RedactionPolicy::parse(&policy)
+                .map_err(|error| anyhow::anyhow!("redaction.policy: {error}"))?;
+            self.redaction_policy.set(policy, origin);
+        }
+        if let Some(pseudonymize) = file.redaction.pseudonymize {
+            self.pseudonymize.set(pseudonymize, origin);
+        }
+        if let Some(cap) = file.query.sql_row_cap {
+            self.sql_row_cap
+                .set(positive("query.sql_row_cap", cap)?, origin);
+        }
+        if let Some(cap) = file.query.search_row_cap {
+            self.search_row_cap
+                .set(positive("query.search_row_cap", cap)?, origin);
+        }
+        if let Some(secs) = file.timeouts.collector_secs {
+            self.collector_timeout_secs
+                .set(positive("timeouts.collector_secs", secs)?, origin);
+        }
+        if let Some(secs) = file.timeouts.serve_request_secs {
+            self.serve_request_timeout_secs
+                .set(positive("timeouts.serve_request_secs", secs)?, origin);
+        }
+        if let Some(gates) = file.run.gates {
+            self.run_gates
+                .set(parse_gates("run.gates", &gates)?, origin);
+        }
+        for (model, pricing) in file.pricing {
+            for (field, price) in [
+                ("input_per_mtok", Some(pricing.input_per_mtok)),
+                ("output_per_mtok", Some(pricing.output_per_mtok)),
+                ("cached_input_per_mtok", pricing.cached_input_per_mtok),
+            ] {
+                if price.is_some_and(|price| !price.is_finite() || price < 0.0) {
+                    bail!("pricing.\"{model}\".{field} must be a non-negative number");
+                }
+            }
+            self.pricing.insert(
+                model,
+                Setting {
+                    value: pricing,
+                    origin: origin.clone(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    fn apply_env(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<()> {
+        let var = |name: &'static str| {
+            env(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|value| (value, ConfigOrigin::Env(name)))
+        };
+        if let Some((value, origin)) = var(ENV_OUT_DIR) {
+            self.out_dir.set(Some(PathBuf::from(value)), &origin);
+        }
+        if let Some((value, origin)) = var(ENV_ADAPTERS) {
+            self.adapters
+                .set(comma_list(ENV_ADAPTERS, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_TAGS) {
+            self.tags.set(comma_list(ENV_TAGS, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_HOST) {
+            let host = normalize_host_id(&value).with_context(|| format!("invalid {ENV_HOST}"))?;
+            self.host.set(Some(host), &origin);
+        }
+        if let Some((value, origin)) = var(ENV_REDACTION) {
+            let policy = RedactionPolicy::parse(&value)
+                .map_err(|error| anyhow::anyhow!("{ENV_REDACTION}: {error}"))?;
+            self.redaction_policy.set(policy, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_PSEUDONYMIZE) {
+            let enabled = match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => true,
+                "0" | "false" | "no" | "off" => false,
+                _ => bail!("{ENV_PSEUDONYMIZE} must be a boolean (got `{value}`)"),
+            };
+            self.pseudonymize.set(enabled, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_SQL_ROW_CAP) {
+            self.sql_row_cap
+                .set(env_positive(ENV_SQL_ROW_CAP, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_SEARCH_ROW_CAP) {
+            self.search_row_cap
+                .set(env_positive(ENV_SEARCH_ROW_CAP, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_COLLECTOR_TIMEOUT) {
+            self.collector_timeout_secs
+                .set(env_positive(ENV_COLLECTOR_TIMEOUT, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_SERVE_REQUEST_TIMEOUT) {
+            self.serve_request_timeout_secs
+                .set(env_positive(ENV_SERVE_REQUEST_TIMEOUT, &value)?, &origin);
+        }
+        if let Some((value, origin)) = var(ENV_RUN_GATES) {
+            let gates = if value.eq_ignore_ascii_case("none") {
+                Vec::new()
+            } else {
+                parse_gates(ENV_RUN_GATES, &comma_list(ENV_RUN_GATES, &value)?)?
+            };
+            self.run_gates.set(gates, &origin);
+        }
+        Ok(())
+    }
+
+    /// Flattened `key -> {value, origin}` view used by `logit config show`.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(String, Value, String)> {
+        let mut entries = vec![
+            (
+                "out_dir".to_string(),
+                self.out_dir
+                    .value
+                    .as_ref()
+                    .map_or(Value::Null, |path| json!(path.display().to_string())),
+                self.out_dir.origin.to_string(),
+            ),
+            entry("adapters", &self.adapters, json!(self.adapters.value)),
+            entry("tags", &self.tags, json!(self.tags.value)),
+            (
+                "host".to_string(),
+                json!(self.host_id()),
+                match &self.host.value {
+                    Some(_) => self.host.origin.to_string(),
+                    None => "derived".to_string(),
+                },
+            ),
+            entry(
+                "redaction.policy",
+                &self.redaction_policy,
+                json!(self.redaction_policy.value.as_str()),
+            ),
+            entry(
+                "redaction.pseudonymize",
+                &self.pseudonymize,
+                json!(self.pseudonymize.value),
+            ),
+            entry(
+                "query.sql_row_cap",
+                &self.sql_row_cap,
+                json!(self.sql_row_cap.value),
+            ),
+            entry(
+                "query.search_row_cap",
+                &self.search_row_cap,
+                json!(self.search_row_cap.value),
+            ),
+            entry(
+                "timeouts.collector_secs",
+                &self.collector_timeout_secs,
+                json!(self.collector_timeout_secs.value),
+            ),
+            entry(
+                "timeouts.serve_request_secs",
+                &self.serve_request_timeout_secs,
+                json!(self.serve_request_timeout_secs.value),
+            ),
+            entry("run.gates", &self.run_gates, json!(self.run_gates.value)),
+        ];
+        for (index, root) in self.roots.iter().enumerate() {
+            entries.push(entry(
+                &format!("roots[{index}]"),
+                root,
+                json!({
+                    "adapter": root.value.adapter.as_str(),
+                    "profile": root.value.profile,
+                    "path": root.value.path,
+                }),
+            ));
+        }
+        for (model, pricing) in &self.pricing {
+            entries.push(entry(
+                &format!("pricing.\"{model}\""),
+                pricing,
+                json!({
+                    "input_per_mtok": pricing.value.input_per_mtok,
+                    "output_per_mtok": pricing.value.output_per_mtok,
+                    "cached_input_per_mtok": pricing.value.cached_input_per_mtok,
+                }),
+            ));
+        }
+        entries
+    }
+}
+
+fn entry<T>(key: &str, setting: &Setting<T>, value: Value) -> (String, Value, String) {
+    (key.to_string(), value, setting.origin.to_string())
+}
+
+#[must_use]
+pub fn user_config_path(home_dir: &Path) -> PathBuf {
+    home_dir.join(USER_CONFIG_FILE)
+}
+
+/// Nearest `.logit.toml` in `cwd` or one of its ancestors.
+#[must_use]
+pub fn find_project_config(cwd: &Path) -> Option<PathBuf> {
+    cwd.ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+pub fn parse_config_toml(input: &str) -> Result<ConfigFile> {
+    toml::from_str::<ConfigFile>(input).context("config file must be valid TOML")
+}
+
+#[must_use]
+pub fn config_json_schema() -> Value {
+    let schema = schemars::schema_for!(ConfigFile);
+    serde_json::to_value(schema).unwrap_or_else(|error| {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "LogitConfig",
+            "type": "object",
+            "x_schema_error": format!("failed to serialize generated config schema: {error}")
+        })
+    })
+}
+
+pub fn write_config_schema_artifact(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("failed to create schema artifact directory")?;
+    }
+    let encoded = serde_json::to_vec_pretty(&config_json_schema())
+        .context("failed to encode config schema json")?;
+    write_atomic(path, &encoded).context("failed to write config schema artifact")
+}
+
+fn non_empty<'a>(key: &str, value: &'a str) -> Result<&'a str> {
+    let value = value.trim();
+    if value.is_empty() {
+        bail!("{key} must not be empty");
+    }
+    Ok(value)
+}
+
+fn names(key: &str, values: Vec<String>) -> Result<Vec<String>> {
+    values
+        .iter()
+        .map(|value| match value.trim() {
+            "" => bail!("{key} entries must be non-empty strings"),
+            trimmed => Ok(trimmed.to_string()),
+        })
+        .collect()
+}
+
+fn parse_gates(key: &str, values: &[String]) -> Result<Vec<RunGate>> {
+    values
+        .iter()
+        .map(|value| RunGate::parse(value).map_err(|error| anyhow::anyhow!("{key}: {error}")))
+        .collect()
+}
+
+fn comma_list(name: &str, value: &str) -> Result<Vec<String>> {
+    names(name, value.split(',').map(str::to_string).collect())
+}
+
+fn positive<T: PartialEq + Default + Copy>(key: &str, value: T) -> Result<T> {
+    if value == T::default() {
+        bail!("{key} must be greater than zero");
+    }
+    Ok(value)
+}
+
+fn env_positive<T>(name: &str, value: &str) -> Result<T>
+where
+    T: std::str::FromStr + PartialEq + Default + Copy,
+{
+    let parsed = value
+        .parse::<T>()
+        .ok()
+        .with_context(|| format!("{name} must be a positive integer (got `{value}`)"))?;
+    positive(name, parsed)
+}
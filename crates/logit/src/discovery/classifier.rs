@@ -1,11 +1,135 @@
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+
+use crate::adapters::AdapterKind;
+use crate::utils::vscdb;
 
 pub use super::SourceClassification;
 use super::{SourceFormatHint, classify_source};
 
 const MAX_CLASSIFY_BYTES: usize = 256 * 1024;
+const MAX_FINGERPRINT_RECORDS: usize = 64;
+
+const CODEX_ROLLOUT_RECORD_TYPES: &[&str] = &[
+    "session_meta",
+    "response_item",
+    "event_msg",
+    "turn_context",
+    "compacted",
+];
+
+/// Parser-level fingerprint of a single source file, detected from content so
+/// files outside the known-path registry can be routed to the right parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ContentFingerprint {
+    CodexRollout,
+    CodexHistory,
+    ClaudeSession,
+    GeminiChat,
+    GeminiLogs,
+    GeminiProtobuf,
+    AmpThread,
+    AmpFileChange,
+    OpenCodeMessages,
+    OpenCodeParts,
+    AiderChatHistory,
+    AiderInputHistory,
+    AiderLlmHistory,
+    CursorStateDb,
+    CopilotStateDb,
+    CopilotChatSession,
+}
+
+impl ContentFingerprint {
+    #[must_use]
+    pub const fn adapter(self) -> AdapterKind {
+        match self {
+            Self::CodexRollout | Self::CodexHistory => AdapterKind::Codex,
+            Self::ClaudeSession => AdapterKind::Claude,
+            Self::GeminiChat | Self::GeminiLogs | Self::GeminiProtobuf => AdapterKind::Gemini,
+            Self::AmpThread | Self::AmpFileChange => AdapterKind::Amp,
+            Self::OpenCodeMessages | Self::OpenCodeParts => AdapterKind::OpenCode,
+            Self::AiderChatHistory | Self::AiderInputHistory | Self::AiderLlmHistory => {
+                AdapterKind::Aider
+            }
+            Self::CursorStateDb => AdapterKind::Cursor,
+            Self::CopilotStateDb | Self::CopilotChatSession => AdapterKind::Copilot,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::CodexRollout => "codex_rollout",
+            Self::CodexHistory => "codex_history",
+            Self::ClaudeSession => "claude_session",
+            Self::GeminiChat => "gemini_chat",
+            Self::GeminiLogs => "gemini_logs",
+            Self::GeminiProtobuf => "gemini_protobuf",
+            Self::AmpThread => "amp_thread",
+            Self::AmpFileChange => "amp_file_change",
+            Self::OpenCodeMessages => "opencode_messages",
+            Self::OpenCodeParts => "opencode_parts",
+            Self::AiderChatHistory => "aider_chat_history",
+            Self::AiderInputHistory => "aider_input_history",
+            Self::AiderLlmHistory => "aider_llm_history",
+            Self::CursorStateDb => "cursor_state_db",
+            Self::CopilotStateDb => "copilot_state_db",
+            Self::CopilotChatSession => "copilot_chat_session",
+        }
+    }
+
+    /// Parser used when the caller forces an adapter for a file whose content
+    /// did not match any of that adapter's fingerprints.
+    #[must_use]
+    pub fn default_for(adapter: AdapterKind, path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(str::to_ascii_lowercase);
+        match adapter {
+            AdapterKind::Codex => Self::CodexRollout,
+            AdapterKind::Claude => Self::ClaudeSession,
+            AdapterKind::Gemini if extension.as_deref() == Some("pb") => Self::GeminiProtobuf,
+            AdapterKind::Gemini => Self::GeminiChat,
+            AdapterKind::Amp => Self::AmpFileChange,
+            AdapterKind::OpenCode => Self::OpenCodeParts,
+            AdapterKind::Aider => Self::AiderChatHistory,
+            AdapterKind::Cursor => Self::CursorStateDb,
+            AdapterKind::Copilot if extension.as_deref() == Some("json") => {
+                Self::CopilotChatSession
+            }
+            AdapterKind::Copilot => Self::CopilotStateDb,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdapterDetection {
+    Detected(ContentFingerprint),
+    Ambiguous(Vec<ContentFingerprint>),
+    Unknown,
+}
+
+impl AdapterDetection {
+    /// Applies an explicit adapter choice: a matching fingerprint wins,
+    /// otherwise the adapter's default parser is used.
+    #[must_use]
+    pub fn resolve_with_override(&self, adapter: AdapterKind, path: &Path) -> ContentFingerprint {
+        let candidates = match self {
+            Self::Detected(fingerprint) => std::slice::from_ref(fingerprint),
+            Self::Ambiguous(fingerprints) => fingerprints.as_slice(),
+            Self::Unknown => &[],
+        };
+        candidates
+            .iter()
+            .copied()
+            .find(|fingerprint| fingerprint.adapter() == adapter)
+            .unwrap_or_else(|| ContentFingerprint::default_for(adapter, path))
+    }
+}
 
 #[must_use]
 pub const fn classify_from_hint(hint: SourceFormatHint) -> Option<SourceClassification> {
@@ -14,6 +138,7 @@ pub const fn classify_from_hint(hint: SourceFormatHint) -> Option<SourceClassifi
         SourceFormatHint::Json => Some(SourceClassification::Json),
         SourceFormatHint::Jsonl => Some(SourceClassification::Jsonl),
         SourceFormatHint::TextLog => Some(SourceClassification::TextLog),
+        SourceFormatHint::Sqlite => Some(SourceClassification::Sqlite),
     }
 }
 
@@ -28,3 +153,212 @@ pub fn classify_file(path: &Path) -> Result<SourceClassification> {
     let sample_end = data.len().min(MAX_CLASSIFY_BYTES);
     Ok(classify_bytes(path, &data[..sample_end]))
 }
+
+/// Detects the adapter and parser for a file from its content. SQLite state
+/// databases cannot be told apart by bytes alone; see [`detect_adapter_file`].
+#[must_use]
+pub fn detect_adapter_bytes(path: &Path, bytes: &[u8]) -> AdapterDetection {
+    let sample_end = bytes.len().min(MAX_CLASSIFY_BYTES);
+    match classify_bytes(path, &bytes[..sample_end]) {
+        SourceClassification::Sqlite => AdapterDetection::Unknown,
+        SourceClassification::Binary => {
+            let is_protobuf = path
+                .extension()
+                .and_then(std::ffi::OsStr::to_str)
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("pb"));
+            if is_protobuf {
+                AdapterDetection::Detected(ContentFingerprint::GeminiProtobuf)
+            } else {
+                AdapterDetection::Unknown
+            }
+        }
+        SourceClassification::Json
+        | SourceClassification::Jsonl
+        | SourceClassification::TextLog => {
+            let Ok(text) = std::str::from_utf8(bytes) else {
+                return AdapterDetection::Unknown;
+            };
+            detect_text(text)
+        }
+    }
+}
+
+pub fn detect_adapter_file(path: &Path) -> Result<AdapterDetection> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("failed to read source file for detection: {path:?}"))?;
+    if vscdb::has_sqlite_magic(&data) {
+        return detect_state_db(path);
+    }
+    Ok(detect_adapter_bytes(path, &data))
+}
+
+fn detect_state_db(path: &Path) -> Result<AdapterDetection> {
+    let connection = vscdb::open_state_db(path)?;
+    if vscdb::table_exists(&connection, vscdb::CURSOR_DISK_KV_TABLE)? {
+        return Ok(AdapterDetection::Detected(
+            ContentFingerprint::CursorStateDb,
+        ));
+    }
+    if !vscdb::table_exists(&connection, vscdb::ITEM_TABLE)? {
+        return Ok(AdapterDetection::Unknown);
+    }
+    let has_copilot_sessions = connection
+        .query_row(
+            &format!(
+                "SELECT EXISTS(SELECT 1 FROM {} WHERE key = ?1)",
+                vscdb::ITEM_TABLE
+            ),
+            [crate::adapters::copilot::INTERACTIVE_SESSIONS_KEY],
+            |row| row.get::<usize, i64>(0),
+        )
+        .with_context(|| format!("failed to probe state database: {}", path.display()))?;
+    Ok(if has_copilot_sessions == 1 {
+        AdapterDetection::Detected(ContentFingerprint::CopilotStateDb)
+    } else {
+        AdapterDetection::Ambiguous(vec![
+            ContentFingerprint::CursorStateDb,
+            ContentFingerprint::CopilotStateDb,
+        ])
+    })
+}
+
+fn detect_text(text: &str) -> AdapterDetection {
+    // A one-record JSONL file also parses as a JSON document, so fall through
+    // to record fingerprints when the document shape is not recognised.
+    if let Some(fingerprint) = serde_json::from_str::<Value>(text)
+        .ok()
+        .and_then(|document| json_document_fingerprint(&document))
+    {
+        return AdapterDetection::Detected(fingerprint);
+    }
+
+    let mut votes = Vec::new();
+    for line in text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(MAX_FINGERPRINT_RECORDS)
+    {
+        if let Ok(Value::Object(record)) = serde_json::from_str::<Value>(line)
+            && let Some(fingerprint) = jsonl_record_fingerprint(&record)
+        {
+            votes.push(fingerprint);
+        }
+    }
+    if votes.is_empty() {
+        return match aider_text_fingerprint(text) {
+            Some(fingerprint) => AdapterDetection::Detected(fingerprint),
+            None => AdapterDetection::Unknown,
+        };
+    }
+    votes.sort();
+    votes.dedup();
+    if let [fingerprint] = votes.as_slice() {
+        AdapterDetection::Detected(*fingerprint)
+    } else {
+        AdapterDetection::Ambiguous(votes)
+    }
+}
+
+fn jsonl_record_fingerprint(record: &Map<String, Value>) -> Option<ContentFingerprint> {
+    let has = |key: &str| record.contains_key(key);
+    let record_type = record.get("type").and_then(Value::as_str);
+
+    if record_type.is_some_and(|kind| CODEX_ROLLOUT_RECORD_TYPES.contains(&kind)) && has("payload")
+    {
+        return Some(ContentFingerprint::CodexRollout);
+    }
+    if record.get("source").and_then(Value::as_str) == Some("codex_history")
+        || (has("session_id") && has("ts") && has("text") && record.len() == 3)
+    {
+        return Some(ContentFingerprint::CodexHistory);
+    }
+    if has("parentUuid") || (has("sessionId") && has("uuid")) {
+        return Some(ContentFingerprint::ClaudeSession);
+    }
+    if has("sessionID") && has("partID") {
+        return Some(ContentFingerprint::OpenCodeParts);
+    }
+    if has("sessionID") && has("messageID") {
+        return Some(ContentFingerprint::OpenCodeMessages);
+    }
+    // Flattened export shapes used by the fixture corpus.
+    if has("session_id") && has("event_type") {
+        return Some(ContentFingerprint::CodexRollout);
+    }
+    if has("project_id") && has("session_id") && has("kind") {
+        return Some(ContentFingerprint::ClaudeSession);
+    }
+    None
+}
+
+fn json_document_fingerprint(document: &Value) -> Option<ContentFingerprint> {
+    match document {
+        Value::Object(root) => {
+            let has = |key: &str| root.contains_key(key);
+            let has_messages = root.get("messages").is_some_and(Value::is_array);
+            if has("thread_id") && has_messages {
+                return Some(ContentFingerprint::AmpThread);
+            }
+            if has_messages
+                && (has("sessionId")
+                    || has("session_id")
+                    || has("conversation_id")
+                    || has("projectHash"))
+            {
+                return Some(ContentFingerprint::GeminiChat);
+            }
+            if has("sessionId") && root.get("requests").is_some_and(Value::is_array) {
+                return Some(ContentFingerprint::CopilotChatSession);
+            }
+            is_amp_file_change(root).then_some(ContentFingerprint::AmpFileChange)
+        }
+        Value::Array(items) => {
+            let objects = items
+                .iter()
+                .filter_map(Value::as_object)
+                .collect::<Vec<_>>();
+            if objects.is_empty() {
+                return None;
+            }
+            if objects
+                .iter()
+                .all(|item| item.contains_key("sessionId") && item.contains_key("messageId"))
+            {
+                return Some(ContentFingerprint::GeminiLogs);
+            }
+            objects
+                .iter()
+                .all(|item| is_amp_file_change(item))
+                .then_some(ContentFingerprint::AmpFileChange)
+        }
+        _ => None,
+    }
+}
+
+fn is_amp_file_change(object: &Map<String, Value>) -> bool {
+    let has_change = ["diff", "before", "after"]
+        .iter()
+        .any(|key| object.contains_key(*key));
+    let has_target = ["uri", "path", "file", "file_path"]
+        .iter()
+        .any(|key| object.contains_key(*key));
+    has_change && has_target
+}
+
+fn aider_text_fingerprint(text: &str) -> Option<ContentFingerprint> {
+    let first = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if first.starts_with("# aider chat started at") {
+        return Some(ContentFingerprint::AiderChatHistory);
+    }
+    if first.starts_with("TO LLM ") || first.starts_with("LLM RESPONSE ") {
+        return Some(ContentFingerprint::AiderLlmHistory);
+    }
+    let second = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .nth(1)?;
+    (first.starts_with("# ") && second.starts_with('+'))
+        .then_some(ContentFingerprint::AiderInputHistory)
+}
@@ -4,8 +4,12 @@ use anyhow::{Context, Result};
 use serde::Serialize;
 
 pub mod classifier;
+pub mod roots;
 
 use crate::adapters::{AdapterKind, all_adapter_kinds, default_paths};
+use crate::utils::fs::write_atomic;
+
+use self::roots::{DEFAULT_PROFILE, SourceRoots};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DiscoveryPathRole {
@@ -21,6 +25,7 @@ pub enum SourceFormatHint {
     Json,
     Jsonl,
     TextLog,
+    Sqlite,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +33,7 @@ pub enum SourceClassification {
     Jsonl,
     Json,
     TextLog,
+    Sqlite,
     Binary,
 }
 
@@ -60,9 +66,204 @@ pub struct SourceSelectionFilter {
     pub path_substrings: Vec<String>,
 }
 
+/// Inclusive `--since`/`--until` window over `timestamp_unix_ms`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TimeWindow {
+    pub since_unix_ms: Option<u64>,
+    pub until_unix_ms: Option<u64>,
+}
+
+impl TimeWindow {
+    #[must_use]
+    pub const fn is_bounded(self) -> bool {
+        self.since_unix_ms.is_some() || self.until_unix_ms.is_some()
+    }
+
+    #[must_use]
+    pub fn contains(self, timestamp_unix_ms: u64) -> bool {
+        self.since_unix_ms
+            .is_none_or(|since| timestamp_unix_ms >= since)
+            && self
+                .until_unix_ms
+                .is_none_or(|until| timestamp_unix_ms <= until)
+    }
+
+    /// Only `since` prunes files: a file last modified before it cannot hold
+    /// newer events, while a recently modified file may still hold old ones.
+    #[must_use]
+    pub fn admits_file(self, path: &Path) -> bool {
+        let Some(since) = self.since_unix_ms else {
+            return true;
+        };
+        let modified_unix_ms = std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .and_then(|age| u64::try_from(age.as_millis()).ok());
+        modified_unix_ms.is_none_or(|modified| modified >= since)
+    }
+}
+
+/// Source and time filters shared by `snapshot`, `normalize` and `ingest`.
+/// Adapter names stay strings so custom adapters and mart `adapter_name`
+/// values can be selected alongside the built-in kinds.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PipelineFilters {
+    pub adapters: Vec<String>,
+    pub format_hints: Vec<SourceFormatHint>,
+    pub path_substrings: Vec<String>,
+    pub window: TimeWindow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
+pub struct PipelineFilterRecord {
+    pub active: bool,
+    pub adapters: Vec<String>,
+    pub formats: Vec<String>,
+    pub path_substrings: Vec<String>,
+    pub since_utc: Option<String>,
+    pub until_utc: Option<String>,
+    pub files_pruned_by_mtime: usize,
+    pub events_outside_window: usize,
+    pub events_tombstoned: usize,
+}
+
+impl PipelineFilters {
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        !self.adapters.is_empty()
+            || !self.format_hints.is_empty()
+            || !self.path_substrings.is_empty()
+            || self.window.is_bounded()
+    }
+
+    #[must_use]
+    pub fn admits_adapter_name(&self, name: &str) -> bool {
+        self.adapters.is_empty()
+            || self
+                .adapters
+                .iter()
+                .any(|adapter| adapter.eq_ignore_ascii_case(name))
+    }
+
+    #[must_use]
+    pub fn admits_format(&self, hint: SourceFormatHint) -> bool {
+        self.format_hints.is_empty() || self.format_hints.contains(&hint)
+    }
+
+    #[must_use]
+    pub fn admits_path(&self, path: &str) -> bool {
+        let needles = self
+            .path_substrings
+            .iter()
+            .map(|needle| needle.to_ascii_lowercase())
+            .collect::<Vec<_>>();
+        matches_path_filter(path, &needles)
+    }
+
+    /// Built-in adapters selected by the filter; names that are not built-in
+    /// are returned separately so callers can match custom adapters or reject
+    /// them.
+    #[must_use]
+    pub fn builtin_adapters(&self) -> (Vec<AdapterKind>, Vec<String>) {
+        if self.adapters.is_empty() {
+            return (all_adapter_kinds().to_vec(), Vec::new());
+        }
+        let mut builtin = Vec::new();
+        let mut other = Vec::new();
+        for name in &self.adapters {
+            match AdapterKind::parse(name) {
+                Some(kind) if !builtin.contains(&kind) => builtin.push(kind),
+                Some(_) => {}
+                None => other.push(name.clone()),
+            }
+        }
+        (builtin, other)
+    }
+
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let record = self.record();
+        let list = |values: &[String]| {
+            if values.is_empty() {
+                "*".to_string()
+            } else {
+                values.join(",")
+            }
+        };
+        format!(
+            "adapters={} formats={} path_contains={} since={} until={}",
+            list(&record.adapters),
+            list(&record.formats),
+            list(&record.path_substrings),
+            record.since_utc.as_deref().unwrap_or("-"),
+            record.until_utc.as_deref().unwrap_or("-")
+        )
+    }
+
+    #[must_use]
+    pub fn record(&self) -> PipelineFilterRecord {
+        PipelineFilterRecord {
+            active: self.is_active(),
+            adapters: self.adapters.clone(),
+            formats: self
+                .format_hints
+                .iter()
+                .map(|hint| hint.as_str().to_string())
+                .collect(),
+            path_substrings: self.path_substrings.clone(),
+            since_utc: self
+                .window
+                .since_unix_ms
+                .map(crate::utils::time::format_unix_ms),
+            until_utc: self
+                .window
+                .until_unix_ms
+                .map(crate::utils::time::format_unix_ms),
+            files_pruned_by_mtime: 0,
+            events_outside_window: 0,
+            events_tombstoned: 0,
+        }
+    }
+}
+
+impl SourceFormatHint {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        source_format_hint_key(self)
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "directory" | "dir" => Ok(Self::Directory),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::Jsonl),
+            "text_log" | "text-log" | "log" => Ok(Self::TextLog),
+            "sqlite" => Ok(Self::Sqlite),
+            other => Err(format!(
+                "unknown source format `{other}` (expected directory, json, jsonl, text_log or sqlite)"
+            )),
+        }
+    }
+
+    /// Best-effort hint for an already-normalized `source_path`.
+    #[must_use]
+    pub fn from_source_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            "json" => Some(Self::Json),
+            "sqlite" | "sqlite3" | "db" | "vscdb" => Some(Self::Sqlite),
+            "log" | "txt" | "md" => Some(Self::TextLog),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PrioritizedSource {
     pub adapter: AdapterKind,
+    pub profile: String,
     pub path: String,
     pub role: DiscoveryPathRole,
     pub format_hint: SourceFormatHint,
@@ -80,6 +281,7 @@ pub struct DiscoveryArtifactLayout {
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct DiscoverySourceEvidence {
     pub adapter: String,
+    pub profile: String,
     pub path: String,
     pub role: String,
     pub format_hint: String,
@@ -234,6 +436,92 @@ const OPENCODE_CANDIDATES: [DiscoveryCandidate; 3] = [
     },
 ];
 
+const AIDER_CANDIDATES: [DiscoveryCandidate; 3] = [
+    DiscoveryCandidate {
+        precedence: 10,
+        path: ".aider.chat.history.md",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::TextLog,
+        recursive: true,
+    },
+    DiscoveryCandidate {
+        precedence: 20,
+        path: ".aider.input.history",
+        role: DiscoveryPathRole::HistoryStream,
+        format_hint: SourceFormatHint::TextLog,
+        recursive: true,
+    },
+    DiscoveryCandidate {
+        precedence: 30,
+        path: ".aider.llm.history",
+        role: DiscoveryPathRole::RuntimeDiagnostics,
+        format_hint: SourceFormatHint::TextLog,
+        recursive: true,
+    },
+];
+
+const CURSOR_CANDIDATES: [DiscoveryCandidate; 4] = [
+    DiscoveryCandidate {
+        precedence: 10,
+        path: "~/.config/Cursor/User/globalStorage/state.vscdb",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Sqlite,
+        recursive: false,
+    },
+    DiscoveryCandidate {
+        precedence: 20,
+        path: "~/.config/Cursor/User/workspaceStorage",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Directory,
+        recursive: true,
+    },
+    DiscoveryCandidate {
+        precedence: 30,
+        path: "~/Library/Application Support/Cursor/User/globalStorage/state.vscdb",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Sqlite,
+        recursive: false,
+    },
+    DiscoveryCandidate {
+        precedence: 40,
+        path: "~/Library/Application Support/Cursor/User/workspaceStorage",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Directory,
+        recursive: true,
+    },
+];
+
+const COPILOT_CANDIDATES: [DiscoveryCandidate; 4] = [
+    DiscoveryCandidate {
+        precedence: 10,
+        path: "~/.config/Code/User/workspaceStorage",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Directory,
+        recursive: true,
+    },
+    DiscoveryCandidate {
+        precedence: 20,
+        path: "~/.config/Code/User/globalStorage/state.vscdb",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Sqlite,
+        recursive: false,
+    },
+    DiscoveryCandidate {
+        precedence: 30,
+        path: "~/Library/Application Support/Code/User/workspaceStorage",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Directory,
+        recursive: true,
+    },
+    DiscoveryCandidate {
+        precedence: 40,
+        path: "~/Library/Application Support/Code/User/globalStorage/state.vscdb",
+        role: DiscoveryPathRole::SessionStore,
+        format_hint: SourceFormatHint::Sqlite,
+        recursive: false,
+    },
+];
+
 #[must_use]
 pub const fn known_path_candidates(adapter: AdapterKind) -> &'static [DiscoveryCandidate] {
     match adapter {
@@ -242,11 +530,18 @@ pub const fn known_path_candidates(adapter: AdapterKind) -> &'static [DiscoveryC
         AdapterKind::Gemini => &GEMINI_CANDIDATES,
         AdapterKind::Amp => &AMP_CANDIDATES,
         AdapterKind::OpenCode => &OPENCODE_CANDIDATES,
+        AdapterKind::Aider => &AIDER_CANDIDATES,
+        AdapterKind::Cursor => &CURSOR_CANDIDATES,
+        AdapterKind::Copilot => &COPILOT_CANDIDATES,
     }
 }
 
 #[must_use]
 pub fn classify_source(path: &Path, sample: &[u8]) -> SourceClassification {
+    if crate::utils::vscdb::has_sqlite_magic(sample) {
+        return SourceClassification::Sqlite;
+    }
+
     if sample.contains(&0) {
         return SourceClassification::Binary;
     }
@@ -341,6 +636,7 @@ pub fn build_sources_artifact(
         .iter()
         .map(|source| DiscoverySourceEvidence {
             adapter: adapter_sort_key(source.adapter).to_string(),
+            profile: source.profile.clone(),
             path: source.path.clone(),
             role: discovery_path_role_key(source.role).to_string(),
             format_hint: source_format_hint_key(source.format_hint).to_string(),
@@ -355,6 +651,7 @@ pub fn build_sources_artifact(
             .cmp(&left.history_score)
             .then_with(|| left.precedence.cmp(&right.precedence))
             .then_with(|| left.adapter.cmp(&right.adapter))
+            .then_with(|| left.profile.cmp(&right.profile))
             .then_with(|| left.path.cmp(&right.path))
     });
 
@@ -417,7 +714,7 @@ pub fn write_sources_artifact(path: &Path, artifact: &DiscoverySourcesArtifact)
 
     let encoded = serde_json::to_vec_pretty(artifact)
         .context("failed to encode discovery sources artifact")?;
-    std::fs::write(path, encoded).with_context(|| {
+    write_atomic(path, &encoded).with_context(|| {
         format!(
             "failed to write discovery sources artifact: {}",
             path.display()
@@ -440,7 +737,7 @@ pub fn write_zsh_history_usage_artifact(
 
     let encoded = serde_json::to_vec_pretty(artifact)
         .context("failed to encode discovery history usage artifact")?;
-    std::fs::write(path, encoded).with_context(|| {
+    write_atomic(path, &encoded).with_context(|| {
         format!(
             "failed to write discovery history usage artifact: {}",
             path.display()
@@ -453,6 +750,19 @@ pub fn prioritize_sources(
     rules: &[DiscoveryRule],
     history_scores: &[HistoryScore],
     filter: &SourceSelectionFilter,
+) -> Vec<PrioritizedSource> {
+    prioritize_sources_with_roots(rules, history_scores, filter, &SourceRoots::default())
+}
+
+/// Expands each registry candidate under every root of its adapter. Candidates
+/// outside the adapter's built-in root (for example `~/.claude.json` or macOS
+/// application-support paths) are emitted once for the default profile.
+#[must_use]
+pub fn prioritize_sources_with_roots(
+    rules: &[DiscoveryRule],
+    history_scores: &[HistoryScore],
+    filter: &SourceSelectionFilter,
+    roots: &SourceRoots,
 ) -> Vec<PrioritizedSource> {
     let normalized_needles = filter
         .path_substrings
@@ -472,19 +782,35 @@ pub fn prioritize_sources(
                 continue;
             }
 
-            if !matches_path_filter(candidate.path, &normalized_needles) {
-                continue;
+            let mut placements = roots
+                .for_adapter(rule.adapter)
+                .filter_map(|root| {
+                    root.rebase(candidate.path)
+                        .map(|path| (root.profile.clone(), path))
+                })
+                .collect::<Vec<_>>();
+            if placements.is_empty() {
+                placements.push((DEFAULT_PROFILE.to_string(), candidate.path.to_string()));
+            }
+            for (profile, path) in placements {
+                if !matches_path_filter(&path, &normalized_needles)
+                    || prioritized.iter().any(|existing: &PrioritizedSource| {
+                        existing.adapter == rule.adapter && existing.path == path
+                    })
+                {
+                    continue;
+                }
+                prioritized.push(PrioritizedSource {
+                    adapter: rule.adapter,
+                    profile,
+                    path,
+                    role: candidate.role,
+                    format_hint: candidate.format_hint,
+                    recursive: candidate.recursive,
+                    precedence: candidate.precedence,
+                    history_score,
+                });
             }
-
-            prioritized.push(PrioritizedSource {
-                adapter: rule.adapter,
-                path: candidate.path.to_string(),
-                role: candidate.role,
-                format_hint: candidate.format_hint,
-                recursive: candidate.recursive,
-                precedence: candidate.precedence,
-                history_score,
-            });
         }
     }
 
@@ -494,6 +820,7 @@ pub fn prioritize_sources(
             .cmp(&left.history_score)
             .then_with(|| left.precedence.cmp(&right.precedence))
             .then_with(|| adapter_sort_key(left.adapter).cmp(adapter_sort_key(right.adapter)))
+            .then_with(|| left.profile.cmp(&right.profile))
             .then_with(|| left.path.cmp(&right.path))
     });
     prioritized
@@ -532,6 +859,9 @@ fn adapter_sort_key(adapter: AdapterKind) -> &'static str {
         AdapterKind::Gemini => "gemini",
         AdapterKind::Amp => "amp",
         AdapterKind::OpenCode => "opencode",
+        AdapterKind::Aider => "aider",
+        AdapterKind::Cursor => "cursor",
+        AdapterKind::Copilot => "copilot",
     }
 }
 
@@ -550,6 +880,7 @@ const fn source_format_hint_key(hint: SourceFormatHint) -> &'static str {
         SourceFormatHint::Json => "json",
         SourceFormatHint::Jsonl => "jsonl",
         SourceFormatHint::TextLog => "text_log",
+        SourceFormatHint::Sqlite => "sqlite",
     }
 }
 
@@ -0,0 +1,262 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use crate::adapters::{AdapterKind, all_adapter_kinds};
+
+pub const ROOTS_FILE: &str = ".logit/roots.toml";
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootOrigin {
+    Builtin,
+    Env,
+    Xdg,
+    Config,
+}
+
+impl RootOrigin {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Builtin => "builtin",
+            Self::Env => "env",
+            Self::Xdg => "xdg",
+            Self::Config => "config",
+        }
+    }
+}
+
+/// One directory an adapter keeps its state under, tagged with the profile
+/// that discovered sources and normalized events are attributed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdapterRoot {
+    pub adapter: AdapterKind,
+    pub profile: String,
+    pub path: String,
+    pub origin: RootOrigin,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceRoots {
+    pub roots: Vec<AdapterRoot>,
+}
+
+impl Default for SourceRoots {
+    fn default() -> Self {
+        Self::resolve(Path::new("/"), |_| None, Vec::new())
+    }
+}
+
+impl SourceRoots {
+    /// Built-in roots adjusted by agent environment overrides and XDG base
+    /// directories, followed by any configured profile roots.
+    pub fn resolve(
+        home_dir: &Path,
+        env: impl Fn(&str) -> Option<String>,
+        configured: Vec<AdapterRoot>,
+    ) -> Self {
+        let env_path = |name: &str| {
+            env(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+                .map(|value| home_relative(&value, home_dir))
+        };
+        let xdg_config = env_path("XDG_CONFIG_HOME");
+        let xdg_data = env_path("XDG_DATA_HOME").unwrap_or_else(|| "~/.local/share".to_string());
+
+        let mut roots = Vec::new();
+        for adapter in all_adapter_kinds() {
+            let Some(prefix) = builtin_root_prefix(adapter) else {
+                continue;
+            };
+            let (path, origin) = match (adapter, root_env_var(adapter).and_then(env_path)) {
+                (_, Some(path)) => (path, RootOrigin::Env),
+                (AdapterKind::Cursor | AdapterKind::Copilot, None) => match &xdg_config {
+                    Some(config) => (
+                        format!("{config}/{}", vscode_family_dir(adapter)),
+                        RootOrigin::Xdg,
+                    ),
+                    None => (prefix.to_string(), RootOrigin::Builtin),
+                },
+                (_, None) => (prefix.to_string(), RootOrigin::Builtin),
+            };
+            roots.push(AdapterRoot {
+                adapter,
+                profile: DEFAULT_PROFILE.to_string(),
+                path,
+                origin,
+            });
+            if adapter == AdapterKind::OpenCode {
+                roots.push(AdapterRoot {
+                    adapter,
+                    profile: DEFAULT_PROFILE.to_string(),
+                    path: format!("{xdg_data}/opencode"),
+                    origin: RootOrigin::Xdg,
+                });
+            }
+        }
+        for root in configured {
+            let path = home_relative(&root.path, home_dir);
+            roots.push(AdapterRoot { path, ..root });
+        }
+        Self { roots }
+    }
+
+    pub fn load(home_dir: &Path) -> Result<Self> {
+        Self::load_with(home_dir, Vec::new())
+    }
+
+    /// Like [`SourceRoots::load`], with `extra` roots (from `config.toml` /
+    /// `.logit.toml`) appended after the ones in `roots.toml`.
+    pub fn load_with(home_dir: &Path, extra: Vec<AdapterRoot>) -> Result<Self> {
+        let path = roots_path(home_dir);
+        let mut configured = if path.is_file() {
+            let raw = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read roots file: {}", path.display()))?;
+            parse_roots_toml(&raw)
+                .with_context(|| format!("invalid roots file: {}", path.display()))?
+        } else {
+            Vec::new()
+        };
+        configured.extend(extra);
+        Ok(Self::resolve(
+            home_dir,
+            |name| std::env::var(name).ok(),
+            configured,
+        ))
+    }
+
+    pub fn for_adapter(&self, adapter: AdapterKind) -> impl Iterator<Item = &AdapterRoot> {
+        self.roots
+            .iter()
+            .filter(move |root| root.adapter == adapter)
+    }
+}
+
+impl AdapterRoot {
+    /// Re-expresses a registry candidate under this root, or `None` when the
+    /// candidate does not live under the adapter's built-in root.
+    #[must_use]
+    pub fn rebase(&self, candidate: &str) -> Option<String> {
+        let prefix = builtin_root_prefix(self.adapter)?;
+        let rest = candidate.strip_prefix(prefix)?;
+        if !rest.is_empty() && !rest.starts_with('/') {
+            return None;
+        }
+        Some(format!("{}{rest}", self.path.trim_end_matches('/')))
+    }
+}
+
+#[must_use]
+pub fn roots_path(home_dir: &Path) -> PathBuf {
+    home_dir.join(ROOTS_FILE)
+}
+
+#[must_use]
+pub const fn builtin_root_prefix(adapter: AdapterKind) -> Option<&'static str> {
+    match adapter {
+        AdapterKind::Codex => Some("~/.codex"),
+        AdapterKind::Claude => Some("~/.claude"),
+        AdapterKind::Gemini => Some("~/.gemini"),
+        AdapterKind::Amp => Some("~/.amp"),
+        AdapterKind::OpenCode => Some("~/.opencode"),
+        AdapterKind::Aider => None,
+        AdapterKind::Cursor => Some("~/.config/Cursor"),
+        AdapterKind::Copilot => Some("~/.config/Code"),
+    }
+}
+
+/// Environment variable that relocates the adapter's built-in root, if any.
+#[must_use]
+pub const fn root_env_var(adapter: AdapterKind) -> Option<&'static str> {
+    match adapter {
+        AdapterKind::Codex => Some("CODEX_HOME"),
+        AdapterKind::Claude => Some("CLAUDE_CONFIG_DIR"),
+        _ => None,
+    }
+}
+
+const fn vscode_family_dir(adapter: AdapterKind) -> &'static str {
+    match adapter {
+        AdapterKind::Cursor => "Cursor",
+        _ => "Code",
+    }
+}
+
+/// Keeps paths under the home directory in `~/` form so `--source-root`
+/// re-rooting applies to them like it does to built-in candidates.
+fn home_relative(path: &str, home_dir: &Path) -> String {
+    if path.starts_with('~') {
+        return path.to_string();
+    }
+    match Path::new(path).strip_prefix(home_dir) {
+        Ok(rest) if home_dir != Path::new("/") => {
+            if rest.as_os_str().is_empty() {
+                "~".to_string()
+            } else {
+                format!("~/{}", rest.display())
+            }
+        }
+        _ => path.to_string(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RootsFile {
+    #[serde(default)]
+    roots: Vec<RootSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RootSpec {
+    adapter: String,
+    profile: String,
+    path: String,
+}
+
+pub fn parse_roots_toml(input: &str) -> Result<Vec<AdapterRoot>> {
+    let file = toml::from_str::<RootsFile>(input).context("roots file must be valid TOML")?;
+    file.roots
+        .iter()
+        .map(|spec| configured_root(&spec.adapter, &spec.profile, &spec.path, RootOrigin::Config))
+        .collect()
+}
+
+pub fn configured_root(
+    adapter: &str,
+    profile: &str,
+    path: &str,
+    origin: RootOrigin,
+) -> Result<AdapterRoot> {
+    let Some(adapter) = AdapterKind::parse(adapter) else {
+        bail!("roots entry names unknown adapter `{adapter}`");
+    };
+    if builtin_root_prefix(adapter).is_none() {
+        bail!(
+            "adapter `{}` discovers repository files and does not take extra roots",
+            adapter.as_str()
+        );
+    }
+    if !is_valid_profile_name(profile) {
+        bail!("roots entry profile `{profile}` must be a slug ([a-z0-9][a-z0-9_-]*)");
+    }
+    if path.trim().is_empty() {
+        bail!("roots entry for profile `{profile}` has an empty path");
+    }
+    Ok(AdapterRoot {
+        adapter,
+        profile: profile.to_string(),
+        path: path.trim().to_string(),
+        origin,
+    })
+}
+
+fn is_valid_profile_name(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some('a'..='z' | '0'..='9'))
+        && chars.all(|ch| matches!(ch, 'a'..='z' | '0'..='9' | '_' | '-'))
+}
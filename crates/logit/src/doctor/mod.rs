@@ -0,0 +1,766 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use crate::adapters::{AdapterKind, all_adapter_kinds};
+use crate::discovery::roots::{SourceRoots, root_env_var};
+use crate::discovery::{PrioritizedSource, SourceSelectionFilter};
+use crate::sqlite::migrations::migration_status;
+use crate::sqlite::{
+    INGEST_RUNS_TABLE, INGEST_WATERMARKS_TABLE, SCHEMA_META_TABLE, SQLITE_SCHEMA_VERSION,
+};
+use crate::utils::time::format_unix_ms;
+
+pub const DOCTOR_SCHEMA_VERSION: &str = "logit.doctor.v1";
+pub const OUT_DIR_PROBE_FILE: &str = ".logit-doctor-probe";
+const PARITY_MISMATCH_SAMPLE: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skip,
+}
+
+impl CheckStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Warn => "warn",
+            Self::Fail => "fail",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DoctorCheck {
+    pub id: String,
+    pub status: CheckStatus,
+    pub summary: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+    pub details: Value,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DoctorCounts {
+    pub pass: usize,
+    pub warn: usize,
+    pub fail: usize,
+    pub skip: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DoctorReport {
+    pub schema_version: String,
+    pub counts: DoctorCounts,
+    pub checks: Vec<DoctorCheck>,
+}
+
+impl DoctorReport {
+    #[must_use]
+    pub fn check(&self, id: &str) -> Option<&DoctorCheck> {
+        self.checks.iter().find(|check| check.id == id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DoctorPlan {
+    pub home_dir: PathBuf,
+    pub cwd: PathBuf,
+    pub source_root: Option<PathBuf>,
+    pub out_dir: PathBuf,
+    /// Adapters to probe; empty means every built-in adapter.
+    pub adapters: Vec<AdapterKind>,
+    pub source_roots: SourceRoots,
+    /// This machine's host id; the parity check leaves out other hosts' rows.
+    pub host: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CandidateProbe {
+    pub profile: String,
+    pub path: String,
+    pub resolved_path: String,
+    pub exists: bool,
+    pub readable: bool,
+    pub files: usize,
+    pub newest_mtime_utc: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Runs every check in checklist order: discovery per adapter, shell history,
+/// out-dir writability, then the mart checks, which are skipped when
+/// `mart.sqlite` is absent.
+#[must_use]
+pub fn run_checks(plan: &DoctorPlan) -> DoctorReport {
+    let adapters = if plan.adapters.is_empty() {
+        all_adapter_kinds().to_vec()
+    } else {
+        plan.adapters.clone()
+    };
+    let mut checks = adapters
+        .into_iter()
+        .map(|adapter| check_discovery(plan, adapter))
+        .collect::<Vec<_>>();
+    checks.push(check_zsh_history(&plan.home_dir));
+    checks.push(check_out_dir(&plan.out_dir));
+    checks.extend(check_mart(&plan.out_dir, &plan.host));
+
+    let mut counts = DoctorCounts::default();
+    for check in &checks {
+        match check.status {
+            CheckStatus::Pass => counts.pass += 1,
+            CheckStatus::Warn => counts.warn += 1,
+            CheckStatus::Fail => counts.fail += 1,
+            CheckStatus::Skip => counts.skip += 1,
+        }
+    }
+    DoctorReport {
+        schema_version: DOCTOR_SCHEMA_VERSION.to_string(),
+        counts,
+        checks,
+    }
+}
+
+fn check(
+    id: impl Into<String>,
+    status: CheckStatus,
+    summary: impl Into<String>,
+    remediation: Option<String>,
+    details: Value,
+) -> DoctorCheck {
+    DoctorCheck {
+        id: id.into(),
+        status,
+        summary: summary.into(),
+        remediation,
+        details,
+    }
+}
+
+fn check_discovery(plan: &DoctorPlan, adapter: AdapterKind) -> DoctorCheck {
+    let id = format!("discovery.{}", adapter.as_str());
+    let filter = SourceSelectionFilter {
+        adapters: vec![adapter],
+        ..SourceSelectionFilter::default()
+    };
+    let sources = crate::discovery::prioritize_sources_with_roots(
+        &crate::discovery::known_path_registry(),
+        &[],
+        &filter,
+        &plan.source_roots,
+    );
+    let candidates = sources
+        .iter()
+        .map(|source| probe_candidate(plan, source))
+        .collect::<Vec<_>>();
+    let files = candidates
+        .iter()
+        .map(|candidate| candidate.files)
+        .sum::<usize>();
+    let newest = candidates
+        .iter()
+        .filter_map(|candidate| candidate.newest_mtime_utc.clone())
+        .max();
+    let details = json!({
+        "adapter": adapter.as_str(),
+        "files": files,
+        "newest_mtime_utc": newest,
+        "candidates": candidates,
+    });
+
+    let unreadable = candidates
+        .iter()
+        .filter(|candidate| candidate.exists && !candidate.readable)
+        .map(|candidate| candidate.resolved_path.as_str())
+        .collect::<Vec<_>>();
+    if !unreadable.is_empty() {
+        return check(
+            id,
+            CheckStatus::Fail,
+            format!("{} candidate path(s) unreadable", unreadable.len()),
+            Some(format!(
+                "grant read access to {} or point logit elsewhere with --source-root",
+                unreadable.join(", ")
+            )),
+            details,
+        );
+    }
+    let existing = candidates
+        .iter()
+        .filter(|candidate| candidate.exists)
+        .count();
+    if existing == 0 {
+        let env_hint = root_env_var(adapter)
+            .map(|name| format!(", set {name}"))
+            .unwrap_or_default();
+        return check(
+            id,
+            CheckStatus::Skip,
+            format!("none of {} candidate path(s) exist", candidates.len()),
+            Some(format!(
+                "if {} logs live elsewhere, pass --source-root{env_hint} or add a [[roots]] entry for `{}`",
+                adapter.as_str(),
+                adapter.as_str()
+            )),
+            details,
+        );
+    }
+    if files == 0 {
+        return check(
+            id,
+            CheckStatus::Warn,
+            format!("{existing} candidate path(s) exist but hold no parseable files"),
+            Some(format!(
+                "use `{}` once so it writes logs, or check that the files use a supported extension",
+                adapter.as_str()
+            )),
+            details,
+        );
+    }
+    check(
+        id,
+        CheckStatus::Pass,
+        format!(
+            "{files} file(s) across {existing} readable path(s), newest {}",
+            newest.as_deref().unwrap_or("unknown")
+        ),
+        None,
+        details,
+    )
+}
+
+fn probe_candidate(plan: &DoctorPlan, source: &PrioritizedSource) -> CandidateProbe {
+    let resolved = if source.adapter == AdapterKind::Aider {
+        plan.source_root
+            .as_deref()
+            .unwrap_or(&plan.cwd)
+            .to_path_buf()
+    } else {
+        crate::normalize::resolve_candidate_path(
+            &source.path,
+            &plan.home_dir,
+            plan.source_root.as_deref(),
+        )
+    };
+    let mut probe = CandidateProbe {
+        profile: source.profile.clone(),
+        path: source.path.clone(),
+        resolved_path: resolved.display().to_string(),
+        exists: resolved.exists(),
+        readable: false,
+        files: 0,
+        newest_mtime_utc: None,
+        error: None,
+    };
+    if !probe.exists {
+        return probe;
+    }
+    let readable = if resolved.is_dir() {
+        std::fs::read_dir(&resolved).map(|_| ())
+    } else {
+        std::fs::File::open(&resolved).map(|_| ())
+    };
+    if let Err(error) = readable {
+        probe.error = Some(error.to_string());
+        return probe;
+    }
+    match crate::normalize::collect_parseable_files_resolved(&resolved, source) {
+        Ok(files) => {
+            probe.readable = true;
+            probe.files = files.len();
+            probe.newest_mtime_utc = files
+                .iter()
+                .filter_map(|file| modified_unix_ms(file))
+                .max()
+                .map(format_unix_ms);
+        }
+        Err(error) => probe.error = Some(format!("{error:#}")),
+    }
+    probe
+}
+
+fn modified_unix_ms(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let elapsed = modified.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(elapsed.as_millis()).ok()
+}
+
+fn check_zsh_history(home_dir: &Path) -> DoctorCheck {
+    let id = "history.zsh";
+    let path = home_dir.join(".zsh_history");
+    let remediation = Some(
+        "without shell history, discovery falls back to registry precedence; point HISTFILE at ~/.zsh_history to enable history-informed ordering"
+            .to_string(),
+    );
+    if !path.is_file() {
+        return check(
+            id,
+            CheckStatus::Warn,
+            "~/.zsh_history not found",
+            remediation,
+            json!({ "path": path.display().to_string() }),
+        );
+    }
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            return check(
+                id,
+                CheckStatus::Fail,
+                format!("~/.zsh_history unreadable: {error}"),
+                Some(format!("grant read access to {}", path.display())),
+                json!({ "path": path.display().to_string() }),
+            );
+        }
+    };
+    let Ok(raw) = String::from_utf8(bytes) else {
+        return check(
+            id,
+            CheckStatus::Fail,
+            "~/.zsh_history is not valid UTF-8, so normalize ignores it",
+            Some(
+                "zsh metafies non-ASCII bytes; re-export the history with `fc -ln 0 > file` or remove the offending lines"
+                    .to_string(),
+            ),
+            json!({ "path": path.display().to_string() }),
+        );
+    };
+    let entries = crate::utils::history::parse_zsh_history(&raw).len();
+    let scores = crate::discovery::zsh_history_scores(&raw)
+        .into_iter()
+        .filter(|score| score.score > 0)
+        .map(|score| (score.adapter.as_str().to_string(), score.score))
+        .collect::<BTreeMap<_, _>>();
+    let details = json!({
+        "path": path.display().to_string(),
+        "entries": entries,
+        "adapter_command_hits": scores,
+    });
+    if entries == 0 {
+        return check(
+            id,
+            CheckStatus::Warn,
+            "~/.zsh_history parsed but holds no entries",
+            remediation,
+            details,
+        );
+    }
+    check(
+        id,
+        CheckStatus::Pass,
+        format!(
+            "{entries} entries parsed, {} adapter(s) seen in commands",
+            scores.len()
+        ),
+        None,
+        details,
+    )
+}
+
+fn check_out_dir(out_dir: &Path) -> DoctorCheck {
+    let id = "out_dir.writable";
+    let details = json!({ "path": out_dir.display().to_string() });
+    let probe = out_dir.join(OUT_DIR_PROBE_FILE);
+    let result = std::fs::create_dir_all(out_dir)
+        .and_then(|()| std::fs::write(&probe, b"logit doctor\n"))
+        .and_then(|()| std::fs::remove_file(&probe));
+    match result {
+        Ok(()) => check(id, CheckStatus::Pass, "out dir is writable", None, details),
+        Err(error) => check(
+            id,
+            CheckStatus::Fail,
+            format!("out dir is not writable: {error}"),
+            Some(
+                "pass --out-dir <PATH> (or set out_dir / LOGIT_OUT_DIR) to a writable directory"
+                    .to_string(),
+            ),
+            details,
+        ),
+    }
+}
+
+fn check_mart(out_dir: &Path, host: &str) -> Vec<DoctorCheck> {
+    const MART_CHECKS: [&str; 5] = [
+        "mart.schema_version",
+        "mart.integrity",
+        "ingest.last_run",
+        "ingest.watermarks",
+        "mart.parity",
+    ];
+    let sqlite_path = out_dir.join("mart.sqlite");
+    let details = json!({ "path": sqlite_path.display().to_string() });
+    let skip_rest = |summary: &str| {
+        MART_CHECKS
+            .iter()
+            .map(|id| check(*id, CheckStatus::Skip, summary, None, json!({})))
+            .collect::<Vec<_>>()
+    };
+    if !sqlite_path.is_file() {
+        let mut checks = vec![check(
+            "mart.present",
+            CheckStatus::Warn,
+            "mart.sqlite not found",
+            Some("run `logit normalize` then `logit ingest refresh` (or `logit run`)".to_string()),
+            details,
+        )];
+        checks.extend(skip_rest("mart.sqlite not found"));
+        return checks;
+    }
+    let connection = match crate::sqlite::open_sqlite_connection_read_only(&sqlite_path) {
+        Ok(connection) => connection,
+        Err(error) => {
+            let mut checks = vec![check(
+                "mart.present",
+                CheckStatus::Fail,
+                format!("mart.sqlite cannot be opened: {error:#}"),
+                Some(rebuild_hint()),
+                details,
+            )];
+            checks.extend(skip_rest("mart.sqlite cannot be opened"));
+            return checks;
+        }
+    };
+    vec![
+        check(
+            "mart.present",
+            CheckStatus::Pass,
+            "mart.sqlite found",
+            None,
+            details,
+        ),
+        check_schema_version(&connection),
+        check_integrity(&connection),
+        check_last_ingest_run(&connection),
+        check_watermarks(&connection),
+        check_parity(out_dir, &sqlite_path, host),
+    ]
+}
+
+fn rebuild_hint() -> String {
+    "move mart.sqlite aside and rebuild it with `logit ingest refresh`".to_string()
+}
+
+fn query_failed(id: &str, what: &str, error: &anyhow::Error) -> DoctorCheck {
+    check(
+        id,
+        CheckStatus::Fail,
+        format!("failed to read {what}: {error:#}"),
+        Some(rebuild_hint()),
+        json!({}),
+    )
+}
+
+fn check_schema_version(connection: &Connection) -> DoctorCheck {
+    let id = "mart.schema_version";
+    let versions = (|| -> Result<Vec<String>> {
+        let mut statement = connection.prepare(&format!(
+            "SELECT schema_version FROM {SCHEMA_META_TABLE} ORDER BY applied_at_utc"
+        ))?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    })();
+    let versions = match versions.context("sqlite schema metadata") {
+        Ok(versions) => versions,
+        Err(error) => return query_failed(id, "schema metadata", &error),
+    };
+    let status = match migration_status(connection) {
+        Ok(status) => status,
+        Err(error) => return query_failed(id, "migration status", &error),
+    };
+    let details = json!({
+        "expected": SQLITE_SCHEMA_VERSION,
+        "recorded": versions,
+        "migration_version": status.current_version,
+        "latest_migration_version": status.latest_version,
+        "pending_migrations": status.pending,
+        "stale_views": status.stale_views,
+    });
+    if !versions
+        .iter()
+        .any(|version| version == SQLITE_SCHEMA_VERSION)
+    {
+        check(
+            id,
+            CheckStatus::Fail,
+            format!(
+                "schema {} does not match {SQLITE_SCHEMA_VERSION}",
+                versions.last().map_or("<none>", String::as_str)
+            ),
+            Some(
+                "run `logit ingest refresh` with this build to apply the current schema"
+                    .to_string(),
+            ),
+            details,
+        )
+    } else if !status.is_current() {
+        check(
+            id,
+            CheckStatus::Fail,
+            format!(
+                "mart is at migration {} of {} with {} stale view(s)",
+                status.current_version,
+                status.latest_version,
+                status.stale_views.len()
+            ),
+            Some("run `logit mart migrate` (add `--dry-run` to preview)".to_string()),
+            details,
+        )
+    } else {
+        check(
+            id,
+            CheckStatus::Pass,
+            format!(
+                "schema {SQLITE_SCHEMA_VERSION} at migration {}",
+                status.current_version
+            ),
+            None,
+            details,
+        )
+    }
+}
+
+fn check_integrity(connection: &Connection) -> DoctorCheck {
+    let id = "mart.integrity";
+    let results = (|| -> Result<Vec<String>> {
+        let mut statement = connection.prepare("PRAGMA integrity_check")?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(Into::into)
+    })();
+    let results = match results {
+        Ok(results) => results,
+        Err(error) => return query_failed(id, "integrity check", &error),
+    };
+    if results == ["ok"] {
+        check(
+            id,
+            CheckStatus::Pass,
+            "integrity_check ok",
+            None,
+            json!({ "results": results }),
+        )
+    } else {
+        check(
+            id,
+            CheckStatus::Fail,
+            format!("integrity_check reported {} problem(s)", results.len()),
+            Some(rebuild_hint()),
+            json!({ "results": results }),
+        )
+    }
+}
+
+fn check_last_ingest_run(connection: &Connection) -> DoctorCheck {
+    let id = "ingest.last_run";
+    let row = connection
+        .query_row(
+            &format!(
+                "SELECT ingest_run_id, status, started_at_utc, finished_at_utc, events_read, events_written, errors_count
+                 FROM {INGEST_RUNS_TABLE}
+                 ORDER BY started_at_utc DESC, ingest_run_id DESC
+                 LIMIT 1"
+            ),
+            [],
+            |row| {
+                Ok(json!({
+                    "ingest_run_id": row.get::<_, String>(0)?,
+                    "status": row.get::<_, String>(1)?,
+                    "started_at_utc": row.get::<_, String>(2)?,
+                    "finished_at_utc": row.get::<_, Option<String>>(3)?,
+                    "events_read": row.get::<_, i64>(4)?,
+                    "events_written": row.get::<_, i64>(5)?,
+                    "errors_count": row.get::<_, i64>(6)?,
+                }))
+            },
+        )
+        .optional()
+        .context("ingest runs");
+    let details = match row {
+        Ok(Some(details)) => details,
+        Ok(None) => {
+            return check(
+                id,
+                CheckStatus::Warn,
+                "no ingest runs recorded",
+                Some("run `logit ingest refresh`".to_string()),
+                json!({}),
+            );
+        }
+        Err(error) => return query_failed(id, "ingest runs", &error),
+    };
+    let status = details["status"].as_str().unwrap_or_default().to_string();
+    let summary = format!(
+        "last run {} {status} at {}",
+        details["ingest_run_id"].as_str().unwrap_or_default(),
+        details["started_at_utc"].as_str().unwrap_or_default()
+    );
+    match status.as_str() {
+        "success" => check(id, CheckStatus::Pass, summary, None, details),
+        "partial_failure" => check(
+            id,
+            CheckStatus::Warn,
+            summary,
+            Some(
+                "read warnings in ingest/report.json, fix the offending events.jsonl rows and re-run `logit ingest refresh`"
+                    .to_string(),
+            ),
+            details,
+        ),
+        _ => check(
+            id,
+            CheckStatus::Fail,
+            summary,
+            Some(
+                "re-run `logit ingest refresh` and read the error envelope; a `running` status means the previous refresh was interrupted"
+                    .to_string(),
+            ),
+            details,
+        ),
+    }
+}
+
+fn check_watermarks(connection: &Connection) -> DoctorCheck {
+    let id = "ingest.watermarks";
+    let states = (|| -> Result<BTreeMap<String, i64>> {
+        let mut statement = connection.prepare(&format!(
+            "SELECT staleness_state, COUNT(*) FROM {INGEST_WATERMARKS_TABLE} GROUP BY staleness_state"
+        ))?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        rows.collect::<rusqlite::Result<BTreeMap<_, _>>>()
+            .map_err(Into::into)
+    })();
+    let states = match states {
+        Ok(states) => states,
+        Err(error) => return query_failed(id, "ingest watermarks", &error),
+    };
+    let last_refreshed = connection
+        .query_row(
+            &format!("SELECT MAX(refreshed_at_utc) FROM {INGEST_WATERMARKS_TABLE}"),
+            [],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .unwrap_or(None);
+    let total = states.values().sum::<i64>();
+    let stale = states.get("stale").copied().unwrap_or(0);
+    let details = json!({
+        "sources": total,
+        "states": states,
+        "last_refreshed_at_utc": last_refreshed,
+    });
+    if total == 0 {
+        return check(
+            id,
+            CheckStatus::Warn,
+            "no source watermarks recorded",
+            Some("run `logit ingest refresh`".to_string()),
+            details,
+        );
+    }
+    if stale > 0 {
+        return check(
+            id,
+            CheckStatus::Warn,
+            format!("{stale} of {total} source(s) stale"),
+            Some(
+                "run `logit normalize` and `logit ingest refresh` to bring stale sources up to date"
+                    .to_string(),
+            ),
+            details,
+        );
+    }
+    check(
+        id,
+        CheckStatus::Pass,
+        format!(
+            "{total} source(s) tracked, last refreshed {}",
+            last_refreshed.as_deref().unwrap_or("unknown")
+        ),
+        None,
+        details,
+    )
+}
+
+fn check_parity(out_dir: &Path, sqlite_path: &Path, host: &str) -> DoctorCheck {
+    let id = "mart.parity";
+    let events_jsonl = crate::normalize::build_artifact_layout(out_dir).events_jsonl;
+    if !events_jsonl.is_file() {
+        return check(
+            id,
+            CheckStatus::Skip,
+            "events.jsonl not found",
+            None,
+            json!({ "events_jsonl": events_jsonl.display().to_string() }),
+        );
+    }
+    let report =
+        match crate::sqlite::verify_jsonl_sqlite_parity(&events_jsonl, sqlite_path, Some(host)) {
+            Ok(report) => report,
+            Err(error) => return query_failed(id, "JSONL/SQLite parity", &error),
+        };
+    let details = json!({
+        "events_jsonl": events_jsonl.display().to_string(),
+        "jsonl_records": report.jsonl_records,
+        "sqlite_records": report.sqlite_records,
+        "compared_records": report.compared_records,
+        "host": host,
+        "other_host_records": report.other_host_records,
+        "mismatches": report.mismatches.len(),
+        "mismatch_sample": report
+            .mismatches
+            .iter()
+            .take(PARITY_MISMATCH_SAMPLE)
+            .map(|mismatch| json!({
+                "event_id": mismatch.event_id,
+                "field": mismatch.field,
+                "detail": mismatch.detail,
+            }))
+            .collect::<Vec<_>>(),
+    });
+    if report.mismatches.is_empty() {
+        check(
+            id,
+            CheckStatus::Pass,
+            if report.other_host_records == 0 {
+                format!("{} record(s) match events.jsonl", report.compared_records)
+            } else {
+                format!(
+                    "{} record(s) match events.jsonl; {} from other hosts not compared",
+                    report.compared_records, report.other_host_records
+                )
+            },
+            None,
+            details,
+        )
+    } else {
+        check(
+            id,
+            CheckStatus::Fail,
+            format!(
+                "{} mismatch(es) between events.jsonl ({} records) and mart ({} records)",
+                report.mismatches.len(),
+                report.jsonl_records,
+                report.sqlite_records
+            ),
+            Some(
+                "re-run `logit ingest refresh` without source filters so the mart mirrors events.jsonl"
+                    .to_string(),
+            ),
+            details,
+        )
+    }
+}
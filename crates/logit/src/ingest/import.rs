@@ -0,0 +1,456 @@
+//! Merges `events.jsonl` files and marts collected on other machines into the
+//! local mart.
+//!
+//! Every imported event carries a host (its own `metadata.host`, or the one
+//! given for the import). Rows are matched on `event_id` first and
+//! `canonical_hash` second, so importing the same artifact twice changes
+//! nothing:
+//!
+//! - same `event_id` and `canonical_hash`: already present, skipped;
+//! - same `event_id`, different hash, same host: a newer version, replaced;
+//! - same `event_id`, different hash, different host: an unrelated event that
+//!   collides, stored under `<host>/<event_id>`, with references to it from
+//!   the same import rewritten to match;
+//! - new `event_id` whose `canonical_hash` is already stored: skipped.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::{
+    IngestRunStatus, build_ingest_run_id, fail_abandoned_ingest_runs, finalize_ingest_run,
+    insert_ingest_run_started, now_utc_rfc3339, parse_events_jsonl, upsert_source_watermarks,
+};
+use crate::models::AgentLogEvent;
+use crate::purge::{EventKey, PurgeSelector, matches_any};
+use crate::sqlite::migrations::{latest_version, migration_status};
+use crate::sqlite::{
+    EVENTS_TABLE, SqliteWriterConfig, open_sqlite_connection, open_sqlite_connection_read_only,
+    read_events, write_events_batched,
+};
+use crate::utils::host::{event_host, normalize_host_id, stamp_host};
+use crate::validate::{ValidationIssue, ValidationMode, validate_jsonl_against_generated_schema};
+
+pub const IMPORT_INVALID_ERROR_CODE: &str = "ingest_import_invalid";
+pub const IMPORT_HOST_MISSING_ERROR_CODE: &str = "ingest_import_host_missing";
+const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+const MAX_REPORTED_ISSUES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    EventsJsonl,
+    Mart,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestImportPlan {
+    pub input_path: PathBuf,
+    pub sqlite_path: PathBuf,
+    /// Host assigned to imported events that do not name one.
+    pub host: Option<String>,
+    pub run_id: Option<String>,
+    /// Selectors from `purge/tombstones.json`; matching events are not merged.
+    pub tombstones: Vec<PurgeSelector>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportHostCounts {
+    pub read: usize,
+    pub inserted: usize,
+    pub replaced: usize,
+    pub rekeyed: usize,
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct IngestImportReport {
+    pub ingest_run_id: String,
+    pub input_path: String,
+    pub format: ImportFormat,
+    pub status: IngestRunStatus,
+    pub started_at_utc: String,
+    pub finished_at_utc: String,
+    pub duration_ms: u64,
+    pub events_read: usize,
+    pub events_tombstoned: usize,
+    pub events_inserted: usize,
+    pub events_replaced: usize,
+    pub events_rekeyed: usize,
+    pub events_skipped: usize,
+    pub validation_warnings: usize,
+    pub hosts: BTreeMap<String, ImportHostCounts>,
+    pub watermarks_upserted: usize,
+    pub watermark_staleness_state: String,
+    pub recovered_runs: Vec<String>,
+}
+
+/// The foreign artifact has baseline validation errors; nothing was imported.
+#[derive(Debug, Clone)]
+pub struct ImportValidationFailure {
+    pub input_path: PathBuf,
+    pub errors: usize,
+    pub warnings: usize,
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl fmt::Display for ImportValidationFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "import input failed validation with {} error(s): {}",
+            self.errors,
+            self.input_path.display()
+        )
+    }
+}
+
+impl std::error::Error for ImportValidationFailure {}
+
+/// Some imported events name no host and none was given for the import.
+#[derive(Debug, Clone)]
+pub struct ImportHostMissing {
+    pub events_without_host: usize,
+}
+
+impl fmt::Display for ImportHostMissing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} imported event(s) have no host; pass --host to say which machine they came from",
+            self.events_without_host
+        )
+    }
+}
+
+impl std::error::Error for ImportHostMissing {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeDecision {
+    Insert,
+    Replace,
+    Rekey,
+    Skip,
+}
+
+pub fn run_import(plan: &IngestImportPlan) -> Result<IngestImportReport> {
+    let started_at_utc = now_utc_rfc3339()?;
+    let started_at = std::time::Instant::now();
+    let ingest_run_id = plan.run_id.clone().unwrap_or_else(build_ingest_run_id);
+    let input_path = plan.input_path.to_string_lossy().to_string();
+    let host = plan.host.as_deref().map(normalize_host_id).transpose()?;
+
+    let format = detect_import_format(&plan.input_path)?;
+    let (mut events, validation_warnings) = read_import_events(&plan.input_path, format)?;
+    let events_read = events.len();
+    events.retain(|event| !matches_any(&plan.tombstones, &EventKey::from_event(event)));
+    let events_tombstoned = events_read - events.len();
+    let events_without_host = events
+        .iter()
+        .filter(|event| event_host(event).is_none())
+        .count();
+    match &host {
+        Some(host) => events.iter_mut().for_each(|event| stamp_host(event, host)),
+        None if events_without_host > 0 => {
+            return Err(ImportHostMissing {
+                events_without_host,
+            }
+            .into());
+        }
+        None => {}
+    }
+    let imported_hosts = events
+        .iter()
+        .filter_map(|event| event_host(event).map(ToOwned::to_owned))
+        .collect::<BTreeSet<_>>();
+    let run_host = host.clone().or_else(|| {
+        (imported_hosts.len() == 1)
+            .then(|| imported_hosts.first().cloned())
+            .flatten()
+    });
+
+    let mut connection = open_sqlite_connection(&plan.sqlite_path)?;
+    crate::sqlite::ensure_sqlite_schema(&connection)?;
+    let recovered_runs = fail_abandoned_ingest_runs(&connection, &ingest_run_id, &started_at_utc)?;
+    insert_ingest_run_started(
+        &connection,
+        &ingest_run_id,
+        &started_at_utc,
+        &input_path,
+        events.len(),
+        validation_warnings,
+        None,
+        "{}",
+        run_host.as_deref(),
+    )?;
+
+    let (to_write, hosts) = plan_merge(&connection, &mut events)?;
+    let write_stats =
+        match write_events_batched(&mut connection, &to_write, SqliteWriterConfig::default()) {
+            Ok(write_stats) => write_stats,
+            Err(error) => {
+                let finished_at_utc = now_utc_rfc3339()?;
+                let error_summary = json!({ "message": format!("{error:#}") }).to_string();
+                let _ = finalize_ingest_run(
+                    &connection,
+                    &ingest_run_id,
+                    IngestRunStatus::Failed,
+                    &finished_at_utc,
+                    events.len(),
+                    0,
+                    validation_warnings,
+                    1,
+                    &error_summary,
+                );
+                return Err(error).context("failed to write imported rows to sqlite mart");
+            }
+        };
+
+    let finished_at_utc = now_utc_rfc3339()?;
+    let owned_hosts = imported_hosts.into_iter().map(Some).collect();
+    let watermark_outcome = upsert_source_watermarks(
+        &connection,
+        &ingest_run_id,
+        &finished_at_utc,
+        &events,
+        true,
+        &owned_hosts,
+    )?;
+    finalize_ingest_run(
+        &connection,
+        &ingest_run_id,
+        IngestRunStatus::Success,
+        &finished_at_utc,
+        events.len(),
+        write_stats.records_written,
+        validation_warnings,
+        0,
+        "{}",
+    )?;
+
+    let total = |count: fn(&ImportHostCounts) -> usize| hosts.values().map(count).sum();
+    Ok(IngestImportReport {
+        ingest_run_id,
+        input_path,
+        format,
+        status: IngestRunStatus::Success,
+        started_at_utc,
+        finished_at_utc,
+        duration_ms: started_at.elapsed().as_millis() as u64,
+        events_read,
+        events_tombstoned,
+        events_inserted: total(|counts| counts.inserted),
+        events_replaced: total(|counts| counts.replaced),
+        events_rekeyed: total(|counts| counts.rekeyed),
+        events_skipped: total(|counts| counts.skipped),
+        validation_warnings,
+        hosts,
+        watermarks_upserted: watermark_outcome.sources_upserted,
+        watermark_staleness_state: watermark_outcome.staleness_state,
+        recovered_runs,
+    })
+}
+
+/// A mart is recognized by the SQLite file header; anything else is read as
+/// `events.jsonl`.
+pub fn detect_import_format(path: &Path) -> Result<ImportFormat> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open import input: {}", path.display()))?;
+    let mut header = [0u8; 16];
+    let read = file
+        .read(&mut header)
+        .with_context(|| format!("failed to read import input: {}", path.display()))?;
+    Ok(if header[..read] == *SQLITE_HEADER {
+        ImportFormat::Mart
+    } else {
+        ImportFormat::EventsJsonl
+    })
+}
+
+/// Reads and validates the foreign events, returning them with the number of
+/// validation warnings. Any validation error rejects the whole artifact.
+fn read_import_events(path: &Path, format: ImportFormat) -> Result<(Vec<AgentLogEvent>, usize)> {
+    let input = match format {
+        ImportFormat::EventsJsonl => std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read import input: {}", path.display()))?,
+        ImportFormat::Mart => {
+            let events = read_foreign_mart(path)?;
+            let mut jsonl = String::new();
+            for event in &events {
+                jsonl.push_str(
+                    &serde_json::to_string(event).context("failed to encode imported event")?,
+                );
+                jsonl.push('\n');
+            }
+            jsonl
+        }
+    };
+
+    let report = validate_jsonl_against_generated_schema(&input, ValidationMode::Baseline);
+    if report.errors > 0 {
+        return Err(ImportValidationFailure {
+            input_path: path.to_path_buf(),
+            errors: report.errors,
+            warnings: report.warnings,
+            issues: report
+                .issues
+                .into_iter()
+                .take(MAX_REPORTED_ISSUES)
+                .collect(),
+        }
+        .into());
+    }
+    let (events, _) = parse_events_jsonl(&input, true)?;
+    Ok((events, report.warnings))
+}
+
+fn read_foreign_mart(path: &Path) -> Result<Vec<AgentLogEvent>> {
+    let connection = open_sqlite_connection_read_only(path)?;
+    let has_events = connection
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            [EVENTS_TABLE],
+            |row| row.get::<usize, i64>(0),
+        )
+        .context("failed to inspect import mart")?;
+    if has_events == 0 {
+        bail!(
+            "import input is a sqlite database without an `{EVENTS_TABLE}` table: {}",
+            path.display()
+        );
+    }
+    let status = migration_status(&connection)?;
+    if status.current_version > latest_version() {
+        bail!(
+            "import mart is at migration {} but this build only knows {}; upgrade logit first",
+            status.current_version,
+            latest_version()
+        );
+    }
+    read_events(&connection)
+}
+
+/// Decides what happens to each event (re-keying collisions in place) and
+/// returns the rows to write with per-host counts. References to a re-keyed
+/// event from elsewhere in the batch are pointed at its new id.
+fn plan_merge(
+    connection: &Connection,
+    events: &mut [AgentLogEvent],
+) -> Result<(Vec<AgentLogEvent>, BTreeMap<String, ImportHostCounts>)> {
+    let mut pending = BTreeMap::<String, (String, Option<String>)>::new();
+    let mut pending_hashes = BTreeSet::<String>::new();
+    let mut to_write = Vec::new();
+    let mut hosts = BTreeMap::<String, ImportHostCounts>::new();
+    let mut rekeyed_ids = BTreeMap::<String, String>::new();
+
+    for event in events.iter_mut() {
+        let host = event_host(event).unwrap_or_default().to_string();
+        let mut decision = decide(connection, &pending, &pending_hashes, event, &host)?;
+        if decision == MergeDecision::Rekey {
+            let rekeyed_id = format!("{host}/{}", event.event_id);
+            rekeyed_ids.insert(event.event_id.clone(), rekeyed_id.clone());
+            event.event_id = rekeyed_id;
+            decision = match decide(connection, &pending, &pending_hashes, event, &host)? {
+                MergeDecision::Insert => MergeDecision::Rekey,
+                other => other,
+            };
+        }
+
+        let counts = hosts.entry(host.clone()).or_default();
+        counts.read += 1;
+        match decision {
+            MergeDecision::Insert => counts.inserted += 1,
+            MergeDecision::Replace => counts.replaced += 1,
+            MergeDecision::Rekey => counts.rekeyed += 1,
+            MergeDecision::Skip => {
+                counts.skipped += 1;
+                continue;
+            }
+        }
+        pending.insert(
+            event.event_id.clone(),
+            (event.canonical_hash.clone(), Some(host)),
+        );
+        pending_hashes.insert(event.canonical_hash.clone());
+        to_write.push(event.clone());
+    }
+    if !rekeyed_ids.is_empty() {
+        for event in &mut to_write {
+            rewrite_event_references(event, &rekeyed_ids);
+        }
+    }
+    Ok((to_write, hosts))
+}
+
+/// Points `parent_event_id` and the dedupe member list at re-keyed ids.
+fn rewrite_event_references(event: &mut AgentLogEvent, rekeyed_ids: &BTreeMap<String, String>) {
+    if let Some(rekeyed_id) = event
+        .parent_event_id
+        .as_ref()
+        .and_then(|parent| rekeyed_ids.get(parent))
+    {
+        event.parent_event_id = Some(rekeyed_id.clone());
+    }
+    if let Some(Value::Array(members)) = event.metadata.get_mut("dedupe_members") {
+        for member in members {
+            if let Some(rekeyed_id) = member.as_str().and_then(|id| rekeyed_ids.get(id)) {
+                *member = Value::String(rekeyed_id.clone());
+            }
+        }
+    }
+}
+
+fn decide(
+    connection: &Connection,
+    pending: &BTreeMap<String, (String, Option<String>)>,
+    pending_hashes: &BTreeSet<String>,
+    event: &AgentLogEvent,
+    host: &str,
+) -> Result<MergeDecision> {
+    let existing = match pending.get(&event.event_id) {
+        Some(existing) => Some(existing.clone()),
+        None => connection
+            .query_row(
+                &format!("SELECT canonical_hash, host FROM {EVENTS_TABLE} WHERE event_id = ?1"),
+                [&event.event_id],
+                |row| {
+                    Ok((
+                        row.get::<usize, String>(0)?,
+                        row.get::<usize, Option<String>>(1)?,
+                    ))
+                },
+            )
+            .optional()
+            .with_context(|| format!("failed to look up event_id={}", event.event_id))?,
+    };
+    if let Some((canonical_hash, existing_host)) = existing {
+        return Ok(if canonical_hash == event.canonical_hash {
+            MergeDecision::Skip
+        } else if existing_host.as_deref() == Some(host) {
+            MergeDecision::Replace
+        } else {
+            MergeDecision::Rekey
+        });
+    }
+
+    let hash_known = pending_hashes.contains(&event.canonical_hash)
+        || connection
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {EVENTS_TABLE} WHERE canonical_hash = ?1)"),
+                [&event.canonical_hash],
+                |row| row.get::<usize, i64>(0),
+            )
+            .with_context(|| format!("failed to look up canonical_hash for {}", event.event_id))?
+            != 0;
+    Ok(if hash_known {
+        MergeDecision::Skip
+    } else {
+        MergeDecision::Insert
+    })
+}
@@ -1,3 +1,5 @@
+pub mod import;
+
 use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
@@ -8,11 +10,16 @@ use serde_json::json;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
-use crate::models::{AgentLogEvent, AgentSource};
+use crate::discovery::{PipelineFilterRecord, PipelineFilters, SourceFormatHint};
+use crate::models::AgentLogEvent;
+use crate::purge::{EventKey, PurgeSelector, matches_any};
 use crate::sqlite::{
     INGEST_RUNS_TABLE, INGEST_WATERMARKS_TABLE, SqliteWriterConfig, open_sqlite_connection,
     write_events_batched,
 };
+use crate::utils::fs::write_atomic;
+use crate::utils::host::{event_host, stamp_host};
+use crate::utils::lock::process_alive;
 
 pub const INGEST_REPORT_SCHEMA_VERSION: &str = "logit.ingest-report.v1";
 
@@ -29,29 +36,43 @@ pub struct IngestRefreshPlan {
     pub sqlite_path: PathBuf,
     pub source_root: PathBuf,
     pub fail_fast: bool,
+    pub filters: PipelineFilters,
+    /// Overrides the generated `ingest_run_id`, e.g. with a `logit run` id.
+    pub run_id: Option<String>,
+    /// Host recorded on the run and on events that do not already name one.
+    pub host: Option<String>,
+    /// Selectors from `purge/tombstones.json`; matching events are not written.
+    pub tombstones: Vec<PurgeSelector>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct IngestRefreshReport {
     pub ingest_run_id: String,
     pub source_root: String,
+    pub host: Option<String>,
     pub status: IngestRunStatus,
     pub started_at_utc: String,
     pub finished_at_utc: String,
     pub duration_ms: u64,
     pub events_read: usize,
+    pub events_filtered: usize,
     pub events_written: usize,
     pub events_skipped: usize,
     pub warnings_count: usize,
     pub errors_count: usize,
     pub watermarks_upserted: usize,
     pub watermark_staleness_state: String,
+    pub redaction_policy: Option<String>,
+    pub filters: PipelineFilterRecord,
     pub warnings: Vec<String>,
+    /// Runs left `running` by a process that exited, marked `failed` by this run.
+    pub recovered_runs: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct IngestReportCounts {
     pub read: usize,
+    pub filtered: usize,
     pub inserted: usize,
     pub updated: usize,
     pub skipped: usize,
@@ -68,12 +89,17 @@ pub struct IngestReportArtifact {
     pub schema_version: String,
     pub ingest_run_id: String,
     pub source_root: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
     pub status: IngestRunStatus,
     pub started_at_utc: String,
     pub finished_at_utc: String,
     pub duration_ms: u64,
     pub counts: IngestReportCounts,
+    pub redaction_policy: Option<String>,
+    pub filters: PipelineFilterRecord,
     pub warnings: Vec<String>,
+    pub recovered_runs: Vec<String>,
     pub watermarks: IngestReportWatermarkStatus,
 }
 
@@ -86,7 +112,7 @@ struct WatermarkUpsertOutcome {
 pub fn run_refresh(plan: &IngestRefreshPlan) -> Result<IngestRefreshReport> {
     let started_at_utc = now_utc_rfc3339()?;
     let started_at = std::time::Instant::now();
-    let ingest_run_id = build_ingest_run_id();
+    let ingest_run_id = plan.run_id.clone().unwrap_or_else(build_ingest_run_id);
     let source_root = plan.source_root.to_string_lossy().to_string();
 
     let input = std::fs::read_to_string(&plan.events_jsonl_path).with_context(|| {
@@ -96,16 +122,34 @@ pub fn run_refresh(plan: &IngestRefreshPlan) -> Result<IngestRefreshReport> {
         )
     })?;
     let (events, warnings) = parse_events_jsonl(&input, plan.fail_fast)?;
+    let events_read = events.len();
+    let (mut events, mut filter_record) = apply_ingest_filters(events, &plan.filters);
+    let filtered_count = events.len();
+    events.retain(|event| !matches_any(&plan.tombstones, &EventKey::from_event(event)));
+    filter_record.events_tombstoned = filtered_count - events.len();
+    let events_filtered = events_read - events.len();
+    if let Some(host) = &plan.host {
+        for event in &mut events {
+            stamp_host(event, host);
+        }
+    }
+    let filters_json =
+        serde_json::to_string(&filter_record).context("failed to encode ingest filter metadata")?;
+    let redaction_policy = read_normalize_redaction_policy(&plan.events_jsonl_path);
 
     let mut connection = open_sqlite_connection(&plan.sqlite_path)?;
     crate::sqlite::ensure_sqlite_schema(&connection)?;
+    let recovered_runs = fail_abandoned_ingest_runs(&connection, &ingest_run_id, &started_at_utc)?;
     insert_ingest_run_started(
         &connection,
         &ingest_run_id,
         &started_at_utc,
         &source_root,
-        events.len(),
+        events_read,
         warnings.len(),
+        redaction_policy.as_deref(),
+        &filters_json,
+        plan.host.as_deref(),
     )?;
 
     let write_stats =
@@ -119,7 +163,7 @@ pub fn run_refresh(plan: &IngestRefreshPlan) -> Result<IngestRefreshReport> {
                     &ingest_run_id,
                     IngestRunStatus::Failed,
                     &finished_at_utc,
-                    events.len(),
+                    events_read,
                     0,
                     warnings.len(),
                     1,
@@ -130,14 +174,23 @@ pub fn run_refresh(plan: &IngestRefreshPlan) -> Result<IngestRefreshReport> {
         };
 
     let finished_at_utc = now_utc_rfc3339()?;
-    let watermark_outcome =
-        upsert_source_watermarks(&connection, &ingest_run_id, &finished_at_utc, &events)?;
+    if let Some(host) = &plan.host {
+        adopt_unhosted_watermarks(&connection, host)?;
+    }
+    let watermark_outcome = upsert_source_watermarks(
+        &connection,
+        &ingest_run_id,
+        &finished_at_utc,
+        &events,
+        !filter_record.active,
+        &owned_hosts(&events, plan.host.as_deref()),
+    )?;
     finalize_ingest_run(
         &connection,
         &ingest_run_id,
         IngestRunStatus::Success,
         &finished_at_utc,
-        events.len(),
+        events_read,
         write_stats.records_written,
         warnings.len(),
         0,
@@ -147,21 +200,109 @@ pub fn run_refresh(plan: &IngestRefreshPlan) -> Result<IngestRefreshReport> {
     Ok(IngestRefreshReport {
         ingest_run_id,
         source_root,
+        host: plan.host.clone(),
         status: IngestRunStatus::Success,
         started_at_utc,
         finished_at_utc,
         duration_ms: started_at.elapsed().as_millis() as u64,
-        events_read: events.len(),
+        events_read,
+        events_filtered,
         events_written: write_stats.records_written,
         events_skipped: warnings.len(),
         warnings_count: warnings.len(),
         errors_count: 0,
         watermarks_upserted: watermark_outcome.sources_upserted,
         watermark_staleness_state: watermark_outcome.staleness_state,
+        redaction_policy,
+        filters: filter_record,
         warnings,
+        recovered_runs,
     })
 }
 
+/// Keeps events admitted by the source filters and time window. The format
+/// filter uses the hint implied by each event's `source_path` extension.
+fn apply_ingest_filters(
+    events: Vec<AgentLogEvent>,
+    filters: &PipelineFilters,
+) -> (Vec<AgentLogEvent>, PipelineFilterRecord) {
+    let mut record = filters.record();
+    let mut kept = Vec::with_capacity(events.len());
+    for event in events {
+        let admitted = filters.admits_adapter_name(event.adapter_name.as_str())
+            && filters.admits_path(&event.source_path)
+            && (filters.format_hints.is_empty()
+                || SourceFormatHint::from_source_path(&event.source_path)
+                    .is_some_and(|hint| filters.admits_format(hint)));
+        if !admitted {
+            continue;
+        }
+        if !filters.window.contains(event.timestamp_unix_ms) {
+            record.events_outside_window += 1;
+            continue;
+        }
+        kept.push(event);
+    }
+    (kept, record)
+}
+
+/// Marks `running` rows whose owning process no longer exists (or is unknown,
+/// for rows written before `owner_pid` was recorded) as `failed`.
+fn fail_abandoned_ingest_runs(
+    connection: &rusqlite::Connection,
+    detected_by: &str,
+    finished_at_utc: &str,
+) -> Result<Vec<String>> {
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT ingest_run_id, owner_pid FROM {INGEST_RUNS_TABLE}
+             WHERE status = 'running'
+             ORDER BY started_at_utc, ingest_run_id"
+        ))
+        .context("failed to prepare running ingest runs query")?;
+    let running = statement
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?))
+        })
+        .context("failed to query running ingest runs")?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .context("failed to read running ingest runs")?;
+
+    let mut recovered = Vec::new();
+    for (ingest_run_id, owner_pid) in running {
+        if owner_pid
+            .and_then(|pid| u32::try_from(pid).ok())
+            .is_some_and(process_alive)
+        {
+            continue;
+        }
+        let error_summary = json!({
+            "message": "ingest run left running by a process that exited",
+            "owner_pid": owner_pid,
+            "detected_by": detected_by,
+        })
+        .to_string();
+        connection
+            .execute(
+                &format!(
+                    "UPDATE {INGEST_RUNS_TABLE}
+                     SET status = 'failed',
+                         finished_at_utc = ?2,
+                         errors_count = errors_count + 1,
+                         error_summary_json = ?3
+                     WHERE ingest_run_id = ?1 AND status = 'running'"
+                ),
+                params![ingest_run_id, finished_at_utc, error_summary],
+            )
+            .with_context(|| {
+                format!("failed to mark abandoned ingest run failed: {ingest_run_id}")
+            })?;
+        recovered.push(ingest_run_id);
+    }
+    Ok(recovered)
+}
+
+#[allow(clippy::too_many_arguments)]
 fn insert_ingest_run_started(
     connection: &rusqlite::Connection,
     ingest_run_id: &str,
@@ -169,19 +310,26 @@ fn insert_ingest_run_started(
     source_root: &str,
     events_read: usize,
     warnings_count: usize,
+    redaction_policy: Option<&str>,
+    filters_json: &str,
+    host: Option<&str>,
 ) -> Result<()> {
     connection
         .execute(
             &format!(
-                "INSERT INTO {INGEST_RUNS_TABLE} (ingest_run_id, started_at_utc, status, source_root, events_read, events_written, warnings_count, errors_count, error_summary_json)
-                 VALUES (?1, ?2, 'running', ?3, ?4, 0, ?5, 0, '{{}}')"
+                "INSERT INTO {INGEST_RUNS_TABLE} (ingest_run_id, started_at_utc, status, source_root, events_read, events_written, warnings_count, errors_count, error_summary_json, redaction_policy, filters_json, owner_pid, host)
+                 VALUES (?1, ?2, 'running', ?3, ?4, 0, ?5, 0, '{{}}', ?6, ?7, ?8, ?9)"
             ),
             params![
                 ingest_run_id,
                 started_at_utc,
                 source_root,
                 to_i64(events_read, "events_read")?,
-                to_i64(warnings_count, "warnings_count")?
+                to_i64(warnings_count, "warnings_count")?,
+                redaction_policy,
+                filters_json,
+                i64::from(std::process::id()),
+                host
             ],
         )
         .with_context(|| format!("failed to insert ingest run start row: {ingest_run_id}"))?;
@@ -228,22 +376,25 @@ fn finalize_ingest_run(
     Ok(())
 }
 
+/// Upserts one watermark per `(host, source)` seen in `events`. Sources that
+/// were not observed are marked stale only when they belong to one of
+/// `owned_hosts`, so a local refresh never ages out another machine's sources.
 fn upsert_source_watermarks(
     connection: &rusqlite::Connection,
     ingest_run_id: &str,
     refreshed_at_utc: &str,
     events: &[AgentLogEvent],
+    mark_missing_stale: bool,
+    owned_hosts: &BTreeSet<Option<String>>,
 ) -> Result<WatermarkUpsertOutcome> {
     let existing_by_source = load_existing_source_watermarks(connection)?;
     let mut watermark_by_source = BTreeMap::<String, SourceWatermarkState>::new();
     for event in events {
-        let source_key = format!(
-            "{}|{}",
-            source_kind_key(event.source_kind),
-            event.source_path
-        );
+        let host = event_host(event);
+        let source_key = watermark_source_key(host, event.source_kind.as_str(), &event.source_path);
         let candidate = SourceWatermarkState {
-            source_kind: source_kind_key(event.source_kind).to_string(),
+            host: host.map(ToOwned::to_owned),
+            source_kind: event.source_kind.as_str().to_string(),
             source_path: event.source_path.clone(),
             source_record_locator: event.source_record_locator.clone(),
             source_record_hash: event.source_record_hash.clone(),
@@ -260,8 +411,8 @@ fn upsert_source_watermarks(
 
     let upsert_sql = format!(
         "INSERT INTO {INGEST_WATERMARKS_TABLE}
-             (source_key, source_kind, source_path, source_record_locator, source_record_hash, last_event_timestamp_unix_ms, last_ingest_run_id, refreshed_at_utc, staleness_state, metadata_json)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             (source_key, source_kind, source_path, source_record_locator, source_record_hash, last_event_timestamp_unix_ms, last_ingest_run_id, refreshed_at_utc, staleness_state, metadata_json, host)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
          ON CONFLICT(source_key) DO UPDATE SET
              source_kind = excluded.source_kind,
              source_path = excluded.source_path,
@@ -271,7 +422,8 @@ fn upsert_source_watermarks(
              last_ingest_run_id = excluded.last_ingest_run_id,
              refreshed_at_utc = excluded.refreshed_at_utc,
              staleness_state = excluded.staleness_state,
-             metadata_json = excluded.metadata_json"
+             metadata_json = excluded.metadata_json,
+             host = excluded.host"
     );
 
     let mut observed_sources = BTreeSet::<String>::new();
@@ -298,6 +450,7 @@ fn upsert_source_watermarks(
                 refreshed_at_utc,
                 "fresh",
                 metadata_json,
+                watermark.host,
             ],
         )?;
         observed_sources.insert(source_key.clone());
@@ -310,8 +463,13 @@ fn upsert_source_watermarks(
          WHERE source_key = ?1"
     );
     let mut stale_source_count = 0usize;
-    for source_key in existing_by_source.keys() {
-        if observed_sources.contains(source_key) {
+    // A filtered refresh only sees part of the corpus, so sources it did not
+    // observe keep their previous state instead of being marked stale.
+    for (source_key, existing) in &existing_by_source {
+        if !mark_missing_stale
+            || observed_sources.contains(source_key)
+            || !owned_hosts.contains(&existing.host)
+        {
             continue;
         }
         let metadata_json = json!({
@@ -366,6 +524,16 @@ fn parse_events_jsonl(input: &str, fail_fast: bool) -> Result<(Vec<AgentLogEvent
     Ok((events, warnings))
 }
 
+fn read_normalize_redaction_policy(events_jsonl_path: &Path) -> Option<String> {
+    let stats_text =
+        std::fs::read_to_string(events_jsonl_path.with_file_name("stats.json")).ok()?;
+    let stats = serde_json::from_str::<serde_json::Value>(&stats_text).ok()?;
+    stats
+        .pointer("/redaction/policy")
+        .and_then(serde_json::Value::as_str)
+        .map(ToOwned::to_owned)
+}
+
 fn now_utc_rfc3339() -> Result<String> {
     OffsetDateTime::now_utc()
         .format(&Rfc3339)
@@ -379,16 +547,6 @@ fn build_ingest_run_id() -> String {
     format!("ingest-{nanos:016x}")
 }
 
-const fn source_kind_key(source: AgentSource) -> &'static str {
-    match source {
-        AgentSource::Codex => "codex",
-        AgentSource::Claude => "claude",
-        AgentSource::Gemini => "gemini",
-        AgentSource::Amp => "amp",
-        AgentSource::OpenCode => "opencode",
-    }
-}
-
 const fn ingest_run_status_key(status: IngestRunStatus) -> &'static str {
     match status {
         IngestRunStatus::Success => "success",
@@ -400,8 +558,43 @@ fn to_i64(value: usize, field: &str) -> Result<i64> {
     i64::try_from(value).map_err(|_| anyhow!("{field} exceeds sqlite INTEGER range"))
 }
 
+/// Hosts whose watermarks this batch speaks for: the local host plus every
+/// host named by an event.
+fn owned_hosts(events: &[AgentLogEvent], host: Option<&str>) -> BTreeSet<Option<String>> {
+    events
+        .iter()
+        .map(|event| event_host(event).map(ToOwned::to_owned))
+        .chain(std::iter::once(host.map(ToOwned::to_owned)))
+        .collect()
+}
+
+/// Watermarks written before host identity existed belong to the local
+/// machine; rekey them under `host` so the next refresh continues them.
+fn adopt_unhosted_watermarks(connection: &rusqlite::Connection, host: &str) -> Result<()> {
+    connection
+        .execute(
+            &format!(
+                "UPDATE OR IGNORE {INGEST_WATERMARKS_TABLE}
+                 SET host = ?1, source_key = ?1 || '|' || source_key
+                 WHERE host IS NULL"
+            ),
+            [host],
+        )
+        .context("failed to assign host to existing ingest watermarks")?;
+    Ok(())
+}
+
+#[must_use]
+pub fn watermark_source_key(host: Option<&str>, source_kind: &str, source_path: &str) -> String {
+    match host {
+        Some(host) => format!("{host}|{source_kind}|{source_path}"),
+        None => format!("{source_kind}|{source_path}"),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct SourceWatermarkState {
+    host: Option<String>,
     source_kind: String,
     source_path: String,
     source_record_locator: String,
@@ -411,6 +604,7 @@ struct SourceWatermarkState {
 
 #[derive(Debug, Clone)]
 struct ExistingSourceWatermarkState {
+    host: Option<String>,
     source_record_locator: Option<String>,
     source_record_hash: Option<String>,
     last_event_timestamp_unix_ms: Option<i64>,
@@ -426,7 +620,7 @@ fn load_existing_source_watermarks(
     connection: &rusqlite::Connection,
 ) -> Result<BTreeMap<String, ExistingSourceWatermarkState>> {
     let mut statement = connection.prepare(&format!(
-        "SELECT source_key, source_record_locator, source_record_hash, last_event_timestamp_unix_ms
+        "SELECT source_key, source_record_locator, source_record_hash, last_event_timestamp_unix_ms, host
          FROM {INGEST_WATERMARKS_TABLE}"
     ))?;
     let rows = statement.query_map([], |row| {
@@ -436,6 +630,7 @@ fn load_existing_source_watermarks(
                 source_record_locator: row.get(1)?,
                 source_record_hash: row.get(2)?,
                 last_event_timestamp_unix_ms: row.get(3)?,
+                host: row.get(4)?,
             },
         ))
     })?;
@@ -494,6 +689,10 @@ pub fn default_plan_from_paths(
         sqlite_path: out_dir.join("mart.sqlite"),
         source_root: source_root.to_path_buf(),
         fail_fast,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     }
 }
 
@@ -508,17 +707,22 @@ pub fn build_ingest_report_artifact(report: &IngestRefreshReport) -> IngestRepor
         schema_version: INGEST_REPORT_SCHEMA_VERSION.to_string(),
         ingest_run_id: report.ingest_run_id.clone(),
         source_root: report.source_root.clone(),
+        host: report.host.clone(),
         status: report.status,
         started_at_utc: report.started_at_utc.clone(),
         finished_at_utc: report.finished_at_utc.clone(),
         duration_ms: report.duration_ms,
         counts: IngestReportCounts {
             read: report.events_read,
+            filtered: report.events_filtered,
             inserted: report.events_written,
             updated: 0,
             skipped: report.events_skipped,
         },
+        redaction_policy: report.redaction_policy.clone(),
+        filters: report.filters.clone(),
         warnings: report.warnings.clone(),
+        recovered_runs: report.recovered_runs.clone(),
         watermarks: IngestReportWatermarkStatus {
             sources_upserted: report.watermarks_upserted,
             staleness_state: report.watermark_staleness_state.clone(),
@@ -538,6 +742,6 @@ pub fn write_ingest_report_artifact(path: &Path, report: &IngestRefreshReport) -
     let artifact = build_ingest_report_artifact(report);
     let encoded =
         serde_json::to_vec_pretty(&artifact).context("failed to encode ingest report artifact")?;
-    std::fs::write(path, encoded)
+    write_atomic(path, &encoded)
         .with_context(|| format!("failed to write ingest report artifact: {}", path.display()))
 }
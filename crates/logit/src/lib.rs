@@ -1,14 +1,23 @@
 #![forbid(unsafe_code)]
 
 pub mod adapters;
+pub mod audit;
 pub mod cli;
 pub mod config;
 pub mod discovery;
+pub mod doctor;
 pub mod ingest;
+pub mod mcp;
 pub mod models;
 pub mod normalize;
+pub mod otlp;
+pub mod pipeline;
+pub mod purge;
+pub mod serve;
 pub mod snapshot;
 pub mod sqlite;
+pub mod transcript;
+pub mod tui;
 pub mod utils;
 pub mod validate;
 
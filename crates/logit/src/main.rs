@@ -3,12 +3,13 @@
 use std::path::PathBuf;
 
 use anyhow::{Result, anyhow};
-use clap::Parser;
 use clap::error::ErrorKind;
-use logit::cli::app::{Cli, Command, RuntimeArgs};
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use logit::cli::app::{Cli, Command, RuntimeArgs, apply_config_defaults};
 use logit::cli::commands;
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use logit::utils::lock::{OUT_DIR_LOCKED_ERROR_CODE, OutDirLock, OutDirLocked};
 use serde_json::json;
 
 const EXIT_SUCCESS: i32 = 0;
@@ -21,19 +22,23 @@ fn main() {
 }
 
 fn run() -> i32 {
-    let cli = match Cli::try_parse() {
-        Ok(cli) => cli,
+    let (cli, matches) = match Cli::command()
+        .try_get_matches()
+        .and_then(|matches| Cli::from_arg_matches(&matches).map(|cli| (cli, matches)))
+    {
+        Ok(parsed) => parsed,
         Err(error) => return exit_code_for_parse_error(error),
     };
     let command_name = command_name(&cli.command);
     let json_only_response = command_requires_json_envelope(&cli.command);
-    if !json_only_response {
+    let quiet = json_only_response || command_owns_stdout(&cli.command);
+    if !quiet {
         println!("logit: starting `{command_name}`");
     }
 
-    match execute(cli) {
+    match execute(cli, &matches) {
         Ok(()) => {
-            if !json_only_response {
+            if !quiet {
                 println!("logit: completed `{command_name}` (exit_code={EXIT_SUCCESS})");
             }
             EXIT_SUCCESS
@@ -51,36 +56,59 @@ fn run() -> i32 {
     }
 }
 
-fn execute(cli: Cli) -> Result<()> {
+fn execute(mut cli: Cli, matches: &ArgMatches) -> Result<()> {
+    if let Command::Inspect(args) = &cli.command {
+        return commands::inspect::run(args);
+    }
+    let (runtime_paths, config) = resolve_runtime(&cli.runtime)?;
+    apply_config_defaults(&mut cli.command, matches, &config);
+    let _out_dir_lock = if command_writes_out_dir(&cli.command) {
+        let invocation = std::env::args().collect::<Vec<_>>().join(" ");
+        Some(OutDirLock::acquire(&runtime_paths.out_dir, &invocation)?)
+    } else {
+        None
+    };
     match cli.command {
-        Command::Snapshot(args) => {
-            let runtime_paths = resolve_runtime_paths(&cli.runtime)?;
-            commands::snapshot::run(&args, &runtime_paths)
-        }
-        Command::Normalize(args) => {
-            let runtime_paths = resolve_runtime_paths(&cli.runtime)?;
-            commands::normalize::run(&args, &runtime_paths)
-        }
+        Command::Snapshot(args) => commands::snapshot::run(&args, &config, &runtime_paths),
+        Command::Normalize(args) => commands::normalize::run(&args, &config, &runtime_paths),
         Command::Inspect(args) => commands::inspect::run(&args),
-        Command::Validate(args) => {
-            let runtime_paths = resolve_runtime_paths(&cli.runtime)?;
-            commands::validate::run(&args, &runtime_paths)
-        }
-        Command::Ingest(args) => {
-            let runtime_paths = resolve_runtime_paths(&cli.runtime)?;
-            commands::ingest::run(&args, &runtime_paths)
-        }
-        Command::Query(args) => {
-            let runtime_paths = resolve_runtime_paths(&cli.runtime)?;
-            commands::query::run(&args, &runtime_paths)
-        }
+        Command::Validate(args) => commands::validate::run(&args, &runtime_paths),
+        Command::Ingest(args) => commands::ingest::run(&args, &config, &runtime_paths),
+        Command::Mart(args) => commands::mart::run(&args, &runtime_paths),
+        Command::Query(args) => commands::query::run(&args, &runtime_paths),
+        Command::Redact(args) => commands::redact::run(&args, &runtime_paths),
+        Command::Audit(args) => commands::audit::run(&args, &runtime_paths),
+        Command::Show(args) => commands::show::run(&args, &runtime_paths),
+        Command::Tui(args) => commands::tui::run(&args, &runtime_paths),
+        Command::Serve(args) => commands::serve::run(&args, &config, &runtime_paths),
+        Command::Mcp(args) => commands::mcp::run(&args, &runtime_paths),
+        Command::Export(args) => commands::export::run(&args, &config, &runtime_paths),
+        Command::Config(args) => commands::config::run(&args, &config, &runtime_paths),
+        Command::Run(args) => commands::run::run(&args, &config, &runtime_paths),
+        Command::Doctor(args) => commands::doctor::run(&args, &config, &runtime_paths),
+        Command::Prune(args) => commands::prune::run(&args, &runtime_paths),
+        Command::Purge(args) => commands::purge::run(&args, &runtime_paths),
     }
 }
 
 fn classify_runtime_error(error: &anyhow::Error) -> i32 {
-    if error
-        .downcast_ref::<commands::validate::ValidationCommandFailure>()
-        .is_some()
+    let envelope_validation_failure = error
+        .downcast_ref::<QueryEnvelopeCommandFailure>()
+        .and_then(|failure| failure.envelope().error.as_ref())
+        .is_some_and(|envelope_error| {
+            envelope_error.code == logit::audit::AUDIT_THRESHOLD_ERROR_CODE
+                || envelope_error.code == logit::ingest::import::IMPORT_INVALID_ERROR_CODE
+        });
+    if envelope_validation_failure
+        || error
+            .downcast_ref::<commands::validate::ValidationCommandFailure>()
+            .is_some()
+        || error
+            .downcast_ref::<logit::pipeline::RunGateFailure>()
+            .is_some()
+        || error
+            .downcast_ref::<commands::doctor::DoctorCheckFailure>()
+            .is_some()
     {
         EXIT_VALIDATION_FAILURE
     } else {
@@ -108,12 +136,62 @@ fn command_name(command: &Command) -> &'static str {
         Command::Inspect(_) => "inspect",
         Command::Validate(_) => "validate",
         Command::Ingest(_) => "ingest",
+        Command::Mart(_) => "mart",
         Command::Query(_) => "query",
+        Command::Redact(_) => "redact",
+        Command::Audit(_) => "audit",
+        Command::Show(_) => "show",
+        Command::Tui(_) => "tui",
+        Command::Serve(_) => "serve",
+        Command::Mcp(_) => "mcp",
+        Command::Export(_) => "export",
+        Command::Config(_) => "config",
+        Command::Run(_) => "run",
+        Command::Doctor(_) => "doctor",
+        Command::Prune(_) => "prune",
+        Command::Purge(_) => "purge",
     }
 }
 
 fn command_requires_json_envelope(command: &Command) -> bool {
-    matches!(command, Command::Ingest(_) | Command::Query(_))
+    matches!(
+        command,
+        Command::Ingest(_)
+            | Command::Mart(_)
+            | Command::Query(_)
+            | Command::Audit(_)
+            | Command::Prune(_)
+            | Command::Purge(_)
+    )
+}
+
+/// Commands that write artifacts under the out dir hold its advisory lock for
+/// their whole run so two of them never interleave writes.
+fn command_writes_out_dir(command: &Command) -> bool {
+    match command {
+        Command::Snapshot(_)
+        | Command::Normalize(_)
+        | Command::Validate(_)
+        | Command::Ingest(_)
+        | Command::Audit(_)
+        | Command::Export(_)
+        | Command::Run(_) => true,
+        Command::Mart(args) => args.writes(),
+        Command::Prune(args) => !args.dry_run,
+        Command::Purge(args) => !args.dry_run,
+        _ => false,
+    }
+}
+
+fn command_owns_stdout(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Show(_)
+            | Command::Tui(_)
+            | Command::Mcp(_)
+            | Command::Config(_)
+            | Command::Doctor(_)
+    )
 }
 
 fn print_json_error_envelope(command_name: &str, error: &anyhow::Error) {
@@ -124,15 +202,27 @@ fn print_json_error_envelope(command_name: &str, error: &anyhow::Error) {
         return;
     }
 
-    let fallback = QueryEnvelope::error(command_name, "runtime_failure", "command failed")
-        .with_error_details(json!({ "cause": format!("{error:#}") }));
+    let fallback = match error.downcast_ref::<OutDirLocked>() {
+        Some(locked) => {
+            QueryEnvelope::error(command_name, OUT_DIR_LOCKED_ERROR_CODE, "out dir is locked")
+                .with_error_details(json!({
+                    "cause": locked.to_string(),
+                    "lock_path": locked.lock_path.display().to_string(),
+                    "owner": locked.owner,
+                }))
+        }
+        None => QueryEnvelope::error(command_name, "runtime_failure", "command failed")
+            .with_error_details(json!({ "cause": format!("{error:#}") })),
+    };
     match serde_json::to_string(&fallback) {
         Ok(encoded) => println!("{encoded}"),
         Err(_) => eprintln!("{error:#}"),
     }
 }
 
-fn resolve_runtime_paths(args: &RuntimeArgs) -> Result<RuntimePaths> {
+/// Resolves home and cwd, merges the layered config on top of them and then
+/// settles the output directory, where `--out-dir` beats every config layer.
+fn resolve_runtime(args: &RuntimeArgs) -> Result<(RuntimePaths, LogitConfig)> {
     let home_dir = match &args.home_dir {
         Some(path) => path.clone(),
         None => std::env::var_os("HOME")
@@ -145,5 +235,13 @@ fn resolve_runtime_paths(args: &RuntimeArgs) -> Result<RuntimePaths> {
         None => std::env::current_dir()?,
     };
 
-    logit::config::resolve_runtime_paths(&home_dir, &cwd, args.out_dir.as_deref())
+    let base = logit::config::resolve_runtime_paths(&home_dir, &cwd, None)?;
+    let mut config = LogitConfig::load(&base.home_dir, &base.cwd)?;
+    config.apply_out_dir_flag(args.out_dir.as_deref());
+    let runtime_paths = logit::config::resolve_runtime_paths(
+        &base.home_dir,
+        &base.cwd,
+        config.out_dir.value.as_deref(),
+    )?;
+    Ok((runtime_paths, config))
 }
@@ -0,0 +1,410 @@
+use std::io::{BufRead, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::cli::commands::query::{
+    DEFAULT_SEARCH_ROW_CAP, DEFAULT_SQL_ROW_CAP, QueryCatalogArgs, QuerySchemaArgs,
+    QuerySearchArgs, QuerySqlArgs, catalog_query_envelope, schema_query_envelope,
+    search_query_envelope, sql_query_envelope,
+};
+use crate::cli::commands::show::{session_transcript_envelope, sessions_envelope};
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+
+pub const PROTOCOL_VERSION: &str = "2025-06-18";
+pub const SUPPORTED_PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+pub const CATALOG_RESOURCE_URI: &str = "logit://catalog";
+pub const CONCEPT_RESOURCE_PREFIX: &str = "logit://catalog/concepts/";
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const RESOURCE_NOT_FOUND: i64 = -32002;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct McpServer {
+    sqlite_path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SqlArguments {
+    sql: String,
+    #[serde(default)]
+    params: Option<Value>,
+    #[serde(default = "default_sql_row_cap")]
+    row_cap: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SchemaArguments {
+    #[serde(default)]
+    include_internal: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CatalogArguments {
+    #[serde(default)]
+    verbose: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchArguments {
+    terms: String,
+    #[serde(default)]
+    raw: bool,
+    #[serde(default = "default_search_row_cap")]
+    row_cap: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptArguments {
+    session_id: String,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    turns: Option<String>,
+    #[serde(default)]
+    full: bool,
+}
+
+const fn default_sql_row_cap() -> usize {
+    DEFAULT_SQL_ROW_CAP
+}
+
+const fn default_search_row_cap() -> usize {
+    DEFAULT_SEARCH_ROW_CAP
+}
+
+impl McpServer {
+    #[must_use]
+    pub fn new(sqlite_path: PathBuf) -> Self {
+        Self { sqlite_path }
+    }
+
+    /// Reads newline-delimited JSON-RPC messages until EOF, writing one
+    /// response line per request (notifications get none).
+    pub fn serve(&self, reader: impl BufRead, mut writer: impl Write) -> Result<()> {
+        for line in reader.lines() {
+            let line = line.context("failed to read mcp message")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(response) = self.handle_message(&line) {
+                writeln!(writer, "{response}").context("failed to write mcp response")?;
+                writer.flush().context("failed to flush mcp response")?;
+            }
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn handle_message(&self, line: &str) -> Option<Value> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(error) => {
+                return Some(error_response(
+                    Value::Null,
+                    &RpcError::new(PARSE_ERROR, format!("parse error: {error}")),
+                ));
+            }
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Responses to server-initiated requests are not expected; ignore them.
+            if id.is_some() && (message.get("result").is_some() || message.get("error").is_some()) {
+                return None;
+            }
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                &RpcError::new(INVALID_REQUEST, "invalid request: missing method"),
+            ));
+        };
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = self.dispatch(method, &params);
+        let id = id?;
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, &error),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(initialize_result(params)),
+            "ping" => Ok(json!({})),
+            method if method.starts_with("notifications/") => Ok(Value::Null),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(params),
+            "resources/list" => Ok(json!({ "resources": resource_definitions() })),
+            "resources/templates/list" => Ok(json!({
+                "resourceTemplates": [{
+                    "uriTemplate": format!("{CONCEPT_RESOURCE_PREFIX}{{concept_id}}"),
+                    "name": "catalog concept",
+                    "description": "Semantic catalog concept descriptor",
+                    "mimeType": "application/json"
+                }]
+            })),
+            "resources/read" => read_resource(params),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("method not found: {method}"),
+            )),
+        }
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "tools/call requires `name`"))?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let envelope = match name {
+            "query_sql" => {
+                let arguments: SqlArguments = parse_arguments(name, arguments)?;
+                sql_query_envelope(
+                    &QuerySqlArgs {
+                        sql: arguments.sql,
+                        params: arguments.params.map(|params| params.to_string()),
+                        row_cap: arguments.row_cap,
+                    },
+                    &self.sqlite_path,
+                )
+            }
+            "query_schema" => {
+                let arguments: SchemaArguments = parse_arguments(name, arguments)?;
+                schema_query_envelope(
+                    &QuerySchemaArgs {
+                        include_internal: arguments.include_internal,
+                    },
+                    &self.sqlite_path,
+                )
+            }
+            "query_catalog" => {
+                let arguments: CatalogArguments = parse_arguments(name, arguments)?;
+                Ok(catalog_query_envelope(&QueryCatalogArgs {
+                    verbose: arguments.verbose,
+                }))
+            }
+            "search_transcripts" => {
+                let arguments: SearchArguments = parse_arguments(name, arguments)?;
+                search_query_envelope(
+                    &QuerySearchArgs {
+                        terms: arguments.terms,
+                        raw: arguments.raw,
+                        row_cap: arguments.row_cap,
+                    },
+                    &self.sqlite_path,
+                )
+            }
+            "list_sessions" => sessions_envelope(&self.sqlite_path),
+            "get_session_transcript" => {
+                let arguments: TranscriptArguments = parse_arguments(name, arguments)?;
+                session_transcript_envelope(
+                    &self.sqlite_path,
+                    &arguments.session_id,
+                    arguments.format.as_deref(),
+                    arguments.turns.as_deref(),
+                    arguments.full,
+                )
+            }
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("unknown tool: {name}"),
+                ));
+            }
+        };
+        let envelope = envelope.unwrap_or_else(|error| {
+            match error.downcast::<QueryEnvelopeCommandFailure>() {
+                Ok(failure) => failure.envelope().clone(),
+                Err(error) => QueryEnvelope::error(name, "mcp_tool_failed", format!("{error:#}")),
+            }
+        });
+        Ok(tool_result(&envelope))
+    }
+}
+
+fn parse_arguments<T: for<'de> Deserialize<'de>>(
+    tool: &str,
+    arguments: Value,
+) -> Result<T, RpcError> {
+    serde_json::from_value(arguments).map_err(|error| {
+        RpcError::new(
+            INVALID_PARAMS,
+            format!("invalid arguments for {tool}: {error}"),
+        )
+    })
+}
+
+fn tool_result(envelope: &QueryEnvelope) -> Value {
+    let structured = serde_json::to_value(envelope).unwrap_or(Value::Null);
+    json!({
+        "content": [{ "type": "text", "text": structured.to_string() }],
+        "structuredContent": structured,
+        "isError": !envelope.ok,
+    })
+}
+
+fn error_response(id: Value, error: &RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let protocol_version = requested
+        .filter(|version| SUPPORTED_PROTOCOL_VERSIONS.contains(version))
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": protocol_version,
+        "capabilities": {
+            "tools": { "listChanged": false },
+            "resources": { "listChanged": false, "subscribe": false }
+        },
+        "serverInfo": { "name": "logit", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Read-only access to the local logit mart (agentlog events, sessions, tool calls). \
+                         Start with query_catalog or query_schema, then use query_sql with row caps."
+    })
+}
+
+fn tool_definitions() -> Vec<Value> {
+    vec![
+        json!({
+            "name": "query_sql",
+            "description": "Run one read-only SELECT/WITH statement against the mart; same guardrails and row cap as `logit query sql`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "sql": { "type": "string" },
+                    "params": { "type": "array", "description": "positional (?1, ?2, ...) bind parameters" },
+                    "row_cap": { "type": "integer", "minimum": 1, "default": DEFAULT_SQL_ROW_CAP }
+                },
+                "required": ["sql"]
+            }
+        }),
+        json!({
+            "name": "query_schema",
+            "description": "List mart tables and views with their columns.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "include_internal": { "type": "boolean", "default": false } }
+            }
+        }),
+        json!({
+            "name": "query_catalog",
+            "description": "Describe semantic concepts (tool calls, sessions, adapters, quality, transcript search) and their relations.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "verbose": { "type": "boolean", "default": false } }
+            }
+        }),
+        json!({
+            "name": "search_transcripts",
+            "description": "Full-text search over event text, tool arguments and tool output, ranked by bm25.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "terms": { "type": "string" },
+                    "raw": { "type": "boolean", "default": false, "description": "pass terms as a raw FTS5 MATCH expression" },
+                    "row_cap": { "type": "integer", "minimum": 1, "default": DEFAULT_SEARCH_ROW_CAP }
+                },
+                "required": ["terms"]
+            }
+        }),
+        json!({
+            "name": "list_sessions",
+            "description": "List sessions with adapter, event counts and cost, most recent first.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }),
+        json!({
+            "name": "get_session_transcript",
+            "description": "Render one session transcript grouped by turn.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session_id": { "type": "string" },
+                    "format": { "type": "string", "enum": ["text", "markdown", "html"], "default": "text" },
+                    "turns": { "type": "string", "description": "1-based turn range: N, A..B, A.. or ..B" },
+                    "full": { "type": "boolean", "default": false, "description": "do not collapse long tool output" }
+                },
+                "required": ["session_id"]
+            }
+        }),
+    ]
+}
+
+fn catalog_concepts() -> Vec<Value> {
+    catalog_query_envelope(&QueryCatalogArgs { verbose: true })
+        .data
+        .and_then(|data| data.get("concepts").and_then(Value::as_array).cloned())
+        .unwrap_or_default()
+}
+
+fn resource_definitions() -> Vec<Value> {
+    let mut resources = vec![json!({
+        "uri": CATALOG_RESOURCE_URI,
+        "name": "catalog",
+        "description": "Full semantic catalog (concepts and relations)",
+        "mimeType": "application/json"
+    })];
+    resources.extend(catalog_concepts().iter().filter_map(|concept| {
+        let concept_id = concept.get("concept_id")?.as_str()?;
+        Some(json!({
+            "uri": format!("{CONCEPT_RESOURCE_PREFIX}{concept_id}"),
+            "name": concept_id,
+            "description": concept.get("description").cloned().unwrap_or(Value::Null),
+            "mimeType": "application/json"
+        }))
+    }));
+    resources
+}
+
+fn read_resource(params: &Value) -> Result<Value, RpcError> {
+    let uri = params
+        .get("uri")
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "resources/read requires `uri`"))?;
+    let payload = if uri == CATALOG_RESOURCE_URI {
+        catalog_query_envelope(&QueryCatalogArgs { verbose: true }).data
+    } else {
+        uri.strip_prefix(CONCEPT_RESOURCE_PREFIX)
+            .and_then(|concept_id| {
+                catalog_concepts().into_iter().find(|concept| {
+                    concept.get("concept_id").and_then(Value::as_str) == Some(concept_id)
+                })
+            })
+    };
+    let payload = payload
+        .ok_or_else(|| RpcError::new(RESOURCE_NOT_FOUND, format!("resource not found: {uri}")))?;
+    Ok(json!({
+        "contents": [{
+            "uri": uri,
+            "mimeType": "application/json",
+            "text": payload.to_string(),
+        }]
+    }))
+}
@@ -13,14 +13,58 @@ pub enum SchemaVersion {
     AgentLogV1,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AgentSource {
     Codex,
     Claude,
     Gemini,
     Amp,
+    #[serde(rename = "open_code", alias = "opencode")]
     OpenCode,
+    Aider,
+    Cursor,
+    Copilot,
+    #[serde(untagged)]
+    Custom(String),
+}
+
+impl AgentSource {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Codex => "codex",
+            Self::Claude => "claude",
+            Self::Gemini => "gemini",
+            Self::Amp => "amp",
+            Self::OpenCode => "opencode",
+            Self::Aider => "aider",
+            Self::Cursor => "cursor",
+            Self::Copilot => "copilot",
+            Self::Custom(name) => name,
+        }
+    }
+
+    #[must_use]
+    pub fn is_valid_custom_name(name: &str) -> bool {
+        let mut chars = name.chars();
+        chars.next().is_some_and(|first| first.is_ascii_lowercase())
+            && name.len() <= 64
+            && chars
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-')
+            && !matches!(
+                name,
+                "codex"
+                    | "claude"
+                    | "gemini"
+                    | "amp"
+                    | "opencode"
+                    | "open_code"
+                    | "aider"
+                    | "cursor"
+                    | "copilot"
+            )
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
@@ -1,32 +1,81 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, BTreeSet};
-use std::io::{BufWriter, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use serde::Serialize;
 use serde_json::{Value, json};
 
+use crate::adapters::custom::CustomAdapterSpec;
 use crate::adapters::{AdapterKind, all_adapter_kinds};
+use crate::config::settings::ModelPricing;
+use crate::discovery::classifier::{self, AdapterDetection, ContentFingerprint};
+use crate::discovery::roots::{DEFAULT_PROFILE, SourceRoots};
 use crate::discovery::{
-    self, DiscoveryPathRole, HistoryScore, PrioritizedSource, SourceFormatHint,
-    SourceSelectionFilter,
-};
-use crate::models::{
-    AgentLogEvent, AgentSource, EventType, RecordFormat, SCHEMA_VERSION, TimestampQuality,
+    self, DiscoveryPathRole, HistoryScore, PipelineFilterRecord, PipelineFilters,
+    PrioritizedSource, SourceFormatHint, SourceSelectionFilter,
 };
+use crate::models::{AgentLogEvent, EventType, RecordFormat, SCHEMA_VERSION, TimestampQuality};
+use crate::purge::{self, EventKey, PurgeSelector};
+use crate::utils::content;
+use crate::utils::fs::{AtomicFile, write_atomic};
+use crate::utils::hash::hash64;
+use crate::utils::host::stamp_host;
+use crate::utils::redaction::{self, RedactionPolicy, RedactionRules};
+
+pub const REDACTION_CLASSES_METADATA_KEY: &str = "redaction_classes";
+pub const SOURCE_PROFILE_METADATA_KEY: &str = "source_profile";
+/// Set to `pricing` on events whose `cost_usd` was derived from the
+/// configured per-model prices rather than reported by the source.
+pub const COST_SOURCE_METADATA_KEY: &str = "cost_source";
+pub const DEFAULT_RUN_ID: &str = "normalize-orchestrator-v1";
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NormalizationPlan {
     pub adapters: Vec<AdapterKind>,
+    pub custom_adapters: Vec<CustomAdapterSpec>,
     pub fail_fast: bool,
+    pub redaction_policy: RedactionPolicy,
+    pub redaction_rules: RedactionRules,
+    pub filters: PipelineFilters,
+    /// Explicit files or directories to normalize instead of the known-path
+    /// registry; adapters are detected per file from content.
+    pub inputs: Vec<PathBuf>,
+    pub input_adapter: Option<AdapterKind>,
+    pub source_roots: SourceRoots,
+    /// Directory scanned for per-repository histories (Aider) when no source
+    /// root override is given; normally the invocation's working directory.
+    pub repo_scan_root: Option<PathBuf>,
+    /// Custom tags appended to every normalized event.
+    pub tags: Vec<String>,
+    /// Host id recorded on events that do not already carry one.
+    pub host: Option<String>,
+    /// Selectors from `purge/tombstones.json`; matching events are dropped.
+    pub tombstones: Vec<PurgeSelector>,
+    /// Per-model prices used to fill in `cost_usd` when a source omits it.
+    pub pricing: BTreeMap<String, ModelPricing>,
+    pub run_id: String,
 }
 
 impl Default for NormalizationPlan {
     fn default() -> Self {
         Self {
             adapters: all_adapter_kinds().to_vec(),
+            custom_adapters: Vec::new(),
             fail_fast: false,
+            redaction_policy: RedactionPolicy::default(),
+            redaction_rules: RedactionRules::default(),
+            filters: PipelineFilters::default(),
+            inputs: Vec::new(),
+            input_adapter: None,
+            source_roots: SourceRoots::default(),
+            repo_scan_root: None,
+            tags: Vec::new(),
+            host: None,
+            tombstones: Vec::new(),
+            pricing: BTreeMap::new(),
+            run_id: DEFAULT_RUN_ID.to_string(),
         }
     }
 }
@@ -64,7 +113,7 @@ pub fn write_schema_artifact(path: &Path) -> Result<()> {
 
     let schema = build_schema_document();
     let encoded = serde_json::to_vec_pretty(&schema).context("failed to encode schema json")?;
-    std::fs::write(path, encoded).context("failed to write schema artifact")
+    write_atomic(path, &encoded).context("failed to write schema artifact")
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -85,16 +134,32 @@ pub struct NormalizeStats {
     pub record_format_counts: BTreeMap<String, usize>,
     pub event_type_counts: BTreeMap<String, usize>,
     pub timestamp_quality_counts: BTreeMap<String, usize>,
+    pub redaction: NormalizeRedactionStats,
+    pub filters: PipelineFilterRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NormalizeRedactionStats {
+    pub policy: String,
+    pub replacement: String,
+    pub events_redacted: usize,
+    pub class_counts: BTreeMap<String, usize>,
+}
+
+const SEEDED_ADAPTER_KEYS: &[&str] = &[
+    "codex", "claude", "gemini", "amp", "opencode", "aider", "cursor", "copilot",
+];
+
 #[must_use]
 pub fn build_normalize_stats(
     events: &[AgentLogEvent],
     dedupe_stats: DedupeStats,
+    redaction_policy: RedactionPolicy,
 ) -> NormalizeStats {
-    let mut adapter_contributions =
-        seeded_counts(&["codex", "claude", "gemini", "amp", "opencode"]);
-    let mut source_contributions = seeded_counts(&["codex", "claude", "gemini", "amp", "opencode"]);
+    let mut adapter_contributions = seeded_counts(SEEDED_ADAPTER_KEYS);
+    let mut source_contributions = seeded_counts(SEEDED_ADAPTER_KEYS);
     let mut record_format_counts = seeded_counts(&[
         "message",
         "tool_call",
@@ -117,16 +182,12 @@ pub fn build_normalize_stats(
     let mut timestamp_quality_counts = seeded_counts(&["exact", "derived", "fallback"]);
     let mut warning_count = 0_usize;
     let mut error_count = 0_usize;
+    let mut events_redacted = 0_usize;
+    let mut redaction_class_counts = BTreeMap::new();
 
     for event in events {
-        increment_count(
-            &mut adapter_contributions,
-            source_kind_key(event.adapter_name),
-        );
-        increment_count(
-            &mut source_contributions,
-            source_kind_key(event.source_kind),
-        );
+        increment_count(&mut adapter_contributions, event.adapter_name.as_str());
+        increment_count(&mut source_contributions, event.source_kind.as_str());
         increment_count(
             &mut record_format_counts,
             record_format_key(event.record_format),
@@ -138,6 +199,12 @@ pub fn build_normalize_stats(
         );
         warning_count += event.warnings.len();
         error_count += event.errors.len();
+        if event.pii_redacted == Some(true) {
+            events_redacted += 1;
+        }
+        for class_name in event_redaction_classes(event) {
+            increment_count(&mut redaction_class_counts, class_name);
+        }
     }
 
     NormalizeStats {
@@ -154,7 +221,156 @@ pub fn build_normalize_stats(
         record_format_counts,
         event_type_counts,
         timestamp_quality_counts,
+        redaction: NormalizeRedactionStats {
+            policy: redaction_policy.as_str().to_string(),
+            replacement: "mask".to_string(),
+            events_redacted,
+            class_counts: redaction_class_counts,
+        },
+        filters: PipelineFilterRecord::default(),
+        run_id: None,
+    }
+}
+
+fn event_redaction_classes(event: &AgentLogEvent) -> impl Iterator<Item = &str> {
+    event
+        .metadata
+        .get(REDACTION_CLASSES_METADATA_KEY)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+}
+
+/// Derives `cost_usd` from token counts for events of a priced model that
+/// report tokens but no cost. Cached input tokens are not reported by any
+/// adapter yet, so all input tokens are billed at `input_per_mtok`.
+pub fn apply_pricing(events: &mut [AgentLogEvent], pricing: &BTreeMap<String, ModelPricing>) {
+    if pricing.is_empty() {
+        return;
+    }
+    for event in events {
+        if event.cost_usd.is_some()
+            || (event.input_tokens.is_none() && event.output_tokens.is_none())
+        {
+            continue;
+        }
+        let Some(price) = event.model.as_deref().and_then(|model| pricing.get(model)) else {
+            continue;
+        };
+        let per_token = |tokens: Option<u64>, per_mtok: f64| {
+            tokens.unwrap_or_default() as f64 * per_mtok / 1_000_000.0
+        };
+        event.cost_usd = Some(
+            per_token(event.input_tokens, price.input_per_mtok)
+                + per_token(event.output_tokens, price.output_per_mtok),
+        );
+        event.metadata.insert(
+            COST_SOURCE_METADATA_KEY.to_string(),
+            Value::String("pricing".to_string()),
+        );
+    }
+}
+
+pub fn redact_events(
+    events: &mut [AgentLogEvent],
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) {
+    if policy == RedactionPolicy::Off {
+        return;
+    }
+    for event in events {
+        redact_event(event, policy, rules);
+    }
+}
+
+pub fn redact_event(event: &mut AgentLogEvent, policy: RedactionPolicy, rules: &RedactionRules) {
+    if policy == RedactionPolicy::Off {
+        return;
+    }
+
+    let mut classes = BTreeSet::new();
+    let mut redact_field = |field: &mut Option<String>| {
+        let Some(text) = field.as_deref() else {
+            return false;
+        };
+        let result = redaction::redact_text(text, policy, rules);
+        if !result.pii_redacted {
+            return false;
+        }
+        classes.extend(result.redaction_classes);
+        *field = Some(result.text);
+        true
+    };
+
+    let content_changed = redact_field(&mut event.content_text);
+    redact_field(&mut event.tool_result_text);
+    if content_changed {
+        event.content_excerpt = event
+            .content_text
+            .as_deref()
+            .and_then(|text| content::derive_excerpt(text, content::DEFAULT_EXCERPT_MAX_CHARS));
+    } else {
+        redact_field(&mut event.content_excerpt);
+    }
+    if let Some(arguments) = event.tool_arguments_json.as_deref() {
+        match serde_json::from_str::<Value>(arguments) {
+            Ok(parsed) => {
+                let result = redaction::redact_json(&parsed, policy, rules);
+                if result.pii_redacted {
+                    classes.extend(result.redaction_classes);
+                    event.tool_arguments_json = Some(result.value.to_string());
+                }
+            }
+            Err(_) => {
+                redact_field(&mut event.tool_arguments_json);
+            }
+        }
+    }
+
+    event.pii_redacted = Some(!classes.is_empty());
+    if classes.is_empty() {
+        return;
     }
+    event.metadata.insert(
+        REDACTION_CLASSES_METADATA_KEY.to_string(),
+        json!(classes.iter().collect::<Vec<_>>()),
+    );
+    // Both hashes are rebuilt from the redacted fields: the adapter's hashes
+    // cover the plaintext, so keeping (or re-keying) them would let a guessed
+    // secret be confirmed offline. Source coordinates stay out of the
+    // canonical hash so copies an adapter hashes path-independently (Codex
+    // history and rollout records) still dedupe once a secret is redacted.
+    let normalized_content = event
+        .content_text
+        .as_deref()
+        .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "));
+    event.canonical_hash = format!(
+        "{:016x}",
+        hash64(&(
+            "redacted.v2",
+            event.source_kind.as_str(),
+            event.session_id.as_deref(),
+            event.role,
+            event.timestamp_utc.as_str(),
+            normalized_content,
+            event.tool_name.as_deref(),
+            event.tool_call_id.as_deref(),
+            event.tool_arguments_json.as_deref(),
+            event.tool_result_text.as_deref(),
+        ))
+    );
+    event.raw_hash = format!(
+        "{:016x}",
+        hash64(&(
+            "redacted.raw.v1",
+            event.source_path.as_str(),
+            event.source_record_locator.as_str(),
+            event.canonical_hash.as_str(),
+        ))
+    );
+    event.source_record_hash = None;
 }
 
 pub fn write_events_artifact(path: &Path, events: &[AgentLogEvent]) -> Result<()> {
@@ -162,17 +378,14 @@ pub fn write_events_artifact(path: &Path, events: &[AgentLogEvent]) -> Result<()
         std::fs::create_dir_all(parent).context("failed to create events artifact directory")?;
     }
 
-    let file = std::fs::File::create(path).context("failed to create events artifact")?;
-    let mut writer = BufWriter::new(file);
+    let mut writer = AtomicFile::create(path).context("failed to create events artifact")?;
     for event in events {
         serde_json::to_writer(&mut writer, event).context("failed to encode events jsonl row")?;
         writer
             .write_all(b"\n")
             .context("failed to write events newline")?;
     }
-    writer
-        .flush()
-        .context("failed to flush events artifact writer")
+    writer.commit().context("failed to commit events artifact")
 }
 
 pub fn write_stats_artifact(path: &Path, stats: &NormalizeStats) -> Result<()> {
@@ -181,17 +394,18 @@ pub fn write_stats_artifact(path: &Path, stats: &NormalizeStats) -> Result<()> {
     }
 
     let encoded = serde_json::to_vec_pretty(stats).context("failed to encode stats json")?;
-    std::fs::write(path, encoded).context("failed to write stats artifact")
+    write_atomic(path, &encoded).context("failed to write stats artifact")
 }
 
 pub fn write_normalize_artifacts(
     layout: &ArtifactLayout,
     events: &[AgentLogEvent],
     dedupe_stats: DedupeStats,
+    redaction_policy: RedactionPolicy,
 ) -> Result<NormalizeStats> {
     write_events_artifact(&layout.events_jsonl, events)?;
     write_schema_artifact(&layout.schema_json)?;
-    let stats = build_normalize_stats(events, dedupe_stats);
+    let stats = build_normalize_stats(events, dedupe_stats, redaction_policy);
     write_stats_artifact(&layout.stats_json, &stats)?;
     Ok(stats)
 }
@@ -204,6 +418,15 @@ pub struct NormalizeOrchestrationResult {
     pub history_scores: Vec<HistoryScore>,
     pub warnings: Vec<String>,
     pub adapter_health: BTreeMap<String, AdapterHealthReport>,
+    pub filters: PipelineFilterRecord,
+    pub detected_inputs: Vec<DetectedInput>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedInput {
+    pub path: PathBuf,
+    pub fingerprint: ContentFingerprint,
+    pub overridden: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -296,19 +519,30 @@ pub fn orchestrate_normalization(
 ) -> Result<NormalizeOrchestrationResult> {
     let filter = SourceSelectionFilter {
         adapters: plan.adapters.clone(),
-        ..SourceSelectionFilter::default()
+        format_hints: plan.filters.format_hints.clone(),
+        path_substrings: plan.filters.path_substrings.clone(),
     };
     let history_scores = discovery::zsh_history_scores(zsh_history);
     let discovery_rules = discovery::known_path_registry();
-    let prioritized_sources =
-        discovery::prioritize_sources(&discovery_rules, &history_scores, &filter);
-    let run_id = "normalize-orchestrator-v1";
+    let prioritized_sources = if plan.adapters.is_empty() || !plan.inputs.is_empty() {
+        Vec::new()
+    } else {
+        discovery::prioritize_sources_with_roots(
+            &discovery_rules,
+            &history_scores,
+            &filter,
+            &plan.source_roots,
+        )
+    };
+    let mut filter_record = plan.filters.record();
+    let run_id = plan.run_id.as_str();
 
     let mut warnings = Vec::new();
     let mut events = Vec::new();
     let mut adapter_health = plan
         .adapters
         .iter()
+        .filter(|_| plan.inputs.is_empty())
         .map(|adapter| {
             (
                 adapter.as_str().to_string(),
@@ -316,6 +550,17 @@ pub fn orchestrate_normalization(
             )
         })
         .collect::<BTreeMap<_, _>>();
+    let detected_inputs = normalize_explicit_inputs(
+        plan,
+        run_id,
+        &mut events,
+        &mut warnings,
+        &mut adapter_health,
+        &mut filter_record,
+    )?;
+    // Aider histories live in repo roots; one walk serves all three file kinds.
+    let repo_scan_root = source_root_override.or(plan.repo_scan_root.as_deref());
+    let mut repo_history_files: Option<Vec<PathBuf>> = None;
     for source in &prioritized_sources {
         let health = adapter_health
             .entry(source.adapter.as_str().to_string())
@@ -334,21 +579,44 @@ pub fn orchestrate_normalization(
             continue;
         }
 
-        if !matches!(
-            source.format_hint,
-            SourceFormatHint::Directory | SourceFormatHint::Jsonl
-        ) {
+        let scans_repo_roots = source.adapter == AdapterKind::Aider;
+        if !scans_repo_roots
+            && !matches!(
+                source.format_hint,
+                SourceFormatHint::Directory | SourceFormatHint::Jsonl | SourceFormatHint::Sqlite
+            )
+        {
             continue;
         }
 
-        let resolved = resolve_candidate_path(&source.path, home_dir, source_root_override);
+        let resolved = if scans_repo_roots {
+            let Some(root) = repo_scan_root else {
+                continue;
+            };
+            root.to_path_buf()
+        } else {
+            resolve_candidate_path(&source.path, home_dir, source_root_override)
+        };
         if !resolved.exists() {
             warnings.push(format!("source path not found: {}", resolved.display()));
             continue;
         }
 
-        let candidate_files = match collect_parseable_files_resolved(&resolved, source) {
-            Ok(files) => files,
+        let collected = if scans_repo_roots {
+            match repo_history_files.take() {
+                Some(files) => Ok(files),
+                None => crate::adapters::aider::find_repo_history_files(&resolved),
+            }
+            .map(|files| {
+                let named = crate::adapters::aider::history_files_named(&files, &source.path);
+                repo_history_files = Some(files);
+                named
+            })
+        } else {
+            collect_parseable_files_resolved(&resolved, source)
+        };
+        let candidate_files = match collected {
+            Ok(files) => retain_files_in_window(files, plan, &mut filter_record),
             Err(error) if plan.fail_fast => {
                 return Err(error).with_context(|| {
                     format!(
@@ -373,6 +641,14 @@ pub fn orchestrate_normalization(
         for file in candidate_files {
             match parse_supported_source_file(source.adapter, source.role, &file, run_id) {
                 Ok((mut parsed_events, mut parse_warnings)) => {
+                    if source.profile != DEFAULT_PROFILE {
+                        for event in &mut parsed_events {
+                            event.metadata.insert(
+                                SOURCE_PROFILE_METADATA_KEY.to_string(),
+                                Value::String(source.profile.clone()),
+                            );
+                        }
+                    }
                     health.files_parsed += 1;
                     health.events_emitted += parsed_events.len();
                     health.warnings.extend(parse_warnings.iter().cloned());
@@ -401,6 +677,107 @@ pub fn orchestrate_normalization(
         }
     }
 
+    for spec in plan
+        .custom_adapters
+        .iter()
+        .filter(|_| plan.inputs.is_empty())
+    {
+        let spec_format = match spec.format {
+            crate::adapters::custom::CustomRecordLayout::Jsonl => SourceFormatHint::Jsonl,
+            crate::adapters::custom::CustomRecordLayout::Json => SourceFormatHint::Json,
+        };
+        if !spec.layout_from_extension && !plan.filters.admits_format(spec_format) {
+            continue;
+        }
+        let health = adapter_health.entry(spec.name.clone()).or_default();
+        for path in spec
+            .paths
+            .iter()
+            .filter(|path| plan.filters.admits_path(path))
+        {
+            health.sources_considered += 1;
+            let resolved = resolve_candidate_path(path, home_dir, source_root_override);
+            if !resolved.exists() {
+                warnings.push(format!("source path not found: {}", resolved.display()));
+                continue;
+            }
+
+            let candidate_files = match collect_custom_adapter_files(&resolved, spec) {
+                Ok(files) => retain_files_in_window(files, plan, &mut filter_record),
+                Err(error) if plan.fail_fast => {
+                    return Err(error).with_context(|| {
+                        format!(
+                            "failed to collect parseable files for adapter `{}` from `{}`",
+                            spec.name,
+                            resolved.display()
+                        )
+                    });
+                }
+                Err(error) => {
+                    let diagnostic = format!(
+                        "adapter `{}` source path unreadable `{}`: {error}",
+                        spec.name,
+                        resolved.display()
+                    );
+                    health.errors.push(diagnostic.clone());
+                    warnings.push(diagnostic);
+                    continue;
+                }
+            };
+            health.files_discovered += candidate_files.len();
+            for file in candidate_files {
+                match crate::adapters::custom::parse_custom_source_file(spec, &file, run_id) {
+                    Ok(mut parsed) => {
+                        health.files_parsed += 1;
+                        health.events_emitted += parsed.events.len();
+                        health.warnings.extend(parsed.warnings.iter().cloned());
+                        events.append(&mut parsed.events);
+                        warnings.append(&mut parsed.warnings);
+                    }
+                    Err(error) if plan.fail_fast => {
+                        return Err(error).with_context(|| {
+                            format!(
+                                "normalize orchestrator failed while parsing `{}` for adapter `{}`",
+                                file.display(),
+                                spec.name
+                            )
+                        });
+                    }
+                    Err(error) => {
+                        let diagnostic = format!(
+                            "adapter `{}` parse error for `{}`: {error}",
+                            spec.name,
+                            file.display()
+                        );
+                        health.errors.push(diagnostic.clone());
+                        warnings.push(diagnostic);
+                    }
+                }
+            }
+        }
+    }
+
+    let parsed_count = events.len();
+    events.retain(|event| plan.filters.window.contains(event.timestamp_unix_ms));
+    filter_record.events_outside_window = parsed_count - events.len();
+    for event in &mut events {
+        for tag in &plan.tags {
+            if !event.tags.contains(tag) {
+                event.tags.push(tag.clone());
+            }
+        }
+        if let Some(host) = &plan.host {
+            stamp_host(event, host);
+        }
+    }
+    apply_pricing(&mut events, &plan.pricing);
+
+    redact_events(&mut events, plan.redaction_policy, &plan.redaction_rules);
+    // After redaction, so canonical hash tombstones compare against the same
+    // hashes `events.jsonl` and the mart store.
+    let redacted_count = events.len();
+    events.retain(|event| !purge::matches_any(&plan.tombstones, &EventKey::from_event(event)));
+    filter_record.events_tombstoned = redacted_count - events.len();
     let (events, dedupe_stats) = dedupe_and_sort_events(events);
     let adapter_health = adapter_health
         .into_iter()
@@ -413,9 +790,165 @@ pub fn orchestrate_normalization(
         history_scores,
         warnings,
         adapter_health,
+        filters: filter_record,
+        detected_inputs,
     })
 }
 
+fn normalize_explicit_inputs(
+    plan: &NormalizationPlan,
+    run_id: &str,
+    events: &mut Vec<AgentLogEvent>,
+    warnings: &mut Vec<String>,
+    adapter_health: &mut BTreeMap<String, AdapterHealthAccumulator>,
+    filter_record: &mut PipelineFilterRecord,
+) -> Result<Vec<DetectedInput>> {
+    let mut detected_inputs = Vec::new();
+    for input in &plan.inputs {
+        let files = if input.is_file() {
+            vec![input.clone()]
+        } else if input.is_dir() {
+            let mut files = Vec::new();
+            collect_dir_files(input, true, &mut files)?;
+            files.sort();
+            files
+        } else {
+            anyhow::bail!("normalize input not found: {}", input.display());
+        };
+        let files = files
+            .into_iter()
+            .filter(|file| {
+                plan.filters.admits_path(&file.to_string_lossy())
+                    && (plan.filters.format_hints.is_empty()
+                        || SourceFormatHint::from_source_path(&file.to_string_lossy())
+                            .is_some_and(|hint| plan.filters.admits_format(hint)))
+            })
+            .collect::<Vec<_>>();
+        let files = retain_files_in_window(files, plan, filter_record);
+
+        let mut undetected = 0_usize;
+        for file in files {
+            let detection = match classifier::detect_adapter_file(&file) {
+                Ok(detection) => detection,
+                Err(error) if plan.fail_fast => return Err(error),
+                Err(error) => {
+                    warnings.push(format!("{error:#}"));
+                    continue;
+                }
+            };
+            let (fingerprint, overridden) = match (plan.input_adapter, &detection) {
+                (Some(adapter), _) => (detection.resolve_with_override(adapter, &file), true),
+                (None, AdapterDetection::Detected(fingerprint)) => (*fingerprint, false),
+                (None, AdapterDetection::Ambiguous(candidates)) => {
+                    let names = candidates
+                        .iter()
+                        .map(|candidate| candidate.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let diagnostic = format!(
+                        "ambiguous adapter fingerprint for `{}` ({names}); pass --input-adapter to choose one",
+                        file.display()
+                    );
+                    if plan.fail_fast {
+                        anyhow::bail!(diagnostic);
+                    }
+                    warnings.push(diagnostic);
+                    continue;
+                }
+                (None, AdapterDetection::Unknown) => {
+                    if is_archive_file(&file) {
+                        warnings.push(format!(
+                            "archive inputs are not unpacked; extract `{}` and pass the directory",
+                            file.display()
+                        ));
+                    } else if input.is_file() {
+                        warnings.push(format!(
+                            "no adapter fingerprint matched `{}`; skipped",
+                            file.display()
+                        ));
+                    }
+                    undetected += 1;
+                    continue;
+                }
+            };
+            let adapter = fingerprint.adapter();
+            if !plan.adapters.contains(&adapter) {
+                continue;
+            }
+            detected_inputs.push(DetectedInput {
+                path: file.clone(),
+                fingerprint,
+                overridden,
+            });
+
+            let health = adapter_health
+                .entry(adapter.as_str().to_string())
+                .or_default();
+            health.sources_considered += 1;
+            health.files_discovered += 1;
+            match parse_fingerprinted_file(fingerprint, &file, run_id) {
+                Ok(Some((mut parsed_events, mut parse_warnings))) => {
+                    health.files_parsed += 1;
+                    health.events_emitted += parsed_events.len();
+                    health.warnings.extend(parse_warnings.iter().cloned());
+                    events.append(&mut parsed_events);
+                    warnings.append(&mut parse_warnings);
+                }
+                Ok(None) => {
+                    let warning = format!(
+                        "adapter `{}` detected `{}` for `{}` but that shape is not yet supported by normalize orchestrator; skipped",
+                        adapter.as_str(),
+                        fingerprint.as_str(),
+                        file.display()
+                    );
+                    health.unsupported = true;
+                    health.warnings.push(warning.clone());
+                    warnings.push(warning);
+                }
+                Err(error) if plan.fail_fast => {
+                    return Err(error).with_context(|| {
+                        format!(
+                            "normalize orchestrator failed while parsing `{}` as `{}`",
+                            file.display(),
+                            fingerprint.as_str()
+                        )
+                    });
+                }
+                Err(error) => {
+                    let diagnostic = format!(
+                        "adapter `{}` parse error for `{}`: {error}",
+                        adapter.as_str(),
+                        file.display()
+                    );
+                    health.errors.push(diagnostic.clone());
+                    warnings.push(diagnostic);
+                }
+            }
+        }
+        if undetected > 0 && input.is_dir() {
+            warnings.push(format!(
+                "skipped {undetected} file(s) with no adapter fingerprint under `{}`",
+                input.display()
+            ));
+        }
+    }
+    Ok(detected_inputs)
+}
+
+fn retain_files_in_window(
+    files: Vec<PathBuf>,
+    plan: &NormalizationPlan,
+    filter_record: &mut PipelineFilterRecord,
+) -> Vec<PathBuf> {
+    let total = files.len();
+    let kept = files
+        .into_iter()
+        .filter(|file| plan.filters.window.admits_file(file))
+        .collect::<Vec<_>>();
+    filter_record.files_pruned_by_mtime += total - kept.len();
+    kept
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct DedupeStats {
     pub input_records: usize,
@@ -555,7 +1088,7 @@ fn dedupe_key_for(event: &AgentLogEvent, strategy: DedupeStrategy) -> String {
         DedupeStrategy::CanonicalHash => format!("canonical:{}", event.canonical_hash),
         DedupeStrategy::FallbackA => format!(
             "a:{}|{}|{}|{}|{}",
-            source_kind_key(event.source_kind),
+            event.source_kind.as_str(),
             event.conversation_id.as_deref().unwrap_or(""),
             event.turn_id.as_deref().unwrap_or(""),
             role_key(event),
@@ -563,7 +1096,7 @@ fn dedupe_key_for(event: &AgentLogEvent, strategy: DedupeStrategy) -> String {
         ),
         DedupeStrategy::FallbackB => format!(
             "b:{}|{}|{}",
-            source_kind_key(event.source_kind),
+            event.source_kind.as_str(),
             event.source_path,
             event.source_record_locator
         ),
@@ -579,11 +1112,20 @@ fn content_key(content: Option<&str>) -> String {
 fn adapter_supported_for_v1(adapter: AdapterKind) -> bool {
     matches!(
         adapter,
-        AdapterKind::Codex | AdapterKind::Claude | AdapterKind::Gemini | AdapterKind::Amp
+        AdapterKind::Codex
+            | AdapterKind::Claude
+            | AdapterKind::Gemini
+            | AdapterKind::Amp
+            | AdapterKind::Aider
+            | AdapterKind::Cursor
+            | AdapterKind::Copilot
     )
 }
 
-fn resolve_candidate_path(
+/// Expands `~/` candidates against `source_root_override` (when given) or the
+/// home directory; other relative candidates resolve under the override only.
+#[must_use]
+pub fn resolve_candidate_path(
     candidate: &str,
     home_dir: &Path,
     source_root_override: Option<&Path>,
@@ -606,10 +1148,17 @@ fn resolve_candidate_path(
     PathBuf::from(candidate)
 }
 
-fn collect_parseable_files_resolved(
+/// Files under `resolved` that the orchestrator would hand to the source's
+/// adapter parser.
+pub fn collect_parseable_files_resolved(
     resolved: &Path,
     source: &PrioritizedSource,
 ) -> Result<Vec<PathBuf>> {
+    if source.adapter == AdapterKind::Aider {
+        return crate::adapters::aider::find_repo_history_files(resolved)
+            .map(|files| crate::adapters::aider::history_files_named(&files, &source.path));
+    }
+
     if resolved.is_file() {
         return Ok(vec![resolved.to_path_buf()]);
     }
@@ -625,6 +1174,22 @@ fn collect_parseable_files_resolved(
     Ok(files)
 }
 
+fn collect_custom_adapter_files(resolved: &Path, spec: &CustomAdapterSpec) -> Result<Vec<PathBuf>> {
+    if resolved.is_file() {
+        return Ok(vec![resolved.to_path_buf()]);
+    }
+
+    if !resolved.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files = Vec::new();
+    collect_dir_files(resolved, spec.recursive, &mut files)?;
+    files.sort();
+    files.retain(|path| crate::adapters::custom::matches_file_glob(spec, path));
+    Ok(files)
+}
+
 fn is_parseable_source_file(path: &Path, source: &PrioritizedSource) -> bool {
     let extension = path
         .extension()
@@ -639,7 +1204,7 @@ fn is_parseable_source_file(path: &Path, source: &PrioritizedSource) -> bool {
         }
         AdapterKind::Gemini => matches!(
             extension.as_deref(),
-            Some("json") | Some("jsonl") | Some("ndjson")
+            Some("json") | Some("jsonl") | Some("ndjson") | Some("pb")
         ),
         AdapterKind::Amp => {
             if amp_file_changes_source {
@@ -655,9 +1220,22 @@ fn is_parseable_source_file(path: &Path, source: &PrioritizedSource) -> bool {
             extension.as_deref(),
             Some("json") | Some("jsonl") | Some("ndjson")
         ),
+        AdapterKind::Aider => path
+            .file_name()
+            .and_then(std::ffi::OsStr::to_str)
+            .is_some_and(|name| name == source.path),
+        AdapterKind::Cursor => is_state_db_file(path),
+        AdapterKind::Copilot => {
+            is_state_db_file(path) || crate::adapters::copilot::is_chat_session_file(path)
+        }
     }
 }
 
+fn is_state_db_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == crate::utils::vscdb::STATE_DB_FILE_NAME)
+}
+
 fn collect_dir_files(dir: &Path, recursive: bool, out: &mut Vec<PathBuf>) -> Result<()> {
     let mut entries = std::fs::read_dir(dir)
         .with_context(|| format!("failed to read source directory: {}", dir.display()))?
@@ -705,6 +1283,11 @@ fn parse_supported_source_file(
                 .unwrap_or_default()
                 .to_ascii_lowercase();
             let source_path = path.to_string_lossy();
+            if file_name.ends_with(".pb") {
+                let parsed =
+                    crate::adapters::gemini::parse_conversation_protobuf_file(path, run_id)?;
+                return Ok((parsed.events, parsed.warnings));
+            }
             if file_name == "logs.json" {
                 let parsed = crate::adapters::gemini::parse_logs_file(path, run_id)?;
                 return Ok((parsed.events, parsed.warnings));
@@ -743,9 +1326,115 @@ fn parse_supported_source_file(
                 path.display()
             )],
         )),
+        AdapterKind::Aider => match source_role {
+            DiscoveryPathRole::SessionStore => {
+                let parsed = crate::adapters::aider::parse_chat_history_file(path, run_id)?;
+                Ok((parsed.events, parsed.warnings))
+            }
+            DiscoveryPathRole::HistoryStream => {
+                let parsed = crate::adapters::aider::parse_input_history_file(path, run_id)?;
+                Ok((parsed.events, parsed.warnings))
+            }
+            _ => {
+                let parsed = crate::adapters::aider::parse_llm_history_file(path, run_id)?;
+                Ok((parsed.events, parsed.warnings))
+            }
+        },
+        AdapterKind::Cursor => {
+            let parsed = crate::adapters::cursor::parse_state_db_file(path, run_id)?;
+            Ok((parsed.events, parsed.warnings))
+        }
+        AdapterKind::Copilot => {
+            let parsed = if crate::adapters::copilot::is_chat_session_file(path) {
+                crate::adapters::copilot::parse_chat_session_file(path, run_id)?
+            } else {
+                crate::adapters::copilot::parse_state_db_file(path, run_id)?
+            };
+            Ok((parsed.events, parsed.warnings))
+        }
     }
 }
 
+fn is_archive_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(std::ffi::OsStr::to_str)
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    [".tar", ".tar.gz", ".tgz", ".zip"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+/// Parses a file with the parser named by its content fingerprint; `None`
+/// means the shape is recognised but has no event mapping yet.
+fn parse_fingerprinted_file(
+    fingerprint: ContentFingerprint,
+    path: &Path,
+    run_id: &str,
+) -> Result<Option<(Vec<AgentLogEvent>, Vec<String>)>> {
+    use crate::adapters::{aider, amp, claude, codex, copilot, cursor, gemini};
+
+    let parsed = match fingerprint {
+        ContentFingerprint::CodexRollout => {
+            let parsed = codex::parse_rollout_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::CodexHistory => {
+            let parsed = codex::parse_history_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::ClaudeSession => {
+            let parsed = claude::parse_project_session_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::GeminiChat => {
+            let parsed = gemini::parse_chat_session_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::GeminiLogs => {
+            let parsed = gemini::parse_logs_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::GeminiProtobuf => {
+            let parsed = gemini::parse_conversation_protobuf_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::AmpFileChange => {
+            let parsed = amp::parse_file_change_event_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::AiderChatHistory => {
+            let parsed = aider::parse_chat_history_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::AiderInputHistory => {
+            let parsed = aider::parse_input_history_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::AiderLlmHistory => {
+            let parsed = aider::parse_llm_history_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::CursorStateDb => {
+            let parsed = cursor::parse_state_db_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::CopilotStateDb => {
+            let parsed = copilot::parse_state_db_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::CopilotChatSession => {
+            let parsed = copilot::parse_chat_session_file(path, run_id)?;
+            (parsed.events, parsed.warnings)
+        }
+        ContentFingerprint::AmpThread
+        | ContentFingerprint::OpenCodeMessages
+        | ContentFingerprint::OpenCodeParts => return Ok(None),
+    };
+    Ok(Some(parsed))
+}
+
 fn seeded_counts(keys: &[&str]) -> BTreeMap<String, usize> {
     keys.iter()
         .map(|key| ((*key).to_string(), 0_usize))
@@ -782,7 +1471,7 @@ fn compare_events(left: &AgentLogEvent, right: &AgentLogEvent) -> Ordering {
             timestamp_quality_rank(left.timestamp_quality)
                 .cmp(&timestamp_quality_rank(right.timestamp_quality))
         })
-        .then_with(|| source_kind_key(left.source_kind).cmp(source_kind_key(right.source_kind)))
+        .then_with(|| left.source_kind.as_str().cmp(right.source_kind.as_str()))
         .then_with(|| left.source_path.cmp(&right.source_path))
         .then_with(|| left.source_record_locator.cmp(&right.source_record_locator))
         .then_with(|| {
@@ -810,16 +1499,6 @@ const fn timestamp_quality_key(quality: TimestampQuality) -> &'static str {
     }
 }
 
-const fn source_kind_key(kind: AgentSource) -> &'static str {
-    match kind {
-        AgentSource::Codex => "codex",
-        AgentSource::Claude => "claude",
-        AgentSource::Gemini => "gemini",
-        AgentSource::Amp => "amp",
-        AgentSource::OpenCode => "opencode",
-    }
-}
-
 const fn record_format_key(record_format: RecordFormat) -> &'static str {
     match record_format {
         RecordFormat::Message => "message",
@@ -858,7 +1537,7 @@ fn role_key(event: &AgentLogEvent) -> &'static str {
 fn provenance_key(event: &AgentLogEvent) -> String {
     format!(
         "{}|{}|{}|{}",
-        source_kind_key(event.source_kind),
+        event.source_kind.as_str(),
         event.source_path,
         event.source_record_locator,
         event.raw_hash
@@ -867,11 +1546,11 @@ fn provenance_key(event: &AgentLogEvent) -> String {
 
 fn provenance_entry(event: &AgentLogEvent) -> Value {
     json!({
-        "source_kind": source_kind_key(event.source_kind),
+        "source_kind": event.source_kind.as_str(),
         "source_path": event.source_path,
         "source_record_locator": event.source_record_locator,
         "raw_hash": event.raw_hash,
-        "adapter_name": source_kind_key(event.adapter_name),
+        "adapter_name": event.adapter_name.as_str(),
         "adapter_version": event.adapter_version,
     })
 }
@@ -0,0 +1,99 @@
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+
+pub const COLLECTOR_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CollectorEndpoint {
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl CollectorEndpoint {
+    /// Accepts `http://host[:port][/path]`; the path defaults to `/v1/traces`
+    /// and the port to the OTLP/HTTP default `4318`. TLS is not supported since
+    /// the target is a local collector.
+    pub fn parse(raw: &str) -> Result<Self> {
+        let Some(rest) = raw.strip_prefix("http://") else {
+            bail!("otlp endpoint must start with http:// (got `{raw}`)");
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (
+                host,
+                port.parse::<u16>()
+                    .with_context(|| format!("invalid otlp endpoint port in `{raw}`"))?,
+            ),
+            None => (authority, 4318),
+        };
+        if host.is_empty() {
+            bail!("otlp endpoint is missing a host (got `{raw}`)");
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: if path.is_empty() || path == "/" {
+                super::TRACES_PATH.to_string()
+            } else {
+                path.to_string()
+            },
+        })
+    }
+
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("http://{}:{}{}", self.host, self.port, self.path)
+    }
+}
+
+/// POSTs one OTLP/JSON payload and returns the collector's status code;
+/// non-2xx responses are errors carrying the response body.
+pub fn post_json(endpoint: &CollectorEndpoint, body: &str, timeout: Duration) -> Result<u16> {
+    let mut stream = TcpStream::connect((endpoint.host.as_str(), endpoint.port))
+        .with_context(|| format!("failed to connect to otlp collector {}", endpoint.url()))?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .and_then(|()| stream.set_write_timeout(Some(timeout)))
+        .context("failed to configure otlp collector connection")?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        endpoint.path,
+        endpoint.host,
+        endpoint.port,
+        body.len()
+    )
+    .and_then(|()| stream.flush())
+    .with_context(|| format!("failed to send otlp payload to {}", endpoint.url()))?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).with_context(|| {
+        format!(
+            "failed to read otlp collector response from {}",
+            endpoint.url()
+        )
+    })?;
+    let status = response
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|status| status.parse::<u16>().ok())
+        .with_context(|| format!("malformed otlp collector response from {}", endpoint.url()))?;
+    if !(200..300).contains(&status) {
+        let body = response
+            .split_once("\r\n\r\n")
+            .map_or("", |(_, body)| body.trim());
+        bail!(
+            "otlp collector {} rejected payload with status {status}: {body}",
+            endpoint.url()
+        );
+    }
+    Ok(status)
+}
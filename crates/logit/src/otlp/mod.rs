@@ -0,0 +1,543 @@
+pub mod collector;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::sqlite::{EVENTS_TABLE, TOOL_CALLS_VIEW};
+use crate::transcript::{TranscriptEvent, TranscriptTurn, group_turns, load_session_events};
+use crate::utils::fs::write_atomic;
+
+pub const SCOPE_NAME: &str = "logit.otlp";
+pub const SERVICE_NAME: &str = "logit";
+pub const TRACES_PATH: &str = "/v1/traces";
+
+const SPAN_KIND_INTERNAL: u8 = 1;
+const SPAN_KIND_CLIENT: u8 = 3;
+const STATUS_CODE_UNSET: u8 = 0;
+const STATUS_CODE_ERROR: u8 = 2;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OtlpExportOptions {
+    pub include_content: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionTrace {
+    pub session_id: String,
+    pub trace_id: String,
+    pub span_count: usize,
+    pub document: Value,
+}
+
+impl SessionTrace {
+    pub fn encode(&self) -> Result<String> {
+        serde_json::to_string_pretty(&self.document).context("failed to encode otlp trace")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ToolSpanRow {
+    tool_name: Option<String>,
+    tool_call_id: Option<String>,
+    call_event_id: Option<String>,
+    result_event_id: Option<String>,
+    start_unix_ms: u64,
+    duration_ms: Option<u64>,
+    pairing_status: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct SessionAttributes {
+    adapter_name: Option<String>,
+    provider: Option<String>,
+}
+
+/// 16-byte trace id, hex encoded, derived from the session id alone so that
+/// re-exporting a session always lands on the same trace.
+#[must_use]
+pub fn trace_id(session_id: &str) -> String {
+    digest_hex(&format!("logit:otlp:trace:{session_id}"), 16)
+}
+
+/// 8-byte span id, hex encoded, derived from the session id and a span key
+/// (`session`, or `turn:`/`tool:` followed by the anchoring event id).
+#[must_use]
+pub fn span_id(session_id: &str, key: &str) -> String {
+    digest_hex(&format!("logit:otlp:span:{session_id}\u{1f}{key}"), 8)
+}
+
+fn digest_hex(input: &str, bytes: usize) -> String {
+    Sha256::digest(input.as_bytes())
+        .iter()
+        .take(bytes)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub fn session_ids(connection: &Connection) -> Result<Vec<String>> {
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT DISTINCT session_id FROM {EVENTS_TABLE}
+             WHERE session_id IS NOT NULL AND session_id != ''
+             ORDER BY session_id"
+        ))
+        .context("failed to prepare otlp session query")?;
+    let rows = statement
+        .query_map([], |row| row.get::<_, String>(0))
+        .context("failed to execute otlp session query")?;
+    rows.map(|row| row.context("failed to decode otlp session row"))
+        .collect()
+}
+
+pub fn build_session_trace(
+    connection: &Connection,
+    session_id: &str,
+    options: OtlpExportOptions,
+) -> Result<SessionTrace> {
+    let events = load_session_events(connection, session_id)?;
+    if events.is_empty() {
+        bail!("session `{session_id}` has no events in the mart");
+    }
+    let attributes = load_session_attributes(connection, session_id)?;
+    let tool_rows = load_tool_span_rows(connection, session_id)?;
+    let events_by_id = events
+        .iter()
+        .map(|event| (event.event_id.clone(), event.clone()))
+        .collect::<BTreeMap<_, _>>();
+    let turns = group_turns(events);
+
+    let trace_id = trace_id(session_id);
+    let root_span_id = span_id(session_id, "session");
+    let mut turn_span_by_event = BTreeMap::<&str, String>::new();
+    let mut spans = Vec::new();
+    for turn in &turns {
+        let Some(first) = turn.events.first() else {
+            continue;
+        };
+        let turn_span_id = span_id(session_id, &format!("turn:{}", first.event_id));
+        for event in &turn.events {
+            turn_span_by_event.insert(event.event_id.as_str(), turn_span_id.clone());
+        }
+        spans.push(turn_span(
+            session_id,
+            &trace_id,
+            &root_span_id,
+            &turn_span_id,
+            turn,
+            &attributes,
+            options,
+        ));
+    }
+    for row in &tool_rows {
+        let anchor = row
+            .call_event_id
+            .as_deref()
+            .or(row.result_event_id.as_deref())
+            .unwrap_or_default();
+        let parent = turn_span_by_event
+            .get(anchor)
+            .cloned()
+            .unwrap_or_else(|| root_span_id.clone());
+        spans.push(tool_span(
+            session_id,
+            &trace_id,
+            &parent,
+            row,
+            &events_by_id,
+            options,
+        ));
+    }
+    spans.insert(
+        0,
+        root_span(session_id, &trace_id, &root_span_id, &turns, &attributes),
+    );
+
+    let span_count = spans.len();
+    let document = json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [
+                    string_attribute("service.name", SERVICE_NAME),
+                    string_attribute("service.version", env!("CARGO_PKG_VERSION")),
+                ]
+            },
+            "scopeSpans": [{
+                "scope": { "name": SCOPE_NAME, "version": env!("CARGO_PKG_VERSION") },
+                "spans": spans
+            }]
+        }]
+    });
+    Ok(SessionTrace {
+        session_id: session_id.to_string(),
+        trace_id,
+        span_count,
+        document,
+    })
+}
+
+#[must_use]
+pub fn trace_file_path(output_dir: &Path, trace: &SessionTrace) -> PathBuf {
+    output_dir.join(format!("{}.json", trace.trace_id))
+}
+
+pub fn write_trace(output_dir: &Path, trace: &SessionTrace) -> Result<PathBuf> {
+    std::fs::create_dir_all(output_dir).with_context(|| {
+        format!(
+            "failed to create otlp output directory: {}",
+            output_dir.display()
+        )
+    })?;
+    let path = trace_file_path(output_dir, trace);
+    write_atomic(&path, format!("{}\n", trace.encode()?).as_bytes())
+        .with_context(|| format!("failed to write otlp trace: {}", path.display()))?;
+    Ok(path)
+}
+
+fn load_session_attributes(connection: &Connection, session_id: &str) -> Result<SessionAttributes> {
+    connection
+        .query_row(
+            &format!(
+                "SELECT MIN(adapter_name), MIN(provider) FROM {EVENTS_TABLE} WHERE session_id = ?1"
+            ),
+            [session_id],
+            |row| {
+                Ok(SessionAttributes {
+                    adapter_name: row.get(0)?,
+                    provider: row.get(1)?,
+                })
+            },
+        )
+        .optional()
+        .context("failed to query otlp session attributes")
+        .map(Option::unwrap_or_default)
+}
+
+fn load_tool_span_rows(connection: &Connection, session_id: &str) -> Result<Vec<ToolSpanRow>> {
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT tool_name, tool_call_id, call_event_id, result_event_id,
+                    COALESCE(call_timestamp_unix_ms, result_timestamp_unix_ms),
+                    duration_ms, pairing_status
+             FROM {TOOL_CALLS_VIEW}
+             WHERE session_id = ?1
+             ORDER BY COALESCE(call_timestamp_unix_ms, result_timestamp_unix_ms),
+                      COALESCE(call_event_id, result_event_id)"
+        ))
+        .context("failed to prepare otlp tool call query")?;
+    let rows = statement
+        .query_map([session_id], |row| {
+            let unsigned = |index: usize| -> rusqlite::Result<Option<u64>> {
+                Ok(row
+                    .get::<usize, Option<i64>>(index)?
+                    .and_then(|value| u64::try_from(value).ok()))
+            };
+            Ok(ToolSpanRow {
+                tool_name: row.get(0)?,
+                tool_call_id: row.get(1)?,
+                call_event_id: row.get(2)?,
+                result_event_id: row.get(3)?,
+                start_unix_ms: unsigned(4)?.unwrap_or(0),
+                duration_ms: unsigned(5)?,
+                pairing_status: row.get(6)?,
+            })
+        })
+        .context("failed to execute otlp tool call query")?;
+    rows.map(|row| row.context("failed to decode otlp tool call row"))
+        .collect()
+}
+
+fn root_span(
+    session_id: &str,
+    trace_id: &str,
+    root_span_id: &str,
+    turns: &[TranscriptTurn],
+    attributes: &SessionAttributes,
+) -> Value {
+    let events = turns.iter().flat_map(|turn| &turn.events);
+    let start = events
+        .clone()
+        .map(|event| event.timestamp_unix_ms)
+        .min()
+        .unwrap_or(0);
+    let end = events
+        .clone()
+        .map(|event| event.timestamp_unix_ms)
+        .max()
+        .unwrap_or(start);
+    let has_error = events.clone().any(|event| event.event_type == "error");
+    let sum = |select: fn(&TranscriptEvent) -> Option<u64>| {
+        events
+            .clone()
+            .filter_map(select)
+            .fold(None, |total: Option<u64>, value| {
+                Some(total.unwrap_or(0) + value)
+            })
+    };
+    let agent = attributes.adapter_name.as_deref().unwrap_or("agent");
+
+    let mut span_attributes = vec![
+        string_attribute("gen_ai.operation.name", "invoke_agent"),
+        string_attribute("gen_ai.agent.name", agent),
+        string_attribute("gen_ai.conversation.id", session_id),
+    ];
+    push_optional_string(
+        &mut span_attributes,
+        "gen_ai.provider.name",
+        attributes.provider.as_deref(),
+    );
+    push_optional_int(
+        &mut span_attributes,
+        "gen_ai.usage.input_tokens",
+        sum(|event| event.input_tokens),
+    );
+    push_optional_int(
+        &mut span_attributes,
+        "gen_ai.usage.output_tokens",
+        sum(|event| event.output_tokens),
+    );
+    span_attributes.push(int_attribute("logit.turn_count", turns.len() as u64));
+    span_attributes.push(int_attribute("logit.duration_ms", end - start));
+
+    span(SpanFields {
+        trace_id,
+        span_id: root_span_id,
+        parent_span_id: None,
+        name: format!("invoke_agent {agent}"),
+        kind: SPAN_KIND_INTERNAL,
+        start_unix_ms: start,
+        end_unix_ms: end,
+        attributes: span_attributes,
+        events: Vec::new(),
+        error: has_error,
+    })
+}
+
+fn turn_span(
+    session_id: &str,
+    trace_id: &str,
+    root_span_id: &str,
+    turn_span_id: &str,
+    turn: &TranscriptTurn,
+    attributes: &SessionAttributes,
+    options: OtlpExportOptions,
+) -> Value {
+    let start = turn
+        .events
+        .iter()
+        .map(|event| event.timestamp_unix_ms)
+        .min()
+        .unwrap_or(0);
+    let model = turn.events.iter().find_map(|event| event.model.as_deref());
+    let (input_tokens, output_tokens, _) = turn.token_totals();
+
+    let mut span_attributes = vec![
+        string_attribute("gen_ai.operation.name", "chat"),
+        string_attribute("gen_ai.conversation.id", session_id),
+    ];
+    push_optional_string(
+        &mut span_attributes,
+        "gen_ai.provider.name",
+        attributes.provider.as_deref(),
+    );
+    push_optional_string(&mut span_attributes, "gen_ai.request.model", model);
+    push_optional_int(
+        &mut span_attributes,
+        "gen_ai.usage.input_tokens",
+        input_tokens,
+    );
+    push_optional_int(
+        &mut span_attributes,
+        "gen_ai.usage.output_tokens",
+        output_tokens,
+    );
+    span_attributes.push(int_attribute("logit.turn.ordinal", turn.ordinal as u64));
+    push_optional_string(
+        &mut span_attributes,
+        "logit.turn_id",
+        turn.turn_id.as_deref(),
+    );
+    span_attributes.push(int_attribute("logit.duration_ms", turn.duration_ms()));
+
+    let span_events = turn
+        .events
+        .iter()
+        .filter_map(|event| span_event(event, options))
+        .collect::<Vec<_>>();
+    span(SpanFields {
+        trace_id,
+        span_id: turn_span_id,
+        parent_span_id: Some(root_span_id),
+        name: model.map_or_else(|| "chat".to_string(), |model| format!("chat {model}")),
+        kind: SPAN_KIND_CLIENT,
+        start_unix_ms: start,
+        end_unix_ms: start + turn.duration_ms(),
+        attributes: span_attributes,
+        events: span_events,
+        error: turn.events.iter().any(|event| event.event_type == "error"),
+    })
+}
+
+fn tool_span(
+    session_id: &str,
+    trace_id: &str,
+    parent_span_id: &str,
+    row: &ToolSpanRow,
+    events_by_id: &BTreeMap<String, TranscriptEvent>,
+    options: OtlpExportOptions,
+) -> Value {
+    let anchor = row
+        .call_event_id
+        .as_deref()
+        .or(row.result_event_id.as_deref())
+        .unwrap_or_default();
+    let tool_name = row.tool_name.as_deref().unwrap_or("tool");
+
+    let mut span_attributes = vec![
+        string_attribute("gen_ai.operation.name", "execute_tool"),
+        string_attribute("gen_ai.tool.name", tool_name),
+    ];
+    push_optional_string(
+        &mut span_attributes,
+        "gen_ai.tool.call.id",
+        row.tool_call_id.as_deref(),
+    );
+    span_attributes.push(string_attribute(
+        "logit.pairing_status",
+        &row.pairing_status,
+    ));
+    push_optional_int(&mut span_attributes, "logit.duration_ms", row.duration_ms);
+    if options.include_content {
+        let event = |id: Option<&String>| id.and_then(|id| events_by_id.get(id));
+        push_optional_string(
+            &mut span_attributes,
+            "gen_ai.tool.call.arguments",
+            event(row.call_event_id.as_ref())
+                .and_then(|event| event.tool_arguments_json.as_deref()),
+        );
+        push_optional_string(
+            &mut span_attributes,
+            "gen_ai.tool.call.result",
+            event(row.result_event_id.as_ref()).and_then(|event| {
+                event
+                    .tool_result_text
+                    .as_deref()
+                    .or(event.content_text.as_deref())
+            }),
+        );
+    }
+
+    span(SpanFields {
+        trace_id,
+        span_id: &span_id(session_id, &format!("tool:{anchor}")),
+        parent_span_id: Some(parent_span_id),
+        name: format!("execute_tool {tool_name}"),
+        kind: SPAN_KIND_INTERNAL,
+        start_unix_ms: row.start_unix_ms,
+        end_unix_ms: row.start_unix_ms + row.duration_ms.unwrap_or(0),
+        attributes: span_attributes,
+        events: Vec::new(),
+        error: false,
+    })
+}
+
+fn span_event(event: &TranscriptEvent, options: OtlpExportOptions) -> Option<Value> {
+    let (name, content_key) = match event.event_type.as_str() {
+        "prompt" => ("gen_ai.content.prompt", "gen_ai.prompt"),
+        "response" => ("gen_ai.content.completion", "gen_ai.completion"),
+        "error" => ("exception", "exception.message"),
+        _ => return None,
+    };
+    let mut attributes = vec![string_attribute("logit.event_id", &event.event_id)];
+    if event.event_type == "error" {
+        attributes.push(string_attribute("exception.type", "agent_error"));
+    }
+    if event.event_type == "response" {
+        push_optional_int(
+            &mut attributes,
+            "gen_ai.usage.input_tokens",
+            event.input_tokens,
+        );
+        push_optional_int(
+            &mut attributes,
+            "gen_ai.usage.output_tokens",
+            event.output_tokens,
+        );
+    }
+    if options.include_content {
+        push_optional_string(
+            &mut attributes,
+            content_key,
+            event
+                .content_text
+                .as_deref()
+                .or(event.content_excerpt.as_deref()),
+        );
+    }
+    Some(json!({
+        "timeUnixNano": unix_nanos(event.timestamp_unix_ms),
+        "name": name,
+        "attributes": attributes,
+    }))
+}
+
+struct SpanFields<'a> {
+    trace_id: &'a str,
+    span_id: &'a str,
+    parent_span_id: Option<&'a str>,
+    name: String,
+    kind: u8,
+    start_unix_ms: u64,
+    end_unix_ms: u64,
+    attributes: Vec<Value>,
+    events: Vec<Value>,
+    error: bool,
+}
+
+fn span(fields: SpanFields<'_>) -> Value {
+    let mut span = json!({
+        "traceId": fields.trace_id,
+        "spanId": fields.span_id,
+        "name": fields.name,
+        "kind": fields.kind,
+        "startTimeUnixNano": unix_nanos(fields.start_unix_ms),
+        "endTimeUnixNano": unix_nanos(fields.end_unix_ms.max(fields.start_unix_ms)),
+        "attributes": fields.attributes,
+        "events": fields.events,
+        "status": {
+            "code": if fields.error { STATUS_CODE_ERROR } else { STATUS_CODE_UNSET }
+        },
+    });
+    if let Some(parent_span_id) = fields.parent_span_id {
+        span["parentSpanId"] = json!(parent_span_id);
+    }
+    span
+}
+
+fn unix_nanos(timestamp_unix_ms: u64) -> String {
+    (u128::from(timestamp_unix_ms) * 1_000_000).to_string()
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    json!({ "key": key, "value": { "stringValue": value } })
+}
+
+fn int_attribute(key: &str, value: u64) -> Value {
+    json!({ "key": key, "value": { "intValue": value.to_string() } })
+}
+
+fn push_optional_string(attributes: &mut Vec<Value>, key: &str, value: Option<&str>) {
+    if let Some(value) = value.filter(|value| !value.is_empty()) {
+        attributes.push(string_attribute(key, value));
+    }
+}
+
+fn push_optional_int(attributes: &mut Vec<Value>, key: &str, value: Option<u64>) {
+    if let Some(value) = value {
+        attributes.push(int_attribute(key, value));
+    }
+}
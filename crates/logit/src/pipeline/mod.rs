@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::normalize::{AdapterHealthReport, AdapterHealthStatus};
+use crate::utils::fs::write_atomic;
+use crate::utils::time::format_unix_ms;
+use crate::validate::ValidationReport;
+
+pub const RUN_MANIFEST_SCHEMA_VERSION: &str = "logit.run-manifest.v1";
+pub const RUN_MANIFEST_FILE: &str = "run_manifest.json";
+
+pub const STAGE_EXIT_SUCCESS: i32 = 0;
+pub const STAGE_EXIT_FAILED: i32 = 1;
+pub const STAGE_EXIT_GATED: i32 = 2;
+
+pub const DEFAULT_GATES: &[RunGate] = &[RunGate::ValidationErrors, RunGate::AdapterFailed];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RunGate {
+    ValidationErrors,
+    ValidationWarnings,
+    AdapterFailed,
+    AdapterPartialFailure,
+}
+
+impl RunGate {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ValidationErrors => "validation-errors",
+            Self::ValidationWarnings => "validation-warnings",
+            Self::AdapterFailed => "adapter-failed",
+            Self::AdapterPartialFailure => "adapter-partial-failure",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "validation-errors" => Ok(Self::ValidationErrors),
+            "validation-warnings" => Ok(Self::ValidationWarnings),
+            "adapter-failed" => Ok(Self::AdapterFailed),
+            "adapter-partial-failure" => Ok(Self::AdapterPartialFailure),
+            other => Err(format!(
+                "unknown run gate `{other}` (expected validation-errors, validation-warnings, adapter-failed or adapter-partial-failure)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStage {
+    Snapshot,
+    Normalize,
+    Validate,
+    Ingest,
+}
+
+impl RunStage {
+    pub const ALL: [Self; 4] = [
+        Self::Snapshot,
+        Self::Normalize,
+        Self::Validate,
+        Self::Ingest,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Snapshot => "snapshot",
+            Self::Normalize => "normalize",
+            Self::Validate => "validate",
+            Self::Ingest => "ingest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Success,
+    Failed,
+    Gated,
+    Skipped,
+}
+
+impl StageStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failed => "failed",
+            Self::Gated => "gated",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    Gated,
+    Failed,
+}
+
+impl RunStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Gated => "gated",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArtifactRecord {
+    pub path: String,
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StageRecord {
+    pub stage: RunStage,
+    pub status: StageStatus,
+    pub duration_ms: u64,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    pub artifacts: Vec<ArtifactRecord>,
+}
+
+impl StageRecord {
+    #[must_use]
+    pub const fn skipped(stage: RunStage) -> Self {
+        Self {
+            stage,
+            status: StageStatus::Skipped,
+            duration_ms: 0,
+            exit_code: None,
+            error: None,
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GateTrip {
+    pub gate: RunGate,
+    pub stage: RunStage,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunManifest {
+    pub schema_version: String,
+    pub run_id: String,
+    pub status: RunStatus,
+    pub exit_code: i32,
+    pub started_at_utc: String,
+    pub finished_at_utc: String,
+    pub duration_ms: u64,
+    pub gates: Vec<RunGate>,
+    pub gate_tripped: Option<GateTrip>,
+    pub stages: Vec<StageRecord>,
+    pub config: Value,
+}
+
+/// Returned by `logit run` when a gate stops the pipeline; maps to the
+/// validation-failure exit code.
+#[derive(Debug)]
+pub struct RunGateFailure {
+    pub run_id: String,
+    pub trip: GateTrip,
+}
+
+impl fmt::Display for RunGateFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "run {} stopped after {}: gate `{}` tripped ({})",
+            self.run_id,
+            self.trip.stage.as_str(),
+            self.trip.gate.as_str(),
+            self.trip.detail
+        )
+    }
+}
+
+impl std::error::Error for RunGateFailure {}
+
+/// `run-<utc timestamp>-<pid>`, e.g. `run-20260225T000140123Z-1a2b`.
+#[must_use]
+pub fn new_run_id(now_unix_ms: u64) -> String {
+    let compact = format_unix_ms(now_unix_ms).replace(['-', ':', '.'], "");
+    format!("run-{compact}-{:04x}", std::process::id() & 0xffff)
+}
+
+#[must_use]
+pub fn run_manifest_path(out_dir: &Path) -> PathBuf {
+    out_dir.join(RUN_MANIFEST_FILE)
+}
+
+pub fn artifact_record(path: &Path) -> Result<ArtifactRecord> {
+    let mut file = std::fs::File::open(path)
+        .with_context(|| format!("failed to open run artifact: {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0_u8; 64 * 1024];
+    let mut bytes = 0_u64;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .with_context(|| format!("failed to read run artifact: {}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        bytes += read as u64;
+    }
+    Ok(ArtifactRecord {
+        path: path.display().to_string(),
+        bytes,
+        sha256: hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect(),
+    })
+}
+
+#[must_use]
+pub fn evaluate_adapter_gates(
+    gates: &[RunGate],
+    adapter_health: &BTreeMap<String, AdapterHealthReport>,
+) -> Option<GateTrip> {
+    let tripped = |status: AdapterHealthStatus| {
+        adapter_health
+            .iter()
+            .filter(|(_, report)| report.status == status)
+            .map(|(adapter, _)| adapter.as_str())
+            .collect::<Vec<_>>()
+    };
+    for (gate, status) in [
+        (RunGate::AdapterFailed, AdapterHealthStatus::Failed),
+        (
+            RunGate::AdapterPartialFailure,
+            AdapterHealthStatus::PartialFailure,
+        ),
+    ] {
+        let adapters = tripped(status);
+        if gates.contains(&gate) && !adapters.is_empty() {
+            return Some(GateTrip {
+                gate,
+                stage: RunStage::Normalize,
+                detail: format!(
+                    "adapter health {} for {}",
+                    status.as_str(),
+                    adapters.join(",")
+                ),
+            });
+        }
+    }
+    None
+}
+
+#[must_use]
+pub fn evaluate_validation_gates(gates: &[RunGate], report: &ValidationReport) -> Option<GateTrip> {
+    let trip = |gate: RunGate, detail: String| GateTrip {
+        gate,
+        stage: RunStage::Validate,
+        detail,
+    };
+    if gates.contains(&RunGate::ValidationErrors) && report.errors > 0 {
+        return Some(trip(
+            RunGate::ValidationErrors,
+            format!("{} validation error(s)", report.errors),
+        ));
+    }
+    if gates.contains(&RunGate::ValidationWarnings) && report.warnings > 0 {
+        return Some(trip(
+            RunGate::ValidationWarnings,
+            format!("{} validation warning(s)", report.warnings),
+        ));
+    }
+    None
+}
+
+pub fn write_run_manifest(path: &Path, manifest: &RunManifest) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create run manifest directory: {}",
+                parent.display()
+            )
+        })?;
+    }
+    let encoded =
+        serde_json::to_vec_pretty(manifest).context("failed to encode run manifest json")?;
+    write_atomic(path, &encoded)
+        .with_context(|| format!("failed to write run manifest: {}", path.display()))
+}
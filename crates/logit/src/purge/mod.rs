@@ -0,0 +1,716 @@
+//! Removes events from every artifact under the out dir and remembers what was
+//! removed so later runs do not bring it back.
+//!
+//! A purge matches events by selector (age, session, source path glob or event
+//! id) and deletes them from `events.jsonl`, the mart (events, full-text index
+//! and watermarks of sources left empty) and `snapshot/samples.jsonl`. Each
+//! selector is appended to `purge/tombstones.json`, which normalize, `ingest
+//! refresh` and `ingest import` consult to drop matching events, and each run
+//! is recorded in `purge/purge_log.jsonl`.
+//!
+//! A source path glob would also match records written to those paths later,
+//! so once the purge completes its glob tombstone is replaced by the source
+//! records (path and locator) of the events it removed. Records are used
+//! rather than canonical hashes because a redacted event's hash depends on
+//! the redaction policy, and a purged secret must stay out under any policy.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::adapters::custom::wildcard_matches;
+use crate::models::AgentLogEvent;
+use crate::sqlite::migrations::migration_status;
+use crate::sqlite::{
+    EVENTS_FTS_TABLE, EVENTS_TABLE, INGEST_WATERMARKS_TABLE, ensure_sqlite_schema,
+    open_sqlite_connection, open_sqlite_connection_read_only,
+};
+use crate::utils::fs::write_atomic;
+use crate::utils::time::{format_unix_ms, unix_timestamp_milliseconds};
+
+pub const TOMBSTONES_SCHEMA_VERSION: &str = "logit.tombstones.v1";
+pub const PURGE_LOG_SCHEMA_VERSION: &str = "logit.purge-log.v1";
+
+/// Which events a purge removes. `*` and `?` in a source path glob match any
+/// run of characters (including `/`) and any single character.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "selector", rename_all = "snake_case")]
+pub enum PurgeSelector {
+    OlderThan {
+        before_unix_ms: u64,
+    },
+    Session {
+        session_id: String,
+    },
+    SourcePath {
+        glob: String,
+    },
+    EventId {
+        event_id: String,
+    },
+    SourceRecord {
+        source_path: String,
+        source_record_locator: String,
+    },
+    /// Written by earlier releases when narrowing a source path glob; still
+    /// honoured when read back.
+    CanonicalHash {
+        canonical_hash: String,
+    },
+}
+
+/// The identifying fields of an event, borrowed from whichever artifact
+/// holds it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventKey<'a> {
+    pub event_id: &'a str,
+    pub session_id: Option<&'a str>,
+    pub source_path: &'a str,
+    pub source_record_locator: &'a str,
+    pub timestamp_unix_ms: u64,
+    pub canonical_hash: &'a str,
+}
+
+impl<'a> EventKey<'a> {
+    #[must_use]
+    pub fn from_event(event: &'a AgentLogEvent) -> Self {
+        Self {
+            event_id: &event.event_id,
+            session_id: event.session_id.as_deref(),
+            source_path: &event.source_path,
+            source_record_locator: &event.source_record_locator,
+            timestamp_unix_ms: event.timestamp_unix_ms,
+            canonical_hash: &event.canonical_hash,
+        }
+    }
+}
+
+impl PurgeSelector {
+    #[must_use]
+    pub fn matches(&self, key: &EventKey<'_>) -> bool {
+        match self {
+            Self::OlderThan { before_unix_ms } => key.timestamp_unix_ms < *before_unix_ms,
+            Self::Session { session_id } => key.session_id == Some(session_id.as_str()),
+            Self::SourcePath { glob } => matches_source_path(glob, key.source_path),
+            Self::EventId { event_id } => key.event_id == event_id,
+            Self::SourceRecord {
+                source_path,
+                source_record_locator,
+            } => {
+                key.source_path == source_path && key.source_record_locator == source_record_locator
+            }
+            Self::CanonicalHash { canonical_hash } => {
+                !canonical_hash.is_empty() && key.canonical_hash == canonical_hash
+            }
+        }
+    }
+}
+
+#[must_use]
+pub fn matches_any(selectors: &[PurgeSelector], key: &EventKey<'_>) -> bool {
+    selectors.iter().any(|selector| selector.matches(key))
+}
+
+#[must_use]
+pub fn matches_source_path(glob: &str, source_path: &str) -> bool {
+    wildcard_matches(glob.as_bytes(), source_path.as_bytes())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    #[serde(flatten)]
+    pub selector: PurgeSelector,
+    pub purge_id: String,
+    pub created_at_utc: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TombstoneList {
+    #[serde(default = "tombstones_schema_version")]
+    pub schema_version: String,
+    #[serde(default)]
+    pub tombstones: Vec<Tombstone>,
+}
+
+fn tombstones_schema_version() -> String {
+    TOMBSTONES_SCHEMA_VERSION.to_string()
+}
+
+impl Default for TombstoneList {
+    fn default() -> Self {
+        Self {
+            schema_version: tombstones_schema_version(),
+            tombstones: Vec::new(),
+        }
+    }
+}
+
+impl TombstoneList {
+    /// Reads the tombstone list; a missing file is an empty list.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read tombstones: {}", path.display()))?;
+        serde_json::from_str(&input)
+            .with_context(|| format!("tombstones must be valid JSON: {}", path.display()))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let encoded = serde_json::to_vec_pretty(self).context("failed to encode tombstones")?;
+        write_atomic(path, &encoded).context("failed to write tombstones")
+    }
+
+    #[must_use]
+    pub fn selectors(&self) -> Vec<PurgeSelector> {
+        self.tombstones
+            .iter()
+            .map(|tombstone| tombstone.selector.clone())
+            .collect()
+    }
+
+    /// Adds selectors that are not already tombstoned and returns how many
+    /// were new.
+    pub fn extend(
+        &mut self,
+        selectors: &[PurgeSelector],
+        purge_id: &str,
+        created_at_utc: &str,
+    ) -> usize {
+        let before = self.tombstones.len();
+        for selector in selectors {
+            if self
+                .tombstones
+                .iter()
+                .any(|tombstone| &tombstone.selector == selector)
+            {
+                continue;
+            }
+            self.tombstones.push(Tombstone {
+                selector: selector.clone(),
+                purge_id: purge_id.to_string(),
+                created_at_utc: created_at_utc.to_string(),
+            });
+        }
+        self.tombstones.len() - before
+    }
+
+    /// Replaces the source path globs tombstoned by `purge_id` with the
+    /// source records (path and locator) of the events they removed.
+    pub fn narrow_source_paths(
+        &mut self,
+        purge_id: &str,
+        source_records: &BTreeSet<(String, String)>,
+        created_at_utc: &str,
+    ) {
+        self.tombstones.retain(|tombstone| {
+            tombstone.purge_id != purge_id
+                || !matches!(tombstone.selector, PurgeSelector::SourcePath { .. })
+        });
+        let selectors = source_records
+            .iter()
+            .map(|(source_path, locator)| PurgeSelector::SourceRecord {
+                source_path: source_path.clone(),
+                source_record_locator: locator.clone(),
+            })
+            .collect::<Vec<_>>();
+        self.extend(&selectors, purge_id, created_at_utc);
+    }
+}
+
+#[must_use]
+pub fn tombstones_path(out_dir: &Path) -> PathBuf {
+    out_dir.join("purge").join("tombstones.json")
+}
+
+#[must_use]
+pub fn purge_log_path(out_dir: &Path) -> PathBuf {
+    out_dir.join("purge").join("purge_log.jsonl")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeKind {
+    Prune,
+    Purge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurgePlan {
+    pub out_dir: PathBuf,
+    pub kind: PurgeKind,
+    pub selectors: Vec<PurgeSelector>,
+    /// Count what would be removed without changing any artifact.
+    pub dry_run: bool,
+    pub purge_id: Option<String>,
+}
+
+/// The outcome of one purge; also the line appended to the purge log.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PurgeReport {
+    pub schema_version: String,
+    pub purge_id: String,
+    pub kind: PurgeKind,
+    pub dry_run: bool,
+    pub executed_at_utc: String,
+    pub duration_ms: u64,
+    pub selectors: Vec<PurgeSelector>,
+    pub events_jsonl_removed: usize,
+    pub mart_events_removed: usize,
+    pub mart_fts_rows_removed: usize,
+    pub mart_watermarks_removed: usize,
+    pub snapshot_samples_removed: usize,
+    pub sessions_affected: usize,
+    pub tombstones_added: usize,
+    pub tombstones_total: usize,
+    pub vacuumed: bool,
+    pub artifacts_missing: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Events removed so far, by source record, so snapshot samples taken from
+/// the same records can be dropped too.
+#[derive(Debug, Default)]
+struct RemovedEvents {
+    records: BTreeSet<(String, String)>,
+    sessions: BTreeSet<String>,
+    /// Source records of events removed by a source path glob; they replace
+    /// the glob in the tombstone list.
+    path_matched_records: BTreeSet<(String, String)>,
+}
+
+impl RemovedEvents {
+    fn record(&mut self, selectors: &[PurgeSelector], key: &EventKey<'_>) {
+        let record = (
+            key.source_path.to_string(),
+            key.source_record_locator.to_string(),
+        );
+        if let Some(session_id) = key.session_id {
+            self.sessions.insert(session_id.to_string());
+        }
+        let path_matched = selectors.iter().any(|selector| {
+            matches!(selector, PurgeSelector::SourcePath { .. }) && selector.matches(key)
+        });
+        if path_matched {
+            self.path_matched_records.insert(record.clone());
+        }
+        self.records.insert(record);
+    }
+}
+
+#[derive(Debug, Default)]
+struct MartPurgeCounts {
+    events: usize,
+    fts_rows: usize,
+    watermarks: usize,
+    vacuumed: bool,
+}
+
+pub fn run_purge(plan: &PurgePlan) -> Result<PurgeReport> {
+    if plan.selectors.is_empty() {
+        bail!("purge needs at least one selector");
+    }
+    let started_at = std::time::Instant::now();
+    let executed_at_utc = format_unix_ms(unix_timestamp_milliseconds());
+    let purge_id = plan.purge_id.clone().unwrap_or_else(build_purge_id);
+
+    // Tombstones go first so a purge interrupted part-way still keeps the
+    // records out of the next normalize.
+    let tombstones_path = tombstones_path(&plan.out_dir);
+    let mut tombstones = TombstoneList::load(&tombstones_path)?;
+    let tombstones_before = tombstones.tombstones.len();
+    if !plan.dry_run && tombstones.extend(&plan.selectors, &purge_id, &executed_at_utc) > 0 {
+        tombstones.write(&tombstones_path)?;
+    }
+
+    let mut removed = RemovedEvents::default();
+    let mut artifacts_missing = Vec::new();
+    let mut warnings = Vec::new();
+
+    let events_jsonl = crate::normalize::build_artifact_layout(&plan.out_dir).events_jsonl;
+    let events_jsonl_removed = if events_jsonl.exists() {
+        purge_events_jsonl(
+            &events_jsonl,
+            &plan.selectors,
+            plan.dry_run,
+            &mut removed,
+            &mut warnings,
+        )?
+    } else {
+        artifacts_missing.push("events.jsonl".to_string());
+        0
+    };
+
+    let sqlite_path = plan.out_dir.join("mart.sqlite");
+    let mart = if sqlite_path.exists() {
+        purge_mart(&sqlite_path, &plan.selectors, plan.dry_run, &mut removed)?
+    } else {
+        artifacts_missing.push("mart.sqlite".to_string());
+        MartPurgeCounts::default()
+    };
+
+    let snapshot = crate::snapshot::build_artifact_layout(&plan.out_dir);
+    let snapshot_samples_removed = if snapshot.samples_jsonl.exists() {
+        purge_snapshot_samples(
+            &snapshot.samples_jsonl,
+            &snapshot.index_json,
+            &plan.selectors,
+            &removed,
+            plan.dry_run,
+            &mut warnings,
+        )?
+    } else {
+        artifacts_missing.push("snapshot/samples.jsonl".to_string());
+        0
+    };
+
+    if plan.dry_run {
+        tombstones.extend(&plan.selectors, &purge_id, &executed_at_utc);
+    }
+    let narrows_source_paths = plan
+        .selectors
+        .iter()
+        .any(|selector| matches!(selector, PurgeSelector::SourcePath { .. }));
+    if narrows_source_paths {
+        tombstones.narrow_source_paths(&purge_id, &removed.path_matched_records, &executed_at_utc);
+        if !plan.dry_run {
+            tombstones.write(&tombstones_path)?;
+        }
+    }
+    let tombstones_added = tombstones.tombstones.len() - tombstones_before;
+
+    let report = PurgeReport {
+        schema_version: PURGE_LOG_SCHEMA_VERSION.to_string(),
+        purge_id,
+        kind: plan.kind,
+        dry_run: plan.dry_run,
+        executed_at_utc,
+        duration_ms: u64::try_from(started_at.elapsed().as_millis()).unwrap_or(u64::MAX),
+        selectors: plan.selectors.clone(),
+        events_jsonl_removed,
+        mart_events_removed: mart.events,
+        mart_fts_rows_removed: mart.fts_rows,
+        mart_watermarks_removed: mart.watermarks,
+        snapshot_samples_removed,
+        sessions_affected: removed.sessions.len(),
+        tombstones_added,
+        tombstones_total: tombstones.tombstones.len(),
+        vacuumed: mart.vacuumed,
+        artifacts_missing,
+        warnings,
+    };
+    if !plan.dry_run {
+        append_purge_log(&purge_log_path(&plan.out_dir), &report)?;
+    }
+    Ok(report)
+}
+
+fn build_purge_id() -> String {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    format!("purge-{nanos:016x}")
+}
+
+/// Rewrites `events.jsonl` without the matching rows. Kept rows are copied
+/// byte for byte; rows that do not parse are kept and reported.
+fn purge_events_jsonl(
+    path: &Path,
+    selectors: &[PurgeSelector],
+    dry_run: bool,
+    removed: &mut RemovedEvents,
+    warnings: &mut Vec<String>,
+) -> Result<usize> {
+    let input = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read events artifact: {}", path.display()))?;
+    let mut kept = String::with_capacity(input.len());
+    let mut removed_count = 0;
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(row) = serde_json::from_str::<Value>(line) else {
+            warnings.push(format!(
+                "events.jsonl line {} is not valid JSON; kept as-is",
+                index + 1
+            ));
+            kept.push_str(line);
+            kept.push('\n');
+            continue;
+        };
+        let key = EventKey {
+            event_id: row_str(&row, "event_id").unwrap_or_default(),
+            session_id: row_str(&row, "session_id"),
+            source_path: row_str(&row, "source_path").unwrap_or_default(),
+            source_record_locator: row_str(&row, "source_record_locator").unwrap_or_default(),
+            timestamp_unix_ms: row
+                .get("timestamp_unix_ms")
+                .and_then(Value::as_u64)
+                .unwrap_or(u64::MAX),
+            canonical_hash: row_str(&row, "canonical_hash").unwrap_or_default(),
+        };
+        if matches_any(selectors, &key) {
+            removed.record(selectors, &key);
+            removed_count += 1;
+        } else {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    if !dry_run && removed_count > 0 {
+        write_atomic(path, kept.as_bytes()).context("failed to rewrite events artifact")?;
+    }
+    Ok(removed_count)
+}
+
+fn row_str<'a>(row: &'a Value, field: &str) -> Option<&'a str> {
+    row.get(field).and_then(Value::as_str)
+}
+
+/// Deletes matching events and their full-text rows, drops the watermarks of
+/// sources left without events, then compacts the file so deleted content
+/// does not linger in free pages.
+fn purge_mart(
+    path: &Path,
+    selectors: &[PurgeSelector],
+    dry_run: bool,
+    removed: &mut RemovedEvents,
+) -> Result<MartPurgeCounts> {
+    let mut connection = if dry_run {
+        let connection = open_sqlite_connection_read_only(path)?;
+        let status = migration_status(&connection)?;
+        if !status.pending.is_empty() {
+            bail!(
+                "mart is at migration version {} of {}; run `logit mart migrate` before a dry run",
+                status.current_version,
+                status.latest_version
+            );
+        }
+        connection
+    } else {
+        let connection = open_sqlite_connection(path)?;
+        ensure_sqlite_schema(&connection)?;
+        connection
+    };
+
+    let mut matched_rowids = Vec::new();
+    let mut source_totals = BTreeMap::<(String, String, Option<String>), (usize, usize)>::new();
+    {
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT rowid, event_id, session_id, source_path, source_record_locator,
+                        timestamp_unix_ms, source_kind, host, canonical_hash
+                 FROM {EVENTS_TABLE}"
+            ))
+            .context("failed to prepare purge scan")?;
+        let mut rows = statement.query([]).context("failed to scan mart events")?;
+        while let Some(row) = rows.next().context("failed to read mart event row")? {
+            let rowid: i64 = row.get(0)?;
+            let event_id: String = row.get(1)?;
+            let session_id: Option<String> = row.get(2)?;
+            let source_path: String = row.get(3)?;
+            let locator: String = row.get(4)?;
+            let timestamp_unix_ms: i64 = row.get(5)?;
+            let source_kind: String = row.get(6)?;
+            let host: Option<String> = row.get(7)?;
+            let canonical_hash: String = row.get(8)?;
+            let key = EventKey {
+                event_id: &event_id,
+                session_id: session_id.as_deref(),
+                source_path: &source_path,
+                source_record_locator: &locator,
+                timestamp_unix_ms: u64::try_from(timestamp_unix_ms).unwrap_or_default(),
+                canonical_hash: &canonical_hash,
+            };
+            let matched = matches_any(selectors, &key);
+            let totals = source_totals
+                .entry((source_kind, source_path.clone(), host))
+                .or_default();
+            totals.0 += 1;
+            if matched {
+                totals.1 += 1;
+                removed.record(selectors, &key);
+                matched_rowids.push(rowid);
+            }
+        }
+    }
+    let emptied_sources = source_totals
+        .into_iter()
+        .filter(|(_, (total, matched))| *matched > 0 && total == matched)
+        .map(|(source, _)| source)
+        .collect::<Vec<_>>();
+
+    let mut counts = MartPurgeCounts {
+        events: matched_rowids.len(),
+        ..MartPurgeCounts::default()
+    };
+    let fts_delete_sql = format!("DELETE FROM {EVENTS_FTS_TABLE} WHERE rowid = ?1");
+    let event_delete_sql = format!("DELETE FROM {EVENTS_TABLE} WHERE rowid = ?1");
+    let watermark_delete_sql = format!(
+        "DELETE FROM {INGEST_WATERMARKS_TABLE}
+         WHERE source_kind = ?1 AND source_path = ?2 AND host IS ?3"
+    );
+    if dry_run {
+        for rowid in &matched_rowids {
+            let indexed = connection
+                .query_row(
+                    &format!("SELECT 1 FROM {EVENTS_FTS_TABLE} WHERE rowid = ?1"),
+                    [rowid],
+                    |_| Ok(()),
+                )
+                .optional()
+                .context("failed to look up full-text row")?;
+            counts.fts_rows += usize::from(indexed.is_some());
+        }
+        for (source_kind, source_path, host) in &emptied_sources {
+            let watermarks: i64 = connection
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM {INGEST_WATERMARKS_TABLE}
+                         WHERE source_kind = ?1 AND source_path = ?2 AND host IS ?3"
+                    ),
+                    rusqlite::params![source_kind, source_path, host],
+                    |row| row.get(0),
+                )
+                .context("failed to count watermarks")?;
+            counts.watermarks += usize::try_from(watermarks).unwrap_or_default();
+        }
+        return Ok(counts);
+    }
+
+    if counts.events > 0 {
+        let tx = connection
+            .transaction()
+            .context("failed to open purge transaction")?;
+        {
+            let mut fts_delete = tx
+                .prepare_cached(&fts_delete_sql)
+                .context("failed to prepare full-text search delete statement")?;
+            let mut event_delete = tx
+                .prepare_cached(&event_delete_sql)
+                .context("failed to prepare event delete statement")?;
+            let mut watermark_delete = tx
+                .prepare_cached(&watermark_delete_sql)
+                .context("failed to prepare watermark delete statement")?;
+            for rowid in &matched_rowids {
+                counts.fts_rows += fts_delete
+                    .execute([rowid])
+                    .context("failed to delete full-text row")?;
+                event_delete
+                    .execute([rowid])
+                    .context("failed to delete mart event")?;
+            }
+            for (source_kind, source_path, host) in &emptied_sources {
+                counts.watermarks += watermark_delete
+                    .execute(rusqlite::params![source_kind, source_path, host])
+                    .context("failed to delete watermark")?;
+            }
+        }
+        tx.commit().context("failed to commit purge transaction")?;
+    }
+
+    connection
+        .execute_batch(&format!(
+            "INSERT INTO {EVENTS_FTS_TABLE}({EVENTS_FTS_TABLE}) VALUES('optimize'); VACUUM;"
+        ))
+        .context("failed to vacuum mart after purge")?;
+    counts.vacuumed = true;
+    Ok(counts)
+}
+
+/// Drops samples taken from removed records or from tombstoned source paths
+/// and keeps `counts.samples_emitted` in the snapshot index in step.
+fn purge_snapshot_samples(
+    samples_path: &Path,
+    index_path: &Path,
+    selectors: &[PurgeSelector],
+    removed: &RemovedEvents,
+    dry_run: bool,
+    warnings: &mut Vec<String>,
+) -> Result<usize> {
+    let input = std::fs::read_to_string(samples_path).with_context(|| {
+        format!(
+            "failed to read snapshot samples artifact: {}",
+            samples_path.display()
+        )
+    })?;
+    let path_globs = selectors
+        .iter()
+        .filter_map(|selector| match selector {
+            PurgeSelector::SourcePath { glob } => Some(glob.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let mut kept = String::with_capacity(input.len());
+    let mut kept_count = 0_usize;
+    let mut removed_count = 0;
+    for (index, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Ok(row) = serde_json::from_str::<Value>(line) else {
+            warnings.push(format!(
+                "snapshot/samples.jsonl line {} is not valid JSON; kept as-is",
+                index + 1
+            ));
+            kept.push_str(line);
+            kept.push('\n');
+            kept_count += 1;
+            continue;
+        };
+        let source_path = row_str(&row, "source_path").unwrap_or_default();
+        let locator = row_str(&row, "source_record_locator").unwrap_or_default();
+        let matched = path_globs
+            .iter()
+            .any(|glob| matches_source_path(glob, source_path))
+            || removed
+                .records
+                .contains(&(source_path.to_string(), locator.to_string()));
+        if matched {
+            removed_count += 1;
+        } else {
+            kept.push_str(line);
+            kept.push('\n');
+            kept_count += 1;
+        }
+    }
+    if dry_run || removed_count == 0 {
+        return Ok(removed_count);
+    }
+
+    write_atomic(samples_path, kept.as_bytes())
+        .context("failed to rewrite snapshot samples artifact")?;
+    if index_path.exists() {
+        let raw = std::fs::read_to_string(index_path)
+            .with_context(|| format!("failed to read snapshot index: {}", index_path.display()))?;
+        let mut index: Value =
+            serde_json::from_str(&raw).context("snapshot index artifact is not valid JSON")?;
+        if let Some(samples_emitted) = index.pointer_mut("/counts/samples_emitted") {
+            *samples_emitted = Value::from(kept_count);
+        }
+        let encoded =
+            serde_json::to_vec_pretty(&index).context("failed to encode snapshot index")?;
+        write_atomic(index_path, &encoded).context("failed to rewrite snapshot index")?;
+    }
+    Ok(removed_count)
+}
+
+fn append_purge_log(path: &Path, report: &PurgeReport) -> Result<()> {
+    let mut log = if path.exists() {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read purge log: {}", path.display()))?
+    } else {
+        String::new()
+    };
+    if !log.is_empty() && !log.ends_with('\n') {
+        log.push('\n');
+    }
+    log.push_str(&serde_json::to_string(report).context("failed to encode purge log entry")?);
+    log.push('\n');
+    write_atomic(path, log.as_bytes()).context("failed to append purge log")
+}
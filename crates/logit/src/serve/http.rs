@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+
+use anyhow::{Context, Result, bail};
+
+pub const MAX_HEADER_BYTES: usize = 16 * 1024;
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Returned by [`read_request`] when `Content-Length` exceeds
+/// [`MAX_BODY_BYTES`], so the server can answer `413` instead of `400`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PayloadTooLarge;
+
+impl std::fmt::Display for PayloadTooLarge {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            formatter,
+            "http request body exceeds {MAX_BODY_BYTES} bytes"
+        )
+    }
+}
+
+impl std::error::Error for PayloadTooLarge {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(&name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+
+    #[must_use]
+    pub fn query_flag(&self, name: &str) -> bool {
+        self.query_param(name)
+            .is_some_and(|value| matches!(value, "" | "1" | "true" | "yes"))
+    }
+
+    #[must_use]
+    pub fn path_segments(&self) -> Vec<String> {
+        self.path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub body: String,
+}
+
+impl HttpResponse {
+    #[must_use]
+    pub fn json(status: u16, body: String) -> Self {
+        Self { status, body }
+    }
+
+    pub fn write_to(&self, writer: &mut impl Write) -> Result<()> {
+        write!(
+            writer,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason_phrase(self.status),
+            self.body.len(),
+            self.body
+        )
+        .context("failed to write http response")?;
+        writer.flush().context("failed to flush http response")
+    }
+}
+
+pub fn read_request(stream: impl Read) -> Result<HttpRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut header_bytes = 0;
+    let mut next_line = |reader: &mut BufReader<_>| -> Result<String> {
+        let mut line = String::new();
+        let remaining = (MAX_HEADER_BYTES + 1).saturating_sub(header_bytes);
+        let read = reader
+            .by_ref()
+            .take(remaining as u64)
+            .read_line(&mut line)
+            .context("failed to read http request")?;
+        header_bytes += read;
+        if header_bytes > MAX_HEADER_BYTES {
+            bail!("http request headers exceed {MAX_HEADER_BYTES} bytes");
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    };
+
+    let request_line = next_line(&mut reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        bail!("malformed http request line");
+    };
+    let (path, raw_query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = BTreeMap::new();
+    loop {
+        let line = next_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            bail!("malformed http header line");
+        };
+        headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+    }
+
+    let content_length = headers
+        .get("content-length")
+        .map(|value| value.parse::<usize>())
+        .transpose()
+        .context("invalid content-length header")?
+        .unwrap_or(0);
+    if content_length > MAX_BODY_BYTES {
+        return Err(PayloadTooLarge.into());
+    }
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .context("failed to read http request body")?;
+
+    Ok(HttpRequest {
+        method: method.to_ascii_uppercase(),
+        path: path.to_string(),
+        query: parse_query_string(raw_query),
+        headers,
+        body,
+    })
+}
+
+#[must_use]
+pub fn parse_query_string(raw: &str) -> BTreeMap<String, String> {
+    raw.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                let high = char::from(bytes[index + 1]).to_digit(16);
+                let low = char::from(bytes[index + 2]).to_digit(16);
+                match (high, low) {
+                    (Some(high), Some(low)) => {
+                        decoded.push(u8::try_from(high * 16 + low).unwrap_or(b'?'));
+                        index += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
@@ -0,0 +1,504 @@
+pub mod http;
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Error, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::cli::commands::query::{
+    DEFAULT_SEARCH_ROW_CAP, DEFAULT_SQL_ROW_CAP, QueryCatalogArgs, QuerySchemaArgs,
+    QuerySearchArgs, QuerySqlArgs, catalog_query_envelope, encode_envelope, schema_query_envelope,
+    search_query_envelope, sql_query_envelope,
+};
+use crate::cli::commands::show::{session_transcript_envelope, sessions_envelope};
+use crate::models::{QueryEnvelope, QueryEnvelopeCommandFailure};
+use crate::utils::time::{format_unix_ms, unix_timestamp_seconds};
+use http::{HttpRequest, HttpResponse, PayloadTooLarge, read_request};
+
+pub const DEFAULT_PORT: u16 = 8765;
+pub const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_MAX_CONNECTIONS: usize = 64;
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServeConfig {
+    pub sqlite_path: PathBuf,
+    pub token: Option<String>,
+    pub request_log: Option<PathBuf>,
+    pub request_timeout: Duration,
+    pub max_connections: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RequestLogEntry {
+    pub timestamp_utc: String,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SqlRequestBody {
+    sql: String,
+    #[serde(default)]
+    params: Option<Value>,
+    #[serde(default = "default_sql_row_cap")]
+    row_cap: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRequestBody {
+    terms: String,
+    #[serde(default)]
+    raw: bool,
+    #[serde(default = "default_search_row_cap")]
+    row_cap: usize,
+}
+
+const fn default_sql_row_cap() -> usize {
+    DEFAULT_SQL_ROW_CAP
+}
+
+const fn default_search_row_cap() -> usize {
+    DEFAULT_SEARCH_ROW_CAP
+}
+
+pub fn load_token(path: &Path) -> Result<String> {
+    let token = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read serve token file: {}", path.display()))?;
+    let token = token.trim();
+    if token.is_empty() {
+        bail!("serve token file is empty: {}", path.display());
+    }
+    Ok(token.to_string())
+}
+
+#[derive(Debug)]
+pub struct Server {
+    listener: TcpListener,
+    config: ServeConfig,
+    shutdown: Arc<AtomicBool>,
+    active_connections: AtomicUsize,
+}
+
+/// Releases a connection slot when its thread finishes, even on panic.
+struct ConnectionSlot<'a>(&'a AtomicUsize);
+
+impl Drop for ConnectionSlot<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Server {
+    pub fn bind(port: u16, config: ServeConfig) -> Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .with_context(|| format!("failed to bind 127.0.0.1:{port}"))?;
+        listener
+            .set_nonblocking(true)
+            .context("failed to configure listener")?;
+        Ok(Self {
+            listener,
+            config,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            active_connections: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.listener
+            .local_addr()
+            .context("failed to read listener address")
+    }
+
+    #[must_use]
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+
+    /// Serves each connection on its own thread until the shutdown flag is
+    /// set, so a slow or idle client cannot hold up the others; requests in
+    /// flight when the flag flips are still answered before `run` returns.
+    /// Connections beyond `max_connections` are answered `503` on the accept
+    /// thread and closed instead of spawning another thread.
+    pub fn run(&self) -> Result<()> {
+        std::thread::scope(|scope| {
+            while !self.shutdown.load(Ordering::SeqCst) {
+                match self.listener.accept() {
+                    Ok((stream, _)) => {
+                        let active = self.active_connections.fetch_add(1, Ordering::SeqCst);
+                        let slot = ConnectionSlot(&self.active_connections);
+                        if active >= self.config.max_connections {
+                            drop(slot);
+                            self.reject_connection(stream);
+                            continue;
+                        }
+                        scope.spawn(move || {
+                            let _slot = slot;
+                            self.serve_connection(stream);
+                        });
+                    }
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                    }
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {}
+                    Err(error) => return Err(error).context("failed to accept connection"),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn serve_connection(&self, mut stream: TcpStream) {
+        let started = Instant::now();
+        let prepared = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.set_read_timeout(Some(self.config.request_timeout)));
+        let (method, path, envelope) = match prepared
+            .context("failed to configure connection")
+            .and_then(|()| read_request(&stream))
+        {
+            Ok(request) => (
+                request.method.clone(),
+                request.path.clone(),
+                handle_request(&self.config, &request),
+            ),
+            Err(error) if error.downcast_ref::<PayloadTooLarge>().is_some() => (
+                "-".to_string(),
+                "-".to_string(),
+                QueryEnvelope::error(
+                    "serve",
+                    "serve_payload_too_large",
+                    "http request body is too large",
+                )
+                .with_error_details(json!({ "cause": format!("{error:#}") })),
+            ),
+            Err(error) => (
+                "-".to_string(),
+                "-".to_string(),
+                QueryEnvelope::error("serve", "serve_request_invalid", "malformed http request")
+                    .with_error_details(json!({ "cause": format!("{error:#}") })),
+            ),
+        };
+        self.respond(&mut stream, started, method, path, &envelope);
+    }
+
+    fn reject_connection(&self, mut stream: TcpStream) {
+        let started = Instant::now();
+        let envelope =
+            QueryEnvelope::error("serve", "serve_busy", "too many concurrent connections")
+                .with_error_details(json!({ "max_connections": self.config.max_connections }));
+        let prepared = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.set_write_timeout(Some(ACCEPT_POLL_INTERVAL)));
+        if let Err(error) = prepared {
+            eprintln!("logit serve: failed to configure connection: {error}");
+            return;
+        }
+        self.respond(
+            &mut stream,
+            started,
+            "-".to_string(),
+            "-".to_string(),
+            &envelope,
+        );
+        drain_before_close(&mut stream);
+    }
+
+    fn respond(
+        &self,
+        stream: &mut TcpStream,
+        started: Instant,
+        method: String,
+        path: String,
+        envelope: &QueryEnvelope,
+    ) {
+        let response = envelope_response(envelope);
+        if let Err(error) = response.write_to(stream) {
+            eprintln!("logit serve: {error:#}");
+        }
+
+        let entry = RequestLogEntry {
+            timestamp_utc: format_unix_ms(unix_timestamp_seconds().saturating_mul(1_000)),
+            method,
+            path,
+            status: response.status,
+            duration_ms: u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX),
+            error_code: envelope.error.as_ref().map(|error| error.code.clone()),
+        };
+        if let Err(error) = log_request(self.config.request_log.as_deref(), &entry) {
+            eprintln!("logit serve: {error:#}");
+        }
+    }
+}
+
+/// Half-closes a rejected connection and discards whatever request it already
+/// sent, for at most `ACCEPT_POLL_INTERVAL`, so closing with unread input does
+/// not reset the connection before the client reads the response.
+fn drain_before_close(stream: &mut TcpStream) {
+    let deadline = Instant::now() + ACCEPT_POLL_INTERVAL;
+    if stream.shutdown(Shutdown::Write).is_err() {
+        return;
+    }
+    let mut buffer = [0; 4096];
+    while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+        if remaining.is_zero() || stream.set_read_timeout(Some(remaining)).is_err() {
+            return;
+        }
+        match stream.read(&mut buffer) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+    }
+}
+
+fn log_request(path: Option<&Path>, entry: &RequestLogEntry) -> Result<()> {
+    eprintln!(
+        "logit serve: {} {} {} {}ms",
+        entry.method, entry.path, entry.status, entry.duration_ms
+    );
+    let Some(path) = path else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create request log directory: {}",
+                parent.display()
+            )
+        })?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("failed to open request log: {}", path.display()))?;
+    let line = serde_json::to_string(entry).context("failed to encode request log entry")?;
+    writeln!(file, "{line}")
+        .with_context(|| format!("failed to append request log: {}", path.display()))
+}
+
+#[must_use]
+pub fn envelope_response(envelope: &QueryEnvelope) -> HttpResponse {
+    let status = envelope
+        .error
+        .as_ref()
+        .map_or(200, |error| status_for_error_code(&error.code));
+    match encode_envelope(envelope) {
+        Ok(body) => HttpResponse::json(status, body),
+        Err(error) => HttpResponse::json(
+            500,
+            json!({ "ok": false, "error": { "code": "query_response_encode_failed", "message": format!("{error:#}") } })
+                .to_string(),
+        ),
+    }
+}
+
+#[must_use]
+pub fn status_for_error_code(code: &str) -> u16 {
+    match code {
+        "serve_unauthorized" => 401,
+        "serve_host_forbidden" => 403,
+        "serve_route_not_found" | "session_not_found" => 404,
+        "serve_method_not_allowed" => 405,
+        "serve_payload_too_large" => 413,
+        "serve_busy"
+        | "query_mart_unavailable"
+        | "query_schema_migration_pending"
+        | "query_search_index_missing" => 503,
+        "serve_internal_error"
+        | "query_response_encode_failed"
+        | "query_schema_introspection_failed" => 500,
+        _ => 400,
+    }
+}
+
+#[must_use]
+pub fn handle_request(config: &ServeConfig, request: &HttpRequest) -> QueryEnvelope {
+    if let Some(rejection) = authorize(config, request) {
+        return rejection;
+    }
+    let segments = request.path_segments();
+    let segments = segments.iter().map(String::as_str).collect::<Vec<_>>();
+    let (command, result) = match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["health"]) => (
+            "serve.health",
+            Ok(QueryEnvelope::ok("serve.health", json!({ "status": "ok" }))
+                .with_meta("read_only", json!(true))),
+        ),
+        ("POST", ["query", "sql"]) => ("query.sql", sql_route(config, request)),
+        ("POST", ["query", "search"]) => ("query.search", search_route(config, request)),
+        ("GET", ["query", "schema"]) => (
+            "query.schema",
+            schema_query_envelope(
+                &QuerySchemaArgs {
+                    include_internal: request.query_flag("include_internal"),
+                },
+                &config.sqlite_path,
+            ),
+        ),
+        ("GET", ["query", "catalog"]) => (
+            "query.catalog",
+            Ok(catalog_query_envelope(&QueryCatalogArgs {
+                verbose: request.query_flag("verbose"),
+            })),
+        ),
+        ("GET", ["sessions"]) => ("sessions.list", sessions_envelope(&config.sqlite_path)),
+        ("GET", ["sessions", session_id, "transcript"]) => (
+            "sessions.transcript",
+            session_transcript_envelope(
+                &config.sqlite_path,
+                session_id,
+                request.query_param("format"),
+                request.query_param("turns"),
+                request.query_flag("full"),
+            ),
+        ),
+        (
+            _,
+            ["health"]
+            | ["query", "sql" | "search" | "schema" | "catalog"]
+            | ["sessions"]
+            | ["sessions", _, "transcript"],
+        ) => (
+            "serve",
+            Err(envelope_error(QueryEnvelope::error(
+                "serve",
+                "serve_method_not_allowed",
+                format!(
+                    "method {} is not allowed for {}",
+                    request.method, request.path
+                ),
+            ))),
+        ),
+        _ => (
+            "serve",
+            Err(envelope_error(QueryEnvelope::error(
+                "serve",
+                "serve_route_not_found",
+                format!("no route for {}", request.path),
+            ))),
+        ),
+    };
+    result.unwrap_or_else(
+        |error| match error.downcast::<QueryEnvelopeCommandFailure>() {
+            Ok(failure) => failure.envelope().clone(),
+            Err(error) => {
+                QueryEnvelope::error(command, "serve_internal_error", format!("{error:#}"))
+            }
+        },
+    )
+}
+
+fn authorize(config: &ServeConfig, request: &HttpRequest) -> Option<QueryEnvelope> {
+    // Checked with or without a token: a browser page on another origin can
+    // reach a loopback port through DNS rebinding, but it cannot forge the
+    // Host header its own hostname puts on the request.
+    let host_allowed = request.header("host").is_some_and(is_loopback_authority);
+    let origin_allowed = request.header("origin").is_none_or(|origin| {
+        origin
+            .strip_prefix("http://")
+            .or_else(|| origin.strip_prefix("https://"))
+            .is_some_and(is_loopback_authority)
+    });
+    if !host_allowed || !origin_allowed {
+        return Some(
+            QueryEnvelope::error(
+                "serve",
+                "serve_host_forbidden",
+                "requests must be addressed to localhost, 127.0.0.1 or [::1]",
+            )
+            .with_error_details(json!({
+                "host": request.header("host"),
+                "origin": request.header("origin"),
+            })),
+        );
+    }
+
+    let expected = config.token.as_deref()?;
+    let presented = request
+        .header("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+    if presented
+        .is_some_and(|presented| constant_time_eq(presented.as_bytes(), expected.as_bytes()))
+    {
+        return None;
+    }
+    Some(QueryEnvelope::error(
+        "serve",
+        "serve_unauthorized",
+        "missing or invalid bearer token",
+    ))
+}
+
+/// Accepts `localhost`, `127.0.0.1` or `[::1]`, optionally followed by a
+/// numeric `:port`.
+#[must_use]
+pub fn is_loopback_authority(authority: &str) -> bool {
+    let authority = authority.trim();
+    let host = match authority.rsplit_once(':') {
+        Some((host, port))
+            if !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            host
+        }
+        _ => authority,
+    };
+    host.eq_ignore_ascii_case("localhost") || host == "127.0.0.1" || host == "[::1]"
+}
+
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0_u8, |diff, (left, right)| diff | (left ^ right))
+            == 0
+}
+
+fn envelope_error(envelope: QueryEnvelope) -> Error {
+    Error::new(QueryEnvelopeCommandFailure::new(envelope))
+}
+
+fn invalid_body(command: &str, error: &serde_json::Error) -> Error {
+    envelope_error(
+        QueryEnvelope::error(
+            command,
+            "serve_request_invalid",
+            "invalid json request body",
+        )
+        .with_error_details(json!({ "cause": error.to_string() })),
+    )
+}
+
+fn sql_route(config: &ServeConfig, request: &HttpRequest) -> Result<QueryEnvelope> {
+    let body: SqlRequestBody =
+        serde_json::from_slice(&request.body).map_err(|error| invalid_body("query.sql", &error))?;
+    let args = QuerySqlArgs {
+        sql: body.sql,
+        params: body.params.map(|params| params.to_string()),
+        row_cap: body.row_cap,
+    };
+    sql_query_envelope(&args, &config.sqlite_path)
+}
+
+fn search_route(config: &ServeConfig, request: &HttpRequest) -> Result<QueryEnvelope> {
+    let body: SearchRequestBody = serde_json::from_slice(&request.body)
+        .map_err(|error| invalid_body("query.search", &error))?;
+    let args = QuerySearchArgs {
+        terms: body.terms,
+        raw: body.raw,
+        row_cap: body.row_cap,
+    };
+    search_query_envelope(&args, &config.sqlite_path)
+}
@@ -2,28 +2,37 @@ pub mod profiler;
 pub mod samples;
 
 use std::collections::BTreeMap;
-use std::io::{BufWriter, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 use serde::Serialize;
 use serde_json::{Value, json};
 
-use crate::adapters::{AdapterKind, all_adapter_kinds};
-use crate::discovery::{PrioritizedSource, SourceSelectionFilter, prioritized_sources};
+use crate::adapters::AdapterKind;
+use crate::discovery::roots::SourceRoots;
+use crate::discovery::{
+    PipelineFilterRecord, PipelineFilters, PrioritizedSource, SourceSelectionFilter,
+    known_path_registry, prioritize_sources_with_roots, zsh_history_scores,
+};
 use crate::models::{AgentSource, SCHEMA_VERSION};
-use crate::utils::redaction::DEFAULT_SNAPSHOT_MAX_CHARS;
+use crate::utils::fs::{AtomicFile, write_atomic};
+use crate::utils::protobuf;
+use crate::utils::redaction::{DEFAULT_SNAPSHOT_MAX_CHARS, RedactionRules};
 
 use self::profiler::{KeyStats, SourceProfile};
 use self::samples::{
     RepresentativeSample, SampleCandidate, extract_representative_samples,
-    redact_and_truncate_samples,
+    redact_and_truncate_samples_with_rules,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SnapshotConfig {
     pub sample_size: usize,
     pub redact_sensitive_values: bool,
+    pub redaction_rules: RedactionRules,
+    pub filters: PipelineFilters,
+    pub source_roots: SourceRoots,
 }
 
 impl Default for SnapshotConfig {
@@ -31,6 +40,9 @@ impl Default for SnapshotConfig {
         Self {
             sample_size: 3,
             redact_sensitive_values: true,
+            redaction_rules: RedactionRules::default(),
+            filters: PipelineFilters::default(),
+            source_roots: SourceRoots::default(),
         }
     }
 }
@@ -63,6 +75,7 @@ pub struct SnapshotIndexCounts {
 pub struct SnapshotDiscoveredSource {
     pub adapter: String,
     pub source_kind: String,
+    pub source_profile: String,
     pub path: String,
     pub resolved_path: String,
     pub format_hint: String,
@@ -81,6 +94,9 @@ pub struct SnapshotIndex {
     pub counts: SnapshotIndexCounts,
     pub sources: Vec<SnapshotDiscoveredSource>,
     pub warnings: Vec<String>,
+    pub filters: PipelineFilterRecord,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -132,11 +148,26 @@ pub fn collect_snapshot_data(
     source_root_override: Option<&Path>,
     zsh_history: &str,
 ) -> Result<SnapshotCollection> {
+    let (adapters, unknown_adapters) = config.filters.builtin_adapters();
+    if let Some(unknown) = unknown_adapters.first() {
+        bail!("unknown adapter `{unknown}` (snapshot profiles built-in adapters only)");
+    }
     let filter = SourceSelectionFilter {
-        adapters: all_adapter_kinds().to_vec(),
-        ..SourceSelectionFilter::default()
+        adapters,
+        format_hints: config.filters.format_hints.clone(),
+        path_substrings: config.filters.path_substrings.clone(),
+    };
+    let prioritized = if filter.adapters.is_empty() {
+        Vec::new()
+    } else {
+        prioritize_sources_with_roots(
+            &known_path_registry(),
+            &zsh_history_scores(zsh_history),
+            &filter,
+            &config.source_roots,
+        )
     };
-    let prioritized = prioritized_sources(zsh_history, &filter);
+    let mut filter_record = config.filters.record();
 
     let mut discovered_sources = Vec::new();
     let mut profile_entries = Vec::new();
@@ -158,7 +189,15 @@ pub fn collect_snapshot_data(
         let mut files_profiled = 0usize;
         let mut records_profiled = 0usize;
         let parseable_files = match collect_parseable_files(&resolved, source) {
-            Ok(files) => files,
+            Ok(files) => {
+                let total = files.len();
+                let kept = files
+                    .into_iter()
+                    .filter(|file| config.filters.window.admits_file(file))
+                    .collect::<Vec<_>>();
+                filter_record.files_pruned_by_mtime += total - kept.len();
+                kept
+            }
             Err(error) => {
                 source_warnings.push(format!(
                     "adapter `{}` source path unreadable `{}`: {error}",
@@ -187,7 +226,7 @@ pub fn collect_snapshot_data(
             let source_kind = adapter_to_source(source.adapter);
             for record in parsed.records {
                 sample_candidates.push(SampleCandidate {
-                    source_kind,
+                    source_kind: source_kind.clone(),
                     source_path: file.to_string_lossy().to_string(),
                     source_record_locator: record.locator,
                     record: record.value,
@@ -208,7 +247,8 @@ pub fn collect_snapshot_data(
 
         discovered_sources.push(SnapshotDiscoveredSource {
             adapter: source.adapter.as_str().to_string(),
-            source_kind: source_kind_key(adapter_to_source(source.adapter)).to_string(),
+            source_kind: adapter_to_source(source.adapter).as_str().to_string(),
+            source_profile: source.profile.clone(),
             path: source.path.clone(),
             resolved_path: resolved.to_string_lossy().to_string(),
             format_hint: format_hint_key(source).to_string(),
@@ -220,7 +260,7 @@ pub fn collect_snapshot_data(
 
         profile_entries.push(SnapshotSchemaProfileEntry {
             adapter: source.adapter.as_str().to_string(),
-            source_kind: source_kind_key(adapter_to_source(source.adapter)).to_string(),
+            source_kind: adapter_to_source(source.adapter).as_str().to_string(),
             source_path: source.path.clone(),
             resolved_path: resolved.to_string_lossy().to_string(),
             format_hint: format_hint_key(source).to_string(),
@@ -234,7 +274,11 @@ pub fn collect_snapshot_data(
 
     let mut samples = extract_representative_samples(&sample_candidates, config.sample_size);
     if config.redact_sensitive_values {
-        samples = redact_and_truncate_samples(&samples, DEFAULT_SNAPSHOT_MAX_CHARS);
+        samples = redact_and_truncate_samples_with_rules(
+            &samples,
+            DEFAULT_SNAPSHOT_MAX_CHARS,
+            &config.redaction_rules,
+        );
     }
 
     let index = SnapshotIndex {
@@ -256,6 +300,8 @@ pub fn collect_snapshot_data(
         },
         sources: discovered_sources,
         warnings,
+        filters: filter_record,
+        run_id: None,
     };
 
     Ok(SnapshotCollection {
@@ -284,15 +330,15 @@ pub fn write_index_artifact(path: &Path, index: &SnapshotIndex) -> Result<()> {
         std::fs::create_dir_all(parent).context("failed to create snapshot index directory")?;
     }
     let encoded = serde_json::to_vec_pretty(index).context("failed to encode snapshot index")?;
-    std::fs::write(path, encoded).context("failed to write snapshot index artifact")
+    write_atomic(path, &encoded).context("failed to write snapshot index artifact")
 }
 
 pub fn write_samples_artifact(path: &Path, samples: &[RepresentativeSample]) -> Result<()> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent).context("failed to create snapshot samples directory")?;
     }
-    let file = std::fs::File::create(path).context("failed to create snapshot samples artifact")?;
-    let mut writer = BufWriter::new(file);
+    let mut writer =
+        AtomicFile::create(path).context("failed to create snapshot samples artifact")?;
     for sample in samples {
         serde_json::to_writer(&mut writer, sample)
             .context("failed to encode snapshot samples jsonl row")?;
@@ -301,8 +347,8 @@ pub fn write_samples_artifact(path: &Path, samples: &[RepresentativeSample]) ->
             .context("failed to write snapshot samples newline")?;
     }
     writer
-        .flush()
-        .context("failed to flush snapshot samples artifact writer")
+        .commit()
+        .context("failed to commit snapshot samples artifact")
 }
 
 pub fn write_schema_profile_artifact(path: &Path, profile: &SnapshotSchemaProfile) -> Result<()> {
@@ -312,7 +358,7 @@ pub fn write_schema_profile_artifact(path: &Path, profile: &SnapshotSchemaProfil
     }
     let encoded =
         serde_json::to_vec_pretty(profile).context("failed to encode snapshot schema profile")?;
-    std::fs::write(path, encoded).context("failed to write snapshot schema profile artifact")
+    write_atomic(path, &encoded).context("failed to write snapshot schema profile artifact")
 }
 
 pub fn verify_snapshot_collection_integrity(collection: &SnapshotCollection) -> Result<()> {
@@ -493,7 +539,7 @@ fn validate_sample_determinism(samples: &[RepresentativeSample]) -> Vec<String>
 
     for sample in samples {
         let key = (
-            source_kind_key(sample.source_kind).to_string(),
+            sample.source_kind.as_str().to_string(),
             sample.source_path.clone(),
         );
         let expected = *expected_rank_by_source.get(&key).unwrap_or(&0);
@@ -523,8 +569,9 @@ fn compare_representative_samples(
     left: &RepresentativeSample,
     right: &RepresentativeSample,
 ) -> std::cmp::Ordering {
-    source_kind_key(left.source_kind)
-        .cmp(source_kind_key(right.source_kind))
+    left.source_kind
+        .as_str()
+        .cmp(right.source_kind.as_str())
         .then_with(|| left.source_path.cmp(&right.source_path))
         .then_with(|| left.sample_rank.cmp(&right.sample_rank))
         .then_with(|| left.source_record_locator.cmp(&right.source_record_locator))
@@ -649,25 +696,39 @@ fn parse_snapshot_records(path: &Path) -> Result<ParsedSnapshotRecords> {
 }
 
 fn parse_snapshot_protobuf_records(path: &Path) -> Result<ParsedSnapshotRecords> {
-    let metadata = std::fs::metadata(path)
-        .with_context(|| format!("failed to stat protobuf source file: {}", path.display()))?;
-    let file_name = path
-        .file_name()
-        .and_then(std::ffi::OsStr::to_str)
-        .unwrap_or("unknown.pb");
-
-    Ok(ParsedSnapshotRecords {
-        records: vec![ParsedSnapshotRecord {
-            locator: "protobuf:metadata".to_string(),
-            value: json!({
-                "snapshot_only": true,
-                "artifact_kind": "protobuf-binary",
-                "file_name": file_name,
-                "byte_size": metadata.len(),
-            }),
-        }],
-        warnings: vec!["protobuf binary indexed as snapshot-only metadata; decode skipped".into()],
-    })
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("failed to read protobuf source file: {}", path.display()))?;
+
+    match protobuf::decode_message(&bytes) {
+        Ok(value) => Ok(ParsedSnapshotRecords {
+            records: vec![ParsedSnapshotRecord {
+                locator: "protobuf:root".to_string(),
+                value,
+            }],
+            warnings: Vec::new(),
+        }),
+        Err(error) => {
+            let file_name = path
+                .file_name()
+                .and_then(std::ffi::OsStr::to_str)
+                .unwrap_or("unknown.pb");
+
+            Ok(ParsedSnapshotRecords {
+                records: vec![ParsedSnapshotRecord {
+                    locator: "protobuf:metadata".to_string(),
+                    value: json!({
+                        "snapshot_only": true,
+                        "artifact_kind": "protobuf-binary",
+                        "file_name": file_name,
+                        "byte_size": bytes.len(),
+                    }),
+                }],
+                warnings: vec![format!(
+                    "protobuf decode failed ({error}); indexed as snapshot-only metadata"
+                )],
+            })
+        }
+    }
 }
 
 fn parse_snapshot_jsonl_records(input: &str) -> ParsedSnapshotRecords {
@@ -793,16 +854,9 @@ fn adapter_to_source(adapter: AdapterKind) -> AgentSource {
         AdapterKind::Gemini => AgentSource::Gemini,
         AdapterKind::Amp => AgentSource::Amp,
         AdapterKind::OpenCode => AgentSource::OpenCode,
-    }
-}
-
-fn source_kind_key(source_kind: AgentSource) -> &'static str {
-    match source_kind {
-        AgentSource::Codex => "codex",
-        AgentSource::Claude => "claude",
-        AgentSource::Gemini => "gemini",
-        AgentSource::Amp => "amp",
-        AgentSource::OpenCode => "opencode",
+        AdapterKind::Aider => AgentSource::Aider,
+        AdapterKind::Cursor => AgentSource::Cursor,
+        AdapterKind::Copilot => AgentSource::Copilot,
     }
 }
 
@@ -812,5 +866,6 @@ fn format_hint_key(source: &PrioritizedSource) -> &'static str {
         crate::discovery::SourceFormatHint::Json => "json",
         crate::discovery::SourceFormatHint::Jsonl => "jsonl",
         crate::discovery::SourceFormatHint::TextLog => "text_log",
+        crate::discovery::SourceFormatHint::Sqlite => "sqlite",
     }
 }
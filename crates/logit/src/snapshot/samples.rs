@@ -4,7 +4,7 @@ use serde::Serialize;
 use serde_json::Value;
 
 use crate::models::AgentSource;
-use crate::utils::redaction;
+use crate::utils::redaction::{self, RedactionRules};
 
 use super::profiler::extract_event_kind;
 
@@ -41,7 +41,7 @@ pub fn extract_representative_samples(
     for candidate in candidates {
         grouped
             .entry((
-                source_kind_key(candidate.source_kind).to_string(),
+                candidate.source_kind.as_str().to_string(),
                 candidate.source_path.clone(),
             ))
             .or_default()
@@ -56,7 +56,7 @@ pub fn extract_representative_samples(
         for (sample_rank, index) in selected_indices.into_iter().enumerate() {
             let candidate = &source_candidates[index];
             extracted.push(RepresentativeSample {
-                source_kind: candidate.source_kind,
+                source_kind: candidate.source_kind.clone(),
                 source_path: candidate.source_path.clone(),
                 source_record_locator: candidate.source_record_locator.clone(),
                 sample_rank,
@@ -73,11 +73,21 @@ pub fn extract_representative_samples(
 pub fn redact_and_truncate_samples(
     samples: &[RepresentativeSample],
     max_chars: usize,
+) -> Vec<RepresentativeSample> {
+    redact_and_truncate_samples_with_rules(samples, max_chars, &RedactionRules::default())
+}
+
+#[must_use]
+pub fn redact_and_truncate_samples_with_rules(
+    samples: &[RepresentativeSample],
+    max_chars: usize,
+    rules: &RedactionRules,
 ) -> Vec<RepresentativeSample> {
     samples
         .iter()
         .map(|sample| {
-            let redacted = redaction::redact_and_truncate_json(&sample.record, max_chars);
+            let redacted =
+                redaction::redact_and_truncate_json_with_rules(&sample.record, max_chars, rules);
             let mut record = redacted.value;
             let sanitized_for_size =
                 enforce_sample_record_size(&mut record, MAX_SAMPLE_RECORD_CHARS);
@@ -106,7 +116,7 @@ pub fn redact_and_truncate_samples(
             }
 
             RepresentativeSample {
-                source_kind: sample.source_kind,
+                source_kind: sample.source_kind.clone(),
                 source_path: sample.source_path.clone(),
                 source_record_locator: sample.source_record_locator.clone(),
                 sample_rank: sample.sample_rank,
@@ -225,21 +235,11 @@ fn evenly_spaced_indices(total: usize, count: usize) -> Vec<usize> {
 fn compare_candidates(left: &SampleCandidate, right: &SampleCandidate) -> std::cmp::Ordering {
     left.source_record_locator
         .cmp(&right.source_record_locator)
-        .then_with(|| source_kind_key(left.source_kind).cmp(source_kind_key(right.source_kind)))
+        .then_with(|| left.source_kind.as_str().cmp(right.source_kind.as_str()))
         .then_with(|| left.source_path.cmp(&right.source_path))
         .then_with(|| canonical_json(&left.record).cmp(&canonical_json(&right.record)))
 }
 
-fn source_kind_key(source_kind: AgentSource) -> &'static str {
-    match source_kind {
-        AgentSource::Codex => "codex",
-        AgentSource::Claude => "claude",
-        AgentSource::Gemini => "gemini",
-        AgentSource::Amp => "amp",
-        AgentSource::OpenCode => "opencode",
-    }
-}
-
 fn canonical_json(value: &Value) -> String {
     serde_json::to_string(value).unwrap_or_default()
 }
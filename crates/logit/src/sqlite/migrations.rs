@@ -0,0 +1,490 @@
+//! Ordered, forward-only migrations for the SQLite mart.
+//!
+//! Each migration runs in its own `BEGIN IMMEDIATE` transaction together with
+//! the update that records its number on the current `agentlog_schema_meta`
+//! row, so a mart is always at a whole migration version. Views and the full-text index are derived objects,
+//! not migrations: after the migrations run, every view whose stored definition
+//! differs from the one compiled into this build is dropped and recreated, and
+//! a missing full-text index is rebuilt from `agentlog_events`.
+//!
+//! To change a table (a new column, a relaxed CHECK constraint) append a
+//! migration; never edit one that has shipped. Constraint changes rebuild the
+//! table: create `<table>_new`, copy rows, drop the old table, rename.
+
+use anyhow::{Context, Result};
+use rusqlite::{Connection, OptionalExtension, Transaction, TransactionBehavior, params};
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use super::{
+    ADAPTERS_VIEW, CREATE_EVENTS_FTS_TABLE_SQL, CREATE_EVENTS_TABLE_SQL,
+    CREATE_INDEX_ADAPTER_EVENT_SQL, CREATE_INDEX_HASHES_SQL, CREATE_INDEX_HOST_TIME_SQL,
+    CREATE_INDEX_INGEST_RUNS_STATUS_SQL, CREATE_INDEX_INGEST_WATERMARKS_HOST_SQL,
+    CREATE_INDEX_INGEST_WATERMARKS_REFRESH_SQL, CREATE_INDEX_INGEST_WATERMARKS_SOURCE_SQL,
+    CREATE_INDEX_RUN_SEQUENCE_SQL, CREATE_INDEX_SESSION_TIME_SQL, CREATE_INDEX_SOURCE_SQL,
+    CREATE_INDEX_TIME_SQL, CREATE_INGEST_RUNS_TABLE_SQL, CREATE_INGEST_WATERMARKS_TABLE_SQL,
+    CREATE_META_TABLE_SQL, CREATE_VIEW_ADAPTERS_SQL, CREATE_VIEW_HOSTS_SQL,
+    CREATE_VIEW_QUALITY_SQL, CREATE_VIEW_SESSIONS_SQL, CREATE_VIEW_TOOL_CALLS_SQL,
+    EVENTS_FTS_TABLE, EVENTS_TABLE, FTS_COLUMNS, HOSTS_VIEW, INGEST_RUNS_TABLE,
+    INGEST_WATERMARKS_TABLE, QUALITY_VIEW, SCHEMA_META_TABLE, SESSIONS_VIEW, SQLITE_SCHEMA_VERSION,
+    TOOL_CALLS_VIEW,
+};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub apply: fn(&Connection) -> Result<()>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "baseline",
+        description: "create events, ingest run and watermark tables with their indexes",
+        apply: apply_baseline,
+    },
+    Migration {
+        version: 2,
+        name: "ingest_run_metadata",
+        description: "add redaction_policy, filters_json and owner_pid to ingest_runs",
+        apply: apply_ingest_run_metadata,
+    },
+    Migration {
+        version: 3,
+        name: "host_identity",
+        description: "add host to agentlog_events, ingest_runs and ingest_watermarks",
+        apply: apply_host_identity,
+    },
+    Migration {
+        version: 4,
+        name: "custom_source_kinds",
+        description: "relax the source_kind and adapter_name CHECK constraints to admit custom adapter slugs",
+        apply: apply_custom_source_kinds,
+    },
+];
+
+pub const VIEW_DEFINITIONS: &[(&str, &str)] = &[
+    (TOOL_CALLS_VIEW, CREATE_VIEW_TOOL_CALLS_SQL),
+    (SESSIONS_VIEW, CREATE_VIEW_SESSIONS_SQL),
+    (ADAPTERS_VIEW, CREATE_VIEW_ADAPTERS_SQL),
+    (QUALITY_VIEW, CREATE_VIEW_QUALITY_SQL),
+    (HOSTS_VIEW, CREATE_VIEW_HOSTS_SQL),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationRecord {
+    pub version: u32,
+    pub name: String,
+    pub description: String,
+}
+
+impl From<&Migration> for MigrationRecord {
+    fn from(migration: &Migration) -> Self {
+        Self {
+            version: migration.version,
+            name: migration.name.to_string(),
+            description: migration.description.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationStatus {
+    pub schema_version: String,
+    pub current_version: u32,
+    pub latest_version: u32,
+    pub pending: Vec<MigrationRecord>,
+    /// Views that are missing or whose definition differs from this build.
+    pub stale_views: Vec<String>,
+}
+
+impl MigrationStatus {
+    #[must_use]
+    pub fn is_current(&self) -> bool {
+        self.pending.is_empty() && self.stale_views.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MigrationOutcome {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub applied: Vec<MigrationRecord>,
+    pub recreated_views: Vec<String>,
+}
+
+#[must_use]
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Reports how far the mart is behind this build without writing to it, so it
+/// works on read-only connections.
+pub fn migration_status(connection: &Connection) -> Result<MigrationStatus> {
+    let current_version = current_version(connection)?;
+    Ok(MigrationStatus {
+        schema_version: SQLITE_SCHEMA_VERSION.to_string(),
+        current_version,
+        latest_version: latest_version(),
+        pending: MIGRATIONS
+            .iter()
+            .filter(|migration| migration.version > current_version)
+            .map(MigrationRecord::from)
+            .collect(),
+        stale_views: stale_views(connection)?,
+    })
+}
+
+/// Whether the mart has been initialized at all, by a migration or by the
+/// create-if-not-exists schema that preceded migrations.
+pub fn schema_initialized(connection: &Connection) -> Result<bool> {
+    object_exists(connection, "table", SCHEMA_META_TABLE)
+}
+
+/// Whether the full-text index exists; it is created outside the numbered
+/// migrations, so an up-to-date mart can still lack it until the next write.
+pub fn events_fts_present(connection: &Connection) -> Result<bool> {
+    object_exists(connection, "table", EVENTS_FTS_TABLE)
+}
+
+pub fn apply_migrations(connection: &Connection) -> Result<MigrationOutcome> {
+    connection
+        .execute_batch(CREATE_META_TABLE_SQL)
+        .context("failed to create sqlite schema meta table")?;
+    ensure_column(
+        connection,
+        SCHEMA_META_TABLE,
+        "migration_version",
+        "INTEGER",
+    )?;
+    ensure_column(connection, SCHEMA_META_TABLE, "migration_name", "TEXT")?;
+
+    let from_version = current_version(connection)?;
+    let mut applied = Vec::new();
+    for migration in MIGRATIONS {
+        let tx = Transaction::new_unchecked(connection, TransactionBehavior::Immediate)
+            .context("failed to open sqlite migration transaction")?;
+        if current_version(&tx)? >= migration.version {
+            continue;
+        }
+        (migration.apply)(&tx).with_context(|| {
+            format!(
+                "sqlite migration {} ({}) failed",
+                migration.version, migration.name
+            )
+        })?;
+        record_migration(&tx, migration)?;
+        tx.commit()
+            .with_context(|| format!("failed to commit sqlite migration {}", migration.version))?;
+        applied.push(MigrationRecord::from(migration));
+    }
+
+    let tx = Transaction::new_unchecked(connection, TransactionBehavior::Immediate)
+        .context("failed to open sqlite view transaction")?;
+    let recreated_views = stale_views(&tx)?;
+    for view in &recreated_views {
+        let Some((_, definition)) = VIEW_DEFINITIONS.iter().find(|(name, _)| name == view) else {
+            continue;
+        };
+        tx.execute_batch(&format!("DROP VIEW IF EXISTS {view};\n{definition}"))
+            .with_context(|| format!("failed to recreate sqlite view {view}"))?;
+    }
+    ensure_events_fts_table(&tx)?;
+    tx.commit()
+        .context("failed to commit sqlite view changes")?;
+
+    Ok(MigrationOutcome {
+        from_version,
+        to_version: current_version(connection)?,
+        applied,
+        recreated_views,
+    })
+}
+
+fn record_migration(connection: &Connection, migration: &Migration) -> Result<()> {
+    let updated = connection
+        .execute(
+            &format!(
+                "UPDATE {SCHEMA_META_TABLE} SET migration_version = ?2, migration_name = ?3
+                 WHERE schema_version = ?1"
+            ),
+            params![SQLITE_SCHEMA_VERSION, migration.version, migration.name],
+        )
+        .with_context(|| format!("failed to record sqlite migration {}", migration.version))?;
+    if updated == 0 {
+        connection
+            .execute(
+                &format!(
+                    "INSERT INTO {SCHEMA_META_TABLE} (schema_version, applied_at_utc, migration_version, migration_name)
+                     VALUES (?1, ?2, ?3, ?4)"
+                ),
+                params![
+                    SQLITE_SCHEMA_VERSION,
+                    applied_at_utc()?,
+                    migration.version,
+                    migration.name
+                ],
+            )
+            .with_context(|| format!("failed to record sqlite migration {}", migration.version))?;
+    }
+    Ok(())
+}
+
+fn current_version(connection: &Connection) -> Result<u32> {
+    if !object_exists(connection, "table", SCHEMA_META_TABLE)?
+        || !column_exists(connection, SCHEMA_META_TABLE, "migration_version")?
+    {
+        return Ok(0);
+    }
+    let version = connection
+        .query_row(
+            &format!(
+                "SELECT COALESCE(MAX(migration_version), 0) FROM {SCHEMA_META_TABLE}
+                 WHERE schema_version = ?1"
+            ),
+            [SQLITE_SCHEMA_VERSION],
+            |row| row.get::<usize, u32>(0),
+        )
+        .context("failed to read sqlite migration version")?;
+    Ok(version)
+}
+
+fn stale_views(connection: &Connection) -> Result<Vec<String>> {
+    let mut stale = Vec::new();
+    for (name, definition) in VIEW_DEFINITIONS {
+        let stored = connection
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'view' AND name = ?1",
+                [name],
+                |row| row.get::<usize, String>(0),
+            )
+            .optional()
+            .with_context(|| format!("failed to read sqlite view definition: {name}"))?;
+        if stored.is_none_or(|stored| canonical_view_sql(&stored) != canonical_view_sql(definition))
+        {
+            stale.push((*name).to_string());
+        }
+    }
+    Ok(stale)
+}
+
+/// SQLite stores a view's `CREATE` text minus `IF NOT EXISTS` and the trailing
+/// semicolon; compare on that form with whitespace collapsed.
+fn canonical_view_sql(sql: &str) -> String {
+    sql.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replacen("CREATE VIEW IF NOT EXISTS ", "CREATE VIEW ", 1)
+        .trim_end_matches(';')
+        .trim_end()
+        .to_string()
+}
+
+fn object_exists(connection: &Connection, kind: &str, name: &str) -> Result<bool> {
+    let exists = connection
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = ?1 AND name = ?2)",
+            [kind, name],
+            |row| row.get::<usize, i64>(0),
+        )
+        .with_context(|| format!("failed to inspect sqlite {kind} {name}"))?;
+    Ok(exists != 0)
+}
+
+// `table_xinfo` rather than `table_info` so generated columns are visible.
+fn column_exists(connection: &Connection, table: &str, column: &str) -> Result<bool> {
+    let exists = connection
+        .query_row(
+            &format!("SELECT EXISTS(SELECT 1 FROM pragma_table_xinfo('{table}') WHERE name = ?1)"),
+            [column],
+            |row| row.get::<usize, i64>(0),
+        )
+        .with_context(|| format!("failed to inspect {table} columns"))?;
+    Ok(exists != 0)
+}
+
+fn ensure_column(
+    connection: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    if !column_exists(connection, table, column)? {
+        connection
+            .execute_batch(&format!(
+                "ALTER TABLE {table} ADD COLUMN {column} {definition}"
+            ))
+            .with_context(|| format!("failed to add {table}.{column} column"))?;
+    }
+    Ok(())
+}
+
+fn applied_at_utc() -> Result<String> {
+    OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .context("failed to format sqlite migration applied timestamp")
+}
+
+// Marts created before migrations existed already hold these objects, so the
+// baseline only creates what is missing.
+fn apply_baseline(connection: &Connection) -> Result<()> {
+    for statement in [
+        CREATE_EVENTS_TABLE_SQL,
+        CREATE_INDEX_RUN_SEQUENCE_SQL,
+        CREATE_INDEX_TIME_SQL,
+        CREATE_INDEX_ADAPTER_EVENT_SQL,
+        CREATE_INDEX_SOURCE_SQL,
+        CREATE_INDEX_HASHES_SQL,
+        CREATE_INDEX_SESSION_TIME_SQL,
+        CREATE_INGEST_RUNS_TABLE_SQL,
+        CREATE_INDEX_INGEST_RUNS_STATUS_SQL,
+        CREATE_INGEST_WATERMARKS_TABLE_SQL,
+        CREATE_INDEX_INGEST_WATERMARKS_SOURCE_SQL,
+        CREATE_INDEX_INGEST_WATERMARKS_REFRESH_SQL,
+    ] {
+        connection
+            .execute_batch(statement)
+            .context("failed to create sqlite baseline schema")?;
+    }
+    Ok(())
+}
+
+fn apply_ingest_run_metadata(connection: &Connection) -> Result<()> {
+    ensure_column(connection, INGEST_RUNS_TABLE, "redaction_policy", "TEXT")?;
+    ensure_column(
+        connection,
+        INGEST_RUNS_TABLE,
+        "filters_json",
+        "TEXT NOT NULL DEFAULT '{}'",
+    )?;
+    ensure_column(connection, INGEST_RUNS_TABLE, "owner_pid", "INTEGER")
+}
+
+fn apply_host_identity(connection: &Connection) -> Result<()> {
+    ensure_column(
+        connection,
+        EVENTS_TABLE,
+        "host",
+        "TEXT GENERATED ALWAYS AS (json_extract(metadata_json, '$.host')) VIRTUAL",
+    )?;
+    ensure_column(connection, INGEST_RUNS_TABLE, "host", "TEXT")?;
+    ensure_column(connection, INGEST_WATERMARKS_TABLE, "host", "TEXT")?;
+    for statement in [
+        CREATE_INDEX_HOST_TIME_SQL,
+        CREATE_INDEX_INGEST_WATERMARKS_HOST_SQL,
+    ] {
+        connection
+            .execute_batch(statement)
+            .context("failed to create sqlite host indexes")?;
+    }
+    Ok(())
+}
+
+// Marts created before custom adapters pinned `source_kind`/`adapter_name` to
+// a fixed `IN (...)` list. Rebuilding keeps rowids, which the full-text index
+// is keyed on; views are dropped first so the rename does not trip over them,
+// and are recreated as missing once the migrations finish.
+fn apply_custom_source_kinds(connection: &Connection) -> Result<()> {
+    let tables = [
+        (
+            EVENTS_TABLE,
+            CREATE_EVENTS_TABLE_SQL,
+            &[
+                CREATE_INDEX_RUN_SEQUENCE_SQL,
+                CREATE_INDEX_TIME_SQL,
+                CREATE_INDEX_ADAPTER_EVENT_SQL,
+                CREATE_INDEX_SOURCE_SQL,
+                CREATE_INDEX_HASHES_SQL,
+                CREATE_INDEX_SESSION_TIME_SQL,
+                CREATE_INDEX_HOST_TIME_SQL,
+            ][..],
+        ),
+        (
+            INGEST_WATERMARKS_TABLE,
+            CREATE_INGEST_WATERMARKS_TABLE_SQL,
+            &[
+                CREATE_INDEX_INGEST_WATERMARKS_SOURCE_SQL,
+                CREATE_INDEX_INGEST_WATERMARKS_REFRESH_SQL,
+                CREATE_INDEX_INGEST_WATERMARKS_HOST_SQL,
+            ][..],
+        ),
+    ];
+    let mut views_dropped = false;
+    for (table, create_sql, indexes) in tables {
+        let stored = connection
+            .query_row(
+                "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [table],
+                |row| row.get::<usize, String>(0),
+            )
+            .optional()
+            .with_context(|| format!("failed to read sqlite table definition: {table}"))?;
+        if !stored.is_some_and(|sql| sql.contains("source_kind IN (")) {
+            continue;
+        }
+        if !views_dropped {
+            for (view, _) in VIEW_DEFINITIONS {
+                connection
+                    .execute_batch(&format!("DROP VIEW IF EXISTS {view};"))
+                    .with_context(|| format!("failed to drop sqlite view {view}"))?;
+            }
+            views_dropped = true;
+        }
+        rebuild_table(connection, table, create_sql)?;
+        for statement in indexes {
+            connection
+                .execute_batch(statement)
+                .with_context(|| format!("failed to recreate {table} indexes"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Recreates `table` from `create_sql`, copying every stored (non-generated)
+/// column together with the rowid.
+fn rebuild_table(connection: &Connection, table: &str, create_sql: &str) -> Result<()> {
+    let columns = connection
+        .prepare(&format!("SELECT name FROM pragma_table_info('{table}')"))
+        .and_then(|mut statement| {
+            statement
+                .query_map([], |row| row.get::<usize, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })
+        .with_context(|| format!("failed to inspect {table} columns"))?
+        .join(", ");
+    let create_new = create_sql.replacen(
+        &format!("CREATE TABLE IF NOT EXISTS {table} ("),
+        &format!("CREATE TABLE {table}_new ("),
+        1,
+    );
+    connection
+        .execute_batch(&format!(
+            "{create_new}
+             INSERT INTO {table}_new (rowid, {columns}) SELECT rowid, {columns} FROM {table};
+             DROP TABLE {table};
+             ALTER TABLE {table}_new RENAME TO {table};"
+        ))
+        .with_context(|| format!("failed to rebuild {table}"))
+}
+
+fn ensure_events_fts_table(connection: &Connection) -> Result<()> {
+    if object_exists(connection, "table", EVENTS_FTS_TABLE)? {
+        return Ok(());
+    }
+    connection
+        .execute_batch(CREATE_EVENTS_FTS_TABLE_SQL)
+        .context("failed to create full-text search table")?;
+    connection
+        .execute(
+            &format!(
+                "INSERT INTO {EVENTS_FTS_TABLE} (rowid, {FTS_COLUMNS})
+                 SELECT rowid, {FTS_COLUMNS} FROM {EVENTS_TABLE}"
+            ),
+            [],
+        )
+        .context("failed to backfill full-text search table")?;
+    Ok(())
+}
@@ -1,15 +1,15 @@
+pub mod migrations;
+pub mod reader;
+
 use std::collections::BTreeMap;
 use std::path::Path;
 
 use anyhow::{Context, Result, anyhow};
 use rusqlite::types::Value as SqlValue;
-use rusqlite::{Connection, params, params_from_iter};
-use time::OffsetDateTime;
-use time::format_description::well_known::Rfc3339;
+use rusqlite::{Connection, OpenFlags, params_from_iter};
 
-use crate::models::{
-    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
-};
+use crate::models::{ActorRole, AgentLogEvent, EventType, RecordFormat, TimestampQuality};
+use crate::utils::host::event_host;
 
 pub const SQLITE_SCHEMA_VERSION: &str = "agentlog.v1.sqlite.v1";
 pub const EVENTS_TABLE: &str = "agentlog_events";
@@ -19,7 +19,9 @@ pub const TOOL_CALLS_VIEW: &str = "v_tool_calls";
 pub const SESSIONS_VIEW: &str = "v_sessions";
 pub const ADAPTERS_VIEW: &str = "v_adapters";
 pub const QUALITY_VIEW: &str = "v_quality";
+pub const HOSTS_VIEW: &str = "v_hosts";
 pub const SCHEMA_META_TABLE: &str = "agentlog_schema_meta";
+pub const EVENTS_FTS_TABLE: &str = "agentlog_events_fts";
 pub const DEFAULT_INSERT_BATCH_SIZE: usize = 500;
 
 pub const EVENT_INSERT_COLUMNS: &[&str] = &[
@@ -115,9 +117,10 @@ CREATE TABLE IF NOT EXISTS agentlog_events (
     raw_hash TEXT NOT NULL,
     canonical_hash TEXT NOT NULL,
     metadata_json TEXT NOT NULL DEFAULT '{}',
+    host TEXT GENERATED ALWAYS AS (json_extract(metadata_json, '$.host')) VIRTUAL,
     CHECK (schema_version = 'agentlog.v1'),
-    CHECK (source_kind IN ('codex', 'claude', 'gemini', 'amp', 'opencode')),
-    CHECK (adapter_name IN ('codex', 'claude', 'gemini', 'amp', 'opencode')),
+    CHECK (source_kind GLOB '[a-z]*' AND source_kind NOT GLOB '*[^a-z0-9_-]*'),
+    CHECK (adapter_name GLOB '[a-z]*' AND adapter_name NOT GLOB '*[^a-z0-9_-]*'),
     CHECK (record_format IN ('message', 'tool_call', 'tool_result', 'system', 'diagnostic')),
     CHECK (event_type IN (
         'prompt',
@@ -167,6 +170,11 @@ CREATE INDEX IF NOT EXISTS idx_agentlog_events_session_time
 ON agentlog_events (session_id, timestamp_unix_ms);
 "#;
 
+const CREATE_INDEX_HOST_TIME_SQL: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_agentlog_events_host_time
+ON agentlog_events (host, timestamp_unix_ms);
+"#;
+
 const CREATE_VIEW_TOOL_CALLS_SQL: &str = r#"
 CREATE VIEW IF NOT EXISTS v_tool_calls AS
 WITH call_events AS (
@@ -301,13 +309,28 @@ SELECT
     COUNT(DISTINCT conversation_id) AS distinct_conversation_count,
     COUNT(DISTINCT turn_id) AS distinct_turn_count,
     COUNT(DISTINCT tool_name) AS distinct_tool_count,
-    COUNT(DISTINCT adapter_name) AS distinct_adapter_count
+    COUNT(DISTINCT adapter_name) AS distinct_adapter_count,
+    MAX(host) AS host
 FROM agentlog_events
 WHERE session_id IS NOT NULL
   AND session_id != ''
 GROUP BY run_id, session_id;
 "#;
 
+const CREATE_VIEW_HOSTS_SQL: &str = r#"
+CREATE VIEW IF NOT EXISTS v_hosts AS
+SELECT
+    COALESCE(host, 'unknown') AS host,
+    COUNT(*) AS event_count,
+    COUNT(DISTINCT run_id) AS run_count,
+    COUNT(DISTINCT session_id) AS session_count,
+    COUNT(DISTINCT adapter_name) AS adapter_count,
+    MIN(timestamp_unix_ms) AS first_event_timestamp_unix_ms,
+    MAX(timestamp_unix_ms) AS last_event_timestamp_unix_ms
+FROM agentlog_events
+GROUP BY COALESCE(host, 'unknown');
+"#;
+
 const CREATE_VIEW_ADAPTERS_SQL: &str = r#"
 CREATE VIEW IF NOT EXISTS v_adapters AS
 SELECT
@@ -355,6 +378,10 @@ CREATE TABLE IF NOT EXISTS ingest_runs (
     warnings_count INTEGER NOT NULL DEFAULT 0,
     errors_count INTEGER NOT NULL DEFAULT 0,
     error_summary_json TEXT NOT NULL DEFAULT '{}',
+    redaction_policy TEXT,
+    filters_json TEXT NOT NULL DEFAULT '{}',
+    owner_pid INTEGER,
+    host TEXT,
     CHECK (status IN ('running', 'success', 'partial_failure', 'failed')),
     CHECK (events_read >= 0),
     CHECK (events_written >= 0),
@@ -380,7 +407,8 @@ CREATE TABLE IF NOT EXISTS ingest_watermarks (
     refreshed_at_utc TEXT NOT NULL,
     staleness_state TEXT NOT NULL DEFAULT 'unknown',
     metadata_json TEXT NOT NULL DEFAULT '{}',
-    CHECK (source_kind IN ('codex', 'claude', 'gemini', 'amp', 'opencode')),
+    host TEXT,
+    CHECK (source_kind GLOB '[a-z]*' AND source_kind NOT GLOB '*[^a-z0-9_-]*'),
     CHECK (staleness_state IN ('fresh', 'stale', 'unknown')),
     CHECK (last_event_timestamp_unix_ms IS NULL OR last_event_timestamp_unix_ms >= 0),
     FOREIGN KEY(last_ingest_run_id) REFERENCES ingest_runs(ingest_run_id)
@@ -397,13 +425,34 @@ CREATE INDEX IF NOT EXISTS idx_ingest_watermarks_refresh
 ON ingest_watermarks (refreshed_at_utc, staleness_state);
 "#;
 
+const CREATE_INDEX_INGEST_WATERMARKS_HOST_SQL: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_ingest_watermarks_host
+ON ingest_watermarks (host, source_kind, source_path);
+"#;
+
 const CREATE_META_TABLE_SQL: &str = r#"
 CREATE TABLE IF NOT EXISTS agentlog_schema_meta (
     schema_version TEXT NOT NULL,
-    applied_at_utc TEXT NOT NULL
+    applied_at_utc TEXT NOT NULL,
+    migration_version INTEGER,
+    migration_name TEXT
 );
 "#;
 
+const CREATE_EVENTS_FTS_TABLE_SQL: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS agentlog_events_fts USING fts5 (
+    content_text,
+    tool_arguments_json,
+    tool_result_text,
+    event_id UNINDEXED,
+    session_id UNINDEXED,
+    adapter_name UNINDEXED
+);
+"#;
+
+const FTS_COLUMNS: &str =
+    "content_text, tool_arguments_json, tool_result_text, event_id, session_id, adapter_name";
+
 #[must_use]
 pub fn schema_statements() -> &'static [&'static str] {
     &[
@@ -414,15 +463,18 @@ pub fn schema_statements() -> &'static [&'static str] {
         CREATE_INDEX_SOURCE_SQL,
         CREATE_INDEX_HASHES_SQL,
         CREATE_INDEX_SESSION_TIME_SQL,
+        CREATE_INDEX_HOST_TIME_SQL,
         CREATE_VIEW_TOOL_CALLS_SQL,
         CREATE_VIEW_SESSIONS_SQL,
         CREATE_VIEW_ADAPTERS_SQL,
         CREATE_VIEW_QUALITY_SQL,
+        CREATE_VIEW_HOSTS_SQL,
         CREATE_INGEST_RUNS_TABLE_SQL,
         CREATE_INDEX_INGEST_RUNS_STATUS_SQL,
         CREATE_INGEST_WATERMARKS_TABLE_SQL,
         CREATE_INDEX_INGEST_WATERMARKS_SOURCE_SQL,
         CREATE_INDEX_INGEST_WATERMARKS_REFRESH_SQL,
+        CREATE_INDEX_INGEST_WATERMARKS_HOST_SQL,
         CREATE_META_TABLE_SQL,
     ]
 }
@@ -466,6 +518,9 @@ pub struct SqliteParityReport {
     pub jsonl_records: usize,
     pub sqlite_records: usize,
     pub compared_records: usize,
+    /// Rows left out because they belong to another host, typically brought
+    /// in by `ingest import`.
+    pub other_host_records: usize,
     pub mismatches: Vec<SqliteParityMismatch>,
 }
 
@@ -493,38 +548,31 @@ pub fn open_sqlite_connection(path: &Path) -> Result<Connection> {
         .with_context(|| format!("failed to open sqlite database: {}", path.display()))
 }
 
-pub fn ensure_sqlite_schema(connection: &Connection) -> Result<()> {
-    connection
-        .execute_batch(&create_schema_sql())
-        .context("failed to create sqlite schema")?;
-
-    if schema_meta_has_version(connection, SQLITE_SCHEMA_VERSION)? {
-        return Ok(());
-    }
+#[must_use]
+pub fn fts_match_expression(terms: &str) -> String {
+    terms
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    let applied_at_utc = OffsetDateTime::now_utc()
-        .format(&Rfc3339)
-        .context("failed to format sqlite schema applied timestamp")?;
-    connection
-        .execute(
-            &format!(
-                "INSERT INTO {SCHEMA_META_TABLE} (schema_version, applied_at_utc) VALUES (?1, ?2)"
-            ),
-            params![SQLITE_SCHEMA_VERSION, applied_at_utc],
+pub fn open_sqlite_connection_read_only(path: &Path) -> Result<Connection> {
+    Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| {
+        format!(
+            "failed to open sqlite database read-only: {}",
+            path.display()
         )
-        .context("failed to write sqlite schema meta row")?;
-
-    Ok(())
+    })
 }
 
-fn schema_meta_has_version(connection: &Connection, schema_version: &str) -> Result<bool> {
-    let query = format!(
-        "SELECT EXISTS(SELECT 1 FROM {SCHEMA_META_TABLE} WHERE schema_version = ?1 LIMIT 1)"
-    );
-    let exists = connection
-        .query_row(&query, [schema_version], |row| row.get::<usize, i64>(0))
-        .context("failed to query sqlite schema version metadata")?;
-    Ok(exists != 0)
+/// Applies pending migrations and recreates views whose definitions changed.
+pub fn ensure_sqlite_schema(connection: &Connection) -> Result<()> {
+    migrations::apply_migrations(connection).map(|_| ())
 }
 
 pub fn write_events_to_sqlite(
@@ -544,6 +592,14 @@ pub fn write_events_batched(
 ) -> Result<SqliteWriteStats> {
     let batch_size = config.batch_size.max(1);
     let insert_sql = build_insert_sql();
+    let fts_delete_sql = format!(
+        "DELETE FROM {EVENTS_FTS_TABLE}
+         WHERE rowid = (SELECT rowid FROM {EVENTS_TABLE} WHERE event_id = ?1)"
+    );
+    let fts_insert_sql = format!(
+        "INSERT INTO {EVENTS_FTS_TABLE} (rowid, {FTS_COLUMNS})
+         SELECT rowid, {FTS_COLUMNS} FROM {EVENTS_TABLE} WHERE event_id = ?1"
+    );
     let mut records_written = 0usize;
     let mut batches_committed = 0usize;
 
@@ -555,12 +611,24 @@ pub fn write_events_batched(
             let mut statement = tx
                 .prepare_cached(&insert_sql)
                 .context("failed to prepare sqlite insert statement")?;
+            let mut fts_delete = tx
+                .prepare_cached(&fts_delete_sql)
+                .context("failed to prepare full-text search delete statement")?;
+            let mut fts_insert = tx
+                .prepare_cached(&fts_insert_sql)
+                .context("failed to prepare full-text search insert statement")?;
 
             for event in batch {
                 let values = event_insert_values(event)?;
+                fts_delete
+                    .execute([&event.event_id])
+                    .with_context(|| format!("failed to unindex event_id={}", event.event_id))?;
                 statement
                     .execute(params_from_iter(values))
                     .with_context(|| format!("failed to insert event_id={}", event.event_id))?;
+                fts_insert
+                    .execute([&event.event_id])
+                    .with_context(|| format!("failed to index event_id={}", event.event_id))?;
                 records_written += 1;
             }
         }
@@ -576,16 +644,20 @@ pub fn write_events_batched(
     })
 }
 
+/// Compares `events.jsonl` with the mart. With `local_host`, only events of
+/// that host (or of none) are compared on either side, since rows imported
+/// from other machines never appear in the local `events.jsonl`.
 pub fn verify_jsonl_sqlite_parity(
     jsonl_path: &Path,
     sqlite_path: &Path,
+    local_host: Option<&str>,
 ) -> Result<SqliteParityReport> {
     let jsonl_input = std::fs::read_to_string(jsonl_path)
         .with_context(|| format!("failed to read JSONL file: {}", jsonl_path.display()))?;
-    let (jsonl_rows, mut mismatches) = parse_jsonl_expected_rows(&jsonl_input)?;
+    let (jsonl_rows, mut mismatches) = parse_jsonl_expected_rows(&jsonl_input, local_host)?;
 
     let connection = open_sqlite_connection(sqlite_path)?;
-    let sqlite_rows = read_sqlite_rows(&connection)?;
+    let (sqlite_rows, other_host_records) = read_sqlite_rows(&connection, local_host)?;
 
     let mut compared_records = 0usize;
 
@@ -653,10 +725,71 @@ pub fn verify_jsonl_sqlite_parity(
         jsonl_records: jsonl_rows.len(),
         sqlite_records: sqlite_rows.len(),
         compared_records,
+        other_host_records,
         mismatches,
     })
 }
 
+/// Reads every event back out of a mart in timeline order, turning the JSON
+/// surrogate columns back into their `AgentLogEvent` fields.
+pub fn read_events(connection: &Connection) -> Result<Vec<AgentLogEvent>> {
+    let query = format!(
+        "SELECT {} FROM {EVENTS_TABLE} ORDER BY timestamp_unix_ms, sequence_global, event_id",
+        EVENT_INSERT_COLUMNS.join(", ")
+    );
+    let mut statement = connection
+        .prepare(&query)
+        .context("failed to prepare sqlite event read query")?;
+    let rows = statement
+        .query_map([], |row| {
+            let mut values = Vec::with_capacity(EVENT_INSERT_COLUMNS.len());
+            for index in 0..EVENT_INSERT_COLUMNS.len() {
+                values.push(row.get::<usize, SqlValue>(index)?);
+            }
+            Ok(values)
+        })
+        .context("failed to execute sqlite event read query")?;
+
+    let mut events = Vec::new();
+    for row in rows {
+        let values = row.context("failed to decode sqlite event row")?;
+        events.push(event_from_row_values(values)?);
+    }
+    Ok(events)
+}
+
+fn event_from_row_values(values: Vec<SqlValue>) -> Result<AgentLogEvent> {
+    let mut object = serde_json::Map::new();
+    for (column, value) in EVENT_INSERT_COLUMNS.iter().zip(values) {
+        let field = column
+            .strip_suffix("_json")
+            .filter(|_| *column != "tool_arguments_json")
+            .unwrap_or(column);
+        let value = match value {
+            SqlValue::Null => continue,
+            SqlValue::Integer(value) if *column == "pii_redacted" => {
+                serde_json::Value::Bool(value != 0)
+            }
+            SqlValue::Integer(value) => serde_json::Value::from(value),
+            SqlValue::Real(value) => serde_json::Value::from(value),
+            SqlValue::Text(text) if field != *column => serde_json::from_str(&text)
+                .with_context(|| format!("invalid json in sqlite column `{column}`"))?,
+            SqlValue::Text(text) => serde_json::Value::String(text),
+            SqlValue::Blob(_) => {
+                return Err(anyhow!("unexpected blob in sqlite column `{column}`"));
+            }
+        };
+        object.insert(field.to_string(), value);
+    }
+    let event_id = object
+        .get("event_id")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    serde_json::from_value(serde_json::Value::Object(object))
+        .with_context(|| format!("sqlite row does not decode as an event: event_id={event_id}"))
+}
+
 fn build_insert_sql() -> String {
     let placeholders = (1..=EVENT_INSERT_COLUMNS.len())
         .map(|index| format!("?{index}"))
@@ -682,11 +815,11 @@ fn event_insert_values(event: &AgentLogEvent) -> Result<Vec<SqlValue>> {
         text_value(&event.run_id),
         int_value(to_i64(event.sequence_global, "sequence_global")?),
         opt_int_value(event.sequence_source, "sequence_source")?,
-        text_value(agent_source_key(event.source_kind)),
+        text_value(event.source_kind.as_str()),
         text_value(&event.source_path),
         text_value(&event.source_record_locator),
         opt_text_value(event.source_record_hash.as_deref()),
-        text_value(agent_source_key(event.adapter_name)),
+        text_value(event.adapter_name.as_str()),
         opt_text_value(event.adapter_version.as_deref()),
         text_value(record_format_key(event.record_format)),
         text_value(event_type_key(event.event_type)),
@@ -763,16 +896,6 @@ fn opt_bool_int_value(value: Option<bool>) -> SqlValue {
     }
 }
 
-fn agent_source_key(source: AgentSource) -> &'static str {
-    match source {
-        AgentSource::Codex => "codex",
-        AgentSource::Claude => "claude",
-        AgentSource::Gemini => "gemini",
-        AgentSource::Amp => "amp",
-        AgentSource::OpenCode => "opencode",
-    }
-}
-
 fn record_format_key(value: RecordFormat) -> &'static str {
     match value {
         RecordFormat::Message => "message",
@@ -816,7 +939,10 @@ fn timestamp_quality_key(value: TimestampQuality) -> &'static str {
     }
 }
 
-fn parse_jsonl_expected_rows(input: &str) -> Result<JsonlParityParseResult> {
+fn parse_jsonl_expected_rows(
+    input: &str,
+    local_host: Option<&str>,
+) -> Result<JsonlParityParseResult> {
     let mut rows = BTreeMap::new();
     let mut mismatches = Vec::new();
 
@@ -840,6 +966,11 @@ fn parse_jsonl_expected_rows(input: &str) -> Result<JsonlParityParseResult> {
                 continue;
             }
         };
+        if let (Some(local_host), Some(host)) = (local_host, event_host(&event))
+            && host != local_host
+        {
+            continue;
+        }
 
         let event_id = event.event_id.clone();
         let values = event_insert_values(&event)?;
@@ -857,13 +988,32 @@ fn parse_jsonl_expected_rows(input: &str) -> Result<JsonlParityParseResult> {
     Ok((rows, mismatches))
 }
 
-fn read_sqlite_rows(connection: &Connection) -> Result<SqliteMirrorRows> {
+/// Reads mart rows keyed by event id, limited to `local_host` (and rows
+/// without a host) when given; also returns how many rows were left out.
+fn read_sqlite_rows(
+    connection: &Connection,
+    local_host: Option<&str>,
+) -> Result<(SqliteMirrorRows, usize)> {
     let event_id_index = EVENT_INSERT_COLUMNS
         .iter()
         .position(|column| *column == "event_id")
         .ok_or_else(|| anyhow!("event_id column missing from insert column list"))?;
+    let other_host_records = match local_host {
+        Some(local_host) => connection
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {EVENTS_TABLE} WHERE host IS NOT NULL AND host != ?1"
+                ),
+                [local_host],
+                |row| row.get::<_, i64>(0),
+            )
+            .context("failed to count other-host rows for parity")?,
+        None => 0,
+    };
     let query = format!(
-        "SELECT {} FROM {EVENTS_TABLE} ORDER BY run_id, sequence_global, event_id",
+        "SELECT {} FROM {EVENTS_TABLE}
+         WHERE ?1 IS NULL OR host IS NULL OR host = ?1
+         ORDER BY run_id, sequence_global, event_id",
         EVENT_INSERT_COLUMNS.join(", ")
     );
 
@@ -871,7 +1021,7 @@ fn read_sqlite_rows(connection: &Connection) -> Result<SqliteMirrorRows> {
         .prepare(&query)
         .context("failed to prepare sqlite parity query")?;
     let rows = statement
-        .query_map([], |row| {
+        .query_map([local_host], |row| {
             let event_id = row.get::<usize, String>(event_id_index)?;
             let mut values = Vec::with_capacity(EVENT_INSERT_COLUMNS.len());
             for index in 0..EVENT_INSERT_COLUMNS.len() {
@@ -887,7 +1037,10 @@ fn read_sqlite_rows(connection: &Connection) -> Result<SqliteMirrorRows> {
         mapped.insert(event_id, values);
     }
 
-    Ok(mapped)
+    Ok((
+        mapped,
+        usize::try_from(other_host_records).unwrap_or_default(),
+    ))
 }
 
 fn sql_values_equal(left: &SqlValue, right: &SqlValue) -> bool {
@@ -914,8 +1067,8 @@ fn format_sql_value(value: &SqlValue) -> String {
 #[cfg(test)]
 mod tests {
     use super::{
-        ADAPTERS_VIEW, EVENTS_TABLE, INGEST_RUNS_TABLE, INGEST_WATERMARKS_TABLE, QUALITY_VIEW,
-        SCHEMA_META_TABLE, SESSIONS_VIEW, SQLITE_SCHEMA_VERSION, TOOL_CALLS_VIEW,
+        ADAPTERS_VIEW, EVENTS_FTS_TABLE, EVENTS_TABLE, INGEST_RUNS_TABLE, INGEST_WATERMARKS_TABLE,
+        QUALITY_VIEW, SCHEMA_META_TABLE, SESSIONS_VIEW, SQLITE_SCHEMA_VERSION, TOOL_CALLS_VIEW,
         ensure_sqlite_schema,
     };
     use rusqlite::{Connection, params};
@@ -929,6 +1082,7 @@ mod tests {
         assert!(table_exists(&connection, INGEST_RUNS_TABLE));
         assert!(table_exists(&connection, INGEST_WATERMARKS_TABLE));
         assert!(table_exists(&connection, SCHEMA_META_TABLE));
+        assert!(table_exists(&connection, EVENTS_FTS_TABLE));
         assert!(view_exists(&connection, TOOL_CALLS_VIEW));
         assert!(view_exists(&connection, SESSIONS_VIEW));
         assert!(view_exists(&connection, ADAPTERS_VIEW));
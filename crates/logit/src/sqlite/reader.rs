@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+
+use super::{EVENTS_FTS_TABLE, EVENTS_TABLE, fts_match_expression};
+use crate::transcript::{TranscriptEvent, load_session_events};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    pub run_id: String,
+    pub session_id: String,
+    pub adapter_name: Option<String>,
+    pub last_event_timestamp_unix_ms: Option<u64>,
+    pub event_count: u64,
+    pub tool_call_count: u64,
+    pub error_count: u64,
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolCallRow {
+    pub tool_name: Option<String>,
+    pub tool_call_id: Option<String>,
+    pub pairing_status: String,
+    pub duration_ms: Option<u64>,
+    pub timestamp_unix_ms: Option<u64>,
+}
+
+impl ToolCallRow {
+    #[must_use]
+    pub fn is_unpaired(&self) -> bool {
+        matches!(
+            self.pairing_status.as_str(),
+            "missing_result" | "orphan_result"
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SearchHit {
+    pub session_id: Option<String>,
+    pub event_id: String,
+    pub snippet: String,
+}
+
+const SESSIONS_SQL: &str = "
+SELECT
+    s.run_id,
+    s.session_id,
+    (SELECT MIN(e.adapter_name) FROM agentlog_events e
+      WHERE e.run_id = s.run_id AND e.session_id = s.session_id) AS adapter_name,
+    s.last_event_timestamp_unix_ms,
+    s.event_count,
+    s.tool_call_count,
+    s.error_count,
+    (SELECT SUM(e.cost_usd) FROM agentlog_events e
+      WHERE e.run_id = s.run_id AND e.session_id = s.session_id) AS cost_usd
+FROM v_sessions s
+ORDER BY s.last_event_timestamp_unix_ms DESC, s.session_id ASC";
+
+const TOOL_CALLS_SQL: &str = "
+SELECT
+    tool_name,
+    tool_call_id,
+    pairing_status,
+    duration_ms,
+    COALESCE(call_timestamp_unix_ms, result_timestamp_unix_ms) AS timestamp_unix_ms
+FROM v_tool_calls
+WHERE session_id = ?1
+ORDER BY timestamp_unix_ms ASC, tool_call_id ASC";
+
+#[derive(Debug)]
+pub struct MartReader {
+    path: PathBuf,
+    connection: Connection,
+}
+
+impl MartReader {
+    pub fn open(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            bail!("sqlite mart not found: {}", path.display());
+        }
+        let connection = super::open_sqlite_connection_read_only(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection,
+        })
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub fn sessions(&self) -> Result<Vec<SessionSummary>> {
+        let mut statement = self
+            .connection
+            .prepare(SESSIONS_SQL)
+            .context("failed to prepare sessions query")?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok(SessionSummary {
+                    run_id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    adapter_name: row.get(2)?,
+                    last_event_timestamp_unix_ms: unsigned(row.get(3)?),
+                    event_count: unsigned(row.get(4)?).unwrap_or(0),
+                    tool_call_count: unsigned(row.get(5)?).unwrap_or(0),
+                    error_count: unsigned(row.get(6)?).unwrap_or(0),
+                    cost_usd: row.get(7)?,
+                })
+            })
+            .context("failed to execute sessions query")?;
+        rows.map(|row| row.context("failed to decode session row"))
+            .collect()
+    }
+
+    pub fn transcript_events(&self, session_id: &str) -> Result<Vec<TranscriptEvent>> {
+        load_session_events(&self.connection, session_id)
+    }
+
+    pub fn tool_calls(&self, session_id: &str) -> Result<Vec<ToolCallRow>> {
+        let mut statement = self
+            .connection
+            .prepare(TOOL_CALLS_SQL)
+            .context("failed to prepare tool calls query")?;
+        let rows = statement
+            .query_map([session_id], |row| {
+                Ok(ToolCallRow {
+                    tool_name: row.get(0)?,
+                    tool_call_id: row.get(1)?,
+                    pairing_status: row.get(2)?,
+                    duration_ms: unsigned(row.get(3)?),
+                    timestamp_unix_ms: unsigned(row.get(4)?),
+                })
+            })
+            .context("failed to execute tool calls query")?;
+        rows.map(|row| row.context("failed to decode tool call row"))
+            .collect()
+    }
+
+    pub fn search(&self, terms: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let (sql, pattern) = if self.has_fts_index()? {
+            (
+                format!(
+                    "SELECT session_id, event_id,
+                            snippet({EVENTS_FTS_TABLE}, -1, '[', ']', '…', 12)
+                     FROM {EVENTS_FTS_TABLE}
+                     WHERE {EVENTS_FTS_TABLE} MATCH ?1
+                     ORDER BY bm25({EVENTS_FTS_TABLE}) ASC, event_id ASC
+                     LIMIT ?2"
+                ),
+                fts_match_expression(terms),
+            )
+        } else {
+            // Marts ingested before the FTS index existed cannot be backfilled
+            // through a read-only connection.
+            (
+                format!(
+                    "SELECT session_id, event_id,
+                            substr(COALESCE(content_text, tool_result_text, ''), 1, 80)
+                     FROM {EVENTS_TABLE}
+                     WHERE COALESCE(content_text, '') || ' ' || COALESCE(tool_result_text, '')
+                           LIKE '%' || ?1 || '%'
+                     ORDER BY timestamp_unix_ms DESC, event_id ASC
+                     LIMIT ?2"
+                ),
+                terms.trim().to_string(),
+            )
+        };
+        let mut statement = self
+            .connection
+            .prepare(&sql)
+            .context("failed to prepare search query")?;
+        let rows = statement
+            .query_map(params![pattern, limit], |row| {
+                Ok(SearchHit {
+                    session_id: row.get(0)?,
+                    event_id: row.get(1)?,
+                    snippet: row.get::<_, String>(2)?.replace('\n', " "),
+                })
+            })
+            .context("failed to execute search query")?;
+        rows.map(|row| row.context("failed to decode search row"))
+            .collect()
+    }
+
+    fn has_fts_index(&self) -> Result<bool> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                [EVENTS_FTS_TABLE],
+                |_| Ok(()),
+            )
+            .optional()
+            .context("failed to inspect sqlite schema")?
+            .is_some())
+    }
+}
+
+fn unsigned(value: Option<i64>) -> Option<u64> {
+    value.and_then(|value| u64::try_from(value).ok())
+}
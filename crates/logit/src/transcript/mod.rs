@@ -0,0 +1,669 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::models::AgentLogEvent;
+
+pub const COLLAPSE_MAX_LINES: usize = 12;
+pub const COLLAPSE_MAX_CHARS: usize = 1_200;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscriptFormat {
+    #[default]
+    Text,
+    Markdown,
+    Html,
+}
+
+impl TranscriptFormat {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "text" | "terminal" => Ok(Self::Text),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            other => Err(format!(
+                "unknown transcript format `{other}` (expected text, markdown or html)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TurnRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl TurnRange {
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let parse_bound = |value: &str| -> Result<Option<usize>, String> {
+            let value = value.trim();
+            if value.is_empty() {
+                return Ok(None);
+            }
+            match value.parse::<usize>() {
+                Ok(0) | Err(_) => Err(format!(
+                    "invalid turn `{value}` (turns are numbered from 1)"
+                )),
+                Ok(turn) => Ok(Some(turn)),
+            }
+        };
+        let (start, end) = match raw.split_once("..") {
+            Some((start, end)) => (parse_bound(start)?, parse_bound(end)?),
+            None => {
+                let turn = parse_bound(raw)?;
+                (turn, turn)
+            }
+        };
+        let start = start.unwrap_or(1);
+        if end.is_some_and(|end| end < start) {
+            return Err(format!("invalid turn range `{raw}` (end before start)"));
+        }
+        Ok(Self { start, end })
+    }
+
+    #[must_use]
+    pub fn contains(self, ordinal: usize) -> bool {
+        ordinal >= self.start && self.end.is_none_or(|end| ordinal <= end)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranscriptEvent {
+    pub event_id: String,
+    pub sequence_global: u64,
+    pub turn_id: Option<String>,
+    pub record_format: String,
+    pub event_type: String,
+    pub role: String,
+    pub timestamp_utc: String,
+    pub timestamp_unix_ms: u64,
+    pub timestamp_quality: String,
+    pub model: Option<String>,
+    pub tool_name: Option<String>,
+    pub content_text: Option<String>,
+    pub content_excerpt: Option<String>,
+    pub tool_arguments_json: Option<String>,
+    pub tool_result_text: Option<String>,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub total_tokens: Option<u64>,
+}
+
+impl TranscriptEvent {
+    #[must_use]
+    pub fn from_agentlog(event: &AgentLogEvent) -> Self {
+        Self {
+            event_id: event.event_id.clone(),
+            sequence_global: event.sequence_global,
+            turn_id: event.turn_id.clone(),
+            record_format: enum_key(&event.record_format),
+            event_type: enum_key(&event.event_type),
+            role: enum_key(&event.role),
+            timestamp_utc: event.timestamp_utc.clone(),
+            timestamp_unix_ms: event.timestamp_unix_ms,
+            timestamp_quality: enum_key(&event.timestamp_quality),
+            model: event.model.clone(),
+            tool_name: event.tool_name.clone(),
+            content_text: event.content_text.clone(),
+            content_excerpt: event.content_excerpt.clone(),
+            tool_arguments_json: event.tool_arguments_json.clone(),
+            tool_result_text: event.tool_result_text.clone(),
+            input_tokens: event.input_tokens,
+            output_tokens: event.output_tokens,
+            total_tokens: event.total_tokens,
+        }
+    }
+
+    fn is_tool_output(&self) -> bool {
+        self.record_format == "tool_result" || self.event_type == "tool_output"
+    }
+
+    fn is_tool_call(&self) -> bool {
+        self.record_format == "tool_call" || self.event_type == "tool_invocation"
+    }
+
+    fn label(&self) -> String {
+        let tool = self.tool_name.as_deref().unwrap_or("tool");
+        if self.is_tool_call() {
+            format!("tool call {tool}")
+        } else if self.is_tool_output() {
+            format!("tool result {tool}")
+        } else {
+            format!("{} {}", self.role, self.event_type)
+        }
+    }
+
+    fn body(&self) -> Option<&str> {
+        let preferred = if self.is_tool_call() {
+            self.tool_arguments_json.as_deref()
+        } else if self.is_tool_output() {
+            self.tool_result_text.as_deref()
+        } else {
+            None
+        };
+        preferred
+            .or(self.content_text.as_deref())
+            .or(self.content_excerpt.as_deref())
+            .filter(|body| !body.trim().is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TranscriptTurn {
+    pub ordinal: usize,
+    pub turn_id: Option<String>,
+    pub events: Vec<TranscriptEvent>,
+}
+
+impl TranscriptTurn {
+    #[must_use]
+    pub fn duration_ms(&self) -> u64 {
+        let first = self
+            .events
+            .iter()
+            .map(|event| event.timestamp_unix_ms)
+            .min();
+        let last = self
+            .events
+            .iter()
+            .map(|event| event.timestamp_unix_ms)
+            .max();
+        match (first, last) {
+            (Some(first), Some(last)) => last - first,
+            _ => 0,
+        }
+    }
+
+    #[must_use]
+    pub fn token_totals(&self) -> (Option<u64>, Option<u64>, Option<u64>) {
+        let sum = |select: fn(&TranscriptEvent) -> Option<u64>| {
+            self.events
+                .iter()
+                .filter_map(select)
+                .fold(None, |total: Option<u64>, value| {
+                    Some(total.unwrap_or(0) + value)
+                })
+        };
+        (
+            sum(|event| event.input_tokens),
+            sum(|event| event.output_tokens),
+            sum(|event| event.total_tokens),
+        )
+    }
+
+    fn annotation(&self) -> String {
+        let first = self
+            .events
+            .first()
+            .map(timestamp_with_marker)
+            .unwrap_or_default();
+        let mut parts = vec![
+            first,
+            format!("{} events", self.events.len()),
+            format_duration(self.duration_ms()),
+        ];
+        let (input, output, total) = self.token_totals();
+        if input.is_some() || output.is_some() || total.is_some() {
+            let show =
+                |value: Option<u64>| value.map_or("-".to_string(), |value| value.to_string());
+            parts.push(format!(
+                "tokens in={} out={} total={}",
+                show(input),
+                show(output),
+                show(total)
+            ));
+        }
+        parts.join(" · ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SessionTranscript {
+    pub session_id: String,
+    pub source: String,
+    pub total_turns: usize,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+impl SessionTranscript {
+    pub fn build(
+        session_id: &str,
+        source: &str,
+        mut events: Vec<TranscriptEvent>,
+        range: Option<TurnRange>,
+    ) -> Result<Self> {
+        if events.is_empty() {
+            bail!("session `{session_id}` has no events in {source}");
+        }
+        events.sort_by(|left, right| {
+            left.sequence_global
+                .cmp(&right.sequence_global)
+                .then_with(|| left.event_id.cmp(&right.event_id))
+        });
+        let turns = group_turns(events);
+        let total_turns = turns.len();
+        let turns = turns
+            .into_iter()
+            .filter(|turn| range.is_none_or(|range| range.contains(turn.ordinal)))
+            .collect::<Vec<_>>();
+        if turns.is_empty() {
+            bail!("session `{session_id}` has {total_turns} turns; requested range selects none");
+        }
+        Ok(Self {
+            session_id: session_id.to_string(),
+            source: source.to_string(),
+            total_turns,
+            turns,
+        })
+    }
+
+    fn turn_span(&self) -> String {
+        let first = self.turns.first().map_or(0, |turn| turn.ordinal);
+        let last = self.turns.last().map_or(0, |turn| turn.ordinal);
+        format!("turns {first}-{last} of {}", self.total_turns)
+    }
+}
+
+#[must_use]
+pub fn group_turns(events: Vec<TranscriptEvent>) -> Vec<TranscriptTurn> {
+    let mut turns: Vec<TranscriptTurn> = Vec::new();
+    let mut by_turn_id = BTreeMap::<String, usize>::new();
+    for event in events {
+        let turn_id = event.turn_id.clone().filter(|turn_id| !turn_id.is_empty());
+        let index = match &turn_id {
+            Some(turn_id) => by_turn_id.get(turn_id).copied(),
+            None if event.event_type == "prompt" => None,
+            None => turns.len().checked_sub(1),
+        };
+        match index {
+            Some(index) => turns[index].events.push(event),
+            None => {
+                if let Some(turn_id) = &turn_id {
+                    by_turn_id.insert(turn_id.clone(), turns.len());
+                }
+                turns.push(TranscriptTurn {
+                    ordinal: turns.len() + 1,
+                    turn_id,
+                    events: vec![event],
+                });
+            }
+        }
+    }
+    turns
+}
+
+pub fn load_session_from_events_jsonl(
+    path: &Path,
+    session_id: &str,
+) -> Result<Vec<TranscriptEvent>> {
+    let input = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read normalized events file: {}", path.display()))?;
+    let mut events = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str::<AgentLogEvent>(trimmed).with_context(|| {
+            format!(
+                "invalid events jsonl row at {}:{}",
+                path.display(),
+                index + 1
+            )
+        })?;
+        if event.session_id.as_deref() == Some(session_id) {
+            events.push(TranscriptEvent::from_agentlog(&event));
+        }
+    }
+    Ok(events)
+}
+
+pub fn load_session_from_mart(path: &Path, session_id: &str) -> Result<Vec<TranscriptEvent>> {
+    if !path.is_file() {
+        bail!("sqlite mart not found: {}", path.display());
+    }
+    let connection = crate::sqlite::open_sqlite_connection_read_only(path)?;
+    load_session_events(&connection, session_id)
+}
+
+pub fn load_session_events(
+    connection: &rusqlite::Connection,
+    session_id: &str,
+) -> Result<Vec<TranscriptEvent>> {
+    let mut statement = connection
+        .prepare(&format!(
+            "SELECT event_id, sequence_global, turn_id, record_format, event_type, role,
+                    timestamp_utc, timestamp_unix_ms, timestamp_quality, model, tool_name,
+                    content_text, content_excerpt, tool_arguments_json, tool_result_text,
+                    input_tokens, output_tokens, total_tokens
+             FROM {}
+             WHERE session_id = ?1
+             ORDER BY sequence_global, event_id",
+            crate::sqlite::EVENTS_TABLE
+        ))
+        .context("failed to prepare session transcript query")?;
+    let rows = statement
+        .query_map([session_id], |row| {
+            let unsigned = |index: usize| -> rusqlite::Result<Option<u64>> {
+                Ok(row
+                    .get::<usize, Option<i64>>(index)?
+                    .and_then(|value| u64::try_from(value).ok()))
+            };
+            Ok(TranscriptEvent {
+                event_id: row.get(0)?,
+                sequence_global: unsigned(1)?.unwrap_or(0),
+                turn_id: row.get(2)?,
+                record_format: row.get(3)?,
+                event_type: row.get(4)?,
+                role: row.get(5)?,
+                timestamp_utc: row.get(6)?,
+                timestamp_unix_ms: unsigned(7)?.unwrap_or(0),
+                timestamp_quality: row.get(8)?,
+                model: row.get(9)?,
+                tool_name: row.get(10)?,
+                content_text: row.get(11)?,
+                content_excerpt: row.get(12)?,
+                tool_arguments_json: row.get(13)?,
+                tool_result_text: row.get(14)?,
+                input_tokens: unsigned(15)?,
+                output_tokens: unsigned(16)?,
+                total_tokens: unsigned(17)?,
+            })
+        })
+        .context("failed to execute session transcript query")?;
+    rows.map(|row| row.context("failed to decode session transcript row"))
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    pub collapse_tool_output: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            collapse_tool_output: true,
+        }
+    }
+}
+
+#[must_use]
+pub fn render(
+    transcript: &SessionTranscript,
+    format: TranscriptFormat,
+    options: RenderOptions,
+) -> String {
+    match format {
+        TranscriptFormat::Text => render_text(transcript, options),
+        TranscriptFormat::Markdown => render_markdown(transcript, options),
+        TranscriptFormat::Html => render_html(transcript, options),
+    }
+}
+
+struct RenderedBody<'a> {
+    shown: String,
+    full: &'a str,
+    collapsed_note: Option<String>,
+}
+
+fn rendered_body(event: &TranscriptEvent, options: RenderOptions) -> Option<RenderedBody<'_>> {
+    let full = event.body()?;
+    let line_count = full.lines().count();
+    let char_count = full.chars().count();
+    let long = line_count > COLLAPSE_MAX_LINES || char_count > COLLAPSE_MAX_CHARS;
+    if !(options.collapse_tool_output && event.is_tool_output() && long) {
+        return Some(RenderedBody {
+            shown: full.to_string(),
+            full,
+            collapsed_note: None,
+        });
+    }
+
+    let shown = event
+        .content_excerpt
+        .as_deref()
+        .filter(|excerpt| !excerpt.trim().is_empty() && excerpt.len() < full.len())
+        .map_or_else(
+            || {
+                let head = full
+                    .lines()
+                    .take(COLLAPSE_MAX_LINES)
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                head.chars().take(COLLAPSE_MAX_CHARS).collect()
+            },
+            ToOwned::to_owned,
+        );
+    Some(RenderedBody {
+        shown,
+        full,
+        collapsed_note: Some(format!(
+            "tool output collapsed: {line_count} lines, {char_count} chars (use --full)"
+        )),
+    })
+}
+
+#[must_use]
+pub fn render_text(transcript: &SessionTranscript, options: RenderOptions) -> String {
+    let mut lines = vec![
+        format!("session: {}", transcript.session_id),
+        format!("source: {}", transcript.source),
+        format!(
+            "{} · timestamps: ~ derived, ? fallback",
+            transcript.turn_span()
+        ),
+    ];
+    for turn in &transcript.turns {
+        lines.push(String::new());
+        let turn_id = turn
+            .turn_id
+            .as_deref()
+            .map(|turn_id| format!(" [{turn_id}]"))
+            .unwrap_or_default();
+        lines.push(format!(
+            "=== turn {}{turn_id} · {}",
+            turn.ordinal,
+            turn.annotation()
+        ));
+        for event in &turn.events {
+            lines.push(format!(
+                "[{}] {}",
+                timestamp_with_marker(event),
+                event.label()
+            ));
+            if let Some(body) = rendered_body(event, options) {
+                lines.extend(body.shown.lines().map(|line| format!("    {line}")));
+                if let Some(note) = body.collapsed_note {
+                    lines.push(format!("    [{note}]"));
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+#[must_use]
+pub fn render_markdown(transcript: &SessionTranscript, options: RenderOptions) -> String {
+    let mut out = vec![
+        format!("# Session `{}`", transcript.session_id),
+        String::new(),
+        format!(
+            "_Source: `{}` · {} · `~` derived timestamp, `?` fallback timestamp_",
+            transcript.source,
+            transcript.turn_span()
+        ),
+    ];
+    for turn in &transcript.turns {
+        out.push(String::new());
+        let turn_id = turn
+            .turn_id
+            .as_deref()
+            .map(|turn_id| format!(" · `{turn_id}`"))
+            .unwrap_or_default();
+        out.push(format!("## Turn {}{turn_id}", turn.ordinal));
+        out.push(String::new());
+        out.push(format!("_{}_", turn.annotation()));
+        for event in &turn.events {
+            out.push(String::new());
+            out.push(format!(
+                "**{}** · `{}`",
+                event.label(),
+                timestamp_with_marker(event)
+            ));
+            let Some(body) = rendered_body(event, options) else {
+                continue;
+            };
+            out.push(String::new());
+            if event.is_tool_call() || event.is_tool_output() {
+                let fence = markdown_fence(&body.shown);
+                let language = if event.is_tool_call() { "json" } else { "text" };
+                out.push(format!("{fence}{language}"));
+                out.push(body.shown.clone());
+                out.push(fence);
+            } else {
+                out.extend(body.shown.lines().map(|line| format!("> {line}")));
+            }
+            if let Some(note) = body.collapsed_note {
+                out.push(String::new());
+                out.push(format!("_{note}_"));
+            }
+        }
+    }
+    out.push(String::new());
+    out.join("\n")
+}
+
+#[must_use]
+pub fn render_html(transcript: &SessionTranscript, options: RenderOptions) -> String {
+    let mut out = vec![
+        "<!DOCTYPE html>".to_string(),
+        "<html lang=\"en\">".to_string(),
+        "<head>".to_string(),
+        "<meta charset=\"utf-8\">".to_string(),
+        format!(
+            "<title>Session {}</title>",
+            html_escape(&transcript.session_id)
+        ),
+        format!("<style>{HTML_STYLE}</style>"),
+        "</head>".to_string(),
+        "<body>".to_string(),
+        format!(
+            "<h1>Session <code>{}</code></h1>",
+            html_escape(&transcript.session_id)
+        ),
+        format!(
+            "<p class=\"meta\">Source <code>{}</code> · {} · <code>~</code> derived timestamp, <code>?</code> fallback timestamp</p>",
+            html_escape(&transcript.source),
+            transcript.turn_span()
+        ),
+    ];
+    for turn in &transcript.turns {
+        let turn_id = turn
+            .turn_id
+            .as_deref()
+            .map(|turn_id| format!(" <code>{}</code>", html_escape(turn_id)))
+            .unwrap_or_default();
+        out.push("<section class=\"turn\">".to_string());
+        out.push(format!("<h2>Turn {}{turn_id}</h2>", turn.ordinal));
+        out.push(format!(
+            "<p class=\"meta\">{}</p>",
+            html_escape(&turn.annotation())
+        ));
+        for event in &turn.events {
+            out.push(format!(
+                "<div class=\"event role-{}\">",
+                html_escape(&event.role)
+            ));
+            out.push(format!(
+                "<div class=\"label\">{} <time>{}</time></div>",
+                html_escape(&event.label()),
+                html_escape(&timestamp_with_marker(event))
+            ));
+            if let Some(body) = rendered_body(event, options) {
+                out.push(format!("<pre>{}</pre>", html_escape(&body.shown)));
+                if let Some(note) = body.collapsed_note {
+                    out.push(format!(
+                        "<details><summary>{}</summary><pre>{}</pre></details>",
+                        html_escape(&note),
+                        html_escape(body.full)
+                    ));
+                }
+            }
+            out.push("</div>".to_string());
+        }
+        out.push("</section>".to_string());
+    }
+    out.push("</body>".to_string());
+    out.push("</html>".to_string());
+    out.push(String::new());
+    out.join("\n")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;color:#1f2328}\
+.meta{color:#59636e;font-size:.9rem}\
+.turn{border-top:1px solid #d1d9e0;margin-top:1.5rem}\
+.event{margin:.75rem 0;padding:.5rem .75rem;border-left:3px solid #d1d9e0}\
+.role-user{border-color:#0969da}.role-assistant{border-color:#1a7f37}.role-tool{border-color:#9a6700}\
+.label{font-weight:600}time{font-weight:400;color:#59636e;margin-left:.5rem}\
+pre{white-space:pre-wrap;word-break:break-word;background:#f6f8fa;padding:.5rem;margin:.25rem 0}";
+
+fn timestamp_with_marker(event: &TranscriptEvent) -> String {
+    let marker = match event.timestamp_quality.as_str() {
+        "derived" => "~",
+        "fallback" => "?",
+        _ => "",
+    };
+    format!("{}{marker}", event.timestamp_utc)
+}
+
+fn format_duration(duration_ms: u64) -> String {
+    match duration_ms {
+        0..=999 => format!("{duration_ms}ms"),
+        1_000..=59_999 => format!("{:.1}s", duration_ms as f64 / 1_000.0),
+        _ => format!(
+            "{}m{:02}s",
+            duration_ms / 60_000,
+            (duration_ms % 60_000) / 1_000
+        ),
+    }
+}
+
+fn markdown_fence(body: &str) -> String {
+    let mut longest = 0usize;
+    let mut current = 0usize;
+    for ch in body.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn html_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn enum_key<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|value| value.as_str().map(ToOwned::to_owned))
+        .unwrap_or_default()
+}
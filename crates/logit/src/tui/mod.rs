@@ -0,0 +1,378 @@
+pub mod view;
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+use crate::transcript::{RenderOptions, SessionTranscript, render_text};
+use crate::validate::ValidationReport;
+
+pub use crate::sqlite::reader::{MartReader, SearchHit, SessionSummary, ToolCallRow};
+
+pub const SEARCH_HIT_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionSort {
+    Recency,
+    Adapter,
+    Cost,
+}
+
+impl SessionSort {
+    #[must_use]
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Recency => Self::Adapter,
+            Self::Adapter => Self::Cost,
+            Self::Cost => Self::Recency,
+        }
+    }
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Recency => "recency",
+            Self::Adapter => "adapter",
+            Self::Cost => "cost",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Sessions,
+    Transcript,
+    ToolCalls,
+    Issues,
+}
+
+impl Focus {
+    const ORDER: [Self; 4] = [
+        Self::Sessions,
+        Self::Transcript,
+        Self::ToolCalls,
+        Self::Issues,
+    ];
+
+    fn shifted(self, forward: bool) -> Self {
+        let index = Self::ORDER
+            .iter()
+            .position(|focus| *focus == self)
+            .unwrap_or(0);
+        let len = Self::ORDER.len();
+        let next = if forward {
+            (index + 1) % len
+        } else {
+            (index + len - 1) % len
+        };
+        Self::ORDER[next]
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchState {
+    pub input: String,
+    pub editing: bool,
+    pub hits: Option<Vec<SearchHit>>,
+    pub selected: usize,
+}
+
+#[derive(Debug)]
+pub struct App {
+    reader: MartReader,
+    pub sessions: Vec<SessionSummary>,
+    pub sort: SessionSort,
+    pub selected_session: usize,
+    pub transcript: Vec<String>,
+    pub transcript_scroll: u16,
+    pub tool_calls: Vec<ToolCallRow>,
+    pub selected_tool_call: usize,
+    pub validation_report_path: PathBuf,
+    pub validation_report: Option<ValidationReport>,
+    pub selected_issue: usize,
+    pub focus: Focus,
+    pub search: SearchState,
+    pub status: String,
+    pub should_quit: bool,
+}
+
+impl App {
+    pub fn new(
+        reader: MartReader,
+        validation_report_path: PathBuf,
+        validation_report: Option<ValidationReport>,
+    ) -> Result<Self> {
+        let sessions = reader.sessions()?;
+        let mut app = Self {
+            reader,
+            sessions,
+            sort: SessionSort::Recency,
+            selected_session: 0,
+            transcript: Vec::new(),
+            transcript_scroll: 0,
+            tool_calls: Vec::new(),
+            selected_tool_call: 0,
+            validation_report_path,
+            validation_report,
+            selected_issue: 0,
+            focus: Focus::Sessions,
+            search: SearchState::default(),
+            status: String::new(),
+            should_quit: false,
+        };
+        app.sort_sessions();
+        app.load_selected_session();
+        Ok(app)
+    }
+
+    #[must_use]
+    pub fn selected_session(&self) -> Option<&SessionSummary> {
+        self.sessions.get(self.selected_session)
+    }
+
+    #[must_use]
+    pub fn mart_label(&self) -> String {
+        self.reader.path().file_name().map_or_else(
+            || self.reader.path().display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    #[must_use]
+    pub fn issue_count(&self) -> usize {
+        self.validation_report
+            .as_ref()
+            .map_or(0, |report| report.issues.len())
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        if self.search.editing {
+            self.handle_search_input(key);
+            return;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.should_quit = true;
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') => self.should_quit = true,
+            KeyCode::Tab => self.focus = self.focus.shifted(true),
+            KeyCode::BackTab => self.focus = self.focus.shifted(false),
+            KeyCode::Char('/') => {
+                self.search.editing = true;
+                self.focus = Focus::Sessions;
+            }
+            KeyCode::Char('s') => {
+                self.sort = self.sort.next();
+                self.sort_sessions();
+                self.load_selected_session();
+            }
+            KeyCode::Esc if self.search.hits.is_some() => {
+                self.search.hits = None;
+                self.status.clear();
+            }
+            KeyCode::Enter if self.focus == Focus::Sessions => self.open_selected_hit(),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            _ => {}
+        }
+    }
+
+    fn handle_search_input(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.search.editing = false,
+            KeyCode::Enter => {
+                self.search.editing = false;
+                self.run_search();
+            }
+            KeyCode::Backspace => {
+                self.search.input.pop();
+            }
+            KeyCode::Char(character) => self.search.input.push(character),
+            _ => {}
+        }
+    }
+
+    fn run_search(&mut self) {
+        if self.search.input.trim().is_empty() {
+            self.search.hits = None;
+            return;
+        }
+        match self.reader.search(&self.search.input, SEARCH_HIT_LIMIT) {
+            Ok(hits) => {
+                self.status = format!("{} hits for `{}`", hits.len(), self.search.input.trim());
+                self.search.hits = Some(hits);
+                self.search.selected = 0;
+            }
+            Err(error) => {
+                self.status = format!("search failed: {error:#}");
+                self.search.hits = None;
+            }
+        }
+    }
+
+    fn open_selected_hit(&mut self) {
+        let Some(hits) = &self.search.hits else {
+            return;
+        };
+        let Some(session_id) = hits
+            .get(self.search.selected)
+            .and_then(|hit| hit.session_id.clone())
+        else {
+            return;
+        };
+        if let Some(index) = self
+            .sessions
+            .iter()
+            .position(|session| session.session_id == session_id)
+        {
+            self.selected_session = index;
+            self.search.hits = None;
+            self.status.clear();
+            self.load_selected_session();
+        }
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        match self.focus {
+            Focus::Sessions => {
+                if let Some(hits) = &self.search.hits {
+                    self.search.selected = step(self.search.selected, delta, hits.len());
+                } else {
+                    let previous = self.selected_session;
+                    self.selected_session = step(previous, delta, self.sessions.len());
+                    if previous != self.selected_session {
+                        self.load_selected_session();
+                    }
+                }
+            }
+            Focus::Transcript => {
+                let max =
+                    u16::try_from(self.transcript.len().saturating_sub(1)).unwrap_or(u16::MAX);
+                self.transcript_scroll = usize::from(self.transcript_scroll)
+                    .saturating_add_signed(delta)
+                    .min(usize::from(max))
+                    .try_into()
+                    .unwrap_or(max);
+            }
+            Focus::ToolCalls => {
+                self.selected_tool_call =
+                    step(self.selected_tool_call, delta, self.tool_calls.len());
+            }
+            Focus::Issues => {
+                self.selected_issue = step(self.selected_issue, delta, self.issue_count());
+            }
+        }
+    }
+
+    fn sort_sessions(&mut self) {
+        let selected_key = self
+            .selected_session()
+            .map(|session| (session.run_id.clone(), session.session_id.clone()));
+        sort_sessions(&mut self.sessions, self.sort);
+        self.selected_session = selected_key
+            .and_then(|(run_id, session_id)| {
+                self.sessions.iter().position(|session| {
+                    session.run_id == run_id && session.session_id == session_id
+                })
+            })
+            .unwrap_or(0);
+    }
+
+    fn load_selected_session(&mut self) {
+        self.transcript.clear();
+        self.transcript_scroll = 0;
+        self.tool_calls.clear();
+        self.selected_tool_call = 0;
+        let Some(session_id) = self
+            .selected_session()
+            .map(|session| session.session_id.clone())
+        else {
+            return;
+        };
+
+        let transcript = self
+            .reader
+            .transcript_events(&session_id)
+            .and_then(|events| {
+                SessionTranscript::build(&session_id, &self.mart_label(), events, None)
+            });
+        self.transcript = match transcript {
+            Ok(transcript) => render_text(&transcript, RenderOptions::default())
+                .lines()
+                .map(ToOwned::to_owned)
+                .collect(),
+            Err(error) => vec![format!("failed to load transcript: {error:#}")],
+        };
+        match self.reader.tool_calls(&session_id) {
+            Ok(tool_calls) => self.tool_calls = tool_calls,
+            Err(error) => self.status = format!("failed to load tool calls: {error:#}"),
+        }
+    }
+}
+
+pub fn load_validation_report(path: &Path) -> Result<Option<ValidationReport>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read validation report: {}", path.display()))?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .with_context(|| format!("failed to parse validation report: {}", path.display()))
+}
+
+pub fn sort_sessions(sessions: &mut [SessionSummary], sort: SessionSort) {
+    let recency = |left: &SessionSummary, right: &SessionSummary| {
+        right
+            .last_event_timestamp_unix_ms
+            .cmp(&left.last_event_timestamp_unix_ms)
+            .then_with(|| left.session_id.cmp(&right.session_id))
+    };
+    match sort {
+        SessionSort::Recency => sessions.sort_by(recency),
+        SessionSort::Adapter => sessions.sort_by(|left, right| {
+            left.adapter_name
+                .cmp(&right.adapter_name)
+                .then_with(|| recency(left, right))
+        }),
+        SessionSort::Cost => sessions.sort_by(|left, right| {
+            let left_cost = left.cost_usd.unwrap_or(f64::NEG_INFINITY);
+            let right_cost = right.cost_usd.unwrap_or(f64::NEG_INFINITY);
+            right_cost
+                .total_cmp(&left_cost)
+                .then_with(|| recency(left, right))
+        }),
+    }
+}
+
+fn step(current: usize, delta: isize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    current.saturating_add_signed(delta).min(len - 1)
+}
+
+pub fn run(app: &mut App) -> Result<()> {
+    let mut terminal = ratatui::try_init().context("failed to initialize terminal")?;
+    let result = run_event_loop(&mut terminal, app);
+    ratatui::try_restore().context("failed to restore terminal")?;
+    result
+}
+
+fn run_event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App) -> Result<()> {
+    while !app.should_quit {
+        terminal
+            .draw(|frame| view::draw(frame, app))
+            .context("failed to draw terminal frame")?;
+        if let Event::Key(key) = event::read().context("failed to read terminal event")?
+            && key.kind == KeyEventKind::Press
+        {
+            app.handle_key(key);
+        }
+    }
+    Ok(())
+}
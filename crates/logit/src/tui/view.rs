@@ -0,0 +1,260 @@
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+
+use super::{App, Focus, SessionSummary, ToolCallRow};
+use crate::utils::time::format_unix_ms;
+use crate::validate::{ValidationIssue, ValidationIssueSeverity};
+
+const HIGHLIGHT_SYMBOL: &str = "> ";
+
+pub fn draw(frame: &mut Frame, app: &App) {
+    let [search_area, body_area, status_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(6),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+    let [sessions_area, detail_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .areas(body_area);
+    let [transcript_area, lower_area] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .areas(detail_area);
+    let [tool_calls_area, issues_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .areas(lower_area);
+
+    draw_search(frame, app, search_area);
+    if app.search.hits.is_some() {
+        draw_search_hits(frame, app, sessions_area);
+    } else {
+        draw_sessions(frame, app, sessions_area);
+    }
+    draw_transcript(frame, app, transcript_area);
+    draw_tool_calls(frame, app, tool_calls_area);
+    draw_issues(frame, app, issues_area);
+    draw_status(frame, app, status_area);
+}
+
+fn pane(title: String, focused: bool) -> Block<'static> {
+    let border_style = if focused {
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    Block::default()
+        .borders(Borders::ALL)
+        .border_style(border_style)
+        .title(title)
+}
+
+fn selection_style() -> Style {
+    Style::default().add_modifier(Modifier::REVERSED)
+}
+
+fn list_state(selected: usize, len: usize) -> ListState {
+    ListState::default().with_selected((len > 0).then_some(selected))
+}
+
+fn draw_search(frame: &mut Frame, app: &App, area: Rect) {
+    let hint = if app.search.editing {
+        "Search (Enter run, Esc cancel)"
+    } else {
+        "Search (/ edit)"
+    };
+    let mut spans = vec![Span::raw("/ "), Span::raw(app.search.input.clone())];
+    if app.search.editing {
+        spans.push(Span::styled(
+            "_",
+            Style::default().add_modifier(Modifier::SLOW_BLINK),
+        ));
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).block(pane(hint.to_string(), app.search.editing)),
+        area,
+    );
+}
+
+fn session_line(session: &SessionSummary) -> String {
+    let when = session.last_event_timestamp_unix_ms.map_or_else(
+        || "-".to_string(),
+        |ms| format_unix_ms(ms)[..16].to_string(),
+    );
+    let cost = session
+        .cost_usd
+        .map_or_else(|| "-".to_string(), |cost| format!("${cost:.2}"));
+    format!(
+        "{} {} {} {} {}ev",
+        session.session_id,
+        session.adapter_name.as_deref().unwrap_or("-"),
+        when,
+        cost,
+        session.event_count
+    )
+}
+
+fn draw_sessions(frame: &mut Frame, app: &App, area: Rect) {
+    let items = app
+        .sessions
+        .iter()
+        .map(|session| ListItem::new(session_line(session)))
+        .collect::<Vec<_>>();
+    let title = format!(
+        "Sessions ({}, by {})",
+        app.sessions.len(),
+        app.sort.as_str()
+    );
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::Sessions))
+        .highlight_style(selection_style())
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut list_state(app.selected_session, app.sessions.len()),
+    );
+}
+
+fn draw_search_hits(frame: &mut Frame, app: &App, area: Rect) {
+    let hits = app.search.hits.as_deref().unwrap_or_default();
+    let items = hits
+        .iter()
+        .map(|hit| {
+            ListItem::new(vec![
+                Line::from(Span::styled(
+                    hit.session_id.as_deref().unwrap_or("-").to_string(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(format!("  {}", hit.snippet)),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let title = format!("Search hits ({}, Enter open, Esc back)", hits.len());
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::Sessions))
+        .highlight_style(selection_style())
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    frame.render_stateful_widget(list, area, &mut list_state(app.search.selected, hits.len()));
+}
+
+fn draw_transcript(frame: &mut Frame, app: &App, area: Rect) {
+    let lines = app
+        .transcript
+        .iter()
+        .map(|line| Line::from(line.as_str()))
+        .collect::<Vec<_>>();
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(pane(
+                "Transcript".to_string(),
+                app.focus == Focus::Transcript,
+            ))
+            .scroll((app.transcript_scroll, 0)),
+        area,
+    );
+}
+
+fn tool_call_style(row: &ToolCallRow) -> Style {
+    match row.pairing_status.as_str() {
+        "missing_result" => Style::default().fg(Color::Red),
+        "orphan_result" => Style::default().fg(Color::Yellow),
+        "invalid_order" => Style::default().fg(Color::Magenta),
+        _ => Style::default(),
+    }
+}
+
+fn draw_tool_calls(frame: &mut Frame, app: &App, area: Rect) {
+    let items = app
+        .tool_calls
+        .iter()
+        .map(|row| {
+            let duration = row
+                .duration_ms
+                .map_or_else(|| "-".to_string(), |ms| format!("{ms}ms"));
+            ListItem::new(format!(
+                "{} {} {}",
+                row.tool_name.as_deref().unwrap_or("-"),
+                row.pairing_status,
+                duration
+            ))
+            .style(tool_call_style(row))
+        })
+        .collect::<Vec<_>>();
+    let unpaired = app
+        .tool_calls
+        .iter()
+        .filter(|row| row.is_unpaired())
+        .count();
+    let title = format!("Tool calls ({}, {unpaired} unpaired)", app.tool_calls.len());
+    let list = List::new(items)
+        .block(pane(title, app.focus == Focus::ToolCalls))
+        .highlight_style(selection_style())
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut list_state(app.selected_tool_call, app.tool_calls.len()),
+    );
+}
+
+fn issue_item(issue: &ValidationIssue) -> ListItem<'static> {
+    let (label, style) = match issue.severity {
+        ValidationIssueSeverity::Error => ("error", Style::default().fg(Color::Red)),
+        ValidationIssueSeverity::Warning => ("warning", Style::default().fg(Color::Yellow)),
+    };
+    ListItem::new(format!("L{} {label}: {}", issue.line, issue.detail)).style(style)
+}
+
+fn draw_issues(frame: &mut Frame, app: &App, area: Rect) {
+    let focused = app.focus == Focus::Issues;
+    let Some(report) = &app.validation_report else {
+        let message = format!(
+            "no validation report at {}",
+            app.validation_report_path.display()
+        );
+        frame.render_widget(
+            Paragraph::new(message).block(pane("Validation issues".to_string(), focused)),
+            area,
+        );
+        return;
+    };
+    let items = report.issues.iter().map(issue_item).collect::<Vec<_>>();
+    let title = format!(
+        "Validation issues ({} errors, {} warnings)",
+        report.errors, report.warnings
+    );
+    let list = List::new(items)
+        .block(pane(title, focused))
+        .highlight_style(selection_style())
+        .highlight_symbol(HIGHLIGHT_SYMBOL);
+    frame.render_stateful_widget(
+        list,
+        area,
+        &mut list_state(app.selected_issue, report.issues.len()),
+    );
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let text = if app.status.is_empty() {
+        format!(
+            "{} (read-only) · q quit · Tab focus · j/k move · s sort · / search",
+            app.mart_label()
+        )
+    } else {
+        app.status.clone()
+    };
+    frame.render_widget(
+        Paragraph::new(text).style(Style::default().add_modifier(Modifier::DIM)),
+        area,
+    );
+}
@@ -0,0 +1,135 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result};
+
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes `bytes` to `path` through a sibling temp file that is fsynced and
+/// renamed into place, so readers see either the old file or the new one.
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(bytes)
+        .with_context(|| format!("failed to write {}", file.temp_path.display()))?;
+    file.commit()
+}
+
+/// `write_atomic` for secrets: the temp file is created with mode `0600` on
+/// unix, so the content is never readable by other users, even briefly.
+pub fn write_atomic_private(path: &Path, bytes: &[u8]) -> Result<()> {
+    let mut file = AtomicFile::create_private(path)?;
+    file.write_all(bytes)
+        .with_context(|| format!("failed to write {}", file.temp_path.display()))?;
+    file.commit()
+}
+
+/// Buffered writer over a temp file next to `path`. `commit` flushes, fsyncs
+/// and renames it over `path`; dropping it uncommitted removes the temp file.
+pub struct AtomicFile {
+    path: PathBuf,
+    temp_path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicFile {
+    pub fn create(path: &Path) -> Result<Self> {
+        Self::open(path, false)
+    }
+
+    pub fn create_private(path: &Path) -> Result<Self> {
+        Self::open(path, true)
+    }
+
+    fn open(path: &Path, private: bool) -> Result<Self> {
+        let parent = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory: {}", parent.display()))?;
+        let file_name = path
+            .file_name()
+            .with_context(|| format!("artifact path has no file name: {}", path.display()))?
+            .to_string_lossy();
+        let temp_path = parent.join(format!(
+            ".{file_name}.tmp-{}-{}",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = OpenOptions::new();
+        options.write(true);
+        if private {
+            options.create_new(true);
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        } else {
+            options.create(true).truncate(true);
+        }
+        let file = options
+            .open(&temp_path)
+            .with_context(|| format!("failed to create temp file: {}", temp_path.display()))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            temp_path,
+            writer: Some(BufWriter::new(file)),
+        })
+    }
+
+    pub fn commit(mut self) -> Result<()> {
+        let Some(writer) = self.writer.take() else {
+            return Ok(());
+        };
+        let file = writer
+            .into_inner()
+            .map_err(|error| error.into_error())
+            .with_context(|| format!("failed to flush {}", self.temp_path.display()))?;
+        file.sync_all()
+            .with_context(|| format!("failed to fsync {}", self.temp_path.display()))?;
+        drop(file);
+        std::fs::rename(&self.temp_path, &self.path).with_context(|| {
+            format!(
+                "failed to move {} into place at {}",
+                self.temp_path.display(),
+                self.path.display()
+            )
+        })?;
+        sync_parent_dir(&self.path);
+        Ok(())
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.write(buf),
+            None => Err(std::io::Error::other("atomic file already committed")),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if self.writer.take().is_some() {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+/// Persists the rename itself; best effort, since not every platform lets a
+/// directory be opened for syncing.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = File::open(parent)
+    {
+        let _ = dir.sync_all();
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
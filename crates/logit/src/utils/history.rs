@@ -124,6 +124,19 @@ fn command_matches_adapter(command: &str, adapter: AdapterKind) -> bool {
                 || normalized.contains(".opencode/")
                 || normalized.contains("/.opencode")
         }
+        AdapterKind::Aider => {
+            primary == "aider"
+                || normalized.contains(".aider.")
+                || normalized.contains("aider-chat")
+        }
+        AdapterKind::Cursor => {
+            primary == "cursor"
+                || normalized.contains("/cursor/user/")
+                || normalized.contains("cursor-agent")
+        }
+        AdapterKind::Copilot => {
+            normalized.contains("copilot") || normalized.contains("/code/user/workspacestorage")
+        }
     }
 }
 
@@ -0,0 +1,89 @@
+use anyhow::{Result, bail};
+use serde_json::Value;
+
+use crate::models::AgentLogEvent;
+
+/// Metadata key that records which machine an event was collected on.
+pub const HOST_METADATA_KEY: &str = "host";
+pub const FALLBACK_HOST_ID: &str = "localhost";
+
+/// Validates a configured host id: lowercased, non-empty and limited to
+/// `[a-z0-9._-]` so it can be embedded in watermark keys and event ids.
+pub fn normalize_host_id(raw: &str) -> Result<String> {
+    let host = raw.trim().to_ascii_lowercase();
+    if host.is_empty() {
+        bail!("host id must not be empty");
+    }
+    if let Some(invalid) = host
+        .chars()
+        .find(|ch| !(ch.is_ascii_alphanumeric() || matches!(ch, '.' | '_' | '-')))
+    {
+        bail!("host id `{raw}` contains unsupported character `{invalid}`; use [a-z0-9._-]");
+    }
+    Ok(host)
+}
+
+/// Derives a host id from the machine name, falling back to `localhost`.
+#[must_use]
+pub fn derived_host_id() -> String {
+    let candidates = [
+        std::fs::read_to_string("/etc/hostname").ok(),
+        std::env::var("HOSTNAME").ok(),
+        std::env::var("COMPUTERNAME").ok(),
+        std::process::Command::new("hostname")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok()),
+    ];
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|candidate| sanitize_host_id(&candidate))
+        .unwrap_or_else(|| FALLBACK_HOST_ID.to_string())
+}
+
+/// The configured host id when set, otherwise the derived one.
+pub fn resolve_host_id(configured: Option<&str>) -> Result<String> {
+    match configured {
+        Some(host) => normalize_host_id(host),
+        None => Ok(derived_host_id()),
+    }
+}
+
+#[must_use]
+pub fn event_host(event: &AgentLogEvent) -> Option<&str> {
+    event
+        .metadata
+        .get(HOST_METADATA_KEY)
+        .and_then(Value::as_str)
+        .filter(|host| !host.is_empty())
+}
+
+/// Records `host` on the event unless it already names one, so events
+/// imported from another machine keep their origin.
+pub fn stamp_host(event: &mut AgentLogEvent, host: &str) {
+    if event_host(event).is_none() {
+        event.metadata.insert(
+            HOST_METADATA_KEY.to_string(),
+            Value::String(host.to_string()),
+        );
+    }
+}
+
+fn sanitize_host_id(raw: &str) -> Option<String> {
+    // Keep the short name; domain suffixes vary with the network a machine is on.
+    let short = raw.trim().split('.').next().unwrap_or_default();
+    let host = short
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-') {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let host = host.trim_matches('-').to_string();
+    (!host.is_empty()).then_some(host)
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::time::{format_unix_ms, unix_timestamp_milliseconds};
+
+pub const OUT_DIR_LOCK_FILE: &str = ".logit.lock";
+pub const OUT_DIR_LOCKED_ERROR_CODE: &str = "out_dir_locked";
+/// How long a lock file that does not parse is respected before it is
+/// treated as left behind by a crashed writer.
+pub const UNREADABLE_LOCK_GRACE: Duration = Duration::from_secs(10);
+
+static LOCK_TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub command: String,
+    pub acquired_at_utc: String,
+}
+
+/// Returned when another live process holds the out-dir lock.
+#[derive(Debug)]
+pub struct OutDirLocked {
+    pub lock_path: PathBuf,
+    pub owner: Option<LockOwner>,
+}
+
+impl fmt::Display for OutDirLocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.owner {
+            Some(owner) => write!(
+                f,
+                "out dir is locked by pid {} (`{}`) since {}; wait for it to finish or remove {} if that process is gone",
+                owner.pid,
+                owner.command,
+                owner.acquired_at_utc,
+                self.lock_path.display()
+            ),
+            None => write!(
+                f,
+                "out dir is locked by another process; remove {} if no logit command is running",
+                self.lock_path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for OutDirLocked {}
+
+/// Advisory lock on an out dir: a `.logit.lock` file naming the owning pid
+/// and command, removed on drop. The owner is written to a temp file and
+/// hard-linked into place, so the lock never exists half-written. A lock left
+/// behind by a process that no longer exists, or an unreadable lock older
+/// than `UNREADABLE_LOCK_GRACE`, is taken over.
+#[derive(Debug)]
+pub struct OutDirLock {
+    path: PathBuf,
+    owner: LockOwner,
+}
+
+impl OutDirLock {
+    pub fn acquire(out_dir: &Path, command: &str) -> Result<Self> {
+        std::fs::create_dir_all(out_dir)
+            .with_context(|| format!("failed to create out dir: {}", out_dir.display()))?;
+        let path = lock_path(out_dir);
+        let owner = LockOwner {
+            pid: std::process::id(),
+            command: command.to_string(),
+            acquired_at_utc: format_unix_ms(unix_timestamp_milliseconds()),
+        };
+        let encoded = serde_json::to_vec(&owner).context("failed to encode out dir lock")?;
+        let temp_path = sibling_path(&path, "tmp");
+        write_synced(&temp_path, &encoded)
+            .with_context(|| format!("failed to write out dir lock: {}", temp_path.display()))?;
+        let acquired = Self::link_into_place(&path, &temp_path, &owner);
+        let _ = std::fs::remove_file(&temp_path);
+        acquired?;
+        Ok(Self { path, owner })
+    }
+
+    fn link_into_place(path: &Path, temp_path: &Path, owner: &LockOwner) -> Result<()> {
+        for _ in 0..3 {
+            match std::fs::hard_link(temp_path, path) {
+                Ok(()) => {
+                    // Re-read, so a takeover that raced another process is
+                    // only reported as acquired if the lock still names us.
+                    return match read_lock_owner(path) {
+                        Some(holder) if holder == *owner => Ok(()),
+                        holder => Err(OutDirLocked {
+                            lock_path: path.to_path_buf(),
+                            owner: holder,
+                        }
+                        .into()),
+                    };
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {
+                    let Ok(raw) = std::fs::read(path) else {
+                        continue;
+                    };
+                    let holder = serde_json::from_slice::<LockOwner>(&raw).ok();
+                    let stale = match &holder {
+                        Some(holder) => !process_alive(holder.pid),
+                        None => unreadable_lock_expired(path),
+                    };
+                    if !stale {
+                        return Err(OutDirLocked {
+                            lock_path: path.to_path_buf(),
+                            owner: holder,
+                        }
+                        .into());
+                    }
+                    remove_stale_lock(path, &raw)?;
+                }
+                Err(error) => {
+                    return Err(error).with_context(|| {
+                        format!("failed to create out dir lock: {}", path.display())
+                    });
+                }
+            }
+        }
+        Err(OutDirLocked {
+            owner: read_lock_owner(path),
+            lock_path: path.to_path_buf(),
+        }
+        .into())
+    }
+
+    #[must_use]
+    pub fn owner(&self) -> &LockOwner {
+        &self.owner
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for OutDirLock {
+    fn drop(&mut self) {
+        if read_lock_owner(&self.path).is_some_and(|owner| owner == self.owner) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[must_use]
+pub fn lock_path(out_dir: &Path) -> PathBuf {
+    out_dir.join(OUT_DIR_LOCK_FILE)
+}
+
+/// Moves the stale lock aside before deleting it, so only one process can
+/// claim a given stale lock. If a fresh lock replaced it in the meantime, that
+/// lock is put back.
+fn remove_stale_lock(path: &Path, stale: &[u8]) -> Result<()> {
+    let grave = sibling_path(path, "stale");
+    match std::fs::rename(path, &grave) {
+        Ok(()) => {}
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(error) => {
+            return Err(error).with_context(|| {
+                format!("failed to remove stale out dir lock: {}", path.display())
+            });
+        }
+    }
+    if std::fs::read(&grave).is_ok_and(|raw| raw != stale) {
+        let _ = std::fs::hard_link(&grave, path);
+    }
+    let _ = std::fs::remove_file(&grave);
+    Ok(())
+}
+
+fn unreadable_lock_expired(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age >= UNREADABLE_LOCK_GRACE)
+}
+
+fn sibling_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}-{}.{suffix}",
+        std::process::id(),
+        LOCK_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    path.with_file_name(name)
+}
+
+fn write_synced(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+#[must_use]
+pub fn read_lock_owner(path: &Path) -> Option<LockOwner> {
+    let raw = std::fs::read(path).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+/// Whether `pid` names a running process. Uses `/proc` on Linux and
+/// `kill -0` elsewhere on Unix; other platforms assume it is alive.
+#[must_use]
+pub fn process_alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Path::new("/proc").join(pid.to_string()).exists()
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success())
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
@@ -1,5 +1,10 @@
 pub mod content;
+pub mod fs;
 pub mod hash;
 pub mod history;
+pub mod host;
+pub mod lock;
+pub mod protobuf;
 pub mod redaction;
 pub mod time;
+pub mod vscdb;
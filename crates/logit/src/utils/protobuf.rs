@@ -0,0 +1,233 @@
+use anyhow::{Result, bail};
+use serde_json::{Map, Value};
+
+pub const MAX_NESTING_DEPTH: usize = 32;
+
+const WIRE_VARINT: u64 = 0;
+const WIRE_FIXED64: u64 = 1;
+const WIRE_LENGTH_DELIMITED: u64 = 2;
+const WIRE_START_GROUP: u64 = 3;
+const WIRE_END_GROUP: u64 = 4;
+const WIRE_FIXED32: u64 = 5;
+
+pub fn decode_message(bytes: &[u8]) -> Result<Value> {
+    decode_message_at_depth(bytes, 0)
+}
+
+#[must_use]
+pub fn field(message: &Value, field_number: u32) -> Option<&Value> {
+    message.as_object()?.get(&field_number.to_string())
+}
+
+#[must_use]
+pub fn repeated_field(message: &Value, field_number: u32) -> Vec<&Value> {
+    match field(message, field_number) {
+        Some(Value::Array(items)) => items.iter().collect(),
+        Some(value) => vec![value],
+        None => Vec::new(),
+    }
+}
+
+fn decode_message_at_depth(bytes: &[u8], depth: usize) -> Result<Value> {
+    if depth > MAX_NESTING_DEPTH {
+        bail!("protobuf nesting exceeds maximum depth of {MAX_NESTING_DEPTH}");
+    }
+
+    let mut fields = Map::new();
+    let mut cursor = 0_usize;
+
+    while cursor < bytes.len() {
+        let tag_offset = cursor;
+        let tag = read_varint(bytes, &mut cursor)?;
+        let field_number = tag >> 3;
+        let wire_type = tag & 0x7;
+        if field_number == 0 || field_number > u64::from(u32::MAX >> 3) {
+            bail!("invalid protobuf field number {field_number} at byte {tag_offset}");
+        }
+
+        let value = match wire_type {
+            WIRE_VARINT => Value::from(read_varint(bytes, &mut cursor)?),
+            WIRE_FIXED64 => {
+                let raw = read_fixed::<8>(bytes, &mut cursor)?;
+                Value::from(u64::from_le_bytes(raw))
+            }
+            WIRE_LENGTH_DELIMITED => {
+                let length = usize::try_from(read_varint(bytes, &mut cursor)?)?;
+                let end = cursor
+                    .checked_add(length)
+                    .filter(|end| *end <= bytes.len())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "truncated protobuf length-delimited field {field_number} at byte {tag_offset}"
+                        )
+                    })?;
+                let payload = &bytes[cursor..end];
+                cursor = end;
+                decode_length_delimited(payload, depth)
+            }
+            WIRE_FIXED32 => {
+                let raw = read_fixed::<4>(bytes, &mut cursor)?;
+                Value::from(u32::from_le_bytes(raw))
+            }
+            WIRE_START_GROUP | WIRE_END_GROUP => {
+                bail!(
+                    "unsupported protobuf group wire type for field {field_number} at byte {tag_offset}"
+                )
+            }
+            other => bail!("invalid protobuf wire type {other} at byte {tag_offset}"),
+        };
+
+        insert_field(&mut fields, field_number.to_string(), value);
+    }
+
+    Ok(Value::Object(fields))
+}
+
+fn decode_length_delimited(payload: &[u8], depth: usize) -> Value {
+    if payload.is_empty() {
+        return Value::String(String::new());
+    }
+
+    let text = std::str::from_utf8(payload).ok();
+    if let Some(text) = text
+        && looks_like_text(text)
+    {
+        return Value::String(text.to_string());
+    }
+    if let Ok(nested) = decode_message_at_depth(payload, depth + 1) {
+        return nested;
+    }
+    if let Some(text) = text {
+        return Value::String(text.to_string());
+    }
+
+    let hex = payload
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    Value::String(format!("0x{hex}"))
+}
+
+fn looks_like_text(text: &str) -> bool {
+    let mut chars = text.chars();
+    let Some(first) = chars.next() else {
+        return true;
+    };
+    !first.is_control() && chars.all(|ch| !ch.is_control() || matches!(ch, '\n' | '\r' | '\t'))
+}
+
+fn insert_field(fields: &mut Map<String, Value>, key: String, value: Value) {
+    match fields.get_mut(&key) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            fields.insert(key, value);
+        }
+    }
+}
+
+fn read_varint(bytes: &[u8], cursor: &mut usize) -> Result<u64> {
+    let start = *cursor;
+    let mut value = 0_u64;
+
+    for shift in (0..64).step_by(7) {
+        let Some(byte) = bytes.get(*cursor).copied() else {
+            bail!("truncated protobuf varint at byte {start}");
+        };
+        *cursor += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    bail!("protobuf varint at byte {start} exceeds 10 bytes")
+}
+
+fn read_fixed<const N: usize>(bytes: &[u8], cursor: &mut usize) -> Result<[u8; N]> {
+    let start = *cursor;
+    let end = start + N;
+    let Some(slice) = bytes.get(start..end) else {
+        bail!(
+            "truncated protobuf fixed{}-bit field at byte {start}",
+            N * 8
+        );
+    };
+    *cursor = end;
+    let mut raw = [0_u8; N];
+    raw.copy_from_slice(slice);
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{decode_message, field, repeated_field};
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn length_delimited(field_number: u64, payload: &[u8]) -> Vec<u8> {
+        let mut out = varint((field_number << 3) | 2);
+        out.extend(varint(payload.len() as u64));
+        out.extend_from_slice(payload);
+        out
+    }
+
+    #[test]
+    fn decodes_scalar_wire_types() {
+        let mut bytes = varint(1 << 3);
+        bytes.extend(varint(300));
+        bytes.extend(varint((2 << 3) | 1));
+        bytes.extend(7_u64.to_le_bytes());
+        bytes.extend(varint((3 << 3) | 5));
+        bytes.extend(9_u32.to_le_bytes());
+
+        let decoded = decode_message(&bytes).expect("scalar fields should decode");
+        assert_eq!(decoded, json!({"1": 300, "2": 7, "3": 9}));
+    }
+
+    #[test]
+    fn decodes_nested_messages_strings_and_repeated_fields() {
+        let inner = length_delimited(1, b"hello");
+        let mut bytes = length_delimited(4, &inner);
+        bytes.extend(length_delimited(4, &length_delimited(1, b"world")));
+        bytes.extend(length_delimited(5, b"plain text"));
+        bytes.extend(length_delimited(6, &[0xff, 0x00]));
+
+        let decoded = decode_message(&bytes).expect("nested fields should decode");
+        assert_eq!(
+            decoded,
+            json!({
+                "4": [{"1": "hello"}, {"1": "world"}],
+                "5": "plain text",
+                "6": "0xff00"
+            })
+        );
+        assert_eq!(repeated_field(&decoded, 4).len(), 2);
+        assert_eq!(repeated_field(&decoded, 5).len(), 1);
+        assert!(field(&decoded, 9).is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_wire_data() {
+        assert!(decode_message(&[0x00, 0x01]).is_err());
+        assert!(decode_message(&[0x0a, 0x05, b'a']).is_err());
+        assert!(decode_message(&[0x0b]).is_err());
+        assert!(decode_message(&[0x08, 0x80]).is_err());
+    }
+}
@@ -1,20 +1,376 @@
 use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
+use anyhow::{Context, Result, bail};
+use hmac::{Hmac, Mac};
 use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::Sha256;
 
 pub const REDACTION_TOKEN: &str = "[REDACTED]";
 pub const DEFAULT_SNAPSHOT_MAX_CHARS: usize = 240;
+pub const RULES_FILE: &str = ".logit/redaction.toml";
+pub const PSEUDONYM_KEY_FILE: &str = "pseudonym.key";
+const PSEUDONYM_KEY_BYTES: usize = 32;
+const PSEUDONYM_DIGEST_HEX_CHARS: usize = 12;
+const ALLOWLIST_REGEX_PREFIX: &str = "re:";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RedactionPolicy {
+    #[serde(rename = "off")]
+    Off,
+    #[default]
+    #[serde(rename = "secrets-only")]
+    SecretsOnly,
+    #[serde(rename = "secrets+pii")]
+    SecretsAndPii,
+}
+
+impl RedactionPolicy {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::SecretsOnly => "secrets-only",
+            Self::SecretsAndPii => "secrets+pii",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "secrets-only" | "secrets" => Ok(Self::SecretsOnly),
+            "secrets+pii" | "secrets-pii" | "all" => Ok(Self::SecretsAndPii),
+            other => Err(format!(
+                "unknown redaction policy `{other}` (expected off, secrets-only or secrets+pii)"
+            )),
+        }
+    }
+
+    const fn includes(self, category: RedactionCategory) -> bool {
+        match (self, category) {
+            (Self::Off, _) => false,
+            (Self::SecretsOnly, RedactionCategory::Pii) => false,
+            (Self::SecretsOnly | Self::SecretsAndPii, _) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RedactionCategory {
+    #[default]
+    Secret,
+    Pii,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RedactionRules {
+    rules: Vec<UserRedactionRule>,
+    allowlist: Vec<AllowlistEntry>,
+    pseudonym_key: Option<PseudonymKey>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PseudonymKey(Vec<u8>);
+
+impl std::fmt::Debug for PseudonymKey {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str("PseudonymKey(..)")
+    }
+}
+
+impl PseudonymKey {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < 16 {
+            bail!("pseudonym key must be at least 16 bytes");
+        }
+        Ok(Self(bytes.to_vec()))
+    }
+
+    pub fn load_or_create(out_dir: &Path) -> Result<Self> {
+        Self::load_or_create_at(&pseudonym_key_path(out_dir))
+    }
+
+    pub fn load_or_create_at(path: &Path) -> Result<Self> {
+        if path.is_file() {
+            let encoded = std::fs::read_to_string(path)
+                .with_context(|| format!("failed to read pseudonym key: {}", path.display()))?;
+            let bytes = decode_hex(encoded.trim())
+                .with_context(|| format!("pseudonym key is not valid hex: {}", path.display()))?;
+            return Self::from_bytes(&bytes);
+        }
+
+        let mut bytes = [0_u8; PSEUDONYM_KEY_BYTES];
+        getrandom::fill(&mut bytes)
+            .map_err(|error| anyhow::anyhow!("failed to generate pseudonym key: {error}"))?;
+        crate::utils::fs::write_atomic_private(
+            path,
+            format!("{}\n", encode_hex(&bytes)).as_bytes(),
+        )
+        .with_context(|| format!("failed to write pseudonym key: {}", path.display()))?;
+        Self::from_bytes(&bytes)
+    }
+
+    #[must_use]
+    pub fn token(&self, class_name: &str, value: &str) -> String {
+        format!(
+            "[{class_name}:{}]",
+            &self.digest_hex(value)[..PSEUDONYM_DIGEST_HEX_CHARS]
+        )
+    }
+
+    #[must_use]
+    pub fn digest_hex(&self, value: &str) -> String {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.0).expect("hmac accepts keys of any length");
+        mac.update(value.as_bytes());
+        encode_hex(&mac.finalize().into_bytes())
+    }
+}
+
+#[must_use]
+pub fn pseudonym_key_path(out_dir: &Path) -> PathBuf {
+    out_dir.join(PSEUDONYM_KEY_FILE)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    if !value.len().is_multiple_of(2) || !value.is_ascii() {
+        bail!("expected an even number of hex digits");
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|index| {
+            u8::from_str_radix(&value[index..index + 2], 16)
+                .with_context(|| format!("invalid hex byte at offset {index}"))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+struct UserRedactionRule {
+    class_name: String,
+    category: RedactionCategory,
+    pattern: String,
+    regex: Regex,
+}
+
+impl PartialEq for UserRedactionRule {
+    fn eq(&self, other: &Self) -> bool {
+        self.class_name == other.class_name
+            && self.category == other.category
+            && self.pattern == other.pattern
+    }
+}
+
+impl Eq for UserRedactionRule {}
+
+/// An allowlist entry matches a whole redaction span: either the exact
+/// literal, or with the `re:` prefix an anchored regex.
+#[derive(Debug, Clone)]
+struct AllowlistEntry {
+    raw: String,
+    regex: Option<Regex>,
+}
+
+impl AllowlistEntry {
+    fn parse(raw: String) -> Result<Self> {
+        let regex = match raw.strip_prefix(ALLOWLIST_REGEX_PREFIX) {
+            Some("") => {
+                bail!("redaction allowlist entries must not be empty")
+            }
+            Some(pattern) => Some(Regex::new(&format!("^(?:{pattern})$")).with_context(|| {
+                format!("redaction allowlist entry `{raw}` has an invalid pattern")
+            })?),
+            None => None,
+        };
+        Ok(Self { raw, regex })
+    }
+
+    fn matches(&self, matched: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(matched),
+            None => matched == self.raw,
+        }
+    }
+}
+
+impl PartialEq for AllowlistEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.raw == other.raw
+    }
+}
+
+impl Eq for AllowlistEntry {}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RedactionRulesFile {
+    #[serde(default)]
+    allowlist: Vec<String>,
+    #[serde(default)]
+    rules: Vec<RedactionRuleSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RedactionRuleSpec {
+    class: String,
+    pattern: String,
+    #[serde(default)]
+    category: RedactionCategory,
+}
+
+impl RedactionRules {
+    pub fn parse_toml(input: &str) -> Result<Self> {
+        let file = toml::from_str::<RedactionRulesFile>(input)
+            .context("redaction rules file must be valid TOML")?;
+
+        let mut seen = redaction_builtin_classes()
+            .iter()
+            .map(|class_name| (*class_name).to_string())
+            .collect::<BTreeSet<_>>();
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for spec in file.rules {
+            if !is_valid_class_name(&spec.class) {
+                bail!(
+                    "redaction rule class `{}` must be a lowercase slug ([a-z][a-z0-9_]*)",
+                    spec.class
+                );
+            }
+            if !seen.insert(spec.class.clone()) {
+                bail!(
+                    "redaction rule class `{}` is already defined by the built-in catalog or an earlier rule",
+                    spec.class
+                );
+            }
+            let regex = Regex::new(&spec.pattern).with_context(|| {
+                format!("redaction rule `{}` has an invalid pattern", spec.class)
+            })?;
+            if regex.is_match("") {
+                bail!(
+                    "redaction rule `{}` pattern must not match the empty string",
+                    spec.class
+                );
+            }
+            rules.push(UserRedactionRule {
+                class_name: spec.class,
+                category: spec.category,
+                pattern: spec.pattern,
+                regex,
+            });
+        }
+
+        if file.allowlist.iter().any(|entry| entry.is_empty()) {
+            bail!("redaction allowlist entries must not be empty");
+        }
+        let allowlist = file
+            .allowlist
+            .into_iter()
+            .map(AllowlistEntry::parse)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            rules,
+            allowlist,
+            pseudonym_key: None,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let input = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read redaction rules: {}", path.display()))?;
+        Self::parse_toml(&input)
+            .with_context(|| format!("invalid redaction rules: {}", path.display()))
+    }
+
+    #[must_use]
+    pub fn with_pseudonym_key(mut self, key: PseudonymKey) -> Self {
+        self.pseudonym_key = Some(key);
+        self
+    }
+
+    #[must_use]
+    pub fn pseudonymizes(&self) -> bool {
+        self.pseudonym_key.is_some()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty() && self.allowlist.is_empty()
+    }
+
+    #[must_use]
+    pub fn custom_classes(&self) -> Vec<&str> {
+        self.rules
+            .iter()
+            .map(|rule| rule.class_name.as_str())
+            .collect()
+    }
+
+    #[must_use]
+    pub fn allowlist(&self) -> Vec<&str> {
+        self.allowlist
+            .iter()
+            .map(|entry| entry.raw.as_str())
+            .collect()
+    }
+
+    fn replacement_token(&self, class_name: &str, value: &str) -> String {
+        match &self.pseudonym_key {
+            Some(key) => key.token(class_name, value),
+            None => REDACTION_TOKEN.to_string(),
+        }
+    }
+
+    fn allows(&self, matched: &str) -> bool {
+        self.allowlist.iter().any(|entry| entry.matches(matched))
+    }
+
+    /// Allows a regex match when an entry covers the whole match or just the
+    /// value group that would be masked (`fixture123` in `password=fixture123`).
+    fn allows_captures(&self, captures: &Captures<'_>, value_group: usize) -> bool {
+        self.allows(&captures[0])
+            || captures
+                .get(value_group)
+                .is_some_and(|value| self.allows(value.as_str()))
+    }
+}
+
+#[must_use]
+pub fn rules_path(home_dir: &Path) -> PathBuf {
+    home_dir.join(RULES_FILE)
+}
+
+fn is_valid_class_name(value: &str) -> bool {
+    let mut chars = value.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|ch| matches!(ch, 'a'..='z' | '0'..='9' | '_'))
+}
+
+type RegexReplacement = for<'a> fn(&Captures<'a>, &dyn Fn(&str) -> String) -> String;
 
 struct RegexRedactionMatcher {
     class_name: &'static str,
+    category: RedactionCategory,
     regex: fn() -> &'static Regex,
-    replacement: for<'a> fn(&Captures<'a>) -> String,
+    replacement: RegexReplacement,
+    /// Capture group holding the masked value; 0 when the whole match is.
+    value_group: usize,
 }
 
 struct HeuristicRedactionMatcher {
     class_name: &'static str,
+    category: RedactionCategory,
     predicate: fn(&str) -> bool,
     replacement: fn(&str) -> String,
 }
@@ -44,47 +400,248 @@ pub fn redact_secret(value: &str) -> String {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RedactionFinding {
+    pub class_name: String,
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedactionSpan {
+    pub class_name: String,
+    pub start: usize,
+    pub end: usize,
+}
+
 #[must_use]
 pub fn redact_and_truncate_text(value: &str, max_chars: usize) -> TextRedactionResult {
+    redact_and_truncate_text_with_rules(value, max_chars, &RedactionRules::default())
+}
+
+#[must_use]
+pub fn redact_and_truncate_text_with_rules(
+    value: &str,
+    max_chars: usize,
+    rules: &RedactionRules,
+) -> TextRedactionResult {
+    let mut classes = BTreeSet::new();
+    let redacted = redact_with_policy(value, RedactionPolicy::SecretsAndPii, rules, &mut classes);
+    let pii_redacted = !classes.is_empty();
+    let (text, truncated) = truncate_deterministic(&redacted, max_chars);
+
+    TextRedactionResult {
+        text,
+        pii_redacted,
+        truncated,
+        redaction_classes: classes.into_iter().collect(),
+    }
+}
+
+#[must_use]
+pub fn redact_text(
+    value: &str,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) -> TextRedactionResult {
     let mut classes = BTreeSet::new();
+    let text = redact_with_policy(value, policy, rules, &mut classes);
+    TextRedactionResult {
+        text,
+        pii_redacted: !classes.is_empty(),
+        truncated: false,
+        redaction_classes: classes.into_iter().collect(),
+    }
+}
+
+#[must_use]
+pub fn redact_json(
+    value: &Value,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) -> JsonRedactionResult {
+    let mut classes = BTreeSet::new();
+    let value = redact_json_strings(value, &mut |text| {
+        redact_with_policy(text, policy, rules, &mut classes)
+    });
+    JsonRedactionResult {
+        value,
+        pii_redacted: !classes.is_empty(),
+        truncated: false,
+        redaction_classes: classes.into_iter().collect(),
+    }
+}
+
+fn redact_json_strings(value: &Value, redact: &mut impl FnMut(&str) -> String) -> Value {
+    match value {
+        Value::String(text) => Value::String(redact(text)),
+        Value::Array(values) => Value::Array(
+            values
+                .iter()
+                .map(|item| redact_json_strings(item, redact))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, item)| (key.clone(), redact_json_strings(item, redact)))
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn redact_with_policy(
+    value: &str,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+    classes: &mut BTreeSet<String>,
+) -> String {
     let mut redacted = value.to_string();
 
+    // A heuristic match spans the whole value, so only an entry matching all
+    // of it suppresses the mask.
     for matcher in heuristic_redaction_matcher_catalog() {
-        if (matcher.predicate)(&redacted) {
+        if policy.includes(matcher.category)
+            && (matcher.predicate)(&redacted)
+            && !rules.allows(&redacted)
+        {
             redacted = (matcher.replacement)(&redacted);
             classes.insert(matcher.class_name.to_string());
         }
     }
 
     for matcher in redaction_matcher_catalog() {
+        if !policy.includes(matcher.category) {
+            continue;
+        }
         redacted = apply_replace_all(
             redacted,
             (matcher.regex)(),
-            matcher.replacement,
+            |captures: &Captures<'_>| {
+                if rules.allows_captures(captures, matcher.value_group) {
+                    captures[0].to_string()
+                } else {
+                    (matcher.replacement)(captures, &|value| {
+                        rules.replacement_token(matcher.class_name, value)
+                    })
+                }
+            },
             matcher.class_name,
-            &mut classes,
+            classes,
         );
     }
 
-    let pii_redacted = !classes.is_empty();
-    let (text, truncated) = truncate_deterministic(&redacted, max_chars);
+    for rule in &rules.rules {
+        if !policy.includes(rule.category) {
+            continue;
+        }
+        redacted = apply_replace_all(
+            redacted,
+            &rule.regex,
+            |captures: &Captures<'_>| {
+                if rules.allows(&captures[0]) {
+                    captures[0].to_string()
+                } else {
+                    rules.replacement_token(&rule.class_name, &captures[0])
+                }
+            },
+            &rule.class_name,
+            classes,
+        );
+    }
 
-    TextRedactionResult {
-        text,
-        pii_redacted,
-        truncated,
-        redaction_classes: classes.into_iter().collect(),
+    redacted
+}
+
+#[must_use]
+pub fn find_redactions(
+    value: &str,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) -> Vec<RedactionFinding> {
+    find_redaction_spans(value, policy, rules)
+        .into_iter()
+        .map(|span| {
+            let prefix = &value[..span.start];
+            let line_start = prefix.rfind('\n').map_or(0, |index| index + 1);
+            RedactionFinding {
+                class_name: span.class_name,
+                line: prefix.matches('\n').count() + 1,
+                column: prefix[line_start..].chars().count() + 1,
+                length: value[span.start..span.end].chars().count(),
+            }
+        })
+        .collect()
+}
+
+#[must_use]
+pub fn find_redaction_spans(
+    value: &str,
+    policy: RedactionPolicy,
+    rules: &RedactionRules,
+) -> Vec<RedactionSpan> {
+    let mut claimed = Vec::<(usize, usize, String)>::new();
+
+    for matcher in heuristic_redaction_matcher_catalog() {
+        if policy.includes(matcher.category) && (matcher.predicate)(value) && !rules.allows(value) {
+            claimed.push((0, value.len(), matcher.class_name.to_string()));
+        }
     }
+
+    let regex_matchers = redaction_matcher_catalog()
+        .iter()
+        .filter(|matcher| policy.includes(matcher.category))
+        .map(|matcher| (matcher.class_name, (matcher.regex)(), matcher.value_group))
+        .chain(
+            rules
+                .rules
+                .iter()
+                .filter(|rule| policy.includes(rule.category))
+                .map(|rule| (rule.class_name.as_str(), &rule.regex, 0)),
+        );
+    for (class_name, regex, value_group) in regex_matchers {
+        for captures in regex.captures_iter(value) {
+            let found = captures.get(0).expect("regex match has group 0");
+            let overlaps = claimed
+                .iter()
+                .any(|(start, end, _)| found.start() < *end && *start < found.end());
+            if overlaps || rules.allows_captures(&captures, value_group) {
+                continue;
+            }
+            claimed.push((found.start(), found.end(), class_name.to_string()));
+        }
+    }
+
+    claimed.sort();
+    claimed
+        .into_iter()
+        .map(|(start, end, class_name)| RedactionSpan {
+            class_name,
+            start,
+            end,
+        })
+        .collect()
 }
 
 #[must_use]
 pub fn redact_and_truncate_json(value: &Value, max_chars: usize) -> JsonRedactionResult {
+    redact_and_truncate_json_with_rules(value, max_chars, &RedactionRules::default())
+}
+
+#[must_use]
+pub fn redact_and_truncate_json_with_rules(
+    value: &Value,
+    max_chars: usize,
+    rules: &RedactionRules,
+) -> JsonRedactionResult {
     let mut classes = BTreeSet::new();
     let mut pii_redacted = false;
     let mut truncated = false;
     let redacted = redact_json_value(
         value,
         max_chars,
+        rules,
         &mut classes,
         &mut pii_redacted,
         &mut truncated,
@@ -101,13 +658,14 @@ pub fn redact_and_truncate_json(value: &Value, max_chars: usize) -> JsonRedactio
 fn redact_json_value(
     value: &Value,
     max_chars: usize,
+    rules: &RedactionRules,
     classes: &mut BTreeSet<String>,
     pii_redacted: &mut bool,
     truncated: &mut bool,
 ) -> Value {
     match value {
         Value::String(text) => {
-            let result = redact_and_truncate_text(text, max_chars);
+            let result = redact_and_truncate_text_with_rules(text, max_chars, rules);
             if result.pii_redacted {
                 *pii_redacted = true;
             }
@@ -122,7 +680,9 @@ fn redact_json_value(
         Value::Array(values) => Value::Array(
             values
                 .iter()
-                .map(|item| redact_json_value(item, max_chars, classes, pii_redacted, truncated))
+                .map(|item| {
+                    redact_json_value(item, max_chars, rules, classes, pii_redacted, truncated)
+                })
                 .collect(),
         ),
         Value::Object(map) => Value::Object(
@@ -130,7 +690,7 @@ fn redact_json_value(
                 .map(|(key, item)| {
                     (
                         key.clone(),
-                        redact_json_value(item, max_chars, classes, pii_redacted, truncated),
+                        redact_json_value(item, max_chars, rules, classes, pii_redacted, truncated),
                     )
                 })
                 .collect(),
@@ -156,7 +716,15 @@ fn apply_replace_all(
 }
 
 #[must_use]
-pub fn redaction_catalog_classes() -> &'static [&'static str] {
+pub fn redaction_catalog_classes(rules: &RedactionRules) -> Vec<String> {
+    redaction_builtin_classes()
+        .iter()
+        .map(|class_name| (*class_name).to_string())
+        .chain(rules.rules.iter().map(|rule| rule.class_name.clone()))
+        .collect()
+}
+
+fn redaction_builtin_classes() -> &'static [&'static str] {
     static CLASSES: OnceLock<Vec<&'static str>> = OnceLock::new();
     CLASSES
         .get_or_init(|| {
@@ -181,6 +749,7 @@ fn heuristic_redaction_matcher_catalog() -> &'static [HeuristicRedactionMatcher]
     CATALOG.get_or_init(|| {
         vec![HeuristicRedactionMatcher {
             class_name: "binary_blob",
+            category: RedactionCategory::Secret,
             predicate: looks_binary_like_text,
             replacement: replace_binary_blob,
         }]
@@ -239,7 +808,7 @@ fn private_key_regex() -> &'static Regex {
 fn bearer_token_regex() -> &'static Regex {
     static REGEX: OnceLock<Regex> = OnceLock::new();
     REGEX.get_or_init(|| {
-        Regex::new(r"(?i)\bbearer\s+[A-Za-z0-9._=\-]{8,}")
+        Regex::new(r"(?i)\bbearer\s+([A-Za-z0-9._=\-]{8,})")
             .expect("bearer token regex should compile")
     })
 }
@@ -283,20 +852,20 @@ fn phone_regex() -> &'static Regex {
     })
 }
 
-fn replace_with_redaction(_captures: &Captures<'_>) -> String {
-    REDACTION_TOKEN.to_string()
+fn replace_with_redaction(captures: &Captures<'_>, token: &dyn Fn(&str) -> String) -> String {
+    token(&captures[0])
 }
 
-fn replace_bearer_token(_captures: &Captures<'_>) -> String {
-    format!("Bearer {REDACTION_TOKEN}")
+fn replace_bearer_token(captures: &Captures<'_>, token: &dyn Fn(&str) -> String) -> String {
+    format!("Bearer {}", token(&captures[1]))
 }
 
-fn replace_secret_assignment(captures: &Captures<'_>) -> String {
-    format!("{}{}{}", &captures[1], &captures[2], REDACTION_TOKEN)
+fn replace_secret_assignment(captures: &Captures<'_>, token: &dyn Fn(&str) -> String) -> String {
+    format!("{}{}{}", &captures[1], &captures[2], token(&captures[3]))
 }
 
-fn replace_url_query_token(captures: &Captures<'_>) -> String {
-    format!("{}{}", &captures[1], REDACTION_TOKEN)
+fn replace_url_query_token(captures: &Captures<'_>, token: &dyn Fn(&str) -> String) -> String {
+    format!("{}{}", &captures[1], token(&captures[2]))
 }
 
 fn redaction_matcher_catalog() -> &'static [RegexRedactionMatcher] {
@@ -305,38 +874,52 @@ fn redaction_matcher_catalog() -> &'static [RegexRedactionMatcher] {
         vec![
             RegexRedactionMatcher {
                 class_name: "private_key_pem",
+                category: RedactionCategory::Secret,
                 regex: private_key_regex,
                 replacement: replace_with_redaction,
+                value_group: 0,
             },
             RegexRedactionMatcher {
                 class_name: "bearer_token",
+                category: RedactionCategory::Secret,
                 regex: bearer_token_regex,
                 replacement: replace_bearer_token,
+                value_group: 1,
             },
             RegexRedactionMatcher {
                 class_name: "api_token",
+                category: RedactionCategory::Secret,
                 regex: api_token_regex,
                 replacement: replace_with_redaction,
+                value_group: 0,
             },
             RegexRedactionMatcher {
                 class_name: "secret_assignment",
+                category: RedactionCategory::Secret,
                 regex: secret_assignment_regex,
                 replacement: replace_secret_assignment,
+                value_group: 3,
             },
             RegexRedactionMatcher {
                 class_name: "url_query_token",
+                category: RedactionCategory::Secret,
                 regex: url_query_token_regex,
                 replacement: replace_url_query_token,
+                value_group: 2,
             },
             RegexRedactionMatcher {
                 class_name: "email",
+                category: RedactionCategory::Pii,
                 regex: email_regex,
                 replacement: replace_with_redaction,
+                value_group: 0,
             },
             RegexRedactionMatcher {
                 class_name: "phone",
+                category: RedactionCategory::Pii,
                 regex: phone_regex,
                 replacement: replace_with_redaction,
+                value_group: 0,
             },
         ]
     })
@@ -347,8 +930,8 @@ mod tests {
     use serde_json::json;
 
     use super::{
-        REDACTION_TOKEN, redact_and_truncate_json, redact_and_truncate_text, redact_secret,
-        redaction_catalog_classes,
+        REDACTION_TOKEN, RedactionRules, redact_and_truncate_json, redact_and_truncate_text,
+        redact_secret, redaction_catalog_classes,
     };
 
     #[test]
@@ -426,7 +1009,7 @@ mod tests {
     #[test]
     fn exposes_catalog_classes_in_deterministic_order() {
         assert_eq!(
-            redaction_catalog_classes(),
+            redaction_catalog_classes(&RedactionRules::default()),
             &[
                 "binary_blob",
                 "private_key_pem",
@@ -181,6 +181,39 @@ pub fn parse_timestamp_to_unix_ms(raw: &str) -> Result<u64> {
     bail!("unsupported timestamp format: {candidate}");
 }
 
+/// Parses a `--since`/`--until` bound: anything `parse_timestamp_to_unix_ms`
+/// accepts, or a relative age such as `90s`, `30m`, `12h`, `7d` or `2w`
+/// counted back from `now_unix_ms`.
+pub fn parse_time_bound(raw: &str, now_unix_ms: u64) -> Result<u64> {
+    let candidate = raw.trim();
+    let unit_ms = match candidate.chars().last() {
+        Some('s') => Some(1_000),
+        Some('m') => Some(60_000),
+        Some('h') => Some(3_600_000),
+        Some('d') => Some(86_400_000),
+        Some('w') => Some(604_800_000),
+        _ => None,
+    };
+    if let Some(unit_ms) = unit_ms
+        && let Ok(amount) = candidate[..candidate.len() - 1].parse::<u64>()
+    {
+        let age_ms = amount
+            .checked_mul(unit_ms)
+            .ok_or_else(|| anyhow::anyhow!("relative time `{candidate}` is too large"))?;
+        return Ok(now_unix_ms.saturating_sub(age_ms));
+    }
+    parse_timestamp_to_unix_ms(candidate)
+}
+
+#[must_use]
+pub fn unix_timestamp_milliseconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| {
+            u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+        })
+}
+
 pub fn format_unix_ms(timestamp_unix_ms: u64) -> String {
     let nanos = i128::from(timestamp_unix_ms)
         .checked_mul(NANOS_PER_MILLI)
@@ -236,7 +269,7 @@ mod tests {
     use super::{
         DurationDerivationInput, DurationDerivationMethod, DurationQuality, TimestampQuality,
         derive_duration, derive_timestamp, fallback_timestamp, format_unix_ms,
-        normalize_timestamp_exact, parse_timestamp_to_unix_ms,
+        normalize_timestamp_exact, parse_time_bound, parse_timestamp_to_unix_ms,
     };
 
     #[test]
@@ -428,4 +461,21 @@ mod tests {
             "heuristic duration should map to low confidence"
         );
     }
+
+    #[test]
+    fn parses_relative_and_absolute_time_bounds() {
+        let now = 1_770_274_803_000;
+        assert_eq!(parse_time_bound("7d", now).ok(), Some(now - 7 * 86_400_000));
+        assert_eq!(parse_time_bound("30m", now).ok(), Some(now - 1_800_000));
+        assert_eq!(
+            parse_time_bound(" 2w ", now).ok(),
+            Some(now - 1_209_600_000)
+        );
+        assert_eq!(
+            parse_time_bound("2026-02-05T07:00:03Z", now).ok(),
+            Some(1_770_274_803_000)
+        );
+        assert_eq!(parse_time_bound("99999999999999999999d", now).ok(), None);
+        assert!(parse_time_bound("last tuesday", now).is_err());
+    }
 }
@@ -0,0 +1,220 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OpenFlags, params};
+use serde_json::Value;
+
+pub const STATE_DB_FILE_NAME: &str = "state.vscdb";
+pub const ITEM_TABLE: &str = "ItemTable";
+pub const CURSOR_DISK_KV_TABLE: &str = "cursorDiskKV";
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeySelector<'a> {
+    Exact(&'a str),
+    Prefix(&'a str),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateEntry {
+    pub table: String,
+    pub key: String,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StateEntries {
+    pub entries: Vec<StateEntry>,
+    pub warnings: Vec<String>,
+}
+
+#[must_use]
+pub fn has_sqlite_magic(sample: &[u8]) -> bool {
+    sample.starts_with(SQLITE_MAGIC)
+}
+
+pub fn open_state_db(path: &Path) -> Result<Connection> {
+    let connection = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .with_context(|| format!("failed to open editor state database read-only: {path:?}"))?;
+    connection
+        .pragma_update(None, "query_only", true)
+        .context("failed to enable query_only mode for editor state database")?;
+    Ok(connection)
+}
+
+pub fn table_exists(connection: &Connection, table: &str) -> Result<bool> {
+    connection
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1)",
+            params![table],
+            |row| row.get::<_, bool>(0),
+        )
+        .with_context(|| format!("failed to inspect editor state table `{table}`"))
+}
+
+pub fn read_json_entries(
+    connection: &Connection,
+    table: &str,
+    selectors: &[KeySelector<'_>],
+) -> Result<StateEntries> {
+    let mut result = StateEntries::default();
+    if !table_exists(connection, table)? {
+        return Ok(result);
+    }
+
+    let exact_sql = format!("SELECT key, value FROM \"{table}\" WHERE key = ?1 ORDER BY key");
+    let prefix_sql =
+        format!("SELECT key, value FROM \"{table}\" WHERE substr(key, 1, ?2) = ?1 ORDER BY key");
+
+    for selector in selectors {
+        let rows = match selector {
+            KeySelector::Exact(key) => {
+                let mut statement = connection
+                    .prepare_cached(&exact_sql)
+                    .with_context(|| format!("failed to query editor state table `{table}`"))?;
+                statement
+                    .query_map(params![key], read_key_value)?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            }
+            KeySelector::Prefix(prefix) => {
+                let mut statement = connection
+                    .prepare_cached(&prefix_sql)
+                    .with_context(|| format!("failed to query editor state table `{table}`"))?;
+                statement
+                    .query_map(
+                        params![prefix, prefix.chars().count() as i64],
+                        read_key_value,
+                    )?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            }
+        }
+        .with_context(|| format!("failed to read rows from editor state table `{table}`"))?;
+
+        for (key, raw) in rows {
+            let text = match raw {
+                SqlValue::Text(text) => text,
+                SqlValue::Blob(bytes) => match String::from_utf8(bytes) {
+                    Ok(text) => text,
+                    Err(_) => {
+                        result.warnings.push(format!(
+                            "table `{table}` key `{key}`: value is not UTF-8; skipped"
+                        ));
+                        continue;
+                    }
+                },
+                SqlValue::Null => continue,
+                _ => {
+                    result.warnings.push(format!(
+                        "table `{table}` key `{key}`: value is not text; skipped"
+                    ));
+                    continue;
+                }
+            };
+
+            match serde_json::from_str::<Value>(&text) {
+                Ok(value) => result.entries.push(StateEntry {
+                    table: table.to_string(),
+                    key,
+                    value,
+                }),
+                Err(error) => result.warnings.push(format!(
+                    "table `{table}` key `{key}`: invalid JSON value ({error}); skipped"
+                )),
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn read_key_value(row: &rusqlite::Row<'_>) -> rusqlite::Result<(String, SqlValue)> {
+    Ok((row.get(0)?, row.get(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::{Connection, params};
+    use serde_json::json;
+
+    use super::{
+        CURSOR_DISK_KV_TABLE, ITEM_TABLE, KeySelector, has_sqlite_magic, read_json_entries,
+    };
+
+    fn state_db() -> Connection {
+        let connection = Connection::open_in_memory().expect("in-memory sqlite should open");
+        connection
+            .execute_batch(
+                "CREATE TABLE ItemTable (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);
+                 CREATE TABLE cursorDiskKV (key TEXT UNIQUE ON CONFLICT REPLACE, value BLOB);",
+            )
+            .expect("state tables should be created");
+        connection
+    }
+
+    #[test]
+    fn reads_exact_and_prefixed_json_entries() {
+        let connection = state_db();
+        connection
+            .execute(
+                "INSERT INTO ItemTable (key, value) VALUES (?1, ?2)",
+                params!["interactive.sessions", r#"[{"sessionId":"s-1"}]"#],
+            )
+            .expect("item row should insert");
+        for (key, value) in [
+            ("composerData:b", br#"{"composerId":"b"}"#.to_vec()),
+            ("composerData:a", br#"{"composerId":"a"}"#.to_vec()),
+            ("composerDataX", br#"{}"#.to_vec()),
+            ("composerData:bad", b"{oops".to_vec()),
+        ] {
+            connection
+                .execute(
+                    "INSERT INTO cursorDiskKV (key, value) VALUES (?1, ?2)",
+                    params![key, value],
+                )
+                .expect("kv row should insert");
+        }
+
+        let items = read_json_entries(
+            &connection,
+            ITEM_TABLE,
+            &[KeySelector::Exact("interactive.sessions")],
+        )
+        .expect("item entries should read");
+        assert_eq!(items.entries.len(), 1);
+        assert_eq!(items.entries[0].value, json!([{"sessionId": "s-1"}]));
+
+        let composers = read_json_entries(
+            &connection,
+            CURSOR_DISK_KV_TABLE,
+            &[KeySelector::Prefix("composerData:")],
+        )
+        .expect("kv entries should read");
+        let keys = composers
+            .entries
+            .iter()
+            .map(|entry| entry.key.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(keys, vec!["composerData:a", "composerData:b"]);
+        assert_eq!(composers.warnings.len(), 1);
+        assert!(composers.warnings[0].contains("composerData:bad"));
+    }
+
+    #[test]
+    fn missing_tables_yield_no_entries() {
+        let connection = Connection::open_in_memory().expect("in-memory sqlite should open");
+        let entries = read_json_entries(
+            &connection,
+            CURSOR_DISK_KV_TABLE,
+            &[KeySelector::Prefix("composerData:")],
+        )
+        .expect("missing table should not error");
+        assert!(entries.entries.is_empty());
+        assert!(has_sqlite_magic(b"SQLite format 3\0rest"));
+        assert!(!has_sqlite_magic(b"{\"json\":true}"));
+    }
+}
@@ -7,6 +7,8 @@ use serde_json::Value;
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 
+use crate::utils::fs::write_atomic;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ValidationMode {
@@ -75,6 +77,8 @@ pub struct ValidationReport {
     pub quality_scorecard: ValidationQualityScorecard,
     pub per_agent_summary: BTreeMap<String, PerAgentValidationStats>,
     pub issues: Vec<ValidationIssue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
 }
 
 impl ValidationReport {
@@ -103,7 +107,7 @@ pub fn write_report_artifact(path: &Path, report: &ValidationReport) -> Result<(
 
     let encoded =
         serde_json::to_vec_pretty(report).context("failed to encode validation report json")?;
-    std::fs::write(path, encoded).context("failed to write validation report artifact")
+    write_atomic(path, &encoded).context("failed to write validation report artifact")
 }
 
 pub fn validate_jsonl_file(path: &Path, mode: ValidationMode) -> Result<ValidationReport> {
@@ -196,6 +200,7 @@ pub fn validate_jsonl_against_generated_schema(
         quality_scorecard,
         per_agent_summary,
         issues,
+        run_id: None,
     }
 }
 
@@ -263,6 +268,7 @@ fn invariant_catalog() -> &'static [InvariantCheck] {
         invariant_timestamp_consistency,
         invariant_hash_presence,
         invariant_content_presence,
+        invariant_source_attribution,
     ]
 }
 
@@ -356,6 +362,51 @@ fn invariant_content_presence(
     issues
 }
 
+/// A mismatch between `adapter_name` and `source_kind` is advisory (v1
+/// built-in adapters set both the same, custom specs may not), but a custom
+/// slug that is malformed or shadows a built-in adapter is an error because
+/// the mart rejects it.
+fn invariant_source_attribution(
+    records: &[(usize, crate::models::AgentLogEvent)],
+    _mode: ValidationMode,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    for (line, record) in records {
+        if record.source_kind != record.adapter_name {
+            issues.push(ValidationIssue {
+                line: *line,
+                kind: ValidationIssueKind::InvariantViolation,
+                severity: ValidationIssueSeverity::Warning,
+                detail: format!(
+                    "adapter_name `{}` must match source_kind `{}`",
+                    record.adapter_name.as_str(),
+                    record.source_kind.as_str()
+                ),
+            });
+        }
+
+        let mut attributions = vec![("source_kind", &record.source_kind)];
+        if record.adapter_name != record.source_kind {
+            attributions.push(("adapter_name", &record.adapter_name));
+        }
+        for (field, source) in attributions {
+            if let crate::models::AgentSource::Custom(name) = source
+                && !crate::models::AgentSource::is_valid_custom_name(name)
+            {
+                issues.push(ValidationIssue {
+                    line: *line,
+                    kind: ValidationIssueKind::InvariantViolation,
+                    severity: ValidationIssueSeverity::Error,
+                    detail: format!(
+                        "custom {field} `{name}` must be a lowercase adapter slug that does not shadow a built-in adapter"
+                    ),
+                });
+            }
+        }
+    }
+    issues
+}
+
 fn validate_timestamp_consistency(record: &crate::models::AgentLogEvent) -> Result<(), String> {
     let parsed = OffsetDateTime::parse(&record.timestamp_utc, &Rfc3339)
         .map_err(|error| format!("timestamp_utc is not RFC3339: {error}"))?;
@@ -522,12 +573,12 @@ fn build_per_agent_summary(
     let mut line_to_agent = BTreeMap::new();
 
     for (line, record) in records {
-        let key = source_kind_key(record.source_kind);
+        let key = record.source_kind.as_str();
         line_to_agent.insert(*line, key);
-        let entry = summary
-            .get_mut(key)
-            .expect("seeded per-agent summary must include all known adapters");
-        entry.records_validated += 1;
+        summary
+            .entry(key.to_string())
+            .or_default()
+            .records_validated += 1;
     }
 
     for issue in issues {
@@ -545,18 +596,10 @@ fn build_per_agent_summary(
 }
 
 fn seeded_per_agent_summary() -> BTreeMap<String, PerAgentValidationStats> {
-    ["codex", "claude", "gemini", "amp", "opencode", "unknown"]
-        .into_iter()
-        .map(|key| (key.to_string(), PerAgentValidationStats::default()))
-        .collect()
-}
-
-fn source_kind_key(source: crate::models::AgentSource) -> &'static str {
-    match source {
-        crate::models::AgentSource::Codex => "codex",
-        crate::models::AgentSource::Claude => "claude",
-        crate::models::AgentSource::Gemini => "gemini",
-        crate::models::AgentSource::Amp => "amp",
-        crate::models::AgentSource::OpenCode => "opencode",
-    }
+    [
+        "codex", "claude", "gemini", "amp", "opencode", "aider", "cursor", "copilot", "unknown",
+    ]
+    .into_iter()
+    .map(|key| (key.to_string(), PerAgentValidationStats::default()))
+    .collect()
 }
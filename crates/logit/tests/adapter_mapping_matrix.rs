@@ -1,4 +1,4 @@
-use logit::adapters::{amp, claude, codex, gemini, opencode};
+use logit::adapters::{aider, amp, claude, codex, copilot, cursor, gemini, opencode};
 use logit::models::{ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat};
 
 fn assert_core_event_contract(event: &AgentLogEvent, source: AgentSource, run_id: &str) {
@@ -12,7 +12,7 @@ fn assert_core_event_contract(event: &AgentLogEvent, source: AgentSource, run_id
     assert!(!event.canonical_hash.trim().is_empty());
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct CanonicalFixtureExpectation {
     fixture_path: &'static str,
     source: AgentSource,
@@ -65,6 +65,36 @@ const CANONICAL_EXPECTATIONS: &[CanonicalFixtureExpectation] = &[
         expected_first_session_id: None,
         expected_first_conversation_id: None,
     },
+    CanonicalFixtureExpectation {
+        fixture_path: "fixtures/aider/input.history",
+        source: AgentSource::Aider,
+        expected_event_count: 3,
+        expected_first_record_format: RecordFormat::Message,
+        expected_first_event_type: EventType::Prompt,
+        expected_first_role: ActorRole::User,
+        expected_first_session_id: None,
+        expected_first_conversation_id: None,
+    },
+    CanonicalFixtureExpectation {
+        fixture_path: "fixtures/cursor/state.vscdb",
+        source: AgentSource::Cursor,
+        expected_event_count: 9,
+        expected_first_record_format: RecordFormat::Message,
+        expected_first_event_type: EventType::Prompt,
+        expected_first_role: ActorRole::User,
+        expected_first_session_id: Some("tab-alpha"),
+        expected_first_conversation_id: Some("tab-alpha"),
+    },
+    CanonicalFixtureExpectation {
+        fixture_path: "fixtures/copilot/chatSessions/session.json",
+        source: AgentSource::Copilot,
+        expected_event_count: 2,
+        expected_first_record_format: RecordFormat::Message,
+        expected_first_event_type: EventType::Prompt,
+        expected_first_role: ActorRole::User,
+        expected_first_session_id: Some("sess-copilot-2"),
+        expected_first_conversation_id: Some("sess-copilot-2"),
+    },
 ];
 
 #[test]
@@ -82,7 +112,7 @@ fn adapter_mapping_matrix_matches_fixture_expectation_table() {
         let first = events
             .first()
             .expect("fixture expectation table requires at least one event");
-        assert_core_event_contract(first, expectation.source, run_id);
+        assert_core_event_contract(first, expectation.source.clone(), run_id);
         assert_eq!(
             first.record_format, expectation.expected_first_record_format,
             "{} first-event record_format mismatch",
@@ -165,6 +195,31 @@ fn parse_fixture_events(
             )
             .events
         }
-        AgentSource::Amp => Vec::new(),
+        AgentSource::Aider => {
+            aider::parse_input_history_text(
+                include_str!("../../../fixtures/aider/input.history"),
+                run_id,
+                expectation.fixture_path,
+            )
+            .events
+        }
+        AgentSource::Cursor => {
+            let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../..")
+                .join(expectation.fixture_path);
+            cursor::parse_state_db_file(&path, run_id)
+                .expect("cursor fixture should parse")
+                .events
+        }
+        AgentSource::Copilot => {
+            copilot::parse_chat_session_json(
+                include_str!("../../../fixtures/copilot/chatSessions/session.json"),
+                run_id,
+                expectation.fixture_path,
+            )
+            .expect("copilot fixture should parse")
+            .events
+        }
+        AgentSource::Amp | AgentSource::Custom(_) => Vec::new(),
     }
 }
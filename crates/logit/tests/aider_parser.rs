@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::adapters::aider::{
+    CHAT_HISTORY_FILE_NAME, INPUT_HISTORY_FILE_NAME, LLM_HISTORY_FILE_NAME,
+    find_repo_history_files, history_files_named, parse_chat_history_markdown,
+    parse_input_history_text, parse_llm_history_text,
+};
+use logit::models::{ActorRole, AgentSource, EventType, RecordFormat, TimestampQuality};
+use logit::normalize::{default_plan, orchestrate_normalization};
+
+const CHAT_FIXTURE: &str = include_str!("../../../fixtures/aider/chat_history.md");
+const INPUT_FIXTURE: &str = include_str!("../../../fixtures/aider/input.history");
+const LLM_FIXTURE: &str = include_str!("../../../fixtures/aider/llm.history");
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+#[test]
+fn parses_chat_history_sessions_turns_and_tool_output() {
+    let result = parse_chat_history_markdown(
+        CHAT_FIXTURE,
+        "run-aider",
+        "/repo/app/.aider.chat.history.md",
+    );
+    assert!(
+        result.warnings.is_empty(),
+        "warnings: {:?}",
+        result.warnings
+    );
+    assert_eq!(result.events.len(), 11);
+    assert!(
+        result
+            .events
+            .iter()
+            .all(|event| event.source_kind == AgentSource::Aider)
+    );
+
+    let session_start = &result.events[0];
+    assert_eq!(session_start.event_type, EventType::SystemNotice);
+    assert_eq!(session_start.timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(session_start.timestamp_utc, "2026-02-10T09:00:00.000Z");
+    assert_eq!(
+        session_start.metadata.get("aider_repo_root"),
+        Some(&serde_json::json!("/repo/app"))
+    );
+
+    let banner = &result.events[1];
+    assert_eq!(banner.record_format, RecordFormat::System);
+    assert_eq!(banner.model.as_deref(), Some("claude-3-7-sonnet-20250219"));
+    assert_eq!(
+        banner.metadata.get("aider_version"),
+        Some(&serde_json::json!("0.82.1"))
+    );
+
+    let prompt = &result.events[2];
+    assert_eq!(prompt.event_type, EventType::Prompt);
+    assert_eq!(prompt.role, ActorRole::User);
+    assert_eq!(prompt.timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(prompt.timestamp_utc, "2026-02-10T09:00:00.002Z");
+    assert_eq!(
+        prompt.content_text.as_deref(),
+        Some("add a greet function to hello.py\nit should take a name argument")
+    );
+    assert_eq!(prompt.session_id, session_start.session_id);
+
+    let response = &result.events[3];
+    assert_eq!(response.event_type, EventType::Response);
+    assert_eq!(
+        response.model.as_deref(),
+        Some("claude-3-7-sonnet-20250219")
+    );
+    assert!(
+        response
+            .content_text
+            .as_deref()
+            .is_some_and(|text| text.contains("#### not a user turn inside a fence"))
+    );
+
+    let applied = &result.events[4];
+    assert_eq!(applied.record_format, RecordFormat::ToolResult);
+    assert_eq!(applied.tool_name, None);
+    assert_eq!(
+        applied.tool_result_text.as_deref(),
+        Some("Applied edit to hello.py\nCommit 1a2b3c4 feat: add greet function")
+    );
+
+    let command = &result.events[5];
+    assert_eq!(command.record_format, RecordFormat::ToolCall);
+    assert_eq!(command.event_type, EventType::ToolInvocation);
+    assert_eq!(command.tool_name.as_deref(), Some("run"));
+    assert_eq!(
+        command.tool_call_id.as_deref(),
+        Some(command.event_id.as_str())
+    );
+    assert_eq!(
+        command.tool_arguments_json.as_deref(),
+        Some(r#"{"command":"/run pytest -q"}"#)
+    );
+
+    let command_output = &result.events[6];
+    assert_eq!(command_output.event_type, EventType::ToolOutput);
+    assert_eq!(command_output.role, ActorRole::Tool);
+    assert_eq!(command_output.tool_name.as_deref(), Some("run"));
+    assert_eq!(command_output.tool_call_id, command.tool_call_id);
+
+    let second_session = &result.events[7];
+    assert_eq!(second_session.timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(second_session.timestamp_utc, "2026-02-10T10:15:30.000Z");
+    assert_ne!(second_session.session_id, session_start.session_id);
+    assert_eq!(result.events[10].model.as_deref(), Some("gpt-4o"));
+    assert_eq!(result.events[10].timestamp_utc, "2026-02-10T10:15:30.003Z");
+}
+
+#[test]
+fn headerless_transcript_uses_fallback_timestamps() {
+    let result = parse_chat_history_markdown(
+        "#### hello\n\nhi there\n",
+        "run-aider",
+        ".aider.chat.history.md",
+    );
+    assert_eq!(result.events.len(), 2);
+    assert!(
+        result
+            .events
+            .iter()
+            .all(|event| event.timestamp_quality == TimestampQuality::Fallback)
+    );
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("before any `# aider chat started at` header"));
+}
+
+#[test]
+fn parses_input_and_llm_history_with_header_timestamps() {
+    let input = parse_input_history_text(INPUT_FIXTURE, "run-aider", ".aider.input.history");
+    assert!(input.warnings.is_empty(), "warnings: {:?}", input.warnings);
+    assert_eq!(input.events.len(), 3);
+    assert_eq!(input.events[0].timestamp_utc, "2026-02-10T09:00:05.120Z");
+    assert_eq!(input.events[0].timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(
+        input.events[0].content_text.as_deref(),
+        Some("add a greet function to hello.py\nit should take a name argument")
+    );
+    assert_eq!(
+        input.events[1].content_text.as_deref(),
+        Some("/run pytest -q")
+    );
+
+    let llm = parse_llm_history_text(LLM_FIXTURE, "run-aider", ".aider.llm.history");
+    assert!(llm.warnings.is_empty(), "warnings: {:?}", llm.warnings);
+    assert_eq!(llm.events.len(), 2);
+    assert_eq!(llm.events[0].event_type, EventType::Prompt);
+    assert_eq!(llm.events[0].timestamp_utc, "2026-02-10T09:00:06.000Z");
+    assert_eq!(
+        llm.events[0].content_text.as_deref(),
+        Some("add a greet function to hello.py\nit should take a name argument")
+    );
+    assert_eq!(
+        llm.events[0].metadata.get("aider_llm_message_count"),
+        Some(&serde_json::json!(2))
+    );
+    assert_eq!(llm.events[1].event_type, EventType::Response);
+    assert_eq!(
+        llm.events[1].content_text.as_deref(),
+        Some("I'll add the function.\n\nhello.py")
+    );
+}
+
+#[test]
+fn repo_scan_finds_history_files_below_source_root() {
+    let root = unique_temp_dir("logit-aider-scan");
+    write_file(
+        &root.join("work/app").join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+    write_file(
+        &root.join("work/lib").join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+    write_file(
+        &root
+            .join("work/app/node_modules/pkg")
+            .join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+    write_file(
+        &root.join(".cache/app").join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+    write_file(
+        &root.join("a/b/c/d/e").join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+
+    write_file(
+        &root.join("work/app").join(INPUT_HISTORY_FILE_NAME),
+        INPUT_FIXTURE,
+    );
+    write_file(&root.join("work/app/notes.md"), "# not aider\n");
+
+    let found = find_repo_history_files(&root).expect("scan should work");
+    assert_eq!(
+        found,
+        vec![
+            root.join("work/app").join(CHAT_HISTORY_FILE_NAME),
+            root.join("work/app").join(INPUT_HISTORY_FILE_NAME),
+            root.join("work/lib").join(CHAT_HISTORY_FILE_NAME),
+        ]
+    );
+    assert_eq!(
+        history_files_named(&found, INPUT_HISTORY_FILE_NAME),
+        vec![root.join("work/app").join(INPUT_HISTORY_FILE_NAME)]
+    );
+}
+
+#[test]
+fn header_timestamps_are_exact_only_when_they_carry_an_offset() {
+    let result = parse_chat_history_markdown(
+        "# aider chat started at 2026-02-10 09:00:00+02:00\n\n#### hello\n",
+        "run-aider",
+        ".aider.chat.history.md",
+    );
+    assert_eq!(result.events[0].timestamp_quality, TimestampQuality::Exact);
+    assert_eq!(result.events[0].timestamp_utc, "2026-02-10T07:00:00.000Z");
+
+    let input = parse_input_history_text(
+        "# 2026-02-10 09:00:05.120000\n+hello\n",
+        "run-aider",
+        ".aider.input.history",
+    );
+    assert_eq!(input.events[0].timestamp_quality, TimestampQuality::Derived);
+}
+
+#[test]
+fn orchestrator_scans_repo_scan_root_instead_of_home_without_source_root() {
+    let home = unique_temp_dir("logit-aider-home");
+    write_file(
+        &home.join("elsewhere/app").join(CHAT_HISTORY_FILE_NAME),
+        CHAT_FIXTURE,
+    );
+    let cwd = unique_temp_dir("logit-aider-cwd");
+    write_file(&cwd.join(INPUT_HISTORY_FILE_NAME), INPUT_FIXTURE);
+
+    let mut plan = default_plan();
+    plan.adapters = vec![AdapterKind::Aider];
+    let result =
+        orchestrate_normalization(&plan, &home, None, "").expect("orchestrator should succeed");
+    assert!(result.events.is_empty());
+
+    plan.repo_scan_root = Some(cwd.clone());
+    let result =
+        orchestrate_normalization(&plan, &home, None, "").expect("orchestrator should succeed");
+    assert_eq!(result.events.len(), 3);
+    assert!(
+        result
+            .events
+            .iter()
+            .all(|event| event.source_path.starts_with(&cwd.display().to_string()))
+    );
+}
+
+#[test]
+fn orchestrator_normalizes_aider_repo_histories() {
+    let source_root = unique_temp_dir("logit-aider-normalize");
+    let repo = source_root.join("projects/app");
+    write_file(&repo.join(CHAT_HISTORY_FILE_NAME), CHAT_FIXTURE);
+    write_file(&repo.join(INPUT_HISTORY_FILE_NAME), INPUT_FIXTURE);
+    write_file(&repo.join(LLM_HISTORY_FILE_NAME), LLM_FIXTURE);
+
+    let mut plan = default_plan();
+    plan.adapters = vec![AdapterKind::Aider];
+    let result = orchestrate_normalization(&plan, Path::new("/tmp/home"), Some(&source_root), "")
+        .expect("orchestrator should succeed");
+
+    let health = result
+        .adapter_health
+        .get("aider")
+        .expect("aider health should be reported");
+    assert_eq!(health.files_parsed, 3);
+    let count_tagged = |tag: &str| {
+        result
+            .events
+            .iter()
+            .filter(|event| event.tags.iter().any(|value| value == tag))
+            .count()
+    };
+    assert_eq!(count_tagged("chat_history"), 11);
+    assert_eq!(count_tagged("input_history"), 3);
+    assert_eq!(count_tagged("llm_history"), 2);
+}
@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::audit::{
+    AuditBaseline, HIGH_ENTROPY_CLASS, build_report, find_high_entropy_spans, read_events_jsonl,
+    scan_events,
+};
+use logit::cli::commands::audit::{AuditSecretsArgs, audit_secrets};
+use logit::config::RuntimePaths;
+use logit::utils::redaction::{PseudonymKey, RedactionPolicy, RedactionRules};
+use serde_json::{Value, json};
+
+const API_KEY: &str = "sk-live0123456789abcdef";
+const ENTROPY_SECRET: &str = "wJalrXUtnFEMIK7MDENGbPxRfiCY8zQ2";
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn event_row(index: usize, event_type: &str, fields: Value) -> String {
+    let mut row = json!({
+        "schema_version": "agentlog.v1",
+        "event_id": format!("evt-{index}"),
+        "run_id": "run-1",
+        "sequence_global": index,
+        "source_kind": "claude",
+        "source_path": "/home/u/.claude/projects/demo/session.jsonl",
+        "source_record_locator": format!("line:{index}"),
+        "adapter_name": "claude",
+        "record_format": if event_type == "tool_output" { "tool_result" } else { "message" },
+        "event_type": event_type,
+        "role": if event_type == "tool_output" { "tool" } else { "user" },
+        "timestamp_utc": "2026-02-25T00:00:00Z",
+        "timestamp_unix_ms": 1_771_977_600_000_u64 + index as u64,
+        "timestamp_quality": "exact",
+        "session_id": "session-demo",
+        "raw_hash": format!("raw-{index}"),
+        "canonical_hash": format!("canonical-{index}")
+    });
+    if let (Value::Object(row), Value::Object(fields)) = (&mut row, fields) {
+        row.extend(fields);
+    }
+    row.to_string()
+}
+
+fn write_events(path: &Path) {
+    let rows = [
+        event_row(
+            1,
+            "prompt",
+            json!({ "content_text": format!("use {API_KEY} for the deploy") }),
+        ),
+        event_row(
+            2,
+            "tool_output",
+            json!({ "tool_result_text": format!("export AWS_SECRET={ENTROPY_SECRET}\nok") }),
+        ),
+        event_row(
+            3,
+            "prompt",
+            json!({ "content_text": "commit 4f9a1c0b7d2e4f9a1c0b7d2e4f9a1c0b7d2e4f9a and ada@example.com" }),
+        ),
+    ];
+    write_file(path, &format!("{}\n", rows.join("\n")));
+}
+
+#[test]
+fn entropy_detector_flags_random_tokens_but_not_hashes_or_paths() {
+    let text = format!(
+        "key {ENTROPY_SECRET} sha 4f9a1c0b7d2e4f9a1c0b7d2e4f9a1c0b7d2e4f9a \
+         uuid 123e4567-e89b-12d3-a456-426614174000 path /usr/lib/x86_64-Linux-gnu2 \
+         word SupercalifragilisticExpialidocious"
+    );
+    let spans = find_high_entropy_spans(&text);
+    assert_eq!(spans.len(), 1);
+    assert_eq!(&text[spans[0].0..spans[0].1], ENTROPY_SECRET);
+}
+
+#[test]
+fn scan_reports_keyed_findings_with_salted_fingerprints() {
+    let events_path = unique_temp_dir("logit-audit-scan").join("events.jsonl");
+    write_events(&events_path);
+    let (events, warnings) = read_events_jsonl(&events_path).expect("events should parse");
+    assert!(warnings.is_empty());
+
+    let salt = PseudonymKey::from_bytes(&[9; 32]).expect("salt should be valid");
+    let findings = scan_events(
+        &events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+        &salt,
+    );
+    assert_eq!(findings.len(), 2);
+
+    let api = &findings[0];
+    assert_eq!(api.redaction_class, "api_token");
+    assert_eq!(api.field, "content_text");
+    assert_eq!(api.event_type, "prompt");
+    assert_eq!(api.session_id.as_deref(), Some("session-demo"));
+    assert_eq!(api.source_record_locator, "line:1");
+    assert_eq!((api.line, api.column, api.length), (1, 5, API_KEY.len()));
+    assert_eq!(api.fingerprint.len(), 16);
+
+    let entropy = &findings[1];
+    assert_eq!(entropy.redaction_class, HIGH_ENTROPY_CLASS);
+    assert_eq!(entropy.field, "tool_result_text");
+    assert_eq!(entropy.event_type, "tool_output");
+
+    let encoded = serde_json::to_string(&findings).expect("findings should encode");
+    assert!(!encoded.contains(API_KEY));
+    assert!(!encoded.contains(ENTROPY_SECRET));
+
+    let resalted = scan_events(
+        &events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+        &PseudonymKey::from_bytes(&[10; 32]).expect("salt should be valid"),
+    );
+    assert_ne!(resalted[0].fingerprint, api.fingerprint);
+
+    let with_pii = scan_events(
+        &events,
+        RedactionPolicy::SecretsAndPii,
+        &RedactionRules::default(),
+        &salt,
+    );
+    assert!(
+        with_pii
+            .iter()
+            .any(|finding| finding.redaction_class == "email")
+    );
+}
+
+#[test]
+fn baseline_suppresses_acknowledged_findings_before_threshold() {
+    let events_path = unique_temp_dir("logit-audit-baseline").join("events.jsonl");
+    write_events(&events_path);
+    let (events, _) = read_events_jsonl(&events_path).expect("events should parse");
+    let salt = PseudonymKey::from_bytes(&[1; 32]).expect("salt should be valid");
+    let findings = scan_events(
+        &events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+        &salt,
+    );
+
+    let report = build_report(
+        "events",
+        RedactionPolicy::SecretsOnly,
+        events.len(),
+        findings.clone(),
+        &AuditBaseline::default(),
+        1,
+        Vec::new(),
+    );
+    assert_eq!(report.findings_active, 2);
+    assert!(report.threshold_exceeded);
+
+    let baseline = AuditBaseline {
+        fingerprints: [findings[1].fingerprint.clone()].into(),
+        ..AuditBaseline::default()
+    };
+    let report = build_report(
+        "events",
+        RedactionPolicy::SecretsOnly,
+        events.len(),
+        findings,
+        &baseline,
+        1,
+        Vec::new(),
+    );
+    assert_eq!(report.findings_total, 2);
+    assert_eq!(report.findings_suppressed, 1);
+    assert_eq!(report.findings_active, 1);
+    assert!(!report.threshold_exceeded);
+    assert_eq!(report.class_counts.get("api_token"), Some(&1));
+}
+
+#[test]
+fn audit_scans_discovered_sources_without_redaction() {
+    let home = unique_temp_dir("logit-audit-discovered-home");
+    write_file(
+        &home.join(".logit/adapters/scripts.toml"),
+        "name = \"scripts\"\npaths = [\"~/transcripts\"]\nfile_globs = [\"*.jsonl\"]\npreset = \"openai-messages\"\n",
+    );
+    write_file(
+        &home.join("transcripts/deploy.jsonl"),
+        &format!(
+            "{}\n",
+            json!({ "role": "user", "content": format!("token: {API_KEY}") })
+        ),
+    );
+    let runtime_paths = RuntimePaths {
+        home_dir: home.clone(),
+        cwd: home.clone(),
+        out_dir: home.join("out"),
+    };
+    let args = AuditSecretsArgs {
+        events: None,
+        source_root: None,
+        redaction: RedactionPolicy::SecretsOnly,
+        max_findings: 0,
+        baseline: None,
+        write_baseline: None,
+        salt_file: None,
+    };
+
+    let report = audit_secrets(&args, &runtime_paths).expect("audit should run");
+    assert_eq!(report.input, "discovered");
+    let finding = report
+        .findings
+        .iter()
+        .find(|finding| finding.source_kind == "scripts")
+        .expect("custom adapter source should be scanned");
+    assert_eq!(finding.redaction_class, "api_token");
+    assert!(finding.source_path.ends_with("transcripts/deploy.jsonl"));
+    assert!(home.join("out/audit/fingerprint.salt").is_file());
+}
+
+#[test]
+fn audit_command_exits_non_zero_over_threshold_and_honors_written_baseline() {
+    let temp = unique_temp_dir("logit-audit-cli");
+    let home_dir = temp.join("home");
+    let out_dir = temp.join("out");
+    std::fs::create_dir_all(&home_dir).expect("home dir should be creatable");
+    let events_path = temp.join("events.jsonl");
+    write_events(&events_path);
+    let baseline_path = temp.join("audit-baseline.json");
+
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_logit"))
+            .arg("--home-dir")
+            .arg(&home_dir)
+            .arg("--cwd")
+            .arg(&temp)
+            .arg("--out-dir")
+            .arg(&out_dir)
+            .args(["audit", "secrets", "--events"])
+            .arg(&events_path)
+            .args(extra)
+            .output()
+            .expect("command should execute")
+    };
+
+    let output = run(&[]);
+    assert_eq!(output.status.code(), Some(2));
+    let envelope: Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    assert_eq!(envelope["ok"], false);
+    assert_eq!(envelope["error"]["code"], "audit_findings_over_threshold");
+    assert_eq!(envelope["data"]["findings_active"], 2);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains(API_KEY));
+
+    let output = run(&["--max-findings", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    let baseline = baseline_path.to_string_lossy().to_string();
+    let output = run(&["--write-baseline", &baseline]);
+    assert_eq!(output.status.code(), Some(0));
+    let output = run(&["--baseline", &baseline]);
+    assert_eq!(output.status.code(), Some(0));
+    let envelope: Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    assert_eq!(envelope["ok"], true);
+    assert_eq!(envelope["data"]["findings_suppressed"], 2);
+    assert!(out_dir.join("audit/secrets_report.json").is_file());
+}
@@ -844,7 +844,7 @@ fn query_benchmark_emits_per_question_results_and_artifact() {
         envelope
             .pointer("/data/preflight/semantic_concept_count")
             .and_then(Value::as_u64),
-        Some(4)
+        Some(5)
     );
     let questions = envelope
         .pointer("/data/questions")
@@ -1,9 +1,21 @@
 use std::path::Path;
 
 use clap::Parser;
+use logit::adapters::AdapterKind;
 use logit::cli::app::{Cli, Command};
+use logit::cli::commands::audit::AuditCommand;
+use logit::cli::commands::config::ConfigCommand;
+use logit::cli::commands::export::ExportCommand;
 use logit::cli::commands::ingest::IngestCommand;
+use logit::cli::commands::mart::MartCommand;
 use logit::cli::commands::query::QueryCommand;
+use logit::cli::commands::redact::RedactCommand;
+use logit::cli::commands::show::ShowCommand;
+use logit::discovery::SourceFormatHint;
+use logit::pipeline::RunGate;
+use logit::purge::PurgeSelector;
+use logit::transcript::{TranscriptFormat, TurnRange};
+use logit::utils::redaction::RedactionPolicy;
 
 #[test]
 fn parses_global_runtime_flags_for_snapshot() {
@@ -129,6 +141,10 @@ fn parses_ingest_refresh_with_global_runtime_flags() {
             );
             assert!(refresh.fail_fast);
         }
+        other => assert!(
+            matches!(other, IngestCommand::Refresh(_)),
+            "expected ingest refresh"
+        ),
     }
 }
 
@@ -168,6 +184,16 @@ fn parses_query_namespace_subcommands() {
         }
     }
 
+    let cli = Cli::parse_from(["logit", "query", "search", "database locked", "--raw"]);
+    let Command::Query(args) = cli.command else {
+        return;
+    };
+    assert!(
+        matches!(&args.command, QueryCommand::Search(search) if search.terms == "database locked" && search.raw && search.row_cap == 50),
+        "expected query search command, got {:?}",
+        args.command
+    );
+
     let cli = Cli::parse_from(["logit", "query", "schema", "--include-internal"]);
     assert!(
         matches!(&cli.command, Command::Query(_)),
@@ -223,3 +249,471 @@ fn parses_query_namespace_subcommands() {
         }
     }
 }
+
+#[test]
+fn parses_redact_test_subcommand() {
+    let cli = Cli::parse_from([
+        "logit",
+        "redact",
+        "test",
+        "token=abc12345",
+        "--redaction",
+        "secrets+pii",
+        "--rules",
+        "/tmp/redaction.toml",
+    ]);
+    assert!(
+        matches!(&cli.command, Command::Redact(_)),
+        "expected redact command, got {:?}",
+        cli.command
+    );
+    let Command::Redact(args) = cli.command else {
+        return;
+    };
+    let RedactCommand::Test(test_args) = args.command;
+    assert_eq!(test_args.input, "token=abc12345");
+    assert_eq!(test_args.redaction, RedactionPolicy::SecretsAndPii);
+    assert_eq!(
+        test_args.rules.as_deref(),
+        Some(Path::new("/tmp/redaction.toml"))
+    );
+    assert!(!test_args.json);
+}
+
+#[test]
+fn parses_audit_secrets_subcommand() {
+    let cli = Cli::parse_from([
+        "logit",
+        "audit",
+        "secrets",
+        "--events",
+        "/tmp/events.jsonl",
+        "--max-findings",
+        "3",
+        "--baseline",
+        "/tmp/audit-baseline.json",
+    ]);
+    assert!(
+        matches!(&cli.command, Command::Audit(_)),
+        "expected audit command, got {:?}",
+        cli.command
+    );
+    let Command::Audit(args) = cli.command else {
+        return;
+    };
+    let AuditCommand::Secrets(secrets_args) = args.command;
+    assert_eq!(
+        secrets_args.events.as_deref(),
+        Some(Path::new("/tmp/events.jsonl"))
+    );
+    assert_eq!(secrets_args.redaction, RedactionPolicy::SecretsOnly);
+    assert_eq!(secrets_args.max_findings, 3);
+    assert_eq!(
+        secrets_args.baseline.as_deref(),
+        Some(Path::new("/tmp/audit-baseline.json"))
+    );
+    assert!(secrets_args.write_baseline.is_none());
+}
+
+#[test]
+fn parses_show_session_subcommand() {
+    let cli = Cli::parse_from([
+        "logit",
+        "show",
+        "session",
+        "session-1",
+        "--turns",
+        "2..4",
+        "--format",
+        "html",
+        "--mart",
+        "/tmp/mart.sqlite",
+    ]);
+    assert!(
+        matches!(&cli.command, Command::Show(_)),
+        "expected show command, got {:?}",
+        cli.command
+    );
+    let Command::Show(args) = cli.command else {
+        return;
+    };
+    let ShowCommand::Session(session_args) = args.command;
+    assert_eq!(session_args.session_id, "session-1");
+    assert_eq!(
+        session_args.turns,
+        Some(TurnRange {
+            start: 2,
+            end: Some(4)
+        })
+    );
+    assert_eq!(session_args.format, TranscriptFormat::Html);
+    assert_eq!(
+        session_args.mart.as_deref(),
+        Some(Path::new("/tmp/mart.sqlite"))
+    );
+    assert!(session_args.events.is_none());
+    assert!(!session_args.full);
+
+    let conflicting = Cli::try_parse_from([
+        "logit",
+        "show",
+        "session",
+        "session-1",
+        "--events",
+        "/tmp/events.jsonl",
+        "--mart",
+        "/tmp/mart.sqlite",
+    ]);
+    assert!(conflicting.is_err());
+}
+
+#[test]
+fn parses_serve_flags_with_default_port() {
+    let cli = Cli::parse_from(["logit", "serve", "--token-file", "/tmp/serve.token"]);
+    let Command::Serve(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Serve(_)),
+            "expected serve command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert_eq!(args.port, logit::serve::DEFAULT_PORT);
+    assert_eq!(
+        args.token_file.as_deref(),
+        Some(Path::new("/tmp/serve.token"))
+    );
+    assert!(args.mart.is_none());
+    assert!(args.request_log.is_none());
+}
+
+#[test]
+fn parses_tui_mart_and_report_flags() {
+    let cli = Cli::parse_from([
+        "logit",
+        "tui",
+        "--mart",
+        "/tmp/mart.sqlite",
+        "--validate-report",
+        "/tmp/report.json",
+    ]);
+    let Command::Tui(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Tui(_)),
+            "expected tui command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert_eq!(args.mart.as_deref(), Some(Path::new("/tmp/mart.sqlite")));
+    assert_eq!(
+        args.validate_report.as_deref(),
+        Some(Path::new("/tmp/report.json"))
+    );
+}
+
+#[test]
+fn parses_mcp_mart_flag() {
+    let cli = Cli::parse_from(["logit", "mcp", "--mart", "/tmp/mart.sqlite"]);
+    let Command::Mcp(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Mcp(_)),
+            "expected mcp command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert_eq!(args.mart.as_deref(), Some(Path::new("/tmp/mart.sqlite")));
+}
+
+#[test]
+fn parses_export_otlp_flags() {
+    let cli = Cli::parse_from([
+        "logit",
+        "export",
+        "otlp",
+        "--session",
+        "session-a",
+        "--session",
+        "session-b",
+        "--endpoint",
+        "http://127.0.0.1:4318",
+        "--include-content",
+    ]);
+    let Command::Export(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Export(_)),
+            "expected export command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    let ExportCommand::Otlp(otlp) = args.command;
+    assert_eq!(otlp.sessions, ["session-a", "session-b"]);
+    assert_eq!(
+        otlp.endpoint.map(|endpoint| endpoint.url()).as_deref(),
+        Some("http://127.0.0.1:4318/v1/traces")
+    );
+    assert!(otlp.include_content);
+    assert!(otlp.output_dir.is_none());
+}
+
+#[test]
+fn parses_source_filter_flags_on_normalize() {
+    let cli = Cli::parse_from([
+        "logit",
+        "normalize",
+        "--adapter",
+        "codex",
+        "--adapter",
+        "claude",
+        "--format",
+        "jsonl",
+        "--path-contains",
+        "sessions",
+        "--since",
+        "2026-01-01T00:00:00Z",
+        "--until",
+        "1767312000000",
+    ]);
+
+    let Command::Normalize(args) = cli.command else {
+        assert!(matches!(cli.command, Command::Normalize(_)));
+        return;
+    };
+    assert_eq!(args.filters.adapters, vec!["codex", "claude"]);
+    assert_eq!(args.filters.formats, vec![SourceFormatHint::Jsonl]);
+    assert_eq!(args.filters.path_substrings, vec!["sessions"]);
+    assert_eq!(args.filters.since, Some(1_767_225_600_000));
+    assert_eq!(args.filters.until, Some(1_767_312_000_000));
+}
+
+#[test]
+fn parses_relative_since_on_ingest_refresh() {
+    let cli = Cli::parse_from(["logit", "ingest", "refresh", "--since", "7d"]);
+
+    let Command::Ingest(args) = cli.command else {
+        assert!(matches!(cli.command, Command::Ingest(_)));
+        return;
+    };
+    let IngestCommand::Refresh(refresh) = args.command else {
+        assert!(matches!(args.command, IngestCommand::Refresh(_)));
+        return;
+    };
+    assert!(refresh.filters.since.is_some());
+    assert!(refresh.filters.until.is_none());
+}
+
+#[test]
+fn parses_ingest_import_path_and_host() {
+    let cli = Cli::parse_from([
+        "logit",
+        "ingest",
+        "import",
+        "exports/laptop-events.jsonl",
+        "--host",
+        "laptop",
+    ]);
+
+    let Command::Ingest(args) = cli.command else {
+        assert!(matches!(cli.command, Command::Ingest(_)));
+        return;
+    };
+    let IngestCommand::Import(import) = args.command else {
+        assert!(matches!(args.command, IngestCommand::Import(_)));
+        return;
+    };
+    assert_eq!(import.path, Path::new("exports/laptop-events.jsonl"));
+    assert_eq!(import.host.as_deref(), Some("laptop"));
+}
+
+#[test]
+fn ingest_import_requires_a_path() {
+    let result = Cli::try_parse_from(["logit", "ingest", "import"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn parses_normalize_positional_inputs_and_input_adapter() {
+    let cli = Cli::parse_from([
+        "logit",
+        "normalize",
+        "shared/rollout.jsonl",
+        "/tmp/colleague-gemini",
+        "--input-adapter",
+        "gemini",
+    ]);
+
+    let Command::Normalize(args) = cli.command else {
+        assert!(matches!(cli.command, Command::Normalize(_)));
+        return;
+    };
+    assert_eq!(
+        args.inputs,
+        vec![
+            Path::new("shared/rollout.jsonl").to_path_buf(),
+            Path::new("/tmp/colleague-gemini").to_path_buf()
+        ]
+    );
+    assert_eq!(args.input_adapter, Some(AdapterKind::Gemini));
+}
+
+#[test]
+fn rejects_input_adapter_without_inputs() {
+    let result = Cli::try_parse_from(["logit", "normalize", "--input-adapter", "codex"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn parses_config_show_and_schema_subcommands() {
+    let cli = Cli::parse_from(["logit", "config", "show", "--json"]);
+    assert!(matches!(
+        &cli.command,
+        Command::Config(args) if matches!(&args.command, ConfigCommand::Show(show) if show.json)
+    ));
+
+    let cli = Cli::parse_from(["logit", "config", "schema", "--output", "schema.json"]);
+    let Command::Config(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Config(_)),
+            "expected config command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert!(matches!(
+        &args.command,
+        ConfigCommand::Schema(schema) if schema.output.as_deref() == Some(Path::new("schema.json"))
+    ));
+}
+
+#[test]
+fn parses_run_gates_and_rejects_gate_with_no_gates() {
+    let cli = Cli::parse_from([
+        "logit",
+        "run",
+        "--gate",
+        "validation-warnings",
+        "--gate",
+        "adapter-partial-failure",
+        "--adapter",
+        "codex",
+    ]);
+    let Command::Run(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Run(_)),
+            "expected run command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert_eq!(
+        args.gates,
+        [RunGate::ValidationWarnings, RunGate::AdapterPartialFailure]
+    );
+    assert_eq!(args.filters.adapters, ["codex"]);
+    assert!(!args.no_gates);
+
+    let conflicting =
+        Cli::try_parse_from(["logit", "run", "--gate", "validation-errors", "--no-gates"]);
+    assert!(conflicting.is_err());
+    assert!(Cli::try_parse_from(["logit", "run", "--gate", "bogus"]).is_err());
+}
+
+#[test]
+fn parses_doctor_adapter_and_json_flags() {
+    let cli = Cli::parse_from([
+        "logit",
+        "doctor",
+        "--adapter",
+        "claude",
+        "--source-root",
+        "/tmp/src",
+        "--json",
+    ]);
+    let Command::Doctor(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Doctor(_)),
+            "expected doctor command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert_eq!(args.adapters, [AdapterKind::Claude]);
+    assert_eq!(args.source_root.as_deref(), Some(Path::new("/tmp/src")));
+    assert!(args.json);
+    assert!(Cli::try_parse_from(["logit", "doctor", "--adapter", "bogus"]).is_err());
+}
+
+#[test]
+fn parses_mart_migrate_dry_run() {
+    let cli = Cli::parse_from(["logit", "mart", "migrate", "--dry-run"]);
+    let Command::Mart(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Mart(_)),
+            "expected mart command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    let MartCommand::Migrate(migrate) = &args.command;
+    assert!(migrate.dry_run);
+    assert!(!args.writes());
+
+    let cli = Cli::parse_from(["logit", "mart", "migrate"]);
+    let Command::Mart(args) = cli.command else {
+        assert!(matches!(&cli.command, Command::Mart(_)));
+        return;
+    };
+    assert!(args.writes());
+}
+
+#[test]
+fn parses_purge_selectors_and_prune_age() {
+    let cli = Cli::parse_from([
+        "logit",
+        "purge",
+        "--session",
+        "session-1",
+        "--source-path",
+        "*/.claude/*",
+        "--event-id",
+        "event-1",
+        "--dry-run",
+    ]);
+    let Command::Purge(args) = cli.command else {
+        assert!(
+            matches!(&cli.command, Command::Purge(_)),
+            "expected purge command, got {:?}",
+            cli.command
+        );
+        return;
+    };
+    assert!(args.dry_run);
+    assert_eq!(
+        args.selectors(),
+        [
+            PurgeSelector::Session {
+                session_id: "session-1".to_string()
+            },
+            PurgeSelector::SourcePath {
+                glob: "*/.claude/*".to_string()
+            },
+            PurgeSelector::EventId {
+                event_id: "event-1".to_string()
+            },
+        ]
+    );
+    assert!(Cli::try_parse_from(["logit", "purge"]).is_err());
+    assert!(Cli::try_parse_from(["logit", "purge", "--dry-run"]).is_err());
+
+    let cli = Cli::parse_from(["logit", "prune", "--older-than", "2026-01-01T00:00:00Z"]);
+    let Command::Prune(args) = cli.command else {
+        assert!(matches!(&cli.command, Command::Prune(_)));
+        return;
+    };
+    assert_eq!(args.older_than, 1_767_225_600_000);
+    assert!(!args.dry_run);
+    assert!(Cli::try_parse_from(["logit", "prune", "--older-than", "soon"]).is_err());
+    assert!(Cli::try_parse_from(["logit", "prune"]).is_err());
+}
@@ -0,0 +1,394 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::{CommandFactory, FromArgMatches};
+use logit::cli::app::{Cli, Command, SourceFilterArgs, apply_config_defaults};
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::cli::commands::query::QueryCommand;
+use logit::config::settings::{config_json_schema, parse_config_toml};
+use logit::config::{ConfigOrigin, LogitConfig, RuntimePaths};
+use logit::pipeline::{DEFAULT_GATES, RunGate};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn parse_with_config(argv: &[&str], config: &LogitConfig) -> Command {
+    let matches = Cli::command()
+        .try_get_matches_from(argv)
+        .expect("argv should parse");
+    let mut cli = Cli::from_arg_matches(&matches).expect("matches should convert");
+    apply_config_defaults(&mut cli.command, &matches, config);
+    cli.command
+}
+
+#[test]
+fn layers_merge_user_project_env_and_flags_with_origins() {
+    let home = unique_temp_dir("logit-config-layers-home");
+    let project = unique_temp_dir("logit-config-layers-project");
+    let user_file = home.join(".logit/config.toml");
+    let project_file = project.join(".logit.toml");
+    write_file(
+        &user_file,
+        r#"
+out_dir = "artifacts"
+adapters = ["codex", "claude"]
+tags = ["team-a"]
+
+[redaction]
+policy = "secrets+pii"
+
+[query]
+sql_row_cap = 200
+search_row_cap = 10
+
+[[roots]]
+adapter = "claude"
+profile = "work"
+path = "~/work/.claude"
+
+[pricing."gpt-5"]
+input_per_mtok = 1.25
+output_per_mtok = 10.0
+"#,
+    );
+    write_file(
+        &project_file,
+        "adapters = [\"codex\"]\n[query]\nsql_row_cap = 25\n",
+    );
+    let cwd = project.join("nested/dir");
+    std::fs::create_dir_all(&cwd).expect("nested cwd should be creatable");
+
+    let mut config = LogitConfig::resolve(&home, &cwd, |name| {
+        (name == "LOGIT_SEARCH_ROW_CAP").then(|| "7".to_string())
+    })
+    .expect("config layers should resolve");
+
+    assert_eq!(
+        config.files,
+        [
+            ConfigOrigin::User(user_file.clone()),
+            ConfigOrigin::Project(project_file.clone())
+        ]
+    );
+    assert_eq!(config.adapters.value, ["codex"]);
+    assert_eq!(
+        config.adapters.origin,
+        ConfigOrigin::Project(project_file.clone())
+    );
+    assert_eq!(config.tags.value, ["team-a"]);
+    assert_eq!(
+        config.redaction_policy.value,
+        RedactionPolicy::SecretsAndPii
+    );
+    assert_eq!(config.sql_row_cap.value, 25);
+    assert_eq!(
+        config.sql_row_cap.origin,
+        ConfigOrigin::Project(project_file)
+    );
+    assert_eq!(config.search_row_cap.value, 7);
+    assert_eq!(
+        config.search_row_cap.origin,
+        ConfigOrigin::Env("LOGIT_SEARCH_ROW_CAP")
+    );
+    assert_eq!(config.collector_timeout_secs.origin, ConfigOrigin::Default);
+    assert_eq!(config.roots.len(), 1);
+    assert_eq!(config.roots[0].value.profile, "work");
+    assert_eq!(config.pricing["gpt-5"].value.output_per_mtok, 10.0);
+    assert_eq!(
+        config.out_dir.value.as_deref(),
+        Some(home.join(".logit/artifacts").as_path())
+    );
+
+    config.apply_out_dir_flag(Some(Path::new("/tmp/flag-out")));
+    assert_eq!(config.out_dir.origin, ConfigOrigin::Flag("--out-dir"));
+    assert_eq!(
+        config.out_dir.value.as_deref(),
+        Some(Path::new("/tmp/flag-out"))
+    );
+}
+
+#[test]
+fn invalid_config_values_fail_with_key_specific_errors() {
+    for (content, expected) in [
+        ("bogus = 1\n", "unknown field `bogus`"),
+        ("[redaction]\npolicy = \"loud\"\n", "redaction.policy"),
+        (
+            "[query]\nsql_row_cap = 0\n",
+            "query.sql_row_cap must be greater than zero",
+        ),
+        (
+            "[[roots]]\nadapter = \"aider\"\nprofile = \"x\"\npath = \"/x\"\n",
+            "does not take extra roots",
+        ),
+        (
+            "[pricing.m]\ninput_per_mtok = -1.0\noutput_per_mtok = 1.0\n",
+            "pricing.\"m\".input_per_mtok must be a non-negative number",
+        ),
+    ] {
+        let home = unique_temp_dir("logit-config-invalid");
+        write_file(&home.join(".logit/config.toml"), content);
+        let error = LogitConfig::resolve(&home, &home, |_| None)
+            .expect_err("invalid config should be rejected");
+        let message = format!("{error:#}");
+        assert!(message.contains("invalid config file"), "{message}");
+        assert!(message.contains(expected), "{message}");
+    }
+
+    let home = unique_temp_dir("logit-config-invalid-env");
+    let error = LogitConfig::resolve(&home, &home, |name| {
+        (name == "LOGIT_SQL_ROW_CAP").then(|| "many".to_string())
+    })
+    .expect_err("non-numeric env row cap should be rejected");
+    assert!(format!("{error:#}").contains("LOGIT_SQL_ROW_CAP must be a positive integer"));
+}
+
+#[test]
+fn config_defaults_apply_only_where_flags_are_absent() {
+    let mut config = LogitConfig::default();
+    config.sql_row_cap.value = 20;
+    config.redaction_policy.value = RedactionPolicy::Off;
+    config.adapters.value = vec!["codex".to_string()];
+
+    let command = parse_with_config(&["logit", "query", "sql", "SELECT 1"], &config);
+    let Command::Query(args) = command else {
+        assert!(
+            matches!(command, Command::Query(_)),
+            "expected query command"
+        );
+        return;
+    };
+    assert!(matches!(args.command, QueryCommand::Sql(sql) if sql.row_cap == 20));
+
+    let command = parse_with_config(
+        &["logit", "query", "sql", "SELECT 1", "--row-cap", "5"],
+        &config,
+    );
+    let Command::Query(args) = command else {
+        assert!(
+            matches!(command, Command::Query(_)),
+            "expected query command"
+        );
+        return;
+    };
+    assert!(matches!(args.command, QueryCommand::Sql(sql) if sql.row_cap == 5));
+
+    let command = parse_with_config(&["logit", "normalize"], &config);
+    let Command::Normalize(args) = command else {
+        assert!(
+            matches!(command, Command::Normalize(_)),
+            "expected normalize command"
+        );
+        return;
+    };
+    assert_eq!(args.redaction, RedactionPolicy::Off);
+    assert_eq!(args.filters.adapters, ["codex"]);
+
+    let command = parse_with_config(
+        &[
+            "logit",
+            "normalize",
+            "--redaction",
+            "secrets-only",
+            "--adapter",
+            "claude",
+        ],
+        &config,
+    );
+    let Command::Normalize(args) = command else {
+        assert!(
+            matches!(command, Command::Normalize(_)),
+            "expected normalize command"
+        );
+        return;
+    };
+    assert_eq!(args.redaction, RedactionPolicy::SecretsOnly);
+    assert_eq!(args.filters.adapters, ["claude"]);
+}
+
+#[test]
+fn no_pseudonymize_flag_overrides_config() {
+    let mut config = LogitConfig::default();
+    config.pseudonymize.value = true;
+
+    for (argv, expected) in [
+        (&["logit", "normalize"][..], true),
+        (&["logit", "normalize", "--no-pseudonymize"][..], false),
+        (&["logit", "snapshot", "--no-pseudonymize"][..], false),
+        (&["logit", "run", "--no-pseudonymize"][..], false),
+    ] {
+        let pseudonymize = match parse_with_config(argv, &config) {
+            Command::Normalize(args) => args.pseudonymize,
+            Command::Snapshot(args) => args.pseudonymize,
+            Command::Run(args) => args.pseudonymize,
+            _ => !expected,
+        };
+        assert_eq!(pseudonymize, expected, "{argv:?}");
+    }
+
+    let conflict = Cli::command().try_get_matches_from([
+        "logit",
+        "normalize",
+        "--pseudonymize",
+        "--no-pseudonymize",
+    ]);
+    assert!(conflict.is_err());
+}
+
+#[test]
+fn normalize_appends_configured_tags_and_host_to_every_event() {
+    let home = unique_temp_dir("logit-config-tags-home");
+    write_file(
+        &home.join(".logit/config.toml"),
+        "tags = [\"team-a\", \"nightly\"]\nhost = \"Build-Box\"\n",
+    );
+    let source_root = unique_temp_dir("logit-config-tags-sources");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    let out_dir = unique_temp_dir("logit-config-tags-out");
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    let config = LogitConfig::resolve(&home, &home, |_| None).expect("config should load");
+    let runtime_paths = RuntimePaths {
+        home_dir: home.clone(),
+        cwd: home,
+        out_dir: out_dir.clone(),
+    };
+    run_normalize(&args, &config, &runtime_paths).expect("normalize run should succeed");
+
+    let rows = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("each event row should parse"))
+        .collect::<Vec<_>>();
+    assert!(!rows.is_empty());
+    for row in rows {
+        let tags = row["tags"].as_array().cloned().unwrap_or_default();
+        assert!(tags.contains(&Value::from("team-a")), "{row}");
+        assert!(tags.contains(&Value::from("nightly")), "{row}");
+        assert_eq!(row["metadata"]["host"], "build-box", "{row}");
+    }
+}
+
+#[test]
+fn host_resolves_from_config_then_env_and_rejects_invalid_ids() {
+    let home = unique_temp_dir("logit-config-host");
+    let user_file = home.join(".logit/config.toml");
+    write_file(&user_file, "host = \"Laptop\"\n");
+
+    let config = LogitConfig::resolve(&home, &home, |_| None).expect("host should resolve");
+    assert_eq!(config.host.value.as_deref(), Some("laptop"));
+    assert_eq!(config.host.origin, ConfigOrigin::User(user_file));
+    assert_eq!(config.host_id(), "laptop");
+
+    let config = LogitConfig::resolve(&home, &home, |name| {
+        (name == "LOGIT_HOST").then(|| "ci-runner-01".to_string())
+    })
+    .expect("env host should resolve");
+    assert_eq!(config.host.value.as_deref(), Some("ci-runner-01"));
+    assert_eq!(config.host.origin, ConfigOrigin::Env("LOGIT_HOST"));
+
+    let error = LogitConfig::resolve(&home, &home, |name| {
+        (name == "LOGIT_HOST").then(|| "my laptop".to_string())
+    })
+    .expect_err("host with a space should be rejected");
+    assert!(format!("{error:#}").contains("LOGIT_HOST"));
+
+    let unset = unique_temp_dir("logit-config-host-unset");
+    let config = LogitConfig::resolve(&unset, &unset, |_| None).expect("defaults should resolve");
+    assert!(config.host.value.is_none());
+    assert!(!config.host_id().is_empty());
+}
+
+#[test]
+fn config_schema_rejects_unknown_keys_and_parses_documented_sections() {
+    let schema = config_json_schema();
+    assert_eq!(schema["title"], "LogitConfig");
+    assert_eq!(schema["additionalProperties"], Value::Bool(false));
+    for key in [
+        "out_dir",
+        "adapters",
+        "tags",
+        "roots",
+        "redaction",
+        "query",
+        "timeouts",
+        "run",
+        "pricing",
+    ] {
+        assert!(schema["properties"].get(key).is_some(), "missing {key}");
+    }
+
+    let file = parse_config_toml("[timeouts]\ncollector_secs = 3\nserve_request_secs = 4\n")
+        .expect("timeouts section should parse");
+    assert_eq!(file.timeouts.collector_secs, Some(3));
+    assert_eq!(file.timeouts.serve_request_secs, Some(4));
+}
+
+#[test]
+fn run_gates_resolve_from_config_and_env_and_apply_when_flags_absent() {
+    assert_eq!(LogitConfig::default().run_gates.value, DEFAULT_GATES);
+
+    let home = unique_temp_dir("logit-config-run-gates");
+    write_file(
+        &home.join(".logit/config.toml"),
+        "[run]\ngates = [\"validation-warnings\"]\n",
+    );
+    let config = LogitConfig::resolve(&home, &home, |_| None).expect("run gates should resolve");
+    assert_eq!(config.run_gates.value, [RunGate::ValidationWarnings]);
+
+    let command = parse_with_config(&["logit", "run"], &config);
+    let Command::Run(args) = command else {
+        assert!(matches!(command, Command::Run(_)), "expected run command");
+        return;
+    };
+    assert_eq!(args.gates, [RunGate::ValidationWarnings]);
+
+    let command = parse_with_config(&["logit", "run", "--no-gates"], &config);
+    let Command::Run(args) = command else {
+        assert!(matches!(command, Command::Run(_)), "expected run command");
+        return;
+    };
+    assert!(args.gates.is_empty());
+
+    let config = LogitConfig::resolve(&home, &home, |name| {
+        (name == "LOGIT_RUN_GATES").then(|| "none".to_string())
+    })
+    .expect("env run gates should resolve");
+    assert!(config.run_gates.value.is_empty());
+    assert_eq!(
+        config.run_gates.origin,
+        ConfigOrigin::Env("LOGIT_RUN_GATES")
+    );
+
+    write_file(
+        &home.join(".logit/config.toml"),
+        "[run]\ngates = [\"loud\"]\n",
+    );
+    let error =
+        LogitConfig::resolve(&home, &home, |_| None).expect_err("unknown gate should be rejected");
+    assert!(format!("{error:#}").contains("run.gates"), "{error:#}");
+}
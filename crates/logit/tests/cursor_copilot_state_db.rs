@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::adapters::{copilot, cursor};
+use logit::models::{ActorRole, AgentSource, EventType, RecordFormat, TimestampQuality};
+use logit::normalize::{default_plan, orchestrate_normalization};
+
+fn fixture_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../fixtures")
+        .join(relative)
+}
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn copy_fixture(relative: &str, destination: &Path) {
+    let parent = destination
+        .parent()
+        .expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::copy(fixture_path(relative), destination).expect("fixture should be copied");
+}
+
+#[test]
+fn cursor_state_db_maps_chat_tabs_and_composers() {
+    let result = cursor::parse_state_db_file(&fixture_path("cursor/state.vscdb"), "run-cursor")
+        .expect("cursor fixture should parse");
+    assert_eq!(result.events.len(), 9);
+    assert_eq!(result.warnings.len(), 1);
+    assert!(result.warnings[0].contains("bubble `missing` not found"));
+    assert!(
+        result
+            .events
+            .iter()
+            .all(|event| event.source_kind == AgentSource::Cursor)
+    );
+
+    let chat = &result.events[..3];
+    assert_eq!(chat[0].event_type, EventType::Prompt);
+    assert_eq!(chat[0].timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(chat[0].timestamp_utc, "2026-02-10T09:00:04.998Z");
+    assert_eq!(chat[1].event_type, EventType::Response);
+    assert_eq!(chat[1].role, ActorRole::Assistant);
+    assert_eq!(chat[1].model.as_deref(), Some("gpt-4o"));
+    assert_eq!(chat[2].timestamp_quality, TimestampQuality::Exact);
+    assert_eq!(
+        chat[0].metadata.get("cursor_chat_title"),
+        Some(&serde_json::json!("Refactor parser"))
+    );
+
+    let response = &result.events[4];
+    assert_eq!(response.conversation_id.as_deref(), Some("comp-inline"));
+    assert_eq!(response.model.as_deref(), Some("claude-3.5-sonnet"));
+    assert_eq!(response.input_tokens, Some(120));
+    assert_eq!(response.output_tokens, Some(40));
+    assert_eq!(response.total_tokens, Some(160));
+
+    let tool_call = &result.events[5];
+    assert_eq!(tool_call.record_format, RecordFormat::ToolCall);
+    assert_eq!(tool_call.tool_name.as_deref(), Some("run_terminal_cmd"));
+    assert_eq!(tool_call.tool_call_id.as_deref(), Some("call-1"));
+    assert_eq!(
+        tool_call.tool_arguments_json.as_deref(),
+        Some(r#"{"command":"cargo test"}"#)
+    );
+    let tool_result = &result.events[6];
+    assert_eq!(tool_result.record_format, RecordFormat::ToolResult);
+    assert_eq!(tool_result.tool_call_id, tool_call.tool_call_id);
+    assert_eq!(
+        tool_result.tool_result_text.as_deref(),
+        Some("test result: ok. 12 passed")
+    );
+
+    let split = &result.events[7..];
+    assert_eq!(split[0].timestamp_utc, "2026-02-10T11:00:05.000Z");
+    assert_eq!(split[1].timestamp_utc, "2026-02-10T11:00:07.000Z");
+    assert!(
+        split
+            .iter()
+            .all(|event| event.source_record_locator.starts_with("cursorDiskKV:"))
+    );
+}
+
+#[test]
+fn copilot_interactive_sessions_map_prompts_responses_and_tools() {
+    let result = copilot::parse_state_db_file(&fixture_path("copilot/state.vscdb"), "run-copilot")
+        .expect("copilot fixture should parse");
+    assert!(
+        result.warnings.is_empty(),
+        "warnings: {:?}",
+        result.warnings
+    );
+    assert_eq!(result.events.len(), 5);
+
+    let prompt = &result.events[0];
+    assert_eq!(prompt.event_type, EventType::Prompt);
+    assert_eq!(prompt.session_id.as_deref(), Some("sess-copilot-1"));
+    assert_eq!(prompt.turn_id.as_deref(), Some("req-1"));
+    assert_eq!(prompt.model.as_deref(), Some("copilot/gpt-4o"));
+    assert_eq!(prompt.timestamp_quality, TimestampQuality::Exact);
+
+    let tool_call = &result.events[1];
+    assert_eq!(tool_call.record_format, RecordFormat::ToolCall);
+    assert_eq!(tool_call.tool_name.as_deref(), Some("copilot_readFile"));
+    assert_eq!(tool_call.content_text.as_deref(), Some("Reading lib.rs"));
+
+    let response = &result.events[2];
+    assert_eq!(response.event_type, EventType::Response);
+    assert_eq!(
+        response.content_text.as_deref(),
+        Some("The reference escapes because it is returned.")
+    );
+    assert_eq!(response.timestamp_utc, "2026-02-10T12:00:07.500Z");
+
+    assert_eq!(
+        result.events[3].timestamp_quality,
+        TimestampQuality::Derived
+    );
+    assert_eq!(
+        result.events[4].content_text.as_deref(),
+        Some("Return an owned `String` instead.")
+    );
+}
+
+#[test]
+fn copilot_chat_session_json_requires_requests_array() {
+    let error = copilot::parse_chat_session_json(r#"{"sessionId":"x"}"#, "run", "session.json")
+        .expect_err("missing requests should fail");
+    assert!(error.to_string().contains("requests"));
+    assert!(copilot::is_chat_session_file(Path::new(
+        "/ws/abc/chatSessions/one.json"
+    )));
+    assert!(!copilot::is_chat_session_file(Path::new(
+        "/ws/abc/one.json"
+    )));
+}
+
+#[test]
+fn orchestrator_normalizes_editor_state_stores() {
+    let home = unique_temp_dir("logit-vscdb-home");
+    copy_fixture(
+        "cursor/state.vscdb",
+        &home.join(".config/Cursor/User/globalStorage/state.vscdb"),
+    );
+    copy_fixture(
+        "copilot/state.vscdb",
+        &home.join(".config/Code/User/workspaceStorage/ws-1/state.vscdb"),
+    );
+    copy_fixture(
+        "copilot/chatSessions/session.json",
+        &home.join(".config/Code/User/workspaceStorage/ws-1/chatSessions/session.json"),
+    );
+    copy_fixture(
+        "copilot/chatSessions/session.json",
+        &home.join(".config/Code/User/workspaceStorage/ws-1/workspace.json"),
+    );
+
+    let mut plan = default_plan();
+    plan.adapters = vec![AdapterKind::Cursor, AdapterKind::Copilot];
+    let result =
+        orchestrate_normalization(&plan, &home, None, "").expect("orchestrator should succeed");
+
+    let cursor_health = result
+        .adapter_health
+        .get("cursor")
+        .expect("cursor health should be reported");
+    assert_eq!(cursor_health.files_parsed, 1);
+    let copilot_health = result
+        .adapter_health
+        .get("copilot")
+        .expect("copilot health should be reported");
+    assert_eq!(copilot_health.files_parsed, 2);
+
+    let count_source = |source: AgentSource| {
+        result
+            .events
+            .iter()
+            .filter(|event| event.source_kind == source)
+            .count()
+    };
+    assert_eq!(count_source(AgentSource::Cursor), 9);
+    assert_eq!(count_source(AgentSource::Copilot), 7);
+}
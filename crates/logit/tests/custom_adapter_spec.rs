@@ -0,0 +1,284 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::custom::{
+    load_custom_adapter_specs, parse_custom_records, parse_spec_json, parse_spec_toml, spec_dir,
+};
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, TimestampQuality,
+};
+use logit::sqlite::{SqliteWriterConfig, write_events_to_sqlite};
+use logit::utils::redaction::RedactionPolicy;
+use logit::validate::{ValidationMode, validate_jsonl_against_generated_schema};
+use serde_json::Value;
+
+const INHOUSE_SPEC: &str = r#"
+name = "inhouse-agent"
+version = "v3"
+paths = ["~/.inhouse/sessions"]
+file_globs = ["*.jsonl"]
+
+[mapping]
+event_id = "/id"
+timestamp = "/ts"
+role = "/author/kind"
+content = "/body"
+tool_name = "/tool/name"
+tool_arguments = "/tool/args"
+tool_result = "/tool/output"
+session_id = "/session"
+
+[role_aliases]
+operator = "user"
+planner = "assistant"
+"#;
+
+const INHOUSE_SESSION: &str = r#"{"id":"m1","ts":"2026-02-10T09:00:00Z","author":{"kind":"operator"},"body":"list failing jobs","session":"s-1"}
+{"id":"m2","ts":"2026-02-10T09:00:01Z","author":{"kind":"planner"},"body":[{"text":"checking CI"}],"session":"s-1"}
+{"id":"m3","ts":"2026-02-10T09:00:02Z","author":{"kind":"planner"},"tool":{"name":"ci_status","args":{"branch":"main"}},"session":"s-1"}
+{"id":"m4","ts":"2026-02-10T09:00:03Z","author":{"kind":"tool"},"tool":{"output":"2 jobs failing"},"session":"s-1"}
+{"id":"m5","author":{"kind":"narrator"},"body":"unknown speaker","session":"s-1"}
+"#;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn parse_inhouse_events() -> (Vec<AgentLogEvent>, Vec<String>) {
+    let spec = parse_spec_toml(INHOUSE_SPEC).expect("inhouse spec should parse");
+    let parsed = parse_custom_records(&spec, INHOUSE_SESSION, "run-custom", "/tmp/s.jsonl")
+        .expect("inhouse session should parse");
+    (parsed.events, parsed.warnings)
+}
+
+#[test]
+fn toml_spec_maps_json_pointers_to_canonical_events() {
+    let (events, warnings) = parse_inhouse_events();
+    assert_eq!(events.len(), 5);
+
+    let custom_source = AgentSource::Custom("inhouse-agent".to_string());
+    assert!(
+        events
+            .iter()
+            .all(|event| event.source_kind == custom_source)
+    );
+    assert!(
+        events
+            .iter()
+            .all(|event| event.adapter_name == custom_source)
+    );
+    assert_eq!(events[0].adapter_version.as_deref(), Some("v3"));
+
+    assert_eq!(events[0].event_type, EventType::Prompt);
+    assert_eq!(events[0].role, ActorRole::User);
+    assert_eq!(events[0].timestamp_quality, TimestampQuality::Exact);
+    assert_eq!(events[0].session_id.as_deref(), Some("s-1"));
+    assert_eq!(events[0].content_text.as_deref(), Some("list failing jobs"));
+    assert!(events[0].event_id.starts_with("inhouse-agent-"));
+
+    assert_eq!(events[1].event_type, EventType::Response);
+    assert_eq!(events[1].content_text.as_deref(), Some("checking CI"));
+
+    assert_eq!(events[2].record_format, RecordFormat::ToolCall);
+    assert_eq!(events[2].tool_name.as_deref(), Some("ci_status"));
+    assert_eq!(
+        events[2].tool_arguments_json.as_deref(),
+        Some(r#"{"branch":"main"}"#)
+    );
+
+    assert_eq!(events[3].record_format, RecordFormat::ToolResult);
+    assert_eq!(
+        events[3].tool_result_text.as_deref(),
+        Some("2 jobs failing")
+    );
+
+    assert_eq!(events[4].role, ActorRole::Runtime);
+    assert_eq!(events[4].timestamp_quality, TimestampQuality::Fallback);
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| warning.contains("unknown role `narrator`"))
+    );
+    assert!(
+        warnings
+            .iter()
+            .any(|warning| warning.contains("record 5: missing timestamp"))
+    );
+}
+
+#[test]
+fn custom_source_kind_round_trips_through_jsonl_validation_and_sqlite() {
+    let (events, _) = parse_inhouse_events();
+    let encoded = serde_json::to_value(&events[0]).expect("event should serialize");
+    assert_eq!(
+        encoded.get("source_kind").and_then(Value::as_str),
+        Some("inhouse-agent")
+    );
+    let decoded: AgentLogEvent =
+        serde_json::from_value(encoded).expect("custom event should deserialize");
+    assert_eq!(decoded.source_kind, events[0].source_kind);
+
+    let jsonl = events
+        .iter()
+        .map(|event| serde_json::to_string(event).expect("event should serialize"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let report = validate_jsonl_against_generated_schema(&jsonl, ValidationMode::Baseline);
+    assert_eq!(report.errors, 0, "issues: {:?}", report.issues);
+    assert_eq!(
+        report
+            .per_agent_summary
+            .get("inhouse-agent")
+            .map(|stats| stats.records_validated),
+        Some(5)
+    );
+
+    let sqlite_path = unique_temp_dir("logit-custom-adapter-sqlite").join("mart.sqlite");
+    std::fs::create_dir_all(sqlite_path.parent().expect("sqlite parent"))
+        .expect("sqlite dir should be creatable");
+    let stats = write_events_to_sqlite(&sqlite_path, &events, SqliteWriterConfig::default())
+        .expect("custom events should satisfy sqlite constraints");
+    assert_eq!(stats.records_written, 5);
+}
+
+#[test]
+fn json_spec_reads_records_from_pointer() {
+    let spec = parse_spec_json(
+        r#"{
+  "name": "scripted-runner",
+  "paths": ["~/.runner/logs"],
+  "format": "json",
+  "records_pointer": "/transcript/turns",
+  "mapping": { "role": "/who", "content": "/said", "timestamp": "/at" }
+}"#,
+    )
+    .expect("json spec should parse");
+
+    let parsed = parse_custom_records(
+        &spec,
+        r#"{"transcript":{"turns":[{"who":"human","said":"hi","at":1770700000000},{"who":"ai","said":"hello"}]}}"#,
+        "run-json",
+        "/tmp/runner.json",
+    )
+    .expect("json payload should parse");
+
+    assert_eq!(parsed.events.len(), 2);
+    assert_eq!(parsed.events[0].role, ActorRole::User);
+    assert_eq!(parsed.events[0].source_record_locator, "index:1");
+    assert_eq!(parsed.events[1].role, ActorRole::Assistant);
+    assert_eq!(parsed.events[1].adapter_version.as_deref(), Some("custom"));
+}
+
+#[test]
+fn rejects_invalid_specs() {
+    let shadowing = INHOUSE_SPEC.replace("inhouse-agent", "codex");
+    assert!(
+        parse_spec_toml(&shadowing)
+            .expect_err("built-in names should be rejected")
+            .to_string()
+            .contains("lowercase slug")
+    );
+
+    let bad_pointer = INHOUSE_SPEC.replace("\"/body\"", "\"body\"");
+    assert!(
+        format!(
+            "{:#}",
+            parse_spec_toml(&bad_pointer).expect_err("relative pointers should be rejected")
+        )
+        .contains("must be a JSON pointer")
+    );
+
+    let bad_alias = INHOUSE_SPEC.replace("planner = \"assistant\"", "planner = \"wizard\"");
+    assert!(parse_spec_toml(&bad_alias).is_err());
+
+    let unknown_key = format!("extra = true\n{INHOUSE_SPEC}");
+    assert!(parse_spec_toml(&unknown_key).is_err());
+}
+
+#[test]
+fn loader_collects_specs_and_reports_rejections() {
+    let home = unique_temp_dir("logit-custom-adapter-loader");
+    let dir = spec_dir(&home);
+    write_file(&dir.join("inhouse.toml"), INHOUSE_SPEC);
+    write_file(&dir.join("inhouse-copy.toml"), INHOUSE_SPEC);
+    write_file(&dir.join("broken.json"), "{ not json");
+    write_file(&dir.join("README.md"), "ignored");
+
+    let loaded = load_custom_adapter_specs(&dir).expect("loader should succeed");
+    assert_eq!(loaded.specs.len(), 1);
+    assert_eq!(loaded.specs[0].name, "inhouse-agent");
+    assert_eq!(loaded.warnings.len(), 2);
+    assert!(loaded.warnings[0].contains("broken.json"));
+    assert!(loaded.warnings[1].contains("duplicate adapter name"));
+
+    let missing = load_custom_adapter_specs(&home.join("missing")).expect("missing dir is ok");
+    assert!(missing.specs.is_empty());
+}
+
+#[test]
+fn normalize_command_runs_custom_adapters_from_home_spec_dir() {
+    let home = unique_temp_dir("logit-custom-adapter-home");
+    let source_root = unique_temp_dir("logit-custom-adapter-source");
+    let out_dir = unique_temp_dir("logit-custom-adapter-out");
+    write_file(&spec_dir(&home).join("inhouse.toml"), INHOUSE_SPEC);
+    write_file(
+        &source_root.join(".inhouse/sessions/2026/s-1.jsonl"),
+        INHOUSE_SESSION,
+    );
+    write_file(
+        &source_root.join(".inhouse/sessions/notes.txt"),
+        "not matched by file globs",
+    );
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home,
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.clone(),
+    };
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: true,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+
+    let events_text =
+        std::fs::read_to_string(out_dir.join("events.jsonl")).expect("events should exist");
+    let custom_rows = events_text
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("event row should parse"))
+        .filter(|row| row.get("source_kind").and_then(Value::as_str) == Some("inhouse-agent"))
+        .count();
+    assert_eq!(custom_rows, 5);
+
+    let stats: Value = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("stats.json")).expect("stats should exist"),
+    )
+    .expect("stats should parse");
+    assert_eq!(
+        stats
+            .pointer("/adapter_contributions/inhouse-agent")
+            .and_then(Value::as_u64),
+        Some(5)
+    );
+}
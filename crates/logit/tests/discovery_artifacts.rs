@@ -1,10 +1,12 @@
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::discovery::build_artifact_layout;
 use logit::normalize::build_artifact_layout as build_normalize_artifact_layout;
+use logit::utils::redaction::RedactionPolicy;
 use serde_json::Value;
 
 fn unique_temp_dir(prefix: &str) -> PathBuf {
@@ -128,11 +130,19 @@ fn normalize_command_emits_discovery_evidence_artifacts() {
         out_dir: out_dir.clone(),
     };
     let args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
         fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_normalize(&args, &runtime_paths).expect("normalize command should succeed");
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize command should succeed");
 
     let layout = build_artifact_layout(&out_dir);
     assert!(layout.sources_json.exists(), "sources.json should exist");
@@ -203,11 +213,19 @@ fn normalize_smoke_handles_realistic_local_home_and_adapter_coverage() {
         out_dir: out_dir.clone(),
     };
     let args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: None,
+        input_adapter: None,
+        message_format: None,
         fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_normalize(&args, &runtime_paths).expect("normalize smoke run should succeed");
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize smoke run should succeed");
 
     let discovery_layout = build_artifact_layout(&out_dir);
     let sources: Value = serde_json::from_str(
@@ -217,7 +235,7 @@ fn normalize_smoke_handles_realistic_local_home_and_adapter_coverage() {
     .expect("sources artifact should parse");
     assert_eq!(
         sources.get("total_sources").and_then(Value::as_u64),
-        Some(16)
+        Some(30)
     );
     let adapter_counts = sources
         .get("adapter_counts")
@@ -235,7 +253,7 @@ fn normalize_smoke_handles_realistic_local_home_and_adapter_coverage() {
     assert_eq!(adapter_counts.get("amp").and_then(Value::as_u64), Some(4));
     assert_eq!(
         adapter_counts.get("opencode").and_then(Value::as_u64),
-        Some(3)
+        Some(6)
     );
 
     let history_usage: Value = serde_json::from_str(
@@ -0,0 +1,226 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::discovery::roots::{
+    AdapterRoot, DEFAULT_PROFILE, RootOrigin, SourceRoots, parse_roots_toml,
+};
+use logit::discovery::{SourceSelectionFilter, known_path_registry, prioritize_sources_with_roots};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn default_root(roots: &SourceRoots, adapter: AdapterKind) -> &AdapterRoot {
+    roots
+        .for_adapter(adapter)
+        .next()
+        .expect("adapter should have a default root")
+}
+
+#[test]
+fn environment_overrides_replace_default_agent_roots() {
+    let home = Path::new("/home/tester");
+    let roots = SourceRoots::resolve(
+        home,
+        |name| match name {
+            "CODEX_HOME" => Some("/srv/codex".to_string()),
+            "CLAUDE_CONFIG_DIR" => Some("/home/tester/.config/claude".to_string()),
+            "XDG_CONFIG_HOME" => Some("/home/tester/.xdg".to_string()),
+            "XDG_DATA_HOME" => Some("/data".to_string()),
+            _ => None,
+        },
+        Vec::new(),
+    );
+
+    let codex = default_root(&roots, AdapterKind::Codex);
+    assert_eq!(codex.path, "/srv/codex");
+    assert_eq!(codex.origin, RootOrigin::Env);
+    assert_eq!(
+        default_root(&roots, AdapterKind::Claude).path,
+        "~/.config/claude"
+    );
+    assert_eq!(
+        default_root(&roots, AdapterKind::Cursor).path,
+        "~/.xdg/Cursor"
+    );
+    assert_eq!(
+        default_root(&roots, AdapterKind::Copilot).path,
+        "~/.xdg/Code"
+    );
+    let opencode = roots
+        .for_adapter(AdapterKind::OpenCode)
+        .map(|root| root.path.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(opencode, vec!["~/.opencode", "/data/opencode"]);
+    assert_eq!(
+        default_root(&roots, AdapterKind::Gemini).origin,
+        RootOrigin::Builtin
+    );
+}
+
+#[test]
+fn defaults_include_xdg_data_root_for_opencode() {
+    let roots = SourceRoots::resolve(Path::new("/home/tester"), |_| None, Vec::new());
+    assert!(
+        roots
+            .for_adapter(AdapterKind::OpenCode)
+            .any(|root| root.path == "~/.local/share/opencode" && root.origin == RootOrigin::Xdg)
+    );
+    assert!(roots.for_adapter(AdapterKind::Aider).next().is_none());
+}
+
+#[test]
+fn parses_roots_file_and_rejects_invalid_entries() {
+    let roots = parse_roots_toml(
+        r#"
+[[roots]]
+adapter = "codex"
+profile = "work"
+path = "~/work/.codex"
+
+[[roots]]
+adapter = "Claude"
+profile = "client-a"
+path = "/mnt/client-a/claude"
+"#,
+    )
+    .expect("roots file should parse");
+    assert_eq!(roots.len(), 2);
+    assert_eq!(roots[0].adapter, AdapterKind::Codex);
+    assert_eq!(roots[0].profile, "work");
+    assert_eq!(roots[1].adapter, AdapterKind::Claude);
+    assert_eq!(roots[1].origin, RootOrigin::Config);
+
+    for (input, expected) in [
+        (
+            "[[roots]]\nadapter = \"nope\"\nprofile = \"x\"\npath = \"/x\"",
+            "unknown adapter",
+        ),
+        (
+            "[[roots]]\nadapter = \"aider\"\nprofile = \"x\"\npath = \"/x\"",
+            "does not take extra roots",
+        ),
+        (
+            "[[roots]]\nadapter = \"codex\"\nprofile = \"Work Laptop\"\npath = \"/x\"",
+            "must be a slug",
+        ),
+    ] {
+        let error = parse_roots_toml(input).expect_err("invalid roots entry should fail");
+        assert!(
+            format!("{error:#}").contains(expected),
+            "unexpected error: {error:#}"
+        );
+    }
+}
+
+#[test]
+fn prioritization_expands_candidates_under_each_profile_root() {
+    let roots = SourceRoots::resolve(
+        Path::new("/home/tester"),
+        |_| None,
+        vec![AdapterRoot {
+            adapter: AdapterKind::Claude,
+            profile: "work".to_string(),
+            path: "/home/tester/work/.claude".to_string(),
+            origin: RootOrigin::Config,
+        }],
+    );
+    let filter = SourceSelectionFilter {
+        adapters: vec![AdapterKind::Claude],
+        ..SourceSelectionFilter::default()
+    };
+    let prioritized = prioritize_sources_with_roots(&known_path_registry(), &[], &filter, &roots);
+
+    let placements = prioritized
+        .iter()
+        .map(|source| (source.profile.as_str(), source.path.as_str()))
+        .collect::<Vec<_>>();
+    assert!(placements.contains(&(DEFAULT_PROFILE, "~/.claude/projects")));
+    assert!(placements.contains(&("work", "~/work/.claude/projects")));
+    assert_eq!(
+        placements
+            .iter()
+            .filter(|(_, path)| path.ends_with(".claude.json"))
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn normalize_tags_events_and_discovery_evidence_with_profile() {
+    let home_dir = unique_temp_dir("logit-roots-home");
+    write_file(
+        &home_dir.join(".logit/roots.toml"),
+        "[[roots]]\nadapter = \"codex\"\nprofile = \"work\"\npath = \"~/profiles/work/.codex\"\n",
+    );
+    let source_root = unique_temp_dir("logit-roots-sources");
+    write_file(
+        &source_root.join("profiles/work/.codex/sessions/rollout.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    let out_dir = unique_temp_dir("logit-roots-out");
+
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs {
+            adapters: vec!["codex".to_string()],
+            ..SourceFilterArgs::default()
+        },
+    };
+    let runtime_paths = RuntimePaths {
+        home_dir,
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.clone(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+
+    let rows = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("each event row should parse"))
+        .collect::<Vec<_>>();
+    assert!(!rows.is_empty());
+    assert!(rows.iter().all(|row| {
+        row.pointer("/metadata/source_profile")
+            .and_then(Value::as_str)
+            == Some("work")
+    }));
+
+    let sources: Value = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("discovery/sources.json"))
+            .expect("sources artifact should exist"),
+    )
+    .expect("sources artifact should parse");
+    let Some(entries) = sources.get("sources").and_then(Value::as_array) else {
+        assert!(sources.get("sources").is_some_and(Value::is_array));
+        return;
+    };
+    assert!(entries.iter().any(|entry| {
+        entry.get("profile").and_then(Value::as_str) == Some("work")
+            && entry.get("path").and_then(Value::as_str) == Some("~/profiles/work/.codex/sessions")
+    }));
+}
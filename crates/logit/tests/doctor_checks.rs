@@ -0,0 +1,254 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::doctor::{DoctorArgs, DoctorCheckFailure, run as run_doctor};
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::discovery::roots::SourceRoots;
+use logit::doctor::{CheckStatus, DoctorPlan, DoctorReport, run_checks};
+use logit::ingest::import::{IngestImportPlan, run_import};
+use logit::ingest::{default_plan_from_paths, run_refresh};
+use logit::sqlite::{SCHEMA_META_TABLE, open_sqlite_connection};
+use logit::utils::redaction::RedactionPolicy;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test file should be writable");
+}
+
+fn plan(home_dir: &Path, source_root: &Path, out_dir: &Path) -> DoctorPlan {
+    DoctorPlan {
+        home_dir: home_dir.to_path_buf(),
+        cwd: home_dir.to_path_buf(),
+        source_root: Some(source_root.to_path_buf()),
+        out_dir: out_dir.to_path_buf(),
+        adapters: vec![AdapterKind::Codex, AdapterKind::Claude],
+        source_roots: SourceRoots::resolve(home_dir, |_| None, Vec::new()),
+        host: LogitConfig::default().host_id(),
+    }
+}
+
+fn status(report: &DoctorReport, id: &str) -> Option<CheckStatus> {
+    report.check(id).map(|check| check.status)
+}
+
+fn normalize_and_ingest(home_dir: &Path, source_root: &Path, out_dir: &Path) {
+    let runtime_paths = RuntimePaths {
+        home_dir: home_dir.to_path_buf(),
+        cwd: home_dir.to_path_buf(),
+        out_dir: out_dir.to_path_buf(),
+    };
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root.to_path_buf()),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+    run_refresh(&default_plan_from_paths(out_dir, source_root, false))
+        .expect("ingest refresh should succeed");
+}
+
+#[test]
+fn doctor_passes_every_check_for_a_healthy_pipeline() {
+    let home_dir = unique_temp_dir("logit-doctor-home");
+    let source_root = unique_temp_dir("logit-doctor-source");
+    let out_dir = unique_temp_dir("logit-doctor-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    write_file(
+        &home_dir.join(".zsh_history"),
+        ": 1740467001:0;codex --full-auto\n: 1740467002:0;ls\n",
+    );
+    normalize_and_ingest(&home_dir, &source_root, &out_dir);
+
+    let report = run_checks(&plan(&home_dir, &source_root, &out_dir));
+
+    for id in [
+        "discovery.codex",
+        "history.zsh",
+        "out_dir.writable",
+        "mart.present",
+        "mart.schema_version",
+        "mart.integrity",
+        "ingest.last_run",
+        "ingest.watermarks",
+        "mart.parity",
+    ] {
+        assert_eq!(status(&report, id), Some(CheckStatus::Pass), "{id}");
+    }
+    assert_eq!(report.counts.fail, 0);
+    assert!(!out_dir.join(logit::doctor::OUT_DIR_PROBE_FILE).exists());
+
+    let codex = report.check("discovery.codex").expect("codex check");
+    assert_eq!(codex.details["files"], 1);
+    assert!(codex.details["newest_mtime_utc"].is_string());
+    let history = report.check("history.zsh").expect("history check");
+    assert_eq!(history.details["entries"], 2);
+    assert_eq!(history.details["adapter_command_hits"]["codex"], 1);
+
+    let claude = report.check("discovery.claude").expect("claude check");
+    assert_eq!(claude.status, CheckStatus::Skip);
+    assert!(
+        claude
+            .remediation
+            .as_deref()
+            .is_some_and(|hint| hint.contains("CLAUDE_CONFIG_DIR")),
+        "{claude:?}"
+    );
+}
+
+#[test]
+fn doctor_parity_ignores_events_imported_from_other_hosts() {
+    let home_dir = unique_temp_dir("logit-doctor-import-home");
+    let source_root = unique_temp_dir("logit-doctor-import-source");
+    let out_dir = unique_temp_dir("logit-doctor-import-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    normalize_and_ingest(&home_dir, &source_root, &out_dir);
+
+    let foreign = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist")
+        .lines()
+        .map(|line| {
+            let mut row: serde_json::Value =
+                serde_json::from_str(line).expect("event row should parse");
+            for field in ["event_id", "canonical_hash"] {
+                row[field] = format!("laptop-{}", row[field].as_str().unwrap_or_default()).into();
+            }
+            row["metadata"]["host"] = "laptop".into();
+            format!("{row}\n")
+        })
+        .collect::<String>();
+    let foreign_path = unique_temp_dir("logit-doctor-import-foreign").join("events.jsonl");
+    write_file(&foreign_path, &foreign);
+    let imported = run_import(&IngestImportPlan {
+        input_path: foreign_path,
+        sqlite_path: out_dir.join("mart.sqlite"),
+        host: None,
+        run_id: None,
+        tombstones: Vec::new(),
+    })
+    .expect("import should succeed");
+    assert!(imported.events_inserted > 0);
+
+    let report = run_checks(&plan(&home_dir, &source_root, &out_dir));
+    let parity = report.check("mart.parity").expect("parity check");
+    assert_eq!(parity.status, CheckStatus::Pass, "{parity:?}");
+    assert_eq!(
+        parity.details["other_host_records"].as_u64(),
+        u64::try_from(imported.events_inserted).ok()
+    );
+    assert_eq!(status(&report, "ingest.last_run"), Some(CheckStatus::Pass));
+}
+
+#[test]
+fn doctor_skips_mart_checks_without_a_mart_and_flags_unparseable_history() {
+    let home_dir = unique_temp_dir("logit-doctor-empty-home");
+    let source_root = unique_temp_dir("logit-doctor-empty-source");
+    let out_dir = unique_temp_dir("logit-doctor-empty-out");
+    std::fs::create_dir_all(source_root.join(".claude/projects"))
+        .expect("empty claude projects dir should be creatable");
+    std::fs::create_dir_all(&home_dir).expect("home should be creatable");
+    std::fs::write(home_dir.join(".zsh_history"), [0x83, 0xff, 0x0a])
+        .expect("history should be writable");
+
+    let report = run_checks(&plan(&home_dir, &source_root, &out_dir));
+
+    assert_eq!(status(&report, "discovery.claude"), Some(CheckStatus::Warn));
+    assert_eq!(status(&report, "history.zsh"), Some(CheckStatus::Fail));
+    assert_eq!(status(&report, "mart.present"), Some(CheckStatus::Warn));
+    for id in [
+        "mart.schema_version",
+        "mart.integrity",
+        "ingest.last_run",
+        "ingest.watermarks",
+        "mart.parity",
+    ] {
+        assert_eq!(status(&report, id), Some(CheckStatus::Skip), "{id}");
+    }
+    for check in report
+        .checks
+        .iter()
+        .filter(|check| matches!(check.status, CheckStatus::Warn | CheckStatus::Fail))
+    {
+        assert!(check.remediation.is_some(), "{} lacks a hint", check.id);
+    }
+}
+
+#[test]
+fn doctor_fails_on_schema_version_and_parity_drift() {
+    let home_dir = unique_temp_dir("logit-doctor-drift-home");
+    let source_root = unique_temp_dir("logit-doctor-drift-source");
+    let out_dir = unique_temp_dir("logit-doctor-drift-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    normalize_and_ingest(&home_dir, &source_root, &out_dir);
+
+    let connection =
+        open_sqlite_connection(&out_dir.join("mart.sqlite")).expect("mart should open");
+    connection
+        .execute(
+            &format!("UPDATE {SCHEMA_META_TABLE} SET schema_version = 'agentlog.v0.sqlite'"),
+            [],
+        )
+        .expect("schema meta should update");
+    connection
+        .execute(
+            "DELETE FROM agentlog_events WHERE rowid = (SELECT MIN(rowid) FROM agentlog_events)",
+            [],
+        )
+        .expect("one event should delete");
+    drop(connection);
+
+    let report = run_checks(&plan(&home_dir, &source_root, &out_dir));
+    assert_eq!(
+        status(&report, "mart.schema_version"),
+        Some(CheckStatus::Fail)
+    );
+    assert_eq!(status(&report, "mart.integrity"), Some(CheckStatus::Pass));
+    let parity = report.check("mart.parity").expect("parity check");
+    assert_eq!(parity.status, CheckStatus::Fail);
+    assert!(parity.details["mismatches"].as_u64().unwrap_or_default() > 0);
+    assert!(parity.remediation.is_some());
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home_dir.clone(),
+        cwd: home_dir.clone(),
+        out_dir,
+    };
+    let args = DoctorArgs {
+        source_root: Some(source_root),
+        adapters: vec![AdapterKind::Codex],
+        json: true,
+    };
+    let error = run_doctor(&args, &LogitConfig::default(), &runtime_paths)
+        .expect_err("failing checks should fail the command");
+    let failure = error
+        .downcast_ref::<DoctorCheckFailure>()
+        .expect("error should be a doctor check failure");
+    assert_eq!(failure.failed, 2);
+}
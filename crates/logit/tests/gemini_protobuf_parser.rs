@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::adapters::gemini::parse_conversation_protobuf_bytes;
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::snapshot::{SnapshotArgs, run as run_snapshot};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::models::{ActorRole, EventType, RecordFormat, TimestampQuality};
+use logit::normalize::{AdapterHealthStatus, default_plan, orchestrate_normalization};
+use logit::snapshot::build_artifact_layout;
+use serde_json::{Value, json};
+
+const CONVERSATION_FIXTURE: &[u8] =
+    include_bytes!("../../../fixtures/gemini/conversation_sample.pb");
+const EMPTY_CONVERSATION_FIXTURE: &[u8] =
+    include_bytes!("../../../fixtures/gemini/conversation_2026-02-04.pb");
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_conversation_fixture(root: &std::path::Path) -> PathBuf {
+    let gemini_tmp = root.join(".gemini/tmp");
+    std::fs::create_dir_all(&gemini_tmp).expect("gemini tmp dir should be creatable");
+    let path = gemini_tmp.join("conversation_2026-02-03.pb");
+    std::fs::write(&path, CONVERSATION_FIXTURE).expect("protobuf fixture should be writable");
+    path
+}
+
+#[test]
+fn parses_protobuf_conversation_fixture_to_canonical_events() {
+    let result = parse_conversation_protobuf_bytes(
+        CONVERSATION_FIXTURE,
+        "run-pb",
+        "fixtures/gemini/conversation_sample.pb",
+    )
+    .expect("protobuf conversation parse should succeed");
+
+    assert_eq!(result.events.len(), 3);
+    assert_eq!(
+        result.warnings,
+        vec!["message 3: missing timestamp; using fallback".to_string()]
+    );
+
+    let first = &result.events[0];
+    assert_eq!(first.record_format, RecordFormat::Message);
+    assert_eq!(first.event_type, EventType::Prompt);
+    assert_eq!(first.role, ActorRole::User);
+    assert_eq!(first.source_record_locator, "protobuf:messages:1");
+    assert_eq!(first.timestamp_quality, TimestampQuality::Exact);
+    assert_eq!(first.timestamp_utc, "2026-02-03T00:00:00.000Z");
+    assert_eq!(first.conversation_id.as_deref(), Some("gemini-pb-conv-1"));
+    assert_eq!(first.session_id.as_deref(), Some("gemini-pb-session-1"));
+    assert_eq!(first.model.as_deref(), Some("gemini-2.5-flash"));
+    assert_eq!(
+        first.content_text.as_deref(),
+        Some("summarize the failing test")
+    );
+    assert!(first.event_id.ends_with("-msg-001"));
+    assert_eq!(
+        first.metadata.get("gemini_pb_root_unknown_fields"),
+        Some(&json!({"15": 3}))
+    );
+
+    let second = &result.events[1];
+    assert_eq!(second.event_type, EventType::Response);
+    assert_eq!(second.role, ActorRole::Assistant);
+    assert_eq!(second.timestamp_utc, "2026-02-03T00:00:01.500Z");
+    assert_eq!(second.model.as_deref(), Some("gemini-2.5-pro"));
+    assert_eq!(
+        second.content_text.as_deref(),
+        Some("The parser rejects\nempty arrays.")
+    );
+    assert_eq!(
+        second.metadata.get("gemini_pb_unknown_fields"),
+        Some(&json!({"9": 42}))
+    );
+
+    let third = &result.events[2];
+    assert_eq!(third.event_type, EventType::SystemNotice);
+    assert_eq!(third.timestamp_quality, TimestampQuality::Fallback);
+    assert_eq!(
+        third.metadata.get("gemini_pb_unknown_fields"),
+        Some(&json!({"7": {"1": "trace-7"}}))
+    );
+}
+
+#[test]
+fn skips_protobuf_payloads_that_are_not_conversations() {
+    let parsed =
+        parse_conversation_protobuf_bytes(EMPTY_CONVERSATION_FIXTURE, "run-pb", "empty.pb")
+            .expect("empty payload should be skipped");
+    assert!(parsed.events.is_empty());
+    assert_eq!(parsed.warnings.len(), 1);
+    assert!(parsed.warnings[0].contains("is empty; skipped `empty.pb`"));
+
+    let parsed = parse_conversation_protobuf_bytes(&[0x08, 0x01], "run-pb", "other.pb")
+        .expect("payload without messages should be skipped");
+    assert!(parsed.events.is_empty());
+    assert!(parsed.warnings[0].contains("no repeated message field 4"));
+
+    let parsed = parse_conversation_protobuf_bytes(&[0x00, 0x9f], "run-pb", "garbage.pb")
+        .expect("invalid wire data should be skipped");
+    assert!(parsed.events.is_empty());
+    assert!(parsed.warnings[0].contains("not valid protobuf wire data"));
+}
+
+#[test]
+fn orchestrator_normalizes_gemini_protobuf_conversations() {
+    let source_root = unique_temp_dir("logit-gemini-protobuf-normalize");
+    write_conversation_fixture(&source_root);
+
+    let mut plan = default_plan();
+    plan.adapters = vec![AdapterKind::Gemini];
+
+    let result = orchestrate_normalization(
+        &plan,
+        std::path::Path::new("/tmp/home"),
+        Some(&source_root),
+        "",
+    )
+    .expect("orchestrator should succeed");
+
+    assert_eq!(
+        result
+            .events
+            .iter()
+            .filter(|event| event.tags.iter().any(|tag| tag == "conversation_protobuf"))
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn orchestrator_skips_empty_protobuf_conversations_without_failing_gemini() {
+    let source_root = unique_temp_dir("logit-gemini-protobuf-empty");
+    write_conversation_fixture(&source_root);
+    std::fs::write(
+        source_root.join(".gemini/tmp/conversation_2026-02-04.pb"),
+        EMPTY_CONVERSATION_FIXTURE,
+    )
+    .expect("empty protobuf fixture should be writable");
+
+    let mut plan = default_plan();
+    plan.adapters = vec![AdapterKind::Gemini];
+    plan.fail_fast = true;
+
+    let result = orchestrate_normalization(
+        &plan,
+        std::path::Path::new("/tmp/home"),
+        Some(&source_root),
+        "",
+    )
+    .expect("orchestrator should skip the empty conversation");
+
+    assert_eq!(result.events.len(), 3);
+    let health = &result.adapter_health["gemini"];
+    assert_eq!(health.status, AdapterHealthStatus::Success);
+    assert!(health.errors.is_empty());
+    assert!(
+        health
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("conversation_2026-02-04.pb"))
+    );
+}
+
+#[test]
+fn snapshot_profiles_decoded_protobuf_key_structure() {
+    let source_root = unique_temp_dir("logit-gemini-protobuf-snapshot-source");
+    let out_dir = unique_temp_dir("logit-gemini-protobuf-snapshot-out");
+    write_conversation_fixture(&source_root);
+
+    let runtime_paths = RuntimePaths {
+        home_dir: PathBuf::from("/tmp/logit-home"),
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.clone(),
+    };
+    let args = SnapshotArgs {
+        source_root: Some(source_root),
+        sample_size: 1,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_snapshot(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("snapshot command should succeed");
+
+    let layout = build_artifact_layout(&out_dir);
+    let schema_profile: Value = serde_json::from_str(
+        &std::fs::read_to_string(&layout.schema_profile_json)
+            .expect("schema profile should be readable"),
+    )
+    .expect("schema profile should be valid json");
+    let gemini_profile = schema_profile
+        .pointer("/profiles")
+        .and_then(Value::as_array)
+        .and_then(|profiles| {
+            profiles.iter().find(|profile| {
+                profile.get("source_path").and_then(Value::as_str) == Some("~/.gemini/tmp")
+            })
+        })
+        .expect("gemini tmp profile entry should exist");
+
+    assert!(gemini_profile.pointer("/key_stats/4[]").is_some());
+    assert!(gemini_profile.pointer("/key_stats/4[].2").is_some());
+    assert!(gemini_profile.pointer("/key_stats/4[].4.1").is_some());
+    assert!(gemini_profile.pointer("/key_stats/artifact_kind").is_none());
+}
@@ -0,0 +1,422 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::discovery::PipelineFilters;
+use logit::ingest::import::{IngestImportPlan, run_import};
+use logit::ingest::{IngestRefreshPlan, run_refresh};
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::sqlite::open_sqlite_connection;
+use serde_json::{Value, json};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("{prefix}-{nanos}"));
+    fs::create_dir_all(&path).expect("temp dir should be creatable");
+    path
+}
+
+fn sample_event(event_id: &str, sequence_global: u64, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global,
+        sequence_source: Some(sequence_global),
+        source_kind: AgentSource::Codex,
+        source_path: "/home/dev/.codex/sessions/rollout.jsonl".to_string(),
+        source_record_locator: format!("line:{sequence_global}"),
+        source_record_hash: Some(format!("source-{event_id}")),
+        adapter_name: AgentSource::Codex,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: format!("2026-02-25T00:00:{sequence_global:02}Z"),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence_global * 1_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some("session-1".to_string()),
+        conversation_id: Some("conversation-1".to_string()),
+        turn_id: Some(format!("turn-{sequence_global}")),
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: Some("openai".to_string()),
+        model: Some("gpt-5".to_string()),
+        content_text: Some(content.to_string()),
+        content_excerpt: Some(content.to_string()),
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: Some(1),
+        output_tokens: Some(2),
+        total_tokens: Some(3),
+        cost_usd: None,
+        tags: vec!["tag".to_string()],
+        flags: vec![],
+        pii_redacted: Some(false),
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}-{content}"),
+        canonical_hash: format!("canonical-{event_id}-{content}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn with_host(mut event: AgentLogEvent, host: &str) -> AgentLogEvent {
+    event.metadata.insert("host".to_string(), json!(host));
+    event
+}
+
+fn write_events_jsonl(path: &Path, events: &[AgentLogEvent]) {
+    let mut output = String::new();
+    for event in events {
+        output.push_str(&serde_json::to_string(event).expect("event should serialize"));
+        output.push('\n');
+    }
+    fs::write(path, output).expect("events jsonl should be writable");
+}
+
+/// Builds a mart the way `ingest refresh` does on another machine.
+fn build_mart(dir: &Path, host: &str, events: &[AgentLogEvent]) -> PathBuf {
+    fs::create_dir_all(dir).expect("mart dir should be creatable");
+    let events_path = dir.join("events.jsonl");
+    write_events_jsonl(&events_path, events);
+    let sqlite_path = dir.join("mart.sqlite");
+    run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: events_path,
+        sqlite_path: sqlite_path.clone(),
+        source_root: dir.to_path_buf(),
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: Some(host.to_string()),
+        tombstones: Vec::new(),
+    })
+    .expect("mart refresh should succeed");
+    sqlite_path
+}
+
+fn import_plan(input_path: &Path, sqlite_path: &Path, host: Option<&str>) -> IngestImportPlan {
+    IngestImportPlan {
+        input_path: input_path.to_path_buf(),
+        sqlite_path: sqlite_path.to_path_buf(),
+        host: host.map(ToOwned::to_owned),
+        run_id: None,
+        tombstones: Vec::new(),
+    }
+}
+
+fn run_logit(out_dir: &Path, args: &[&str]) -> (Option<i32>, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(out_dir)
+        .arg("--cwd")
+        .arg(out_dir)
+        .arg("--out-dir")
+        .arg(out_dir)
+        .args(args)
+        .output()
+        .expect("command should execute");
+    let envelope =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    (output.status.code(), envelope)
+}
+
+fn host_counts(sqlite_path: &Path) -> Vec<(String, i64)> {
+    let connection = open_sqlite_connection(sqlite_path).expect("mart should open");
+    let mut statement = connection
+        .prepare("SELECT host, event_count FROM v_hosts ORDER BY host")
+        .expect("host query should prepare");
+    statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("host query should run")
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .expect("host rows should decode")
+}
+
+#[test]
+fn jsonl_import_stamps_host_and_is_idempotent() {
+    let out_dir = unique_temp_dir("logit-import-jsonl");
+    let input = out_dir.join("laptop-events.jsonl");
+    write_events_jsonl(
+        &input,
+        &[
+            sample_event("event-1", 1, "first"),
+            sample_event("event-2", 2, "second"),
+        ],
+    );
+
+    let (code, envelope) = run_logit(
+        &out_dir,
+        &[
+            "ingest",
+            "import",
+            "laptop-events.jsonl",
+            "--host",
+            "Laptop",
+        ],
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["ok"], true);
+    assert_eq!(envelope["command"], "ingest.import");
+    assert_eq!(envelope["data"]["format"], "events_jsonl");
+    assert_eq!(envelope["data"]["events_inserted"], 2);
+    assert_eq!(envelope["data"]["hosts"]["laptop"]["inserted"], 2);
+
+    let (code, envelope) = run_logit(
+        &out_dir,
+        &[
+            "ingest",
+            "import",
+            "laptop-events.jsonl",
+            "--host",
+            "laptop",
+        ],
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["data"]["events_inserted"], 0);
+    assert_eq!(envelope["data"]["events_skipped"], 2);
+
+    let sqlite_path = out_dir.join("mart.sqlite");
+    assert_eq!(host_counts(&sqlite_path), vec![("laptop".to_string(), 2)]);
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    let run_hosts = connection
+        .prepare("SELECT DISTINCT host FROM ingest_runs")
+        .expect("run query should prepare")
+        .query_map([], |row| row.get::<_, Option<String>>(0))
+        .expect("run query should run")
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .expect("run rows should decode");
+    assert_eq!(run_hosts, vec![Some("laptop".to_string())]);
+}
+
+#[test]
+fn import_without_host_fails_when_events_name_none() {
+    let out_dir = unique_temp_dir("logit-import-host-missing");
+    write_events_jsonl(
+        &out_dir.join("events-in.jsonl"),
+        &[sample_event("event-1", 1, "first")],
+    );
+
+    let (code, envelope) = run_logit(&out_dir, &["ingest", "import", "events-in.jsonl"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["ok"], false);
+    assert_eq!(envelope["error"]["code"], "ingest_import_host_missing");
+    assert_eq!(envelope["error"]["details"]["events_without_host"], 1);
+    assert!(!out_dir.join("mart.sqlite").exists());
+}
+
+#[test]
+fn import_rejects_artifacts_that_fail_validation() {
+    let out_dir = unique_temp_dir("logit-import-invalid");
+    let mut event =
+        serde_json::to_value(sample_event("event-1", 1, "first")).expect("event should serialize");
+    event
+        .as_object_mut()
+        .expect("event should be an object")
+        .remove("canonical_hash");
+    fs::write(out_dir.join("broken.jsonl"), format!("{event}\n")).expect("input should write");
+
+    let (code, envelope) = run_logit(
+        &out_dir,
+        &["ingest", "import", "broken.jsonl", "--host", "laptop"],
+    );
+    assert_eq!(code, Some(2));
+    assert_eq!(envelope["error"]["code"], "ingest_import_invalid");
+    assert!(
+        envelope["error"]["details"]["errors"]
+            .as_u64()
+            .is_some_and(|errors| errors > 0)
+    );
+    assert!(!out_dir.join("mart.sqlite").exists());
+}
+
+#[test]
+fn mart_import_merges_hosts_and_rekeys_colliding_event_ids() {
+    let root = unique_temp_dir("logit-import-mart");
+    let local_mart = build_mart(
+        &root.join("local"),
+        "laptop",
+        &[
+            sample_event("event-1", 1, "laptop prompt"),
+            sample_event("event-3", 3, "laptop only"),
+        ],
+    );
+    let foreign_mart = build_mart(
+        &root.join("desktop"),
+        "desktop",
+        &[
+            sample_event("event-1", 1, "desktop prompt"),
+            sample_event("event-2", 2, "desktop only"),
+        ],
+    );
+
+    let report = run_import(&import_plan(&foreign_mart, &local_mart, None))
+        .expect("mart import should succeed");
+    assert_eq!(report.events_read, 2);
+    assert_eq!(report.events_inserted, 1);
+    assert_eq!(report.events_rekeyed, 1);
+    assert_eq!(report.hosts["desktop"].read, 2);
+
+    let connection = open_sqlite_connection(&local_mart).expect("mart should open");
+    let rekeyed_content = connection
+        .query_row(
+            "SELECT content_text FROM agentlog_events WHERE event_id = 'desktop/event-1'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("rekeyed event should exist");
+    assert_eq!(rekeyed_content, "desktop prompt");
+    let local_content = connection
+        .query_row(
+            "SELECT content_text FROM agentlog_events WHERE event_id = 'event-1'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("local event should be untouched");
+    assert_eq!(local_content, "laptop prompt");
+    assert_eq!(
+        host_counts(&local_mart),
+        vec![("desktop".to_string(), 2), ("laptop".to_string(), 2)]
+    );
+
+    let repeat = run_import(&import_plan(&foreign_mart, &local_mart, None))
+        .expect("repeat mart import should succeed");
+    assert_eq!(repeat.events_inserted, 0);
+    assert_eq!(repeat.events_rekeyed, 0);
+    assert_eq!(repeat.events_skipped, 2);
+}
+
+#[test]
+fn rekeyed_events_keep_their_references_within_the_batch() {
+    let root = unique_temp_dir("logit-import-rekey-references");
+    let local_mart = build_mart(
+        &root.join("local"),
+        "laptop",
+        &[sample_event("event-1", 1, "laptop prompt")],
+    );
+    let mut reply = with_host(sample_event("event-2", 2, "desktop reply"), "desktop");
+    reply.parent_event_id = Some("event-1".to_string());
+    let mut prompt = with_host(sample_event("event-1", 1, "desktop prompt"), "desktop");
+    prompt
+        .metadata
+        .insert("dedupe_members".to_string(), json!(["event-1"]));
+    let input = root.join("desktop.jsonl");
+    write_events_jsonl(&input, &[reply, prompt]);
+
+    let report =
+        run_import(&import_plan(&input, &local_mart, None)).expect("import should succeed");
+    assert_eq!(report.events_rekeyed, 1);
+
+    let connection = open_sqlite_connection(&local_mart).expect("mart should open");
+    let parent = connection
+        .query_row(
+            "SELECT parent_event_id FROM agentlog_events WHERE event_id = 'event-2'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("reply should exist");
+    assert_eq!(parent, "desktop/event-1");
+    let members = connection
+        .query_row(
+            "SELECT json_extract(metadata_json, '$.dedupe_members[0]')
+             FROM agentlog_events WHERE event_id = 'desktop/event-1'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("rekeyed prompt should exist");
+    assert_eq!(members, "desktop/event-1");
+}
+
+#[test]
+fn reimport_from_the_same_host_replaces_changed_events() {
+    let root = unique_temp_dir("logit-import-replace");
+    let sqlite_path = root.join("mart.sqlite");
+    let input = root.join("laptop.jsonl");
+    write_events_jsonl(&input, &[sample_event("event-1", 1, "draft")]);
+    run_import(&import_plan(&input, &sqlite_path, Some("laptop")))
+        .expect("first import should succeed");
+
+    write_events_jsonl(
+        &input,
+        &[with_host(sample_event("event-1", 1, "final"), "laptop")],
+    );
+    let report =
+        run_import(&import_plan(&input, &sqlite_path, None)).expect("reimport should succeed");
+    assert_eq!(report.events_replaced, 1);
+    assert_eq!(report.hosts["laptop"].replaced, 1);
+
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    let content = connection
+        .query_row(
+            "SELECT content_text FROM agentlog_events WHERE event_id = 'event-1'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("event should exist");
+    assert_eq!(content, "final");
+}
+
+#[test]
+fn local_refresh_does_not_mark_imported_host_watermarks_stale() {
+    let root = unique_temp_dir("logit-import-watermarks");
+    let foreign_input = root.join("desktop.jsonl");
+    write_events_jsonl(
+        &foreign_input,
+        &[with_host(sample_event("event-9", 9, "desktop"), "desktop")],
+    );
+    let local_dir = root.join("local");
+    let local_mart = build_mart(
+        &local_dir,
+        "laptop",
+        &[sample_event("event-1", 1, "laptop")],
+    );
+    run_import(&import_plan(&foreign_input, &local_mart, None)).expect("import should succeed");
+
+    write_events_jsonl(&local_dir.join("events.jsonl"), &[]);
+    run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: local_dir.join("events.jsonl"),
+        sqlite_path: local_mart.clone(),
+        source_root: local_dir.clone(),
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: Some("laptop".to_string()),
+        tombstones: Vec::new(),
+    })
+    .expect("local refresh should succeed");
+
+    let connection = open_sqlite_connection(&local_mart).expect("mart should open");
+    let states = connection
+        .prepare("SELECT host, source_key, staleness_state FROM ingest_watermarks ORDER BY host")
+        .expect("watermark query should prepare")
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .expect("watermark query should run")
+        .collect::<rusqlite::Result<Vec<(String, String, String)>>>()
+        .expect("watermark rows should decode");
+    assert_eq!(
+        states,
+        vec![
+            (
+                "desktop".to_string(),
+                "desktop|codex|/home/dev/.codex/sessions/rollout.jsonl".to_string(),
+                "fresh".to_string()
+            ),
+            (
+                "laptop".to_string(),
+                "laptop|codex|/home/dev/.codex/sessions/rollout.jsonl".to_string(),
+                "stale".to_string()
+            ),
+        ]
+    );
+}
@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+use logit::discovery::{PipelineFilters, TimeWindow};
 use logit::ingest::{
     INGEST_REPORT_SCHEMA_VERSION, IngestRefreshPlan, IngestRunStatus, ingest_report_artifact_path,
     run_refresh, write_ingest_report_artifact,
@@ -26,7 +27,7 @@ fn sample_event(
         run_id: "run-1".to_string(),
         sequence_global,
         sequence_source: Some(sequence_global),
-        source_kind,
+        source_kind: source_kind.clone(),
         source_path: source_path.to_string(),
         source_record_locator: format!("line:{sequence_global}"),
         source_record_hash: Some(format!("source-{event_id}")),
@@ -153,6 +154,10 @@ fn refresh_pipeline_writes_events_run_stats_and_watermarks() {
         sqlite_path: sqlite_path.clone(),
         source_root,
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     };
     let report = run_refresh(&plan).expect("ingest refresh should succeed");
 
@@ -223,6 +228,10 @@ fn refresh_pipeline_tolerates_invalid_rows_when_fail_fast_is_false() {
         sqlite_path: sqlite_path.clone(),
         source_root,
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     })
     .expect("ingest refresh should succeed with warning mode");
 
@@ -259,6 +268,10 @@ fn refresh_pipeline_is_idempotent_for_repeat_artifact_replay() {
         sqlite_path: sqlite_path.clone(),
         source_root,
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     };
 
     let first_report = run_refresh(&plan).expect("first ingest refresh should succeed");
@@ -307,6 +320,10 @@ fn writes_machine_readable_ingest_report_artifact() {
         sqlite_path,
         source_root: source_root.clone(),
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     })
     .expect("ingest refresh should succeed");
 
@@ -363,6 +380,10 @@ fn refresh_pipeline_marks_missing_sources_stale_in_followup_refresh() {
         sqlite_path: sqlite_path.clone(),
         source_root: source_root.clone(),
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     })
     .expect("first ingest refresh should succeed");
     assert_eq!(first_report.watermarks_upserted, 2);
@@ -382,6 +403,10 @@ fn refresh_pipeline_marks_missing_sources_stale_in_followup_refresh() {
         sqlite_path: sqlite_path.clone(),
         source_root,
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     })
     .expect("follow-up ingest refresh should succeed");
     assert_eq!(second_report.watermarks_upserted, 1);
@@ -514,6 +539,10 @@ fn refresh_pipeline_preserves_duration_rollups_in_semantic_views() {
         sqlite_path: sqlite_path.clone(),
         source_root,
         fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
     };
 
     let first_report = run_refresh(&plan).expect("first ingest refresh should succeed");
@@ -570,3 +599,230 @@ fn refresh_pipeline_preserves_duration_rollups_in_semantic_views() {
     assert_eq!(session_rollup.4, 1);
     assert_eq!(session_rollup.5, 1);
 }
+
+#[test]
+fn refresh_pipeline_records_normalize_redaction_policy_in_ingest_runs() {
+    let (source_root, events_path, sqlite_path) = temp_paths("ingest-redaction-policy");
+    let events = vec![sample_event(
+        "evt-1",
+        1,
+        AgentSource::Codex,
+        "/tmp/codex/events.jsonl",
+        1_771_977_600_001,
+    )];
+    write_events_jsonl(&events_path, &events);
+    fs::write(
+        events_path.with_file_name("stats.json"),
+        r#"{"redaction":{"policy":"secrets+pii","events_redacted":0,"class_counts":{}}}"#,
+    )
+    .expect("stats json should be writable");
+
+    let plan = IngestRefreshPlan {
+        events_jsonl_path: events_path,
+        sqlite_path: sqlite_path.clone(),
+        source_root,
+        fail_fast: true,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
+    };
+    let report = run_refresh(&plan).expect("ingest refresh should succeed");
+    assert_eq!(report.redaction_policy.as_deref(), Some("secrets+pii"));
+
+    let connection = open_sqlite_connection(&sqlite_path).expect("db should reopen");
+    let policy: Option<String> = connection
+        .query_row(
+            &format!(
+                "SELECT redaction_policy FROM {INGEST_RUNS_TABLE} WHERE ingest_run_id = ?1 LIMIT 1"
+            ),
+            [&report.ingest_run_id],
+            |row| row.get(0),
+        )
+        .expect("ingest run policy query should succeed");
+    assert_eq!(policy.as_deref(), Some("secrets+pii"));
+}
+
+#[test]
+fn filtered_refresh_records_filters_and_leaves_unobserved_sources_fresh() {
+    let (source_root, events_path, sqlite_path) = temp_paths("ingest-pipeline-filters");
+    write_events_jsonl(
+        &events_path,
+        &[
+            sample_event(
+                "evt-1",
+                1,
+                AgentSource::Codex,
+                "/tmp/codex/events.jsonl",
+                1_771_977_600_001,
+            ),
+            sample_event(
+                "evt-2",
+                2,
+                AgentSource::Claude,
+                "/tmp/claude/session.jsonl",
+                1_771_977_600_002,
+            ),
+        ],
+    );
+    run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: events_path.clone(),
+        sqlite_path: sqlite_path.clone(),
+        source_root: source_root.clone(),
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
+    })
+    .expect("first ingest refresh should succeed");
+
+    write_events_jsonl(
+        &events_path,
+        &[
+            sample_event(
+                "evt-3",
+                3,
+                AgentSource::Codex,
+                "/tmp/codex/events.jsonl",
+                1_771_977_600_010,
+            ),
+            sample_event(
+                "evt-4",
+                4,
+                AgentSource::Codex,
+                "/tmp/codex/events.jsonl",
+                1_771_977_900_000,
+            ),
+            sample_event(
+                "evt-5",
+                5,
+                AgentSource::Claude,
+                "/tmp/claude/session.jsonl",
+                1_771_977_600_020,
+            ),
+        ],
+    );
+    let report = run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: events_path,
+        sqlite_path: sqlite_path.clone(),
+        source_root,
+        fail_fast: false,
+        filters: PipelineFilters {
+            adapters: vec!["codex".to_string()],
+            window: TimeWindow {
+                since_unix_ms: None,
+                until_unix_ms: Some(1_771_977_700_000),
+            },
+            ..PipelineFilters::default()
+        },
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
+    })
+    .expect("filtered ingest refresh should succeed");
+    assert_eq!(report.events_read, 3);
+    assert_eq!(report.events_filtered, 2);
+    assert_eq!(report.events_written, 1);
+    assert!(report.filters.active);
+    assert_eq!(report.filters.events_outside_window, 1);
+    assert_eq!(report.watermark_staleness_state, "fresh");
+
+    let connection = open_sqlite_connection(&sqlite_path).expect("db should reopen");
+    let stale_count: i64 = connection
+        .query_row(
+            &format!(
+                "SELECT COUNT(*) FROM {INGEST_WATERMARKS_TABLE} WHERE staleness_state = 'stale'"
+            ),
+            [],
+            |row| row.get(0),
+        )
+        .expect("stale watermark count should succeed");
+    assert_eq!(stale_count, 0);
+
+    let filters_json: String = connection
+        .query_row(
+            &format!(
+                "SELECT filters_json FROM {INGEST_RUNS_TABLE} WHERE ingest_run_id = ?1 LIMIT 1"
+            ),
+            [&report.ingest_run_id],
+            |row| row.get(0),
+        )
+        .expect("ingest run filters query should succeed");
+    let filters: serde_json::Value =
+        serde_json::from_str(&filters_json).expect("filters json should parse");
+    assert_eq!(filters.get("active"), Some(&serde_json::json!(true)));
+    assert_eq!(filters.get("adapters"), Some(&serde_json::json!(["codex"])));
+    assert_eq!(
+        filters.get("until_utc"),
+        Some(&serde_json::json!("2026-02-25T00:01:40.000Z"))
+    );
+}
+
+#[test]
+fn refresh_marks_runs_abandoned_by_exited_processes_failed() {
+    let (source_root, events_path, sqlite_path) = temp_paths("ingest-abandoned-runs");
+    let event = sample_event(
+        "evt-1",
+        1,
+        AgentSource::Codex,
+        "/tmp/codex/events.jsonl",
+        1_771_977_600_001,
+    );
+    write_events_jsonl(&events_path, &[event]);
+    let plan = IngestRefreshPlan {
+        events_jsonl_path: events_path,
+        sqlite_path: sqlite_path.clone(),
+        source_root,
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: None,
+        tombstones: Vec::new(),
+    };
+    run_refresh(&plan).expect("first ingest refresh should succeed");
+
+    let exited_pid = std::process::Command::new("true")
+        .spawn()
+        .and_then(|mut child| child.wait().map(|_| child.id()))
+        .expect("child process should run");
+    let connection = open_sqlite_connection(&sqlite_path).expect("sqlite should open");
+    for (run_id, owner_pid) in [
+        ("crashed-run", Some(i64::from(exited_pid))),
+        ("legacy-run", None),
+        ("live-run", Some(i64::from(std::process::id()))),
+    ] {
+        connection
+            .execute(
+                &format!(
+                    "INSERT INTO {INGEST_RUNS_TABLE} (ingest_run_id, started_at_utc, status, source_root, owner_pid)
+                     VALUES (?1, '2026-01-01T00:00:00Z', 'running', '/tmp', ?2)"
+                ),
+                rusqlite::params![run_id, owner_pid],
+            )
+            .expect("running row should insert");
+    }
+    drop(connection);
+
+    let report = run_refresh(&plan).expect("second ingest refresh should succeed");
+    assert_eq!(report.recovered_runs, vec!["crashed-run", "legacy-run"]);
+
+    let connection = open_sqlite_connection(&sqlite_path).expect("sqlite should reopen");
+    let status_of = |run_id: &str| -> (String, Option<String>, String) {
+        connection
+            .query_row(
+                &format!(
+                    "SELECT status, finished_at_utc, error_summary_json FROM {INGEST_RUNS_TABLE} WHERE ingest_run_id = ?1"
+                ),
+                [run_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("run row should exist")
+    };
+    let (status, finished_at_utc, error_summary) = status_of("crashed-run");
+    assert_eq!(status, "failed");
+    assert!(finished_at_utc.is_some());
+    assert!(error_summary.contains(&report.ingest_run_id));
+    assert_eq!(status_of("legacy-run").0, "failed");
+    assert_eq!(status_of("live-run").0, "running");
+}
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::AdapterKind;
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::discovery::classifier::{
+    AdapterDetection, ContentFingerprint, detect_adapter_bytes, detect_adapter_file,
+};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../fixtures");
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn detect_fixture(relative: &str) -> AdapterDetection {
+    detect_adapter_file(&Path::new(FIXTURES).join(relative))
+        .expect("fixture detection should succeed")
+}
+
+fn normalize_inputs(
+    inputs: Vec<PathBuf>,
+    input_adapter: Option<AdapterKind>,
+    out_dir: &Path,
+) -> anyhow::Result<Vec<Value>> {
+    let args = NormalizeArgs {
+        inputs,
+        source_root: None,
+        input_adapter,
+        message_format: None,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    let runtime_paths = RuntimePaths {
+        home_dir: unique_temp_dir("logit-input-detection-home"),
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.to_path_buf(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)?;
+    Ok(std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("each event row should parse"))
+        .collect())
+}
+
+fn adapter_names(rows: &[Value]) -> Vec<&str> {
+    let mut names = rows
+        .iter()
+        .filter_map(|row| row.get("adapter_name").and_then(Value::as_str))
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+#[test]
+fn fixture_corpus_files_are_fingerprinted_by_content() {
+    let expectations = [
+        (
+            "codex/rollout_primary.jsonl",
+            ContentFingerprint::CodexRollout,
+        ),
+        (
+            "codex/history_auxiliary.jsonl",
+            ContentFingerprint::CodexHistory,
+        ),
+        (
+            "claude/project_session.jsonl",
+            ContentFingerprint::ClaudeSession,
+        ),
+        ("gemini/chat_messages.json", ContentFingerprint::GeminiChat),
+        (
+            "gemini/conversation_sample.pb",
+            ContentFingerprint::GeminiProtobuf,
+        ),
+        ("amp/thread_payloads.json", ContentFingerprint::AmpThread),
+        (
+            "opencode/session_parts.jsonl",
+            ContentFingerprint::OpenCodeParts,
+        ),
+        (
+            "opencode/session_messages.jsonl",
+            ContentFingerprint::OpenCodeMessages,
+        ),
+        (
+            "aider/chat_history.md",
+            ContentFingerprint::AiderChatHistory,
+        ),
+        ("aider/input.history", ContentFingerprint::AiderInputHistory),
+        ("aider/llm.history", ContentFingerprint::AiderLlmHistory),
+        ("cursor/state.vscdb", ContentFingerprint::CursorStateDb),
+        ("copilot/state.vscdb", ContentFingerprint::CopilotStateDb),
+        (
+            "copilot/chatSessions/session.json",
+            ContentFingerprint::CopilotChatSession,
+        ),
+    ];
+    for (relative, expected) in expectations {
+        assert_eq!(
+            detect_fixture(relative),
+            AdapterDetection::Detected(expected),
+            "unexpected detection for {relative}"
+        );
+    }
+}
+
+#[test]
+fn native_record_shapes_are_fingerprinted_regardless_of_extension() {
+    let codex = concat!(
+        r#"{"timestamp":"2026-02-01T12:00:00Z","type":"session_meta","payload":{"id":"s-1"}}"#,
+        "\n",
+        r#"{"timestamp":"2026-02-01T12:00:01Z","type":"response_item","payload":{"type":"message"}}"#,
+        "\n"
+    );
+    assert_eq!(
+        detect_adapter_bytes(Path::new("export.txt"), codex.as_bytes()),
+        AdapterDetection::Detected(ContentFingerprint::CodexRollout)
+    );
+
+    let claude = r#"{"parentUuid":null,"sessionId":"c-1","uuid":"u-1","type":"user","message":{"role":"user","content":"hi"}}"#;
+    assert_eq!(
+        detect_adapter_bytes(Path::new("session"), claude.as_bytes()),
+        AdapterDetection::Detected(ContentFingerprint::ClaudeSession)
+    );
+
+    let gemini =
+        r#"{"sessionId":"g-1","projectHash":"abc","messages":[{"type":"user","content":"hi"}]}"#;
+    assert_eq!(
+        detect_adapter_bytes(Path::new("session.json"), gemini.as_bytes()),
+        AdapterDetection::Detected(ContentFingerprint::GeminiChat)
+    );
+
+    let gemini_logs = r#"[{"sessionId":"g-1","messageId":0,"type":"user","message":"hi"}]"#;
+    assert_eq!(
+        detect_adapter_bytes(Path::new("logs.json"), gemini_logs.as_bytes()),
+        AdapterDetection::Detected(ContentFingerprint::GeminiLogs)
+    );
+
+    assert_eq!(
+        detect_adapter_bytes(Path::new("notes.json"), br#"{"hello":"world"}"#),
+        AdapterDetection::Unknown
+    );
+}
+
+#[test]
+fn mixed_adapter_records_are_ambiguous_until_overridden() {
+    let mixed = concat!(
+        r#"{"type":"session_meta","payload":{"id":"s-1"}}"#,
+        "\n",
+        r#"{"parentUuid":null,"sessionId":"c-1","uuid":"u-1"}"#,
+        "\n"
+    );
+    let detection = detect_adapter_bytes(Path::new("mixed.jsonl"), mixed.as_bytes());
+    assert_eq!(
+        detection,
+        AdapterDetection::Ambiguous(vec![
+            ContentFingerprint::CodexRollout,
+            ContentFingerprint::ClaudeSession,
+        ])
+    );
+    assert_eq!(
+        detection.resolve_with_override(AdapterKind::Claude, Path::new("mixed.jsonl")),
+        ContentFingerprint::ClaudeSession
+    );
+    assert_eq!(
+        AdapterDetection::Unknown.resolve_with_override(AdapterKind::Gemini, Path::new("a.pb")),
+        ContentFingerprint::GeminiProtobuf
+    );
+}
+
+#[test]
+fn normalize_accepts_an_explicit_file_outside_known_paths() {
+    let input = unique_temp_dir("logit-input-detection-file").join("shared-export.log");
+    write_file(
+        &input,
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    let out_dir = unique_temp_dir("logit-input-detection-file-out");
+
+    let rows = normalize_inputs(vec![input.clone()], None, &out_dir)
+        .expect("normalize should succeed for explicit file");
+    assert!(!rows.is_empty());
+    assert_eq!(adapter_names(&rows), vec!["codex"]);
+    assert!(rows.iter().all(|row| {
+        row.get("source_path").and_then(Value::as_str) == Some(input.to_string_lossy().as_ref())
+    }));
+}
+
+#[test]
+fn normalize_walks_explicit_directories_and_detects_each_file() {
+    let input_dir = unique_temp_dir("logit-input-detection-dir");
+    write_file(
+        &input_dir.join("colleague/claude.jsonl"),
+        include_str!("../../../fixtures/claude/project_session.jsonl"),
+    );
+    write_file(
+        &input_dir.join("colleague/tmp/abc/chats/chat.json"),
+        include_str!("../../../fixtures/gemini/chat_messages.json"),
+    );
+    write_file(
+        &input_dir.join("colleague/settings.json"),
+        r#"{"theme":"dark"}"#,
+    );
+    let out_dir = unique_temp_dir("logit-input-detection-dir-out");
+
+    let rows = normalize_inputs(vec![input_dir], None, &out_dir)
+        .expect("normalize should succeed for explicit directory");
+    assert_eq!(adapter_names(&rows), vec!["claude", "gemini"]);
+}
+
+#[test]
+fn normalize_skips_ambiguous_inputs_unless_adapter_is_forced() {
+    let input = unique_temp_dir("logit-input-detection-ambiguous").join("mixed.jsonl");
+    write_file(
+        &input,
+        &format!(
+            "{}{}",
+            include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+            r#"{"parentUuid":null,"sessionId":"c-1","uuid":"u-1"}"#
+        ),
+    );
+
+    let auto_rows = normalize_inputs(
+        vec![input.clone()],
+        None,
+        &unique_temp_dir("logit-input-detection-ambiguous-auto"),
+    )
+    .expect("ambiguous input should warn, not fail");
+    assert!(auto_rows.is_empty());
+
+    let forced_rows = normalize_inputs(
+        vec![input],
+        Some(AdapterKind::Codex),
+        &unique_temp_dir("logit-input-detection-ambiguous-forced"),
+    )
+    .expect("forced adapter should normalize ambiguous input");
+    assert_eq!(adapter_names(&forced_rows), vec!["codex"]);
+}
+
+#[test]
+fn normalize_rejects_missing_explicit_input() {
+    let missing = unique_temp_dir("logit-input-detection-missing").join("nope.jsonl");
+    let result = normalize_inputs(
+        vec![missing],
+        None,
+        &unique_temp_dir("logit-input-detection-missing-out"),
+    );
+    let Err(error) = result else {
+        assert!(result.is_err());
+        return;
+    };
+    assert!(format!("{error:#}").contains("normalize input not found"));
+}
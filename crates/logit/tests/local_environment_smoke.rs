@@ -2,14 +2,16 @@ use std::collections::BTreeSet;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::discovery::build_artifact_layout as build_discovery_artifact_layout;
 use logit::models::AgentSource;
 use logit::normalize::{
     build_artifact_layout as build_normalize_artifact_layout, default_plan,
     orchestrate_normalization,
 };
+use logit::utils::redaction::RedactionPolicy;
 use serde_json::Value;
 
 fn unique_temp_dir(prefix: &str) -> PathBuf {
@@ -128,11 +130,18 @@ fn normalize_command_smoke_writes_artifacts_for_local_layout() {
         out_dir: out_dir.clone(),
     };
     let args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
         fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_normalize(&args, &runtime_paths)
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
         .expect("normalize should succeed in local-layout smoke run");
 
     let normalize_layout = build_normalize_artifact_layout(&out_dir);
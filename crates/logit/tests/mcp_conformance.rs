@@ -0,0 +1,418 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use logit::mcp::{
+    INVALID_PARAMS, METHOD_NOT_FOUND, McpServer, PARSE_ERROR, PROTOCOL_VERSION, RESOURCE_NOT_FOUND,
+};
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::sqlite::migrations::migration_status;
+use logit::sqlite::{
+    SCHEMA_META_TABLE, SqliteWriterConfig, open_sqlite_connection, write_events_to_sqlite,
+};
+use serde_json::{Value, json};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn sample_event(event_id: &str, session_id: &str, sequence: u64, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: sequence,
+        sequence_source: Some(sequence),
+        source_kind: AgentSource::Gemini,
+        source_path: "/tmp/gemini/chat.json".to_string(),
+        source_record_locator: format!("message:{sequence}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Gemini,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: format!("2026-02-25T00:00:0{sequence}Z"),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence * 1_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(session_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn seed_mart(out_dir: &Path) {
+    let events = vec![
+        sample_event("evt-1", "session-a", 1, "flaky migration test on ci"),
+        sample_event("evt-2", "session-a", 2, "rerun the migration with logging"),
+        sample_event("evt-3", "session-b", 3, "bump the toolchain"),
+    ];
+    write_events_to_sqlite(
+        &out_dir.join("mart.sqlite"),
+        &events,
+        SqliteWriterConfig::default(),
+    )
+    .expect("events should be written");
+}
+
+fn request(id: u64, method: &str, params: Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string()
+}
+
+fn tool_call(id: u64, name: &str, arguments: Value) -> String {
+    request(
+        id,
+        "tools/call",
+        json!({ "name": name, "arguments": arguments }),
+    )
+}
+
+/// Drives `logit mcp` over stdin/stdout pipes and returns responses keyed by id.
+fn drive(out_dir: &Path, messages: &[String]) -> (BTreeMap<String, Value>, usize) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(out_dir)
+        .arg("--cwd")
+        .arg(out_dir)
+        .arg("--out-dir")
+        .arg(out_dir)
+        .arg("mcp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("mcp server should start");
+    {
+        let mut stdin = child.stdin.take().expect("stdin should be piped");
+        for message in messages {
+            writeln!(stdin, "{message}").expect("message should be written");
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .expect("mcp server should exit on eof");
+    assert_eq!(output.status.code(), Some(0));
+
+    let stdout = String::from_utf8(output.stdout).expect("stdout should be utf-8");
+    let responses = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("every stdout line is json-rpc"))
+        .collect::<Vec<_>>();
+    let count = responses.len();
+    let by_id = responses
+        .into_iter()
+        .map(|response| {
+            assert_eq!(response["jsonrpc"], "2.0");
+            (response["id"].to_string(), response)
+        })
+        .collect();
+    (by_id, count)
+}
+
+fn structured(response: &Value) -> &Value {
+    &response["result"]["structuredContent"]
+}
+
+#[test]
+fn mcp_handshake_lists_tools_and_ignores_notifications() {
+    let out_dir = unique_temp_dir("logit-mcp-handshake");
+    seed_mart(&out_dir);
+    let messages = [
+        request(
+            1,
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "conformance", "version": "0" }
+            }),
+        ),
+        json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }).to_string(),
+        request(2, "ping", json!({})),
+        request(3, "tools/list", json!({})),
+        request(4, "initialize", json!({ "protocolVersion": "1999-01-01" })),
+    ];
+    let (responses, count) = drive(&out_dir, &messages);
+    assert_eq!(count, 4, "notifications must not produce responses");
+
+    let init = &responses["1"]["result"];
+    assert_eq!(init["protocolVersion"], "2024-11-05");
+    assert_eq!(init["serverInfo"]["name"], "logit");
+    assert!(init["capabilities"]["tools"].is_object());
+    assert!(init["capabilities"]["resources"].is_object());
+    assert_eq!(
+        responses["4"]["result"]["protocolVersion"],
+        PROTOCOL_VERSION
+    );
+    assert_eq!(responses["2"]["result"], json!({}));
+
+    let tools = responses["3"]["result"]["tools"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let names = tools
+        .iter()
+        .filter_map(|tool| tool["name"].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "query_sql",
+            "query_schema",
+            "query_catalog",
+            "search_transcripts",
+            "list_sessions",
+            "get_session_transcript"
+        ]
+    );
+    assert!(
+        tools
+            .iter()
+            .all(|tool| tool["inputSchema"]["type"] == "object")
+    );
+}
+
+#[test]
+fn mcp_tools_return_query_envelopes_with_shared_guardrails() {
+    let out_dir = unique_temp_dir("logit-mcp-tools");
+    seed_mart(&out_dir);
+    let messages = [
+        tool_call(
+            1,
+            "query_sql",
+            json!({
+                "sql": "SELECT event_id FROM agentlog_events WHERE session_id = ?1 ORDER BY event_id",
+                "params": ["session-a"],
+                "row_cap": 1
+            }),
+        ),
+        tool_call(
+            2,
+            "query_sql",
+            json!({ "sql": "DROP TABLE agentlog_events" }),
+        ),
+        tool_call(3, "query_sql", json!({ "sql": "SELECT 1", "row_cap": 0 })),
+        tool_call(4, "query_schema", json!({})),
+        tool_call(5, "query_catalog", json!({ "verbose": false })),
+        tool_call(6, "search_transcripts", json!({ "terms": "migration" })),
+        tool_call(7, "list_sessions", json!({})),
+        tool_call(
+            8,
+            "get_session_transcript",
+            json!({ "session_id": "session-a", "format": "markdown" }),
+        ),
+        tool_call(
+            9,
+            "get_session_transcript",
+            json!({ "session_id": "missing" }),
+        ),
+        tool_call(10, "query_sql", json!({ "statement": "SELECT 1" })),
+        tool_call(11, "drop_everything", json!({})),
+    ];
+    let (responses, _) = drive(&out_dir, &messages);
+
+    let sql = &responses["1"];
+    assert_eq!(sql["result"]["isError"], false);
+    assert_eq!(structured(sql)["command"], "query.sql");
+    assert_eq!(
+        structured(sql)["data"]["rows"],
+        json!([{ "event_id": "evt-1" }])
+    );
+    assert_eq!(structured(sql)["meta"]["truncated"], true);
+    let text = sql["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap_or_default();
+    assert_eq!(
+        serde_json::from_str::<Value>(text).ok().as_ref(),
+        Some(structured(sql))
+    );
+
+    assert_eq!(responses["2"]["result"]["isError"], true);
+    assert_eq!(
+        structured(&responses["2"])["error"]["code"],
+        "sql_guardrail_violation"
+    );
+    assert_eq!(
+        structured(&responses["3"])["error"]["code"],
+        "query_row_cap_invalid"
+    );
+    assert_eq!(structured(&responses["4"])["command"], "query.schema");
+    assert_eq!(structured(&responses["5"])["meta"]["concept_count"], 5);
+    assert_eq!(structured(&responses["6"])["meta"]["row_count"], 2);
+    assert_eq!(
+        structured(&responses["7"])["data"]["sessions"][0]["session_id"],
+        "session-b"
+    );
+    let document = structured(&responses["8"])["data"]["document"]
+        .as_str()
+        .unwrap_or_default();
+    assert!(document.contains("rerun the migration with logging"));
+    assert_eq!(
+        structured(&responses["9"])["error"]["code"],
+        "session_not_found"
+    );
+
+    assert_eq!(responses["10"]["error"]["code"], INVALID_PARAMS);
+    assert_eq!(responses["11"]["error"]["code"], INVALID_PARAMS);
+}
+
+#[test]
+fn mcp_search_never_creates_or_migrates_the_mart() {
+    let out_dir = unique_temp_dir("logit-mcp-search-read-only");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let sqlite_path = out_dir.join("mart.sqlite");
+    let (responses, _) = drive(
+        &out_dir,
+        &[tool_call(
+            1,
+            "search_transcripts",
+            json!({ "terms": "migration" }),
+        )],
+    );
+    assert_eq!(responses["1"]["result"]["isError"], true);
+    assert_eq!(
+        structured(&responses["1"])["error"]["code"],
+        "query_mart_unavailable"
+    );
+    assert!(!sqlite_path.exists());
+
+    seed_mart(&out_dir);
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    connection
+        .execute_batch(&format!(
+            "UPDATE {SCHEMA_META_TABLE} SET migration_version = 2, migration_name = 'ingest_run_metadata'"
+        ))
+        .expect("migration version should rewind");
+    let (responses, _) = drive(
+        &out_dir,
+        &[tool_call(
+            2,
+            "search_transcripts",
+            json!({ "terms": "migration" }),
+        )],
+    );
+    assert_eq!(
+        structured(&responses["2"])["error"]["code"],
+        "query_schema_migration_pending"
+    );
+    let status = migration_status(&connection).expect("status should load");
+    assert_eq!(status.current_version, 2);
+}
+
+#[test]
+fn mcp_resources_expose_catalog_concepts() {
+    let out_dir = unique_temp_dir("logit-mcp-resources");
+    let messages = [
+        request(1, "resources/list", json!({})),
+        request(
+            2,
+            "resources/read",
+            json!({ "uri": "logit://catalog/concepts/sessions" }),
+        ),
+        request(3, "resources/read", json!({ "uri": "logit://catalog" })),
+        request(
+            4,
+            "resources/read",
+            json!({ "uri": "logit://catalog/concepts/nope" }),
+        ),
+        request(5, "resources/templates/list", json!({})),
+    ];
+    let (responses, _) = drive(&out_dir, &messages);
+
+    let uris = responses["1"]["result"]["resources"]
+        .as_array()
+        .map(|resources| {
+            resources
+                .iter()
+                .filter_map(|resource| resource["uri"].as_str())
+                .map(ToOwned::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    assert_eq!(uris.len(), 6);
+    assert_eq!(uris[0], "logit://catalog");
+    assert!(uris.contains(&"logit://catalog/concepts/tool_calls".to_string()));
+
+    let contents = &responses["2"]["result"]["contents"][0];
+    assert_eq!(contents["mimeType"], "application/json");
+    let concept: Value = serde_json::from_str(contents["text"].as_str().unwrap_or_default())
+        .expect("resource text should be json");
+    assert_eq!(concept["concept_id"], "sessions");
+    assert_eq!(concept["primary_relation"], "v_sessions");
+
+    let catalog: Value = serde_json::from_str(
+        responses["3"]["result"]["contents"][0]["text"]
+            .as_str()
+            .unwrap_or_default(),
+    )
+    .expect("catalog text should be json");
+    assert!(catalog["relations"].is_array());
+    assert_eq!(responses["4"]["error"]["code"], RESOURCE_NOT_FOUND);
+    assert_eq!(
+        responses["5"]["result"]["resourceTemplates"][0]["uriTemplate"],
+        "logit://catalog/concepts/{concept_id}"
+    );
+}
+
+#[test]
+fn mcp_reports_protocol_errors() {
+    let server = McpServer::new(unique_temp_dir("logit-mcp-protocol").join("mart.sqlite"));
+
+    let parse = server
+        .handle_message("{not json")
+        .expect("parse errors get a response");
+    assert_eq!(parse["id"], Value::Null);
+    assert_eq!(parse["error"]["code"], PARSE_ERROR);
+
+    let unknown = server
+        .handle_message(&request(7, "prompts/list", json!({})))
+        .expect("unknown methods get a response");
+    assert_eq!(unknown["id"], 7);
+    assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+
+    let missing_mart = server
+        .handle_message(&tool_call(8, "list_sessions", json!({})))
+        .expect("tool calls get a response");
+    assert_eq!(missing_mart["result"]["isError"], true);
+    assert_eq!(
+        missing_mart["result"]["structuredContent"]["error"]["code"],
+        "query_mart_unavailable"
+    );
+
+    assert!(
+        server
+            .handle_message(r#"{"jsonrpc":"2.0","method":"notifications/cancelled","params":{}}"#)
+            .is_none()
+    );
+}
@@ -2,14 +2,18 @@ use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
-use logit::config::RuntimePaths;
+use logit::config::settings::ModelPricing;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::models::{
     ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
 };
 use logit::normalize::{
-    DedupeStats, build_artifact_layout, build_normalize_stats, write_events_artifact,
+    COST_SOURCE_METADATA_KEY, DedupeStats, apply_pricing, build_artifact_layout,
+    build_normalize_stats, write_events_artifact,
 };
+use logit::utils::redaction::RedactionPolicy;
 use serde_json::Value;
 
 fn fixture_event(event_id: &str) -> AgentLogEvent {
@@ -138,6 +142,7 @@ fn normalize_stats_aggregate_counts_and_breakdowns() {
             unique_records: 2,
             duplicate_records: 1,
         },
+        RedactionPolicy::SecretsOnly,
     );
 
     assert_eq!(stats.schema_version, "agentlog.v1");
@@ -155,6 +160,43 @@ fn normalize_stats_aggregate_counts_and_breakdowns() {
     assert_eq!(stats.timestamp_quality_counts.get("exact"), Some(&1));
     assert_eq!(stats.timestamp_quality_counts.get("fallback"), Some(&1));
     assert_eq!(stats.timestamp_quality_counts.get("derived"), Some(&0));
+    assert_eq!(stats.redaction.policy, "secrets-only");
+    assert_eq!(stats.redaction.events_redacted, 0);
+}
+
+#[test]
+fn pricing_fills_in_missing_costs_for_priced_models() {
+    let mut priced = fixture_event("evt-1");
+    priced.model = Some("gpt-5".to_string());
+    priced.input_tokens = Some(200_000);
+    priced.output_tokens = Some(50_000);
+    let mut reported = priced.clone();
+    reported.event_id = "evt-2".to_string();
+    reported.cost_usd = Some(9.0);
+    let mut unpriced = priced.clone();
+    unpriced.event_id = "evt-3".to_string();
+    unpriced.model = Some("other-model".to_string());
+    let mut events = vec![priced, reported, unpriced];
+
+    let pricing = BTreeMap::from([(
+        "gpt-5".to_string(),
+        ModelPricing {
+            input_per_mtok: 1.25,
+            output_per_mtok: 10.0,
+            cached_input_per_mtok: None,
+        },
+    )]);
+    apply_pricing(&mut events, &pricing);
+
+    let cost = events[0].cost_usd.expect("priced event should get a cost");
+    assert!((cost - 0.75).abs() < 1e-9, "{cost}");
+    assert_eq!(
+        events[0].metadata.get(COST_SOURCE_METADATA_KEY),
+        Some(&Value::from("pricing"))
+    );
+    assert_eq!(events[1].cost_usd, Some(9.0));
+    assert!(!events[1].metadata.contains_key(COST_SOURCE_METADATA_KEY));
+    assert_eq!(events[2].cost_usd, None);
 }
 
 #[test]
@@ -166,11 +208,19 @@ fn normalize_command_emits_all_normalize_artifacts() {
         out_dir: out_dir.clone(),
     };
     let args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: Some(PathBuf::from("/tmp/source-root")),
+        input_adapter: None,
+        message_format: None,
         fail_fast: true,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_normalize(&args, &runtime_paths).expect("normalize command should succeed");
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize command should succeed");
 
     let layout = build_artifact_layout(&out_dir);
     assert!(layout.events_jsonl.exists(), "events artifact should exist");
@@ -2,10 +2,12 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use logit::adapters::AdapterKind;
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::models::AgentSource;
 use logit::normalize::{default_plan, orchestrate_normalization};
+use logit::utils::redaction::RedactionPolicy;
 use serde_json::Value;
 
 fn unique_temp_dir(prefix: &str) -> PathBuf {
@@ -240,11 +242,19 @@ fn normalize_command_emits_artifacts_from_orchestrated_sources() {
         out_dir: out_dir.clone(),
     };
     let args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: Some(source_root.clone()),
+        input_adapter: None,
+        message_format: None,
         fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_normalize(&args, &runtime_paths).expect("normalize run should succeed");
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize run should succeed");
 
     let events_path = out_dir.join("events.jsonl");
     let events_content =
@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::normalize::{
+    REDACTION_CLASSES_METADATA_KEY, build_normalize_stats, dedupe_and_sort_events, redact_event,
+    redact_events,
+};
+use logit::utils::redaction::{RedactionPolicy, RedactionRules};
+
+fn sample_event(event_id: &str, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: 0,
+        sequence_source: Some(1),
+        source_kind: AgentSource::Codex,
+        source_path: "/tmp/codex/session.jsonl".to_string(),
+        source_record_locator: format!("line:{event_id}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Codex,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: "2026-02-25T00:00:00Z".to_string(),
+        timestamp_unix_ms: 1_771_977_600_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some("session-1".to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: Some(content.to_string()),
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn redaction_policy_parses_names_and_aliases() {
+    assert_eq!(RedactionPolicy::default(), RedactionPolicy::SecretsOnly);
+    assert_eq!(RedactionPolicy::parse("off"), Ok(RedactionPolicy::Off));
+    assert_eq!(
+        RedactionPolicy::parse("secrets+pii"),
+        Ok(RedactionPolicy::SecretsAndPii)
+    );
+    assert!(RedactionPolicy::parse("everything").is_err());
+}
+
+#[test]
+fn secrets_only_policy_keeps_pii_and_redacts_tool_fields() {
+    let mut event = sample_event("evt-1", "mail ada@example.com the key sk-abcdefghijklmnop");
+    event.record_format = RecordFormat::ToolCall;
+    event.tool_arguments_json =
+        Some(r#"{"env":{"API_KEY":"x"},"header":"Bearer abcdefgh12345678"}"#.to_string());
+    event.tool_result_text = Some("password=hunter22".to_string());
+
+    redact_event(
+        &mut event,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+    );
+
+    assert_eq!(
+        event.content_text.as_deref(),
+        Some("mail ada@example.com the key [REDACTED]")
+    );
+    assert_eq!(event.content_excerpt, event.content_text);
+    assert_eq!(
+        event.tool_arguments_json.as_deref(),
+        Some(r#"{"env":{"API_KEY":"x"},"header":"Bearer [REDACTED]"}"#)
+    );
+    assert_eq!(
+        event.tool_result_text.as_deref(),
+        Some("password=[REDACTED]")
+    );
+    assert_eq!(event.pii_redacted, Some(true));
+    assert_eq!(
+        event.metadata.get(REDACTION_CLASSES_METADATA_KEY),
+        Some(&serde_json::json!([
+            "api_token",
+            "bearer_token",
+            "secret_assignment"
+        ]))
+    );
+    assert_ne!(event.canonical_hash, "canonical-evt-1");
+}
+
+#[test]
+fn pii_policy_redacts_contacts_and_off_leaves_events_untouched() {
+    let mut event = sample_event("evt-1", "call 555-010-9999 or ada@example.com");
+    redact_event(
+        &mut event,
+        RedactionPolicy::SecretsAndPii,
+        &RedactionRules::default(),
+    );
+    assert_eq!(
+        event.content_text.as_deref(),
+        Some("call [REDACTED] or [REDACTED]")
+    );
+    assert_eq!(
+        event.metadata.get(REDACTION_CLASSES_METADATA_KEY),
+        Some(&serde_json::json!(["email", "phone"]))
+    );
+
+    let mut untouched = sample_event("evt-2", "token=abc12345");
+    redact_event(
+        &mut untouched,
+        RedactionPolicy::Off,
+        &RedactionRules::default(),
+    );
+    assert_eq!(untouched.content_text.as_deref(), Some("token=abc12345"));
+    assert_eq!(untouched.pii_redacted, None);
+    assert_eq!(untouched.canonical_hash, "canonical-evt-2");
+
+    let mut clean = sample_event("evt-3", "nothing to see");
+    redact_event(
+        &mut clean,
+        RedactionPolicy::SecretsAndPii,
+        &RedactionRules::default(),
+    );
+    assert_eq!(clean.pii_redacted, Some(false));
+    assert_eq!(clean.canonical_hash, "canonical-evt-3");
+    assert!(clean.metadata.is_empty());
+}
+
+#[test]
+fn redacted_duplicates_collapse_and_stats_record_policy() {
+    let mut events = vec![
+        sample_event("evt-1", "token=abc12345"),
+        sample_event("evt-1", "token=abc12345"),
+        sample_event("evt-2", "plain"),
+    ];
+    redact_events(
+        &mut events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+    );
+    assert_eq!(events[0].canonical_hash, events[1].canonical_hash);
+
+    let (events, dedupe_stats) = dedupe_and_sort_events(events);
+    assert_eq!(events.len(), 2);
+
+    let stats = build_normalize_stats(&events, dedupe_stats, RedactionPolicy::SecretsOnly);
+    assert_eq!(stats.redaction.policy, "secrets-only");
+    assert_eq!(stats.redaction.events_redacted, 1);
+    assert_eq!(
+        stats.redaction.class_counts.get("secret_assignment"),
+        Some(&1)
+    );
+}
+
+#[test]
+fn redaction_keeps_path_independent_hashes_deduplicating() {
+    let mut history = sample_event("evt-1", "deploy with password=hunter22");
+    history.source_path = "/tmp/codex/history.jsonl".to_string();
+    history.canonical_hash = "codex-conversation-1".to_string();
+    let mut rollout = sample_event("evt-1", "deploy with password=hunter22");
+    rollout.source_path = "/tmp/codex/sessions/rollout.jsonl".to_string();
+    rollout.source_record_locator = "line:7".to_string();
+    rollout.canonical_hash = "codex-conversation-1".to_string();
+    let mut events = vec![history, rollout];
+
+    redact_events(
+        &mut events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+    );
+    assert_eq!(events[0].pii_redacted, Some(true));
+    assert_eq!(events[0].canonical_hash, events[1].canonical_hash);
+    assert_ne!(events[0].canonical_hash, "codex-conversation-1");
+
+    let (events, dedupe_stats) = dedupe_and_sort_events(events);
+    assert_eq!(events.len(), 1);
+    assert_eq!(dedupe_stats.duplicate_records, 1);
+}
+
+#[test]
+fn redacted_hashes_do_not_depend_on_the_secret_value() {
+    let mut first = sample_event("evt-1", "deploy with password=hunter22");
+    first.source_record_hash = Some("record-evt-1".to_string());
+    let mut second = sample_event("evt-1", "deploy with password=correcthorse");
+    second.canonical_hash = "canonical-other".to_string();
+    second.raw_hash = "raw-other".to_string();
+    let mut events = vec![first, second];
+
+    redact_events(
+        &mut events,
+        RedactionPolicy::SecretsOnly,
+        &RedactionRules::default(),
+    );
+
+    assert_eq!(events[0].content_text, events[1].content_text);
+    assert_eq!(events[0].canonical_hash, events[1].canonical_hash);
+    assert_eq!(events[0].raw_hash, events[1].raw_hash);
+    assert_ne!(events[0].raw_hash, "raw-evt-1");
+    assert_eq!(events[0].source_record_hash, None);
+}
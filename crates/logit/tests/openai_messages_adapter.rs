@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::adapters::custom::{parse_custom_records, parse_spec_json, parse_spec_toml};
+use logit::adapters::openai_messages::MessagePreset;
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::models::{ActorRole, AgentSource, EventType, RecordFormat, TimestampQuality};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+const TRANSCRIPT_FIXTURE: &str = include_str!("../../../fixtures/openai_messages/transcript.jsonl");
+const GOOSE_FIXTURE: &str = include_str!("../../../fixtures/goose/session.jsonl");
+
+const SCRIPTS_SPEC: &str = r#"
+name = "build-bot"
+paths = ["~/transcripts"]
+file_globs = ["*.jsonl"]
+preset = "openai-messages"
+"#;
+
+const GOOSE_SPEC: &str = r#"
+name = "goose"
+version = "v1"
+paths = ["~/.local/share/goose/sessions"]
+preset = "goose"
+"#;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+#[test]
+fn openai_messages_preset_pairs_tool_calls_and_results() {
+    let spec = parse_spec_toml(SCRIPTS_SPEC).expect("preset spec should parse");
+    let parsed = parse_custom_records(
+        &spec,
+        TRANSCRIPT_FIXTURE,
+        "run-openai",
+        "/data/transcripts/release-debug.jsonl",
+    )
+    .expect("transcript should parse");
+
+    assert_eq!(parsed.events.len(), 8);
+    assert_eq!(parsed.warnings.len(), 1);
+    assert!(parsed.warnings[0].contains("tool result `call_unknown` has no matching tool call"));
+
+    let source = AgentSource::Custom("build-bot".to_string());
+    assert!(parsed.events.iter().all(|event| {
+        event.source_kind == source
+            && event.session_id.as_deref() == Some("release-debug")
+            && event.tags == ["build-bot", "custom_adapter", "openai-messages"]
+    }));
+
+    assert_eq!(parsed.events[0].record_format, RecordFormat::System);
+    assert_eq!(parsed.events[1].event_type, EventType::Prompt);
+
+    let ci_call = &parsed.events[2];
+    assert_eq!(ci_call.record_format, RecordFormat::ToolCall);
+    assert_eq!(ci_call.tool_name.as_deref(), Some("ci_logs"));
+    assert_eq!(ci_call.tool_call_id.as_deref(), Some("call_ci"));
+    assert_eq!(
+        ci_call.tool_arguments_json.as_deref(),
+        Some(r#"{"job":"release"}"#)
+    );
+    assert_eq!(ci_call.model.as_deref(), Some("gpt-4o-2024-08-06"));
+    assert_eq!(ci_call.input_tokens, Some(180));
+    assert_eq!(ci_call.output_tokens, Some(42));
+    assert_eq!(ci_call.total_tokens, Some(222));
+    assert_eq!(parsed.events[3].total_tokens, None);
+
+    let ci_result = &parsed.events[4];
+    assert_eq!(ci_result.record_format, RecordFormat::ToolResult);
+    assert_eq!(ci_result.role, ActorRole::Tool);
+    assert_eq!(ci_result.tool_name.as_deref(), Some("ci_logs"));
+    assert_eq!(ci_result.tool_call_id.as_deref(), Some("call_ci"));
+    assert_eq!(
+        ci_result.tool_result_text.as_deref(),
+        Some("error: linker `cc` not found")
+    );
+    assert_eq!(ci_result.timestamp_quality, TimestampQuality::Derived);
+    assert_eq!(ci_result.timestamp_utc, "2026-02-10T14:00:03.001Z");
+
+    let diff_result = &parsed.events[5];
+    assert_eq!(diff_result.tool_name.as_deref(), Some("git_diff"));
+    assert_eq!(
+        diff_result.tool_result_text.as_deref(),
+        Some("M ci/release.yml")
+    );
+
+    let response = &parsed.events[6];
+    assert_eq!(response.event_type, EventType::Response);
+    assert_eq!(response.input_tokens, Some(260));
+    assert_eq!(response.output_tokens, Some(18));
+    assert_eq!(response.total_tokens, Some(278));
+
+    assert_eq!(parsed.events[7].tool_name, None);
+}
+
+#[test]
+fn goose_preset_reads_session_header_and_content_parts() {
+    let spec = parse_spec_toml(GOOSE_SPEC).expect("goose spec should parse");
+    let parsed = parse_custom_records(
+        &spec,
+        GOOSE_FIXTURE,
+        "run-goose",
+        "/home/u/.local/share/goose/sessions/20260210_150000.jsonl",
+    )
+    .expect("goose session should parse");
+
+    assert!(
+        parsed.warnings.is_empty(),
+        "warnings: {:?}",
+        parsed.warnings
+    );
+    assert_eq!(parsed.events.len(), 5);
+    assert!(parsed.events.iter().all(|event| {
+        event.session_id.as_deref() == Some("20260210_150000")
+            && event.metadata.get("goose_description") == Some(&serde_json::json!("Fix flaky test"))
+    }));
+
+    assert_eq!(parsed.events[0].event_type, EventType::Prompt);
+    assert_eq!(parsed.events[0].timestamp_utc, "2026-02-10T15:00:00.000Z");
+    assert_eq!(parsed.events[1].event_type, EventType::Response);
+
+    let call = &parsed.events[2];
+    assert_eq!(call.tool_name.as_deref(), Some("developer__shell"));
+    assert_eq!(call.tool_call_id.as_deref(), Some("toolu_01"));
+    assert_eq!(
+        call.tool_arguments_json.as_deref(),
+        Some(r#"{"command":"cargo test retry -- --test-threads=1"}"#)
+    );
+
+    let result = &parsed.events[3];
+    assert_eq!(result.record_format, RecordFormat::ToolResult);
+    assert_eq!(result.tool_name.as_deref(), Some("developer__shell"));
+    assert_eq!(
+        result.tool_result_text.as_deref(),
+        Some("test retry::backoff ... FAILED")
+    );
+}
+
+#[test]
+fn preset_specs_reject_field_mappings_and_read_json_message_arrays() {
+    let mixed = format!("{SCRIPTS_SPEC}\n[mapping]\ncontent = \"/body\"\n");
+    assert!(
+        parse_spec_toml(&mixed)
+            .expect_err("preset plus mapping should be rejected")
+            .to_string()
+            .contains("defines its own field mapping")
+    );
+
+    let spec = parse_spec_json(
+        r#"{"name":"dumps","paths":["~/dumps"],"format":"json","preset":"openai_messages"}"#,
+    )
+    .expect("json preset spec should parse");
+    let parsed = parse_custom_records(
+        &spec,
+        r#"{"model":"m","messages":[{"role":"user","content":"hi"},{"role":"assistant","content":"hello"}]}"#,
+        "run-json",
+        "/tmp/chat.json",
+    )
+    .expect("messages document should parse");
+    assert_eq!(parsed.events.len(), 2);
+    assert_eq!(parsed.events[1].role, ActorRole::Assistant);
+    assert_eq!(parsed.events[1].source_record_locator, "index:2");
+}
+
+#[test]
+fn normalize_reads_preset_sources_below_source_root() {
+    let home = unique_temp_dir("logit-openai-messages-home");
+    let source_root = unique_temp_dir("logit-openai-messages-source");
+    let out_dir = unique_temp_dir("logit-openai-messages-out");
+    write_file(&home.join(".logit/adapters/build-bot.toml"), SCRIPTS_SPEC);
+    write_file(
+        &source_root.join("transcripts/release-debug.jsonl"),
+        TRANSCRIPT_FIXTURE,
+    );
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home,
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.clone(),
+    };
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: true,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+
+    let events_text =
+        std::fs::read_to_string(out_dir.join("events.jsonl")).expect("events should exist");
+    let tool_results = events_text
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("event row should parse"))
+        .filter(|row| row.get("source_kind").and_then(Value::as_str) == Some("build-bot"))
+        .filter(|row| row.get("record_format").and_then(Value::as_str) == Some("tool_result"))
+        .count();
+    assert_eq!(tool_results, 3);
+}
+
+#[test]
+fn normalize_message_format_reads_source_root_without_a_spec() {
+    let home = unique_temp_dir("logit-message-format-home");
+    let source_root = unique_temp_dir("logit-message-format-source");
+    let out_dir = unique_temp_dir("logit-message-format-out");
+    write_file(
+        &source_root.join("scripts/release-debug.jsonl"),
+        TRANSCRIPT_FIXTURE,
+    );
+    write_file(
+        &source_root.join("dumps/chat.json"),
+        r#"{"messages":[{"role":"user","content":"ping"},{"role":"assistant","content":"pong"}]}"#,
+    );
+    write_file(&source_root.join("dumps/notes.txt"), "not a transcript");
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home,
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.clone(),
+    };
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: Some(MessagePreset::OpenAiMessages),
+        fail_fast: true,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+
+    let rows = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("event row should parse"))
+        .collect::<Vec<_>>();
+    assert_eq!(rows.len(), 10);
+    assert!(
+        rows.iter()
+            .all(|row| row.get("source_kind").and_then(Value::as_str) == Some("openai-messages"))
+    );
+    assert!(rows.iter().any(|row| {
+        row.get("source_path")
+            .and_then(Value::as_str)
+            .is_some_and(|path| path.ends_with("dumps/chat.json"))
+    }));
+}
+
+#[test]
+fn normalize_message_format_expands_quoted_input_globs() {
+    let home = unique_temp_dir("logit-message-format-glob-home");
+    let cwd = unique_temp_dir("logit-message-format-glob-cwd");
+    let out_dir = unique_temp_dir("logit-message-format-glob-out");
+    write_file(&cwd.join("sessions/20260201_1.jsonl"), GOOSE_FIXTURE);
+    write_file(&cwd.join("sessions/ignored.json"), "{}");
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home,
+        cwd: cwd.clone(),
+        out_dir: out_dir.clone(),
+    };
+    let args = NormalizeArgs {
+        inputs: vec![PathBuf::from("sessions/*.jsonl")],
+        source_root: None,
+        input_adapter: None,
+        message_format: Some(MessagePreset::Goose),
+        fail_fast: true,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize should succeed");
+
+    let rows = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("event row should parse"))
+        .collect::<Vec<_>>();
+    assert!(!rows.is_empty());
+    assert!(rows.iter().all(|row| {
+        row.get("source_kind").and_then(Value::as_str) == Some("goose")
+            && row
+                .get("source_path")
+                .and_then(Value::as_str)
+                .is_some_and(|path| path.ends_with("sessions/20260201_1.jsonl"))
+    }));
+}
+
+#[test]
+fn normalize_message_format_requires_inputs_or_source_root() {
+    let runtime_paths = RuntimePaths {
+        home_dir: unique_temp_dir("logit-message-format-none-home"),
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: unique_temp_dir("logit-message-format-none-out"),
+    };
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: None,
+        input_adapter: None,
+        message_format: Some(MessagePreset::OpenAiMessages),
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    let error = run_normalize(&args, &LogitConfig::default(), &runtime_paths)
+        .expect_err("normalize should refuse");
+    assert!(error.to_string().contains("--message-format"));
+}
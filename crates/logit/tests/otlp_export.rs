@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::otlp::collector::{COLLECTOR_TIMEOUT, CollectorEndpoint, post_json};
+use logit::otlp::{
+    OtlpExportOptions, build_session_trace, session_ids, span_id, trace_id, write_trace,
+};
+use logit::sqlite::{SqliteWriterConfig, open_sqlite_connection_read_only, write_events_to_sqlite};
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn sample_event(
+    event_id: &str,
+    session_id: &str,
+    offset_ms: u64,
+    event_type: EventType,
+) -> AgentLogEvent {
+    let (record_format, role) = match event_type {
+        EventType::Prompt => (RecordFormat::Message, ActorRole::User),
+        EventType::ToolInvocation => (RecordFormat::ToolCall, ActorRole::Assistant),
+        EventType::ToolOutput => (RecordFormat::ToolResult, ActorRole::Tool),
+        EventType::Error => (RecordFormat::Diagnostic, ActorRole::Runtime),
+        _ => (RecordFormat::Message, ActorRole::Assistant),
+    };
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: offset_ms,
+        sequence_source: Some(offset_ms),
+        source_kind: AgentSource::Claude,
+        source_path: "/tmp/claude/session.jsonl".to_string(),
+        source_record_locator: format!("line:{offset_ms}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Claude,
+        adapter_version: Some("v1".to_string()),
+        record_format,
+        event_type,
+        role,
+        timestamp_utc: "2026-02-25T00:00:00Z".to_string(),
+        timestamp_unix_ms: 1_771_977_600_000 + offset_ms,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(session_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: Some("anthropic".to_string()),
+        model: Some("claude-sonnet".to_string()),
+        content_text: Some(format!("content of {event_id}")),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn tool_event(event_id: &str, offset_ms: u64, event_type: EventType) -> AgentLogEvent {
+    let mut event = sample_event(event_id, "session-a", offset_ms, event_type);
+    event.tool_name = Some("Bash".to_string());
+    event.tool_call_id = Some("call-1".to_string());
+    event.tool_arguments_json = Some(r#"{"command":"cargo test"}"#.to_string());
+    event
+}
+
+fn seed_mart(dir: &Path) -> PathBuf {
+    let mart = dir.join("mart.sqlite");
+    let mut response = sample_event("evt-4", "session-a", 4_000, EventType::Response);
+    response.input_tokens = Some(120);
+    response.output_tokens = Some(30);
+    let events = vec![
+        sample_event("evt-1", "session-a", 0, EventType::Prompt),
+        tool_event("evt-2", 1_000, EventType::ToolInvocation),
+        tool_event("evt-3", 2_500, EventType::ToolOutput),
+        response,
+        sample_event("evt-5", "session-a", 5_000, EventType::Prompt),
+        sample_event("evt-6", "session-a", 6_000, EventType::Error),
+        sample_event("evt-7", "session-b", 7_000, EventType::Prompt),
+    ];
+    write_events_to_sqlite(&mart, &events, SqliteWriterConfig::default())
+        .expect("events should be written");
+    mart
+}
+
+fn spans(document: &Value) -> Vec<Value> {
+    document["resourceSpans"][0]["scopeSpans"][0]["spans"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn attribute<'a>(item: &'a Value, key: &str) -> Option<&'a Value> {
+    item["attributes"]
+        .as_array()?
+        .iter()
+        .find(|attribute| attribute["key"] == key)
+        .map(|attribute| &attribute["value"])
+}
+
+#[test]
+fn session_trace_maps_turns_tool_calls_and_events_to_spans() {
+    let dir = unique_temp_dir("logit-otlp-spans");
+    let connection = open_sqlite_connection_read_only(&seed_mart(&dir)).expect("mart should open");
+    assert_eq!(
+        session_ids(&connection).expect("sessions should load"),
+        ["session-a", "session-b"]
+    );
+
+    let trace = build_session_trace(&connection, "session-a", OtlpExportOptions::default())
+        .expect("trace should build");
+    assert_eq!(trace.trace_id, trace_id("session-a"));
+    assert_eq!(trace.trace_id.len(), 32);
+    assert_eq!(trace.span_count, 4);
+    let spans = spans(&trace.document);
+    assert!(spans.iter().all(|span| span["traceId"] == trace.trace_id));
+
+    let root = &spans[0];
+    assert_eq!(root["name"], "invoke_agent claude");
+    assert_eq!(root["spanId"], span_id("session-a", "session"));
+    assert!(root.get("parentSpanId").is_none());
+    assert_eq!(root["status"]["code"], 2);
+    assert_eq!(
+        attribute(root, "gen_ai.conversation.id"),
+        Some(&serde_json::json!({ "stringValue": "session-a" }))
+    );
+    assert_eq!(
+        attribute(root, "gen_ai.usage.input_tokens"),
+        Some(&serde_json::json!({ "intValue": "120" }))
+    );
+
+    let first_turn = &spans[1];
+    assert_eq!(first_turn["name"], "chat claude-sonnet");
+    assert_eq!(first_turn["spanId"], span_id("session-a", "turn:evt-1"));
+    assert_eq!(first_turn["parentSpanId"], root["spanId"]);
+    assert_eq!(
+        attribute(first_turn, "gen_ai.provider.name"),
+        Some(&serde_json::json!({ "stringValue": "anthropic" }))
+    );
+    assert_eq!(
+        attribute(first_turn, "gen_ai.usage.output_tokens"),
+        Some(&serde_json::json!({ "intValue": "30" }))
+    );
+    let event_names = first_turn["events"]
+        .as_array()
+        .map(|events| {
+            events
+                .iter()
+                .filter_map(|event| event["name"].as_str())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    assert_eq!(
+        event_names,
+        ["gen_ai.content.prompt", "gen_ai.content.completion"]
+    );
+    assert!(!trace.document.to_string().contains("content of evt-1"));
+
+    let second_turn = &spans[2];
+    assert_eq!(second_turn["status"]["code"], 2);
+    assert_eq!(second_turn["events"][1]["name"], "exception");
+
+    let tool = &spans[3];
+    assert_eq!(tool["name"], "execute_tool Bash");
+    assert_eq!(tool["spanId"], span_id("session-a", "tool:evt-2"));
+    assert_eq!(tool["parentSpanId"], first_turn["spanId"]);
+    assert_eq!(tool["startTimeUnixNano"], "1771977601000000000");
+    assert_eq!(tool["endTimeUnixNano"], "1771977602500000000");
+    assert_eq!(
+        attribute(tool, "logit.duration_ms"),
+        Some(&serde_json::json!({ "intValue": "1500" }))
+    );
+    assert_eq!(
+        attribute(tool, "gen_ai.tool.call.id"),
+        Some(&serde_json::json!({ "stringValue": "call-1" }))
+    );
+}
+
+#[test]
+fn content_is_opt_in_and_reexports_are_byte_identical() {
+    let dir = unique_temp_dir("logit-otlp-idempotent");
+    let connection = open_sqlite_connection_read_only(&seed_mart(&dir)).expect("mart should open");
+    let options = OtlpExportOptions {
+        include_content: true,
+    };
+    let trace = build_session_trace(&connection, "session-a", options).expect("trace should build");
+    let encoded = trace.document.to_string();
+    assert!(encoded.contains("content of evt-1"));
+    assert!(encoded.contains("cargo test"));
+
+    let output_dir = dir.join("otlp");
+    let first_path = write_trace(&output_dir, &trace).expect("trace should be written");
+    let first = std::fs::read(&first_path).expect("trace file should exist");
+    let again =
+        build_session_trace(&connection, "session-a", options).expect("trace should rebuild");
+    let second_path = write_trace(&output_dir, &again).expect("trace should be rewritten");
+    assert_eq!(first_path, second_path);
+    assert_eq!(
+        first,
+        std::fs::read(&second_path).expect("trace file should exist")
+    );
+    assert_eq!(
+        first_path.file_name().and_then(|name| name.to_str()),
+        Some(format!("{}.json", trace_id("session-a")).as_str())
+    );
+
+    let missing = build_session_trace(&connection, "session-z", options);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn collector_endpoint_defaults_and_post_round_trip() {
+    let endpoint = CollectorEndpoint::parse("http://localhost").expect("endpoint should parse");
+    assert_eq!(endpoint.port, 4318);
+    assert_eq!(endpoint.path, "/v1/traces");
+    assert!(CollectorEndpoint::parse("https://localhost:4318").is_err());
+    assert!(CollectorEndpoint::parse("http://:4318").is_err());
+
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+    let port = listener
+        .local_addr()
+        .expect("listener should have an address")
+        .port();
+    let collector = std::thread::spawn(move || {
+        let mut received = Vec::new();
+        for status in ["200 OK", "400 Bad Request"] {
+            let (stream, _) = listener.accept().expect("collector should accept");
+            let mut reader = BufReader::new(stream);
+            let mut request_line = String::new();
+            reader
+                .read_line(&mut request_line)
+                .expect("request line should be readable");
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader
+                    .read_line(&mut header)
+                    .expect("header should be readable");
+                if header.trim().is_empty() {
+                    break;
+                }
+                if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader
+                .read_exact(&mut body)
+                .expect("body should be readable");
+            let reply = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: 8\r\nConnection: close\r\n\r\nrejected"
+            );
+            reader
+                .get_mut()
+                .write_all(reply.as_bytes())
+                .expect("reply should be written");
+            received.push((request_line, String::from_utf8_lossy(&body).into_owned()));
+        }
+        received
+    });
+
+    let endpoint = CollectorEndpoint::parse(&format!("http://127.0.0.1:{port}/v1/traces"))
+        .expect("endpoint should parse");
+    assert_eq!(
+        post_json(&endpoint, r#"{"resourceSpans":[]}"#, COLLECTOR_TIMEOUT)
+            .expect("collector should accept"),
+        200
+    );
+    let rejected = post_json(&endpoint, "{}", COLLECTOR_TIMEOUT);
+    assert!(
+        rejected
+            .as_ref()
+            .is_err_and(|error| format!("{error:#}").contains("status 400"))
+    );
+
+    let received = collector.join().expect("collector thread should finish");
+    assert_eq!(received[0].0.trim(), "POST /v1/traces HTTP/1.1");
+    assert_eq!(received[0].1, r#"{"resourceSpans":[]}"#);
+}
+
+#[test]
+fn export_command_writes_one_trace_file_per_session() {
+    let dir = unique_temp_dir("logit-otlp-command");
+    seed_mart(&dir);
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(&dir)
+        .arg("--cwd")
+        .arg(&dir)
+        .arg("--out-dir")
+        .arg(&dir)
+        .args(["export", "otlp", "--session", "session-b"])
+        .output()
+        .expect("export should run");
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("export: complete traces=1 spans=2"));
+
+    let path = dir
+        .join("otlp")
+        .join(format!("{}.json", trace_id("session-b")));
+    let document: Value =
+        serde_json::from_slice(&std::fs::read(&path).expect("trace file should be written"))
+            .expect("trace file should be json");
+    assert_eq!(spans(&document).len(), 2);
+}
@@ -0,0 +1,192 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::utils::fs::{AtomicFile, write_atomic};
+use logit::utils::lock::{
+    LockOwner, OUT_DIR_LOCK_FILE, OutDirLock, OutDirLocked, UNREADABLE_LOCK_GRACE, lock_path,
+    read_lock_owner,
+};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn dir_entries(dir: &PathBuf) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .expect("dir should be readable")
+        .map(|entry| {
+            entry
+                .expect("entry should be readable")
+                .file_name()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn atomic_writes_replace_content_and_leave_no_temp_files() {
+    let out_dir = unique_temp_dir("logit-atomic-write");
+    let path = out_dir.join("nested/report.json");
+
+    write_atomic(&path, b"{\"v\":1}").expect("first write should succeed");
+    write_atomic(&path, b"{\"v\":2}").expect("second write should succeed");
+
+    assert_eq!(
+        std::fs::read_to_string(&path).expect("artifact should be readable"),
+        "{\"v\":2}"
+    );
+    assert_eq!(dir_entries(&out_dir.join("nested")), vec!["report.json"]);
+}
+
+#[test]
+fn uncommitted_atomic_file_keeps_previous_content() {
+    let out_dir = unique_temp_dir("logit-atomic-abort");
+    let path = out_dir.join("events.jsonl");
+    write_atomic(&path, b"old\n").expect("seed write should succeed");
+
+    let mut file = AtomicFile::create(&path).expect("atomic file should open");
+    file.write_all(b"partial")
+        .expect("temp file should accept writes");
+    drop(file);
+
+    assert_eq!(
+        std::fs::read_to_string(&path).expect("artifact should be readable"),
+        "old\n"
+    );
+    assert_eq!(dir_entries(&out_dir), vec!["events.jsonl"]);
+}
+
+#[test]
+fn out_dir_lock_rejects_live_holders_and_releases_on_drop() {
+    let out_dir = unique_temp_dir("logit-lock-live");
+
+    let lock = OutDirLock::acquire(&out_dir, "logit ingest refresh").expect("lock should acquire");
+    let owner = read_lock_owner(&lock_path(&out_dir)).expect("lock file should name its owner");
+    assert_eq!(owner.pid, std::process::id());
+    assert_eq!(owner.command, "logit ingest refresh");
+
+    let error = OutDirLock::acquire(&out_dir, "logit normalize")
+        .expect_err("second acquire should be refused");
+    let Some(locked) = error.downcast_ref::<OutDirLocked>() else {
+        assert!(error.downcast_ref::<OutDirLocked>().is_some(), "{error:#}");
+        return;
+    };
+    assert_eq!(locked.owner.as_ref(), Some(&owner));
+    assert!(locked.to_string().contains("logit ingest refresh"));
+
+    drop(lock);
+    assert!(!out_dir.join(OUT_DIR_LOCK_FILE).exists());
+    OutDirLock::acquire(&out_dir, "logit normalize").expect("released lock should reacquire");
+}
+
+#[test]
+fn out_dir_lock_takes_over_a_lock_left_by_an_exited_process() {
+    let out_dir = unique_temp_dir("logit-lock-stale");
+    let exited_pid = std::process::Command::new("true")
+        .spawn()
+        .and_then(|mut child| child.wait().map(|_| child.id()))
+        .expect("child process should run");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let stale = LockOwner {
+        pid: exited_pid,
+        command: "logit snapshot".to_string(),
+        acquired_at_utc: "2026-01-01T00:00:00Z".to_string(),
+    };
+    std::fs::write(
+        lock_path(&out_dir),
+        serde_json::to_vec(&stale).expect("lock owner should encode"),
+    )
+    .expect("stale lock should be writable");
+
+    let lock = OutDirLock::acquire(&out_dir, "logit run").expect("stale lock should be taken over");
+    assert_eq!(lock.owner().pid, std::process::id());
+    assert_eq!(
+        read_lock_owner(lock.path()).map(|owner| owner.command),
+        Some("logit run".to_string())
+    );
+}
+
+#[test]
+fn out_dir_lock_respects_an_unreadable_lock_until_the_grace_period_passes() {
+    let out_dir = unique_temp_dir("logit-lock-unreadable");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    std::fs::write(lock_path(&out_dir), b"{\"pid\":").expect("torn lock should be writable");
+
+    let error = OutDirLock::acquire(&out_dir, "logit normalize")
+        .expect_err("a fresh unreadable lock should be respected");
+    let Some(locked) = error.downcast_ref::<OutDirLocked>() else {
+        assert!(error.downcast_ref::<OutDirLocked>().is_some(), "{error:#}");
+        return;
+    };
+    assert_eq!(locked.owner, None);
+
+    let aged = SystemTime::now() - UNREADABLE_LOCK_GRACE - std::time::Duration::from_secs(1);
+    std::fs::File::options()
+        .write(true)
+        .open(lock_path(&out_dir))
+        .and_then(|file| file.set_modified(aged))
+        .expect("lock mtime should be settable");
+
+    let lock = OutDirLock::acquire(&out_dir, "logit normalize")
+        .expect("an unreadable lock past the grace period should be taken over");
+    assert_eq!(lock.owner().pid, std::process::id());
+    assert_eq!(dir_entries(&out_dir), vec![OUT_DIR_LOCK_FILE]);
+}
+
+#[test]
+fn concurrent_takeover_of_a_stale_lock_has_a_single_winner() {
+    let out_dir = unique_temp_dir("logit-lock-race");
+    let exited_pid = std::process::Command::new("true")
+        .spawn()
+        .and_then(|mut child| child.wait().map(|_| child.id()))
+        .expect("child process should run");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let stale = LockOwner {
+        pid: exited_pid,
+        command: "logit snapshot".to_string(),
+        acquired_at_utc: "2026-01-01T00:00:00Z".to_string(),
+    };
+    std::fs::write(
+        lock_path(&out_dir),
+        serde_json::to_vec(&stale).expect("lock owner should encode"),
+    )
+    .expect("stale lock should be writable");
+
+    let results = std::thread::scope(|scope| {
+        let handles = (0..8)
+            .map(|index| {
+                let out_dir = &out_dir;
+                scope.spawn(move || OutDirLock::acquire(out_dir, &format!("logit run #{index}")))
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("acquire thread should not panic"))
+            .collect::<Vec<_>>()
+    });
+
+    let winners = results
+        .iter()
+        .filter_map(|result| result.as_ref().ok())
+        .collect::<Vec<_>>();
+    assert_eq!(winners.len(), 1);
+    assert_eq!(
+        read_lock_owner(&lock_path(&out_dir)).as_ref(),
+        Some(winners[0].owner())
+    );
+    assert!(
+        results
+            .iter()
+            .filter_map(|result| result.as_ref().err())
+            .all(|error| error.downcast_ref::<OutDirLocked>().is_some())
+    );
+    assert_eq!(dir_entries(&out_dir), vec![OUT_DIR_LOCK_FILE]);
+}
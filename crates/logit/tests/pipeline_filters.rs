@@ -0,0 +1,186 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::cli::commands::snapshot::{SnapshotArgs, run as run_snapshot};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &std::path::Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn seed_codex_and_claude_sources(root: &std::path::Path) {
+    write_file(
+        &root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    write_file(
+        &root.join(".claude/projects/project_session.jsonl"),
+        include_str!("../../../fixtures/claude/project_session.jsonl"),
+    );
+}
+
+fn runtime_paths(out_dir: &std::path::Path) -> RuntimePaths {
+    RuntimePaths {
+        home_dir: PathBuf::from("/tmp/logit-home"),
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.to_path_buf(),
+    }
+}
+
+fn normalize_with(filters: SourceFilterArgs, label: &str) -> (Vec<Value>, Value) {
+    let source_root = unique_temp_dir(&format!("logit-filters-{label}-sources"));
+    seed_codex_and_claude_sources(&source_root);
+    let out_dir = unique_temp_dir(&format!("logit-filters-{label}-out"));
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters,
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths(&out_dir))
+        .expect("normalize run should succeed");
+
+    let rows = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist")
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("each event row should parse"))
+        .collect::<Vec<_>>();
+    let stats = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("stats.json")).expect("stats artifact should exist"),
+    )
+    .expect("stats artifact should parse");
+    (rows, stats)
+}
+
+#[test]
+fn normalize_without_filters_records_inactive_filters_in_stats() {
+    let (rows, stats) = normalize_with(SourceFilterArgs::default(), "none");
+    assert!(!rows.is_empty());
+    assert_eq!(stats.pointer("/filters/active"), Some(&Value::Bool(false)));
+}
+
+#[test]
+fn normalize_adapter_filter_emits_only_selected_adapter() {
+    let (rows, stats) = normalize_with(
+        SourceFilterArgs {
+            adapters: vec!["Codex".to_string()],
+            ..SourceFilterArgs::default()
+        },
+        "adapter",
+    );
+    assert!(!rows.is_empty());
+    assert!(
+        rows.iter()
+            .all(|row| row.get("adapter_name").and_then(Value::as_str) == Some("codex"))
+    );
+    assert_eq!(stats.pointer("/filters/active"), Some(&Value::Bool(true)));
+    assert_eq!(
+        stats.pointer("/filters/adapters"),
+        Some(&serde_json::json!(["codex"]))
+    );
+}
+
+#[test]
+fn normalize_until_bound_drops_events_outside_window() {
+    let (rows, stats) = normalize_with(
+        SourceFilterArgs {
+            until: Some(946_684_800_000),
+            ..SourceFilterArgs::default()
+        },
+        "until",
+    );
+    assert!(rows.is_empty());
+    assert_eq!(
+        stats.pointer("/filters/until_utc"),
+        Some(&serde_json::json!("2000-01-01T00:00:00.000Z"))
+    );
+    assert!(
+        stats
+            .pointer("/filters/events_outside_window")
+            .and_then(Value::as_u64)
+            .is_some_and(|count| count > 0)
+    );
+}
+
+#[test]
+fn snapshot_since_bound_prunes_files_by_mtime() {
+    let source_root = unique_temp_dir("logit-filters-snapshot-sources");
+    seed_codex_and_claude_sources(&source_root);
+    let out_dir = unique_temp_dir("logit-filters-snapshot-out");
+    let args = SnapshotArgs {
+        source_root: Some(source_root),
+        sample_size: 1,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs {
+            since: Some(32_503_680_000_000),
+            ..SourceFilterArgs::default()
+        },
+    };
+    run_snapshot(&args, &LogitConfig::default(), &runtime_paths(&out_dir))
+        .expect("snapshot run should succeed");
+
+    let index: Value = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("snapshot/index.json"))
+            .expect("snapshot index should exist"),
+    )
+    .expect("snapshot index should parse");
+    assert_eq!(index.pointer("/filters/active"), Some(&Value::Bool(true)));
+    assert!(
+        index
+            .pointer("/filters/files_pruned_by_mtime")
+            .and_then(Value::as_u64)
+            .is_some_and(|count| count >= 2)
+    );
+    assert_eq!(
+        index.pointer("/counts/records_profiled"),
+        Some(&serde_json::json!(0))
+    );
+}
+
+#[test]
+fn snapshot_rejects_unknown_adapter_filter() {
+    let out_dir = unique_temp_dir("logit-filters-snapshot-unknown-out");
+    let args = SnapshotArgs {
+        source_root: Some(unique_temp_dir("logit-filters-snapshot-unknown-sources")),
+        sample_size: 1,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs {
+            adapters: vec!["not-an-adapter".to_string()],
+            ..SourceFilterArgs::default()
+        },
+    };
+    let error = run_snapshot(&args, &LogitConfig::default(), &runtime_paths(&out_dir))
+        .expect_err("unknown adapter filter should fail");
+    assert!(format!("{error:#}").contains("not-an-adapter"));
+}
+
+#[test]
+fn filters_reject_since_later_than_until() {
+    let filters = SourceFilterArgs {
+        since: Some(2_000),
+        until: Some(1_000),
+        ..SourceFilterArgs::default()
+    };
+    assert!(filters.to_filters().is_err());
+}
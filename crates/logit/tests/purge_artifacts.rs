@@ -0,0 +1,489 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
+use logit::cli::commands::snapshot::{SnapshotArgs, run as run_snapshot};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::discovery::PipelineFilters;
+use logit::ingest::import::{IngestImportPlan, run_import};
+use logit::ingest::{IngestRefreshPlan, run_refresh};
+use logit::purge::{
+    PurgeKind, PurgePlan, PurgeSelector, TombstoneList, purge_log_path, run_purge, tombstones_path,
+};
+use logit::sqlite::open_sqlite_connection;
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn runtime_paths(out_dir: &Path) -> RuntimePaths {
+    RuntimePaths {
+        home_dir: PathBuf::from("/tmp/logit-home"),
+        cwd: PathBuf::from("/tmp/logit-cwd"),
+        out_dir: out_dir.to_path_buf(),
+    }
+}
+
+fn normalize(source_root: &Path, out_dir: &Path) {
+    normalize_with_redaction(source_root, out_dir, RedactionPolicy::default());
+}
+
+fn normalize_with_redaction(source_root: &Path, out_dir: &Path, redaction: RedactionPolicy) {
+    let args = NormalizeArgs {
+        inputs: Vec::new(),
+        source_root: Some(source_root.to_path_buf()),
+        input_adapter: None,
+        message_format: None,
+        fail_fast: false,
+        redaction,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_normalize(&args, &LogitConfig::default(), &runtime_paths(out_dir))
+        .expect("normalize run should succeed");
+}
+
+/// Seeds codex and claude sources and runs normalize, snapshot and ingest
+/// refresh so every purgeable artifact exists.
+fn build_out_dir(label: &str) -> (PathBuf, PathBuf) {
+    let source_root = unique_temp_dir(&format!("logit-purge-{label}-sources"));
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    write_file(
+        &source_root.join(".claude/projects/project_session.jsonl"),
+        include_str!("../../../fixtures/claude/project_session.jsonl"),
+    );
+    let out_dir = unique_temp_dir(&format!("logit-purge-{label}-out"));
+    normalize(&source_root, &out_dir);
+    let snapshot = SnapshotArgs {
+        source_root: Some(source_root.clone()),
+        sample_size: 3,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
+    };
+    run_snapshot(&snapshot, &LogitConfig::default(), &runtime_paths(&out_dir))
+        .expect("snapshot run should succeed");
+    run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: out_dir.join("events.jsonl"),
+        sqlite_path: out_dir.join("mart.sqlite"),
+        source_root: source_root.clone(),
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: Some("workstation".to_string()),
+        tombstones: Vec::new(),
+    })
+    .expect("ingest refresh should succeed");
+    (source_root, out_dir)
+}
+
+fn read_jsonl(path: &Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .expect("jsonl artifact should exist")
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each jsonl row should parse"))
+        .collect()
+}
+
+fn field<'a>(row: &'a Value, name: &str) -> &'a str {
+    row.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn claude_session_id(out_dir: &Path) -> String {
+    read_jsonl(&out_dir.join("events.jsonl"))
+        .iter()
+        .find(|row| field(row, "adapter_name") == "claude" && !field(row, "session_id").is_empty())
+        .map(|row| field(row, "session_id").to_string())
+        .expect("claude fixture should produce a session")
+}
+
+fn mart_count(out_dir: &Path, sql: &str, param: &str) -> i64 {
+    let connection = open_sqlite_connection(&out_dir.join("mart.sqlite")).expect("mart opens");
+    connection
+        .query_row(sql, [param], |row| row.get(0))
+        .expect("count query should run")
+}
+
+fn purge_plan(out_dir: &Path, selectors: Vec<PurgeSelector>, dry_run: bool) -> PurgePlan {
+    PurgePlan {
+        out_dir: out_dir.to_path_buf(),
+        kind: PurgeKind::Purge,
+        selectors,
+        dry_run,
+        purge_id: Some("purge-test".to_string()),
+    }
+}
+
+#[test]
+fn purge_session_removes_rows_from_every_artifact_and_records_tombstone() {
+    let (source_root, out_dir) = build_out_dir("session");
+    let session_id = claude_session_id(&out_dir);
+    let samples_before = read_jsonl(&out_dir.join("snapshot/samples.jsonl"));
+    let session_events = read_jsonl(&out_dir.join("events.jsonl"))
+        .into_iter()
+        .filter(|row| field(row, "session_id") == session_id)
+        .collect::<Vec<_>>();
+
+    let report = run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::Session {
+            session_id: session_id.clone(),
+        }],
+        false,
+    ))
+    .expect("purge should succeed");
+
+    assert_eq!(report.events_jsonl_removed, session_events.len());
+    assert_eq!(report.mart_events_removed, session_events.len());
+    assert_eq!(report.mart_fts_rows_removed, session_events.len());
+    assert_eq!(report.sessions_affected, 1);
+    assert!(report.snapshot_samples_removed > 0);
+    assert_eq!(report.tombstones_added, 1);
+    assert!(report.vacuumed);
+    assert!(report.artifacts_missing.is_empty());
+
+    let remaining = read_jsonl(&out_dir.join("events.jsonl"));
+    assert!(!remaining.is_empty());
+    assert!(
+        remaining
+            .iter()
+            .all(|row| field(row, "session_id") != session_id)
+    );
+    assert_eq!(
+        mart_count(
+            &out_dir,
+            "SELECT COUNT(*) FROM agentlog_events WHERE session_id = ?1",
+            &session_id
+        ),
+        0
+    );
+    assert_eq!(
+        mart_count(
+            &out_dir,
+            "SELECT COUNT(*) FROM agentlog_events_fts WHERE session_id = ?1",
+            &session_id
+        ),
+        0
+    );
+
+    let samples_after = read_jsonl(&out_dir.join("snapshot/samples.jsonl"));
+    assert_eq!(
+        samples_before.len() - samples_after.len(),
+        report.snapshot_samples_removed
+    );
+    assert!(samples_after.iter().all(|sample| {
+        !session_events.iter().any(|event| {
+            field(event, "source_path") == field(sample, "source_path")
+                && field(event, "source_record_locator") == field(sample, "source_record_locator")
+        })
+    }));
+    let index: Value = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("snapshot/index.json"))
+            .expect("snapshot index should exist"),
+    )
+    .expect("snapshot index should parse");
+    assert_eq!(
+        index
+            .pointer("/counts/samples_emitted")
+            .and_then(Value::as_u64),
+        Some(samples_after.len() as u64)
+    );
+
+    let tombstones =
+        TombstoneList::load(&tombstones_path(&out_dir)).expect("tombstones should load");
+    assert_eq!(
+        tombstones.selectors(),
+        [PurgeSelector::Session {
+            session_id: session_id.clone()
+        }]
+    );
+    let log = read_jsonl(&purge_log_path(&out_dir));
+    assert_eq!(log.len(), 1);
+    assert_eq!(field(&log[0], "purge_id"), "purge-test");
+    assert_eq!(field(&log[0], "kind"), "purge");
+
+    normalize(&source_root, &out_dir);
+    assert!(
+        read_jsonl(&out_dir.join("events.jsonl"))
+            .iter()
+            .all(|row| field(row, "session_id") != session_id)
+    );
+    let stats: Value = serde_json::from_str(
+        &std::fs::read_to_string(out_dir.join("stats.json")).expect("stats should exist"),
+    )
+    .expect("stats should parse");
+    assert_eq!(
+        stats
+            .pointer("/filters/events_tombstoned")
+            .and_then(Value::as_u64),
+        Some(session_events.len() as u64)
+    );
+}
+
+#[test]
+fn purge_source_path_glob_drops_watermarks_of_emptied_sources() {
+    let (_, out_dir) = build_out_dir("source-path");
+    let watermarks = |pattern: &str| {
+        mart_count(
+            &out_dir,
+            "SELECT COUNT(*) FROM ingest_watermarks WHERE source_path GLOB ?1",
+            pattern,
+        )
+    };
+    assert!(watermarks("*/.claude/*") > 0);
+    assert!(watermarks("*/.codex/*") > 0);
+
+    let report = run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::SourcePath {
+            glob: "*/.claude/*".to_string(),
+        }],
+        false,
+    ))
+    .expect("purge should succeed");
+
+    assert!(report.mart_events_removed > 0);
+    assert!(report.mart_watermarks_removed > 0);
+    assert_eq!(watermarks("*/.claude/*"), 0);
+    assert!(watermarks("*/.codex/*") > 0);
+    assert!(
+        read_jsonl(&out_dir.join("snapshot/samples.jsonl"))
+            .iter()
+            .all(|sample| !field(sample, "source_path").contains("/.claude/"))
+    );
+
+    let tombstones = TombstoneList::load(&tombstones_path(&out_dir))
+        .expect("tombstones should load")
+        .selectors();
+    assert_eq!(tombstones.len(), report.events_jsonl_removed);
+    assert_eq!(report.tombstones_added, tombstones.len());
+    assert!(
+        tombstones
+            .iter()
+            .all(|selector| matches!(selector, PurgeSelector::SourceRecord { .. }))
+    );
+}
+
+#[test]
+fn purge_source_path_keeps_redacted_secrets_out_under_another_policy() {
+    let source_root = unique_temp_dir("logit-purge-policy-sources");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_secret.jsonl"),
+        &include_str!("../../../fixtures/codex/rollout_primary.jsonl").replace(
+            "Summarize the latest build output.",
+            "Deploy with password=hunter2secretvalue",
+        ),
+    );
+    write_file(
+        &source_root.join(".claude/projects/project_session.jsonl"),
+        include_str!("../../../fixtures/claude/project_session.jsonl"),
+    );
+    let out_dir = unique_temp_dir("logit-purge-policy-out");
+    normalize(&source_root, &out_dir);
+    let report = run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::SourcePath {
+            glob: "*rollout_secret.jsonl".to_string(),
+        }],
+        false,
+    ))
+    .expect("purge should succeed");
+    assert!(report.events_jsonl_removed > 0);
+
+    normalize_with_redaction(&source_root, &out_dir, RedactionPolicy::Off);
+    let events_jsonl = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist");
+    assert!(!events_jsonl.contains("hunter2secretvalue"));
+    let rows = read_jsonl(&out_dir.join("events.jsonl"));
+    assert!(!rows.is_empty());
+    assert!(
+        rows.iter()
+            .all(|row| !field(row, "source_path").ends_with("rollout_secret.jsonl"))
+    );
+}
+
+#[test]
+fn purge_source_path_does_not_tombstone_records_written_later() {
+    let (source_root, out_dir) = build_out_dir("source-path-later");
+    run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::SourcePath {
+            glob: "*/.claude/*".to_string(),
+        }],
+        false,
+    ))
+    .expect("purge should succeed");
+
+    write_file(
+        &source_root.join(".claude/projects/later_session.jsonl"),
+        &include_str!("../../../fixtures/claude/project_session.jsonl")
+            .replace("claude-s-001", "claude-s-002")
+            .replace("migration plan", "rollback plan"),
+    );
+    normalize(&source_root, &out_dir);
+    let claude_rows = read_jsonl(&out_dir.join("events.jsonl"))
+        .into_iter()
+        .filter(|row| field(row, "adapter_name") == "claude")
+        .collect::<Vec<_>>();
+    assert!(!claude_rows.is_empty());
+    assert!(
+        claude_rows
+            .iter()
+            .all(|row| field(row, "source_path").ends_with("later_session.jsonl"))
+    );
+}
+
+#[test]
+fn ingest_refresh_and_import_skip_tombstoned_events() {
+    let (source_root, out_dir) = build_out_dir("ingest-tombstones");
+    let session_id = claude_session_id(&out_dir);
+    let original_events =
+        std::fs::read_to_string(out_dir.join("events.jsonl")).expect("events artifact exists");
+    let session_events = read_jsonl(&out_dir.join("events.jsonl"))
+        .into_iter()
+        .filter(|row| field(row, "session_id") == session_id)
+        .count();
+    run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::Session {
+            session_id: session_id.clone(),
+        }],
+        false,
+    ))
+    .expect("purge should succeed");
+    let tombstones = TombstoneList::load(&tombstones_path(&out_dir))
+        .expect("tombstones should load")
+        .selectors();
+    let session_rows = || {
+        mart_count(
+            &out_dir,
+            "SELECT COUNT(*) FROM agentlog_events WHERE session_id = ?1",
+            &session_id,
+        )
+    };
+
+    let foreign = out_dir.join("foreign/events.jsonl");
+    write_file(&foreign, &original_events);
+    let report = run_import(&IngestImportPlan {
+        input_path: foreign,
+        sqlite_path: out_dir.join("mart.sqlite"),
+        host: Some("laptop".to_string()),
+        run_id: None,
+        tombstones: tombstones.clone(),
+    })
+    .expect("import should succeed");
+    assert_eq!(report.events_tombstoned, session_events);
+    assert_eq!(session_rows(), 0);
+
+    write_file(&out_dir.join("events.jsonl"), &original_events);
+    let report = run_refresh(&IngestRefreshPlan {
+        events_jsonl_path: out_dir.join("events.jsonl"),
+        sqlite_path: out_dir.join("mart.sqlite"),
+        source_root,
+        fail_fast: false,
+        filters: PipelineFilters::default(),
+        run_id: None,
+        host: Some("workstation".to_string()),
+        tombstones,
+    })
+    .expect("ingest refresh should succeed");
+    assert_eq!(report.filters.events_tombstoned, session_events);
+    assert_eq!(session_rows(), 0);
+}
+
+#[test]
+fn purge_dry_run_counts_matches_without_touching_artifacts() {
+    let (_, out_dir) = build_out_dir("dry-run");
+    let events_before =
+        std::fs::read(out_dir.join("events.jsonl")).expect("events artifact should exist");
+    let mart_before = std::fs::read(out_dir.join("mart.sqlite")).expect("mart should exist");
+    let session_id = claude_session_id(&out_dir);
+
+    let report = run_purge(&purge_plan(
+        &out_dir,
+        vec![PurgeSelector::Session { session_id }],
+        true,
+    ))
+    .expect("dry run should succeed");
+
+    assert!(report.dry_run);
+    assert!(report.events_jsonl_removed > 0);
+    assert_eq!(report.mart_events_removed, report.events_jsonl_removed);
+    assert!(!report.vacuumed);
+    assert_eq!(
+        std::fs::read(out_dir.join("events.jsonl")).expect("events artifact should exist"),
+        events_before
+    );
+    assert_eq!(
+        std::fs::read(out_dir.join("mart.sqlite")).expect("mart should exist"),
+        mart_before
+    );
+    assert!(!tombstones_path(&out_dir).exists());
+    assert!(!purge_log_path(&out_dir).exists());
+}
+
+#[test]
+fn prune_cli_removes_events_older_than_age_and_emits_envelope() {
+    let (_, out_dir) = build_out_dir("prune-cli");
+    let total = read_jsonl(&out_dir.join("events.jsonl")).len();
+    let output = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(&out_dir)
+        .arg("--cwd")
+        .arg(&out_dir)
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .args(["prune", "--older-than", "0d"])
+        .output()
+        .expect("prune should execute");
+    assert_eq!(output.status.code(), Some(0));
+    let envelope: Value =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    assert_eq!(envelope.pointer("/ok"), Some(&Value::Bool(true)));
+    assert_eq!(envelope.pointer("/command"), Some(&Value::from("prune")));
+    assert_eq!(
+        envelope
+            .pointer("/data/events_jsonl_removed")
+            .and_then(Value::as_u64),
+        Some(total as u64)
+    );
+    assert_eq!(
+        envelope.pointer("/data/selectors/0/selector"),
+        Some(&Value::from("older_than"))
+    );
+    assert!(read_jsonl(&out_dir.join("events.jsonl")).is_empty());
+    assert_eq!(
+        mart_count(
+            &out_dir,
+            "SELECT COUNT(*) FROM agentlog_events WHERE event_id != ?1",
+            ""
+        ),
+        0
+    );
+
+    let missing_selector = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("purge")
+        .output()
+        .expect("purge should execute");
+    assert_eq!(missing_selector.status.code(), Some(64));
+}
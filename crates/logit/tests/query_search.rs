@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::sqlite::migrations::migration_status;
+use logit::sqlite::{
+    EVENTS_FTS_TABLE, SCHEMA_META_TABLE, SqliteWriterConfig, ensure_sqlite_schema,
+    open_sqlite_connection, write_events_to_sqlite,
+};
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn sample_event(event_id: &str, session_id: &str, sequence: u64, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: sequence,
+        sequence_source: Some(sequence),
+        source_kind: AgentSource::Codex,
+        source_path: "/tmp/codex/session.jsonl".to_string(),
+        source_record_locator: format!("line:{sequence}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Codex,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: format!("2026-02-25T00:00:0{sequence}Z"),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence * 1_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(session_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn seed_mart(out_dir: &Path) -> Vec<AgentLogEvent> {
+    let mut tool_event = sample_event("evt-3", "session-b", 3, "running migrations");
+    tool_event.record_format = RecordFormat::ToolResult;
+    tool_event.event_type = EventType::ToolOutput;
+    tool_event.role = ActorRole::Tool;
+    tool_event.tool_name = Some("shell".to_string());
+    tool_event.content_text = None;
+    tool_event.tool_result_text = Some("error: sqlite database is locked".to_string());
+    let events = vec![
+        sample_event("evt-1", "session-a", 1, "why is the sqlite database locked"),
+        sample_event("evt-2", "session-a", 2, "try closing the other connection"),
+        tool_event,
+    ];
+    write_events_to_sqlite(
+        &out_dir.join("mart.sqlite"),
+        &events,
+        SqliteWriterConfig::default(),
+    )
+    .expect("events should be written");
+    events
+}
+
+fn run_logit(out_dir: &Path, args: &[&str]) -> (Option<i32>, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(out_dir)
+        .arg("--cwd")
+        .arg(out_dir)
+        .arg("--out-dir")
+        .arg(out_dir)
+        .args(args)
+        .output()
+        .expect("command should execute");
+    let envelope =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    (output.status.code(), envelope)
+}
+
+fn hit_ids(envelope: &Value) -> Vec<String> {
+    envelope
+        .pointer("/data/hits")
+        .and_then(Value::as_array)
+        .map(|hits| {
+            hits.iter()
+                .filter_map(|hit| hit.get("event_id").and_then(Value::as_str))
+                .map(ToOwned::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn search_returns_ranked_hits_with_snippets_and_session_context() {
+    let out_dir = unique_temp_dir("logit-query-search");
+    seed_mart(&out_dir);
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "database locked"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["command"], "query.search");
+    assert_eq!(
+        envelope["meta"]["match_expression"],
+        "\"database\" \"locked\""
+    );
+
+    let mut ids = hit_ids(&envelope);
+    ids.sort();
+    assert_eq!(ids, ["evt-1", "evt-3"]);
+
+    let hits = envelope["data"]["hits"]
+        .as_array()
+        .expect("hits should be an array");
+    let tool_hit = hits
+        .iter()
+        .find(|hit| hit["event_id"] == "evt-3")
+        .expect("tool output should match");
+    assert_eq!(tool_hit["tool_name"], "shell");
+    assert_eq!(tool_hit["session_id"], "session-b");
+    assert!(
+        tool_hit["snippet"]
+            .as_str()
+            .is_some_and(|snippet| snippet.contains("<mark>locked</mark>"))
+    );
+    let prompt_hit = hits
+        .iter()
+        .find(|hit| hit["event_id"] == "evt-1")
+        .expect("prompt should match");
+    assert_eq!(prompt_hit["session_event_count"], 2);
+    assert_eq!(
+        prompt_hit["session_last_timestamp_utc"],
+        "2026-02-25T00:00:02Z"
+    );
+    assert!(prompt_hit["score"].is_number());
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "--row-cap", "1", "locked"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(hit_ids(&envelope).len(), 1);
+    assert_eq!(envelope["meta"]["truncated"], true);
+}
+
+#[test]
+fn search_quotes_terms_by_default_and_accepts_raw_match_syntax() {
+    let out_dir = unique_temp_dir("logit-query-search-raw");
+    seed_mart(&out_dir);
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "sqlite-database"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(hit_ids(&envelope).len(), 2);
+
+    let (code, envelope) = run_logit(
+        &out_dir,
+        &["query", "search", "--raw", "tool_result_text: locked"],
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(hit_ids(&envelope), ["evt-3"]);
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "--raw", "locked AND ("]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["ok"], false);
+    assert_eq!(envelope["error"]["code"], "query_execution_failed");
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "   "]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["error"]["code"], "query_search_terms_empty");
+}
+
+#[test]
+fn rewritten_events_are_reindexed_and_missing_index_is_backfilled() {
+    let out_dir = unique_temp_dir("logit-query-search-sync");
+    let mut events = seed_mart(&out_dir);
+    let sqlite_path = out_dir.join("mart.sqlite");
+
+    events[0].content_text = Some("rename the parser module".to_string());
+    write_events_to_sqlite(&sqlite_path, &events[..1], SqliteWriterConfig::default())
+        .expect("events should be rewritten");
+    let count_matches = |expression: &str| -> i64 {
+        let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+        connection
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM {EVENTS_FTS_TABLE} WHERE {EVENTS_FTS_TABLE} MATCH ?1"
+                ),
+                [expression],
+                |row| row.get(0),
+            )
+            .expect("match query should succeed")
+    };
+    assert_eq!(count_matches("parser"), 1);
+    assert_eq!(count_matches("database"), 1);
+
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    connection
+        .execute_batch(&format!("DROP TABLE {EVENTS_FTS_TABLE}"))
+        .expect("fts table should drop");
+    ensure_sqlite_schema(&connection).expect("schema ensure should backfill");
+    assert_eq!(count_matches("parser"), 1);
+    assert_eq!(count_matches("connection"), 1);
+}
+
+#[test]
+fn search_reads_the_mart_without_creating_or_migrating_it() {
+    let out_dir = unique_temp_dir("logit-query-search-read-only");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let sqlite_path = out_dir.join("mart.sqlite");
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "locked"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["error"]["code"], "query_mart_unavailable");
+    assert!(!sqlite_path.exists());
+
+    seed_mart(&out_dir);
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    connection
+        .execute_batch(&format!(
+            "UPDATE {SCHEMA_META_TABLE} SET migration_version = 2, migration_name = 'ingest_run_metadata'"
+        ))
+        .expect("migration version should rewind");
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "locked"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["error"]["code"], "query_schema_migration_pending");
+    let status = migration_status(&connection).expect("status should load");
+    assert_eq!(status.current_version, 2);
+
+    ensure_sqlite_schema(&connection).expect("mart should migrate");
+    connection
+        .execute_batch(&format!("DROP TABLE {EVENTS_FTS_TABLE}"))
+        .expect("fts table should drop");
+    let (code, envelope) = run_logit(&out_dir, &["query", "search", "locked"]);
+    assert_eq!(code, Some(1));
+    assert_eq!(envelope["error"]["code"], "query_search_index_missing");
+    let fts_tables: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = ?1",
+            [EVENTS_FTS_TABLE],
+            |row| row.get(0),
+        )
+        .expect("sqlite_master should be readable");
+    assert_eq!(fts_tables, 0);
+}
+
+#[test]
+fn catalog_lists_fts_relation_and_schema_hides_shadow_tables() {
+    let out_dir = unique_temp_dir("logit-query-search-catalog");
+    seed_mart(&out_dir);
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "catalog"]);
+    assert_eq!(code, Some(0));
+    let relations = envelope["data"]["relations"]
+        .as_array()
+        .expect("relations should be an array");
+    assert!(relations.iter().any(|relation| {
+        relation["name"] == EVENTS_FTS_TABLE && relation["kind"] == "virtual_table"
+    }));
+    let concepts = envelope["data"]["concepts"]
+        .as_array()
+        .expect("concepts should be an array");
+    assert!(
+        concepts
+            .iter()
+            .any(|concept| concept["primary_relation"] == EVENTS_FTS_TABLE)
+    );
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "schema"]);
+    assert_eq!(code, Some(0));
+    let names = envelope["data"]["tables"]
+        .as_array()
+        .expect("tables should be an array")
+        .iter()
+        .filter_map(|table| table["name"].as_str())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&EVENTS_FTS_TABLE));
+    assert!(
+        !names
+            .iter()
+            .any(|name| name.starts_with("agentlog_events_fts_"))
+    );
+
+    let (code, envelope) = run_logit(
+        &out_dir,
+        &[
+            "query",
+            "sql",
+            "SELECT event_id FROM agentlog_events_fts WHERE agentlog_events_fts MATCH 'connection'",
+        ],
+    );
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["data"]["rows"][0]["event_id"], "evt-2");
+}
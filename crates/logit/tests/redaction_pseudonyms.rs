@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::normalize::redact_events;
+use logit::snapshot::samples::{RepresentativeSample, redact_and_truncate_samples_with_rules};
+use logit::utils::redaction::{
+    PseudonymKey, RedactionPolicy, RedactionRules, pseudonym_key_path, redact_text,
+};
+use regex::Regex;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn pseudonym_rules(seed: u8) -> RedactionRules {
+    RedactionRules::default()
+        .with_pseudonym_key(PseudonymKey::from_bytes(&[seed; 32]).expect("key should be valid"))
+}
+
+fn sample_event(event_id: &str, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: 0,
+        sequence_source: Some(1),
+        source_kind: AgentSource::Claude,
+        source_path: format!("/tmp/claude/{event_id}.jsonl"),
+        source_record_locator: "line:1".to_string(),
+        source_record_hash: None,
+        adapter_name: AgentSource::Claude,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: "2026-02-25T00:00:00Z".to_string(),
+        timestamp_unix_ms: 1_771_977_600_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(event_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+#[test]
+fn pseudonyms_are_keyed_deterministic_and_keep_context() {
+    let rules = pseudonym_rules(7);
+    let input = "mail ada@example.com with Bearer abcdefgh12345678 and password=hunter22";
+    let first = redact_text(input, RedactionPolicy::SecretsAndPii, &rules);
+    let second = redact_text(input, RedactionPolicy::SecretsAndPii, &rules);
+    assert_eq!(first, second);
+
+    let shape = Regex::new(
+        r"^mail \[email:[0-9a-f]{12}\] with Bearer \[bearer_token:[0-9a-f]{12}\] and password=\[secret_assignment:[0-9a-f]{12}\]$",
+    )
+    .expect("shape regex should compile");
+    assert!(shape.is_match(&first.text), "got {}", first.text);
+    assert!(!first.text.contains("ada@example.com"));
+    assert!(!first.text.contains("hunter22"));
+    assert!(first.pii_redacted);
+
+    let other_key = redact_text(input, RedactionPolicy::SecretsAndPii, &pseudonym_rules(8));
+    assert_ne!(first.text, other_key.text);
+
+    let masked = redact_text(
+        input,
+        RedactionPolicy::SecretsAndPii,
+        &RedactionRules::default(),
+    );
+    assert_eq!(
+        masked.text,
+        "mail [REDACTED] with Bearer [REDACTED] and password=[REDACTED]"
+    );
+}
+
+#[test]
+fn normalized_events_share_tokens_for_the_same_value() {
+    let rules = pseudonym_rules(3);
+    let mut events = vec![
+        sample_event("session-a", "ping ada@example.com about the deploy"),
+        sample_event("session-b", "ada@example.com approved it"),
+        sample_event("session-c", "bob@example.com approved it"),
+    ];
+    redact_events(&mut events, RedactionPolicy::SecretsAndPii, &rules);
+
+    let token = |event: &AgentLogEvent| {
+        let text = event.content_text.as_deref().unwrap_or_default();
+        let start = text.find("[email:").expect("email token should exist");
+        text[start..start + "[email:".len() + 13].to_string()
+    };
+    assert_eq!(token(&events[0]), token(&events[1]));
+    assert_ne!(token(&events[0]), token(&events[2]));
+    assert!(events.iter().all(|event| event.pii_redacted == Some(true)));
+
+    let samples = vec![RepresentativeSample {
+        source_kind: AgentSource::Claude,
+        source_path: "/tmp/claude/session-a.jsonl".to_string(),
+        source_record_locator: "line:1".to_string(),
+        sample_rank: 1,
+        event_kind: None,
+        record: serde_json::json!({ "message": { "text": "from ada@example.com" } }),
+    }];
+    let redacted = redact_and_truncate_samples_with_rules(&samples, 240, &rules);
+    assert_eq!(
+        redacted[0].record["message"]["text"],
+        format!("from {}", token(&events[0]))
+    );
+}
+
+#[test]
+fn pseudonym_key_is_created_once_in_out_dir() {
+    let out_dir = unique_temp_dir("logit-pseudonym-key");
+    let key_path = pseudonym_key_path(&out_dir);
+    assert!(!key_path.exists());
+
+    let created = PseudonymKey::load_or_create(&out_dir).expect("key should be created");
+    let encoded = std::fs::read_to_string(&key_path).expect("key file should exist");
+    assert_eq!(encoded.trim().len(), 64);
+    let reloaded = PseudonymKey::load_or_create(&out_dir).expect("key should reload");
+    assert_eq!(created, reloaded);
+    assert_eq!(format!("{created:?}"), "PseudonymKey(..)");
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&key_path)
+            .expect("key metadata should be readable")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    std::fs::write(&key_path, "not-hex").expect("key file should be writable");
+    assert!(PseudonymKey::load_or_create(&out_dir).is_err());
+    assert!(PseudonymKey::from_bytes(&[1; 8]).is_err());
+}
@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::cli::commands::redact::{RedactTestArgs, build_test_report, render_text_report};
+use logit::config::RuntimePaths;
+use logit::models::AgentSource;
+use logit::snapshot::samples::{RepresentativeSample, redact_and_truncate_samples_with_rules};
+use logit::utils::redaction::{
+    RedactionPolicy, RedactionRules, find_redactions, redact_text, redaction_catalog_classes,
+};
+
+const RULES: &str = r#"
+allowlist = ["sk-fixture000000000000", "support@acme.test"]
+
+[[rules]]
+class = "acme_live_token"
+pattern = "acme_live_[A-Za-z0-9]{12,}"
+
+[[rules]]
+class = "acme_customer_id"
+pattern = "\\bCUST-[0-9]{6}\\b"
+category = "pii"
+"#;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+#[test]
+fn user_rules_extend_catalog_and_respect_policy_category() {
+    let rules = RedactionRules::parse_toml(RULES).expect("rules should parse");
+    let catalog = redaction_catalog_classes(&rules);
+    assert_eq!(
+        &catalog[catalog.len() - 2..],
+        ["acme_live_token", "acme_customer_id"]
+    );
+
+    let input = "deploy with acme_live_9f8e7d6c5b4a for CUST-004211";
+    let secrets = redact_text(input, RedactionPolicy::SecretsOnly, &rules);
+    assert_eq!(secrets.text, "deploy with [REDACTED] for CUST-004211");
+    assert_eq!(secrets.redaction_classes, ["acme_live_token"]);
+
+    let all = redact_text(input, RedactionPolicy::SecretsAndPii, &rules);
+    assert_eq!(all.text, "deploy with [REDACTED] for [REDACTED]");
+    assert_eq!(
+        all.redaction_classes,
+        ["acme_customer_id", "acme_live_token"]
+    );
+}
+
+#[test]
+fn allowlist_entries_suppress_builtin_matches() {
+    let rules = RedactionRules::parse_toml(RULES).expect("rules should parse");
+    let input =
+        "fixture key sk-fixture000000000000, real key sk-abcdefghijklmnop, mail support@acme.test";
+
+    let result = redact_text(input, RedactionPolicy::SecretsAndPii, &rules);
+    assert_eq!(
+        result.text,
+        "fixture key sk-fixture000000000000, real key [REDACTED], mail support@acme.test"
+    );
+    assert_eq!(result.redaction_classes, ["api_token"]);
+
+    let samples = vec![RepresentativeSample {
+        source_kind: AgentSource::Codex,
+        source_path: "/tmp/codex.jsonl".to_string(),
+        source_record_locator: "line:1".to_string(),
+        sample_rank: 1,
+        event_kind: None,
+        record: serde_json::json!({ "text": "acme_live_9f8e7d6c5b4a sk-fixture000000000000" }),
+    }];
+    let redacted = redact_and_truncate_samples_with_rules(&samples, 240, &rules);
+    assert_eq!(
+        redacted[0].record["text"],
+        "[REDACTED] sk-fixture000000000000"
+    );
+}
+
+#[test]
+fn allowlist_entries_match_whole_spans_only() {
+    let rules = RedactionRules::parse_toml(
+        "allowlist = [\"sk-fixture000000000000\", \"re:sk-test[a-z]{16}\"]\n",
+    )
+    .expect("rules should parse");
+    let input = "sk-fixture000000000000abcd sk-testabcdefghijklmnop sk-testabcdefghijklmnopq1";
+
+    let result = redact_text(input, RedactionPolicy::SecretsOnly, &rules);
+    assert_eq!(result.text, "[REDACTED] sk-testabcdefghijklmnop [REDACTED]");
+
+    let blob = format!("sk-fixture000000000000{}", "\u{1}".repeat(20));
+    let result = redact_text(&blob, RedactionPolicy::SecretsOnly, &rules);
+    assert_eq!(result.text, "[REDACTED]");
+    assert_eq!(result.redaction_classes, ["binary_blob"]);
+}
+
+#[test]
+fn allowlist_entries_match_the_masked_value_of_keyed_secrets() {
+    let rules = RedactionRules::parse_toml(
+        "allowlist = [\"fixture123\", \"fixturetoken99\", \"re:demo[0-9]+\"]\n",
+    )
+    .expect("rules should parse");
+    let input = "password=fixture123 password=fixture1234 Bearer fixturetoken99 \
+                 https://x.test/cb?access_token=demo42&x=1";
+
+    let result = redact_text(input, RedactionPolicy::SecretsOnly, &rules);
+    assert_eq!(
+        result.text,
+        "password=fixture123 password=[REDACTED] Bearer fixturetoken99 \
+         https://x.test/cb?access_token=demo42&x=1"
+    );
+    assert_eq!(result.redaction_classes, ["secret_assignment"]);
+
+    let findings = find_redactions(input, RedactionPolicy::SecretsOnly, &rules);
+    assert_eq!(findings.len(), 1);
+    assert_eq!(findings[0].class_name, "secret_assignment");
+    assert_eq!(findings[0].column, 21);
+}
+
+#[test]
+fn invalid_rule_files_are_rejected() {
+    for (input, expected) in [
+        (
+            "[[rules]]\nclass = \"Email\"\npattern = \"x\"\n",
+            "lowercase slug",
+        ),
+        (
+            "[[rules]]\nclass = \"email\"\npattern = \"x\"\n",
+            "already defined",
+        ),
+        (
+            "[[rules]]\nclass = \"broken\"\npattern = \"(\"\n",
+            "invalid pattern",
+        ),
+        (
+            "[[rules]]\nclass = \"empty\"\npattern = \"a*\"\n",
+            "empty string",
+        ),
+        ("allowlist = [\"\"]\n", "must not be empty"),
+        ("allowlist = [\"re:\"]\n", "must not be empty"),
+        ("allowlist = [\"re:(\"]\n", "invalid pattern"),
+        (
+            "[[rules]]\nclass = \"x\"\npattern = \"x\"\ncategory = \"other\"\n",
+            "valid TOML",
+        ),
+    ] {
+        let error = RedactionRules::parse_toml(input).expect_err("rules should be rejected");
+        assert!(
+            format!("{error:#}").contains(expected),
+            "expected `{expected}` in `{error:#}`"
+        );
+    }
+    assert_eq!(
+        RedactionRules::load(Path::new("/nonexistent/redaction.toml"))
+            .expect("missing rules file should load as empty"),
+        RedactionRules::default()
+    );
+}
+
+#[test]
+fn findings_report_positions_without_matched_values() {
+    let rules = RedactionRules::parse_toml(RULES).expect("rules should parse");
+    let input = "first line\nAuthorization: Bearer sk-abcdefghijklmnop\nuser CUST-004211";
+    let findings = find_redactions(input, RedactionPolicy::SecretsAndPii, &rules);
+
+    let summary = findings
+        .iter()
+        .map(|finding| {
+            (
+                finding.class_name.as_str(),
+                finding.line,
+                finding.column,
+                finding.length,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        [("bearer_token", 2, 16, 26), ("acme_customer_id", 3, 6, 11)]
+    );
+}
+
+#[test]
+fn redact_test_command_reads_rules_from_home_and_input_files() {
+    let home = unique_temp_dir("logit-redact-test-home");
+    write_file(&home.join(".logit/redaction.toml"), RULES);
+    let input_path = home.join("notes.txt");
+    write_file(
+        &input_path,
+        "token acme_live_9f8e7d6c5b4a\nmail ops@example.com\n",
+    );
+
+    let runtime_paths = RuntimePaths {
+        home_dir: home.clone(),
+        cwd: home.clone(),
+        out_dir: home.join("out"),
+    };
+    let args = RedactTestArgs {
+        input: input_path.display().to_string(),
+        redaction: RedactionPolicy::SecretsAndPii,
+        rules: None,
+        json: false,
+    };
+    let report = build_test_report(&args, &runtime_paths).expect("report should build");
+    assert_eq!(report.input, input_path.display().to_string());
+    assert_eq!(report.allowlist_entries, 2);
+    assert_eq!(report.findings.len(), 2);
+    assert_eq!(report.class_counts.get("acme_live_token"), Some(&1));
+    assert_eq!(report.class_counts.get("email"), Some(&1));
+
+    let rendered = render_text_report(&report);
+    assert!(rendered.contains("finding: class=email line=2 column=6 length=15"));
+    assert!(!rendered.contains("acme_live_9f8e7d6c5b4a"));
+    assert!(!rendered.contains("ops@example.com"));
+
+    let inline = RedactTestArgs {
+        input: "password=hunter22".to_string(),
+        redaction: RedactionPolicy::SecretsOnly,
+        rules: Some(home.join("missing.toml")),
+        json: true,
+    };
+    let report = build_test_report(&inline, &runtime_paths).expect("report should build");
+    assert_eq!(report.input, "<text>");
+    assert_eq!(report.allowlist_entries, 0);
+    assert_eq!(report.class_counts.get("secret_assignment"), Some(&1));
+}
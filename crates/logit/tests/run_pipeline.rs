@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::cli::app::SourceFilterArgs;
+use logit::cli::commands::run::{RunArgs, run};
+use logit::config::{LogitConfig, RuntimePaths};
+use logit::pipeline::{RUN_MANIFEST_SCHEMA_VERSION, RunGate, RunGateFailure, run_manifest_path};
+use logit::utils::redaction::RedactionPolicy;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test file should be writable");
+}
+
+fn read_json(path: &Path) -> Value {
+    let raw = std::fs::read_to_string(path).expect("json artifact should be readable");
+    serde_json::from_str(&raw).expect("json artifact should parse")
+}
+
+fn run_args(source_root: &Path, gates: Vec<RunGate>) -> RunArgs {
+    RunArgs {
+        source_root: Some(source_root.to_path_buf()),
+        sample_size: 2,
+        fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        gates,
+        no_gates: false,
+        filters: SourceFilterArgs::default(),
+    }
+}
+
+fn runtime_paths(home_dir: &Path, out_dir: &Path) -> RuntimePaths {
+    RuntimePaths {
+        home_dir: home_dir.to_path_buf(),
+        cwd: home_dir.to_path_buf(),
+        out_dir: out_dir.to_path_buf(),
+    }
+}
+
+#[test]
+fn run_executes_all_stages_and_threads_run_id_through_artifacts() {
+    let source_root = unique_temp_dir("logit-run-source");
+    let home_dir = unique_temp_dir("logit-run-home");
+    let out_dir = unique_temp_dir("logit-run-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    write_file(
+        &source_root.join(".claude/projects/project_session.jsonl"),
+        include_str!("../../../fixtures/claude/project_session.jsonl"),
+    );
+
+    run(
+        &run_args(&source_root, vec![RunGate::AdapterFailed]),
+        &LogitConfig::default(),
+        &runtime_paths(&home_dir, &out_dir),
+    )
+    .expect("run should succeed");
+
+    let manifest = read_json(&run_manifest_path(&out_dir));
+    assert_eq!(manifest["schema_version"], RUN_MANIFEST_SCHEMA_VERSION);
+    assert_eq!(manifest["status"], "success");
+    assert_eq!(manifest["exit_code"], 0);
+    assert_eq!(manifest["gates"], serde_json::json!(["adapter-failed"]));
+    assert!(manifest["gate_tripped"].is_null());
+    assert_eq!(manifest["config"]["run"]["validation_mode"], "strict");
+    assert!(manifest["config"]["settings"]["run.gates"].is_object());
+    let run_id = manifest["run_id"]
+        .as_str()
+        .expect("manifest should carry a run id")
+        .to_string();
+    assert!(run_id.starts_with("run-"), "{run_id}");
+
+    let stages = manifest["stages"]
+        .as_array()
+        .expect("manifest stages should be an array");
+    let names = stages
+        .iter()
+        .map(|stage| stage["stage"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["snapshot", "normalize", "validate", "ingest"]);
+    for stage in stages {
+        assert_eq!(stage["status"], "success", "{stage}");
+        assert_eq!(stage["exit_code"], 0, "{stage}");
+        let artifacts = stage["artifacts"]
+            .as_array()
+            .expect("stage artifacts should be an array");
+        assert!(!artifacts.is_empty(), "{stage}");
+        for artifact in artifacts {
+            let sha256 = artifact["sha256"].as_str().unwrap_or_default();
+            assert_eq!(sha256.len(), 64, "{artifact}");
+            assert!(Path::new(artifact["path"].as_str().unwrap_or_default()).is_file());
+        }
+    }
+
+    let events = std::fs::read_to_string(out_dir.join("events.jsonl"))
+        .expect("events artifact should exist");
+    assert!(!events.trim().is_empty());
+    for line in events.lines() {
+        let event = serde_json::from_str::<Value>(line).expect("event row should parse");
+        assert_eq!(event["run_id"], run_id.as_str());
+    }
+    assert_eq!(
+        read_json(&out_dir.join("stats.json"))["run_id"],
+        run_id.as_str()
+    );
+    assert_eq!(
+        read_json(&out_dir.join("snapshot/index.json"))["run_id"],
+        run_id.as_str()
+    );
+    assert_eq!(
+        read_json(&out_dir.join("validate/report.json"))["run_id"],
+        run_id.as_str()
+    );
+    assert_eq!(
+        read_json(&out_dir.join("ingest/report.json"))["ingest_run_id"],
+        run_id.as_str()
+    );
+}
+
+#[test]
+fn run_stops_at_tripped_validation_gate_and_records_skipped_stages() {
+    let source_root = unique_temp_dir("logit-run-gated-source");
+    let home_dir = unique_temp_dir("logit-run-gated-home");
+    let out_dir = unique_temp_dir("logit-run-gated-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_empty.jsonl"),
+        r#"{"session_id":"codex-s-009","event_id":"evt-001","event_type":"user_prompt","created_at":"2026-02-01T12:00:00Z","text":""}
+{"session_id":"codex-s-009","event_id":"evt-002","event_type":"assistant_response","created_at":"2026-02-01T12:00:02Z","text":""}
+"#,
+    );
+
+    let error = run(
+        &run_args(&source_root, vec![RunGate::ValidationErrors]),
+        &LogitConfig::default(),
+        &runtime_paths(&home_dir, &out_dir),
+    )
+    .expect_err("strict validation errors should trip the gate");
+    let failure = error
+        .downcast_ref::<RunGateFailure>()
+        .expect("error should be a run gate failure");
+    assert_eq!(failure.trip.gate, RunGate::ValidationErrors);
+
+    let manifest = read_json(&run_manifest_path(&out_dir));
+    assert_eq!(manifest["status"], "gated");
+    assert_eq!(manifest["exit_code"], 2);
+    assert_eq!(manifest["run_id"], failure.run_id.as_str());
+    assert_eq!(manifest["gate_tripped"]["gate"], "validation-errors");
+    assert_eq!(manifest["gate_tripped"]["stage"], "validate");
+    let statuses = manifest["stages"]
+        .as_array()
+        .expect("manifest stages should be an array")
+        .iter()
+        .map(|stage| stage["status"].as_str().unwrap_or_default())
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, ["success", "success", "gated", "skipped"]);
+    assert!(!out_dir.join("mart.sqlite").exists());
+}
+
+#[test]
+fn run_records_failed_stage_and_returns_its_error() {
+    let source_root = unique_temp_dir("logit-run-failed-source");
+    let home_dir = unique_temp_dir("logit-run-failed-home");
+    let out_dir = unique_temp_dir("logit-run-failed-out");
+    write_file(
+        &source_root.join(".codex/sessions/rollout_primary.jsonl"),
+        include_str!("../../../fixtures/codex/rollout_primary.jsonl"),
+    );
+    std::fs::write(
+        source_root.join(".codex/sessions/rollout_binary.jsonl"),
+        [0xff, 0xfe, 0x00, 0x0a],
+    )
+    .expect("binary rollout should be writable");
+
+    let error = run(
+        &run_args(&source_root, Vec::new()),
+        &LogitConfig::default(),
+        &runtime_paths(&home_dir, &out_dir),
+    )
+    .expect_err("unreadable rollout should fail the snapshot stage");
+    assert!(error.downcast_ref::<RunGateFailure>().is_none());
+
+    let manifest = read_json(&run_manifest_path(&out_dir));
+    assert_eq!(manifest["status"], "failed");
+    assert_eq!(manifest["exit_code"], 1);
+    assert_eq!(manifest["stages"][0]["status"], "failed");
+    assert!(manifest["stages"][0]["error"].is_string());
+    assert_eq!(manifest["stages"][3]["status"], "skipped");
+}
@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::thread::JoinHandle;
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::serve::http::{MAX_BODY_BYTES, MAX_HEADER_BYTES, parse_query_string, percent_decode};
+use logit::serve::{
+    DEFAULT_MAX_CONNECTIONS, REQUEST_READ_TIMEOUT, ServeConfig, Server, is_loopback_authority,
+};
+use logit::sqlite::{SqliteWriterConfig, write_events_to_sqlite};
+use serde_json::{Value, json};
+
+const TOKEN: &str = "local-test-token";
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn sample_event(event_id: &str, session_id: &str, sequence: u64, content: &str) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: sequence,
+        sequence_source: Some(sequence),
+        source_kind: AgentSource::Codex,
+        source_path: "/tmp/codex/session.jsonl".to_string(),
+        source_record_locator: format!("line:{sequence}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Codex,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: if sequence % 2 == 1 {
+            EventType::Prompt
+        } else {
+            EventType::Response
+        },
+        role: if sequence % 2 == 1 {
+            ActorRole::User
+        } else {
+            ActorRole::Assistant
+        },
+        timestamp_utc: format!("2026-02-25T00:00:0{sequence}Z"),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence * 1_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(session_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn seed_mart(dir: &Path) -> PathBuf {
+    let mart = dir.join("mart.sqlite");
+    let events = vec![
+        sample_event("evt-1", "session-a", 1, "why is the sqlite database locked"),
+        sample_event("evt-2", "session-a", 2, "another connection holds the lock"),
+        sample_event("evt-3", "session-b", 3, "summarize the release notes"),
+    ];
+    write_events_to_sqlite(&mart, &events, SqliteWriterConfig::default())
+        .expect("events should be written");
+    mart
+}
+
+struct RunningServer {
+    addr: SocketAddr,
+    shutdown: std::sync::Arc<std::sync::atomic::AtomicBool>,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+impl RunningServer {
+    fn start(config: ServeConfig) -> Self {
+        let server = Server::bind(0, config).expect("server should bind");
+        let addr = server.local_addr().expect("server should report address");
+        let shutdown = server.shutdown_flag();
+        let handle = std::thread::spawn(move || server.run());
+        Self {
+            addr,
+            shutdown,
+            handle,
+        }
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        target: &str,
+        token: Option<&str>,
+        body: Option<Value>,
+    ) -> (u16, Value) {
+        let auth = token
+            .map(|token| format!("Authorization: Bearer {token}\r\n"))
+            .unwrap_or_default();
+        self.request_with_headers(method, target, &format!("Host: localhost\r\n{auth}"), body)
+    }
+
+    fn request_with_headers(
+        &self,
+        method: &str,
+        target: &str,
+        headers: &str,
+        body: Option<Value>,
+    ) -> (u16, Value) {
+        let body = body.map(|body| body.to_string()).unwrap_or_default();
+        self.raw_request(&format!(
+            "{method} {target} HTTP/1.1\r\n{headers}Content-Length: {}\r\n\r\n{body}",
+            body.len()
+        ))
+    }
+
+    fn raw_request(&self, request: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(self.addr).expect("client should connect");
+        stream
+            .write_all(request.as_bytes())
+            .expect("request should be written");
+        let mut raw = String::new();
+        stream
+            .read_to_string(&mut raw)
+            .expect("response should be readable");
+        let (head, payload) = raw
+            .split_once("\r\n\r\n")
+            .expect("response should have headers and body");
+        let status = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|status| status.parse().ok())
+            .expect("response should have a status code");
+        assert!(head.contains("Content-Type: application/json"));
+        (
+            status,
+            serde_json::from_str(payload).expect("response body should be json"),
+        )
+    }
+
+    fn stop(self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        let result = self.handle.join().expect("server thread should not panic");
+        assert!(result.is_ok());
+    }
+}
+
+fn config(dir: &Path, token: Option<&str>) -> ServeConfig {
+    ServeConfig {
+        sqlite_path: seed_mart(dir),
+        token: token.map(ToOwned::to_owned),
+        request_log: Some(dir.join("serve/requests.jsonl")),
+        request_timeout: REQUEST_READ_TIMEOUT,
+        max_connections: DEFAULT_MAX_CONNECTIONS,
+    }
+}
+
+#[test]
+fn query_endpoints_reuse_envelope_contract_and_guardrails() {
+    let dir = unique_temp_dir("logit-serve-query");
+    let server = RunningServer::start(config(&dir, None));
+
+    let (status, envelope) = server.request(
+        "POST",
+        "/query/sql",
+        None,
+        Some(json!({
+            "sql": "SELECT event_id FROM agentlog_events WHERE session_id = ?1 ORDER BY event_id",
+            "params": ["session-a"],
+            "row_cap": 1
+        })),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(envelope["ok"], true);
+    assert_eq!(envelope["command"], "query.sql");
+    assert_eq!(envelope["data"]["rows"], json!([{ "event_id": "evt-1" }]));
+    assert_eq!(envelope["meta"]["truncated"], true);
+    assert_eq!(envelope["meta"]["params_count"], 1);
+
+    let (status, envelope) = server.request(
+        "POST",
+        "/query/sql",
+        None,
+        Some(json!({ "sql": "DELETE FROM agentlog_events" })),
+    );
+    assert_eq!(status, 400);
+    assert_eq!(envelope["error"]["code"], "sql_guardrail_violation");
+
+    let (status, envelope) =
+        server.request("POST", "/query/sql", None, Some(json!({ "query": 1 })));
+    assert_eq!(status, 400);
+    assert_eq!(envelope["error"]["code"], "serve_request_invalid");
+
+    let (status, envelope) = server.request("GET", "/query/schema", None, None);
+    assert_eq!(status, 200);
+    assert_eq!(envelope["command"], "query.schema");
+    assert!(envelope["meta"]["view_count"].as_u64().unwrap_or(0) > 0);
+
+    let (status, envelope) = server.request("GET", "/query/catalog?verbose=true", None, None);
+    assert_eq!(status, 200);
+    assert_eq!(envelope["meta"]["verbose"], true);
+
+    let (status, envelope) = server.request(
+        "POST",
+        "/query/search",
+        None,
+        Some(json!({ "terms": "locked" })),
+    );
+    assert_eq!(status, 200);
+    assert_eq!(envelope["meta"]["row_count"], 1);
+
+    let (status, envelope) = server.request("DELETE", "/query/sql", None, None);
+    assert_eq!(status, 405);
+    assert_eq!(envelope["error"]["code"], "serve_method_not_allowed");
+    let (status, envelope) = server.request("GET", "/nope", None, None);
+    assert_eq!(status, 404);
+    assert_eq!(envelope["error"]["code"], "serve_route_not_found");
+
+    server.stop();
+}
+
+#[test]
+fn session_endpoints_list_sessions_and_render_transcripts() {
+    let dir = unique_temp_dir("logit-serve-sessions");
+    let server = RunningServer::start(config(&dir, None));
+
+    let (status, envelope) = server.request("GET", "/sessions", None, None);
+    assert_eq!(status, 200);
+    assert_eq!(envelope["meta"]["row_count"], 2);
+    assert_eq!(envelope["data"]["sessions"][0]["session_id"], "session-b");
+    assert_eq!(envelope["data"]["sessions"][1]["event_count"], 2);
+
+    let (status, envelope) = server.request(
+        "GET",
+        "/sessions/session-a/transcript?format=markdown&turns=1",
+        None,
+        None,
+    );
+    assert_eq!(status, 200);
+    assert_eq!(envelope["command"], "sessions.transcript");
+    assert_eq!(envelope["data"]["turn_count"], 1);
+    let document = envelope["data"]["document"].as_str().unwrap_or_default();
+    assert!(document.starts_with("# Session `session-a`"));
+    assert!(document.contains("another connection holds the lock"));
+
+    let (status, envelope) = server.request("GET", "/sessions/session%2Dz/transcript", None, None);
+    assert_eq!(status, 404);
+    assert_eq!(envelope["error"]["code"], "session_not_found");
+    assert_eq!(envelope["error"]["details"]["session_id"], "session-z");
+
+    let (status, envelope) = server.request(
+        "GET",
+        "/sessions/session-a/transcript?format=pdf",
+        None,
+        None,
+    );
+    assert_eq!(status, 400);
+    assert_eq!(envelope["error"]["code"], "transcript_request_invalid");
+
+    server.stop();
+}
+
+#[test]
+fn bearer_token_is_required_and_requests_are_logged() {
+    let dir = unique_temp_dir("logit-serve-auth");
+    let server = RunningServer::start(config(&dir, Some(TOKEN)));
+
+    let (status, envelope) = server.request("GET", "/health", None, None);
+    assert_eq!(status, 401);
+    assert_eq!(envelope["error"]["code"], "serve_unauthorized");
+    let (status, _) = server.request("GET", "/health", Some("wrong-token"), None);
+    assert_eq!(status, 401);
+    let (status, envelope) = server.request("GET", "/health", Some(TOKEN), None);
+    assert_eq!(status, 200);
+    assert_eq!(envelope["data"]["status"], "ok");
+
+    server.stop();
+
+    let log = std::fs::read_to_string(dir.join("serve/requests.jsonl"))
+        .expect("request log should be written");
+    let entries = log
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).expect("log line should be json"))
+        .collect::<Vec<_>>();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0]["path"], "/health");
+    assert_eq!(entries[0]["status"], 401);
+    assert_eq!(entries[0]["error_code"], "serve_unauthorized");
+    assert_eq!(entries[2]["status"], 200);
+    assert!(!log.contains(TOKEN));
+}
+
+#[test]
+fn missing_mart_maps_to_service_unavailable() {
+    let dir = unique_temp_dir("logit-serve-missing");
+    let server = RunningServer::start(ServeConfig {
+        sqlite_path: dir.join("absent/mart.sqlite"),
+        token: None,
+        request_log: None,
+        request_timeout: REQUEST_READ_TIMEOUT,
+        max_connections: DEFAULT_MAX_CONNECTIONS,
+    });
+    let (status, envelope) = server.request("GET", "/sessions", None, None);
+    assert_eq!(status, 503);
+    assert_eq!(envelope["error"]["code"], "query_mart_unavailable");
+    server.stop();
+}
+
+#[test]
+fn search_on_missing_mart_does_not_create_it() {
+    let dir = unique_temp_dir("logit-serve-search-missing");
+    let sqlite_path = dir.join("mart.sqlite");
+    let server = RunningServer::start(ServeConfig {
+        sqlite_path: sqlite_path.clone(),
+        token: None,
+        request_log: None,
+        request_timeout: REQUEST_READ_TIMEOUT,
+        max_connections: DEFAULT_MAX_CONNECTIONS,
+    });
+    let (status, envelope) = server.request(
+        "POST",
+        "/query/search",
+        None,
+        Some(json!({ "terms": "locked" })),
+    );
+    assert_eq!(status, 503);
+    assert_eq!(envelope["error"]["code"], "query_mart_unavailable");
+    server.stop();
+    assert!(!sqlite_path.exists());
+}
+
+#[test]
+fn foreign_host_or_origin_is_rejected_even_without_token() {
+    let dir = unique_temp_dir("logit-serve-host");
+    let server = RunningServer::start(config(&dir, None));
+
+    let (status, envelope) =
+        server.request_with_headers("GET", "/health", "Host: attacker.example:8765\r\n", None);
+    assert_eq!(status, 403);
+    assert_eq!(envelope["error"]["code"], "serve_host_forbidden");
+    let (status, _) = server.request_with_headers("GET", "/health", "", None);
+    assert_eq!(status, 403);
+    let (status, _) = server.request_with_headers(
+        "GET",
+        "/health",
+        "Host: localhost:8765\r\nOrigin: http://attacker.example\r\n",
+        None,
+    );
+    assert_eq!(status, 403);
+    let (status, _) = server.request_with_headers(
+        "GET",
+        "/health",
+        "Host: 127.0.0.1:8765\r\nOrigin: http://localhost:3000\r\n",
+        None,
+    );
+    assert_eq!(status, 200);
+    server.stop();
+
+    for allowed in [
+        "localhost",
+        "LOCALHOST:80",
+        "127.0.0.1:8765",
+        "[::1]",
+        "[::1]:9000",
+    ] {
+        assert!(is_loopback_authority(allowed), "{allowed}");
+    }
+    for denied in [
+        "localhost.attacker.example",
+        "127.0.0.2",
+        "::1",
+        "localhost:",
+        "0.0.0.0:8765",
+    ] {
+        assert!(!is_loopback_authority(denied), "{denied}");
+    }
+}
+
+#[test]
+fn idle_connection_does_not_block_other_clients() {
+    let dir = unique_temp_dir("logit-serve-idle");
+    let server = RunningServer::start(config(&dir, None));
+    let idle = TcpStream::connect(server.addr).expect("idle client should connect");
+
+    let started = std::time::Instant::now();
+    let (status, _) = server.request("GET", "/health", None, None);
+    assert_eq!(status, 200);
+    assert!(started.elapsed() < REQUEST_READ_TIMEOUT / 2);
+
+    drop(idle);
+    server.stop();
+}
+
+#[test]
+fn oversized_requests_are_rejected_without_unbounded_reads() {
+    let dir = unique_temp_dir("logit-serve-oversized");
+    let server = RunningServer::start(config(&dir, None));
+
+    let (status, body) = server.raw_request(&format!(
+        "POST /query/sql HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+        MAX_BODY_BYTES + 1
+    ));
+    assert_eq!(status, 413);
+    assert_eq!(body["error"]["code"], "serve_payload_too_large");
+
+    let started = std::time::Instant::now();
+    let unterminated = format!("GET /{}", "a".repeat(MAX_HEADER_BYTES - 4));
+    let (status, body) = server.raw_request(&unterminated);
+    assert_eq!(status, 400);
+    assert_eq!(body["error"]["code"], "serve_request_invalid");
+    assert!(started.elapsed() < REQUEST_READ_TIMEOUT / 2);
+
+    server.stop();
+}
+
+#[test]
+fn connections_beyond_the_cap_are_answered_busy() {
+    let dir = unique_temp_dir("logit-serve-busy");
+    let server = RunningServer::start(ServeConfig {
+        max_connections: 1,
+        ..config(&dir, None)
+    });
+    let idle = TcpStream::connect(server.addr).expect("idle client should connect");
+    std::thread::sleep(std::time::Duration::from_millis(200));
+
+    let (status, body) = server.request("GET", "/health", None, None);
+    assert_eq!(status, 503);
+    assert_eq!(body["error"]["code"], "serve_busy");
+    assert_eq!(body["error"]["details"]["max_connections"], 1);
+
+    drop(idle);
+    let deadline = std::time::Instant::now() + REQUEST_READ_TIMEOUT / 2;
+    loop {
+        let (status, _) = server.request("GET", "/health", None, None);
+        if status == 200 {
+            break;
+        }
+        assert!(
+            std::time::Instant::now() < deadline,
+            "slot was not released"
+        );
+        std::thread::sleep(std::time::Duration::from_millis(25));
+    }
+
+    server.stop();
+}
+
+#[test]
+fn query_strings_are_percent_decoded() {
+    assert_eq!(percent_decode("a%20b+c%2Fd%zz"), "a b c/d%zz");
+    let query = parse_query_string("format=markdown&turns=1..2&full");
+    assert_eq!(query.get("format").map(String::as_str), Some("markdown"));
+    assert_eq!(query.get("turns").map(String::as_str), Some("1..2"));
+    assert_eq!(query.get("full").map(String::as_str), Some(""));
+}
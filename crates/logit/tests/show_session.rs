@@ -0,0 +1,363 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use logit::cli::commands::show::{ShowSessionArgs, render_session};
+use logit::config::RuntimePaths;
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::sqlite::{SqliteWriterConfig, write_events_to_sqlite};
+use logit::transcript::{
+    RenderOptions, SessionTranscript, TranscriptEvent, TranscriptFormat, TurnRange, group_turns,
+    render,
+};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn event(
+    sequence: u64,
+    turn_id: Option<&str>,
+    record_format: RecordFormat,
+    event_type: EventType,
+    role: ActorRole,
+) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: format!("evt-{sequence}"),
+        run_id: "run-1".to_string(),
+        sequence_global: sequence,
+        sequence_source: Some(sequence),
+        source_kind: AgentSource::Claude,
+        source_path: "/tmp/claude/session.jsonl".to_string(),
+        source_record_locator: format!("line:{sequence}"),
+        source_record_hash: None,
+        adapter_name: AgentSource::Claude,
+        adapter_version: Some("v1".to_string()),
+        record_format,
+        event_type,
+        role,
+        timestamp_utc: format!("2026-02-25T00:00:{:02}Z", sequence * 2),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence * 2_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some("session-1".to_string()),
+        conversation_id: None,
+        turn_id: turn_id.map(ToOwned::to_owned),
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: None,
+        content_excerpt: None,
+        content_mime: None,
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{sequence}"),
+        canonical_hash: format!("canonical-{sequence}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn session_events() -> Vec<AgentLogEvent> {
+    let mut prompt = event(
+        1,
+        Some("turn-a"),
+        RecordFormat::Message,
+        EventType::Prompt,
+        ActorRole::User,
+    );
+    prompt.content_text = Some("list the <src> files".to_string());
+    prompt.input_tokens = Some(12);
+
+    let mut call = event(
+        2,
+        Some("turn-a"),
+        RecordFormat::ToolCall,
+        EventType::ToolInvocation,
+        ActorRole::Assistant,
+    );
+    call.tool_name = Some("shell".to_string());
+    call.tool_arguments_json = Some(r#"{"cmd":"ls src"}"#.to_string());
+
+    let mut result = event(
+        3,
+        Some("turn-a"),
+        RecordFormat::ToolResult,
+        EventType::ToolOutput,
+        ActorRole::Tool,
+    );
+    result.tool_name = Some("shell".to_string());
+    result.tool_result_text = Some(
+        (1..=40)
+            .map(|index| format!("file_{index}.rs"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    );
+    result.content_excerpt = Some("file_1.rs ... (40 entries)".to_string());
+
+    let mut response = event(
+        4,
+        Some("turn-a"),
+        RecordFormat::Message,
+        EventType::Response,
+        ActorRole::Assistant,
+    );
+    response.content_text = Some("There are 40 files.".to_string());
+    response.output_tokens = Some(30);
+    response.timestamp_quality = TimestampQuality::Derived;
+
+    let mut follow_up = event(
+        5,
+        Some("turn-b"),
+        RecordFormat::Message,
+        EventType::Prompt,
+        ActorRole::User,
+    );
+    follow_up.content_text = Some("thanks".to_string());
+
+    let mut other_session = event(
+        6,
+        Some("turn-z"),
+        RecordFormat::Message,
+        EventType::Prompt,
+        ActorRole::User,
+    );
+    other_session.session_id = Some("session-2".to_string());
+    other_session.content_text = Some("unrelated".to_string());
+
+    vec![prompt, call, result, response, follow_up, other_session]
+}
+
+fn write_events_jsonl(path: &Path, events: &[AgentLogEvent]) {
+    let rows = events
+        .iter()
+        .map(|event| serde_json::to_string(event).expect("event should encode"))
+        .collect::<Vec<_>>();
+    std::fs::create_dir_all(path.parent().expect("path should have parent"))
+        .expect("parent should be creatable");
+    std::fs::write(path, format!("{}\n", rows.join("\n"))).expect("events should be written");
+}
+
+fn show_args(session_id: &str) -> ShowSessionArgs {
+    ShowSessionArgs {
+        session_id: session_id.to_string(),
+        turns: None,
+        format: TranscriptFormat::Text,
+        events: None,
+        mart: None,
+        full: false,
+        output: None,
+    }
+}
+
+#[test]
+fn turn_ranges_parse_single_open_and_closed_forms() {
+    assert_eq!(
+        TurnRange::parse("3"),
+        Ok(TurnRange {
+            start: 3,
+            end: Some(3)
+        })
+    );
+    assert_eq!(
+        TurnRange::parse("2..5"),
+        Ok(TurnRange {
+            start: 2,
+            end: Some(5)
+        })
+    );
+    assert_eq!(
+        TurnRange::parse("4.."),
+        Ok(TurnRange {
+            start: 4,
+            end: None
+        })
+    );
+    assert_eq!(
+        TurnRange::parse("..2"),
+        Ok(TurnRange {
+            start: 1,
+            end: Some(2)
+        })
+    );
+    assert!(TurnRange::parse("0").is_err());
+    assert!(TurnRange::parse("5..2").is_err());
+    assert!(TranscriptFormat::parse("md").is_ok());
+    assert!(TranscriptFormat::parse("pdf").is_err());
+}
+
+#[test]
+fn events_without_turn_ids_start_a_turn_at_each_prompt() {
+    let events = session_events()
+        .into_iter()
+        .take(5)
+        .map(|mut event| {
+            event.turn_id = None;
+            TranscriptEvent::from_agentlog(&event)
+        })
+        .collect::<Vec<_>>();
+    let turns = group_turns(events);
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].events.len(), 4);
+    assert_eq!(turns[1].ordinal, 2);
+    assert_eq!(turns[0].token_totals(), (Some(12), Some(30), None));
+    assert_eq!(turns[0].duration_ms(), 6_000);
+}
+
+#[test]
+fn text_transcript_groups_turns_and_collapses_long_tool_output() {
+    let out_dir = unique_temp_dir("logit-show-text");
+    write_events_jsonl(&out_dir.join("events.jsonl"), &session_events());
+    let runtime_paths = RuntimePaths {
+        home_dir: out_dir.clone(),
+        cwd: out_dir.clone(),
+        out_dir: out_dir.clone(),
+    };
+
+    let rendered =
+        render_session(&show_args("session-1"), &runtime_paths).expect("session should render");
+    assert!(rendered.contains("session: session-1"));
+    assert!(rendered.contains("turns 1-2 of 2"));
+    assert!(rendered.contains(
+        "=== turn 1 [turn-a] · 2026-02-25T00:00:02Z · 4 events · 6.0s · tokens in=12 out=30 total=-"
+    ));
+    assert!(rendered.contains("[2026-02-25T00:00:04Z] tool call shell"));
+    assert!(rendered.contains("    file_1.rs ... (40 entries)"));
+    assert!(rendered.contains("[tool output collapsed: 40 lines"));
+    assert!(!rendered.contains("file_40.rs"));
+    assert!(rendered.contains("[2026-02-25T00:00:08Z~] assistant response"));
+    assert!(!rendered.contains("unrelated"));
+
+    let mut full_args = show_args("session-1");
+    full_args.full = true;
+    full_args.turns = Some(TurnRange {
+        start: 1,
+        end: Some(1),
+    });
+    let rendered = render_session(&full_args, &runtime_paths).expect("session should render");
+    assert!(rendered.contains("file_40.rs"));
+    assert!(rendered.contains("turns 1-1 of 2"));
+    assert!(!rendered.contains("thanks"));
+
+    let missing = render_session(&show_args("session-404"), &runtime_paths);
+    assert!(missing.is_err());
+}
+
+#[test]
+fn mart_and_events_sources_render_identical_transcripts() {
+    let out_dir = unique_temp_dir("logit-show-mart");
+    let events = session_events();
+    let events_path = out_dir.join("events.jsonl");
+    write_events_jsonl(&events_path, &events);
+    write_events_to_sqlite(
+        &out_dir.join("mart.sqlite"),
+        &events,
+        SqliteWriterConfig::default(),
+    )
+    .expect("mart should be written");
+    let runtime_paths = RuntimePaths {
+        home_dir: out_dir.clone(),
+        cwd: out_dir.clone(),
+        out_dir: out_dir.clone(),
+    };
+
+    let mut from_events = show_args("session-1");
+    from_events.events = Some(events_path);
+    from_events.format = TranscriptFormat::Markdown;
+    let mut from_mart = show_args("session-1");
+    from_mart.format = TranscriptFormat::Markdown;
+
+    let events_markdown = render_session(&from_events, &runtime_paths).expect("jsonl render");
+    let mart_markdown = render_session(&from_mart, &runtime_paths).expect("mart render");
+    let strip_source = |markdown: &str| {
+        markdown
+            .lines()
+            .filter(|line| !line.starts_with("_Source:"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    assert_eq!(strip_source(&events_markdown), strip_source(&mart_markdown));
+    assert!(mart_markdown.contains("mart.sqlite"));
+    assert!(mart_markdown.contains("## Turn 1 · `turn-a`"));
+    assert!(mart_markdown.contains("> list the <src> files"));
+    assert!(mart_markdown.contains("```json\n{\"cmd\":\"ls src\"}\n```"));
+}
+
+#[test]
+fn html_transcript_is_escaped_and_keeps_full_output_in_details() {
+    let events = session_events()
+        .iter()
+        .take(5)
+        .map(TranscriptEvent::from_agentlog)
+        .collect::<Vec<_>>();
+    let transcript = SessionTranscript::build("session-1", "events.jsonl", events, None)
+        .expect("transcript should build");
+    let html = render(
+        &transcript,
+        TranscriptFormat::Html,
+        RenderOptions::default(),
+    );
+
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(html.contains("list the &lt;src&gt; files"));
+    assert!(!html.contains("<src>"));
+    assert!(html.contains("<details><summary>tool output collapsed: 40 lines"));
+    assert!(html.contains("file_40.rs"));
+    assert!(html.contains("class=\"event role-tool\""));
+}
+
+#[test]
+fn show_session_command_writes_document_without_progress_banner() {
+    let temp = unique_temp_dir("logit-show-cli");
+    write_events_jsonl(&temp.join("events.jsonl"), &session_events());
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_logit"))
+            .arg("--home-dir")
+            .arg(&temp)
+            .arg("--cwd")
+            .arg(&temp)
+            .arg("--out-dir")
+            .arg(&temp)
+            .args(["show", "session", "session-1"])
+            .args(extra)
+            .output()
+            .expect("command should execute")
+    };
+
+    let output = run(&["--format", "markdown", "--turns", "2"]);
+    assert_eq!(output.status.code(), Some(0));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("# Session `session-1`"));
+    assert!(stdout.contains("## Turn 2 · `turn-b`"));
+    assert!(!stdout.contains("logit:"));
+
+    let html_path = temp.join("session.html");
+    let html_arg = html_path.to_string_lossy().to_string();
+    let output = run(&["--format", "html", "--output", &html_arg]);
+    assert_eq!(output.status.code(), Some(0));
+    let html = std::fs::read_to_string(&html_path).expect("html should be written");
+    assert!(html.contains("<h1>Session <code>session-1</code></h1>"));
+
+    let output = run(&["--turns", "9"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("requested range selects none"));
+}
@@ -1,8 +1,9 @@
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::snapshot::{SnapshotArgs, run as run_snapshot};
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::snapshot::build_artifact_layout;
 use logit::utils::redaction::REDACTION_TOKEN;
 use serde_json::Value;
@@ -108,9 +109,13 @@ fn snapshot_command_emits_index_samples_and_schema_profile() {
     let args = SnapshotArgs {
         source_root: Some(source_root.clone()),
         sample_size: 1,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_snapshot(&args, &runtime_paths).expect("snapshot command should succeed");
+    run_snapshot(&args, &LogitConfig::default(), &runtime_paths)
+        .expect("snapshot command should succeed");
 
     let layout = build_artifact_layout(&out_dir);
     assert!(layout.index_json.exists(), "snapshot index should exist");
@@ -238,9 +243,12 @@ fn snapshot_warns_and_continues_when_source_directory_is_unreadable() {
     let args = SnapshotArgs {
         source_root: Some(source_root),
         sample_size: 1,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
 
-    run_snapshot(&args, &runtime_paths)
+    run_snapshot(&args, &LogitConfig::default(), &runtime_paths)
         .expect("snapshot should continue when a source path is unreadable");
 
     let layout = build_artifact_layout(&out_dir);
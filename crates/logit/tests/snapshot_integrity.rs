@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::discovery::PipelineFilterRecord;
 use logit::models::{AgentSource, SCHEMA_VERSION};
 use logit::snapshot::samples::RepresentativeSample;
 use logit::snapshot::{
@@ -42,6 +43,7 @@ fn fixture_collection() -> SnapshotCollection {
             sources: vec![SnapshotDiscoveredSource {
                 adapter: "codex".to_string(),
                 source_kind: "codex".to_string(),
+                source_profile: "default".to_string(),
                 path: "~/.codex/sessions".to_string(),
                 resolved_path: "/tmp/sessions".to_string(),
                 format_hint: "directory".to_string(),
@@ -51,6 +53,8 @@ fn fixture_collection() -> SnapshotCollection {
                 records_profiled: 2,
             }],
             warnings: Vec::new(),
+            filters: PipelineFilterRecord::default(),
+            run_id: None,
         },
         samples: vec![
             RepresentativeSample {
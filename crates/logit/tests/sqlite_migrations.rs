@@ -0,0 +1,306 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use logit::sqlite::migrations::{MIGRATIONS, apply_migrations, latest_version, migration_status};
+use logit::sqlite::{
+    ADAPTERS_VIEW, INGEST_RUNS_TABLE, SCHEMA_META_TABLE, SQLITE_SCHEMA_VERSION, create_schema_sql,
+    open_sqlite_connection,
+};
+use rusqlite::Connection;
+use serde_json::Value;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time should be after unix epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn run_logit(out_dir: &Path, args: &[&str]) -> (Option<i32>, Value) {
+    let output = Command::new(env!("CARGO_BIN_EXE_logit"))
+        .arg("--home-dir")
+        .arg(out_dir)
+        .arg("--cwd")
+        .arg(out_dir)
+        .arg("--out-dir")
+        .arg(out_dir)
+        .args(args)
+        .output()
+        .expect("command should execute");
+    let envelope =
+        serde_json::from_slice(&output.stdout).expect("stdout should be a json envelope");
+    (output.status.code(), envelope)
+}
+
+/// Builds a mart the way builds before migrations did: a meta table without
+/// migration columns, an `ingest_runs` table missing later columns and an
+/// outdated `v_adapters` definition.
+fn create_legacy_mart(path: &Path) -> Connection {
+    let connection = open_sqlite_connection(path).expect("legacy mart should open");
+    connection
+        .execute_batch(&format!(
+            "CREATE TABLE {SCHEMA_META_TABLE} (schema_version TEXT NOT NULL, applied_at_utc TEXT NOT NULL);
+             INSERT INTO {SCHEMA_META_TABLE} VALUES ('{SQLITE_SCHEMA_VERSION}', '2025-01-01T00:00:00Z');"
+        ))
+        .expect("legacy meta should be created");
+    connection
+        .execute_batch(&create_schema_sql())
+        .expect("legacy schema should be created");
+    connection
+        .execute_batch(&format!(
+            "ALTER TABLE {INGEST_RUNS_TABLE} DROP COLUMN owner_pid;
+             DROP VIEW {ADAPTERS_VIEW};
+             CREATE VIEW {ADAPTERS_VIEW} AS SELECT adapter_name, COUNT(*) AS event_count
+             FROM agentlog_events GROUP BY adapter_name;"
+        ))
+        .expect("legacy drift should apply");
+    connection
+}
+
+fn column_exists(connection: &Connection, table: &str, column: &str) -> bool {
+    connection
+        .query_row(
+            &format!("SELECT COUNT(*) FROM pragma_table_info('{table}') WHERE name = ?1"),
+            [column],
+            |row| row.get::<_, i64>(0),
+        )
+        .expect("column lookup should succeed")
+        > 0
+}
+
+#[test]
+fn legacy_mart_is_adopted_by_migrations_and_views_are_recreated() {
+    let out_dir = unique_temp_dir("logit-migrations-legacy");
+    let connection = create_legacy_mart(&out_dir.join("mart.sqlite"));
+
+    let status = migration_status(&connection).expect("status should read");
+    assert_eq!(status.current_version, 0);
+    assert_eq!(status.latest_version, latest_version());
+    assert_eq!(status.pending.len(), MIGRATIONS.len());
+    assert_eq!(status.stale_views, [ADAPTERS_VIEW]);
+    assert!(!status.is_current());
+
+    let outcome = apply_migrations(&connection).expect("migrations should apply");
+    assert_eq!(outcome.from_version, 0);
+    assert_eq!(outcome.to_version, latest_version());
+    assert_eq!(
+        outcome
+            .applied
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>(),
+        MIGRATIONS
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(outcome.recreated_views, [ADAPTERS_VIEW]);
+    assert!(column_exists(&connection, INGEST_RUNS_TABLE, "owner_pid"));
+    assert!(column_exists(
+        &connection,
+        ADAPTERS_VIEW,
+        "pii_redacted_count"
+    ));
+    assert!(
+        migration_status(&connection)
+            .expect("status should read")
+            .is_current()
+    );
+
+    let recorded: Vec<(String, Option<u32>)> = connection
+        .prepare(&format!(
+            "SELECT schema_version, migration_version FROM {SCHEMA_META_TABLE} ORDER BY rowid"
+        ))
+        .and_then(|mut statement| {
+            statement
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+                .collect()
+        })
+        .expect("meta rows should read");
+    assert_eq!(
+        recorded,
+        [(SQLITE_SCHEMA_VERSION.to_string(), Some(latest_version()))]
+    );
+
+    let again = apply_migrations(&connection).expect("second apply should be a no-op");
+    assert!(again.applied.is_empty());
+    assert!(again.recreated_views.is_empty());
+}
+
+#[test]
+fn mart_migrate_dry_run_reports_without_changing_the_mart() {
+    let out_dir = unique_temp_dir("logit-migrations-cli");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let sqlite_path = out_dir.join("mart.sqlite");
+
+    let (code, envelope) = run_logit(&out_dir, &["mart", "migrate", "--dry-run"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["command"], "mart.migrate");
+    assert_eq!(envelope["data"]["from_version"], 0);
+    assert!(!sqlite_path.exists(), "dry run must not create the mart");
+
+    drop(create_legacy_mart(&sqlite_path));
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "schema"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["data"]["migrations"]["current_version"], 0);
+    assert_eq!(
+        envelope["data"]["migrations"]["pending"]
+            .as_array()
+            .map(Vec::len),
+        Some(MIGRATIONS.len())
+    );
+    assert_eq!(
+        envelope["data"]["migrations"]["stale_views"],
+        serde_json::json!([ADAPTERS_VIEW])
+    );
+
+    let (code, envelope) = run_logit(&out_dir, &["mart", "migrate", "--dry-run"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["data"]["dry_run"], true);
+    assert_eq!(envelope["data"]["to_version"], latest_version());
+    let connection = open_sqlite_connection(&sqlite_path).expect("mart should open");
+    assert!(!column_exists(&connection, INGEST_RUNS_TABLE, "owner_pid"));
+    drop(connection);
+
+    let (code, envelope) = run_logit(&out_dir, &["mart", "migrate"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(envelope["data"]["dry_run"], false);
+    assert_eq!(envelope["data"]["to_version"], latest_version());
+    assert_eq!(
+        envelope["data"]["views"],
+        serde_json::json!([ADAPTERS_VIEW])
+    );
+
+    let (code, envelope) = run_logit(&out_dir, &["query", "schema"]);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        envelope["meta"]["migration_version"],
+        serde_json::json!(latest_version())
+    );
+    assert_eq!(envelope["meta"]["migrations_pending"], 0);
+}
+
+#[test]
+fn host_identity_migration_adds_generated_host_column_to_version_two_marts() {
+    let out_dir = unique_temp_dir("logit-migrations-host");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let connection =
+        open_sqlite_connection(&out_dir.join("mart.sqlite")).expect("mart should open");
+    apply_migrations(&connection).expect("fresh mart should migrate");
+    connection
+        .execute_batch(&format!(
+            "DROP VIEW v_hosts;
+             DROP VIEW v_sessions;
+             DROP INDEX idx_agentlog_events_host_time;
+             DROP INDEX idx_ingest_watermarks_host;
+             ALTER TABLE agentlog_events DROP COLUMN host;
+             ALTER TABLE {INGEST_RUNS_TABLE} DROP COLUMN host;
+             ALTER TABLE ingest_watermarks DROP COLUMN host;
+             UPDATE {SCHEMA_META_TABLE} SET migration_version = 2, migration_name = 'ingest_run_metadata';
+             INSERT INTO agentlog_events (schema_version, event_id, run_id, sequence_global, source_kind, source_path, source_record_locator, adapter_name, record_format, event_type, role, timestamp_utc, timestamp_unix_ms, timestamp_quality, raw_hash, canonical_hash, metadata_json)
+             VALUES ('agentlog.v1', 'event-1', 'run-1', 1, 'codex', '/tmp/rollout.jsonl', 'line:1', 'codex', 'message', 'prompt', 'user', '2026-02-25T00:00:00Z', 1771977600000, 'exact', 'raw', 'canonical', '{{\"host\":\"desktop\"}}');"
+        ))
+        .expect("version two drift should apply");
+
+    let outcome = apply_migrations(&connection).expect("version two mart should migrate");
+    assert_eq!(outcome.from_version, 2);
+    assert_eq!(
+        outcome
+            .applied
+            .iter()
+            .map(|migration| migration.name.as_str())
+            .collect::<Vec<_>>(),
+        ["host_identity", "custom_source_kinds"]
+    );
+    assert!(column_exists(&connection, INGEST_RUNS_TABLE, "host"));
+    let host = connection
+        .query_row("SELECT host FROM v_hosts", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .expect("v_hosts should read the generated host column");
+    assert_eq!(host, "desktop");
+}
+
+#[test]
+fn custom_source_kinds_migration_relaxes_fixed_source_kind_checks() {
+    let out_dir = unique_temp_dir("logit-migrations-custom-kinds");
+    std::fs::create_dir_all(&out_dir).expect("out dir should be creatable");
+    let connection =
+        open_sqlite_connection(&out_dir.join("mart.sqlite")).expect("mart should open");
+    let fixed_kinds = "IN ('codex', 'claude', 'gemini', 'amp', 'opencode')";
+    let legacy_schema = create_schema_sql()
+        .replace(
+            "source_kind GLOB '[a-z]*' AND source_kind NOT GLOB '*[^a-z0-9_-]*'",
+            &format!("source_kind {fixed_kinds}"),
+        )
+        .replace(
+            "adapter_name GLOB '[a-z]*' AND adapter_name NOT GLOB '*[^a-z0-9_-]*'",
+            &format!("adapter_name {fixed_kinds}"),
+        );
+    connection
+        .execute_batch(&format!(
+            "CREATE TABLE {SCHEMA_META_TABLE} (schema_version TEXT NOT NULL, applied_at_utc TEXT NOT NULL);
+             INSERT INTO {SCHEMA_META_TABLE} VALUES ('{SQLITE_SCHEMA_VERSION}', '2025-01-01T00:00:00Z');
+             {legacy_schema}"
+        ))
+        .expect("legacy schema should be created");
+    let insert_event = |event_id: &str, source_kind: &str| {
+        connection.execute(
+            "INSERT INTO agentlog_events (schema_version, event_id, run_id, sequence_global, source_kind, source_path, source_record_locator, adapter_name, record_format, event_type, role, timestamp_utc, timestamp_unix_ms, timestamp_quality, content_text, raw_hash, canonical_hash)
+             VALUES ('agentlog.v1', ?1, 'run-1', 1, ?2, '/tmp/source.jsonl', 'line:1', ?2, 'message', 'prompt', 'user', '2026-02-25T00:00:00Z', 1771977600000, 'exact', 'rollback the canary', 'raw', 'canonical')",
+            [event_id, source_kind],
+        )
+    };
+    insert_event("event-gone", "claude").expect("built-in source kind should insert");
+    insert_event("event-codex", "codex").expect("built-in source kind should insert");
+    assert!(insert_event("event-bot", "build-bot").is_err());
+    connection
+        .execute_batch(
+            "DELETE FROM agentlog_events WHERE event_id = 'event-gone';
+             CREATE VIRTUAL TABLE agentlog_events_fts USING fts5 (
+                 content_text, tool_arguments_json, tool_result_text,
+                 event_id UNINDEXED, session_id UNINDEXED, adapter_name UNINDEXED
+             );
+             INSERT INTO agentlog_events_fts (rowid, content_text, event_id, adapter_name)
+             SELECT rowid, content_text, event_id, adapter_name FROM agentlog_events;",
+        )
+        .expect("full-text index should be built over a rowid gap");
+
+    apply_migrations(&connection).expect("legacy mart should migrate");
+
+    insert_event("event-bot", "build-bot").expect("custom source kind should insert");
+    connection
+        .execute(
+            "INSERT INTO ingest_watermarks (source_key, source_kind, source_path, refreshed_at_utc)
+             VALUES ('laptop|build-bot|/tmp/source.jsonl', 'build-bot', '/tmp/source.jsonl', '2026-02-25T00:00:00Z')",
+            [],
+        )
+        .expect("custom watermark should insert");
+    assert!(insert_event("event-bad", "Bad Kind").is_err());
+
+    let matched = connection
+        .query_row(
+            "SELECT e.event_id FROM agentlog_events_fts f JOIN agentlog_events e ON e.rowid = f.rowid
+             WHERE agentlog_events_fts MATCH 'canary'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .expect("full-text rows should still point at their events");
+    assert_eq!(matched, "event-codex");
+    let adapters = connection
+        .query_row(
+            &format!("SELECT COUNT(*) FROM {ADAPTERS_VIEW}"),
+            [],
+            |row| row.get::<_, i64>(0),
+        )
+        .expect("views should be recreated");
+    assert_eq!(adapters, 2);
+    assert!(
+        migration_status(&connection)
+            .expect("status should read")
+            .is_current()
+    );
+}
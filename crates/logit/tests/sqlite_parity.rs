@@ -85,8 +85,8 @@ fn parity_report_passes_for_identical_jsonl_and_sqlite() {
     write_events_to_sqlite(&sqlite_path, &events, SqliteWriterConfig { batch_size: 1 })
         .expect("sqlite mirror write should succeed");
 
-    let report =
-        verify_jsonl_sqlite_parity(&jsonl_path, &sqlite_path).expect("parity check should succeed");
+    let report = verify_jsonl_sqlite_parity(&jsonl_path, &sqlite_path, None)
+        .expect("parity check should succeed");
     assert!(report.is_match(), "expected parity report to match");
     assert_eq!(report.jsonl_records, 2);
     assert_eq!(report.sqlite_records, 2);
@@ -117,8 +117,8 @@ fn parity_report_detects_count_and_field_mismatches() {
         )
         .expect("update should succeed");
 
-    let report =
-        verify_jsonl_sqlite_parity(&jsonl_path, &sqlite_path).expect("parity check should succeed");
+    let report = verify_jsonl_sqlite_parity(&jsonl_path, &sqlite_path, None)
+        .expect("parity check should succeed");
     assert!(!report.is_match(), "expected parity mismatches");
 
     let has_count_mismatch = report
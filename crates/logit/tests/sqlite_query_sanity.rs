@@ -22,7 +22,7 @@ fn sample_event(
         run_id: run_id.to_string(),
         sequence_global,
         sequence_source: Some(sequence_global),
-        source_kind: adapter_name,
+        source_kind: adapter_name.clone(),
         source_path: "/tmp/events.jsonl".to_string(),
         source_record_locator: format!("line:{sequence_global}"),
         source_record_hash: None,
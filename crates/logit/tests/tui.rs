@@ -0,0 +1,332 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use logit::models::{
+    ActorRole, AgentLogEvent, AgentSource, EventType, RecordFormat, SchemaVersion, TimestampQuality,
+};
+use logit::sqlite::{SqliteWriterConfig, write_events_to_sqlite};
+use logit::tui::{App, Focus, MartReader, SessionSort, load_validation_report, view};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::Color;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock should be after epoch")
+        .as_nanos();
+    std::env::temp_dir().join(format!("{prefix}-{nanos}"))
+}
+
+fn write_file(path: &Path, content: &str) {
+    let parent = path.parent().expect("test file path should have parent");
+    std::fs::create_dir_all(parent).expect("test parent directory should be creatable");
+    std::fs::write(path, content).expect("test fixture file should be written");
+}
+
+fn event(
+    event_id: &str,
+    session_id: &str,
+    adapter: AgentSource,
+    sequence: u64,
+    content: &str,
+) -> AgentLogEvent {
+    AgentLogEvent {
+        schema_version: SchemaVersion::AgentLogV1,
+        event_id: event_id.to_string(),
+        run_id: "run-1".to_string(),
+        sequence_global: sequence,
+        sequence_source: Some(sequence),
+        source_kind: adapter.clone(),
+        source_path: "/tmp/agent/session.jsonl".to_string(),
+        source_record_locator: format!("line:{sequence}"),
+        source_record_hash: None,
+        adapter_name: adapter,
+        adapter_version: Some("v1".to_string()),
+        record_format: RecordFormat::Message,
+        event_type: EventType::Prompt,
+        role: ActorRole::User,
+        timestamp_utc: format!("2026-02-25T00:00:{sequence:02}Z"),
+        timestamp_unix_ms: 1_771_977_600_000 + sequence * 1_000,
+        timestamp_quality: TimestampQuality::Exact,
+        session_id: Some(session_id.to_string()),
+        conversation_id: None,
+        turn_id: None,
+        parent_event_id: None,
+        actor_id: None,
+        actor_name: None,
+        provider: None,
+        model: None,
+        content_text: Some(content.to_string()),
+        content_excerpt: None,
+        content_mime: Some("text/plain".to_string()),
+        tool_name: None,
+        tool_call_id: None,
+        tool_arguments_json: None,
+        tool_result_text: None,
+        input_tokens: None,
+        output_tokens: None,
+        total_tokens: None,
+        cost_usd: None,
+        tags: vec![],
+        flags: vec![],
+        pii_redacted: None,
+        warnings: vec![],
+        errors: vec![],
+        raw_hash: format!("raw-{event_id}"),
+        canonical_hash: format!("canonical-{event_id}"),
+        metadata: BTreeMap::new(),
+    }
+}
+
+fn tool_event(
+    event_id: &str,
+    sequence: u64,
+    format: RecordFormat,
+    call_id: &str,
+    text: &str,
+) -> AgentLogEvent {
+    let mut row = event(event_id, "session-a", AgentSource::Codex, sequence, text);
+    row.record_format = format;
+    row.tool_name = Some("shell".to_string());
+    row.tool_call_id = Some(call_id.to_string());
+    if format == RecordFormat::ToolCall {
+        row.event_type = EventType::ToolInvocation;
+        row.role = ActorRole::Assistant;
+        row.tool_arguments_json = Some(format!("{{\"cmd\":\"{text}\"}}"));
+    } else {
+        row.event_type = EventType::ToolOutput;
+        row.role = ActorRole::Tool;
+        row.tool_result_text = Some(text.to_string());
+    }
+    row.content_text = None;
+    row
+}
+
+fn seed(temp: &Path) -> (PathBuf, PathBuf) {
+    let mut prompt = event(
+        "evt-1",
+        "session-a",
+        AgentSource::Codex,
+        1,
+        "why is the sqlite database locked",
+    );
+    prompt.cost_usd = Some(0.75);
+    let mut later = event(
+        "evt-9",
+        "session-b",
+        AgentSource::Claude,
+        9,
+        "summarize the release notes",
+    );
+    later.cost_usd = Some(0.05);
+    let events = vec![
+        prompt,
+        tool_event("evt-2", 2, RecordFormat::ToolCall, "call-1", "cargo test"),
+        tool_event("evt-3", 3, RecordFormat::ToolResult, "call-1", "ok"),
+        tool_event("evt-4", 4, RecordFormat::ToolCall, "call-2", "sqlite3 mart"),
+        tool_event(
+            "evt-5",
+            5,
+            RecordFormat::ToolResult,
+            "call-9",
+            "database is locked",
+        ),
+        later,
+    ];
+    let mart = temp.join("mart.sqlite");
+    write_events_to_sqlite(&mart, &events, SqliteWriterConfig::default())
+        .expect("events should be written");
+
+    let report = temp.join("validate/report.json");
+    write_file(
+        &report,
+        &serde_json::json!({
+            "schema_version": "agentlog.v1",
+            "mode": "baseline",
+            "status": "fail",
+            "interpreted_exit_code": 2,
+            "total_records": 6,
+            "records_validated": 6,
+            "errors": 1,
+            "warnings": 1,
+            "quality_scorecard": {
+                "overall_score": 80,
+                "coverage_score": 80,
+                "parse_success_score": 90,
+                "content_completeness_score": 70,
+                "timestamp_quality_score": 80,
+                "weakest_dimensions": ["content_completeness"]
+            },
+            "per_agent_summary": {},
+            "issues": [
+                { "line": 4, "kind": "schema_violation", "severity": "error", "detail": "missing event_id" },
+                { "line": 6, "kind": "invariant_violation", "severity": "warning", "detail": "timestamp fallback" }
+            ]
+        })
+        .to_string(),
+    );
+    (mart, report)
+}
+
+fn open_app(temp: &Path) -> App {
+    let (mart, report_path) = seed(temp);
+    let reader = MartReader::open(&mart).expect("mart should open read-only");
+    let report = load_validation_report(&report_path).expect("report should parse");
+    App::new(reader, PathBuf::from("validate/report.json"), report).expect("app should load")
+}
+
+fn render(app: &App) -> Buffer {
+    let mut terminal =
+        Terminal::new(TestBackend::new(110, 32)).expect("test terminal should build");
+    terminal
+        .draw(|frame| view::draw(frame, app))
+        .expect("frame should draw");
+    terminal.backend().buffer().clone()
+}
+
+fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    (0..area.height)
+        .map(|y| {
+            let line = (0..area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn find_text(buffer: &Buffer, needle: &str) -> Option<(u16, u16)> {
+    buffer_text(buffer)
+        .lines()
+        .enumerate()
+        .find_map(|(y, line)| {
+            let byte = line.find(needle)?;
+            let x = line[..byte].chars().count();
+            Some((u16::try_from(x).ok()?, u16::try_from(y).ok()?))
+        })
+}
+
+fn press(app: &mut App, code: KeyCode) {
+    app.handle_key(KeyEvent::from(code));
+}
+
+#[test]
+fn tui_renders_sessions_transcript_tool_calls_and_issues() {
+    let temp = unique_temp_dir("logit-tui-render");
+    let mut app = open_app(&temp);
+    assert_eq!(
+        app.selected_session()
+            .map(|session| session.session_id.as_str()),
+        Some("session-b")
+    );
+
+    press(&mut app, KeyCode::Down);
+    let buffer = render(&app);
+    insta::assert_snapshot!("tui_session_a", buffer_text(&buffer));
+
+    let (x, y) = find_text(&buffer, "missing_result").expect("missing result row should render");
+    assert_eq!(buffer[(x, y)].fg, Color::Red);
+    let (x, y) = find_text(&buffer, "orphan_result").expect("orphan result row should render");
+    assert_eq!(buffer[(x, y)].fg, Color::Yellow);
+    let (x, y) = find_text(&buffer, "missing event_id").expect("issue row should render");
+    assert_eq!(buffer[(x, y)].fg, Color::Red);
+}
+
+#[test]
+fn tui_sort_cycles_recency_adapter_cost() {
+    let temp = unique_temp_dir("logit-tui-sort");
+    let mut app = open_app(&temp);
+    let order = |app: &App| {
+        app.sessions
+            .iter()
+            .map(|session| session.session_id.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(app.sort, SessionSort::Recency);
+    assert_eq!(order(&app), ["session-b", "session-a"]);
+
+    press(&mut app, KeyCode::Char('s'));
+    assert_eq!(app.sort, SessionSort::Adapter);
+    assert_eq!(order(&app), ["session-b", "session-a"]);
+    assert_eq!(app.sessions[0].adapter_name.as_deref(), Some("claude"));
+
+    press(&mut app, KeyCode::Char('s'));
+    assert_eq!(app.sort, SessionSort::Cost);
+    assert_eq!(order(&app), ["session-a", "session-b"]);
+    assert_eq!(
+        app.selected_session()
+            .map(|session| session.session_id.as_str()),
+        Some("session-b")
+    );
+    assert!(app.tool_calls.is_empty());
+}
+
+#[test]
+fn tui_search_box_queries_mart_and_opens_hit_session() {
+    let temp = unique_temp_dir("logit-tui-search");
+    let mut app = open_app(&temp);
+
+    press(&mut app, KeyCode::Char('/'));
+    assert!(app.search.editing);
+    for character in "locked".chars() {
+        press(&mut app, KeyCode::Char(character));
+    }
+    assert!(!app.should_quit);
+    press(&mut app, KeyCode::Enter);
+    assert!(!app.search.editing);
+    let hits = app.search.hits.clone().unwrap_or_default();
+    assert_eq!(hits.len(), 2);
+    assert!(
+        hits.iter()
+            .all(|hit| hit.session_id.as_deref() == Some("session-a"))
+    );
+    insta::assert_snapshot!("tui_search_hits", buffer_text(&render(&app)));
+
+    press(&mut app, KeyCode::Enter);
+    assert!(app.search.hits.is_none());
+    assert_eq!(
+        app.selected_session()
+            .map(|session| session.session_id.as_str()),
+        Some("session-a")
+    );
+    assert_eq!(app.tool_calls.len(), 3);
+    assert_eq!(
+        app.tool_calls
+            .iter()
+            .filter(|row| row.is_unpaired())
+            .count(),
+        2
+    );
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.focus, Focus::Transcript);
+    press(&mut app, KeyCode::BackTab);
+    press(&mut app, KeyCode::Char('q'));
+    assert!(app.should_quit);
+}
+
+#[test]
+fn tui_opens_mart_read_only_and_tolerates_missing_report() {
+    let temp = unique_temp_dir("logit-tui-readonly");
+    let (mart, _) = seed(&temp);
+    let reader = MartReader::open(&mart).expect("mart should open read-only");
+    let write = reader
+        .connection()
+        .execute("DELETE FROM agentlog_events", []);
+    assert!(write.is_err());
+
+    let missing = temp.join("missing/report.json");
+    let report = load_validation_report(&missing).expect("missing report should not fail");
+    assert!(report.is_none());
+    let app = App::new(reader, missing, report).expect("app should load");
+    assert_eq!(app.issue_count(), 0);
+    let text = buffer_text(&render(&app));
+    assert!(text.contains("no validation report at"));
+
+    assert!(MartReader::open(&temp.join("absent.sqlite")).is_err());
+}
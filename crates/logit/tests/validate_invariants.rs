@@ -141,6 +141,62 @@ fn empty_hashes_are_rejected() {
     );
 }
 
+#[test]
+fn adapter_name_mismatch_is_a_warning_and_open_code_keeps_its_json_spelling() {
+    let mut event = sample_event("1");
+    event.source_kind = AgentSource::OpenCode;
+    event.adapter_name = AgentSource::Custom("opencode-mirror".to_string());
+
+    let encoded = serde_json::to_string(&event).expect("event should serialize");
+    assert!(encoded.contains(r#""source_kind":"open_code""#));
+    let input = format!("{encoded}\n");
+    let report = validate_jsonl_against_generated_schema(&input, ValidationMode::Strict);
+
+    assert_eq!(report.errors, 0);
+    assert!(report.issues.iter().any(|issue| issue.kind
+        == ValidationIssueKind::InvariantViolation
+        && issue.severity == ValidationIssueSeverity::Warning
+        && issue.detail.contains("must match source_kind")));
+}
+
+#[test]
+fn malformed_custom_slugs_are_errors() {
+    let mut malformed = sample_event("1");
+    malformed.source_kind = AgentSource::Custom("My Tool".to_string());
+    malformed.adapter_name = AgentSource::Custom("My Tool".to_string());
+    let mut mismatched = sample_event("2");
+    mismatched.adapter_name = AgentSource::Custom("mirror.v2".to_string());
+
+    let input = [malformed, mismatched]
+        .iter()
+        .map(|event| serde_json::to_string(event).expect("event should serialize") + "\n")
+        .collect::<String>();
+    let report = validate_jsonl_against_generated_schema(&input, ValidationMode::Strict);
+
+    let slug_errors = report
+        .issues
+        .iter()
+        .filter(|issue| {
+            issue.severity == ValidationIssueSeverity::Error
+                && issue.detail.contains("must be a lowercase adapter slug")
+        })
+        .map(|issue| (issue.line, issue.detail.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        slug_errors,
+        vec![
+            (
+                1,
+                "custom source_kind `My Tool` must be a lowercase adapter slug that does not shadow a built-in adapter"
+            ),
+            (
+                2,
+                "custom adapter_name `mirror.v2` must be a lowercase adapter slug that does not shadow a built-in adapter"
+            ),
+        ]
+    );
+}
+
 #[test]
 fn invariant_catalog_executor_emits_combined_findings_deterministically() {
     let mut event = sample_event("1");
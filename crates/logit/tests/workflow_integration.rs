@@ -1,15 +1,17 @@
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use logit::cli::app::SourceFilterArgs;
 use logit::cli::commands::normalize::{NormalizeArgs, run as run_normalize};
 use logit::cli::commands::snapshot::{SnapshotArgs, run as run_snapshot};
 use logit::cli::commands::validate::{ValidateArgs, run as run_validate};
-use logit::config::RuntimePaths;
+use logit::config::{LogitConfig, RuntimePaths};
 use logit::discovery::build_artifact_layout as build_discovery_artifact_layout;
 use logit::normalize::build_artifact_layout as build_normalize_artifact_layout;
 use logit::snapshot::{
     build_artifact_layout as build_snapshot_artifact_layout, verify_snapshot_artifacts_parseable,
 };
+use logit::utils::redaction::RedactionPolicy;
 use logit::validate::build_artifact_layout as build_validate_artifact_layout;
 use serde_json::Value;
 
@@ -61,14 +63,26 @@ fn end_to_end_snapshot_normalize_validate_workflow_emits_consistent_artifacts()
     let snapshot_args = SnapshotArgs {
         source_root: Some(source_root.clone()),
         sample_size: 2,
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
-    run_snapshot(&snapshot_args, &runtime_paths).expect("snapshot command should succeed");
+    run_snapshot(&snapshot_args, &LogitConfig::default(), &runtime_paths)
+        .expect("snapshot command should succeed");
 
     let normalize_args = NormalizeArgs {
+        inputs: Vec::new(),
         source_root: Some(source_root),
+        input_adapter: None,
+        message_format: None,
         fail_fast: false,
+        redaction: RedactionPolicy::default(),
+        pseudonymize: false,
+        no_pseudonymize: false,
+        filters: SourceFilterArgs::default(),
     };
-    run_normalize(&normalize_args, &runtime_paths).expect("normalize command should succeed");
+    run_normalize(&normalize_args, &LogitConfig::default(), &runtime_paths)
+        .expect("normalize command should succeed");
 
     let normalize_layout = build_normalize_artifact_layout(&out_dir);
     let validate_args = ValidateArgs {
@@ -38,7 +38,7 @@ opencode --project /tmp/demo
 "#;
 
     let scores = zsh_history_scores(history);
-    assert_eq!(scores.len(), 5);
+    assert_eq!(scores.len(), 8);
 
     let amp = scores
         .iter()